@@ -0,0 +1,6 @@
+pub mod backup;
+pub mod owner;
+pub mod path;
+pub mod permissions;
+pub mod times;
+pub mod xattr;
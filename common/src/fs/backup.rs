@@ -0,0 +1,220 @@
+use std::ffi::OsString;
+use std::io;
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::path::{Path, PathBuf};
+
+const DEFAULT_SUFFIX: &str = "~";
+const SUFFIX_VARIABLE: &str = "SIMPLE_BACKUP_SUFFIX";
+const CONTROL_VARIABLE: &str = "VERSION_CONTROL";
+const CONTROL_OPTION: &str = "backup type";
+/* a numbered backup of `name` is `name.~N~` */
+const NUMBERED_START: &[u8] = b".~";
+const NUMBERED_END: &[u8] = b"~";
+
+///
+/// The names `--backup` accepts, each with its synonyms, in the order the coreutils list them.
+///
+pub const BACKUP_ARGUMENTS: [&[&str]; 4] = [&["none", "off"], &["simple", "never"], &["existing", "nil"], &["numbered", "t"]];
+const BACKUP_TYPES: [Backup; 4] = [Backup::None, Backup::Simple, Backup::Existing, Backup::Numbered];
+
+///
+/// How an existing file is kept before it's replaced, as `--backup` chooses.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backup {
+    /// It isn't.
+    None,
+    /// As `name~`, or whatever the suffix is.
+    Simple,
+    /// As `name.~N~`, one more than the highest such backup so far.
+    Numbered,
+    /// Numbered if there are numbered backups already, simple otherwise.
+    Existing,
+}
+
+///
+/// A backup type that isn't one of the names, or is the start of more than one of them.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackupError {
+    Invalid { argument: String, context: &'static str },
+    Ambiguous { argument: String, context: &'static str },
+}
+
+impl BackupError {
+    ///
+    /// Describe the error the way the coreutils do, prefixed with the program name and followed by
+    /// the names that would do.
+    ///
+    pub fn message(&self, program: &str) -> String {
+        let (problem, argument, context) = match self {
+            BackupError::Invalid { argument, context } => ("invalid", argument, context),
+            BackupError::Ambiguous { argument, context } => ("ambiguous", argument, context),
+        };
+        let choices: Vec<String> = BACKUP_ARGUMENTS.iter()
+            .map(|synonyms| format!("  - {}", synonyms.iter().map(|name| format!("'{}'", name)).collect::<Vec<_>>().join(", ")))
+            .collect();
+        format!("{}: {} argument '{}' for '{}'\nValid arguments are:\n{}", program, problem, argument, context, choices.join("\n"))
+    }
+}
+
+///
+/// The backup type asked for with `--backup=CONTROL`, or, when `control` is [None] as with `-b`,
+/// by `VERSION_CONTROL`; without either, it's [Backup::Existing].  Names may be abbreviated.
+///
+pub fn backup_type(control: Option<&str>) -> Result<Backup, BackupError> {
+    match control {
+        Some(control) => parse_backup(control, CONTROL_OPTION),
+        None => match std::env::var(CONTROL_VARIABLE) {
+            Ok(control) if !control.is_empty() => parse_backup(&control, "$VERSION_CONTROL"),
+            _ => Ok(Backup::Existing),
+        },
+    }
+}
+
+fn parse_backup(argument: &str, context: &'static str) -> Result<Backup, BackupError> {
+    let names = || BACKUP_ARGUMENTS.iter().zip(BACKUP_TYPES).flat_map(|(synonyms, backup)| synonyms.iter().map(move |name| (*name, backup)));
+    if let Some((_, backup)) = names().find(|(name, _)| *name == argument) {
+        return Ok(backup);
+    }
+
+    let mut matches = names().filter(|(name, _)| !argument.is_empty() && name.starts_with(argument)).map(|(_, backup)| backup);
+    match matches.next() {
+        None => Err(BackupError::Invalid { argument: argument.to_string(), context }),
+        Some(first) if matches.all(|backup| backup == first) => Ok(first),
+        Some(_) => Err(BackupError::Ambiguous { argument: argument.to_string(), context }),
+    }
+}
+
+///
+/// The suffix for simple backups: the one given with `-S`, else `SIMPLE_BACKUP_SUFFIX`, else `~`.
+/// An empty suffix, or one with a `/` in it, is passed over.
+///
+pub fn backup_suffix(suffix: Option<&str>) -> String {
+    let usable = |suffix: &str| !suffix.is_empty() && !suffix.contains('/');
+    suffix.filter(|suffix| usable(suffix))
+        .map(str::to_string)
+        .or_else(|| std::env::var(SUFFIX_VARIABLE).ok().filter(|suffix| usable(suffix)))
+        .unwrap_or_else(|| DEFAULT_SUFFIX.to_string())
+}
+
+///
+/// The name a backup of `path` would be given; [None] when backups aren't made.
+///
+pub fn backup_name(path: &Path, backup: Backup, suffix: &str) -> Option<PathBuf> {
+    let numbered = match backup {
+        Backup::None => return None,
+        Backup::Simple => None,
+        Backup::Numbered => Some(highest_version(path).unwrap_or(0)),
+        Backup::Existing => highest_version(path),
+    };
+
+    let mut name = path.as_os_str().as_bytes().to_vec();
+    match numbered {
+        Some(version) => name.extend_from_slice(format!(".~{}~", version + 1).as_bytes()),
+        None => name.extend_from_slice(suffix.as_bytes()),
+    }
+    Some(PathBuf::from(OsString::from_vec(name)))
+}
+
+///
+/// Move `path` out of the way to its backup name, which is returned; [None] when backups aren't made.
+///
+pub fn make_backup(path: &Path, backup: Backup, suffix: &str) -> io::Result<Option<PathBuf>> {
+    let Some(name) = backup_name(path, backup, suffix) else {
+        return Ok(None);
+    };
+    std::fs::rename(path, &name)?;
+    Ok(Some(name))
+}
+
+///
+/// The highest version among the numbered backups of `path`, if it has any.  Versions don't start
+/// with a zero.
+///
+fn highest_version(path: &Path) -> Option<u64> {
+    let base = path.file_name()?.as_bytes();
+    let directory = path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    std::fs::read_dir(directory).ok()?
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let version = name.as_bytes().strip_prefix(base)?.strip_prefix(NUMBERED_START)?.strip_suffix(NUMBERED_END)?;
+            let valid = version.first().is_some_and(|digit| (b'1'..=b'9').contains(digit)) && version.iter().all(u8::is_ascii_digit);
+            if !valid {
+                return None;
+            }
+            std::str::from_utf8(version).ok()?.parse().ok()
+        })
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+    use crate::fs::backup::{backup_name, backup_suffix, backup_type, make_backup, Backup, BackupError};
+
+    #[test]
+    fn test_backup_type() {
+        let cases = [
+            ("none", Ok(Backup::None)),
+            ("off", Ok(Backup::None)),
+            ("simple", Ok(Backup::Simple)),
+            ("never", Ok(Backup::Simple)),
+            ("existing", Ok(Backup::Existing)),
+            ("nil", Ok(Backup::Existing)),
+            ("numbered", Ok(Backup::Numbered)),
+            ("t", Ok(Backup::Numbered)),
+            ("si", Ok(Backup::Simple)),
+            ("ne", Ok(Backup::Simple)),
+            ("nu", Ok(Backup::Numbered)),
+            ("n", Err(BackupError::Ambiguous { argument: "n".to_string(), context: "backup type" })),
+            ("bogus", Err(BackupError::Invalid { argument: "bogus".to_string(), context: "backup type" })),
+            ("", Err(BackupError::Invalid { argument: String::new(), context: "backup type" })),
+        ];
+        for (argument, expected) in cases {
+            assert_eq!(expected, backup_type(Some(argument)), "{}", argument);
+        }
+    }
+
+    #[test]
+    fn test_message() {
+        let error = BackupError::Invalid { argument: "x".to_string(), context: "backup type" };
+        assert_eq!(
+            "mv: invalid argument 'x' for 'backup type'\nValid arguments are:\n  - 'none', 'off'\n  - 'simple', 'never'\n  - 'existing', 'nil'\n  - 'numbered', 't'",
+            error.message("mv")
+        );
+    }
+
+    #[test]
+    fn test_backup_suffix() {
+        assert_eq!(".bak", backup_suffix(Some(".bak")));
+        assert_eq!(backup_suffix(None), backup_suffix(Some("a/b")));
+        assert_eq!(backup_suffix(None), backup_suffix(Some("")));
+    }
+
+    #[test]
+    fn test_backup_name() {
+        let dir = std::env::temp_dir().join(format!("common-backup-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("file");
+        std::fs::write(&file, b"").unwrap();
+
+        assert_eq!(None, backup_name(&file, Backup::None, "~"));
+        assert_eq!(Some(dir.join("file~")), backup_name(&file, Backup::Simple, "~"));
+        assert_eq!(Some(dir.join("file.orig")), backup_name(&file, Backup::Existing, ".orig"));
+        assert_eq!(Some(dir.join("file.~1~")), backup_name(&file, Backup::Numbered, "~"));
+
+        for name in ["file.~2~", "file.~10~", "file.~011~", "file.~x~", "other.~50~"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+        assert_eq!(Some(dir.join("file.~11~")), backup_name(&file, Backup::Numbered, "~"));
+        assert_eq!(Some(dir.join("file.~11~")), backup_name(&file, Backup::Existing, "~"));
+        assert_eq!(Some(PathBuf::from("name~")), backup_name(Path::new("name"), Backup::Simple, "~"));
+
+        assert_eq!(Some(dir.join("file.~11~")), make_backup(&file, Backup::Numbered, "~").unwrap());
+        assert!(!file.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,28 @@
+use std::ffi::CString;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+///
+/// Change the owner and group of a file, or of a symbolic link itself.  When the owner can't be
+/// changed, the group still may be, and the error from changing the owner is returned.
+///
+pub fn change_owner(path: &Path, uid: u32, gid: u32) -> io::Result<()> {
+    let path = CString::new(path.as_os_str().as_bytes()).map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+    /* SAFETY: lchown only reads the path */
+    if unsafe { libc::lchown(path.as_ptr(), uid, gid) } == 0 {
+        return Ok(());
+    }
+    let error = io::Error::last_os_error();
+    /* SAFETY: as above; an id of -1 is left as it is */
+    unsafe { libc::lchown(path.as_ptr(), u32::MAX, gid) };
+    Err(error)
+}
+
+///
+/// Whether the process may give files away to other users.
+///
+pub fn privileged() -> bool {
+    /* SAFETY: geteuid can't fail */
+    unsafe { libc::geteuid() == 0 }
+}
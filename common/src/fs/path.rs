@@ -0,0 +1,167 @@
+use std::ffi::OsStr;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Component, Path, PathBuf};
+
+/* as many symbolic links as Linux follows in resolving a name */
+const MAX_SYMBOLIC_LINKS: usize = 40;
+
+///
+/// The last part of a name as it was given, which is what it's called when put in a directory;
+/// unlike [Path::file_name], `d/.` ends in `.`, so it's the contents of `d` that are meant.
+///
+pub fn base_name(name: &Path) -> &Path {
+    let bytes = name.as_os_str().as_bytes();
+    let trimmed = match bytes.iter().rposition(|&byte| byte != b'/') {
+        Some(end) => &bytes[..=end],
+        None => return name,
+    };
+    let start = trimmed.iter().rposition(|&byte| byte == b'/').map_or(0, |slash| slash + 1);
+    Path::new(OsStr::from_bytes(&trimmed[start..]))
+}
+
+///
+/// The directory a name is in, as it was given: `a/b/` is in `a`, and `b` is in `.`.
+///
+pub fn dir_name(name: &Path) -> &Path {
+    let bytes = name.as_os_str().as_bytes();
+    let Some(last) = bytes.iter().rposition(|&byte| byte != b'/') else {
+        return if bytes.is_empty() { Path::new(".") } else { Path::new("/") };
+    };
+    let Some(slash) = bytes[..last].iter().rposition(|&byte| byte == b'/') else {
+        return Path::new(".");
+    };
+    let end = bytes[..slash].iter().rposition(|&byte| byte != b'/').map_or(1, |last| last + 1);
+    Path::new(OsStr::from_bytes(&bytes[..end]))
+}
+
+///
+/// The absolute name of `path` with every symbolic link in it followed, and no `.` or `..` left;
+/// like [std::fs::canonicalize], except that none of it needs to exist.  What's missing is taken
+/// as it's spelled, so a `..` after it goes back up lexically.
+///
+pub fn canonicalize_missing(path: &Path) -> io::Result<PathBuf> {
+    let mut resolved = if path.is_absolute() { PathBuf::from("/") } else { std::env::current_dir()? };
+    let mut pending: Vec<PathBuf> = names(path).rev().collect();
+    let mut links = 0;
+    while let Some(name) = pending.pop() {
+        if name.as_os_str() == ".." {
+            resolved.pop();
+            continue;
+        }
+        resolved.push(&name);
+        if let Ok(target) = std::fs::read_link(&resolved) {
+            links += 1;
+            if links > MAX_SYMBOLIC_LINKS {
+                return Err(io::Error::from_raw_os_error(libc::ELOOP));
+            }
+            resolved.pop();
+            if target.is_absolute() {
+                resolved = PathBuf::from("/");
+            }
+            pending.extend(names(&target).rev());
+        }
+    }
+    Ok(resolved)
+}
+
+///
+/// The names a path is made of, without `.`; `..` is kept, as it depends on what comes before.
+///
+fn names(path: &Path) -> impl DoubleEndedIterator<Item = PathBuf> + '_ {
+    path.components().filter_map(|component| match component {
+        Component::Normal(name) => Some(PathBuf::from(name)),
+        Component::ParentDir => Some(PathBuf::from("..")),
+        _ => None,
+    })
+}
+
+///
+/// How to get to `path` from `directory`, both of them absolute and canonical: `/a/b/c` from
+/// `/a/d` is `../b/c`.  A directory is `.` from itself.
+///
+pub fn relative_path(path: &Path, directory: &Path) -> PathBuf {
+    let path: Vec<Component> = path.components().collect();
+    let directory: Vec<Component> = directory.components().collect();
+    let common = path.iter().zip(&directory).take_while(|(a, b)| a == b).count();
+
+    let mut relative = PathBuf::new();
+    for _ in common..directory.len() {
+        relative.push("..");
+    }
+    for component in &path[common..] {
+        relative.push(component);
+    }
+    if relative.as_os_str().is_empty() {
+        relative.push(".");
+    }
+    relative
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use crate::fs::path::{base_name, canonicalize_missing, dir_name, relative_path};
+
+    #[test]
+    fn test_base_name() {
+        assert_eq!(Path::new("a"), base_name(Path::new("a")));
+        assert_eq!(Path::new("b"), base_name(Path::new("a/b")));
+        assert_eq!(Path::new("b"), base_name(Path::new("/a/b//")));
+        assert_eq!(Path::new("."), base_name(Path::new("d/.")));
+        assert_eq!(Path::new("/"), base_name(Path::new("/")));
+    }
+
+    #[test]
+    fn test_dir_name() {
+        assert_eq!(Path::new("."), dir_name(Path::new("a")));
+        assert_eq!(Path::new("a"), dir_name(Path::new("a//b/")));
+        assert_eq!(Path::new("d"), dir_name(Path::new("d/.")));
+        assert_eq!(Path::new("/"), dir_name(Path::new("/a")));
+        assert_eq!(Path::new("/"), dir_name(Path::new("//")));
+        assert_eq!(Path::new("."), dir_name(Path::new("")));
+    }
+
+    #[test]
+    fn test_canonicalize_missing() {
+        let dir = std::env::temp_dir().join(format!("common-test-{}-canonicalize", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("real/inner")).unwrap();
+        std::os::unix::fs::symlink("real/inner", dir.join("link")).unwrap();
+        std::os::unix::fs::symlink(dir.join("real"), dir.join("absolute")).unwrap();
+        std::os::unix::fs::symlink("loop", dir.join("loop")).unwrap();
+        let dir = fs::canonicalize(&dir).unwrap();
+
+        let cases = [
+            ("real/./inner", "real/inner"),
+            ("link", "real/inner"),
+            ("link/..", "real"),
+            ("link/missing/../x", "real/inner/x"),
+            ("absolute/inner/../../real", "real"),
+            ("missing/more", "missing/more"),
+        ];
+        for (name, expected) in cases {
+            assert_eq!(dir.join(expected), canonicalize_missing(&dir.join(name)).unwrap(), "{}", name);
+        }
+        assert_eq!(PathBuf::from("/"), canonicalize_missing(Path::new("/../..")).unwrap());
+        assert_eq!(Some(libc::ELOOP), canonicalize_missing(&dir.join("loop/x")).unwrap_err().raw_os_error());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_relative_path() {
+        let cases = [
+            ("/a/b/c", "/a/d", "../b/c"),
+            ("/a/b", "/a/b", "."),
+            ("/a/b/c", "/a/b", "c"),
+            ("/a", "/a/b/c", "../.."),
+            ("/x/y", "/", "x/y"),
+            ("/", "/a/b", "../.."),
+            ("/ab/c", "/a/c", "../../ab/c"),
+        ];
+        for (path, directory, expected) in cases {
+            assert_eq!(Path::new(expected), relative_path(Path::new(path), Path::new(directory)), "{} from {}", path, directory);
+        }
+    }
+}
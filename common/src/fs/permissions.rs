@@ -0,0 +1,421 @@
+const MODE_BITS: u32 = 0o7777;
+const SET_USER: u32 = 0o4000;
+const SET_GROUP: u32 = 0o2000;
+const STICKY: u32 = 0o1000;
+const USER: u32 = 0o700;
+const GROUP: u32 = 0o070;
+const OTHERS: u32 = 0o007;
+/* each permission, for all three of user, group and others */
+const READ: u32 = 0o444;
+const WRITE: u32 = 0o222;
+const EXECUTE: u32 = 0o111;
+/* a mode given in octal is taken whole from five digits; with fewer, a directory keeps its set-id bits */
+const OCTAL_DIGITS: usize = 5;
+
+const PERMISSIONS: [(u32, char); 9] = [
+    (0o400, 'r'), (0o200, 'w'), (0o100, 'x'),
+    (0o040, 'r'), (0o020, 'w'), (0o010, 'x'),
+    (0o004, 'r'), (0o002, 'w'), (0o001, 'x'),
+];
+/* the special bits, each with the execute bit it shares a column with */
+const SPECIAL: [(u32, u32, usize, char); 3] = [(SET_USER, 0o100, 2, 's'), (SET_GROUP, 0o010, 5, 's'), (STICKY, 0o001, 8, 't')];
+
+///
+/// How one change treats the permissions it's given.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    /// `+`
+    Add,
+    /// `-`
+    Remove,
+    /// `=`; what isn't given is taken away.
+    Set,
+}
+
+///
+/// Where the permissions a change works with come from.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    /// They're the ones given.
+    Given,
+    /// They're copied from those of `u`, `g` or `o`, as the file has them when the change is made.
+    Copied,
+    /// As given, with execute added when the file is a directory or already executable by someone; `X`.
+    ExecuteIfAny,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Change {
+    operator: Operator,
+    source: Source,
+    /* the bits of the users named, or 0 when none were, so the umask limits the change instead */
+    affected: u32,
+    value: u32,
+    /* the bits the mode spelled out; a directory's set-id bits are kept unless they're among them */
+    mentioned: u32,
+}
+
+///
+/// A mode as `chmod` and `mkdir -m` take it: octal, as `755`, or symbolic, as `u+rwx,g-w,o=`.
+/// It's a list of changes, made in turn to whatever mode a file has.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mode {
+    changes: Vec<Change>,
+}
+
+impl Mode {
+    ///
+    /// Read a mode; [None] when it isn't one.
+    ///
+    pub fn parse(mode: &str) -> Option<Mode> {
+        let bytes = mode.as_bytes();
+        if bytes.first().is_some_and(u8::is_ascii_digit) {
+            let (value, length) = octal(bytes)?;
+            if length != bytes.len() {
+                return None;
+            }
+            let mentioned = if length < OCTAL_DIGITS { value & (SET_USER | SET_GROUP) | STICKY | USER | GROUP | OTHERS } else { MODE_BITS };
+            let change = Change { operator: Operator::Set, source: Source::Given, affected: MODE_BITS, value, mentioned };
+            return Some(Mode { changes: vec![change] });
+        }
+
+        let mut changes = vec![];
+        let mut index = 0;
+        loop {
+            /* who: any of u, g, o and a, then at least one operator */
+            let mut affected = 0;
+            loop {
+                match bytes.get(index) {
+                    Some(b'u') => affected |= SET_USER | USER,
+                    Some(b'g') => affected |= SET_GROUP | GROUP,
+                    Some(b'o') => affected |= STICKY | OTHERS,
+                    Some(b'a') => affected |= MODE_BITS,
+                    Some(b'+' | b'-' | b'=') => break,
+                    _ => return None,
+                }
+                index += 1;
+            }
+
+            while let Some(operator) = bytes.get(index).and_then(|&byte| operator(byte)) {
+                index += 1;
+                let (source, value, mentioned) = match bytes.get(index) {
+                    Some(byte) if byte.is_ascii_digit() => {
+                        let (value, length) = octal(&bytes[index..])?;
+                        index += length;
+                        /* an octal value stands alone, for everyone */
+                        if affected != 0 || bytes.get(index).is_some_and(|&byte| byte != b',') {
+                            return None;
+                        }
+                        affected = MODE_BITS;
+                        (Source::Given, value, MODE_BITS)
+                    }
+                    Some(&byte @ (b'u' | b'g' | b'o')) => {
+                        index += 1;
+                        let value = match byte {
+                            b'u' => USER,
+                            b'g' => GROUP,
+                            _ => OTHERS,
+                        };
+                        (Source::Copied, value, 0)
+                    }
+                    _ => {
+                        let mut source = Source::Given;
+                        let mut value = 0;
+                        loop {
+                            match bytes.get(index) {
+                                Some(b'r') => value |= READ,
+                                Some(b'w') => value |= WRITE,
+                                Some(b'x') => value |= EXECUTE,
+                                Some(b'X') => source = Source::ExecuteIfAny,
+                                Some(b's') => value |= SET_USER | SET_GROUP,
+                                Some(b't') => value |= STICKY,
+                                _ => break,
+                            }
+                            index += 1;
+                        }
+                        (source, value, 0)
+                    }
+                };
+                let mentioned = match (mentioned, affected) {
+                    (0, 0) => value,
+                    (0, affected) => affected & value,
+                    (mentioned, _) => mentioned,
+                };
+                changes.push(Change { operator, source, affected, value, mentioned });
+            }
+
+            match bytes.get(index) {
+                Some(b',') => index += 1,
+                None => return Some(Mode { changes }),
+                Some(_) => return None,
+            }
+        }
+    }
+
+    ///
+    /// A mode that sets every bit the way `mode` has it, as `chmod --reference` takes from a file.
+    ///
+    pub fn from_reference(mode: u32) -> Mode {
+        let change = Change { operator: Operator::Set, source: Source::Given, affected: MODE_BITS, value: mode & MODE_BITS, mentioned: MODE_BITS };
+        Mode { changes: vec![change] }
+    }
+
+    ///
+    /// Make the changes to `mode`, the mode of a file, or of a `directory`.  Where no users were
+    /// named, as in `+x`, what the `umask` masks is left alone.  Returns the new mode, and the
+    /// bits the changes had a say in, whether or not they differ.
+    ///
+    pub fn adjust(&self, mode: u32, directory: bool, umask: u32) -> (u32, u32) {
+        let mut mode = mode & MODE_BITS;
+        let mut bits = 0;
+        for change in &self.changes {
+            let omitted = if directory { (SET_USER | SET_GROUP) & !change.mentioned } else { 0 };
+            let mut value = change.value;
+            match change.source {
+                Source::Given => {}
+                Source::Copied => {
+                    value &= mode;
+                    value |= spread(value, READ) | spread(value, WRITE) | spread(value, EXECUTE);
+                }
+                Source::ExecuteIfAny => {
+                    if mode & EXECUTE != 0 || directory {
+                        value |= EXECUTE;
+                    }
+                }
+            }
+            value &= (if change.affected != 0 { change.affected } else { !umask }) & !omitted;
+
+            match change.operator {
+                Operator::Add => {
+                    bits |= value;
+                    mode |= value;
+                }
+                Operator::Remove => {
+                    bits |= value;
+                    mode &= !value;
+                }
+                Operator::Set => {
+                    let preserved = (if change.affected != 0 { !change.affected } else { 0 }) | omitted;
+                    bits |= MODE_BITS & !preserved;
+                    mode = (mode & preserved) | value;
+                }
+            }
+        }
+        (mode, bits)
+    }
+}
+
+fn operator(byte: u8) -> Option<Operator> {
+    match byte {
+        b'+' => Some(Operator::Add),
+        b'-' => Some(Operator::Remove),
+        b'=' => Some(Operator::Set),
+        _ => None,
+    }
+}
+
+///
+/// The octal number the bytes start with, and how many digits it took; [None] when it's more than
+/// a mode can be.
+///
+fn octal(bytes: &[u8]) -> Option<(u32, usize)> {
+    let mut value = 0;
+    let length = bytes.iter().take_while(|byte| (b'0'..=b'7').contains(byte)).count();
+    for &digit in &bytes[..length] {
+        value = value * 8 + u32::from(digit - b'0');
+        if value > MODE_BITS {
+            return None;
+        }
+    }
+    Some((value, length))
+}
+
+///
+/// All three of a permission when any of user, group and others has it.
+///
+fn spread(value: u32, permission: u32) -> u32 {
+    if value & permission != 0 { permission } else { 0 }
+}
+
+///
+/// The process's file mode creation mask.
+///
+pub fn umask() -> u32 {
+    /* SAFETY: umask can't fail; the mask is put back as it was straight away */
+    let mask = unsafe { libc::umask(0) };
+    unsafe { libc::umask(mask) };
+    mask as u32
+}
+
+///
+/// Permission bits the way `ls -l` shows them, without the kind of file: `rwxr-xr-x`.
+///
+pub fn permission_string(mode: u32) -> String {
+    let mut characters: Vec<char> = PERMISSIONS.iter().map(|&(bit, character)| if mode & bit != 0 { character } else { '-' }).collect();
+    for (bit, execute, column, character) in SPECIAL {
+        if mode & bit != 0 {
+            characters[column] = if mode & execute != 0 { character } else { character.to_ascii_uppercase() };
+        }
+    }
+    characters.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fs::permissions::{permission_string, Mode};
+
+    fn adjust(mode: &str, from: u32, directory: bool, umask: u32) -> u32 {
+        Mode::parse(mode).unwrap().adjust(from, directory, umask).0
+    }
+
+    /* from GNU chmod: the mode, the mode it's made to, whether that's a directory's, the umask, and the result */
+    const ADJUSTMENTS: [(&str, u32, bool, u32, u32); 101] = [
+        ("755",          0o644,  false, 0o022, 0o755),
+        ("0",            0o7777, false, 0o022, 0o000),
+        ("7777",         0o000,  false, 0o022, 0o7777),
+        ("00755",        0o2700, true,  0o000, 0o755),
+        ("0755",         0o2700, true,  0o000, 0o2755),
+        ("755",          0o2700, true,  0o000, 0o2755),
+        ("755",          0o6700, true,  0o000, 0o6755),
+        ("1755",         0o6700, true,  0o000, 0o7755),
+        ("4755",         0o2700, true,  0o000, 0o6755),
+        ("u+r",          0o000,  false, 0o000, 0o400),
+        ("u+w",          0o000,  false, 0o000, 0o200),
+        ("u+x",          0o000,  false, 0o000, 0o100),
+        ("g+r",          0o000,  false, 0o000, 0o040),
+        ("g+w",          0o000,  false, 0o000, 0o020),
+        ("g+x",          0o000,  false, 0o000, 0o010),
+        ("o+r",          0o000,  false, 0o000, 0o004),
+        ("o+w",          0o000,  false, 0o000, 0o002),
+        ("o+x",          0o000,  false, 0o000, 0o001),
+        ("a+r",          0o000,  false, 0o000, 0o444),
+        ("a+w",          0o000,  false, 0o000, 0o222),
+        ("a+x",          0o000,  false, 0o000, 0o111),
+        ("u-r",          0o777,  false, 0o000, 0o377),
+        ("g-w",          0o777,  false, 0o000, 0o757),
+        ("o-x",          0o777,  false, 0o000, 0o776),
+        ("a-rwx",        0o777,  false, 0o000, 0o000),
+        ("u=r",          0o777,  false, 0o000, 0o477),
+        ("g=w",          0o777,  false, 0o000, 0o727),
+        ("o=x",          0o777,  false, 0o000, 0o771),
+        ("a=rw",         0o777,  false, 0o000, 0o666),
+        ("ug=rx",        0o777,  false, 0o000, 0o557),
+        ("uo-w",         0o777,  false, 0o000, 0o575),
+        ("go+rw",        0o000,  false, 0o000, 0o066),
+        ("+r",           0o000,  false, 0o022, 0o444),
+        ("+w",           0o000,  false, 0o022, 0o200),
+        ("+x",           0o000,  false, 0o022, 0o111),
+        ("+rwx",         0o000,  false, 0o027, 0o750),
+        ("-w",           0o666,  false, 0o022, 0o466),
+        ("-rwx",         0o777,  false, 0o077, 0o077),
+        ("=",            0o777,  false, 0o022, 0o000),
+        ("=r",           0o777,  false, 0o022, 0o444),
+        ("=rwx",         0o000,  false, 0o007, 0o770),
+        ("a=",           0o777,  false, 0o022, 0o000),
+        ("u+s",          0o755,  false, 0o000, 0o4755),
+        ("g+s",          0o755,  false, 0o000, 0o2755),
+        ("o+s",          0o755,  false, 0o000, 0o755),
+        ("a+s",          0o755,  false, 0o000, 0o6755),
+        ("+s",           0o755,  false, 0o022, 0o6755),
+        ("u+t",          0o755,  false, 0o000, 0o755),
+        ("o+t",          0o755,  false, 0o000, 0o1755),
+        ("+t",           0o755,  false, 0o022, 0o1755),
+        ("a-s",          0o6755, false, 0o000, 0o755),
+        ("u-s",          0o6755, false, 0o000, 0o2755),
+        ("g-s",          0o6755, true,  0o000, 0o4755),
+        ("=",            0o6755, true,  0o000, 0o6000),
+        ("u=",           0o6755, true,  0o000, 0o6055),
+        ("g=",           0o6755, true,  0o000, 0o6705),
+        ("a=rwx",        0o6755, true,  0o000, 0o6777),
+        ("a=rwxs",       0o755,  true,  0o000, 0o6777),
+        ("-t",           0o1777, true,  0o000, 0o777),
+        ("a+X",          0o644,  false, 0o000, 0o644),
+        ("a+X",          0o744,  false, 0o000, 0o755),
+        ("a+X",          0o604,  false, 0o000, 0o604),
+        ("a+X",          0o601,  false, 0o000, 0o711),
+        ("a+X",          0o600,  true,  0o000, 0o711),
+        ("a-X",          0o777,  false, 0o000, 0o666),
+        ("a-X",          0o666,  false, 0o000, 0o666),
+        ("+X",           0o600,  true,  0o022, 0o711),
+        ("g=X",          0o700,  false, 0o000, 0o710),
+        ("u=rX",         0o600,  false, 0o000, 0o400),
+        ("go=u",         0o640,  false, 0o000, 0o666),
+        ("go=u",         0o750,  false, 0o000, 0o777),
+        ("ug=o",         0o604,  false, 0o000, 0o444),
+        ("u=g",          0o070,  false, 0o000, 0o770),
+        ("o=g",          0o750,  false, 0o000, 0o755),
+        ("g+u",          0o700,  false, 0o000, 0o770),
+        ("o-u",          0o777,  false, 0o000, 0o770),
+        ("a-g",          0o750,  false, 0o000, 0o200),
+        ("u=u",          0o600,  false, 0o000, 0o600),
+        ("go=u",         0o4750, false, 0o000, 0o4777),
+        ("u+g",          0o000,  false, 0o000, 0o000),
+        ("+u",           0o640,  false, 0o022, 0o644),
+        ("=u",           0o740,  false, 0o077, 0o700),
+        ("u+rwx,g-w,o=", 0o666,  false, 0o022, 0o740),
+        ("u+r,g+w,o+x",  0o000,  false, 0o000, 0o421),
+        ("u+r-w=x",      0o777,  false, 0o000, 0o177),
+        ("u=rwx,go=rx",  0o000,  false, 0o000, 0o755),
+        ("a=r,u+w",      0o000,  false, 0o000, 0o644),
+        ("+w,-w",        0o000,  false, 0o000, 0o000),
+        ("o=u,u=g",      0o640,  false, 0o000, 0o446),
+        ("u=r,+x",       0o000,  false, 0o022, 0o511),
+        ("ug+s,o+t",     0o755,  false, 0o000, 0o7755),
+        ("go-rwx+X",     0o777,  false, 0o000, 0o711),
+        ("u+rX-x",       0o644,  false, 0o000, 0o644),
+        ("+644",         0o000,  false, 0o022, 0o644),
+        ("-022",         0o777,  false, 0o022, 0o755),
+        ("=600",         0o777,  false, 0o022, 0o600),
+        ("=7",           0o777,  false, 0o000, 0o007),
+        ("+7777",        0o000,  false, 0o022, 0o7777),
+        ("-0",           0o777,  false, 0o000, 0o777),
+        ("=0",           0o777,  false, 0o000, 0o000),
+        ("+2000",        0o755,  true,  0o000, 0o2755),
+    ];
+
+    #[test]
+    fn test_parse() {
+        let valid = [
+            "755", "0", "7777", "00007777", "000000", "u+rwx,g-w,o=", "a=", "=", "+", "u+", "u=,g=", "+x", "ug+s,o+t", "u=g",
+            "g=u+w", "+u+g", "-w", "=644", "+7,u+r", "-7777", "=u,+7", "go-rwx+X", "u++r", "u+-r", "u+rw=x-X", "a+tX",
+            "u+r,+w",
+        ];
+        for mode in valid {
+            assert!(Mode::parse(mode).is_some(), "{}", mode);
+        }
+        let invalid = [
+            "", "8", "9", "07778", "17777", "0755x", "755 ", " 755", "75x", "ug", "u", "u,g+r", "u+q", "z+r", "U+r", "u+R",
+            "u:r", "u+r g+w", "u+r,", ",u+r", "u+r,,g+r", "u=go", "u=ug", "u+gw", "u+rg", "u+7", "u+644", "+7a", "+644x",
+            "+10000", "xyz",
+        ];
+        for mode in invalid {
+            assert!(Mode::parse(mode).is_none(), "{}", mode);
+        }
+    }
+
+    #[test]
+    fn test_adjust() {
+        for (mode, from, directory, umask, expected) in ADJUSTMENTS {
+            assert_eq!(expected, adjust(mode, from, directory, umask), "{} {:o}", mode, from);
+        }
+        assert_eq!((0o700, 0o7777), Mode::parse("700").unwrap().adjust(0o755, false, 0));
+        assert_eq!((0o755, 0o200), Mode::parse("u+w").unwrap().adjust(0o755, false, 0));
+        assert_eq!((0o555, 0o222), Mode::parse("-w").unwrap().adjust(0o755, false, 0));
+    }
+
+    #[test]
+    fn test_from_reference() {
+        assert_eq!((0o640, 0o7777), Mode::from_reference(0o100640).adjust(0o2755, true, 0o022));
+        assert_eq!(0o4711, Mode::from_reference(0o4711).adjust(0o644, false, 0o777).0);
+    }
+
+    #[test]
+    fn test_permission_string() {
+        assert_eq!("r--r--r--", permission_string(0o444));
+        assert_eq!("rwxr-x---", permission_string(0o750));
+        assert_eq!("rwsr-Sr-t", permission_string(0o7745));
+    }
+}
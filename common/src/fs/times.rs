@@ -0,0 +1,100 @@
+use std::ffi::CString;
+use std::fs::{FileTimes, Metadata};
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use std::time::SystemTime;
+use crate::time::local::epoch_seconds;
+
+///
+/// Access and modification times to give a file; a time that's [None] is left as it is.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Times {
+    pub accessed: Option<SystemTime>,
+    pub modified: Option<SystemTime>,
+}
+
+impl Times {
+    ///
+    /// Both times of a file, to be given to another; times the platform can't tell are left out.
+    ///
+    pub fn of(metadata: &Metadata) -> Times {
+        Times { accessed: metadata.accessed().ok(), modified: metadata.modified().ok() }
+    }
+
+    ///
+    /// The times for [std::fs::File::set_times], for a file that's open already.
+    ///
+    pub fn file_times(&self) -> FileTimes {
+        let mut times = FileTimes::new();
+        if let Some(accessed) = self.accessed {
+            times = times.set_accessed(accessed);
+        }
+        if let Some(modified) = self.modified {
+            times = times.set_modified(modified);
+        }
+        times
+    }
+
+    ///
+    /// Give the times to the file at `path`, without opening it; unless `follow` is set, a symbolic
+    /// link gets them itself rather than the file it leads to.
+    ///
+    pub fn apply(&self, path: &Path, follow: bool) -> io::Result<()> {
+        let path = CString::new(path.as_os_str().as_bytes()).map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+        let times = [timespec(self.accessed), timespec(self.modified)];
+        let flags = if follow { 0 } else { libc::AT_SYMLINK_NOFOLLOW };
+
+        /* SAFETY: utimensat only reads the path and the two timespecs */
+        let result = unsafe { libc::utimensat(libc::AT_FDCWD, path.as_ptr(), times.as_ptr(), flags) };
+        if result != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+fn timespec(time: Option<SystemTime>) -> libc::timespec {
+    match time {
+        Some(time) => {
+            let (seconds, nanoseconds) = epoch_seconds(time);
+            libc::timespec { tv_sec: seconds as libc::time_t, tv_nsec: nanoseconds as libc::c_long }
+        }
+        None => libc::timespec { tv_sec: 0, tv_nsec: libc::UTIME_OMIT },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::time::{Duration, UNIX_EPOCH};
+    use crate::fs::times::Times;
+
+    #[test]
+    fn test_apply() {
+        let dir = std::env::temp_dir().join(format!("common-times-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("file");
+        let link = dir.join("link");
+        File::create(&file).unwrap();
+        let _ = std::fs::remove_file(&link);
+        std::os::unix::fs::symlink("file", &link).unwrap();
+
+        let accessed = UNIX_EPOCH + Duration::new(1_000_000, 5);
+        let modified = UNIX_EPOCH + Duration::new(2_000_000, 7);
+        Times { accessed: Some(accessed), modified: Some(modified) }.apply(&file, true).unwrap();
+        assert_eq!(Times { accessed: Some(accessed), modified: Some(modified) }, Times::of(&std::fs::metadata(&file).unwrap()));
+
+        Times { accessed: None, modified: Some(accessed) }.apply(&file, true).unwrap();
+        assert_eq!(Times { accessed: Some(accessed), modified: Some(accessed) }, Times::of(&std::fs::metadata(&file).unwrap()));
+
+        Times { accessed: Some(modified), modified: Some(modified) }.apply(&link, false).unwrap();
+        assert_eq!(Some(modified), std::fs::symlink_metadata(&link).unwrap().modified().ok());
+        assert_eq!(Some(accessed), std::fs::metadata(&file).unwrap().modified().ok());
+
+        File::options().write(true).open(&file).unwrap().set_times(Times { accessed: None, modified: Some(modified) }.file_times()).unwrap();
+        assert_eq!(Times { accessed: Some(accessed), modified: Some(modified) }, Times::of(&std::fs::metadata(&file).unwrap()));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,94 @@
+use std::ffi::CString;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+const ATTRIBUTE_BUFFER: usize = 1024;
+
+///
+/// The names of a file's extended attributes; a symbolic link's own are listed, not its target's.
+///
+pub fn list_attributes(path: &Path) -> io::Result<Vec<CString>> {
+    let path = c_path(path)?;
+    let list = read_sized(|buffer, size| {
+        /* SAFETY: llistxattr writes no more than size bytes into the buffer */
+        unsafe { libc::llistxattr(path.as_ptr(), buffer, size) }
+    })?;
+    Ok(list.split(|&byte| byte == 0).filter(|name| !name.is_empty()).filter_map(|name| CString::new(name).ok()).collect())
+}
+
+///
+/// The value of one of a file's extended attributes, without following a symbolic link.
+///
+pub fn get_attribute(path: &Path, name: &CString) -> io::Result<Vec<u8>> {
+    let path = c_path(path)?;
+    read_sized(|buffer, size| {
+        /* SAFETY: lgetxattr writes no more than size bytes into the buffer */
+        unsafe { libc::lgetxattr(path.as_ptr(), name.as_ptr(), buffer.cast(), size) }
+    })
+}
+
+///
+/// Give a file an extended attribute, replacing any it has by that name, without following a
+/// symbolic link.
+///
+pub fn set_attribute(path: &Path, name: &CString, value: &[u8]) -> io::Result<()> {
+    let path = c_path(path)?;
+    /* SAFETY: lsetxattr only reads the path, the name and the value */
+    if unsafe { libc::lsetxattr(path.as_ptr(), name.as_ptr(), value.as_ptr().cast(), value.len(), 0) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+///
+/// Call one of the `*xattr` functions that fill a buffer, growing the buffer until what's asked
+/// for fits.
+///
+fn read_sized(call: impl Fn(*mut libc::c_char, usize) -> libc::ssize_t) -> io::Result<Vec<u8>> {
+    let mut buffer = vec![0u8; ATTRIBUTE_BUFFER];
+    loop {
+        let length = call(buffer.as_mut_ptr().cast(), buffer.len());
+        if length >= 0 {
+            buffer.truncate(length as usize);
+            return Ok(buffer);
+        }
+        let error = io::Error::last_os_error();
+        if error.raw_os_error() != Some(libc::ERANGE) {
+            return Err(error);
+        }
+        /* the size needed, which may have changed again by the time it's asked for */
+        let needed = call(std::ptr::null_mut(), 0);
+        buffer.resize((needed.max(0) as usize).max(buffer.len() * 2), 0);
+    }
+}
+
+fn c_path(path: &Path) -> io::Result<CString> {
+    CString::new(path.as_os_str().as_bytes()).map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+    use crate::fs::xattr::{get_attribute, list_attributes, set_attribute};
+
+    #[test]
+    fn test_attributes() {
+        let dir = std::env::temp_dir().join(format!("common-xattr-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("file");
+        std::fs::write(&file, b"").unwrap();
+        let name = CString::new("user.test").unwrap();
+
+        /* not every file system takes user attributes */
+        if set_attribute(&file, &name, b"value").is_ok() {
+            assert!(list_attributes(&file).unwrap().contains(&name));
+            assert_eq!(b"value".to_vec(), get_attribute(&file, &name).unwrap());
+            let long = vec![b'x'; 3000];
+            set_attribute(&file, &name, &long).unwrap();
+            assert_eq!(long, get_attribute(&file, &name).unwrap());
+        }
+        assert!(get_attribute(&file, &CString::new("user.missing").unwrap()).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
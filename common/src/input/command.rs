@@ -28,7 +28,7 @@ pub struct StringCommand {
 ///
 pub struct YearCommand {
     pub def: CommandDefinition,
-    pub value: Option<u16>,
+    pub value: Option<i32>,
 }
 
 ///
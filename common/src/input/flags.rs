@@ -1,6 +1,7 @@
 pub mod flag_data;
 pub mod flags_common;
 pub mod flags_long;
+pub mod flags_options;
 pub mod flags_short;
 pub mod flags_unrecognized;
 mod flags_dash_enf;
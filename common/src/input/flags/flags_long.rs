@@ -87,22 +87,22 @@ mod tests {
         assert_eq!(2, fv.flag_definitions.len());
 
         /* success when exact match */
-        assert_eq!(false, fv.is_valid_flag("-v"));
-        assert_eq!(true, fv.is_valid_flag("--verbose"));
+        assert!(!fv.is_valid_flag("-v"));
+        assert!(fv.is_valid_flag("--verbose"));
 
         /* fail when no dashes */
-        assert_eq!(false, fv.is_valid_flag("v"));
-        assert_eq!(false, fv.is_valid_flag("verbose"));
+        assert!(!fv.is_valid_flag("v"));
+        assert!(!fv.is_valid_flag("verbose"));
 
         /* fail when dash count doesn't match */
-        assert_eq!(false, fv.is_valid_flag("--v"));
-        assert_eq!(false, fv.is_valid_flag("-valid"));
+        assert!(!fv.is_valid_flag("--v"));
+        assert!(!fv.is_valid_flag("-valid"));
 
         /* fail when names don't match */
-        assert_eq!(false, fv.is_valid_flag("h"));
-        assert_eq!(false, fv.is_valid_flag("help"));
-        assert_eq!(false, fv.is_valid_flag("-h"));
-        assert_eq!(false, fv.is_valid_flag("-help"));
+        assert!(!fv.is_valid_flag("h"));
+        assert!(!fv.is_valid_flag("help"));
+        assert!(!fv.is_valid_flag("-h"));
+        assert!(!fv.is_valid_flag("-help"));
     }
 
     #[test]
@@ -117,21 +117,21 @@ mod tests {
         assert_eq!(4, fv.flag_definitions.len());
 
         /* success when exact match */
-        assert_eq!(true, fv.is_valid_flag("-v"));
-        assert_eq!(true, fv.is_valid_flag("--verbose"));
+        assert!(fv.is_valid_flag("-v"));
+        assert!(fv.is_valid_flag("--verbose"));
 
         /* success, as dashes are not enforced */
-        assert_eq!(true, fv.is_valid_flag("v"));
-        assert_eq!(true, fv.is_valid_flag("verbose"));
+        assert!(fv.is_valid_flag("v"));
+        assert!(fv.is_valid_flag("verbose"));
 
         /* success, as dashes are not enforced */
-        assert_eq!(true, fv.is_valid_flag("--v"));
-        assert_eq!(true, fv.is_valid_flag("-verbose"));
+        assert!(fv.is_valid_flag("--v"));
+        assert!(fv.is_valid_flag("-verbose"));
 
         /* fail when names don't match */
-        assert_eq!(false, fv.is_valid_flag("h"));
-        assert_eq!(false, fv.is_valid_flag("help"));
-        assert_eq!(false, fv.is_valid_flag("-h"));
-        assert_eq!(false, fv.is_valid_flag("-help"));
+        assert!(!fv.is_valid_flag("h"));
+        assert!(!fv.is_valid_flag("help"));
+        assert!(!fv.is_valid_flag("-h"));
+        assert!(!fv.is_valid_flag("-help"));
     }
 }
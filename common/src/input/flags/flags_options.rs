@@ -0,0 +1,263 @@
+const DASH: char = '-';
+const END_OF_OPTIONS: &str = "--";
+
+///
+/// Whether an option takes a value, and how.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionValue {
+    /// A switch; `-v`, `--verbose`.
+    None,
+    /// A value is always required; `-n 5`, `-n5`, `--lines 5`, `--lines=5`.
+    Required,
+    /// A value may be attached to the long form only; `--follow`, `--follow=name`.
+    Optional,
+}
+
+///
+/// Definition of one supported option.
+///
+/// `id` is what the caller matches on; it doesn't need to match either spelling of the option.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OptionSpec {
+    pub id: &'static str,
+    pub short: Option<char>,
+    pub long: Option<&'static str>,
+    pub value: OptionValue,
+}
+
+impl OptionSpec {
+    ///
+    /// An option that takes no value.
+    ///
+    pub const fn flag(id: &'static str, short: Option<char>, long: Option<&'static str>) -> OptionSpec {
+        OptionSpec { id, short, long, value: OptionValue::None }
+    }
+
+    ///
+    /// An option that must be given a value.
+    ///
+    pub const fn required(id: &'static str, short: Option<char>, long: Option<&'static str>) -> OptionSpec {
+        OptionSpec { id, short, long, value: OptionValue::Required }
+    }
+
+    ///
+    /// An option that may be given a value with `--long=value`.
+    ///
+    pub const fn optional(id: &'static str, short: Option<char>, long: Option<&'static str>) -> OptionSpec {
+        OptionSpec { id, short, long, value: OptionValue::Optional }
+    }
+
+    fn display_name(&self) -> String {
+        match (self.long, self.short) {
+            (Some(long), _) => format!("--{}", long),
+            (None, Some(short)) => format!("-{}", short),
+            (None, None) => self.id.to_string(),
+        }
+    }
+}
+
+///
+/// One argument, after matching it against the supported options.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParsedArgument {
+    Option { id: &'static str, value: Option<String> },
+    Operand(String),
+}
+
+///
+/// Reasons an argument list can't be parsed.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OptionError {
+    InvalidOption(char),
+    UnrecognizedOption(String),
+    AmbiguousOption(String),
+    MissingValue(String),
+    UnexpectedValue(String),
+}
+
+impl OptionError {
+    ///
+    /// Describe the error the way the coreutils do, prefixed with the program name.
+    ///
+    pub fn message(&self, program: &str) -> String {
+        match self {
+            OptionError::InvalidOption(flag) => format!("{}: invalid option -- '{}'", program, flag),
+            OptionError::UnrecognizedOption(argument) => format!("{}: unrecognized option '{}'", program, argument),
+            OptionError::AmbiguousOption(argument) => format!("{}: option '{}' is ambiguous", program, argument),
+            OptionError::MissingValue(option) if option.starts_with(END_OF_OPTIONS) => format!("{}: option '{}' requires an argument", program, option),
+            OptionError::MissingValue(option) => format!("{}: option requires an argument -- '{}'", program, option),
+            OptionError::UnexpectedValue(option) => format!("{}: option '{}' doesn't allow an argument", program, option),
+        }
+    }
+}
+
+///
+/// Split command line arguments (without the program name) into options and operands.
+///
+/// Short options may be clustered (`-fq`) and the last one in a cluster may carry its value (`-n5`).
+/// Long options may be abbreviated to any unambiguous prefix.  Options and operands may be mixed
+/// freely; everything after `--` is an operand, as is a lone `-`.
+///
+pub fn parse_options(args: &[String], specs: &[OptionSpec]) -> Result<Vec<ParsedArgument>, OptionError> {
+    let mut parsed = vec![];
+    let mut iter = args.iter();
+
+    while let Some(argument) = iter.next() {
+        if argument == END_OF_OPTIONS {
+            parsed.extend(iter.by_ref().map(|a| ParsedArgument::Operand(a.clone())));
+        } else if let Some(long) = argument.strip_prefix(END_OF_OPTIONS) {
+            parsed.push(parse_long(argument, long, specs, &mut iter)?);
+        } else if argument.len() > 1 && argument.starts_with(DASH) {
+            parsed.extend(parse_short_cluster(&argument[1..], specs, &mut iter)?);
+        } else {
+            parsed.push(ParsedArgument::Operand(argument.clone()));
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn parse_long<'a>(argument: &str, long: &str, specs: &[OptionSpec], rest: &mut impl Iterator<Item = &'a String>) -> Result<ParsedArgument, OptionError> {
+    let (name, attached) = match long.split_once('=') {
+        Some((name, value)) => (name, Some(value.to_string())),
+        None => (long, None),
+    };
+    let spec = find_long(name, specs).map_err(|ambiguous| {
+        if ambiguous {
+            OptionError::AmbiguousOption(argument.to_string())
+        } else {
+            OptionError::UnrecognizedOption(argument.to_string())
+        }
+    })?;
+
+    let value = match (spec.value, attached) {
+        (OptionValue::None, Some(_)) => return Err(OptionError::UnexpectedValue(spec.display_name())),
+        (OptionValue::None, None) => None,
+        (OptionValue::Optional, attached) => attached,
+        (OptionValue::Required, Some(value)) => Some(value),
+        (OptionValue::Required, None) => Some(rest.next().cloned().ok_or_else(|| OptionError::MissingValue(spec.display_name()))?),
+    };
+
+    Ok(ParsedArgument::Option { id: spec.id, value })
+}
+
+fn parse_short_cluster<'a>(cluster: &str, specs: &[OptionSpec], rest: &mut impl Iterator<Item = &'a String>) -> Result<Vec<ParsedArgument>, OptionError> {
+    let mut parsed = vec![];
+
+    for (offset, flag) in cluster.char_indices() {
+        let spec = specs.iter()
+            .find(|spec| spec.short == Some(flag))
+            .ok_or(OptionError::InvalidOption(flag))?;
+
+        /* a value-taking option swallows the remainder of the cluster, or else the next argument */
+        if spec.value == OptionValue::Required {
+            let remainder = &cluster[offset + flag.len_utf8()..];
+            let value = if remainder.is_empty() {
+                rest.next().cloned().ok_or_else(|| OptionError::MissingValue(flag.to_string()))?
+            } else {
+                remainder.to_string()
+            };
+            parsed.push(ParsedArgument::Option { id: spec.id, value: Some(value) });
+            break;
+        }
+
+        parsed.push(ParsedArgument::Option { id: spec.id, value: None });
+    }
+
+    Ok(parsed)
+}
+
+///
+/// Find the spec for a long name; an exact match wins, then a unique prefix.  The error is true when
+/// the name is a prefix of more than one option.
+///
+fn find_long<'s>(name: &str, specs: &'s [OptionSpec]) -> Result<&'s OptionSpec, bool> {
+    if name.is_empty() {
+        return Err(false);
+    }
+    if let Some(spec) = specs.iter().find(|spec| spec.long == Some(name)) {
+        return Ok(spec);
+    }
+
+    let candidates: Vec<&OptionSpec> = specs.iter()
+        .filter(|spec| spec.long.is_some_and(|long| long.starts_with(name)))
+        .collect();
+    match candidates.as_slice() {
+        [single] => Ok(single),
+        [] => Err(false),
+        _ => Err(true),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::flags::flags_options::{parse_options, OptionError, OptionSpec, ParsedArgument};
+
+    const SPECS: [OptionSpec; 5] = [
+        OptionSpec::required("lines", Some('n'), Some("lines")),
+        OptionSpec::flag("quiet", Some('q'), Some("quiet")),
+        OptionSpec::flag("quick", None, Some("quick")),
+        OptionSpec::optional("follow", Some('f'), Some("follow")),
+        OptionSpec::flag("verbose", Some('v'), Some("verbose")),
+    ];
+
+    fn parse(line: &str) -> Result<Vec<ParsedArgument>, OptionError> {
+        let args: Vec<String> = line.split_whitespace().map(|s| s.to_string()).collect();
+        parse_options(&args, &SPECS)
+    }
+
+    fn option(id: &'static str, value: Option<&str>) -> ParsedArgument {
+        ParsedArgument::Option { id, value: value.map(|v| v.to_string()) }
+    }
+
+    fn operand(value: &str) -> ParsedArgument {
+        ParsedArgument::Operand(value.to_string())
+    }
+
+    #[test]
+    fn test_values() {
+        assert_eq!(Ok(vec![option("lines", Some("5"))]), parse("-n 5"));
+        assert_eq!(Ok(vec![option("lines", Some("5"))]), parse("-n5"));
+        assert_eq!(Ok(vec![option("lines", Some("+5"))]), parse("--lines +5"));
+        assert_eq!(Ok(vec![option("lines", Some("-5"))]), parse("--lines=-5"));
+        assert_eq!(Ok(vec![option("lines", Some("5"))]), parse("--li=5"));
+        assert_eq!(Ok(vec![option("follow", None), option("follow", Some("name"))]), parse("--follow --follow=name"));
+    }
+
+    #[test]
+    fn test_clusters() {
+        assert_eq!(Ok(vec![option("quiet", None), option("verbose", None)]), parse("-qv"));
+        assert_eq!(Ok(vec![option("follow", None), option("lines", Some("20"))]), parse("-fn20"));
+        assert_eq!(Ok(vec![option("quiet", None), option("lines", Some("v"))]), parse("-qnv"));
+    }
+
+    #[test]
+    fn test_operands() {
+        assert_eq!(Ok(vec![operand("a"), option("quiet", None), operand("-"), operand("b")]), parse("a -q - b"));
+        assert_eq!(Ok(vec![option("quiet", None), operand("-v"), operand("--lines")]), parse("-q -- -v --lines"));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(Err(OptionError::InvalidOption('x')), parse("-qx"));
+        assert_eq!(Err(OptionError::UnrecognizedOption("--bogus".to_string())), parse("--bogus"));
+        assert_eq!(Err(OptionError::AmbiguousOption("--qui".to_string())), parse("--qui"));
+        assert_eq!(Err(OptionError::MissingValue("n".to_string())), parse("-n"));
+        assert_eq!(Err(OptionError::MissingValue("--lines".to_string())), parse("--lines"));
+        assert_eq!(Err(OptionError::UnexpectedValue("--quiet".to_string())), parse("--quiet=yes"));
+    }
+
+    #[test]
+    fn test_messages() {
+        assert_eq!("tail: invalid option -- 'x'", OptionError::InvalidOption('x').message("tail"));
+        assert_eq!("tail: unrecognized option '--bogus'", OptionError::UnrecognizedOption("--bogus".to_string()).message("tail"));
+        assert_eq!("tail: option '--qui' is ambiguous", OptionError::AmbiguousOption("--qui".to_string()).message("tail"));
+        assert_eq!("tail: option requires an argument -- 'n'", OptionError::MissingValue("n".to_string()).message("tail"));
+        assert_eq!("tail: option '--lines' requires an argument", OptionError::MissingValue("--lines".to_string()).message("tail"));
+        assert_eq!("tail: option '--quiet' doesn't allow an argument", OptionError::UnexpectedValue("--quiet".to_string()).message("tail"));
+    }
+}
@@ -4,10 +4,12 @@ use crate::input::flags::flags_dash_enf::validate_dashes;
 use crate::input::flags::flags_unrecognized::UnrecognizedFlag;
 
 const DASH_COUNT : usize = 1;
+const DASH: char = '-';
 
 pub struct ShortFlags {
     pub flag_definitions: Vec<Flag>,
     enforce_dash_count: bool,
+    strict: bool,
 }
 
 impl ShortFlags {
@@ -27,7 +29,36 @@ impl ShortFlags {
         }
 
         /* done */
-        ShortFlags { flag_definitions: fd, enforce_dash_count }
+        ShortFlags { flag_definitions: fd, enforce_dash_count, strict: false }
+    }
+
+    ///
+    /// Create flags in strict mode, where a cluster is only valid when it's exactly one dash
+    /// followed by one or more defined flags, taken as is, without trimming.  Checking a cluster
+    /// never panics, so this suits checking arguments that may or may not be options.
+    ///
+    pub fn new_strict_from_combined_string(flags: &str) -> ShortFlags {
+        let name = flags.strip_prefix(DASH).unwrap_or(flags);
+        let fd = name.chars()
+            .map(|c| Flag { expected_dash_count: DASH_COUNT, name: c.to_string() })
+            .collect();
+        ShortFlags { flag_definitions: fd, enforce_dash_count: true, strict: true }
+    }
+
+    fn find_strict_matching_flags(&self, flag: &str) -> (Vec<Flag>, Vec<UnrecognizedFlag>) {
+        /* anything but a single dash and a name isn't a cluster at all */
+        let Some(name) = flag.strip_prefix(DASH).filter(|name| !name.is_empty() && !name.starts_with(DASH)) else {
+            return (vec!(), vec!(UnrecognizedFlag { index: 0, argument: Some(flag.to_string()) }));
+        };
+
+        let mut flags: Vec<Flag> = vec!();
+        let mut unrecognized: Vec<UnrecognizedFlag> = vec!();
+        for (pf_idx, potential_flag) in name.chars().map(|c| c.to_string()).enumerate() {
+            let (pf_flags, pf_unrecognized) = self.search_for_name_matches(pf_idx, &potential_flag);
+            flags.extend(pf_flags);
+            unrecognized.extend(pf_unrecognized);
+        }
+        (flags, unrecognized)
     }
 
     fn search_for_name_matches(&self, index : usize, name: &str) -> (Vec<Flag>, Vec<UnrecognizedFlag>) {
@@ -55,6 +86,10 @@ impl FlagValidator for ShortFlags {
     }
 
     fn find_matching_flags(&self, flag: &str) -> (Vec<Flag>, Vec<UnrecognizedFlag>) {
+        if self.strict {
+            return self.find_strict_matching_flags(flag);
+        }
+
         let (dashes, name) = read_dashes_and_name(flag);
         let mut flags: Vec<Flag> = vec!();
         let mut unrecognized: Vec<UnrecognizedFlag> = vec!();
@@ -93,17 +128,17 @@ mod tests {
         assert_eq!(5, fv.flag_definitions.len());
 
         /* success when exact match */
-        assert_eq!(true, fv.is_valid_flag("-v"));
+        assert!(fv.is_valid_flag("-v"));
 
         /* fail when not exact match */
-        assert_eq!(false, fv.is_valid_flag("v"));
-        assert_eq!(false, fv.is_valid_flag("--v"));
+        assert!(!fv.is_valid_flag("v"));
+        assert!(!fv.is_valid_flag("--v"));
 
         /* fail when names don't match */
-        assert_eq!(false, fv.is_valid_flag("h"));
-        assert_eq!(false, fv.is_valid_flag("help"));
-        assert_eq!(false, fv.is_valid_flag("-h"));
-        assert_eq!(false, fv.is_valid_flag("-help"));
+        assert!(!fv.is_valid_flag("h"));
+        assert!(!fv.is_valid_flag("help"));
+        assert!(!fv.is_valid_flag("-h"));
+        assert!(!fv.is_valid_flag("-help"));
 
         /* test with combined args successful */
         let (ca1f, ca1u) = fv.find_matching_flags("-vq");
@@ -125,15 +160,15 @@ mod tests {
         assert_eq!(5, fv.flag_definitions.len());
 
         /* success if names match; because dashes not enforced */
-        assert_eq!(true, fv.is_valid_flag("-v"));
-        assert_eq!(true, fv.is_valid_flag("v"));
-        assert_eq!(true, fv.is_valid_flag("--v"));
+        assert!(fv.is_valid_flag("-v"));
+        assert!(fv.is_valid_flag("v"));
+        assert!(fv.is_valid_flag("--v"));
 
         /* fail when names dont match */
-        assert_eq!(false, fv.is_valid_flag("h"));
-        assert_eq!(false, fv.is_valid_flag("help"));
-        assert_eq!(false, fv.is_valid_flag("-h"));
-        assert_eq!(false, fv.is_valid_flag("-help"));
+        assert!(!fv.is_valid_flag("h"));
+        assert!(!fv.is_valid_flag("help"));
+        assert!(!fv.is_valid_flag("-h"));
+        assert!(!fv.is_valid_flag("-help"));
 
         /* test with combined args successful */
         let (ca1f, ca1u) = fv.find_matching_flags("vq");
@@ -145,4 +180,33 @@ mod tests {
         assert_eq!(0, ca2f.len());
         assert_eq!(3, ca2u.len());
     }
+
+    #[test]
+    fn test_strict() {
+        /* create object under test */
+        let fv = ShortFlags::new_strict_from_combined_string("-neE");
+
+        /* verify our creation */
+        assert_eq!(3, fv.flag_definitions.len());
+
+        /* success when every flag in the cluster is defined */
+        assert!(fv.is_valid_flag("-n"));
+        assert!(fv.is_valid_flag("-neE"));
+        assert!(fv.is_valid_flag("-nnn"));
+
+        /* fail when any flag in the cluster isn't */
+        assert!(!fv.is_valid_flag("-nx"));
+        let (flags, unrecognized) = fv.find_matching_flags("-nxe");
+        assert_eq!(2, flags.len());
+        assert_eq!(1, unrecognized.len());
+
+        /* fail, without panicking, when it isn't a single dash and a name, as is */
+        assert!(!fv.is_valid_flag("-"));
+        assert!(!fv.is_valid_flag("--"));
+        assert!(!fv.is_valid_flag("--n"));
+        assert!(!fv.is_valid_flag("n"));
+        assert!(!fv.is_valid_flag(" -n"));
+        assert!(!fv.is_valid_flag("-n "));
+        assert!(!fv.is_valid_flag(""));
+    }
 }
@@ -1,3 +1,5 @@
+use std::io::Write;
+
 ///
 /// Storage for expected errors.
 ///
@@ -6,3 +8,36 @@ pub struct KnownError {
     pub code: i32,
     pub message: Option<String>,
 }
+
+impl KnownError {
+    pub fn new(code: i32, message: String) -> KnownError {
+        KnownError {
+            code,
+            message: Some(message),
+        }
+    }
+}
+
+///
+/// Write the message of each error to `err`, one per line.
+///
+pub fn report(err: &mut dyn Write, errors: &[KnownError]) {
+    for error in errors {
+        if let Some(message) = &error.message {
+            let _ = writeln!(err, "{}", message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::known_error::{report, KnownError};
+
+    #[test]
+    fn test_report() {
+        let errors = vec![KnownError::new(1, "a: first".to_string()), KnownError { code: 0, message: None }, KnownError::new(2, "a: second".to_string())];
+        let mut err = Vec::new();
+        report(&mut err, &errors);
+        assert_eq!("a: first\na: second\n", String::from_utf8(err).unwrap());
+    }
+}
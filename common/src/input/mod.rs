@@ -1,3 +1,5 @@
 pub mod known_error;
 pub mod command;
-pub mod flags;
\ No newline at end of file
+pub mod flags;
+pub mod size;
+pub mod source;
\ No newline at end of file
@@ -0,0 +1,69 @@
+const BLOCK_SUFFIX: char = 'b';
+const UNITS: &str = "KMGTPEZY";
+
+///
+/// Parse a count with an optional multiplier suffix, as `head`, `tail` and `sort` accept them:
+/// `b` 512, `kB` 1000, `K` 1024, `MB` 1000*1000, `M` 1024*1024, and so on for G, T, P, E, Z, Y.
+/// Binary prefixes may be spelled out too: `KiB` = `K`, `MiB` = `M`.
+///
+/// [None] when the text isn't a count, or the count doesn't fit in a [u64].
+///
+pub fn parse_size(text: &str) -> Option<u64> {
+    let digits_end = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+    let (digits, suffix) = text.split_at(digits_end);
+    if digits.is_empty() {
+        return None;
+    }
+
+    let number: u64 = digits.parse().ok()?;
+    number.checked_mul(multiplier(suffix)?)
+}
+
+fn multiplier(suffix: &str) -> Option<u64> {
+    let mut chars = suffix.chars();
+    let unit = match chars.next() {
+        None => return Some(1),
+        Some(BLOCK_SUFFIX) if suffix.len() == 1 => return Some(512),
+        Some('k') => 'K',
+        Some(unit) => unit,
+    };
+    let power = UNITS.find(unit)? as u32 + 1;
+
+    let base: u64 = match chars.as_str() {
+        "" | "iB" => 1024,
+        "B" => 1000,
+        _ => return None,
+    };
+    base.checked_pow(power)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::size::parse_size;
+
+    #[test]
+    fn test_plain() {
+        assert_eq!(Some(0), parse_size("0"));
+        assert_eq!(Some(42), parse_size("42"));
+        assert_eq!(None, parse_size(""));
+        assert_eq!(None, parse_size("-1"));
+        assert_eq!(None, parse_size("ten"));
+        assert_eq!(None, parse_size("99999999999999999999"));
+    }
+
+    #[test]
+    fn test_suffixes() {
+        assert_eq!(Some(1024), parse_size("2b"));
+        assert_eq!(Some(1000), parse_size("1kB"));
+        assert_eq!(Some(1024), parse_size("1K"));
+        assert_eq!(Some(1024), parse_size("1k"));
+        assert_eq!(Some(1024), parse_size("1KiB"));
+        assert_eq!(Some(3 * 1024 * 1024), parse_size("3M"));
+        assert_eq!(Some(1_000_000), parse_size("1MB"));
+        assert_eq!(Some(1 << 60), parse_size("1E"));
+        assert_eq!(None, parse_size("1Z"));
+        assert_eq!(None, parse_size("1Q"));
+        assert_eq!(None, parse_size("1Kb"));
+        assert_eq!(None, parse_size("1bB"));
+    }
+}
@@ -0,0 +1,97 @@
+use std::fs::File;
+use std::io::{self, Read};
+
+const STDIN_OPERAND: &str = "-";
+const STDIN_NAME: &str = "standard input";
+
+///
+/// Somewhere to read input from: a named file, or standard input.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    Stdin,
+    File(String),
+}
+
+impl InputSource {
+    ///
+    /// Map a file operand to its source; `-` is standard input.
+    ///
+    pub fn from_operand(operand: &str) -> InputSource {
+        if operand == STDIN_OPERAND {
+            InputSource::Stdin
+        } else {
+            InputSource::File(operand.to_string())
+        }
+    }
+
+    ///
+    /// Map all file operands to sources; with no operands, standard input is read.
+    ///
+    pub fn from_operands(operands: &[String]) -> Vec<InputSource> {
+        if operands.is_empty() {
+            vec![InputSource::Stdin]
+        } else {
+            operands.iter().map(|operand| InputSource::from_operand(operand)).collect()
+        }
+    }
+
+    ///
+    /// Name used in headers and error messages.
+    ///
+    pub fn name(&self) -> &str {
+        match self {
+            InputSource::Stdin => STDIN_NAME,
+            InputSource::File(path) => path,
+        }
+    }
+
+    ///
+    /// Open the source for reading.
+    ///
+    pub fn open(&self) -> io::Result<Box<dyn Read>> {
+        match self {
+            InputSource::Stdin => Ok(Box::new(io::stdin())),
+            InputSource::File(path) => Ok(Box::new(File::open(path)?)),
+        }
+    }
+}
+
+///
+/// Describe an I/O error without the `(os error N)` suffix that [io::Error] adds, the way the coreutils
+/// report them: `No such file or directory`.
+///
+pub fn describe_io_error(error: &io::Error) -> String {
+    let text = error.to_string();
+    match text.find(" (os error ") {
+        Some(index) => text[..index].to_string(),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use crate::input::source::{describe_io_error, InputSource};
+
+    #[test]
+    fn test_from_operands() {
+        assert_eq!(vec![InputSource::Stdin], InputSource::from_operands(&[]));
+        assert_eq!(
+            vec![InputSource::File("a".to_string()), InputSource::Stdin],
+            InputSource::from_operands(&["a".to_string(), "-".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_name() {
+        assert_eq!("standard input", InputSource::Stdin.name());
+        assert_eq!("a.txt", InputSource::File("a.txt".to_string()).name());
+    }
+
+    #[test]
+    fn test_describe_io_error() {
+        assert_eq!("No such file or directory", describe_io_error(&io::Error::from_raw_os_error(libc::ENOENT)));
+        assert_eq!("custom", describe_io_error(&io::Error::other("custom")));
+    }
+}
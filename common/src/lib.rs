@@ -1 +1,6 @@
-pub mod input;
\ No newline at end of file
+pub mod fs;
+pub mod input;
+pub mod output;
+pub mod time;
+#[cfg(feature = "test-support")]
+pub mod test_support;
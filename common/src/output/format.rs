@@ -0,0 +1,4 @@
+pub mod argument;
+pub mod directive;
+pub mod escape;
+pub mod render;
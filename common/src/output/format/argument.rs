@@ -0,0 +1,358 @@
+///
+/// What was wrong with an argument given as a number.  The value is still used, as far as it
+/// could be read.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgumentProblem {
+    /// Too big or too small; the value is the nearest that fits.
+    OutOfRange,
+    /// A number followed by something else.
+    NotCompletelyConverted,
+    /// Not a number at all; the value is zero.
+    NotNumeric,
+    /// A character constant like `'ab` with more after the character; only a warning.
+    IgnoredAfterCharacter(String),
+}
+
+impl ArgumentProblem {
+    ///
+    /// Whether the problem makes the program fail in the end, rather than just being mentioned.
+    ///
+    pub fn is_failure(&self) -> bool {
+        !matches!(self, ArgumentProblem::IgnoredAfterCharacter(_))
+    }
+
+    ///
+    /// Describe the problem with `argument` the way the coreutils do, prefixed with the program name.
+    ///
+    pub fn message(&self, program: &str, argument: &str) -> String {
+        match self {
+            ArgumentProblem::OutOfRange => format!("{}: '{}': Numerical result out of range", program, argument),
+            ArgumentProblem::NotCompletelyConverted => format!("{}: '{}': value not completely converted", program, argument),
+            ArgumentProblem::NotNumeric => format!("{}: '{}': expected a numeric value", program, argument),
+            ArgumentProblem::IgnoredAfterCharacter(rest) => format!("{}: warning: {}: character(s) following character constant have been ignored", program, rest),
+        }
+    }
+}
+
+///
+/// A number read from an argument, along with anything wrong with it.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Converted<T> {
+    pub value: T,
+    pub problem: Option<ArgumentProblem>,
+}
+
+impl<T> Converted<T> {
+    fn new(value: T, problem: Option<ArgumentProblem>) -> Converted<T> {
+        Converted { value, problem }
+    }
+}
+
+///
+/// Read a signed integer the way `printf` does: decimal, `0x` hexadecimal or `0` octal after
+/// optional leading space and a sign, or the character code of a character after `'` or `"`.
+///
+pub fn to_signed(argument: &str) -> Converted<i64> {
+    if let Some(converted) = character_constant(argument) {
+        return Converted::new(converted.value as i64, converted.problem);
+    }
+    let (negative, magnitude, used, overflow) = read_integer(argument);
+    let value = match (negative, i64::try_from(magnitude)) {
+        (false, Ok(value)) => Some(value),
+        (true, _) if magnitude <= i64::MIN.unsigned_abs() => Some(0i64.wrapping_sub_unsigned(magnitude)),
+        _ => None,
+    };
+    match value.filter(|_| !overflow) {
+        Some(value) => Converted::new(value, trailing(argument, used)),
+        None => Converted::new(if negative { i64::MIN } else { i64::MAX }, Some(ArgumentProblem::OutOfRange)),
+    }
+}
+
+///
+/// Read an unsigned integer as [to_signed] does; a negative number wraps around, as `-1` is the
+/// largest value.
+///
+pub fn to_unsigned(argument: &str) -> Converted<u64> {
+    if let Some(converted) = character_constant(argument) {
+        return Converted::new(converted.value as u64, converted.problem);
+    }
+    let (negative, magnitude, used, overflow) = read_integer(argument);
+    if overflow {
+        return Converted::new(u64::MAX, Some(ArgumentProblem::OutOfRange));
+    }
+    let value = if negative { magnitude.wrapping_neg() } else { magnitude };
+    Converted::new(value, trailing(argument, used))
+}
+
+///
+/// Read a floating point number: decimal, with an optional exponent; `0x` hexadecimal, with an
+/// optional binary exponent; `inf`, `infinity` or `nan`; or a character code as [to_signed] has it.
+///
+pub fn to_float(argument: &str) -> Converted<f64> {
+    if let Some(converted) = character_constant(argument) {
+        return Converted::new(converted.value as f64, converted.problem);
+    }
+
+    let text = argument.as_bytes();
+    let mut index = text.iter().take_while(|byte| byte.is_ascii_whitespace()).count();
+    let negative = text.get(index) == Some(&b'-');
+    if matches!(text.get(index), Some(b'-' | b'+')) {
+        index += 1;
+    }
+
+    let rest = &argument[index..];
+    let infinity = special(rest, "infinity").or_else(|| special(rest, "inf"));
+    let (magnitude, used) = if let Some(used) = infinity {
+        (f64::INFINITY, used)
+    } else if let Some(used) = special(rest, "nan") {
+        (f64::NAN, used + nan_payload(&rest[used..]))
+    } else if let Some(read) = read_hexadecimal_float(rest.as_bytes()) {
+        read
+    } else if let Some(used) = decimal_float_length(rest.as_bytes()) {
+        (rest[..used].parse::<f64>().unwrap_or(0.0), used)
+    } else {
+        return Converted::new(0.0, trailing(argument, 0));
+    };
+
+    let value = if negative { -magnitude } else { magnitude };
+    if value.is_infinite() && infinity.is_none() {
+        return Converted::new(value, Some(ArgumentProblem::OutOfRange));
+    }
+    Converted::new(value, trailing(argument, index + used))
+}
+
+///
+/// `'c` or `"c`: the code of the character after the quote.
+///
+fn character_constant(argument: &str) -> Option<Converted<u32>> {
+    let rest = argument.strip_prefix('\'').or_else(|| argument.strip_prefix('"'))?;
+    let character = rest.chars().next()?;
+    let after = &rest[character.len_utf8()..];
+    let problem = (!after.is_empty()).then(|| ArgumentProblem::IgnoredAfterCharacter(after.to_string()));
+    Some(Converted::new(character as u32, problem))
+}
+
+///
+/// Read an integer's sign and magnitude from the start of `argument`, returning them with how many
+/// bytes were used and whether the magnitude overflowed.
+///
+fn read_integer(argument: &str) -> (bool, u64, usize, bool) {
+    let text = argument.as_bytes();
+    let mut index = text.iter().take_while(|byte| byte.is_ascii_whitespace()).count();
+    let negative = text.get(index) == Some(&b'-');
+    if matches!(text.get(index), Some(b'-' | b'+')) {
+        index += 1;
+    }
+
+    let hexadecimal = matches!(text.get(index..index + 2), Some([b'0', b'x' | b'X'])) && text.get(index + 2).is_some_and(u8::is_ascii_hexdigit);
+    let radix = if hexadecimal {
+        index += 2;
+        16
+    } else if text.get(index) == Some(&b'0') {
+        8
+    } else {
+        10
+    };
+
+    let start = index;
+    let mut magnitude = 0u64;
+    let mut overflow = false;
+    while let Some(digit) = text.get(index).and_then(|&byte| (byte as char).to_digit(radix)) {
+        match magnitude.checked_mul(radix as u64).and_then(|value| value.checked_add(digit as u64)) {
+            Some(value) => magnitude = value,
+            None => overflow = true,
+        }
+        index += 1;
+    }
+    if index == start {
+        return (false, 0, 0, false);
+    }
+    (negative, magnitude, index, overflow)
+}
+
+///
+/// The problem, if any, with only the first `used` bytes of an argument being a number.  An empty
+/// argument is zero, with nothing wrong.
+///
+fn trailing(argument: &str, used: usize) -> Option<ArgumentProblem> {
+    if used == argument.len() {
+        None
+    } else if used == 0 {
+        Some(ArgumentProblem::NotNumeric)
+    } else {
+        Some(ArgumentProblem::NotCompletelyConverted)
+    }
+}
+
+fn special(text: &str, word: &str) -> Option<usize> {
+    text.get(..word.len()).filter(|start| start.eq_ignore_ascii_case(word)).map(|_| word.len())
+}
+
+///
+/// `nan` may be followed by `(...)` holding letters, digits and underscores.
+///
+fn nan_payload(text: &str) -> usize {
+    let Some(rest) = text.strip_prefix('(') else {
+        return 0;
+    };
+    let inside = rest.bytes().take_while(|&byte| byte.is_ascii_alphanumeric() || byte == b'_').count();
+    if rest.as_bytes().get(inside) == Some(&b')') { inside + 2 } else { 0 }
+}
+
+///
+/// How much of `text` is a decimal number: digits with an optional fraction, then an optional
+/// exponent, which only counts when it has digits.
+///
+fn decimal_float_length(text: &[u8]) -> Option<usize> {
+    let whole = text.iter().take_while(|byte| byte.is_ascii_digit()).count();
+    let mut index = whole;
+    let mut fraction = 0;
+    if text.get(index) == Some(&b'.') {
+        fraction = text[index + 1..].iter().take_while(|byte| byte.is_ascii_digit()).count();
+        if whole + fraction > 0 {
+            index += 1 + fraction;
+        }
+    }
+    if whole + fraction == 0 {
+        return None;
+    }
+    Some(index + exponent_length(&text[index..], b'e'))
+}
+
+fn exponent_length(text: &[u8], marker: u8) -> usize {
+    if !text.first().is_some_and(|byte| byte.eq_ignore_ascii_case(&marker)) {
+        return 0;
+    }
+    let sign = usize::from(matches!(text.get(1), Some(b'-' | b'+')));
+    let digits = text[1 + sign..].iter().take_while(|byte| byte.is_ascii_digit()).count();
+    if digits == 0 { 0 } else { 1 + sign + digits }
+}
+
+///
+/// Read `0x` hexadecimal digits with an optional fraction and `p` exponent, returning the value and
+/// how many bytes were used.  Digits past the sixteenth are dropped.
+///
+fn read_hexadecimal_float(text: &[u8]) -> Option<(f64, usize)> {
+    if !matches!(text.get(..2), Some([b'0', b'x' | b'X'])) {
+        return None;
+    }
+    let mut index = 2;
+    let mut mantissa = 0u64;
+    let mut exponent = 0i64;
+    let mut digits = 0;
+    let mut fraction = false;
+
+    loop {
+        match text.get(index) {
+            Some(&byte) if byte.is_ascii_hexdigit() => {
+                let digit = (byte as char).to_digit(16).unwrap_or(0) as u64;
+                if mantissa >> 60 == 0 {
+                    mantissa = mantissa << 4 | digit;
+                    if fraction {
+                        exponent -= 4;
+                    }
+                } else if !fraction {
+                    exponent += 4;
+                }
+                digits += 1;
+            }
+            Some(b'.') if !fraction => fraction = true,
+            _ => break,
+        }
+        index += 1;
+    }
+    if digits == 0 {
+        return None;
+    }
+
+    let exponent_used = exponent_length(&text[index..], b'p');
+    if exponent_used > 0 {
+        let written = std::str::from_utf8(&text[index + 1..index + exponent_used]).unwrap_or("0");
+        exponent = exponent.saturating_add(written.parse::<i64>().unwrap_or(if written.starts_with('-') { i64::MIN / 2 } else { i64::MAX / 2 }));
+    }
+    Some((scale(mantissa as f64, exponent), index + exponent_used))
+}
+
+///
+/// `value` times two to the power `exponent`, a step at a time so nothing overflows on the way.
+///
+fn scale(mut value: f64, mut exponent: i64) -> f64 {
+    const STEP: i64 = 1000;
+    while exponent > STEP && value.is_finite() && value != 0.0 {
+        value *= 2f64.powi(STEP as i32);
+        exponent -= STEP;
+    }
+    while exponent < -STEP && value != 0.0 {
+        value *= 2f64.powi(-STEP as i32);
+        exponent += STEP;
+    }
+    value * 2f64.powi(exponent as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::output::format::argument::{to_float, to_signed, to_unsigned, ArgumentProblem, Converted};
+
+    fn fine<T>(value: T) -> Converted<T> {
+        Converted { value, problem: None }
+    }
+
+    fn problem<T>(value: T, problem: ArgumentProblem) -> Converted<T> {
+        Converted { value, problem: Some(problem) }
+    }
+
+    #[test]
+    fn test_signed() {
+        assert_eq!(fine(42), to_signed("42"));
+        assert_eq!(fine(-16), to_signed("-0x10"));
+        assert_eq!(fine(8), to_signed("010"));
+        assert_eq!(fine(5), to_signed(" +5"));
+        assert_eq!(fine(0), to_signed(""));
+        assert_eq!(fine(i64::MIN), to_signed("-9223372036854775808"));
+        assert_eq!(problem(i64::MAX, ArgumentProblem::OutOfRange), to_signed("99999999999999999999"));
+        assert_eq!(problem(12, ArgumentProblem::NotCompletelyConverted), to_signed("12abc"));
+        assert_eq!(problem(0, ArgumentProblem::NotCompletelyConverted), to_signed("09"));
+        assert_eq!(problem(0, ArgumentProblem::NotCompletelyConverted), to_signed("0x"));
+        assert_eq!(problem(5, ArgumentProblem::NotCompletelyConverted), to_signed("5 "));
+        assert_eq!(problem(0, ArgumentProblem::NotNumeric), to_signed("abc"));
+    }
+
+    #[test]
+    fn test_characters() {
+        assert_eq!(fine(97), to_signed("'a"));
+        assert_eq!(fine(233), to_signed("\"é"));
+        assert_eq!(problem(97, ArgumentProblem::IgnoredAfterCharacter("b".to_string())), to_signed("'ab"));
+        assert_eq!(problem(0, ArgumentProblem::NotNumeric), to_signed("'"));
+        assert_eq!(fine(97.0), to_float("'a"));
+    }
+
+    #[test]
+    fn test_unsigned() {
+        assert_eq!(fine(u64::MAX), to_unsigned("-1"));
+        assert_eq!(fine(255), to_unsigned("0xff"));
+        assert_eq!(problem(u64::MAX, ArgumentProblem::OutOfRange), to_unsigned("18446744073709551616"));
+    }
+
+    #[test]
+    fn test_float() {
+        assert_eq!(fine(1.5), to_float("1.5"));
+        assert_eq!(fine(-0.25), to_float("-.25e0"));
+        assert_eq!(fine(8.0), to_float("0x1p3"));
+        assert_eq!(fine(255.5), to_float("0xff.8"));
+        assert_eq!(fine(f64::INFINITY), to_float("inf"));
+        assert_eq!(fine(f64::NEG_INFINITY), to_float("-Infinity"));
+        assert!(to_float("nan(0x1)").value.is_nan() && to_float("nan").problem.is_none());
+        assert_eq!(problem(1.0, ArgumentProblem::NotCompletelyConverted), to_float("1e"));
+        assert_eq!(problem(0.0, ArgumentProblem::NotNumeric), to_float("."));
+        assert_eq!(problem(f64::INFINITY, ArgumentProblem::OutOfRange), to_float("1e400"));
+    }
+
+    #[test]
+    fn test_messages() {
+        assert_eq!("printf: 'x': expected a numeric value", ArgumentProblem::NotNumeric.message("printf", "x"));
+        assert_eq!("printf: warning: b: character(s) following character constant have been ignored", ArgumentProblem::IgnoredAfterCharacter("b".to_string()).message("printf", "'ab"));
+        assert!(!ArgumentProblem::IgnoredAfterCharacter("b".to_string()).is_failure());
+    }
+}
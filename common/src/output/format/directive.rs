@@ -0,0 +1,334 @@
+use crate::output::format::escape::{read_escape, Escape, EscapeError, EscapeMode};
+
+///
+/// Flags that may follow the `%` of a directive.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Flags {
+    /// `-`: pad on the right.
+    pub left: bool,
+    /// `+`: always show a sign.
+    pub sign: bool,
+    /// ` `: show a space where a plus sign would go.
+    pub space: bool,
+    /// `#`: the alternate form; a radix prefix, or a decimal point that's always there.
+    pub alternate: bool,
+    /// `0`: pad numbers with zeros rather than spaces.
+    pub zero: bool,
+    /// `'`: group thousands, which the `C` locale doesn't do.
+    pub grouping: bool,
+}
+
+///
+/// A field width or precision: given in the format, or taken from the next argument (`*`).
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Count {
+    Given(usize),
+    Argument,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Radix {
+    Octal,
+    Decimal,
+    Hexadecimal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Notation {
+    /// `%f`
+    Fixed,
+    /// `%e`
+    Exponent,
+    /// `%g`: fixed or exponent, whichever suits the value.
+    General,
+    /// `%a`
+    Hexadecimal,
+}
+
+///
+/// What a directive turns its argument into.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conversion {
+    /// `%d` and `%i`
+    Signed,
+    /// `%o`, `%u`, `%x` and `%X`
+    Unsigned { radix: Radix, upper: bool },
+    /// `%f`, `%e`, `%g` and `%a`, and their upper case forms
+    Float { notation: Notation, upper: bool },
+    /// `%c`: the argument's first byte
+    Character,
+    /// `%s`
+    String,
+    /// `%b`: a string with its backslash escapes interpreted
+    Escaped,
+    /// `%q`: a string quoted for the shell
+    Quoted,
+}
+
+///
+/// A `%` directive.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Directive {
+    pub flags: Flags,
+    pub width: Option<Count>,
+    pub precision: Option<Count>,
+    pub conversion: Conversion,
+}
+
+///
+/// Reasons a format can't be used past some point.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatError {
+    /// A directive, as written, that isn't one.
+    InvalidConversion(String),
+    Escape(EscapeError),
+}
+
+impl FormatError {
+    ///
+    /// Describe the error the way the coreutils do, prefixed with the program name.
+    ///
+    pub fn message(&self, program: &str) -> String {
+        match self {
+            FormatError::InvalidConversion(directive) => format!("{}: {}: invalid conversion specification", program, directive),
+            FormatError::Escape(error) => error.message(program),
+        }
+    }
+}
+
+///
+/// One piece of a format.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Piece {
+    /// Text written as is, with its escapes already interpreted.
+    Literal(Vec<u8>),
+    Directive(Directive),
+    /// `\c`: nothing more is written at all.
+    Stop,
+    /// Everything before this is written, then the error is reported.
+    Invalid(FormatError),
+}
+
+///
+/// Split a format into literal text and directives, interpreting backslash escapes along the way.
+/// Nothing follows a [Piece::Stop] or a [Piece::Invalid]; what comes before them still counts, so
+/// the part of the format that's fine is written before an error is reported.
+///
+pub fn parse_format(format: &[u8]) -> Vec<Piece> {
+    let mut pieces = vec![];
+    let mut literal = vec![];
+    let mut index = 0;
+
+    while index < format.len() {
+        let byte = format[index];
+        index += 1;
+        let piece = match byte {
+            b'\\' => match read_escape(&format[index..], EscapeMode::Format) {
+                Ok((escape, used)) => {
+                    index += used;
+                    match escape {
+                        Escape::Bytes(bytes) => {
+                            literal.extend_from_slice(&bytes);
+                            continue;
+                        }
+                        Escape::Stop => Piece::Stop,
+                    }
+                }
+                Err(error) => Piece::Invalid(FormatError::Escape(error)),
+            },
+            b'%' if format.get(index) == Some(&b'%') => {
+                index += 1;
+                literal.push(b'%');
+                continue;
+            }
+            b'%' => {
+                let start = index - 1;
+                match parse_directive(format, &mut index) {
+                    Some(directive) => Piece::Directive(directive),
+                    None => Piece::Invalid(FormatError::InvalidConversion(String::from_utf8_lossy(&format[start..index]).into_owned())),
+                }
+            }
+            _ => {
+                literal.push(byte);
+                continue;
+            }
+        };
+
+        if !literal.is_empty() {
+            pieces.push(Piece::Literal(std::mem::take(&mut literal)));
+        }
+        let last = matches!(piece, Piece::Stop | Piece::Invalid(_));
+        pieces.push(piece);
+        if last {
+            return pieces;
+        }
+    }
+
+    if !literal.is_empty() {
+        pieces.push(Piece::Literal(literal));
+    }
+    pieces
+}
+
+///
+/// Whether the flags and precision suit a conversion, the way `printf` checks them: grouping only
+/// suits decimal numbers, `#` doesn't suit decimal integers or strings, and neither `0` nor a
+/// precision suits `%c`.
+///
+fn allows(flags: &Flags, precision: bool, conversion: u8) -> bool {
+    match conversion {
+        b'd' | b'i' | b'u' => !flags.alternate,
+        b'f' | b'F' | b'g' | b'G' => true,
+        b'o' | b'x' | b'X' | b'e' | b'E' | b'a' | b'A' => !flags.grouping,
+        b's' => !flags.grouping && !flags.alternate && !flags.zero,
+        b'c' => !flags.grouping && !flags.alternate && !flags.zero && !precision,
+        _ => false,
+    }
+}
+
+///
+/// Parse the directive after a `%`, leaving `index` just past it.  [None] when it isn't a valid
+/// directive, with `index` just past the character that showed it.
+///
+fn parse_directive(format: &[u8], index: &mut usize) -> Option<Directive> {
+    let at = |index: usize| format.get(index).copied();
+
+    /* %b and %q take no flags, width or precision */
+    match at(*index) {
+        Some(b'b') | Some(b'q') => {
+            *index += 1;
+            let conversion = if format[*index - 1] == b'b' { Conversion::Escaped } else { Conversion::Quoted };
+            return Some(Directive { flags: Flags::default(), width: None, precision: None, conversion });
+        }
+        _ => {}
+    }
+
+    let mut flags = Flags::default();
+    loop {
+        match at(*index) {
+            Some(b'-') => flags.left = true,
+            Some(b'+') => flags.sign = true,
+            Some(b' ') => flags.space = true,
+            Some(b'#') => flags.alternate = true,
+            Some(b'0') => flags.zero = true,
+            Some(b'\'') | Some(b'I') => flags.grouping = true,
+            _ => break,
+        }
+        *index += 1;
+    }
+
+    let width = parse_count(format, index);
+    let precision = if at(*index) == Some(b'.') {
+        *index += 1;
+        Some(parse_count(format, index).unwrap_or(Count::Given(0)))
+    } else {
+        None
+    };
+
+    /* length modifiers make no difference here */
+    while matches!(at(*index), Some(b'h' | b'l' | b'L' | b'j' | b't' | b'z')) {
+        *index += 1;
+    }
+
+    let letter = at(*index)?;
+    *index += 1;
+    if !allows(&flags, precision.is_some(), letter) {
+        return None;
+    }
+    let conversion = match letter {
+        b'd' | b'i' => Conversion::Signed,
+        b'o' => Conversion::Unsigned { radix: Radix::Octal, upper: false },
+        b'u' => Conversion::Unsigned { radix: Radix::Decimal, upper: false },
+        b'x' | b'X' => Conversion::Unsigned { radix: Radix::Hexadecimal, upper: letter == b'X' },
+        b'f' | b'F' => Conversion::Float { notation: Notation::Fixed, upper: letter == b'F' },
+        b'e' | b'E' => Conversion::Float { notation: Notation::Exponent, upper: letter == b'E' },
+        b'g' | b'G' => Conversion::Float { notation: Notation::General, upper: letter == b'G' },
+        b'a' | b'A' => Conversion::Float { notation: Notation::Hexadecimal, upper: letter == b'A' },
+        b'c' => Conversion::Character,
+        _ => Conversion::String,
+    };
+    Some(Directive { flags, width, precision, conversion })
+}
+
+fn parse_count(format: &[u8], index: &mut usize) -> Option<Count> {
+    if format.get(*index) == Some(&b'*') {
+        *index += 1;
+        return Some(Count::Argument);
+    }
+
+    let digits = format[*index..].iter().take_while(|byte| byte.is_ascii_digit()).count();
+    if digits == 0 {
+        return None;
+    }
+    let count = format[*index..*index + digits].iter().fold(0usize, |count, digit| count.saturating_mul(10).saturating_add((digit - b'0') as usize));
+    *index += digits;
+    Some(Count::Given(count))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::output::format::directive::{parse_format, Conversion, Count, Directive, Flags, FormatError, Notation, Piece, Radix};
+    use crate::output::format::escape::EscapeError;
+
+    fn directive(format: &str) -> Directive {
+        match parse_format(format.as_bytes()).as_slice() {
+            [Piece::Directive(directive)] => *directive,
+            pieces => panic!("not a single directive: {:?}", pieces),
+        }
+    }
+
+    fn invalid(format: &str) -> Option<FormatError> {
+        match parse_format(format.as_bytes()).pop() {
+            Some(Piece::Invalid(error)) => Some(error),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_pieces() {
+        let directive = Directive { flags: Flags::default(), width: None, precision: None, conversion: Conversion::Signed };
+        assert_eq!(
+            vec![Piece::Literal(b"a%\n".to_vec()), Piece::Directive(directive), Piece::Literal(b"b".to_vec())],
+            parse_format(b"a%%\\n%db")
+        );
+        assert_eq!(vec![Piece::Literal(b"a".to_vec()), Piece::Stop], parse_format(b"a\\c%d"));
+        assert!(parse_format(b"").is_empty());
+    }
+
+    #[test]
+    fn test_directives() {
+        let padded = directive("%-+ 0'*.*lld");
+        assert!(padded.flags.left && padded.flags.sign && padded.flags.space && padded.flags.zero && padded.flags.grouping);
+        assert_eq!((Some(Count::Argument), Some(Count::Argument)), (padded.width, padded.precision));
+
+        let sized = directive("%10.3s");
+        assert_eq!((Some(Count::Given(10)), Some(Count::Given(3)), Conversion::String), (sized.width, sized.precision, sized.conversion));
+        assert_eq!(Some(Count::Given(0)), directive("%.f").precision);
+
+        assert_eq!(Conversion::Unsigned { radix: Radix::Hexadecimal, upper: true }, directive("%X").conversion);
+        assert_eq!(Conversion::Float { notation: Notation::General, upper: false }, directive("%g").conversion);
+        assert_eq!(Conversion::Float { notation: Notation::Hexadecimal, upper: true }, directive("%A").conversion);
+        assert_eq!(Conversion::Escaped, directive("%b").conversion);
+        assert_eq!(Conversion::Quoted, directive("%q").conversion);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(Some(FormatError::InvalidConversion("%".to_string())), invalid("a%"));
+        assert_eq!(Some(FormatError::InvalidConversion("%zb".to_string())), invalid("%zb"));
+        assert_eq!(Some(FormatError::InvalidConversion("%5b".to_string())), invalid("%5b"));
+        assert_eq!(Some(FormatError::InvalidConversion("%#d".to_string())), invalid("%#d%d"));
+        assert_eq!(Some(FormatError::InvalidConversion("%.2c".to_string())), invalid("%.2c"));
+        assert_eq!(Some(FormatError::InvalidConversion("%05s".to_string())), invalid("%05s"));
+        assert_eq!(Some(FormatError::InvalidConversion("%'x".to_string())), invalid("%'x"));
+        assert_eq!(Some(FormatError::Escape(EscapeError::MissingHexadecimal)), invalid("ok\\x"));
+        assert_eq!("printf: %y: invalid conversion specification", invalid("%y").unwrap().message("printf"));
+        assert_eq!(None, invalid("%'d %#x %0f"));
+    }
+}
@@ -0,0 +1,188 @@
+const ALERT: u8 = 0x07;
+const BACKSPACE: u8 = 0x08;
+const ESCAPE: u8 = 0x1b;
+const FORM_FEED: u8 = 0x0c;
+const VERTICAL_TAB: u8 = 0x0b;
+
+///
+/// Where a backslash escape is read: in a format, `\NNN` is an octal byte; in a `%b` argument,
+/// `\0NNN` is too, as `echo` has it, and so is `\NNN`.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscapeMode {
+    Format,
+    Argument,
+}
+
+///
+/// What an escape stands for.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Escape {
+    Bytes(Vec<u8>),
+    /// `\c`: nothing more is written at all.
+    Stop,
+}
+
+///
+/// Reasons an escape can't be read.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EscapeError {
+    /// `\x`, `\u` or `\U` without enough hexadecimal digits after it.
+    MissingHexadecimal,
+    /// `\u` or `\U` naming something that isn't a character, or that can be written plainly.
+    InvalidCharacterName(String),
+}
+
+impl EscapeError {
+    ///
+    /// Describe the error the way the coreutils do, prefixed with the program name.
+    ///
+    pub fn message(&self, program: &str) -> String {
+        match self {
+            EscapeError::MissingHexadecimal => format!("{}: missing hexadecimal number in escape", program),
+            EscapeError::InvalidCharacterName(name) => format!("{}: invalid universal character name {}", program, name),
+        }
+    }
+}
+
+///
+/// Read the escape that starts at `text`, just after its backslash, returning what it stands for
+/// and how many bytes of `text` it took.  A backslash that doesn't start a known escape stands
+/// for itself, as does one at the very end.
+///
+pub fn read_escape(text: &[u8], mode: EscapeMode) -> Result<(Escape, usize), EscapeError> {
+    let Some(&first) = text.first() else {
+        return Ok((Escape::Bytes(vec![b'\\']), 0));
+    };
+
+    let simple = match first {
+        b'"' => Some(b'"'),
+        b'\\' => Some(b'\\'),
+        b'a' => Some(ALERT),
+        b'b' => Some(BACKSPACE),
+        b'e' => Some(ESCAPE),
+        b'f' => Some(FORM_FEED),
+        b'n' => Some(b'\n'),
+        b'r' => Some(b'\r'),
+        b't' => Some(b'\t'),
+        b'v' => Some(VERTICAL_TAB),
+        _ => None,
+    };
+    if let Some(byte) = simple {
+        return Ok((Escape::Bytes(vec![byte]), 1));
+    }
+
+    match first {
+        b'c' => Ok((Escape::Stop, 1)),
+        b'x' => {
+            let (value, used) = digits(&text[1..], 16, 2);
+            if used == 0 {
+                return Err(EscapeError::MissingHexadecimal);
+            }
+            Ok((Escape::Bytes(vec![value as u8]), 1 + used))
+        }
+        b'0'..=b'7' => {
+            /* a leading zero doesn't count towards the three digits in an argument */
+            let skip = usize::from(mode == EscapeMode::Argument && first == b'0');
+            let (value, used) = digits(&text[skip..], 8, 3);
+            Ok((Escape::Bytes(vec![value as u8]), skip + used))
+        }
+        b'u' | b'U' => {
+            let length = if first == b'u' { 4 } else { 8 };
+            let (value, used) = digits(&text[1..], 16, length);
+            if used < length {
+                return Err(EscapeError::MissingHexadecimal);
+            }
+            let name = format!("\\{}{:0width$x}", first as char, value, width = length);
+            character(value).map(|bytes| (Escape::Bytes(bytes), 1 + used)).ok_or(EscapeError::InvalidCharacterName(name))
+        }
+        _ => Ok((Escape::Bytes(vec![b'\\', first]), 1)),
+    }
+}
+
+///
+/// Interpret every escape in `text`, appending the result to `output`.  Returns whether `\c`
+/// stopped it short.
+///
+pub fn unescape(text: &[u8], mode: EscapeMode, output: &mut Vec<u8>) -> Result<bool, EscapeError> {
+    let mut index = 0;
+    while index < text.len() {
+        let byte = text[index];
+        index += 1;
+        if byte != b'\\' {
+            output.push(byte);
+            continue;
+        }
+        let (escape, used) = read_escape(&text[index..], mode)?;
+        index += used;
+        match escape {
+            Escape::Bytes(bytes) => output.extend_from_slice(&bytes),
+            Escape::Stop => return Ok(true),
+        }
+    }
+    Ok(false)
+}
+
+///
+/// Read up to `limit` digits in `radix` from the start of `text`, returning the value and how
+/// many digits were used.
+///
+fn digits(text: &[u8], radix: u32, limit: usize) -> (u32, usize) {
+    text.iter()
+        .take(limit)
+        .map_while(|&byte| (byte as char).to_digit(radix))
+        .fold((0u32, 0), |(value, used), digit| (value.wrapping_mul(radix).wrapping_add(digit), used + 1))
+}
+
+///
+/// Encode a universal character name, which may not be a surrogate, nor below U+00A0 other than
+/// `$`, `@` and `` ` ``, which have no other way to be written in C.
+///
+fn character(value: u32) -> Option<Vec<u8>> {
+    let allowed = value >= 0xa0 || matches!(value, 0x24 | 0x40 | 0x60);
+    let character = char::from_u32(value).filter(|_| allowed)?;
+    Some(character.to_string().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::output::format::escape::{unescape, EscapeError, EscapeMode};
+
+    fn format(text: &str) -> Result<(Vec<u8>, bool), EscapeError> {
+        let mut output = vec![];
+        unescape(text.as_bytes(), EscapeMode::Format, &mut output).map(|stopped| (output, stopped))
+    }
+
+    fn argument(text: &str) -> Vec<u8> {
+        let mut output = vec![];
+        unescape(text.as_bytes(), EscapeMode::Argument, &mut output).unwrap();
+        output
+    }
+
+    #[test]
+    fn test_characters() {
+        assert_eq!(Ok((b"\"\\\x07\x08\x1b\x0c\n\r\t\x0b".to_vec(), false)), format("\\\"\\\\\\a\\b\\e\\f\\n\\r\\t\\v"));
+        assert_eq!(Ok((b"\\q\\".to_vec(), false)), format("\\q\\"));
+        assert_eq!(Ok((b"a".to_vec(), true)), format("a\\cb"));
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(Ok((b"\x081A".to_vec(), false)), format("\\0101\\101"));
+        assert_eq!(b"AA1".to_vec(), argument("\\0101\\1011"));
+        assert_eq!(Ok((vec![0xff], false)), format("\\777"));
+        assert_eq!(Ok((b"A\x04g".to_vec(), false)), format("\\x41\\x4g"));
+        assert_eq!(Err(EscapeError::MissingHexadecimal), format("\\xg"));
+    }
+
+    #[test]
+    fn test_universal() {
+        assert_eq!(Ok(("é$".as_bytes().to_vec(), false)), format("\\u00e9\\U00000024"));
+        assert_eq!(Err(EscapeError::MissingHexadecimal), format("\\u12"));
+        assert_eq!(Err(EscapeError::InvalidCharacterName("\\u0041".to_string())), format("\\u0041"));
+        assert_eq!(Err(EscapeError::InvalidCharacterName("\\ud800".to_string())), format("\\ud800"));
+        assert_eq!("printf: invalid universal character name \\u0041", EscapeError::InvalidCharacterName("\\u0041".to_string()).message("printf"));
+    }
+}
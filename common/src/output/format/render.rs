@@ -0,0 +1,352 @@
+use crate::output::format::directive::{Flags, Notation, Radix};
+
+const DEFAULT_PRECISION: usize = 6;
+/* the mantissa of a long double holds the leading hexadecimal digit and fifteen more */
+const HEXADECIMAL_FRACTION_DIGITS: usize = 15;
+
+///
+/// A directive's flags with its width and precision settled.  A negative width, which can only
+/// come from an argument, means padding on the right; a negative precision means none at all.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    pub flags: Flags,
+    pub width: usize,
+    pub precision: Option<usize>,
+}
+
+impl Layout {
+    pub fn new(flags: Flags, width: Option<i64>, precision: Option<i64>) -> Layout {
+        let width = width.unwrap_or(0);
+        let flags = Flags { left: flags.left || width < 0, ..flags };
+        let precision = precision.and_then(|precision| usize::try_from(precision).ok());
+        Layout { flags, width: width.unsigned_abs() as usize, precision }
+    }
+}
+
+///
+/// Render a signed integer; `%d` and `%i`.
+///
+pub fn render_signed(layout: &Layout, value: i64) -> Vec<u8> {
+    let digits = integer_digits(layout, value.unsigned_abs().to_string());
+    pad(layout, sign(&layout.flags, value < 0), &digits, layout.precision.is_none())
+}
+
+///
+/// Render an unsigned integer; `%o`, `%u`, `%x` and `%X`.
+///
+pub fn render_unsigned(layout: &Layout, value: u64, radix: Radix, upper: bool) -> Vec<u8> {
+    let digits = match (radix, upper) {
+        (Radix::Octal, _) => format!("{:o}", value),
+        (Radix::Decimal, _) => value.to_string(),
+        (Radix::Hexadecimal, false) => format!("{:x}", value),
+        (Radix::Hexadecimal, true) => format!("{:X}", value),
+    };
+    let mut digits = integer_digits(layout, digits);
+
+    let mut prefix = "";
+    if layout.flags.alternate {
+        match radix {
+            Radix::Octal if !digits.starts_with('0') => digits.insert(0, '0'),
+            Radix::Hexadecimal if value != 0 => prefix = if upper { "0X" } else { "0x" },
+            _ => {}
+        }
+    }
+    pad(layout, prefix, &digits, layout.precision.is_none())
+}
+
+///
+/// Render a floating point number; `%f`, `%e`, `%g` and `%a`, and their upper case forms.
+///
+pub fn render_float(layout: &Layout, value: f64, notation: Notation, upper: bool) -> Vec<u8> {
+    let sign = sign(&layout.flags, value.is_sign_negative());
+    if !value.is_finite() {
+        let word = if value.is_nan() { "nan" } else { "inf" };
+        let word = if upper { word.to_uppercase() } else { word.to_string() };
+        return pad(layout, sign, &word, false);
+    }
+
+    let magnitude = value.abs();
+    let alternate = layout.flags.alternate;
+    let (prefix, body) = match notation {
+        Notation::Fixed => ("", fixed(magnitude, layout.precision.unwrap_or(DEFAULT_PRECISION), alternate)),
+        Notation::Exponent => ("", exponent(magnitude, layout.precision.unwrap_or(DEFAULT_PRECISION), alternate)),
+        Notation::General => ("", general(magnitude, layout.precision.unwrap_or(DEFAULT_PRECISION), alternate)),
+        Notation::Hexadecimal => (if upper { "0X" } else { "0x" }, hexadecimal(magnitude, layout.precision, alternate)),
+    };
+    let body = if upper { body.to_uppercase() } else { body };
+
+    let mut prefixed = String::from(sign);
+    prefixed.push_str(prefix);
+    pad(layout, &prefixed, &body, true)
+}
+
+///
+/// Render bytes, as many as the precision allows; `%s`, and `%c` with a single byte.
+///
+pub fn render_bytes(layout: &Layout, bytes: &[u8]) -> Vec<u8> {
+    let shown = layout.precision.map_or(bytes, |precision| &bytes[..precision.min(bytes.len())]);
+    pad_bytes(layout, b"", shown, false)
+}
+
+///
+/// Digits at least as many as the precision; a precision of zero shows zero as nothing at all.
+///
+fn integer_digits(layout: &Layout, digits: String) -> String {
+    match layout.precision {
+        Some(0) if digits == "0" => String::new(),
+        Some(precision) if digits.len() < precision => format!("{}{}", "0".repeat(precision - digits.len()), digits),
+        _ => digits,
+    }
+}
+
+fn sign(flags: &Flags, negative: bool) -> &'static str {
+    if negative {
+        "-"
+    } else if flags.sign {
+        "+"
+    } else if flags.space {
+        " "
+    } else {
+        ""
+    }
+}
+
+fn fixed(magnitude: f64, precision: usize, alternate: bool) -> String {
+    let mut text = format!("{:.*}", precision, magnitude);
+    if alternate && precision == 0 {
+        text.push('.');
+    }
+    text
+}
+
+fn exponent(magnitude: f64, precision: usize, alternate: bool) -> String {
+    let (mut mantissa, exponent) = scientific(magnitude, precision);
+    if alternate && precision == 0 {
+        mantissa.push('.');
+    }
+    format!("{}e{}{:02}", mantissa, if exponent < 0 { '-' } else { '+' }, exponent.unsigned_abs())
+}
+
+///
+/// `%e` when the exponent is below -4 or no smaller than the precision, `%f` otherwise, with
+/// trailing zeros taken off unless `#` keeps them.
+///
+fn general(magnitude: f64, precision: usize, alternate: bool) -> String {
+    let precision = precision.max(1);
+    let (_, decimal_exponent) = scientific(magnitude, precision - 1);
+    let exponent_form = decimal_exponent < -4 || decimal_exponent >= precision as i32;
+
+    let text = if exponent_form {
+        exponent(magnitude, precision - 1, alternate)
+    } else {
+        fixed(magnitude, (precision as i32 - 1 - decimal_exponent) as usize, alternate)
+    };
+    if alternate {
+        return text;
+    }
+
+    let (number, suffix) = match text.find('e') {
+        Some(index) => text.split_at(index),
+        None => (text.as_str(), ""),
+    };
+    let number = if number.contains('.') { number.trim_end_matches('0').trim_end_matches('.') } else { number };
+    format!("{}{}", number, suffix)
+}
+
+///
+/// The digits of `d.ddd` and the exponent, rounded to `precision` digits after the point.
+///
+fn scientific(magnitude: f64, precision: usize) -> (String, i32) {
+    let text = format!("{:.*e}", precision, magnitude);
+    let (mantissa, exponent) = text.split_once('e').unwrap_or((&text, "0"));
+    (mantissa.to_string(), exponent.parse().unwrap_or(0))
+}
+
+///
+/// The digits and binary exponent the way `printf` shows a long double on x86: the leading digit
+/// holds the top four bits of the mantissa, so one is `8p-3` rather than `1p+0`.
+///
+fn hexadecimal(magnitude: f64, precision: Option<usize>, alternate: bool) -> String {
+    let (leading, mut fraction, mut exponent) = if magnitude == 0.0 {
+        (0u64, 0u64, 0i64)
+    } else {
+        let bits = magnitude.to_bits();
+        let stored = (bits >> 52) as i64;
+        let (mantissa, power) = match stored {
+            0 => (bits & ((1 << 52) - 1), -1074),
+            _ => (bits & ((1 << 52) - 1) | 1 << 52, stored - 1075),
+        };
+        let shift = mantissa.leading_zeros();
+        let mantissa = mantissa << shift;
+        (mantissa >> 60, mantissa & ((1 << 60) - 1), power - shift as i64 + 63 - 3)
+    };
+
+    let mut leading = leading;
+    let mut digits = HEXADECIMAL_FRACTION_DIGITS;
+    if let Some(precision) = precision.filter(|&precision| precision < HEXADECIMAL_FRACTION_DIGITS) {
+        /* round half to even on the bits being dropped */
+        let dropped = 4 * (HEXADECIMAL_FRACTION_DIGITS - precision) as u32;
+        let kept = fraction >> dropped;
+        let rest = fraction & ((1 << dropped) - 1);
+        let half = 1 << (dropped - 1);
+        let whole = (leading << (60 - dropped)) | kept;
+        let rounded = if rest > half || (rest == half && whole & 1 == 1) { whole + 1 } else { whole };
+        leading = rounded >> (60 - dropped);
+        fraction = (rounded & ((1 << (60 - dropped)) - 1)) << dropped;
+        if leading == 16 {
+            leading = 1;
+            exponent += 4;
+        }
+        digits = precision;
+    }
+
+    let mut text = format!("{:x}", leading);
+    let mut fraction_digits = format!("{:015x}", fraction);
+    fraction_digits.truncate(digits);
+    match precision {
+        None => fraction_digits = fraction_digits.trim_end_matches('0').to_string(),
+        Some(precision) => fraction_digits.push_str(&"0".repeat(precision.saturating_sub(HEXADECIMAL_FRACTION_DIGITS))),
+    }
+    if !fraction_digits.is_empty() || alternate {
+        text.push('.');
+        text.push_str(&fraction_digits);
+    }
+    format!("{}p{}{}", text, if exponent < 0 { '-' } else { '+' }, exponent.unsigned_abs())
+}
+
+fn pad(layout: &Layout, prefix: &str, body: &str, zero_allowed: bool) -> Vec<u8> {
+    pad_bytes(layout, prefix.as_bytes(), body.as_bytes(), zero_allowed)
+}
+
+///
+/// Pad to the field width: on the right with `-`, with zeros between the prefix and the body with
+/// `0` where it's allowed, and on the left otherwise.
+///
+fn pad_bytes(layout: &Layout, prefix: &[u8], body: &[u8], zero_allowed: bool) -> Vec<u8> {
+    let length = prefix.len() + body.len();
+    let padding = layout.width.saturating_sub(length);
+    let mut output = Vec::with_capacity(length + padding);
+
+    if layout.flags.left {
+        output.extend_from_slice(prefix);
+        output.extend_from_slice(body);
+        output.resize(length + padding, b' ');
+    } else if layout.flags.zero && zero_allowed {
+        output.extend_from_slice(prefix);
+        output.resize(prefix.len() + padding, b'0');
+        output.extend_from_slice(body);
+    } else {
+        output.resize(padding, b' ');
+        output.extend_from_slice(prefix);
+        output.extend_from_slice(body);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::output::format::directive::{Flags, Notation, Radix};
+    use crate::output::format::render::{render_bytes, render_float, render_signed, render_unsigned, Layout};
+
+    fn layout(flags: &str, width: Option<i64>, precision: Option<i64>) -> Layout {
+        let flags = Flags {
+            left: flags.contains('-'),
+            sign: flags.contains('+'),
+            space: flags.contains(' '),
+            alternate: flags.contains('#'),
+            zero: flags.contains('0'),
+            grouping: false,
+        };
+        Layout::new(flags, width, precision)
+    }
+
+    fn text(bytes: Vec<u8>) -> String {
+        String::from_utf8(bytes).unwrap()
+    }
+
+    fn float(flags: &str, width: Option<i64>, precision: Option<i64>, notation: Notation, value: f64) -> String {
+        text(render_float(&layout(flags, width, precision), value, notation, false))
+    }
+
+    #[test]
+    fn test_layout() {
+        let negative = Layout::new(Flags::default(), Some(-5), Some(-1));
+        assert!(negative.flags.left);
+        assert_eq!((5, None), (negative.width, negative.precision));
+    }
+
+    #[test]
+    fn test_signed() {
+        assert_eq!("42", text(render_signed(&layout("", None, None), 42)));
+        assert_eq!("-00042", text(render_signed(&layout("", None, Some(5)), -42)));
+        assert_eq!("     007", text(render_signed(&layout("0", Some(8), Some(3)), 7)));
+        assert_eq!("-0005", text(render_signed(&layout("0", Some(5), None), -5)));
+        assert_eq!("+5| 5|5    |", format!("{}|{}|{}|", text(render_signed(&layout("+", None, None), 5)), text(render_signed(&layout(" ", None, None), 5)), text(render_signed(&layout("-0", Some(5), None), 5))));
+        assert_eq!("", text(render_signed(&layout("", None, Some(0)), 0)));
+    }
+
+    #[test]
+    fn test_unsigned() {
+        assert_eq!("010", text(render_unsigned(&layout("#", None, None), 8, Radix::Octal, false)));
+        assert_eq!("0", text(render_unsigned(&layout("#", None, Some(0)), 0, Radix::Octal, false)));
+        assert_eq!("0xff", text(render_unsigned(&layout("#", None, None), 255, Radix::Hexadecimal, false)));
+        assert_eq!("0X00FF", text(render_unsigned(&layout("#0", Some(6), None), 255, Radix::Hexadecimal, true)));
+        assert_eq!("0", text(render_unsigned(&layout("#", None, None), 0, Radix::Hexadecimal, false)));
+        assert_eq!("18446744073709551615", text(render_unsigned(&layout("+", None, None), u64::MAX, Radix::Decimal, false)));
+    }
+
+    #[test]
+    fn test_fixed_and_exponent() {
+        assert_eq!("3.141593", float("", None, None, Notation::Fixed, std::f64::consts::PI));
+        assert_eq!("0 2 2", format!("{} {} {}", float("", None, Some(0), Notation::Fixed, 0.5), float("", None, Some(0), Notation::Fixed, 1.5), float("", None, Some(0), Notation::Fixed, 2.5)));
+        assert_eq!("3.", float("#", None, Some(0), Notation::Fixed, 3.0));
+        assert_eq!("-1.500000|2.00    ", format!("{}|{}", float("0", Some(8), None, Notation::Fixed, -1.5), float("-", Some(8), Some(2), Notation::Fixed, 2.0)));
+        assert_eq!("1.23e+04", float("", None, Some(2), Notation::Exponent, 12345.0));
+        assert_eq!("-05.00e+00", float("0", Some(10), Some(2), Notation::Exponent, -5.0));
+        assert_eq!("0.000000e+00|1e-300", format!("{}|{}", float("", None, None, Notation::Exponent, 0.0), float("", None, Some(0), Notation::Exponent, 1e-300)));
+        assert_eq!("3.e+00", float("#", None, Some(0), Notation::Exponent, 3.0));
+    }
+
+    #[test]
+    fn test_general() {
+        assert_eq!("100000 1e+06", format!("{} {}", float("", None, None, Notation::General, 1e5), float("", None, None, Notation::General, 1e6)));
+        assert_eq!("0.0001 1e-05", format!("{} {}", float("", None, None, Notation::General, 1e-4), float("", None, None, Notation::General, 1e-5)));
+        assert_eq!("1.23457e+08", float("", None, None, Notation::General, 123456789.0));
+        assert_eq!("1.50000", float("#", None, None, Notation::General, 1.5));
+        assert_eq!("0", float("", None, Some(0), Notation::General, 0.0));
+        assert_eq!("1E-05", text(render_float(&layout("", None, None), 1e-5, Notation::General, true)));
+    }
+
+    #[test]
+    fn test_hexadecimal() {
+        assert_eq!("0x8p-3|0xf.fp+4|0x0p+0|-0x0p+0", format!("{}|{}|{}|{}",
+            float("", None, None, Notation::Hexadecimal, 1.0),
+            float("", None, None, Notation::Hexadecimal, 255.0),
+            float("", None, None, Notation::Hexadecimal, 0.0),
+            float("", None, None, Notation::Hexadecimal, -0.0)));
+        assert_eq!("0x1p+1|0xf.fp-3", format!("{}|{}", float("", None, Some(0), Notation::Hexadecimal, 1.99), float("", None, Some(1), Notation::Hexadecimal, 1.99)));
+        assert_eq!("0x8.p-3|0x000000000000008p-3|+0x8p-3", format!("{}|{}|{}",
+            float("#", None, Some(0), Notation::Hexadecimal, 1.0),
+            float("0", Some(20), None, Notation::Hexadecimal, 1.0),
+            float("+", None, None, Notation::Hexadecimal, 1.0)));
+        assert_eq!("0x8.00000000000000000000p-3", float("", None, Some(20), Notation::Hexadecimal, 1.0));
+        assert_eq!("0XF.FP+4", text(render_float(&layout("", None, None), 255.0, Notation::Hexadecimal, true)));
+    }
+
+    #[test]
+    fn test_special() {
+        assert_eq!("inf|-nan|  INF", format!("{}|{}|{}",
+            float("", None, None, Notation::Fixed, f64::INFINITY),
+            float("", None, None, Notation::Exponent, -f64::NAN),
+            text(render_float(&layout("0", Some(5), None), f64::INFINITY, Notation::General, true))));
+    }
+
+    #[test]
+    fn test_bytes() {
+        assert_eq!("abc|       abc|ab   ", format!("{}|{}|{}",
+            text(render_bytes(&layout("", None, Some(3)), b"abcdef")),
+            text(render_bytes(&layout("", Some(10), Some(3)), b"abcdef")),
+            text(render_bytes(&layout("-", Some(5), None), b"ab"))));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod format;
+pub mod quote;
+pub mod terminal;
\ No newline at end of file
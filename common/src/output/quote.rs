@@ -0,0 +1,300 @@
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+/* characters the shell treats specially anywhere in a word */
+const SHELL_SPECIAL: &[char] = &[' ', '!', '"', '$', '&', '\'', '(', ')', '*', ';', '<', '=', '>', '?', '[', '\\', '^', '`', '|'];
+/* characters the shell treats specially at the start of a word */
+const SHELL_SPECIAL_FIRST: &[char] = &['#', '~'];
+/* characters that keep a special meaning between double quotes */
+const DOUBLE_QUOTE_SPECIAL: &[char] = &['"', '$', '\\', '`', '!'];
+/* control characters the shell can't take unquoted, even when they're written as they are */
+const SHELL_SPECIAL_CONTROL: &[char] = &['\n', '\t', '\r'];
+
+///
+/// How a name is written so it can be read back, as with `ls --quoting-style`.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotingStyle {
+    /// As it is.
+    Literal,
+    /// Quoted for the shell when it needs to be, with unprintable characters as they are.
+    Shell,
+    /// Always quoted for the shell.
+    ShellAlways,
+    /// Quoted for the shell when it needs to be, with unprintable characters as `$'\n'`.
+    ShellEscape,
+    /// Always quoted for the shell, with unprintable characters as `$'\n'`.
+    ShellEscapeAlways,
+    /// In double quotes, with `C` escapes.
+    C,
+    /// With `C` escapes, but no quotes.
+    Escape,
+}
+
+///
+/// A piece of text: a character that can be shown as it is, or a byte that can't.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    Printable(char),
+    Unprintable(char),
+    Invalid(u8),
+}
+
+///
+/// Quote text in the given style.  The result holds bytes that aren't `UTF-8` as they were for the
+/// styles that write unprintable characters as they are.
+///
+pub fn quote(text: &[u8], style: QuotingStyle) -> Vec<u8> {
+    match style {
+        QuotingStyle::Literal => text.to_vec(),
+        QuotingStyle::Shell => shell_quote(text, false, false),
+        QuotingStyle::ShellAlways => shell_quote(text, true, false),
+        QuotingStyle::ShellEscape => shell_quote(text, false, true),
+        QuotingStyle::ShellEscapeAlways => shell_quote(text, true, true),
+        QuotingStyle::C => {
+            let mut quoted = vec![b'"'];
+            c_escape(&mut quoted, &units(text), true);
+            quoted.push(b'"');
+            quoted
+        }
+        QuotingStyle::Escape => {
+            let mut quoted = vec![];
+            c_escape(&mut quoted, &units(text), false);
+            quoted
+        }
+    }
+}
+
+///
+/// Quote text so the shell reads it back as it was, the way `printf %q` and `ls --quoting-style=shell-escape`
+/// do: as it is when nothing in it is special; in double quotes when the only trouble is a single
+/// quote; and otherwise in single quotes, with anything unprintable written as `$'\n'`.
+///
+pub fn shell_escape(text: &[u8]) -> String {
+    /* every byte that isn't printable ASCII or UTF-8 is escaped */
+    String::from_utf8_lossy(&shell_quote(text, false, true)).into_owned()
+}
+
+///
+/// A name for a message, always quoted for the shell so it stands apart from the text around it,
+/// the way the `coreutils` quote their operands in diagnostics.
+///
+pub fn quote_name(name: &[u8]) -> String {
+    String::from_utf8_lossy(&shell_quote(name, true, true)).into_owned()
+}
+
+///
+/// [quote_name], for a path.
+///
+pub fn quote_path(path: &Path) -> String {
+    quote_name(path.as_os_str().as_bytes())
+}
+
+///
+/// Quote for the shell; `always` quotes even text that doesn't need it, and `escape` writes
+/// unprintable characters as escapes rather than as they are.
+///
+fn shell_quote(text: &[u8], always: bool, escape: bool) -> Vec<u8> {
+    if text.is_empty() {
+        return b"''".to_vec();
+    }
+
+    let units = units(text);
+    let printable = units.iter().all(|unit| matches!(unit, Unit::Printable(_)));
+    let special = units.iter().enumerate().any(|(index, unit)| match unit {
+        Unit::Printable(c) => SHELL_SPECIAL.contains(c) || (index == 0 && SHELL_SPECIAL_FIRST.contains(c)),
+        Unit::Unprintable(c) => escape || SHELL_SPECIAL_CONTROL.contains(c),
+        Unit::Invalid(_) => escape,
+    });
+    let characters = || units.iter().filter_map(|unit| match unit { Unit::Printable(c) => Some(*c), _ => None });
+    if !special && !always {
+        return text.to_vec();
+    }
+    if printable && characters().any(|c| c == '\'') && !characters().any(|c| DOUBLE_QUOTE_SPECIAL.contains(&c)) {
+        return format!("\"{}\"", characters().collect::<String>()).into_bytes();
+    }
+
+    single_quote(&units, escape)
+}
+
+///
+/// Quote in single quotes.  A single quote becomes `'\''`; when escaping, the quotes are closed
+/// around runs of unprintable characters, which go in `$'...'` as escapes.
+///
+fn single_quote(units: &[Unit], escape: bool) -> Vec<u8> {
+    let mut quoted = vec![b'\''];
+    let mut escaping = false;
+
+    for unit in units {
+        match unit {
+            Unit::Printable(c) => {
+                if escaping {
+                    quoted.extend_from_slice(b"''");
+                    escaping = false;
+                }
+                if *c == '\'' {
+                    quoted.extend_from_slice(b"'\\''");
+                } else {
+                    push_char(&mut quoted, *c);
+                }
+            }
+            Unit::Unprintable(c) if !escape => push_char(&mut quoted, *c),
+            Unit::Invalid(byte) if !escape => quoted.push(*byte),
+            unprintable => {
+                if !escaping {
+                    quoted.extend_from_slice(b"'$'");
+                    escaping = true;
+                }
+                push_escape(&mut quoted, *unprintable);
+            }
+        }
+    }
+    quoted.push(b'\'');
+    quoted
+}
+
+///
+/// Write text with `C` escapes; in double quotes a `"` is escaped, and out of them a space is.
+///
+fn c_escape(quoted: &mut Vec<u8>, units: &[Unit], double_quoted: bool) {
+    for unit in units {
+        match unit {
+            Unit::Printable('\\') => quoted.extend_from_slice(b"\\\\"),
+            Unit::Printable('"') if double_quoted => quoted.extend_from_slice(b"\\\""),
+            Unit::Printable(' ') if !double_quoted => quoted.extend_from_slice(b"\\ "),
+            Unit::Printable(c) => push_char(quoted, *c),
+            unprintable => push_escape(quoted, *unprintable),
+        }
+    }
+}
+
+fn push_escape(quoted: &mut Vec<u8>, unit: Unit) {
+    let escape: &[u8] = match unit {
+        Unit::Unprintable('\x07') => b"\\a",
+        Unit::Unprintable('\x08') => b"\\b",
+        Unit::Unprintable('\x0c') => b"\\f",
+        Unit::Unprintable('\n') => b"\\n",
+        Unit::Unprintable('\r') => b"\\r",
+        Unit::Unprintable('\t') => b"\\t",
+        Unit::Unprintable('\x0b') => b"\\v",
+        Unit::Unprintable(c) | Unit::Printable(c) => {
+            let mut buffer = [0u8; 4];
+            for byte in c.encode_utf8(&mut buffer).bytes() {
+                quoted.extend_from_slice(format!("\\{:03o}", byte).as_bytes());
+            }
+            return;
+        }
+        Unit::Invalid(byte) => {
+            quoted.extend_from_slice(format!("\\{:03o}", byte).as_bytes());
+            return;
+        }
+    };
+    quoted.extend_from_slice(escape);
+}
+
+fn push_char(quoted: &mut Vec<u8>, c: char) {
+    let mut buffer = [0u8; 4];
+    quoted.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+}
+
+///
+/// Split text into characters, keeping bytes that aren't `UTF-8` apart.
+///
+fn units(text: &[u8]) -> Vec<Unit> {
+    let mut units = vec![];
+    for chunk in text.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            units.push(if c.is_control() { Unit::Unprintable(c) } else { Unit::Printable(c) });
+        }
+        units.extend(chunk.invalid().iter().map(|&byte| Unit::Invalid(byte)));
+    }
+    units
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use crate::output::quote::{quote, quote_name, quote_path, shell_escape, QuotingStyle};
+
+    fn escaped(text: &str) -> String {
+        shell_escape(text.as_bytes())
+    }
+
+    fn styled(text: &[u8], style: QuotingStyle) -> String {
+        String::from_utf8_lossy(&quote(text, style)).into_owned()
+    }
+
+    #[test]
+    fn test_plain() {
+        assert_eq!("''", escaped(""));
+        assert_eq!("abc", escaped("abc"));
+        assert_eq!("a#b~{}]", escaped("a#b~{}]"));
+        assert_eq!("é", escaped("é"));
+    }
+
+    #[test]
+    fn test_single_quotes() {
+        assert_eq!("'a b'", escaped("a b"));
+        assert_eq!("'#x'", escaped("#x"));
+        assert_eq!("'~a'", escaped("~a"));
+        assert_eq!("'a=b'", escaped("a=b"));
+        assert_eq!("'x\\ny'", escaped("x\\ny"));
+        assert_eq!("'a'\\''$b'", escaped("a'$b"));
+        assert_eq!("'a'\\''b\"c'", escaped("a'b\"c"));
+    }
+
+    #[test]
+    fn test_double_quotes() {
+        assert_eq!("\"a'b\"", escaped("a'b"));
+        assert_eq!("\"a'b c\"", escaped("a'b c"));
+        assert_eq!("\"''\"", escaped("''"));
+        assert_eq!("\"é'x y\"", escaped("é'x y"));
+    }
+
+    #[test]
+    fn test_unprintable() {
+        assert_eq!("'a'$'\\n''b'", escaped("a\nb"));
+        assert_eq!("''$'\\n\\n''b'", escaped("\n\nb"));
+        assert_eq!("'a'$'\\a\\b\\f\\r\\v\\033''z'", escaped("a\x07\x08\x0c\r\x0b\x1bz"));
+        assert_eq!("'a'$'\\n'", escaped("a\n"));
+        assert_eq!("'a'\\'''$'\\n''b'", escaped("a'\nb"));
+        assert_eq!("''$'\\377'", shell_escape(b"\xff"));
+        assert_eq!("'é'$'\\251'", shell_escape(b"\xc3\xa9\xa9"));
+        assert_eq!("'a'$'\\177''b'", escaped("a\x7fb"));
+    }
+
+    #[test]
+    fn test_shell() {
+        assert_eq!("a", styled(b"a", QuotingStyle::Shell));
+        assert_eq!("'c d'", styled(b"c d", QuotingStyle::Shell));
+        assert_eq!("\"e'f\"", styled(b"e'f", QuotingStyle::Shell));
+        assert_eq!("'g\nh'", styled(b"g\nh", QuotingStyle::Shell));
+        assert_eq!("\x01", styled(b"\x01", QuotingStyle::Shell));
+        assert_eq!(b"'a b\xff'".to_vec(), quote(b"a b\xff", QuotingStyle::Shell));
+        assert_eq!("'a'", styled(b"a", QuotingStyle::ShellAlways));
+        assert_eq!("'g\nh'", styled(b"g\nh", QuotingStyle::ShellAlways));
+        assert_eq!("'a'", styled(b"a", QuotingStyle::ShellEscapeAlways));
+        assert_eq!("\"e'f\"", styled(b"e'f", QuotingStyle::ShellEscapeAlways));
+        assert_eq!("'g'$'\\n''h'", styled(b"g\nh", QuotingStyle::ShellEscapeAlways));
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!("'a'", quote_name(b"a"));
+        assert_eq!("\"e'f\"", quote_name(b"e'f"));
+        assert_eq!("'g'$'\\377'", quote_name(b"g\xff"));
+        assert_eq!("'/tmp/a b'", quote_path(Path::new("/tmp/a b")));
+    }
+
+    #[test]
+    fn test_c_and_escape() {
+        assert_eq!("\"a\"", styled(b"a", QuotingStyle::C));
+        assert_eq!("\"c d\"", styled(b"c d", QuotingStyle::C));
+        assert_eq!("\"e'f\\\"\"", styled(b"e'f\"", QuotingStyle::C));
+        assert_eq!("\"g\\nh\\001\\\\\\377\"", styled(b"g\nh\x01\\\xff", QuotingStyle::C));
+        assert_eq!("c\\ d\"", styled(b"c d\"", QuotingStyle::Escape));
+        assert_eq!("g\\nh\\001\\\\\\377", styled(b"g\nh\x01\\\xff", QuotingStyle::Escape));
+        assert_eq!("a\nb", styled(b"a\nb", QuotingStyle::Literal));
+    }
+}
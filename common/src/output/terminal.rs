@@ -0,0 +1,52 @@
+const COLUMNS_VARIABLE: &str = "COLUMNS";
+
+///
+/// Determine the width of the terminal attached to stdout, if any.
+///
+/// The `COLUMNS` environment variable wins when it holds a positive number; otherwise the
+/// terminal is asked directly.  [None] is returned when stdout isn't a terminal.
+///
+pub fn terminal_width() -> Option<usize> {
+    if let Some(width) = parse_columns(std::env::var(COLUMNS_VARIABLE).ok()) {
+        return Some(width);
+    }
+
+    query_terminal_width()
+}
+
+fn parse_columns(value: Option<String>) -> Option<usize> {
+    value
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .filter(|&w| w > 0)
+}
+
+#[cfg(unix)]
+fn query_terminal_width() -> Option<usize> {
+    /* SAFETY: TIOCGWINSZ only writes into the winsize struct we hand it */
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    let result = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+    if result == 0 && size.ws_col > 0 {
+        Some(size.ws_col as usize)
+    } else {
+        None
+    }
+}
+
+#[cfg(not(unix))]
+fn query_terminal_width() -> Option<usize> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::output::terminal::parse_columns;
+
+    #[test]
+    fn test_parse_columns() {
+        assert_eq!(Some(80), parse_columns(Some("80".to_owned())));
+        assert_eq!(Some(132), parse_columns(Some(" 132 ".to_owned())));
+        assert_eq!(None, parse_columns(Some("0".to_owned())));
+        assert_eq!(None, parse_columns(Some("wide".to_owned())));
+        assert_eq!(None, parse_columns(None));
+    }
+}
@@ -0,0 +1,101 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+///
+/// A name in the temp directory that no other test, in this process or another, is using.
+///
+fn unique_path(name: &str) -> PathBuf {
+    let unique = COUNTER.fetch_add(1, Ordering::SeqCst);
+    std::env::temp_dir().join(format!("rs_coreutils-test-{}-{}-{}", std::process::id(), unique, name))
+}
+
+///
+/// A uniquely named directory in the temp directory, removed with its contents when dropped.
+///
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    pub fn new(name: &str) -> TempDir {
+        let path = unique_path(name);
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TempDir { path }
+    }
+
+    pub fn path(&self) -> &str {
+        self.path.to_str().unwrap()
+    }
+
+    pub fn join(&self, name: &str) -> PathBuf {
+        self.path.join(name)
+    }
+
+    ///
+    /// Write a file in the directory, and return its path as a string for use as an argument.
+    ///
+    pub fn file(&self, name: &str, contents: &[u8]) -> String {
+        let path = self.join(name);
+        fs::write(&path, contents).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+///
+/// A uniquely named file in the temp directory, removed when dropped.  The file itself isn't created
+/// until something is written to it.
+///
+pub struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    pub fn new(name: &str) -> TempFile {
+        let path = unique_path(name);
+        let _ = fs::remove_file(&path);
+        TempFile { path }
+    }
+
+    pub fn path(&self) -> &str {
+        self.path.to_str().unwrap()
+    }
+
+    pub fn append(&self, text: &str) {
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path).unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    }
+
+    pub fn truncate(&self, text: &str) {
+        fs::write(&self.path, text).unwrap();
+    }
+
+    ///
+    /// Replace the file with a new one with the same name, the way log rotation does.
+    ///
+    pub fn replace(&self, text: &str) {
+        let staged = self.path.with_extension("new");
+        fs::write(&staged, text).unwrap();
+        fs::rename(&staged, &self.path).unwrap();
+    }
+
+    pub fn remove(&self) {
+        fs::remove_file(&self.path).unwrap();
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
@@ -0,0 +1,110 @@
+use std::ffi::CStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+///
+/// A moment broken down into calendar fields in the local time zone; `TZ` is honored when set.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalTime {
+    pub year: i64,
+    /// 1 to 12.
+    pub month: u32,
+    /// 1 to 31.
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+    pub nanosecond: u32,
+    /// 0 to 6, from Sunday.
+    pub weekday: u32,
+    /// 0 to 365, from the first of January.
+    pub year_day: u32,
+    /// Seconds east of UTC.
+    pub offset: i64,
+    /// The zone's abbreviation, such as `UTC` or `CEST`.
+    pub zone: String,
+    /// Seconds since the epoch.
+    pub timestamp: i64,
+}
+
+impl LocalTime {
+    ///
+    /// Break a moment down in the local time zone; [None] when it's too far from the epoch to have a
+    /// calendar date.
+    ///
+    pub fn new(time: SystemTime) -> Option<LocalTime> {
+        let (timestamp, nanosecond) = epoch_seconds(time);
+        let seconds = libc::time_t::try_from(timestamp).ok()?;
+
+        /* SAFETY: localtime_r only writes into the tm struct we hand it */
+        let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+        let result = unsafe { libc::localtime_r(&seconds, &mut tm) };
+        if result.is_null() {
+            return None;
+        }
+
+        let zone = if tm.tm_zone.is_null() {
+            String::new()
+        } else {
+            /* SAFETY: a zone set by localtime_r points at a string that lives as long as the process */
+            unsafe { CStr::from_ptr(tm.tm_zone) }.to_string_lossy().into_owned()
+        };
+        Some(LocalTime {
+            year: tm.tm_year as i64 + 1900,
+            month: tm.tm_mon as u32 + 1,
+            day: tm.tm_mday as u32,
+            hour: tm.tm_hour as u32,
+            minute: tm.tm_min as u32,
+            second: tm.tm_sec as u32,
+            nanosecond,
+            weekday: tm.tm_wday as u32,
+            year_day: tm.tm_yday as u32,
+            offset: tm.tm_gmtoff as i64,
+            zone,
+            timestamp,
+        })
+    }
+}
+
+///
+/// Whole seconds since the epoch, with the nanoseconds past them; times before the epoch have
+/// negative seconds, but the nanoseconds still count forward.
+///
+pub fn epoch_seconds(time: SystemTime) -> (i64, u32) {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(since) => (since.as_secs() as i64, since.subsec_nanos()),
+        Err(before) => {
+            let before = before.duration();
+            match before.subsec_nanos() {
+                0 => (-(before.as_secs() as i64), 0),
+                nanos => (-(before.as_secs() as i64) - 1, 1_000_000_000 - nanos),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+    use crate::time::local::{epoch_seconds, LocalTime};
+
+    #[test]
+    fn test_epoch_seconds() {
+        assert_eq!((0, 0), epoch_seconds(UNIX_EPOCH));
+        assert_eq!((90, 5), epoch_seconds(UNIX_EPOCH + Duration::new(90, 5)));
+        assert_eq!((-90, 0), epoch_seconds(UNIX_EPOCH - Duration::new(90, 0)));
+        assert_eq!((-91, 999_999_999), epoch_seconds(UNIX_EPOCH - Duration::new(90, 1)));
+    }
+
+    #[test]
+    fn test_new() {
+        /* the zone is whatever the machine's is, so only what holds in every zone is checked */
+        let time = LocalTime::new(UNIX_EPOCH + Duration::new(86_400 * 180, 250)).unwrap();
+
+        assert_eq!(1970, time.year);
+        assert!((6..=7).contains(&time.month));
+        assert_eq!(250, time.nanosecond);
+        assert_eq!(86_400 * 180, time.timestamp);
+        assert_eq!(time.timestamp + time.offset, ((time.year_day as i64) * 24 + time.hour as i64) * 3600 + time.minute as i64 * 60 + time.second as i64);
+    }
+}
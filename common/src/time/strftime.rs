@@ -0,0 +1,287 @@
+use crate::time::local::LocalTime;
+
+pub const MONTHS: [&str; 12] = ["January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November", "December"];
+pub const MONTH_ABBREVIATIONS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+pub const WEEKDAYS: [&str; 7] = ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"];
+pub const WEEKDAY_ABBREVIATIONS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+const NANOSECOND_DIGITS: usize = 9;
+
+///
+/// How a conversion is padded to its width; the default depends on the conversion.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Padding {
+    Zeros,
+    Spaces,
+    None,
+}
+
+///
+/// One `%` conversion's flags and width, as in `%_5d`.
+///
+#[derive(Debug, Clone, Copy, Default)]
+struct Spec {
+    padding: Option<Padding>,
+    upper: bool,
+    width: Option<usize>,
+    colons: usize,
+}
+
+///
+/// Format a time the way `date +FORMAT` and `strftime` do, in the `C` locale.  The flags `_`, `-`,
+/// `0` and `^` and a field width may follow the `%`; a conversion that isn't known is written as
+/// it was given.
+///
+pub fn strftime(format: &str, time: &LocalTime) -> String {
+    let mut formatted = String::new();
+    let mut rest = format;
+
+    while let Some(start) = rest.find('%') {
+        formatted.push_str(&rest[..start]);
+        let directive = &rest[start..];
+        let (spec, length) = read_spec(&directive[1..]);
+        match directive[1 + length..].chars().next() {
+            Some(conversion) => {
+                let end = 1 + length + conversion.len_utf8();
+                match convert(conversion, &spec, time) {
+                    Some(converted) => formatted.push_str(&converted),
+                    None => formatted.push_str(&directive[..end]),
+                }
+                rest = &directive[end..];
+            }
+            None => {
+                formatted.push_str(directive);
+                rest = "";
+            }
+        }
+    }
+    formatted.push_str(rest);
+    formatted
+}
+
+///
+/// Read the flags, width and colons between a `%` and its conversion; returns them with the bytes read.
+///
+fn read_spec(text: &str) -> (Spec, usize) {
+    let mut spec = Spec::default();
+    let bytes = text.as_bytes();
+    let mut position = 0;
+
+    while let Some(&flag) = bytes.get(position) {
+        match flag {
+            b'_' => spec.padding = Some(Padding::Spaces),
+            b'-' => spec.padding = Some(Padding::None),
+            b'0' => spec.padding = Some(Padding::Zeros),
+            b'^' => spec.upper = true,
+            b'#' => {}
+            _ => break,
+        }
+        position += 1;
+    }
+
+    let digits = bytes[position..].iter().take_while(|b| b.is_ascii_digit()).count();
+    if digits > 0 {
+        spec.width = text[position..position + digits].parse().ok();
+        position += digits;
+    }
+
+    /* E and O pick alternative representations, which the C locale doesn't have */
+    while matches!(bytes.get(position), Some(b'E' | b'O')) {
+        position += 1;
+    }
+    while bytes.get(position) == Some(&b':') {
+        spec.colons += 1;
+        position += 1;
+    }
+    (spec, position)
+}
+
+fn convert(conversion: char, spec: &Spec, time: &LocalTime) -> Option<String> {
+    let (hour12, meridiem) = match time.hour {
+        0 => (12, "AM"),
+        hour @ 1..=11 => (hour, "AM"),
+        12 => (12, "PM"),
+        hour => (hour - 12, "PM"),
+    };
+    let number = |value: i64, width: usize, padding: Padding| Some(pad_number(value, spec, width, padding));
+    let text = |value: &str| Some(pad_text(value, spec));
+    if spec.colons > 0 && conversion != 'z' {
+        return None;
+    }
+
+    match conversion {
+        'a' => text(WEEKDAY_ABBREVIATIONS[time.weekday as usize]),
+        'A' => text(WEEKDAYS[time.weekday as usize]),
+        'b' | 'h' => text(MONTH_ABBREVIATIONS[time.month as usize - 1]),
+        'B' => text(MONTHS[time.month as usize - 1]),
+        'c' => text(&strftime("%a %b %e %H:%M:%S %Y", time)),
+        'C' => number(time.year.div_euclid(100), 2, Padding::Zeros),
+        'd' => number(time.day as i64, 2, Padding::Zeros),
+        'D' | 'x' => text(&strftime("%m/%d/%y", time)),
+        'e' => number(time.day as i64, 2, Padding::Spaces),
+        'F' => text(&strftime("%Y-%m-%d", time)),
+        'g' => number(iso_week(time).0.rem_euclid(100), 2, Padding::Zeros),
+        'G' => number(iso_week(time).0, 1, Padding::Zeros),
+        'H' => number(time.hour as i64, 2, Padding::Zeros),
+        'I' => number(hour12 as i64, 2, Padding::Zeros),
+        'j' => number(time.year_day as i64 + 1, 3, Padding::Zeros),
+        'k' => number(time.hour as i64, 2, Padding::Spaces),
+        'l' => number(hour12 as i64, 2, Padding::Spaces),
+        'm' => number(time.month as i64, 2, Padding::Zeros),
+        'M' => number(time.minute as i64, 2, Padding::Zeros),
+        'n' => text("\n"),
+        'N' => Some(nanoseconds(time.nanosecond, spec)),
+        'p' => text(meridiem),
+        'P' => text(&meridiem.to_lowercase()),
+        'r' => text(&strftime("%I:%M:%S %p", time)),
+        'R' => text(&strftime("%H:%M", time)),
+        's' => number(time.timestamp, 1, Padding::Zeros),
+        'S' => number(time.second as i64, 2, Padding::Zeros),
+        't' => text("\t"),
+        'T' | 'X' => text(&strftime("%H:%M:%S", time)),
+        'u' => number(((time.weekday + 6) % 7 + 1) as i64, 1, Padding::Zeros),
+        'U' => number(((time.year_day + 7 - time.weekday) / 7) as i64, 2, Padding::Zeros),
+        'V' => number(iso_week(time).1, 2, Padding::Zeros),
+        'w' => number(time.weekday as i64, 1, Padding::Zeros),
+        'W' => number(((time.year_day + 7 - (time.weekday + 6) % 7) / 7) as i64, 2, Padding::Zeros),
+        'y' => number(time.year.rem_euclid(100), 2, Padding::Zeros),
+        'Y' => number(time.year, 1, Padding::Zeros),
+        'z' => zone_offset(time.offset, spec),
+        'Z' => text(&time.zone),
+        '%' => text("%"),
+        _ => None,
+    }
+}
+
+fn pad_number(value: i64, spec: &Spec, width: usize, padding: Padding) -> String {
+    let digits = value.unsigned_abs().to_string();
+    let sign = if value < 0 { "-" } else { "" };
+    let width = spec.width.unwrap_or(width).saturating_sub(sign.len());
+    match spec.padding.unwrap_or(padding) {
+        Padding::Zeros => format!("{}{:0>width$}", sign, digits, width = width),
+        Padding::Spaces => format!("{:>width$}", format!("{}{}", sign, digits), width = width + sign.len()),
+        Padding::None => format!("{}{}", sign, digits),
+    }
+}
+
+fn pad_text(value: &str, spec: &Spec) -> String {
+    let value = if spec.upper { value.to_uppercase() } else { value.to_string() };
+    let width = spec.width.unwrap_or(0);
+    match spec.padding {
+        Some(Padding::Zeros) => format!("{:0>width$}", value, width = width),
+        _ => format!("{:>width$}", value, width = width),
+    }
+}
+
+///
+/// `%N` is always nine digits unless a width asks for fewer, or more, which are zeros.
+///
+fn nanoseconds(nanosecond: u32, spec: &Spec) -> String {
+    let digits = format!("{:09}", nanosecond);
+    match spec.width {
+        Some(width) if width <= NANOSECOND_DIGITS => digits[..width].to_string(),
+        Some(width) => format!("{:0<width$}", digits, width = width),
+        None => digits,
+    }
+}
+
+///
+/// `%z` is `+hhmm`; `%:z` is `+hh:mm` and `%::z` is `+hh:mm:ss`.
+///
+fn zone_offset(offset: i64, spec: &Spec) -> Option<String> {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let offset = offset.unsigned_abs();
+    let (hours, minutes, seconds) = (offset / 3600, offset / 60 % 60, offset % 60);
+    match spec.colons {
+        0 => Some(format!("{}{:02}{:02}", sign, hours, minutes)),
+        1 => Some(format!("{}{:02}:{:02}", sign, hours, minutes)),
+        2 => Some(format!("{}{:02}:{:02}:{:02}", sign, hours, minutes, seconds)),
+        _ => None,
+    }
+}
+
+///
+/// The ISO 8601 week-based year and week number; weeks start on Monday, and the first week of a
+/// year is the one holding its first Thursday.
+///
+fn iso_week(time: &LocalTime) -> (i64, i64) {
+    let weekday = ((time.weekday + 6) % 7 + 1) as i64;
+    let week = (time.year_day as i64 + 1 - weekday + 10) / 7;
+
+    if week < 1 {
+        (time.year - 1, weeks_in_year(time.year - 1))
+    } else if week > weeks_in_year(time.year) {
+        (time.year + 1, 1)
+    } else {
+        (time.year, week)
+    }
+}
+
+fn weeks_in_year(year: i64) -> i64 {
+    let december_31st = |year: i64| (year + year.div_euclid(4) - year.div_euclid(100) + year.div_euclid(400)).rem_euclid(7);
+    if december_31st(year) == 4 || december_31st(year - 1) == 3 { 53 } else { 52 }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::time::local::LocalTime;
+    use crate::time::strftime::strftime;
+
+    fn time(year: i64, month: u32, day: u32, year_day: u32, weekday: u32) -> LocalTime {
+        LocalTime {
+            year,
+            month,
+            day,
+            hour: 6,
+            minute: 5,
+            second: 9,
+            nanosecond: 279_120_191,
+            weekday,
+            year_day,
+            offset: -(4 * 3600 + 30 * 60),
+            zone: "XST".to_string(),
+            timestamp: 1_792_391_796,
+        }
+    }
+
+    #[test]
+    fn test_ls_styles() {
+        let october = time(2026, 10, 19, 291, 1);
+
+        assert_eq!("Oct 19 06:05", strftime("%b %e %H:%M", &october));
+        assert_eq!("Oct 19  2026", strftime("%b %e  %Y", &october));
+        assert_eq!("2026-10-19 06:05", strftime("%Y-%m-%d %H:%M", &october));
+        assert_eq!("2026-10-19 06:05:09.279120191 -0430", strftime("%Y-%m-%d %H:%M:%S.%N %z", &october));
+        assert_eq!("Jan  3  1999", strftime("%b %e  %Y", &time(1999, 1, 3, 2, 0)));
+    }
+
+    #[test]
+    fn test_conversions() {
+        let october = time(2026, 10, 19, 291, 1);
+
+        assert_eq!("Mon Monday October 20 26 292 1 1", strftime("%a %A %B %C %y %j %u %w", &october));
+        assert_eq!("10/19/26 06:05:09 AM  6 am", strftime("%D %r %l %P", &october));
+        assert_eq!("Mon Oct 19 06:05:09 2026", strftime("%c", &october));
+        assert_eq!("-04:30 -04:30:00 XST 1792391796", strftime("%:z %::z %Z %s", &october));
+        assert_eq!("a\tb\nc %", strftime("a%tb%nc %%", &october));
+        assert_eq!("42 42 43 2026 26", strftime("%U %W %V %G %g", &october));
+    }
+
+    #[test]
+    fn test_flags() {
+        let october = time(2026, 10, 19, 291, 1);
+
+        assert_eq!("   19|10|OCT|279|000019", strftime("%_5d|%-m|%^b|%3N|%06e", &october));
+        assert_eq!("279120191000| 6|06", strftime("%12N|%_H|%0k", &october));
+        assert_eq!("%Q|%:d|%", strftime("%Q|%:d|%", &october));
+    }
+
+    #[test]
+    fn test_iso_weeks() {
+        /* 2021-01-03 is a Sunday in the last week of 2020, and 2024-12-30 a Monday in the first week of 2025 */
+        assert_eq!("2020-W53-7", strftime("%G-W%V-%u", &time(2021, 1, 3, 2, 0)));
+        assert_eq!("2025-W01-1", strftime("%G-W%V-%u", &time(2024, 12, 30, 364, 1)));
+        assert_eq!("00 00", strftime("%U %W", &time(2022, 1, 1, 0, 6)));
+    }
+}
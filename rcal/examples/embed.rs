@@ -0,0 +1,26 @@
+use rcal::calendar::{grid, render, CalendarOptions, MonthRange, WeekStart};
+use rcal::time::locale::Locale;
+use rcal::time::month::Month;
+
+///
+/// Render a quarter with weeks starting on monday, then walk the same months as a grid.
+///
+fn main() {
+    let options = CalendarOptions {
+        week_start: WeekStart::Monday,
+        locale: Locale::from_environment(),
+        ..CalendarOptions::new(MonthRange::new(Month::new(1, 2025).unwrap(), 3).unwrap())
+    };
+
+    render(&options).iter().for_each(|line| println!("{}", line));
+
+    for month in grid(&options) {
+        let holidays: Vec<String> = month.weeks.iter()
+            .flatten()
+            .flatten()
+            .filter(|day| !day.holidays.is_empty())
+            .map(|day| format!("{} {}", day.date, day.holidays.join(", ")))
+            .collect();
+        println!("{}: {} weeks {:?}", month.name, month.weeks.len(), holidays);
+    }
+}
@@ -0,0 +1,232 @@
+use crate::errors::months_out_of_range;
+use crate::output::{formatter, ics, json};
+use crate::state::app_state::ApplicationState;
+use crate::state::config::MAX_MONTHS_PER_ROW;
+use crate::time::events::Event;
+use crate::time::locale::{Locale, ENGLISH};
+use crate::time::month::Month;
+use chrono::NaiveDate;
+use common::input::known_error::KnownError;
+
+pub use crate::output::grid::{GridDay, MonthGrid};
+
+pub(crate) const DEFAULT_MONTHS_PER_ROW: usize = 3;
+
+///
+/// A run of consecutive months to display.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonthRange {
+    first: Month,
+    count: usize,
+}
+
+impl MonthRange {
+    ///
+    /// `count` months, starting with `first`; all of them have to be displayable.
+    ///
+    pub fn new(first: Month, count: usize) -> Result<MonthRange, KnownError> {
+        let mut last = first;
+        for _ in 1..count.max(1) {
+            last = last.next().ok_or_else(months_out_of_range)?;
+        }
+
+        Ok(MonthRange { first, count: count.max(1) })
+    }
+
+    ///
+    /// A single month.
+    ///
+    pub fn month(month: Month) -> MonthRange {
+        MonthRange { first: month, count: 1 }
+    }
+
+    ///
+    /// January through December of the given year.
+    ///
+    pub fn year(year: i32) -> Result<MonthRange, KnownError> {
+        Ok(MonthRange { first: Month::new(1, year)?, count: 12 })
+    }
+
+    pub fn first(&self) -> Month {
+        self.first
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    ///
+    /// Every month in the range, in order.
+    ///
+    pub fn months(&self) -> Vec<Month> {
+        std::iter::successors(Some(self.first), Month::next)
+            .take(self.count)
+            .collect()
+    }
+}
+
+///
+/// The first day of each week row.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WeekStart {
+    Sunday,
+    Monday,
+}
+
+///
+/// What [render] produces.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RenderStyle {
+    Text,
+    Json,
+    Ics,
+}
+
+///
+/// Everything needed to draw a calendar.  Start from [CalendarOptions::new], and override fields as needed:
+///
+/// ```
+/// use rcal::calendar::{render, CalendarOptions, MonthRange, WeekStart};
+///
+/// let options = CalendarOptions { week_start: WeekStart::Monday, ..CalendarOptions::new(MonthRange::year(2024).unwrap()) };
+/// let lines = render(&options);
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct CalendarOptions {
+    pub range: MonthRange,
+    pub week_start: WeekStart,
+    pub highlight: Option<NaiveDate>,
+    pub style: RenderStyle,
+    pub months_per_row: usize,
+    pub locale: &'static Locale,
+    pub events: Vec<Event>,
+    /* the date exported documents are stamped with; the clock is read when it isn't given */
+    pub today: Option<NaiveDate>,
+}
+
+impl CalendarOptions {
+    ///
+    /// Plain text in english, three months per row, weeks starting on sunday, and nothing highlighted.
+    ///
+    pub fn new(range: MonthRange) -> CalendarOptions {
+        CalendarOptions {
+            range,
+            week_start: WeekStart::Sunday,
+            highlight: None,
+            style: RenderStyle::Text,
+            months_per_row: DEFAULT_MONTHS_PER_ROW,
+            locale: &ENGLISH,
+            events: vec![],
+            today: None,
+        }
+    }
+
+    pub(crate) fn effective_months_per_row(&self) -> usize {
+        self.months_per_row.clamp(1, MAX_MONTHS_PER_ROW)
+    }
+}
+
+///
+/// Render the calendar as lines of output, in the requested style.
+///
+pub fn render(options: &CalendarOptions) -> Vec<String> {
+    let state = ApplicationState::new(options);
+    match options.style {
+        RenderStyle::Text => formatter::format_calendar(&[], state)
+            .iter()
+            .skip(1)
+            .map(|s| s.to_owned())
+            .collect(),
+        RenderStyle::Json => json::format_json(&state),
+        RenderStyle::Ics => ics::format_ics(&state, options.today.unwrap_or_else(|| chrono::Utc::now().date_naive())),
+    }
+}
+
+///
+/// Lay out every month in the range as weeks of days, for callers that draw the calendar themselves.
+///
+pub fn grid(options: &CalendarOptions) -> Vec<MonthGrid> {
+    let state = ApplicationState::new(options);
+    state.months()
+        .map(|month| MonthGrid::new(month, &state))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::calendar::{grid, render, CalendarOptions, MonthRange, RenderStyle, WeekStart};
+    use crate::time::locale::Locale;
+    use crate::time::month::Month;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_range() {
+        let range = MonthRange::new(Month::new(11, 2024).unwrap(), 3).unwrap();
+
+        assert_eq!(vec![Month::new(11, 2024).unwrap(), Month::new(12, 2024).unwrap(), Month::new(1, 2025).unwrap()], range.months());
+        assert_eq!(12, MonthRange::year(2024).unwrap().months().len());
+        assert_eq!(1, MonthRange::new(Month::new(1, 2024).unwrap(), 0).unwrap().count());
+        assert!(MonthRange::new(Month::new(12, 9999).unwrap(), 2).is_err());
+        assert!(MonthRange::year(10000).is_err());
+    }
+
+    #[test]
+    fn test_render_monday_first() {
+        let options = CalendarOptions {
+            week_start: WeekStart::Monday,
+            ..CalendarOptions::new(MonthRange::month(Month::new(9, 2024).unwrap()))
+        };
+
+        let lines = render(&options);
+
+        assert_eq!("   September 2024                                                ", lines[0]);
+        assert_eq!(" Mo Tu We Th Fr Sa Su                                            ", lines[1]);
+        assert_eq!("                    1                                            ", lines[2]);
+        assert_eq!("  2  3  4  5  6  7  8                                            ", lines[3]);
+        assert_eq!(" 30                                                              ", lines[7]);
+    }
+
+    #[test]
+    fn test_render_styles() {
+        let options = CalendarOptions {
+            style: RenderStyle::Ics,
+            highlight: NaiveDate::from_ymd_opt(2024, 3, 5),
+            today: NaiveDate::from_ymd_opt(2024, 3, 1),
+            ..CalendarOptions::new(MonthRange::month(Month::new(3, 2024).unwrap()))
+        };
+
+        let lines = render(&options);
+
+        assert_eq!("UID:20240305-today@rcal\r", lines[5]);
+        assert_eq!("DTSTAMP:20240301T000000Z\r", lines[6]);
+        assert_eq!("SUMMARY:Easter\r", lines[17]);
+    }
+
+    #[test]
+    fn test_grid() {
+        let options = CalendarOptions {
+            highlight: NaiveDate::from_ymd_opt(2024, 3, 31),
+            locale: Locale::find("fr_FR.UTF-8"),
+            week_start: WeekStart::Monday,
+            ..CalendarOptions::new(MonthRange::new(Month::new(3, 2024).unwrap(), 2).unwrap())
+        };
+
+        let months = grid(&options);
+
+        assert_eq!(2, months.len());
+        assert_eq!("mars", months[0].name);
+        assert_eq!(vec!["lu", "ma", "me", "je", "ve", "sa", "di"], months[0].weekdays);
+        assert_eq!(5, months[0].weeks.len());
+        assert!(months[0].weeks[0][3].is_none());
+        assert_eq!(NaiveDate::from_ymd_opt(2024, 3, 1), months[0].weeks[0][4].as_ref().map(|day| day.date));
+
+        let easter = months[0].weeks[4][6].as_ref().unwrap();
+        assert!(easter.highlighted);
+        assert_eq!(vec!["Easter"], easter.holidays);
+        assert_eq!("avril", months[1].name);
+    }
+}
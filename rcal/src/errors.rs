@@ -0,0 +1,125 @@
+use std::io;
+use common::input::known_error::KnownError;
+use common::input::source::describe_io_error;
+use crate::time::year::Year;
+
+const ERROR_CODE: i32 = 1;
+
+///
+/// The year argument couldn't be read as a number.
+///
+pub(crate) fn invalid_year(argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("rcal: not a valid year {}", argument))
+}
+
+///
+/// The year argument is a number, but not one we can display.
+///
+pub(crate) fn year_out_of_range(argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("rcal: year {} not in range {}..{}", argument, Year::MIN, Year::MAX))
+}
+
+///
+/// The month argument didn't match any known month.
+///
+pub(crate) fn invalid_month(argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("rcal: {} is not a valid month", argument))
+}
+
+///
+/// The month argument is a prefix of more than one month.
+///
+pub(crate) fn ambiguous_month(argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("rcal: {} is ambiguous; it could be more than one month", argument))
+}
+
+///
+/// A single-character flag that isn't supported.
+///
+pub(crate) fn invalid_option(flag: char) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("rcal: invalid option -- '{}'", flag))
+}
+
+///
+/// A long option, or anything else dash-prefixed, that isn't supported.
+///
+pub(crate) fn unrecognized_option(argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("rcal: unrecognized option '{}'", argument))
+}
+
+///
+/// An option that takes a value was the last argument.
+///
+pub(crate) fn requires_argument(option: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("rcal: option {} requires an argument", option))
+}
+
+///
+/// An option that takes a numeric value was given something else.
+///
+pub(crate) fn requires_number(option: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("rcal: option {} requires a number", option))
+}
+
+///
+/// More positional arguments than `[[month] year]`.
+///
+pub(crate) fn too_many_arguments(argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("rcal: too many arguments, starting at {}", argument))
+}
+
+///
+/// The columns argument was neither a count nor `auto`.
+///
+pub(crate) fn invalid_columns(argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("rcal: not a valid number of columns {}", argument))
+}
+
+///
+/// Adding months before/after walked off either end of the supported years.
+///
+pub(crate) fn months_out_of_range() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("rcal: months before {} or after {} can't be displayed", Year::MIN, Year::MAX))
+}
+
+///
+/// The output format wasn't one we know how to write.
+///
+pub(crate) fn invalid_format(argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("rcal: {} is not a valid format; use text, json or ics", argument))
+}
+
+///
+/// iCalendar dates have four digit years, so months before year 1 can't be exported.
+///
+pub(crate) fn ics_out_of_range() -> KnownError {
+    KnownError::new(ERROR_CODE, "rcal: months before year 1 can't be written as ics".to_string())
+}
+
+///
+/// A date option wasn't in the form `yyyy-mm-dd`.
+///
+pub(crate) fn invalid_date(option: &str, argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("rcal: option {} requires a date in the form yyyy-mm-dd, not {}", option, argument))
+}
+
+///
+/// The events file couldn't be opened or read.
+///
+pub(crate) fn unreadable_events(path: &str, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("rcal: can't read events file {}: {}", path, describe_io_error(error)))
+}
+
+///
+/// A line in the events file doesn't start with a date we understand.
+///
+pub(crate) fn invalid_event(path: &str, line: usize, date: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("rcal: events file {}, line {}: can't read date '{}'", path, line, date))
+}
+
+///
+/// The current date option wasn't in the form `yyyy-mm` or `yyyy-mm-dd`.
+///
+pub(crate) fn invalid_current_date(argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("rcal: option -d requires a date in the form yyyy-mm or yyyy-mm-dd, not {}", argument))
+}
@@ -0,0 +1,7 @@
+pub mod calendar;
+pub mod time;
+pub(crate) mod state;
+pub(crate) mod output;
+mod errors;
+
+pub use output::cal::cal;
@@ -1,12 +1,6 @@
-extern crate alloc;
-
-use output::cal::cal;
+use rcal::cal;
+use rcal::time::today::TodayFactory;
 use std::process;
-use crate::time::today::TodayFactory;
-
-mod time;
-mod state;
-mod output;
 
 fn main() {
     let args = std::env::args();
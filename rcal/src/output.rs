@@ -1,5 +1,9 @@
 pub mod formatter;
 pub mod cal;
+pub mod json;
+pub mod ics;
+pub mod grid;
 mod cal_no_flag_tests;
 mod cal_flag_tests;
-mod cal_error_tests;
\ No newline at end of file
+mod cal_error_tests;
+mod cal_export_tests;
\ No newline at end of file
@@ -1,22 +1,25 @@
 use common::input::known_error::KnownError;
-use crate::state::config::{Config};
-use crate::output::formatter;
-use crate::state::app_state::ApplicationState;
+use crate::calendar::render;
+use crate::state::config::Config;
+use crate::state::app_state::calendar_options;
+use crate::time::locale::Locale;
 use crate::time::today::TodayFactory;
 
+///
+/// Run `cal` with command line arguments; the first argument is the program name.
+///
 pub fn cal(args: Vec<String>, today_factory: TodayFactory) -> Result<Vec<String>, Vec<KnownError>> {
     let config = Config::new(&args);
-    let today = today_factory.create();
-    let state = ApplicationState::new(&config, today.as_ref());
-    let lines = formatter::format_calendar(&config.errors, state)
-        .iter()
-        .skip(1)
-        .map(|s| s.to_owned())
-        .collect();
-
-    if config.errors.is_empty() {
-        Ok(lines)
-    } else {
-        Err(config.errors)
+    if !config.errors.is_empty() {
+        return Err(config.errors);
     }
+
+    /* a current date on the command line wins over whatever clock we were given */
+    let today = config.debug_current_date.as_deref()
+        .and_then(TodayFactory::parse)
+        .unwrap_or(today_factory)
+        .create();
+    let options = calendar_options(&config, today.as_ref(), Locale::from_environment()).map_err(|error| vec![error])?;
+
+    Ok(render(&options))
 }
@@ -2,6 +2,7 @@
 mod error_tests {
     use crate::output::cal::cal;
     use crate::time::today::TodayFactory;
+    use chrono::NaiveDate;
 
     #[test]
     fn test_no_flags_month_only() {
@@ -9,7 +10,7 @@ mod error_tests {
 
         let result = cal(args, TodayFactory::Actual);
 
-        assert_eq!(false, result.is_ok());
+        assert!(result.is_err());
         let lines = result.unwrap_err();
 
         assert_eq!(1, lines.len());
@@ -22,7 +23,7 @@ mod error_tests {
 
         let result = cal(args, TodayFactory::Actual);
 
-        assert_eq!(true, result.is_err());
+        assert!(result.is_err());
         let lines: Vec<_> = result.unwrap_err().iter().map(|x| x.message.clone().unwrap()).collect();
 
         assert_eq!(1, lines.len());
@@ -36,11 +37,126 @@ mod error_tests {
 
         let result = cal(args, TodayFactory::Actual);
 
-        assert_eq!(true, result.is_err());
+        assert!(result.is_err());
         let lines: Vec<_> = result.unwrap_err().iter().map(|x| x.message.clone().unwrap()).collect();
 
         assert_eq!(1, lines.len());
 
         assert_eq!("rcal: not a valid year feb", lines[0]);
     }
+
+    #[test]
+    fn test_year_with_too_many_columns() {
+        let args = "exe 2012 -c 13".split_whitespace().map(|s| s.to_string()).collect::<Vec<String>>();
+
+        let result = cal(args, TodayFactory::Actual);
+
+        assert!(result.is_err());
+        let lines: Vec<_> = result.unwrap_err().iter().map(|x| x.message.clone().unwrap()).collect();
+
+        assert_eq!(1, lines.len());
+
+        assert_eq!("rcal: not a valid number of columns 13", lines[0]);
+    }
+
+    #[test]
+    fn test_invalid_input_matrix() {
+        let cases = [
+            /* invalid months */
+            ("exe 13 2024", "rcal: 13 is not a valid month"),
+            ("exe smarch 2024", "rcal: smarch is not a valid month"),
+            ("exe -m smarch -y 2024", "rcal: smarch is not a valid month"),
+
+            /* years out of range, or not years at all */
+            ("exe 10000", "rcal: year 10000 not in range -9999..9999"),
+            ("exe jan 10000", "rcal: year 10000 not in range -9999..9999"),
+            ("exe -y 10000", "rcal: year 10000 not in range -9999..9999"),
+            ("exe -y -10000", "rcal: year -10000 not in range -9999..9999"),
+            ("exe -y 4294967297", "rcal: year 4294967297 not in range -9999..9999"),
+            ("exe -y 99999999999999999999", "rcal: not a valid year 99999999999999999999"),
+            ("exe -y abc", "rcal: not a valid year abc"),
+
+            /* missing or non-numeric option values */
+            ("exe -y", "rcal: option -y requires a number"),
+            ("exe -A", "rcal: option -A requires a number"),
+            ("exe -B", "rcal: option -B requires a number"),
+            ("exe -A x", "rcal: option -A requires a number"),
+            ("exe -B 2.5", "rcal: option -B requires a number"),
+            ("exe -m", "rcal: option -m requires an argument"),
+            ("exe -s", "rcal: option -s requires an argument"),
+            ("exe -d", "rcal: option -d requires an argument"),
+            ("exe -H", "rcal: option -H requires an argument"),
+            ("exe -W", "rcal: option -W requires an argument"),
+            ("exe -c", "rcal: option -c requires an argument"),
+
+            /* unknown options and extra arguments */
+            ("exe -x", "rcal: invalid option -- 'x'"),
+            ("exe -hx", "rcal: invalid option -- 'x'"),
+            ("exe --bogus", "rcal: unrecognized option '--bogus'"),
+            ("exe jan 2024 1", "rcal: too many arguments, starting at 1"),
+
+            /* walking off either end of the supported years */
+            ("exe -m jan -y -9999 -B 1", "rcal: months before -9999 or after 9999 can't be displayed"),
+            ("exe dec 9999 -A 1", "rcal: months before -9999 or after 9999 can't be displayed"),
+            ("exe -3 -m dec -y 9999", "rcal: months before -9999 or after 9999 can't be displayed"),
+        ];
+
+        for (input, expected) in cases {
+            let args = input.split_whitespace().map(|s| s.to_string()).collect::<Vec<String>>();
+
+            let result = cal(args, TodayFactory::Actual);
+
+            assert!(result.is_err(), "expected an error for [{}]", input);
+            let lines: Vec<_> = result.unwrap_err().iter().map(|x| x.message.clone().unwrap()).collect();
+
+            assert_eq!(vec![expected.to_string()], lines, "input: [{}]", input);
+        }
+    }
+
+    #[test]
+    fn test_boundary_months_render() {
+        let first = cal("exe -3 -m jan -y 1".split_whitespace().map(|s| s.to_string()).collect(), TodayFactory::Actual).unwrap();
+        let last = cal("exe dec 9999".split_whitespace().map(|s| s.to_string()).collect(), TodayFactory::Actual).unwrap();
+        let negative = cal("exe -y -44 -m mar".split_whitespace().map(|s| s.to_string()).collect(), TodayFactory::Actual).unwrap();
+        let positional = cal("exe -- mar -44".split_whitespace().map(|s| s.to_string()).collect(), TodayFactory::Actual).unwrap();
+
+        assert_eq!("     December 0             January 1            February 1      ", first[0]);
+        assert_eq!("                 1  2      1  2  3  4  5  6               1  2  3", first[2]);
+        assert_eq!("    December 9999                                                ", last[0]);
+        assert_eq!("           1  2  3  4                                            ", last[2]);
+        assert_eq!(" 26 27 28 29 30 31                                               ", last[6]);
+        assert_eq!("      March -44                                                  ", negative[0]);
+        assert_eq!("              1  2  3                                            ", negative[2]);
+        assert_eq!(negative, positional);
+    }
+
+    #[test]
+    fn test_missing_events_file() {
+        let args = "exe --events /nonexistent/rcal/events jan 2024".split_whitespace().map(|s| s.to_string()).collect::<Vec<String>>();
+
+        let errors = cal(args, TodayFactory::Actual).unwrap_err();
+
+        assert_eq!(1, errors.len());
+        assert_eq!(Some("rcal: can't read events file /nonexistent/rcal/events: No such file or directory".to_owned()), errors[0].message);
+    }
+
+    #[test]
+    fn test_invalid_current_date() {
+        for value in ["2024-13", "2024-02-30", "10000-01", "soon"] {
+            let args = vec!["exe".to_owned(), "-d".to_owned(), value.to_owned()];
+
+            let errors = cal(args, TodayFactory::Actual).unwrap_err();
+
+            assert_eq!(Some(format!("rcal: option -d requires a date in the form yyyy-mm or yyyy-mm-dd, not {}", value)), errors[0].message);
+        }
+    }
+
+    #[test]
+    fn test_invalid_fixed_today() {
+        let args = || vec!["exe".to_owned()];
+        let beyond = NaiveDate::from_ymd_opt(10000, 1, 1).unwrap();
+
+        assert_eq!(Some("rcal: 13 is not a valid month".to_owned()), cal(args(), TodayFactory::Other { y: 2024, m: 13 }).unwrap_err()[0].message);
+        assert!(cal(args(), TodayFactory::Date(beyond)).is_err());
+    }
 }
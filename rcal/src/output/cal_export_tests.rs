@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod export_tests {
+    use crate::output::cal::cal;
+    use crate::time::today::TodayFactory;
+
+    fn run(arguments: &str) -> Vec<String> {
+        let args = arguments.split_whitespace().map(|s| s.to_string()).collect::<Vec<String>>();
+        cal(args, TodayFactory::Other { y: 2024, m: 6 }).unwrap()
+    }
+
+    #[test]
+    fn test_json_month() {
+        let lines = run("exe --format=json -H 2024-02-14 feb 2024");
+
+        assert_eq!(40, lines.len());
+        assert_eq!("{", lines[0]);
+        assert_eq!("  \"months\": [", lines[1]);
+        assert_eq!("    {", lines[2]);
+        assert_eq!("      \"year\": 2024,", lines[3]);
+        assert_eq!("      \"month\": 2,", lines[4]);
+        assert_eq!("      \"name\": \"February\",", lines[5]);
+        assert_eq!("      \"days\": [", lines[6]);
+        assert_eq!("        { \"date\": \"2024-02-01\", \"day\": 1, \"weekday\": \"Thursday\", \"week\": 5, \"julian_day\": 32, \"highlighted\": false, \"holidays\": [] },", lines[7]);
+        assert_eq!("        { \"date\": \"2024-02-14\", \"day\": 14, \"weekday\": \"Wednesday\", \"week\": 7, \"julian_day\": 45, \"highlighted\": true, \"holidays\": [] },", lines[20]);
+        assert_eq!("        { \"date\": \"2024-02-29\", \"day\": 29, \"weekday\": \"Thursday\", \"week\": 9, \"julian_day\": 60, \"highlighted\": false, \"holidays\": [] }", lines[35]);
+        assert_eq!("      ]", lines[36]);
+        assert_eq!("    }", lines[37]);
+        assert_eq!("  ]", lines[38]);
+        assert_eq!("}", lines[39]);
+    }
+
+    #[test]
+    fn test_json_follows_month_selection() {
+        let lines = run("exe --format json -h -B 1 -A 1 apr 2025");
+
+        let months: Vec<&String> = lines.iter().filter(|line| line.contains("\"month\":")).collect();
+        assert_eq!(vec!["      \"month\": 3,", "      \"month\": 4,", "      \"month\": 5,"], months);
+        assert_eq!("    },", lines[39]);
+        assert!(lines.iter().all(|line| !line.contains("\"highlighted\": true")));
+        assert!(lines.contains(&"        { \"date\": \"2025-04-20\", \"day\": 20, \"weekday\": \"Sunday\", \"week\": 16, \"julian_day\": 110, \"highlighted\": false, \"holidays\": [\"Easter\", \"Orthodox Easter\"] },".to_string()));
+    }
+
+    #[test]
+    fn test_ics_events() {
+        let lines = run("exe --format=ics -H 2024-03-05 -3 apr 2024");
+
+        assert_eq!(29, lines.len());
+        assert_eq!("BEGIN:VCALENDAR\r", lines[0]);
+        assert_eq!("VERSION:2.0\r", lines[1]);
+        assert_eq!("CALSCALE:GREGORIAN\r", lines[3]);
+        assert_eq!(vec![
+            "BEGIN:VEVENT\r",
+            "UID:20240305-today@rcal\r",
+            "DTSTAMP:20240601T000000Z\r",
+            "DTSTART;VALUE=DATE:20240305\r",
+            "DTEND;VALUE=DATE:20240306\r",
+            "SUMMARY:Today\r",
+            "TRANSP:TRANSPARENT\r",
+            "END:VEVENT\r",
+        ], lines[4..12].to_vec());
+        assert_eq!("SUMMARY:Easter\r", lines[17]);
+        assert_eq!("DTSTART;VALUE=DATE:20240505\r", lines[23]);
+        assert_eq!("SUMMARY:Orthodox Easter\r", lines[25]);
+        assert_eq!("END:VCALENDAR\r", lines[28]);
+    }
+
+    #[test]
+    fn test_ics_skips_dates_not_on_display() {
+        let lines = run("exe --format=ics -H 2024-03-05 jan 2024");
+
+        assert_eq!(vec!["BEGIN:VCALENDAR\r", "VERSION:2.0\r"], lines[0..2].to_vec());
+        assert_eq!(5, lines.len());
+        assert_eq!("END:VCALENDAR\r", lines[4]);
+    }
+
+    #[test]
+    fn test_export_errors() {
+        let args = "exe --format=ics -H 2024-3".split_whitespace().map(|s| s.to_string()).collect::<Vec<String>>();
+
+        let errors = cal(args, TodayFactory::Other { y: 2024, m: 6 }).unwrap_err();
+
+        assert_eq!(Some("rcal: option -H requires a date in the form yyyy-mm-dd, not 2024-3".to_owned()), errors[0].message);
+    }
+
+    #[test]
+    fn test_export_before_year_one() {
+        let before = "exe --format=ics -d -0044-03-15 -y -44 -m mar".split_whitespace().map(|s| s.to_string()).collect::<Vec<String>>();
+        let spanning = "exe --format=ics -3 -m jan -y 1".split_whitespace().map(|s| s.to_string()).collect::<Vec<String>>();
+        let first = "exe --format=ics -d 0001-01-01 -m jan -y 1".split_whitespace().map(|s| s.to_string()).collect::<Vec<String>>();
+
+        let expected = Some("rcal: months before year 1 can't be written as ics".to_owned());
+        assert_eq!(expected, cal(before, TodayFactory::Other { y: 2024, m: 6 }).unwrap_err()[0].message);
+        assert_eq!(expected, cal(spanning, TodayFactory::Other { y: 2024, m: 6 }).unwrap_err()[0].message);
+        assert!(cal(first, TodayFactory::Other { y: 2024, m: 6 }).unwrap().contains(&"DTSTART;VALUE=DATE:00010101\r".to_string()));
+    }
+}
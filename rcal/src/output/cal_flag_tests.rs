@@ -1,7 +1,9 @@
 #[cfg(test)]
 mod flag_tests {
     use crate::output::cal::cal;
-    use crate::time::today::TodayFactory;
+    use crate::time::month::Month;
+    use crate::time::today::{Today, TodayFactory};
+    use common::input::known_error::KnownError;
 
     #[test]
     fn test_year_and_before() {
@@ -9,10 +11,10 @@ mod flag_tests {
 
         let result = cal(args, TodayFactory::Actual);
 
-        assert_eq!(true, result.is_ok());
+        assert!(result.is_ok());
         let lines = result.unwrap();
 
-        assert_eq!(47, lines.len());
+        assert_eq!(44, lines.len());
 
         assert_eq!("                              1985                               ", lines[0]);
         assert_eq!("       October              November              December       ", lines[1]);
@@ -33,34 +35,31 @@ mod flag_tests {
         assert_eq!(" 26 27 28 29 30 31     23 24 25 26 27 28     23 24 25 26 27 28 29", lines[16]);
         assert_eq!("                                             30 31               ", lines[17]);
         assert_eq!("                                                                 ", lines[18]);
-        assert_eq!("                              1986                               ", lines[19]);// todo: doesn't belong here?
-        assert_eq!("        April                  May                  June         ", lines[20]);
-        assert_eq!(" Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa", lines[21]);
-        assert_eq!("        1  2  3  4  5               1  2  3   1  2  3  4  5  6  7", lines[22]);
-        assert_eq!("  6  7  8  9 10 11 12   4  5  6  7  8  9 10   8  9 10 11 12 13 14", lines[23]);
-        assert_eq!(" 13 14 15 16 17 18 19  11 12 13 14 15 16 17  15 16 17 18 19 20 21", lines[24]);
-        assert_eq!(" 20 21 22 23 24 25 26  18 19 20 21 22 23 24  22 23 24 25 26 27 28", lines[25]);
-        assert_eq!(" 27 28 29 30           25 26 27 28 29 30 31  29 30               ", lines[26]);
-        assert_eq!("                                                                 ", lines[27]);
-        assert_eq!("                              1986                               ", lines[28]);// todo: doesn't belong here?
-        assert_eq!("        July                 August               September      ", lines[29]);
-        assert_eq!(" Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa", lines[30]);
-        assert_eq!("        1  2  3  4  5                  1  2      1  2  3  4  5  6", lines[31]);
-        assert_eq!("  6  7  8  9 10 11 12   3  4  5  6  7  8  9   7  8  9 10 11 12 13", lines[32]);
-        assert_eq!(" 13 14 15 16 17 18 19  10 11 12 13 14 15 16  14 15 16 17 18 19 20", lines[33]);
-        assert_eq!(" 20 21 22 23 24 25 26  17 18 19 20 21 22 23  21 22 23 24 25 26 27", lines[34]);
-        assert_eq!(" 27 28 29 30 31        24 25 26 27 28 29 30  28 29 30            ", lines[35]);
-        assert_eq!("                       31                                        ", lines[36]);
-        assert_eq!("                                                                 ", lines[37]);
-        assert_eq!("                              1986                               ", lines[38]);// todo: doesn't belong here?
-        assert_eq!("       October              November              December       ", lines[39]);
-        assert_eq!(" Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa", lines[40]);
-        assert_eq!("           1  2  3  4                     1      1  2  3  4  5  6", lines[41]);
-        assert_eq!("  5  6  7  8  9 10 11   2  3  4  5  6  7  8   7  8  9 10 11 12 13", lines[42]);
-        assert_eq!(" 12 13 14 15 16 17 18   9 10 11 12 13 14 15  14 15 16 17 18 19 20", lines[43]);
-        assert_eq!(" 19 20 21 22 23 24 25  16 17 18 19 20 21 22  21 22 23 24 25 26 27", lines[44]);
-        assert_eq!(" 26 27 28 29 30 31     23 24 25 26 27 28 29  28 29 30 31         ", lines[45]);
-        assert_eq!("                       30                                        ", lines[46]);
+        assert_eq!("        April                  May                  June         ", lines[19]);
+        assert_eq!(" Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa", lines[20]);
+        assert_eq!("        1  2  3  4  5               1  2  3   1  2  3  4  5  6  7", lines[21]);
+        assert_eq!("  6  7  8  9 10 11 12   4  5  6  7  8  9 10   8  9 10 11 12 13 14", lines[22]);
+        assert_eq!(" 13 14 15 16 17 18 19  11 12 13 14 15 16 17  15 16 17 18 19 20 21", lines[23]);
+        assert_eq!(" 20 21 22 23 24 25 26  18 19 20 21 22 23 24  22 23 24 25 26 27 28", lines[24]);
+        assert_eq!(" 27 28 29 30           25 26 27 28 29 30 31  29 30               ", lines[25]);
+        assert_eq!("                                                                 ", lines[26]);
+        assert_eq!("        July                 August               September      ", lines[27]);
+        assert_eq!(" Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa", lines[28]);
+        assert_eq!("        1  2  3  4  5                  1  2      1  2  3  4  5  6", lines[29]);
+        assert_eq!("  6  7  8  9 10 11 12   3  4  5  6  7  8  9   7  8  9 10 11 12 13", lines[30]);
+        assert_eq!(" 13 14 15 16 17 18 19  10 11 12 13 14 15 16  14 15 16 17 18 19 20", lines[31]);
+        assert_eq!(" 20 21 22 23 24 25 26  17 18 19 20 21 22 23  21 22 23 24 25 26 27", lines[32]);
+        assert_eq!(" 27 28 29 30 31        24 25 26 27 28 29 30  28 29 30            ", lines[33]);
+        assert_eq!("                       31                                        ", lines[34]);
+        assert_eq!("                                                                 ", lines[35]);
+        assert_eq!("       October              November              December       ", lines[36]);
+        assert_eq!(" Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa", lines[37]);
+        assert_eq!("           1  2  3  4                     1      1  2  3  4  5  6", lines[38]);
+        assert_eq!("  5  6  7  8  9 10 11   2  3  4  5  6  7  8   7  8  9 10 11 12 13", lines[39]);
+        assert_eq!(" 12 13 14 15 16 17 18   9 10 11 12 13 14 15  14 15 16 17 18 19 20", lines[40]);
+        assert_eq!(" 19 20 21 22 23 24 25  16 17 18 19 20 21 22  21 22 23 24 25 26 27", lines[41]);
+        assert_eq!(" 26 27 28 29 30 31     23 24 25 26 27 28 29  28 29 30 31         ", lines[42]);
+        assert_eq!("                       30                                        ", lines[43]);
     }
 
     #[test]
@@ -69,10 +68,10 @@ mod flag_tests {
 
         let result = cal(args, TodayFactory::Actual);
 
-        assert_eq!(true, result.is_ok());
+        assert!(result.is_ok());
         let lines = result.unwrap();
 
-        assert_eq!(16, lines.len());
+        assert_eq!(15, lines.len());
 
         assert_eq!("    November 1986         December 1986         January 1987     ", lines[0]);
         assert_eq!(" Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa", lines[1]);
@@ -83,13 +82,12 @@ mod flag_tests {
         assert_eq!(" 23 24 25 26 27 28 29  28 29 30 31           25 26 27 28 29 30 31", lines[6]);
         assert_eq!(" 30                                                              ", lines[7]);
         assert_eq!("                                                                 ", lines[8]);
-        assert_eq!("                                                                 ", lines[9]);// todo: year missing?
-        assert_eq!("      February                                                   ", lines[10]);
-        assert_eq!(" Su Mo Tu We Th Fr Sa                                            ", lines[11]);
-        assert_eq!("  1  2  3  4  5  6  7                                            ", lines[12]);
-        assert_eq!("  8  9 10 11 12 13 14                                            ", lines[13]);
-        assert_eq!(" 15 16 17 18 19 20 21                                            ", lines[14]);
-        assert_eq!(" 22 23 24 25 26 27 28                                            ", lines[15]);
+        assert_eq!("    February 1987                                                ", lines[9]);
+        assert_eq!(" Su Mo Tu We Th Fr Sa                                            ", lines[10]);
+        assert_eq!("  1  2  3  4  5  6  7                                            ", lines[11]);
+        assert_eq!("  8  9 10 11 12 13 14                                            ", lines[12]);
+        assert_eq!(" 15 16 17 18 19 20 21                                            ", lines[13]);
+        assert_eq!(" 22 23 24 25 26 27 28                                            ", lines[14]);
     }
 
     #[test]
@@ -98,10 +96,10 @@ mod flag_tests {
 
         let result = cal(args, TodayFactory::Actual);
 
-        assert_eq!(true, result.is_ok());
+        assert!(result.is_ok());
         let lines = result.unwrap();
 
-        assert_eq!(17, lines.len());
+        assert_eq!(16, lines.len());
 
         assert_eq!("    November 1987         December 1987         January 1988     ", lines[0]);
         assert_eq!(" Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa", lines[1]);
@@ -112,14 +110,13 @@ mod flag_tests {
         assert_eq!(" 29 30                 27 28 29 30 31        24 25 26 27 28 29 30", lines[6]);
         assert_eq!("                                             31                  ", lines[7]);
         assert_eq!("                                                                 ", lines[8]);
-        assert_eq!("                              1988                               ", lines[9]);// todo: this line shouldn't be here
-        assert_eq!("      February                March                              ", lines[10]);// todo: these should each have the year next to them
-        assert_eq!(" Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa                      ", lines[11]);
-        assert_eq!("     1  2  3  4  5  6         1  2  3  4  5                      ", lines[12]);
-        assert_eq!("  7  8  9 10 11 12 13   6  7  8  9 10 11 12                      ", lines[13]);
-        assert_eq!(" 14 15 16 17 18 19 20  13 14 15 16 17 18 19                      ", lines[14]);
-        assert_eq!(" 21 22 23 24 25 26 27  20 21 22 23 24 25 26                      ", lines[15]);
-        assert_eq!(" 28 29                 27 28 29 30 31                            ", lines[16]);
+        assert_eq!("    February 1988          March 1988                            ", lines[9]);
+        assert_eq!(" Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa                      ", lines[10]);
+        assert_eq!("     1  2  3  4  5  6         1  2  3  4  5                      ", lines[11]);
+        assert_eq!("  7  8  9 10 11 12 13   6  7  8  9 10 11 12                      ", lines[12]);
+        assert_eq!(" 14 15 16 17 18 19 20  13 14 15 16 17 18 19                      ", lines[13]);
+        assert_eq!(" 21 22 23 24 25 26 27  20 21 22 23 24 25 26                      ", lines[14]);
+        assert_eq!(" 28 29                 27 28 29 30 31                            ", lines[15]);
     }
 
     #[test]
@@ -128,10 +125,10 @@ mod flag_tests {
 
         let result = cal(args, TodayFactory::Actual);
 
-        assert_eq!(true, result.is_ok());
+        assert!(result.is_ok());
         let lines = result.unwrap();
 
-        assert_eq!(28, lines.len());
+        assert_eq!(26, lines.len());
 
         assert_eq!("                              2002                               ", lines[0]);
         assert_eq!("        March                 April                  May         ", lines[1]);
@@ -143,23 +140,211 @@ mod flag_tests {
         assert_eq!(" 24 25 26 27 28 29 30  28 29 30              26 27 28 29 30 31   ", lines[7]);
         assert_eq!(" 31                                                              ", lines[8]);
         assert_eq!("                                                                 ", lines[9]);
-        assert_eq!("                              2002                               ", lines[10]);
-        assert_eq!("        June                  July                 August        ", lines[11]);
-        assert_eq!(" Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa", lines[12]);
-        assert_eq!("                    1      1  2  3  4  5  6               1  2  3", lines[13]);
-        assert_eq!("  2  3  4  5  6  7  8   7  8  9 10 11 12 13   4  5  6  7  8  9 10", lines[14]);
-        assert_eq!("  9 10 11 12 13 14 15  14 15 16 17 18 19 20  11 12 13 14 15 16 17", lines[15]);
-        assert_eq!(" 16 17 18 19 20 21 22  21 22 23 24 25 26 27  18 19 20 21 22 23 24", lines[16]);
-        assert_eq!(" 23 24 25 26 27 28 29  28 29 30 31           25 26 27 28 29 30 31", lines[17]);
-        assert_eq!(" 30                                                              ", lines[18]);
-        assert_eq!("                                                                 ", lines[19]);
-        assert_eq!("                              2002                               ", lines[20]);
-        assert_eq!("      September              October                             ", lines[21]);
-        assert_eq!(" Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa                      ", lines[22]);
-        assert_eq!("  1  2  3  4  5  6  7         1  2  3  4  5                      ", lines[23]);
-        assert_eq!("  8  9 10 11 12 13 14   6  7  8  9 10 11 12                      ", lines[24]);
-        assert_eq!(" 15 16 17 18 19 20 21  13 14 15 16 17 18 19                      ", lines[25]);
-        assert_eq!(" 22 23 24 25 26 27 28  20 21 22 23 24 25 26                      ", lines[26]);
-        assert_eq!(" 29 30                 27 28 29 30 31                            ", lines[27]);
+        assert_eq!("        June                  July                 August        ", lines[10]);
+        assert_eq!(" Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa", lines[11]);
+        assert_eq!("                    1      1  2  3  4  5  6               1  2  3", lines[12]);
+        assert_eq!("  2  3  4  5  6  7  8   7  8  9 10 11 12 13   4  5  6  7  8  9 10", lines[13]);
+        assert_eq!("  9 10 11 12 13 14 15  14 15 16 17 18 19 20  11 12 13 14 15 16 17", lines[14]);
+        assert_eq!(" 16 17 18 19 20 21 22  21 22 23 24 25 26 27  18 19 20 21 22 23 24", lines[15]);
+        assert_eq!(" 23 24 25 26 27 28 29  28 29 30 31           25 26 27 28 29 30 31", lines[16]);
+        assert_eq!(" 30                                                              ", lines[17]);
+        assert_eq!("                                                                 ", lines[18]);
+        assert_eq!("      September              October                             ", lines[19]);
+        assert_eq!(" Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa                      ", lines[20]);
+        assert_eq!("  1  2  3  4  5  6  7         1  2  3  4  5                      ", lines[21]);
+        assert_eq!("  8  9 10 11 12 13 14   6  7  8  9 10 11 12                      ", lines[22]);
+        assert_eq!(" 15 16 17 18 19 20 21  13 14 15 16 17 18 19                      ", lines[23]);
+        assert_eq!(" 22 23 24 25 26 27 28  20 21 22 23 24 25 26                      ", lines[24]);
+        assert_eq!(" 29 30                 27 28 29 30 31                            ", lines[25]);
+    }
+
+    #[test]
+    fn test_year_with_four_columns() {
+        let args = "exe 2012 -c 4".split_whitespace().map(|s| s.to_string()).collect::<Vec<String>>();
+
+        let result = cal(args, TodayFactory::Actual);
+
+        assert!(result.is_ok());
+        let lines = result.unwrap();
+
+        assert_eq!(25, lines.len());
+
+        assert_eq!("                                         2012                                          ", lines[0]);
+        assert_eq!("       January              February                March                 April        ", lines[1]);
+        assert_eq!(" Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa", lines[2]);
+        assert_eq!("  1  2  3  4  5  6  7            1  2  3  4               1  2  3   1  2  3  4  5  6  7", lines[3]);
+        assert_eq!("  8  9 10 11 12 13 14   5  6  7  8  9 10 11   4  5  6  7  8  9 10   8  9 10 11 12 13 14", lines[4]);
+        assert_eq!(" 15 16 17 18 19 20 21  12 13 14 15 16 17 18  11 12 13 14 15 16 17  15 16 17 18 19 20 21", lines[5]);
+        assert_eq!(" 22 23 24 25 26 27 28  19 20 21 22 23 24 25  18 19 20 21 22 23 24  22 23 24 25 26 27 28", lines[6]);
+        assert_eq!(" 29 30 31              26 27 28 29           25 26 27 28 29 30 31  29 30               ", lines[7]);
+        assert_eq!("                                                                                       ", lines[8]);
+        assert_eq!("         May                  June                  July                 August        ", lines[9]);
+        assert_eq!(" Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa", lines[10]);
+        assert_eq!("        1  2  3  4  5                  1  2   1  2  3  4  5  6  7            1  2  3  4", lines[11]);
+        assert_eq!("  6  7  8  9 10 11 12   3  4  5  6  7  8  9   8  9 10 11 12 13 14   5  6  7  8  9 10 11", lines[12]);
+        assert_eq!(" 13 14 15 16 17 18 19  10 11 12 13 14 15 16  15 16 17 18 19 20 21  12 13 14 15 16 17 18", lines[13]);
+        assert_eq!(" 20 21 22 23 24 25 26  17 18 19 20 21 22 23  22 23 24 25 26 27 28  19 20 21 22 23 24 25", lines[14]);
+        assert_eq!(" 27 28 29 30 31        24 25 26 27 28 29 30  29 30 31              26 27 28 29 30 31   ", lines[15]);
+        assert_eq!("                                                                                       ", lines[16]);
+        assert_eq!("      September              October              November              December       ", lines[17]);
+        assert_eq!(" Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa", lines[18]);
+        assert_eq!("                    1      1  2  3  4  5  6               1  2  3                     1", lines[19]);
+        assert_eq!("  2  3  4  5  6  7  8   7  8  9 10 11 12 13   4  5  6  7  8  9 10   2  3  4  5  6  7  8", lines[20]);
+        assert_eq!("  9 10 11 12 13 14 15  14 15 16 17 18 19 20  11 12 13 14 15 16 17   9 10 11 12 13 14 15", lines[21]);
+        assert_eq!(" 16 17 18 19 20 21 22  21 22 23 24 25 26 27  18 19 20 21 22 23 24  16 17 18 19 20 21 22", lines[22]);
+        assert_eq!(" 23 24 25 26 27 28 29  28 29 30 31           25 26 27 28 29 30     23 24 25 26 27 28 29", lines[23]);
+        assert_eq!(" 30                                                                30 31               ", lines[24]);
+    }
+
+    #[test]
+    fn test_month_year_before_with_long_columns() {
+        let args = "exe jan 2024 -B 1 --columns=1".split_whitespace().map(|s| s.to_string()).collect::<Vec<String>>();
+
+        let result = cal(args, TodayFactory::Actual);
+
+        assert!(result.is_ok());
+        let lines = result.unwrap();
+
+        assert_eq!(18, lines.len());
+
+        assert_eq!("        2023         ", lines[0]);
+        assert_eq!("      December       ", lines[1]);
+        assert_eq!(" Su Mo Tu We Th Fr Sa", lines[2]);
+        assert_eq!(" 31                  ", lines[8]);
+        assert_eq!("                     ", lines[9]);
+        assert_eq!("        2024         ", lines[10]);
+        assert_eq!("       January       ", lines[11]);
+        assert_eq!(" Su Mo Tu We Th Fr Sa", lines[12]);
+    }
+
+    #[test]
+    fn test_numeric_month_and_year() {
+        let args = "exe 3 2024".split_whitespace().map(|s| s.to_string()).collect::<Vec<String>>();
+
+        let result = cal(args, TodayFactory::Actual);
+
+        assert!(result.is_ok());
+        let lines = result.unwrap();
+
+        assert_eq!(8, lines.len());
+
+        assert_eq!("     March 2024                                                  ", lines[0]);
+        assert_eq!(" Su Mo Tu We Th Fr Sa                                            ", lines[1]);
+        assert_eq!("                 1  2                                            ", lines[2]);
+        assert_eq!(" 31                                                              ", lines[7]);
+    }
+
+    #[test]
+    fn test_month_flag_without_year() {
+        let cases = [
+            ("exe -m 3", "     March 2024      "),
+            ("exe -m sept", "   September 2024    "),
+            ("exe -m 4f", "     April 2025      "),
+            ("exe -m 8f", "     August 2024     "),
+            ("exe -m 6p", "      June 2023      "),
+            ("exe -m 3p", "     March 2024      "),
+        ];
+
+        for (input, expected) in cases {
+            let args = input.split_whitespace().map(|s| s.to_string()).collect::<Vec<String>>();
+
+            let result = cal(args, TodayFactory::Other { y: 2024, m: 6 });
+
+            assert!(result.is_ok(), "input: [{}]", input);
+            assert_eq!(expected, &result.unwrap()[0][..21], "input: [{}]", input);
+        }
+    }
+
+    #[test]
+    fn test_three_months_around_month() {
+        let args = "exe -3 -m jun -y 2024".split_whitespace().map(|s| s.to_string()).collect::<Vec<String>>();
+
+        let result = cal(args, TodayFactory::Actual);
+
+        assert!(result.is_ok());
+        let lines = result.unwrap();
+
+        assert_eq!(9, lines.len());
+
+        assert_eq!("                              2024                               ", lines[0]);
+        assert_eq!("         May                  June                  July         ", lines[1]);
+        assert_eq!(" Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa", lines[2]);
+        assert_eq!("           1  2  3  4                     1      1  2  3  4  5  6", lines[3]);
+        assert_eq!("  5  6  7  8  9 10 11   2  3  4  5  6  7  8   7  8  9 10 11 12 13", lines[4]);
+        assert_eq!(" 12 13 14 15 16 17 18   9 10 11 12 13 14 15  14 15 16 17 18 19 20", lines[5]);
+        assert_eq!(" 19 20 21 22 23 24 25  16 17 18 19 20 21 22  21 22 23 24 25 26 27", lines[6]);
+        assert_eq!(" 26 27 28 29 30 31     23 24 25 26 27 28 29  28 29 30 31         ", lines[7]);
+        assert_eq!("                       30                                        ", lines[8]);
+    }
+
+    #[test]
+    fn test_span_across_years() {
+        let args = "exe --span -n 3 -m jan -y 2024".split_whitespace().map(|s| s.to_string()).collect::<Vec<String>>();
+
+        let result = cal(args, TodayFactory::Actual);
+
+        assert!(result.is_ok());
+        let lines = result.unwrap();
+
+        assert_eq!("    December 2023         January 2024          February 2024    ", lines[0]);
+    }
+
+    #[test]
+    fn test_one_month_today() {
+        let args = "exe -1".split_whitespace().map(|s| s.to_string()).collect::<Vec<String>>();
+
+        let result = cal(args, TodayFactory::Other { y: 2024, m: 6 });
+
+        assert!(result.is_ok());
+        let lines = result.unwrap();
+
+        assert_eq!("      June 2024                                                  ", lines[0]);
+    }
+
+    #[test]
+    fn test_weeks_start_monday() {
+        let args = "exe -M sep 2024".split_whitespace().map(|s| s.to_string()).collect::<Vec<String>>();
+
+        let lines = cal(args, TodayFactory::Actual).unwrap();
+
+        assert_eq!(" Mo Tu We Th Fr Sa Su                                            ", lines[1]);
+        assert_eq!("                    1                                            ", lines[2]);
+        assert_eq!(" 30                                                              ", lines[7]);
+    }
+
+    #[test]
+    fn test_custom_today() {
+        struct Halloween {}
+        impl Today for Halloween {
+            fn make_today(&self) -> Result<Month, KnownError> {
+                Month::new(10, 1999)
+            }
+        }
+        let args = "exe".split_whitespace().map(|s| s.to_string()).collect::<Vec<String>>();
+
+        let lines = cal(args, TodayFactory::Custom(Box::new(Halloween {}))).unwrap();
+
+        assert_eq!("    October 1999                                                 ", lines[0]);
+    }
+
+    #[test]
+    fn test_current_date_month() {
+        let args = "exe -3 -d 2012-11".split_whitespace().map(|s| s.to_string()).collect::<Vec<String>>();
+
+        let lines = cal(args, TodayFactory::Actual).unwrap();
+
+        assert_eq!("                              2012                               ", lines[0]);
+        assert_eq!("       October              November              December       ", lines[1]);
+    }
+
+    #[test]
+    fn test_current_date_day() {
+        let args = "exe --format=json -d 2024-02-14".split_whitespace().map(|s| s.to_string()).collect::<Vec<String>>();
+
+        let lines = cal(args, TodayFactory::Other { y: 1999, m: 1 }).unwrap();
+
+        assert_eq!("      \"month\": 2,", lines[4]);
+        assert!(lines[20].contains("\"date\": \"2024-02-14\""));
+        assert!(lines[20].contains("\"highlighted\": true"));
     }
 }
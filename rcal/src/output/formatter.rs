@@ -1,15 +1,16 @@
-use crate::output::formatter::Position::{Center, Left, Right};
+use crate::output::grid::{GridDay, MonthGrid};
 use crate::state::app_state::ApplicationState;
 use crate::state::chunk::{Chunk, YearMode};
-use crate::time::days::calc_days_in_month;
-use crate::time::month::Month;
-use crate::time::name::month_display_name;
-use chrono::{Datelike, NaiveDate};
+use crate::time::locale::Locale;
+use chrono::Datelike;
 use std::string::ToString;
 use string_builder::Builder;
 use common::input::known_error::KnownError;
+use unicode_width::UnicodeWidthStr;
 
 const BLANK_ROW: &str = "                     ";
+const MONTH_SEPARATOR: &str = " ";
+const EVENT_MARKER: char = '*';
 
 pub fn format_calendar(errors: &[KnownError], app_state: ApplicationState) -> Vec<String> {
     let mut lines = vec![];
@@ -17,7 +18,7 @@ pub fn format_calendar(errors: &[KnownError], app_state: ApplicationState) -> Ve
     if errors.is_empty() {
         app_state.chunks
             .iter()
-            .for_each(|chunk| format_chunk(chunk)
+            .for_each(|chunk| format_chunk(chunk, &app_state)
                 .iter()
                 .for_each(|line| lines.push(line.to_owned())));
     }
@@ -25,126 +26,139 @@ pub fn format_calendar(errors: &[KnownError], app_state: ApplicationState) -> Ve
     lines
 }
 
-enum Position {
-    Left,
-    Center,
-    Right,
-}
+fn format_chunk(chunk: &Chunk, app_state: &ApplicationState) -> Vec<String> {
+    /* the row is always as wide as the configured number of months, even if this chunk has fewer */
+    let columns = app_state.months_per_row.max(chunk.months.len());
+    let row_width = BLANK_ROW.len() * columns + MONTH_SEPARATOR.len() * (columns - 1);
 
-fn format_chunk(chunk: &Chunk) -> Vec<String> {
-    /* prepare output */
-    let mut output = vec![];
+    /* prepare output; every chunk is preceded by a blank row */
+    let mut output = vec![" ".repeat(row_width)];
 
-    /* grab each of the 3 months, which may be an empty vector */
-    let mut left = format_month(&chunk.left, chunk, Left);
-    let mut center = match &chunk.center {
-        Some(x) => format_month(x, chunk, Center),
-        None => vec![]
-    };
-    let mut right = match &chunk.right {
-        Some(x) => format_month(x, chunk, Right),
-        None => vec![]
-    };
+    /* if year config is own-line, add the year, centered across the whole row */
+    if matches!(chunk.year_mode, YearMode::OwnLine) {
+        output.push(format!("{:^width$}", chunk.months[0].year, width = row_width));
+    }
 
-    /* figure out which one has the most lines */
-    let largest = find_largest(&left, &center, &right);
+    /* format each month; pad with empty months up to the configured row size */
+    let grids: Vec<MonthGrid> = chunk.months
+        .iter()
+        .map(|month| MonthGrid::new(month, app_state))
+        .collect();
+    let mut months: Vec<Vec<String>> = grids
+        .iter()
+        .map(|grid| format_month(grid, chunk))
+        .collect();
+    months.resize_with(columns, Vec::new);
 
-    /* potentially expand each to match the month with the most lines */
-    extend(&mut left, largest);
-    extend(&mut center, largest);
-    extend(&mut right, largest);
+    /* figure out which one has the most lines, and potentially expand each to match */
+    let largest = months.iter().map(|m| m.len()).max().unwrap_or(0);
+    months.iter_mut().for_each(|m| extend(m, largest));
 
-    /* combine each line of each of the 3 results into a single line, added to a single result */
+    /* combine each line of each of the results into a single line, added to a single result */
     for idx in 0..largest {
-        let x = left[idx].to_owned();
-        let y = center[idx].to_owned();
-        let z = right[idx].to_owned();
-        output.push(format!("{} {} {}", x, y, z));
+        let line: Vec<&str> = months.iter().map(|m| m[idx].as_str()).collect();
+        output.push(line.join(MONTH_SEPARATOR));
     }
 
+    /* list the events in this chunk's months below it */
+    output.extend(format_agenda(&grids, app_state.locale, row_width));
+
     /* done */
     output
 }
 
-fn format_month(month: &Month, chunk: &Chunk, position: Position) -> Vec<String> {
-    /* create initial variables */
-    let first_day: i32 = (NaiveDate::from_ymd_opt(month.year as i32, month.month as u32, 1).unwrap().weekday().num_days_from_sunday() + 1) as i32;
-    let mut next_index: i32 = 2 - first_day;
-    let max: i32 = calc_days_in_month(month.month as u32, month.year as i32) as i32;
-    let month_name = month_display_name(month, matches!(chunk.year_mode, YearMode::WithMonth));
-
-    /* create output lines var, and add static lines to it */
+fn format_month(grid: &MonthGrid, chunk: &Chunk) -> Vec<String> {
+    /* create output lines var, and add month name and days of week */
     let mut lines = vec![];
-    lines.push(BLANK_ROW.to_string());
-
-    /* if we're on the center node and year config is own-line, add the year, centered */
-    if matches!(&chunk.year_mode, YearMode::OwnLine) {
-        if matches!(position, Center) {
-            lines.push(format!("{:^21}", month.year))
-        } else {
-            lines.push(BLANK_ROW.to_string());
-        }
-    }
+    let month_name = match chunk.year_mode {
+        YearMode::WithMonth => format!("{} {}", grid.name, grid.month.year),
+        _ => grid.name.clone(),
+    };
+    lines.push(center(&month_name, BLANK_ROW.len()));
+    lines.push(grid.weekdays.iter().map(|day| format!(" {}", pad_left(day, 2))).collect());
 
-    /* add month name and days of week */
-    lines.push(format!("{:^21}", month_name));
-    lines.push(" Su Mo Tu We Th Fr Sa".to_string());
-
-    /* iterate over configs and format */
-    let mut prev_row_max: i32 = 0;
-    for _line_number in 1..=7 {
-        if prev_row_max <= max {
-            let mut line_builder = Builder::default();
-            for _column_number in 0..7 {
-                line_builder.append(format!(" {:>2}", format_day(next_index, max)));
-                next_index += 1;
-            }
-            prev_row_max = next_index;
-            lines.push(line_builder.string().unwrap().clone());
+    /* one line per week */
+    for week in &grid.weeks {
+        let mut line_builder = Builder::default();
+        for day in week {
+            line_builder.append(format!("{:>3}", format_day(day.as_ref())));
         }
+        lines.push(line_builder.string().unwrap().clone());
     }
 
     /* done */
     lines
 }
 
-fn format_day(day: i32, max_days: i32) -> String {
-    if day > max_days || day <= 0 {
-        "  ".to_string()
-    } else {
-        format!("{:>2}", day)
+fn format_day(day: Option<&GridDay>) -> String {
+    match day {
+        None => "  ".to_string(),
+        /* the marker takes the place of the space separating this day from the one before */
+        Some(day) if !day.events.is_empty() => format!("{}{}", EVENT_MARKER, day.date.day()),
+        Some(day) => format!("{:>2}", day.date.day()),
     }
 }
 
+fn format_agenda(grids: &[MonthGrid], locale: &Locale, row_width: usize) -> Vec<String> {
+    grids.iter()
+        .flat_map(|grid| grid.weeks.iter().flatten().flatten())
+        .flat_map(|day| day.events.iter().map(move |event| (day.date, event)))
+        .map(|(date, event)| format!("{} {:>2}  {}", locale.abbreviations[date.month0() as usize], date.day(), event))
+        .map(|line| pad_right(line.trim_end(), row_width))
+        .collect()
+}
+
+///
+/// Center text by how wide it displays, rather than by how many characters it has; accented
+/// letters may be several characters, and CJK characters take two columns.
+///
+fn center(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(text.width());
+    let left = padding / 2;
+    format!("{}{}{}", " ".repeat(left), text, " ".repeat(padding - left))
+}
+
+fn pad_left(text: &str, width: usize) -> String {
+    format!("{}{}", " ".repeat(width.saturating_sub(text.width())), text)
+}
+
+fn pad_right(text: &str, width: usize) -> String {
+    format!("{}{}", text, " ".repeat(width.saturating_sub(text.width())))
+}
+
 fn extend(vector: &mut Vec<String>, target_len: usize) {
     while vector.len() != target_len {
         vector.push(BLANK_ROW.to_string());
     }
 }
 
-fn find_largest(left: &[String], center: &[String], right: &[String]) -> usize {
-    [left.len(), center.len(), right.len()].iter().max().unwrap().to_owned()
-}
-
 #[cfg(test)]
 mod test {
+    use crate::calendar::WeekStart;
     use crate::output::formatter::format_calendar;
     use crate::state::app_state::ApplicationState;
     use crate::state::chunk::Chunk;
-    use crate::state::chunk::YearMode::{OwnLine, WithMonth};
+    use crate::state::chunk::YearMode::{NoDisplay, OwnLine, WithMonth};
+    use crate::time::events::parse_events;
+    use crate::time::locale::{Locale, ENGLISH};
     use crate::time::month::Month;
 
     #[test]
     fn test_one_month() {
         let app_config = ApplicationState {
             chunks: vec!(
-                Chunk::one(Month::new(1, 2024), WithMonth)
-            )
+                Chunk::new(vec![Month::new(1, 2024).unwrap()], WithMonth)
+            ),
+            months_per_row: 3,
+            locale: &ENGLISH,
+            highlight: None,
+            events: vec![],
+            week_start: WeekStart::Sunday,
         };
-        let result = format_calendar(&vec!(), app_config);
+        let result = format_calendar(&[], app_config);
 
         assert_eq!(8, result.len());
-        assert_eq!("                                                                 ", result.get(0).unwrap());
+        assert_eq!("                                                                 ", result.first().unwrap());
         assert_eq!("    January 2024                                                 ", result.get(1).unwrap());
         assert_eq!(" Su Mo Tu We Th Fr Sa                                            ", result.get(2).unwrap());
         assert_eq!("     1  2  3  4  5  6                                            ", result.get(3).unwrap());
@@ -158,16 +172,21 @@ mod test {
     fn test_two_months() {
         let app_config = ApplicationState {
             chunks: vec!(
-                Chunk::two(
-                    Month::new(2, 2024),
-                    Month::new(3, 2024),
+                Chunk::new(vec![
+                    Month::new(2, 2024).unwrap(),
+                    Month::new(3, 2024).unwrap()],
                     WithMonth)
-            )
+            ),
+            months_per_row: 3,
+            locale: &ENGLISH,
+            highlight: None,
+            events: vec![],
+            week_start: WeekStart::Sunday,
         };
-        let result = format_calendar(&vec!(), app_config);
+        let result = format_calendar(&[], app_config);
 
         assert_eq!(9, result.len());
-        assert_eq!("                                                                 ", result.get(0).unwrap());
+        assert_eq!("                                                                 ", result.first().unwrap());
         assert_eq!("    February 2024          March 2024                            ", result.get(1).unwrap());
         assert_eq!(" Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa                      ", result.get(2).unwrap());
         assert_eq!("              1  2  3                  1  2                      ", result.get(3).unwrap());
@@ -182,17 +201,22 @@ mod test {
     fn test_three_months() {
         let app_config = ApplicationState {
             chunks: vec!(
-                Chunk::three(
-                    Month::new(4, 2024),
-                    Month::new(5, 2024),
-                    Month::new(6, 2024),
+                Chunk::new(vec![
+                    Month::new(4, 2024).unwrap(),
+                    Month::new(5, 2024).unwrap(),
+                    Month::new(6, 2024).unwrap()],
                     OwnLine)
-            )
+            ),
+            months_per_row: 3,
+            locale: &ENGLISH,
+            highlight: None,
+            events: vec![],
+            week_start: WeekStart::Sunday,
         };
-        let result = format_calendar(&vec!(), app_config);
+        let result = format_calendar(&[], app_config);
 
         assert_eq!(10, result.len());
-        assert_eq!("                                                                 ", result.get(0).unwrap());
+        assert_eq!("                                                                 ", result.first().unwrap());
         assert_eq!("                              2024                               ", result.get(1).unwrap());
         assert_eq!("        April                  May                  June         ", result.get(2).unwrap());
         assert_eq!(" Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa", result.get(3).unwrap());
@@ -208,18 +232,23 @@ mod test {
     fn test_four_months() {
         let app_config = ApplicationState {
             chunks: vec!(
-                Chunk::three(
-                    Month::new(7, 2024),
-                    Month::new(8, 2024),
-                    Month::new(9, 2024),
+                Chunk::new(vec![
+                    Month::new(7, 2024).unwrap(),
+                    Month::new(8, 2024).unwrap(),
+                    Month::new(9, 2024).unwrap()],
                     OwnLine),
-                Chunk::one(Month::new(10, 2024), WithMonth)
-            )
+                Chunk::new(vec![Month::new(10, 2024).unwrap()], NoDisplay)
+            ),
+            months_per_row: 3,
+            locale: &ENGLISH,
+            highlight: None,
+            events: vec![],
+            week_start: WeekStart::Sunday,
         };
-        let result = format_calendar(&vec!(), app_config);
+        let result = format_calendar(&[], app_config);
 
         assert_eq!(17, result.len());
-        assert_eq!("                                                                 ", result.get(0).unwrap());
+        assert_eq!("                                                                 ", result.first().unwrap());
         assert_eq!("                              2024                               ", result.get(1).unwrap());
         assert_eq!("        July                 August               September      ", result.get(2).unwrap());
         assert_eq!(" Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa", result.get(3).unwrap());
@@ -229,7 +258,7 @@ mod test {
         assert_eq!(" 21 22 23 24 25 26 27  18 19 20 21 22 23 24  22 23 24 25 26 27 28", result.get(7).unwrap());
         assert_eq!(" 28 29 30 31           25 26 27 28 29 30 31  29 30               ", result.get(8).unwrap());
         assert_eq!("                                                                 ", result.get(9).unwrap());
-        assert_eq!("    October 2024                                                 ", result.get(10).unwrap());// todo: 2024 shouldn't be on this line
+        assert_eq!("       October                                                   ", result.get(10).unwrap());
         assert_eq!(" Su Mo Tu We Th Fr Sa                                            ", result.get(11).unwrap());
         assert_eq!("        1  2  3  4  5                                            ", result.get(12).unwrap());
         assert_eq!("  6  7  8  9 10 11 12                                            ", result.get(13).unwrap());
@@ -237,4 +266,129 @@ mod test {
         assert_eq!(" 20 21 22 23 24 25 26                                            ", result.get(15).unwrap());
         assert_eq!(" 27 28 29 30 31                                                  ", result.get(16).unwrap());
     }
+
+    #[test]
+    fn test_four_months_per_row() {
+        let app_config = ApplicationState {
+            chunks: vec!(
+                Chunk::new(vec![
+                    Month::new(1, 2024).unwrap(),
+                    Month::new(2, 2024).unwrap(),
+                    Month::new(3, 2024).unwrap(),
+                    Month::new(4, 2024).unwrap()],
+                    OwnLine),
+                Chunk::new(vec![Month::new(5, 2024).unwrap()], NoDisplay)
+            ),
+            months_per_row: 4,
+            locale: &ENGLISH,
+            highlight: None,
+            events: vec![],
+            week_start: WeekStart::Sunday,
+        };
+        let result = format_calendar(&[], app_config);
+
+        assert_eq!(18, result.len());
+        assert_eq!("                                                                                       ", result.first().unwrap());
+        assert_eq!("                                         2024                                          ", result.get(1).unwrap());
+        assert_eq!("       January              February                March                 April        ", result.get(2).unwrap());
+        assert_eq!(" Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa", result.get(3).unwrap());
+        assert_eq!("     1  2  3  4  5  6               1  2  3                  1  2      1  2  3  4  5  6", result.get(4).unwrap());
+        assert_eq!("                                                                                       ", result.get(10).unwrap());
+        assert_eq!("         May                                                                           ", result.get(11).unwrap());
+        assert_eq!(" Su Mo Tu We Th Fr Sa                                                                  ", result.get(12).unwrap());
+        assert_eq!("           1  2  3  4                                                                  ", result.get(13).unwrap());
+    }
+
+    #[test]
+    fn test_one_month_per_row() {
+        let app_config = ApplicationState {
+            chunks: vec!(
+                Chunk::new(vec![Month::new(12, 2024).unwrap()], OwnLine),
+            ),
+            months_per_row: 1,
+            locale: &ENGLISH,
+            highlight: None,
+            events: vec![],
+            week_start: WeekStart::Sunday,
+        };
+        let result = format_calendar(&[], app_config);
+
+        assert_eq!(9, result.len());
+        assert_eq!("                     ", result.first().unwrap());
+        assert_eq!("        2024         ", result.get(1).unwrap());
+        assert_eq!("      December       ", result.get(2).unwrap());
+        assert_eq!(" Su Mo Tu We Th Fr Sa", result.get(3).unwrap());
+        assert_eq!("  1  2  3  4  5  6  7", result.get(4).unwrap());
+        assert_eq!(" 29 30 31            ", result.get(8).unwrap());
+    }
+
+    #[test]
+    fn test_localized_names() {
+        let app_config = ApplicationState {
+            chunks: vec!(
+                Chunk::new(vec![
+                    Month::new(3, 2024).unwrap(),
+                    Month::new(12, 2024).unwrap()],
+                    NoDisplay),
+            ),
+            months_per_row: 2,
+            locale: Locale::find("de_DE.UTF-8"),
+            highlight: None,
+            events: vec![],
+            week_start: WeekStart::Sunday,
+        };
+        let result = format_calendar(&[], app_config);
+
+        assert_eq!("        März                Dezember       ", result.get(1).unwrap());
+        assert_eq!(" So Mo Di Mi Do Fr Sa  So Mo Di Mi Do Fr Sa", result.get(2).unwrap());
+    }
+
+    #[test]
+    fn test_wide_names() {
+        let app_config = ApplicationState {
+            chunks: vec!(
+                Chunk::new(vec![
+                    Month::new(11, 2024).unwrap(),
+                    Month::new(12, 2024).unwrap()],
+                    WithMonth),
+            ),
+            months_per_row: 2,
+            locale: Locale::find("zh_CN.UTF-8"),
+            highlight: None,
+            events: vec![],
+            week_start: WeekStart::Sunday,
+        };
+        let result = format_calendar(&[], app_config);
+
+        /* each CJK character takes two columns, so the month blocks still line up */
+        assert_eq!("     十一月 2024           十二月 2024     ", result.get(1).unwrap());
+        assert_eq!(" 日 一 二 三 四 五 六  日 一 二 三 四 五 六", result.get(2).unwrap());
+        assert_eq!("                 1  2   1  2  3  4  5  6  7", result.get(3).unwrap());
+    }
+
+    #[test]
+    fn test_events() {
+        let events = parse_events("test", "*/1\tfirst\nFriday\tpizza\nEaster+1\tEaster Monday", &ENGLISH).unwrap();
+        let app_config = ApplicationState {
+            chunks: vec!(
+                Chunk::new(vec![Month::new(4, 2024).unwrap()], WithMonth),
+            ),
+            months_per_row: 1,
+            locale: &ENGLISH,
+            highlight: None,
+            events,
+            week_start: WeekStart::Sunday,
+        };
+        let result = format_calendar(&[], app_config);
+
+        assert_eq!(14, result.len());
+        assert_eq!("     April 2024      ", result.get(1).unwrap());
+        assert_eq!("    *1  2  3  4 *5  6", result.get(3).unwrap());
+        assert_eq!("  7  8  9 10 11*12 13", result.get(4).unwrap());
+        assert_eq!(" 28 29 30            ", result.get(7).unwrap());
+        assert_eq!("Apr  1  first        ", result.get(8).unwrap());
+        assert_eq!("Apr  1  Easter Monday", result.get(9).unwrap());
+        assert_eq!("Apr  5  pizza        ", result.get(10).unwrap());
+        assert_eq!("Apr 26  pizza        ", result.get(13).unwrap());
+    }
 }
@@ -0,0 +1,72 @@
+use crate::calendar::WeekStart;
+use crate::state::app_state::ApplicationState;
+use crate::time::days::calc_days_in_month;
+use crate::time::holiday::holidays_in;
+use crate::time::month::Month;
+use crate::time::name::month_display_name;
+use chrono::{Datelike, NaiveDate};
+
+///
+/// A single day in a [MonthGrid].
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridDay {
+    pub date: NaiveDate,
+    pub highlighted: bool,
+    pub holidays: Vec<&'static str>,
+    pub events: Vec<String>,
+}
+
+///
+/// A month laid out as weeks; days outside the month are [None].
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonthGrid {
+    pub month: Month,
+    pub name: String,
+    pub weekdays: Vec<&'static str>,
+    pub weeks: Vec<[Option<GridDay>; 7]>,
+}
+
+impl MonthGrid {
+    pub(crate) fn new(month: &Month, app_state: &ApplicationState) -> MonthGrid {
+        /* rotate the weekdays so the configured first day leads */
+        let first_weekday = match app_state.week_start {
+            WeekStart::Sunday => 0,
+            WeekStart::Monday => 1,
+        };
+        let mut weekdays = app_state.locale.weekdays.to_vec();
+        weekdays.rotate_left(first_weekday);
+
+        /* blank cells before the first, then every day in the month */
+        let first = NaiveDate::from_ymd_opt(month.year.value(), month.month as u32, 1).unwrap();
+        let leading = (first.weekday().num_days_from_sunday() as usize + 7 - first_weekday) % 7;
+        let holidays = holidays_in(month);
+        let mut cells: Vec<Option<GridDay>> = vec![None; leading];
+        for day in 1..=calc_days_in_month(month.month as u32, month.year.value()) as u32 {
+            let date = first.with_day(day).unwrap();
+            cells.push(Some(GridDay {
+                date,
+                highlighted: app_state.highlight == Some(date),
+                holidays: holidays.iter().filter(|h| h.date == date).map(|h| h.name).collect(),
+                events: app_state.events.iter()
+                    .filter(|event| event.recurrence.matches(date))
+                    .map(|event| event.description.clone())
+                    .collect(),
+            }));
+        }
+
+        /* break into weeks, filling out the last one */
+        cells.resize(cells.len().div_ceil(7) * 7, None);
+        let weeks = cells.chunks(7)
+            .map(|week| std::array::from_fn(|index| week[index].clone()))
+            .collect();
+
+        MonthGrid {
+            month: *month,
+            name: month_display_name(month, false, app_state.locale),
+            weekdays,
+            weeks,
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use crate::state::app_state::ApplicationState;
+use crate::time::holiday::holidays_in;
+use chrono::{Datelike, NaiveDate};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+const HIGHLIGHT_SUMMARY: &str = "Today";
+
+///
+/// Write the highlighted date and any holidays in the months on display as an iCalendar (RFC 5545) document,
+/// one all-day event per date.
+///
+/// `stamp` is the date the document is created on; calendars require it on every event.  Dates before
+/// year 1 are left out, as iCalendar years have exactly four digits.
+///
+pub fn format_ics(app_state: &ApplicationState, stamp: NaiveDate) -> Vec<String> {
+    /* collect events first; the highlighted date sorts ahead of any holiday on the same day */
+    let mut events: Vec<(NaiveDate, &str)> = vec![];
+    for month in app_state.months() {
+        if let Some(highlight) = app_state.highlight.filter(|date| date.year() == month.year.value() && date.month() == month.month as u32) {
+            events.push((highlight, HIGHLIGHT_SUMMARY));
+        }
+        holidays_in(month).iter().for_each(|holiday| events.push((holiday.date, holiday.name)));
+    }
+    events.retain(|(date, _)| date.year() >= 1);
+    events.sort_by_key(|(date, _)| *date);
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:-//rs_coreutils//rcal {}//EN", VERSION),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    for (date, summary) in events {
+        lines.extend(format_event(date, summary, stamp));
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    /* iCalendar lines end in CRLF; the LF is added when printing */
+    lines.iter().map(|line| format!("{}\r", line)).collect()
+}
+
+fn format_event(date: NaiveDate, summary: &str, stamp: NaiveDate) -> Vec<String> {
+    let end = date.succ_opt().unwrap_or(date);
+    vec![
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}-{}@rcal", date.format("%Y%m%d"), summary.to_lowercase().replace(' ', "-")),
+        format!("DTSTAMP:{}T000000Z", stamp.format("%Y%m%d")),
+        format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")),
+        format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")),
+        format!("SUMMARY:{}", escape(summary)),
+        "TRANSP:TRANSPARENT".to_string(),
+        "END:VEVENT".to_string(),
+    ]
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::output::ics::escape;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!("Easter", escape("Easter"));
+        assert_eq!("a\\, b\\; c\\\\d\\n", escape("a, b; c\\d\n"));
+    }
+}
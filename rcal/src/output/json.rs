@@ -0,0 +1,108 @@
+use crate::state::app_state::ApplicationState;
+use crate::time::days::calc_days_in_month;
+use crate::time::holiday::{holidays_in, Holiday};
+use crate::time::month::Month;
+use crate::time::name::month_display_name;
+use chrono::{Datelike, NaiveDate, Weekday};
+
+const INDENT: &str = "  ";
+
+///
+/// Write the months on display as a json document; one line per day, so the output stays diffable.
+///
+/// Each day carries its weekday, ISO week number and julian day (the day of the year, as `cal -j` numbers them).
+///
+pub fn format_json(app_state: &ApplicationState) -> Vec<String> {
+    let months: Vec<&Month> = app_state.months().collect();
+
+    let mut lines = vec!["{".to_string(), format!("{}\"months\": [", INDENT)];
+    for (index, month) in months.iter().enumerate() {
+        let separator = if index + 1 < months.len() { "," } else { "" };
+        lines.extend(format_month(month, app_state, separator));
+    }
+    lines.push(format!("{}]", INDENT));
+    lines.push("}".to_string());
+
+    lines
+}
+
+fn format_month(month: &Month, app_state: &ApplicationState, separator: &str) -> Vec<String> {
+    let indent = INDENT.repeat(2);
+    let holidays = holidays_in(month);
+    let days_in_month = calc_days_in_month(month.month as u32, month.year.value()) as u32;
+
+    let mut lines = vec![
+        format!("{}{{", indent),
+        format!("{}{}\"year\": {},", indent, INDENT, month.year),
+        format!("{}{}\"month\": {},", indent, INDENT, month.month),
+        format!("{}{}\"name\": {},", indent, INDENT, quote(&month_display_name(month, false, app_state.locale))),
+        format!("{}{}\"days\": [", indent, INDENT),
+    ];
+    for day in 1..=days_in_month {
+        let date = NaiveDate::from_ymd_opt(month.year.value(), month.month as u32, day).unwrap();
+        let comma = if day < days_in_month { "," } else { "" };
+        lines.push(format!("{}{}{}{}", indent, INDENT, INDENT, format_day(date, app_state.highlight, &holidays)) + comma);
+    }
+    lines.push(format!("{}{}]", indent, INDENT));
+    lines.push(format!("{}}}{}", indent, separator));
+
+    lines
+}
+
+fn format_day(date: NaiveDate, highlight: Option<NaiveDate>, holidays: &[Holiday]) -> String {
+    let names: Vec<String> = holidays.iter()
+        .filter(|holiday| holiday.date == date)
+        .map(|holiday| quote(holiday.name))
+        .collect();
+
+    format!(
+        "{{ \"date\": \"{}\", \"day\": {}, \"weekday\": \"{}\", \"week\": {}, \"julian_day\": {}, \"highlighted\": {}, \"holidays\": [{}] }}",
+        date.format("%Y-%m-%d"),
+        date.day(),
+        weekday_name(date.weekday()),
+        date.iso_week().week(),
+        date.ordinal(),
+        highlight == Some(date),
+        names.join(", "),
+    )
+}
+
+fn weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+}
+
+fn quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for ch in text.chars() {
+        match ch {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::output::json::quote;
+
+    #[test]
+    fn test_quote() {
+        assert_eq!("\"März\"", quote("März"));
+        assert_eq!("\"a \\\"b\\\" \\\\ c\\n\\u0001\"", quote("a \"b\" \\ c\n\u{1}"));
+    }
+}
@@ -1,121 +1,225 @@
-use crate::state::config::Config;
+use crate::calendar::{CalendarOptions, MonthRange, RenderStyle, WeekStart, DEFAULT_MONTHS_PER_ROW};
+use crate::state::config::{Config, MonthsPerRow, MAX_MONTHS_PER_ROW};
 use crate::state::chunk::YearMode::{NoDisplay, OwnLine, WithMonth};
 use crate::state::chunk::{Chunk, YearMode};
 use crate::time::month::Month;
+use crate::time::year::Year;
+use crate::time::events::{read_events, Event};
+use crate::time::locale::Locale;
 use crate::time::name::month_arg_match;
 use crate::time::today::Today;
+use crate::errors::{ics_out_of_range, invalid_date, months_out_of_range};
+use chrono::NaiveDate;
+use common::input::known_error::KnownError;
+use common::output::terminal::terminal_width;
 use std::collections::HashSet;
 
+const MONTH_WIDTH: usize = 21;
+const DEFAULT_TERMINAL_WIDTH: usize = 80;
+
+///
+/// The calendar options, laid out into rows of months.
+///
 pub struct ApplicationState {
     pub chunks: Vec<Chunk>,
+    pub months_per_row: usize,
+    pub locale: &'static Locale,
+    pub highlight: Option<NaiveDate>,
+    pub events: Vec<Event>,
+    pub week_start: WeekStart,
 }
 
 impl ApplicationState {
-    pub fn new(config: &Config, today: &dyn Today) -> ApplicationState {
-        ApplicationState { chunks: months_to_chunks(determine_months(config, today)) }
+    pub fn new(options: &CalendarOptions) -> ApplicationState {
+        let months_per_row = options.effective_months_per_row();
+        ApplicationState {
+            chunks: months_to_chunks(options.range.months(), months_per_row),
+            months_per_row,
+            locale: options.locale,
+            highlight: options.highlight,
+            events: options.events.clone(),
+            week_start: options.week_start,
+        }
+    }
+
+    ///
+    /// Every month on display, in order.
+    ///
+    pub fn months(&self) -> impl Iterator<Item = &Month> {
+        self.chunks.iter().flat_map(|chunk| chunk.months.iter())
     }
 }
 
-#[allow(clippy::unnecessary_unwrap)]
-fn determine_months(arguments: &Config, today: &dyn Today) -> Vec<Month> {
-    /* create storage */
-    let mut months = vec![];
+///
+/// Translate command line configuration into calendar options.
+///
+pub(crate) fn calendar_options(config: &Config, today: &dyn Today, locale: &'static Locale) -> Result<CalendarOptions, KnownError> {
+    let months = determine_months(config, today, locale)?;
+    if config.output_format == RenderStyle::Ics && months[0].year.value() < 1 {
+        return Err(ics_out_of_range());
+    }
+    Ok(CalendarOptions {
+        range: MonthRange::new(months[0], months.len())?,
+        week_start: if config.weeks_start_monday { WeekStart::Monday } else { WeekStart::Sunday },
+        highlight: determine_highlight(config, today)?,
+        style: config.output_format,
+        months_per_row: resolve_months_per_row(config.months_per_row, terminal_width()),
+        locale,
+        events: match config.events_file.as_deref() {
+            Some(path) => read_events(path, locale)?,
+            None => vec![],
+        },
+        today: today.make_today_date(),
+    })
+}
 
-    /* first: check out the year and month arguments, as those ones don't have flags */
-    if arguments.year.is_some() {
-        let the_year = arguments.year.unwrap();
+fn determine_highlight(config: &Config, today: &dyn Today) -> Result<Option<NaiveDate>, KnownError> {
+    if config.turn_off_highlight_today {
+        return Ok(None);
+    }
 
-        if let Some(the_month) = arguments.month.clone().and_then(|m| month_arg_match(&m)) {
-            months.push(Month::new(the_month, the_year));
-        } else {
-            for the_month in 1..=12 {
-                months.push(Month::new(the_month, the_year));
-            }
+    match config.debug_highlighting.as_deref() {
+        Some(value) => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map(Some)
+            .map_err(|_| invalid_date("-H", value)),
+        None => Ok(today.make_today_date()),
+    }
+}
+
+fn resolve_months_per_row(requested: Option<MonthsPerRow>, width: Option<usize>) -> usize {
+    match requested {
+        None => DEFAULT_MONTHS_PER_ROW,
+        Some(MonthsPerRow::Fixed(count)) => count,
+        Some(MonthsPerRow::Auto) => {
+            /* each month takes its own width, plus a separator; the last month doesn't need the separator */
+            let available = width.unwrap_or(DEFAULT_TERMINAL_WIDTH) + 1;
+            (available / (MONTH_WIDTH + 1)).clamp(1, MAX_MONTHS_PER_ROW)
         }
+    }
+}
+
+///
+/// How many months to show around the month the display is anchored on.
+///
+struct Window {
+    count: usize,
+    centered: bool,
+}
+
+fn determine_window(arguments: &Config, whole_year: bool) -> Window {
+    if arguments.only_current_month {
+        Window { count: 1, centered: false }
+    } else if arguments.previous_current_next_month {
+        Window { count: 3, centered: true }
+    } else if let Some(count) = arguments.months_count {
+        Window { count, centered: arguments.span_months }
+    } else if whole_year {
+        Window { count: 12, centered: arguments.span_months }
     } else {
-        months.push(today.make_today());
-    }
-
-    /* next: check for months before the current month */
-    if arguments.before.is_some() {
-        let maybe_min = months.iter().min();
-        if maybe_min.is_some() {
-            let mut count = arguments.before.unwrap();
-            let mut prev = *maybe_min.unwrap();
-            while count > 0 {
-                prev = prev.prev();
-                months.push(prev);
-                count -= 1;
-            }
-        }
+        Window { count: 1, centered: false }
     }
+}
+
+fn determine_months(arguments: &Config, today: &dyn Today, locale: &Locale) -> Result<Vec<Month>, KnownError> {
+    /* first: check out the year and month arguments, as those ones don't have flags */
+    let the_month = arguments.month.as_deref().map(|month| month_arg_match(month, locale)).transpose()?;
+    let window = determine_window(arguments, arguments.year.is_some() && the_month.is_none());
 
-    /* next: months after */
-    if arguments.after.is_some() {
-        let maybe_max = months.iter().max();
-        if maybe_max.is_some() {
-            let mut count = arguments.after.unwrap();
-            let mut next = *maybe_max.unwrap();
-            while count > 0 {
-                next = next.next();
-                months.push(next);
-                count -= 1;
+    /* next: find the month everything else is relative to */
+    let anchor = match (arguments.year, the_month) {
+        (Some(the_year), Some(the_month)) => Month::new(the_month.month, the_year.value())?,
+        (Some(the_year), None) => {
+            /* a plain year view starts in january; anything else is about today's month, in that year */
+            if window.count == 12 && !window.centered {
+                Month::new(1, the_year.value())?
+            } else {
+                Month::new(today.make_today()?.month, the_year.value())?
             }
-        }
-    }
+        },
+        (None, Some(the_month)) => {
+            /* no year given: use the current one, possibly shifted by an 'f' or 'p' suffix */
+            let the_year = the_month.year_relative_to(&today.make_today()?);
+            Month::new(the_month.month, the_year.value())?
+        },
+        (None, None) => today.make_today()?,
+    };
+
+    /* next: fill out the window around the anchor */
+    let mut months = vec![anchor];
+    let window_before = if window.centered { window.count / 2 } else { 0 };
+    add_months_before(&mut months, window_before)?;
+    add_months_after(&mut months, window.count - 1 - window_before)?;
+
+    /* next: check for months before/after the selected range */
+    add_months_before(&mut months, arguments.before.unwrap_or(0))?;
+    add_months_after(&mut months, arguments.after.unwrap_or(0))?;
 
     /* IMPORTANT last step: sort the returning months */
     months.sort();
     months.dedup();
 
     /* done */
-    months
+    Ok(months)
+}
+
+fn add_months_before(months: &mut Vec<Month>, count: usize) -> Result<(), KnownError> {
+    if let Some(min) = months.iter().min() {
+        let mut prev = *min;
+        for _ in 0..count {
+            prev = prev.prev().ok_or_else(months_out_of_range)?;
+            months.push(prev);
+        }
+    }
+    Ok(())
 }
 
-fn months_to_chunks(month_configs: Vec<Month>) -> Vec<Chunk> {
+fn add_months_after(months: &mut Vec<Month>, count: usize) -> Result<(), KnownError> {
+    if let Some(max) = months.iter().max() {
+        let mut next = *max;
+        for _ in 0..count {
+            next = next.next().ok_or_else(months_out_of_range)?;
+            months.push(next);
+        }
+    }
+    Ok(())
+}
+
+fn months_to_chunks(month_configs: Vec<Month>, months_per_row: usize) -> Vec<Chunk> {
     /* create storage */
     let mut chunks = vec![];
-    let mut years_displayed_on_own_line = vec![];
-
-    /* iterate over all months; break into chunks of 3, each of which becomes a chunk config */
-    for chunk in month_configs.chunks(3) {
-        let chunk_config = if chunk.len() == 1 {
-            let left = *chunk.first().unwrap();
-            let year_mode = determine_year_display_mode(chunk, &mut years_displayed_on_own_line);
-            Chunk::one(left, year_mode)
-        } else if chunk.len() == 2 {
-            let left = *chunk.first().unwrap();
-            let center = *chunk.get(1).unwrap();
-            let year_mode = determine_year_display_mode(chunk, &mut years_displayed_on_own_line);
-            Chunk::two(left, center, year_mode)
+    let mut years_on_own_line = HashSet::new();
+    let mut years_with_month = HashSet::new();
+
+    /* iterate over all months; break into rows, each of which becomes a chunk config */
+    for chunk in month_configs.chunks(months_per_row) {
+        let year_mode = if month_configs.len() == 1 {
+            /* a lone month always carries its own year */
+            WithMonth
         } else {
-            let left = *chunk.first().unwrap();
-            let center = *chunk.get(1).unwrap();
-            let right = *chunk.get(2).unwrap();
-            let year_mode = determine_year_display_mode(chunk, &mut years_displayed_on_own_line);
-            Chunk::three(left, center, right, year_mode)
+            determine_year_display_mode(chunk, &mut years_on_own_line, &mut years_with_month)
         };
-        chunks.push(chunk_config);
+        chunks.push(Chunk::new(chunk.to_vec(), year_mode));
     }
 
     /* done */
     chunks
 }
 
-fn determine_year_display_mode(chunk: &[Month], years_displayed_on_own_line: &mut [u16]) -> YearMode {
-    let years_in_current_chunk: HashSet<u16> = chunk.iter()
+fn determine_year_display_mode(chunk: &[Month], years_on_own_line: &mut HashSet<Year>, years_with_month: &mut HashSet<Year>) -> YearMode {
+    let years_in_current_chunk: HashSet<Year> = chunk.iter()
         .map(|c| c.year)
         .collect();
     if years_in_current_chunk.len() > 1 {
+        /* mixed years; once a year is shown next to its months, keep showing it that way */
+        years_with_month.extend(years_in_current_chunk);
         WithMonth
-    } else if years_in_current_chunk.len() == 1 {
-        let this_chunks_year = years_in_current_chunk.iter()
-            .next()
-            .unwrap()
-            .to_owned();
-        if years_displayed_on_own_line.contains(&this_chunks_year) {
+    } else if let Some(this_chunks_year) = years_in_current_chunk.into_iter().next() {
+        if years_with_month.contains(&this_chunks_year) {
+            WithMonth
+        } else if years_on_own_line.contains(&this_chunks_year) {
             NoDisplay
         } else {
+            years_on_own_line.insert(this_chunks_year);
             OwnLine
         }
     } else {
@@ -125,27 +229,30 @@ fn determine_year_display_mode(chunk: &[Month], years_displayed_on_own_line: &mu
 
 #[cfg(test)]
 mod static_date_tests {
-    use crate::state::config::Config;
-    use crate::state::app_state::determine_months;
+    use crate::state::config::{Config, MonthsPerRow};
+    use crate::state::app_state::{determine_months, months_to_chunks, resolve_months_per_row};
+    use crate::state::chunk::YearMode::{NoDisplay, OwnLine, WithMonth};
+    use crate::time::locale::ENGLISH;
     use crate::time::month::Month;
+    use crate::time::year::Year;
     use crate::time::today::Today;
+    use common::input::known_error::KnownError;
     struct TestOnlyToday {}
 
     impl Today for TestOnlyToday {
-        fn make_today(&self) -> Month {
-            return Month { month: 2, year: 2024 };
+        fn make_today(&self) -> Result<Month, KnownError> {
+            Month::new(2, 2024)
         }
     }
 
     #[test]
     fn test_before_only() {
-        let mut input = Config::default();
-        input.before = Some(3);
+        let input = Config { before: Some(3), ..Default::default() };
 
-        let output = determine_months(&input, &TestOnlyToday{});
+        let output = determine_months(&input, &TestOnlyToday{}, &ENGLISH).unwrap();
 
         assert_eq!(4, output.len());
-        assert_eq!("11/2023", format!("{}", output.get(0).unwrap()));
+        assert_eq!("11/2023", format!("{}", output.first().unwrap()));
         assert_eq!("12/2023", format!("{}", output.get(1).unwrap()));
         assert_eq!("1/2024", format!("{}", output.get(2).unwrap()));
         assert_eq!("2/2024", format!("{}", output.get(3).unwrap()));
@@ -153,16 +260,117 @@ mod static_date_tests {
 
     #[test]
     fn test_after_only() {
-        let mut input = Config::default();
-        input.after = Some(4);
+        let input = Config { after: Some(4), ..Default::default() };
 
-        let output = determine_months(&input, &TestOnlyToday{});
+        let output = determine_months(&input, &TestOnlyToday{}, &ENGLISH).unwrap();
 
         assert_eq!(5, output.len());
-        assert_eq!("2/2024", format!("{}", output.get(0).unwrap()));
+        assert_eq!("2/2024", format!("{}", output.first().unwrap()));
         assert_eq!("3/2024", format!("{}", output.get(1).unwrap()));
         assert_eq!("4/2024", format!("{}", output.get(2).unwrap()));
         assert_eq!("5/2024", format!("{}", output.get(3).unwrap()));
         assert_eq!("6/2024", format!("{}", output.get(4).unwrap()));
     }
+
+    #[test]
+    fn test_resolve_months_per_row() {
+        assert_eq!(3, resolve_months_per_row(None, Some(200)));
+        assert_eq!(5, resolve_months_per_row(Some(MonthsPerRow::Fixed(5)), Some(20)));
+        assert_eq!(3, resolve_months_per_row(Some(MonthsPerRow::Auto), None));
+        assert_eq!(3, resolve_months_per_row(Some(MonthsPerRow::Auto), Some(65)));
+        assert_eq!(2, resolve_months_per_row(Some(MonthsPerRow::Auto), Some(64)));
+        assert_eq!(1, resolve_months_per_row(Some(MonthsPerRow::Auto), Some(10)));
+        assert_eq!(12, resolve_months_per_row(Some(MonthsPerRow::Auto), Some(1000)));
+    }
+
+    #[test]
+    fn test_year_shown_once_per_row_layout() {
+        let months = (1..=12).map(|m| Month::new(m, 2024).unwrap()).collect();
+
+        let chunks = months_to_chunks(months, 5);
+
+        assert_eq!(3, chunks.len());
+        assert_eq!(5, chunks[0].months.len());
+        assert_eq!(2, chunks[2].months.len());
+        assert_eq!(OwnLine, chunks[0].year_mode);
+        assert_eq!(NoDisplay, chunks[1].year_mode);
+        assert_eq!(NoDisplay, chunks[2].year_mode);
+    }
+
+    #[test]
+    fn test_year_stays_with_month_after_mixed_row() {
+        let months = vec![Month::new(11, 1987).unwrap(), Month::new(12, 1987).unwrap(), Month::new(1, 1988).unwrap(), Month::new(2, 1988).unwrap()];
+
+        let chunks = months_to_chunks(months, 3);
+
+        assert_eq!(2, chunks.len());
+        assert_eq!(WithMonth, chunks[0].year_mode);
+        assert_eq!(WithMonth, chunks[1].year_mode);
+    }
+
+    fn months_for(config: Config) -> Vec<String> {
+        determine_months(&config, &TestOnlyToday{}, &ENGLISH)
+            .unwrap()
+            .iter()
+            .map(|m| format!("{}", m))
+            .collect()
+    }
+
+    #[test]
+    fn test_three_months() {
+        let input = Config { previous_current_next_month: true, ..Default::default() };
+
+        assert_eq!(vec!["1/2024", "2/2024", "3/2024"], months_for(input));
+    }
+
+    #[test]
+    fn test_three_months_with_after() {
+        let input = Config { previous_current_next_month: true, after: Some(2), ..Default::default() };
+
+        assert_eq!(vec!["1/2024", "2/2024", "3/2024", "4/2024", "5/2024"], months_for(input));
+    }
+
+    #[test]
+    fn test_one_month_wins() {
+        let three = Config { previous_current_next_month: true, only_current_month: true, ..Default::default() };
+        let year = Config { only_current_month: true, year: Year::new(2023).ok(), ..Default::default() };
+
+        assert_eq!(vec!["2/2024"], months_for(three));
+        assert_eq!(vec!["2/2023"], months_for(year));
+    }
+
+    #[test]
+    fn test_months_count() {
+        let input = Config { months_count: Some(4), ..Default::default() };
+
+        assert_eq!(vec!["2/2024", "3/2024", "4/2024", "5/2024"], months_for(input));
+    }
+
+    #[test]
+    fn test_months_count_span() {
+        let odd = Config { months_count: Some(5), span_months: true, ..Default::default() };
+        let even = Config { months_count: Some(4), span_months: true, ..Default::default() };
+
+        assert_eq!(vec!["12/2023", "1/2024", "2/2024", "3/2024", "4/2024"], months_for(odd));
+        assert_eq!(vec!["12/2023", "1/2024", "2/2024", "3/2024"], months_for(even));
+    }
+
+    #[test]
+    fn test_year_span() {
+        let input = Config { year: Year::new(2024).ok(), span_months: true, ..Default::default() };
+
+        let output = months_for(input);
+
+        assert_eq!(12, output.len());
+        assert_eq!("8/2023", output[0]);
+        assert_eq!("2/2024", output[6]);
+        assert_eq!("7/2024", output[11]);
+    }
+
+    #[test]
+    fn test_three_months_out_of_range() {
+        let input = Config { previous_current_next_month: true, month: Some("jan".to_owned()), year: Year::new(-9999).ok(), ..Default::default() };
+
+        assert!(determine_months(&input, &TestOnlyToday{}, &ENGLISH).is_err());
+    }
 }
@@ -1,12 +1,14 @@
 use crate::time::month::Month;
 
+///
+/// A single row of months, rendered side by side.
+///
 pub struct Chunk {
-    pub left: Month,
-    pub center: Option<Month>,
-    pub right: Option<Month>,
+    pub months: Vec<Month>,
     pub year_mode: YearMode
 }
 
+#[derive(Debug, PartialEq)]
 pub enum YearMode {
     WithMonth,
     NoDisplay,
@@ -14,15 +16,11 @@ pub enum YearMode {
 }
 
 impl Chunk {
-    pub fn one(p_left: Month, p_year_mode: YearMode) -> Chunk {
-        Chunk { left: p_left, center: None, right: None, year_mode: p_year_mode }
-    }
-
-    pub fn two(p_left: Month, p_center: Month, p_year_mode: YearMode) -> Chunk {
-        Chunk { left: p_left, center: Option::from(p_center), right: None, year_mode: p_year_mode }
-    }
+    pub fn new(p_months: Vec<Month>, p_year_mode: YearMode) -> Chunk {
+        if p_months.is_empty() {
+            panic!("a chunk needs at least one month");
+        }
 
-    pub fn three(p_left: Month, p_center: Month, p_right: Month, p_year_mode: YearMode) -> Chunk {
-        Chunk { left: p_left, center: Option::from(p_center), right: Option::from(p_right), year_mode: p_year_mode }
+        Chunk { months: p_months, year_mode: p_year_mode }
     }
 }
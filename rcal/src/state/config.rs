@@ -1,10 +1,29 @@
-use crate::time::name::month_num_to_name;
+use crate::calendar::RenderStyle;
+use crate::errors::{invalid_columns, invalid_current_date, invalid_format, invalid_option, invalid_year, requires_argument, requires_number, too_many_arguments, unrecognized_option, year_out_of_range};
+use crate::time::locale::Locale;
+use crate::time::name::month_arg_match;
 use crate::time::today::TodayFactory;
+use crate::time::year::Year;
 use common::input::known_error::KnownError;
 use common::input::flags::flags_unrecognized::UnrecognizedFlag;
 
 const VALID_FLAGS: &str = "hJejopw31CMSb";
 const VALID_COMBINED_FLAGS: &str = "bhJjpwSM";
+const COLUMNS_AUTO: &str = "auto";
+const COLUMNS_LONG_PREFIX: &str = "--columns=";
+const MONTHS_LONG_PREFIX: &str = "--months=";
+const FORMAT_LONG_PREFIX: &str = "--format=";
+const EVENTS_LONG_PREFIX: &str = "--events=";
+pub(crate) const MAX_MONTHS_PER_ROW: usize = 12;
+
+///
+/// Number of months to lay out side by side.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum MonthsPerRow {
+    Fixed(usize),
+    Auto,
+}
 
 ///
 /// Storage for the application configuration.
@@ -25,16 +44,21 @@ pub(crate) struct Config {
     pub(crate) weeks_start_monday: bool,
     pub(crate) weeks_start_sunday: bool,
     pub(crate) use_old_style_format: bool,
+    pub(crate) span_months: bool,
 
     /* string fields read from command line */
     pub(crate) month: Option<String>,
     pub(crate) country_code: Option<String>,
-    pub(crate) year: Option<u16>,
+    pub(crate) year: Option<Year>,
     pub(crate) after: Option<usize>,
     pub(crate) before: Option<usize>,
     pub(crate) debug_current_date: Option<String>,
     pub(crate) debug_highlighting: Option<String>,
     pub(crate) first_week_has_at_least_days: Option<String>,
+    pub(crate) months_per_row: Option<MonthsPerRow>,
+    pub(crate) months_count: Option<usize>,
+    pub(crate) output_format: RenderStyle,
+    pub(crate) events_file: Option<String>,
 
     /* unrecognized arguments */
     pub(crate) unrecognized: Vec<UnrecognizedFlag>,
@@ -60,12 +84,17 @@ impl Default for Config {
             weeks_start_monday: false,
             weeks_start_sunday: false,
             use_old_style_format: false,
+            span_months: false,
 
             month: None,
             country_code: None,
             debug_current_date: None,
             debug_highlighting: None,
             first_week_has_at_least_days: None,
+            months_per_row: None,
+            months_count: None,
+            output_format: RenderStyle::Text,
+            events_file: None,
 
             year: None,
             after: None,
@@ -84,125 +113,181 @@ impl Config {
     pub(crate) fn new(args: &[String]) -> Config {
         let mut config = Self::default();
 
-        /* initial handling of arguments */
-        if args.len() == 1 && !args.first().unwrap().starts_with("-") {
-            let today = TodayFactory::Actual.create().make_today();
-            config.month = Option::from(month_num_to_name(today.month));
-            config.year = Option::from(today.year);
-        } else {
-            let mut prev_arg_month = false;
-            let mut prev_arg_country_code = false;
-            let mut prev_arg_year = false;
-            let mut prev_arg_months_add_after = false;
-            let mut prev_arg_months_add_before = false;
-            let mut prev_arg_debug_current_date = false;
-            let mut prev_arg_debug_highlighting = false;
-            let mut prev_arg_first_week_has_at_least_days = false;
-
-            for (index, argument) in args.iter().enumerate() {
-                if index == 0 {
-                    continue;
-                }
+        /* flags and their values; with no arguments at all, the current month is shown */
+        let mut prev_arg_month = false;
+        let mut prev_arg_country_code = false;
+        let mut prev_arg_year = false;
+        let mut prev_arg_months_add_after = false;
+        let mut prev_arg_months_add_before = false;
+        let mut prev_arg_debug_current_date = false;
+        let mut prev_arg_debug_highlighting = false;
+        let mut prev_arg_first_week_has_at_least_days = false;
+        let mut prev_arg_columns = false;
+        let mut prev_arg_months_count = false;
+        let mut prev_arg_format = false;
+        let mut prev_arg_events = false;
+        let mut end_of_options = false;
+
+        for (index, argument) in args.iter().enumerate() {
+            if index == 0 {
+                continue;
+            }
 
-                if prev_arg_month {
-                    prev_arg_month = false;
-                    config.month = Some(argument.to_owned());
-                } else if prev_arg_country_code {
-                    prev_arg_country_code = false;
-                    config.country_code = Some(argument.to_owned());
-                } else if prev_arg_year {
-                    prev_arg_year = false;
-                    config.year = Some(argument.parse::<u16>().unwrap());
-                } else if prev_arg_months_add_after {
-                    prev_arg_months_add_after = false;
-                    config.after = Some(argument.parse::<usize>().unwrap());
-                } else if prev_arg_months_add_before {
-                    prev_arg_months_add_before = false;
-                    config.before = Some(argument.parse::<usize>().unwrap());
-                } else if prev_arg_debug_current_date {
-                    prev_arg_debug_current_date = false;
-                    config.debug_current_date = Some(argument.to_owned());
-                } else if prev_arg_debug_highlighting {
-                    prev_arg_debug_highlighting = false;
-                    config.debug_highlighting = Some(argument.to_owned());
-                } else if prev_arg_first_week_has_at_least_days {
-                    prev_arg_first_week_has_at_least_days = false;
-                    config.first_week_has_at_least_days = Some(argument.to_owned());
-                } else {
-                    match argument.as_str() {
-                        "-m" => prev_arg_month = true,
-                        "-s" => prev_arg_country_code = true,
-                        "-y" => prev_arg_year = true,
-                        "-A" => prev_arg_months_add_after = true,
-                        "-B" => prev_arg_months_add_before = true,
-                        "-d" => prev_arg_debug_current_date = true,
-                        "-H" => prev_arg_debug_highlighting = true,
-                        "-W" => prev_arg_first_week_has_at_least_days = true,
-
-                        _ => {
-                            if argument.starts_with('-') {
-                                /* check for combined arguments */
-                                if Self::is_valid_flag(VALID_COMBINED_FLAGS, argument) {
-                                    for ch in argument.chars() {
-                                        Self::set_config_for_flag(&mut config, ch)
+            if prev_arg_month {
+                prev_arg_month = false;
+                config.month = Some(argument.to_owned());
+            } else if prev_arg_country_code {
+                prev_arg_country_code = false;
+                config.country_code = Some(argument.to_owned());
+            } else if prev_arg_year {
+                prev_arg_year = false;
+                match Self::parse_year(argument) {
+                    Ok(year) => config.year = Some(year),
+                    Err(error) => config.errors.push(error),
+                }
+            } else if prev_arg_months_add_after {
+                prev_arg_months_add_after = false;
+                match argument.parse::<usize>() {
+                    Ok(count) => config.after = Some(count),
+                    Err(_) => config.errors.push(requires_number("-A")),
+                }
+            } else if prev_arg_months_add_before {
+                prev_arg_months_add_before = false;
+                match argument.parse::<usize>() {
+                    Ok(count) => config.before = Some(count),
+                    Err(_) => config.errors.push(requires_number("-B")),
+                }
+            } else if prev_arg_debug_current_date {
+                prev_arg_debug_current_date = false;
+                if TodayFactory::parse(argument).is_none() {
+                    config.errors.push(invalid_current_date(argument));
+                }
+                config.debug_current_date = Some(argument.to_owned());
+            } else if prev_arg_debug_highlighting {
+                prev_arg_debug_highlighting = false;
+                config.debug_highlighting = Some(argument.to_owned());
+            } else if prev_arg_first_week_has_at_least_days {
+                prev_arg_first_week_has_at_least_days = false;
+                config.first_week_has_at_least_days = Some(argument.to_owned());
+            } else if prev_arg_columns {
+                prev_arg_columns = false;
+                Self::set_months_per_row(&mut config, argument);
+            } else if prev_arg_months_count {
+                prev_arg_months_count = false;
+                Self::set_months_count(&mut config, "-n", argument);
+            } else if prev_arg_format {
+                prev_arg_format = false;
+                Self::set_output_format(&mut config, argument);
+            } else if prev_arg_events {
+                prev_arg_events = false;
+                config.events_file = Some(argument.to_owned());
+            } else if end_of_options {
+                /* after --, everything is positional, even when it starts with a dash, e.g. a negative year */
+                config
+                    .unrecognized
+                    .push(UnrecognizedFlag::new(index, argument.to_owned()))
+            } else {
+                match argument.as_str() {
+                    "--" => end_of_options = true,
+                    "-m" => prev_arg_month = true,
+                    "-s" => prev_arg_country_code = true,
+                    "-y" => prev_arg_year = true,
+                    "-A" => prev_arg_months_add_after = true,
+                    "-B" => prev_arg_months_add_before = true,
+                    "-d" => prev_arg_debug_current_date = true,
+                    "-H" => prev_arg_debug_highlighting = true,
+                    "-W" => prev_arg_first_week_has_at_least_days = true,
+                    "-c" | "--columns" => prev_arg_columns = true,
+                    "-n" | "--months" => prev_arg_months_count = true,
+                    "--span" => config.span_months = true,
+                    "--format" => prev_arg_format = true,
+                    "--events" => prev_arg_events = true,
+
+                    _ => {
+                        if let Some(value) = argument.strip_prefix(COLUMNS_LONG_PREFIX) {
+                            Self::set_months_per_row(&mut config, value);
+                        } else if let Some(value) = argument.strip_prefix(MONTHS_LONG_PREFIX) {
+                            Self::set_months_count(&mut config, "--months", value);
+                        } else if let Some(value) = argument.strip_prefix(FORMAT_LONG_PREFIX) {
+                            Self::set_output_format(&mut config, value);
+                        } else if let Some(value) = argument.strip_prefix(EVENTS_LONG_PREFIX) {
+                            config.events_file = Some(value.to_owned());
+                        } else if argument.starts_with('-') {
+                            /* check for combined arguments */
+                            if Self::is_valid_flag(VALID_COMBINED_FLAGS, argument)
+                                || (argument.len() == 2 && Self::is_valid_flag(VALID_FLAGS, argument)) {
+                                for ch in argument.chars() {
+                                    if let Err(error) = Self::set_config_for_flag(&mut config, ch) {
+                                        config.errors.push(error);
                                     }
-                                } else if argument.len() == 2 && Self::is_valid_flag(VALID_FLAGS, argument) {
-                                    Self::set_config_for_flag(&mut config, argument.chars().nth(1).unwrap(), )
-                                } else {
-                                    config
-                                        .unrecognized
-                                        .push(UnrecognizedFlag::new(index, argument.to_owned()))
                                 }
                             } else {
-                                config
-                                    .unrecognized
-                                    .push(UnrecognizedFlag::new(index, argument.to_owned()))
+                                config.errors.push(Self::invalid_flag_error(argument));
                             }
+                        } else {
+                            config
+                                .unrecognized
+                                .push(UnrecognizedFlag::new(index, argument.to_owned()))
                         }
                     }
                 }
             }
         }
 
+        /* options at the very end never received their value */
+        let pending = [
+            (prev_arg_month, "-m"),
+            (prev_arg_country_code, "-s"),
+            (prev_arg_debug_current_date, "-d"),
+            (prev_arg_debug_highlighting, "-H"),
+            (prev_arg_first_week_has_at_least_days, "-W"),
+            (prev_arg_columns, "-c"),
+            (prev_arg_format, "--format"),
+            (prev_arg_events, "--events"),
+        ];
+        pending.iter()
+            .filter(|(waiting, _)| *waiting)
+            .for_each(|(_, option)| config.errors.push(requires_argument(option)));
+        [(prev_arg_year, "-y"), (prev_arg_months_add_after, "-A"), (prev_arg_months_add_before, "-B"), (prev_arg_months_count, "-n")].iter()
+            .filter(|(waiting, _)| *waiting)
+            .for_each(|(_, option)| config.errors.push(requires_number(option)));
+
         /* sort unrecognized, just in case */
         config.unrecognized.sort();
 
-        /* print contents */
-        //config.unrecognized.iter().for_each(|u| println!("{:?}", u));
-
-        /* deal with unrecognized args */
-        if config.unrecognized.len() == 1 {
-            /* 1 arg: year */
-            let ua = config.unrecognized.remove(0);
-            let temp_year = ua.argument.expect("argument missing");
-
-            if let Ok(year) = temp_year.parse::<u16>() {
-                config.year = Some(year);
-            } else {
-                config.errors.push(KnownError {
-                    code: 1,
-                    message: Some(format!("rcal: not a valid year {}", temp_year)),
-                })
-            }
-        } else if config.unrecognized.len() == 2 {
-            /* 2 args: month year */
-            if let Some(pos) = config.unrecognized.iter().position(|u| u.index == 1) {
-                let ua = config.unrecognized.remove(pos);
-                config.month = ua.argument;
-            }
+        /* deal with unrecognized args; these are positional, in the form [[month] year] */
+        let positional: Vec<String> = config.unrecognized
+            .drain(..)
+            .filter_map(|u| u.argument)
+            .collect();
+        match positional.as_slice() {
+            [] => {},
+            [year] => {
+                /* 1 arg: year */
+                match Self::parse_year(year) {
+                    Ok(year) => config.year = Some(year),
+                    Err(error) => config.errors.push(error),
+                }
+            },
+            [month, year, rest @ ..] => {
+                /* 2 args: month year */
+                config.month = Some(month.to_owned());
+
+                match Self::parse_year(year) {
+                    Ok(year) => config.year = Some(year),
+                    Err(error) => config.errors.push(error),
+                }
 
-            if let Some(pos) = config.unrecognized.iter().position(|u| u.index == 2) {
-                let ua = config.unrecognized.remove(pos);
-                let temp_year = ua.argument.expect("argument missing");
-
-                if let Ok(year) = temp_year.parse::<u16>() {
-                    config.year = Some(year);
-                } else {
-                    config.errors.push(KnownError {
-                        code: 1,
-                        message: Some(format!("rcal: not a valid year {}", temp_year)),
-                    })
+                if let Some(extra) = rest.first() {
+                    config.errors.push(too_many_arguments(extra));
                 }
+            },
+        }
+
+        /* only check the month once everything else is in order; a bad year is the more likely mistake */
+        if config.errors.is_empty() {
+            if let Some(Err(error)) = config.month.as_deref().map(|month| month_arg_match(month, Locale::from_environment())) {
+                config.errors.push(error);
             }
         }
 
@@ -210,6 +295,57 @@ impl Config {
         config
     }
 
+    fn set_months_per_row(config: &mut Config, value: &str) {
+        if value == COLUMNS_AUTO {
+            config.months_per_row = Some(MonthsPerRow::Auto);
+        } else {
+            match value.parse::<usize>() {
+                Ok(count) if (1..=MAX_MONTHS_PER_ROW).contains(&count) => config.months_per_row = Some(MonthsPerRow::Fixed(count)),
+                _ => config.errors.push(invalid_columns(value)),
+            }
+        }
+    }
+
+    fn set_output_format(config: &mut Config, value: &str) {
+        match value {
+            "text" => config.output_format = RenderStyle::Text,
+            "json" => config.output_format = RenderStyle::Json,
+            "ics" => config.output_format = RenderStyle::Ics,
+            _ => config.errors.push(invalid_format(value)),
+        }
+    }
+
+    fn parse_year(value: &str) -> Result<Year, KnownError> {
+        match value.parse::<i64>() {
+            Ok(year) => i32::try_from(year)
+                .ok()
+                .and_then(|year| Year::new(year).ok())
+                .ok_or_else(|| year_out_of_range(value)),
+            Err(_) => Err(invalid_year(value)),
+        }
+    }
+
+    fn set_months_count(config: &mut Config, option: &str, value: &str) {
+        match value.parse::<usize>() {
+            Ok(count) if count > 0 => config.months_count = Some(count),
+            _ => config.errors.push(requires_number(option)),
+        }
+    }
+
+    fn invalid_flag_error(argument: &str) -> KnownError {
+        if argument.starts_with("--") || argument.len() < 2 {
+            return unrecognized_option(argument);
+        }
+
+        /* single flags have a wider set of valid characters than combined ones */
+        let allowed = if argument.len() == 2 { VALID_FLAGS } else { VALID_COMBINED_FLAGS };
+        argument.chars()
+            .skip(1)
+            .find(|&ch| !allowed.contains(ch))
+            .map(invalid_option)
+            .unwrap_or_else(|| unrecognized_option(argument))
+    }
+
     fn is_valid_flag(flags: &str, argument: &str) -> bool {
         for ch in argument.chars() {
             if ch == '-' {
@@ -222,7 +358,7 @@ impl Config {
         true
     }
 
-    fn set_config_for_flag(config: &mut Config, flag: char) {
+    fn set_config_for_flag(config: &mut Config, flag: char) -> Result<(), KnownError> {
         match flag {
             '-' => {}
             'h' => config.turn_off_highlight_today = true,
@@ -238,14 +374,16 @@ impl Config {
             'M' => config.weeks_start_monday = true,
             'S' => config.weeks_start_sunday = true,
             'b' => config.use_old_style_format = true,
-            _ => panic!("unrecognized flag: {}", flag),
+            _ => return Err(invalid_option(flag)),
         }
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::state::config::Config;
+    use crate::calendar::RenderStyle;
+    use crate::state::config::{Config, MonthsPerRow};
 
     #[test]
     fn test_all_args() {
@@ -284,25 +422,25 @@ mod test {
 
         let config: &Config = &Config::new(&args);
 
-        assert_eq!(true, config.turn_off_highlight_today);
-        assert_eq!(true, config.display_julian_calendar);
-        assert_eq!(true, config.display_date_of_easter);
-        assert_eq!(true, config.display_julian_days);
-        assert_eq!(true, config.display_date_orthodox_easter);
-        assert_eq!(true, config.print_country_codes);
-        assert_eq!(true, config.print_number_of_week);
-        assert_eq!(true, config.previous_current_next_month);
-        assert_eq!(true, config.only_current_month);
-        assert_eq!(true, config.cal_mode);
-        assert_eq!(true, config.weeks_start_monday);
-        assert_eq!(true, config.weeks_start_sunday);
-        assert_eq!(true, config.use_old_style_format);
+        assert!(config.turn_off_highlight_today);
+        assert!(config.display_julian_calendar);
+        assert!(config.display_date_of_easter);
+        assert!(config.display_julian_days);
+        assert!(config.display_date_orthodox_easter);
+        assert!(config.print_country_codes);
+        assert!(config.print_number_of_week);
+        assert!(config.previous_current_next_month);
+        assert!(config.only_current_month);
+        assert!(config.cal_mode);
+        assert!(config.weeks_start_monday);
+        assert!(config.weeks_start_sunday);
+        assert!(config.use_old_style_format);
 
         assert_eq!("january", config.month.clone().unwrap());
         assert_eq!("uk", config.country_code.clone().unwrap());
-        assert_eq!(2012, config.year.clone().unwrap());
-        assert_eq!(6, config.before.clone().unwrap());
-        assert_eq!(5, config.after.clone().unwrap());
+        assert_eq!(2012, config.year.unwrap());
+        assert_eq!(6, config.before.unwrap());
+        assert_eq!(5, config.after.unwrap());
         assert_eq!("2012-11", config.debug_current_date.clone().unwrap());
         assert_eq!("2002-06-08", config.debug_highlighting.clone().unwrap());
         assert_eq!("4", config.first_week_has_at_least_days.clone().unwrap());
@@ -325,6 +463,7 @@ mod test {
             weeks_start_monday: true, \
             weeks_start_sunday: true, \
             use_old_style_format: true, \
+            span_months: false, \
             month: Some(\"january\"), \
             country_code: Some(\"uk\"), \
             year: Some(2012), \
@@ -333,6 +472,10 @@ mod test {
             debug_current_date: Some(\"2012-11\"), \
             debug_highlighting: Some(\"2002-06-08\"), \
             first_week_has_at_least_days: Some(\"4\"), \
+            months_per_row: None, \
+            months_count: None, \
+            output_format: Text, \
+            events_file: None, \
             unrecognized: [], \
             errors: [] \
             }",
@@ -349,19 +492,19 @@ mod test {
 
         let config = Config::new(&args);
 
-        assert_eq!(true, config.turn_off_highlight_today);
-        assert_eq!(true, config.display_julian_calendar);
-        assert_eq!(false, config.display_date_of_easter);
-        assert_eq!(true, config.display_julian_days);
-        assert_eq!(false, config.display_date_orthodox_easter);
-        assert_eq!(true, config.print_country_codes);
-        assert_eq!(true, config.print_number_of_week);
-        assert_eq!(false, config.previous_current_next_month);
-        assert_eq!(false, config.only_current_month);
-        assert_eq!(false, config.cal_mode);
-        assert_eq!(true, config.weeks_start_monday);
-        assert_eq!(true, config.weeks_start_sunday);
-        assert_eq!(true, config.use_old_style_format);
+        assert!(config.turn_off_highlight_today);
+        assert!(config.display_julian_calendar);
+        assert!(!config.display_date_of_easter);
+        assert!(config.display_julian_days);
+        assert!(!config.display_date_orthodox_easter);
+        assert!(config.print_country_codes);
+        assert!(config.print_number_of_week);
+        assert!(!config.previous_current_next_month);
+        assert!(!config.only_current_month);
+        assert!(!config.cal_mode);
+        assert!(config.weeks_start_monday);
+        assert!(config.weeks_start_sunday);
+        assert!(config.use_old_style_format);
 
         assert_eq!(None, config.month);
         assert_eq!(None, config.country_code);
@@ -390,6 +533,7 @@ mod test {
             weeks_start_monday: true, \
             weeks_start_sunday: true, \
             use_old_style_format: true, \
+            span_months: false, \
             month: None, \
             country_code: None, \
             year: None, \
@@ -398,6 +542,10 @@ mod test {
             debug_current_date: None, \
             debug_highlighting: None, \
             first_week_has_at_least_days: None, \
+            months_per_row: None, \
+            months_count: None, \
+            output_format: Text, \
+            events_file: None, \
             unrecognized: [], \
             errors: [] \
             }",
@@ -415,19 +563,19 @@ mod test {
 
         let config = Config::new(&args);
 
-        assert_eq!(true, config.turn_off_highlight_today);
-        assert_eq!(true, config.display_julian_calendar);
-        assert_eq!(false, config.display_date_of_easter);
-        assert_eq!(true, config.display_julian_days);
-        assert_eq!(false, config.display_date_orthodox_easter);
-        assert_eq!(true, config.print_country_codes);
-        assert_eq!(true, config.print_number_of_week);
-        assert_eq!(false, config.previous_current_next_month);
-        assert_eq!(false, config.only_current_month);
-        assert_eq!(false, config.cal_mode);
-        assert_eq!(true, config.weeks_start_monday);
-        assert_eq!(true, config.weeks_start_sunday);
-        assert_eq!(true, config.use_old_style_format);
+        assert!(config.turn_off_highlight_today);
+        assert!(config.display_julian_calendar);
+        assert!(!config.display_date_of_easter);
+        assert!(config.display_julian_days);
+        assert!(!config.display_date_orthodox_easter);
+        assert!(config.print_country_codes);
+        assert!(config.print_number_of_week);
+        assert!(!config.previous_current_next_month);
+        assert!(!config.only_current_month);
+        assert!(!config.cal_mode);
+        assert!(config.weeks_start_monday);
+        assert!(config.weeks_start_sunday);
+        assert!(config.use_old_style_format);
 
         assert_eq!(None, config.month);
         assert_eq!(None, config.country_code);
@@ -456,6 +604,7 @@ mod test {
             weeks_start_monday: true, \
             weeks_start_sunday: true, \
             use_old_style_format: true, \
+            span_months: false, \
             month: None, \
             country_code: None, \
             year: Some(2021), \
@@ -464,6 +613,10 @@ mod test {
             debug_current_date: None, \
             debug_highlighting: None, \
             first_week_has_at_least_days: None, \
+            months_per_row: None, \
+            months_count: None, \
+            output_format: Text, \
+            events_file: None, \
             unrecognized: [], \
             errors: [] \
             }",
@@ -480,23 +633,23 @@ mod test {
 
         let config = Config::new(&args);
 
-        assert_eq!(false, config.turn_off_highlight_today);
-        assert_eq!(false, config.display_julian_calendar);
-        assert_eq!(false, config.display_date_of_easter);
-        assert_eq!(false, config.display_julian_days);
-        assert_eq!(false, config.display_date_orthodox_easter);
-        assert_eq!(false, config.print_country_codes);
-        assert_eq!(false, config.print_number_of_week);
-        assert_eq!(false, config.previous_current_next_month);
-        assert_eq!(false, config.only_current_month);
-        assert_eq!(false, config.cal_mode);
-        assert_eq!(false, config.weeks_start_monday);
-        assert_eq!(false, config.weeks_start_sunday);
-        assert_eq!(false, config.use_old_style_format);
+        assert!(!config.turn_off_highlight_today);
+        assert!(!config.display_julian_calendar);
+        assert!(!config.display_date_of_easter);
+        assert!(!config.display_julian_days);
+        assert!(!config.display_date_orthodox_easter);
+        assert!(!config.print_country_codes);
+        assert!(!config.print_number_of_week);
+        assert!(!config.previous_current_next_month);
+        assert!(!config.only_current_month);
+        assert!(!config.cal_mode);
+        assert!(!config.weeks_start_monday);
+        assert!(!config.weeks_start_sunday);
+        assert!(!config.use_old_style_format);
 
         assert_eq!(None, config.month);
         assert_eq!(None, config.country_code);
-        assert_eq!(2022, config.year.clone().unwrap());
+        assert_eq!(2022, config.year.unwrap());
         assert_eq!(None, config.before);
         assert_eq!(None, config.after);
         assert_eq!(None, config.debug_current_date);
@@ -521,6 +674,7 @@ mod test {
             weeks_start_monday: false, \
             weeks_start_sunday: false, \
             use_old_style_format: false, \
+            span_months: false, \
             month: None, \
             country_code: None, \
             year: Some(2022), \
@@ -529,6 +683,10 @@ mod test {
             debug_current_date: None, \
             debug_highlighting: None, \
             first_week_has_at_least_days: None, \
+            months_per_row: None, \
+            months_count: None, \
+            output_format: Text, \
+            events_file: None, \
             unrecognized: [], \
             errors: [] \
             }",
@@ -546,23 +704,23 @@ mod test {
 
         let config = Config::new(&args);
 
-        assert_eq!(false, config.turn_off_highlight_today);
-        assert_eq!(false, config.display_julian_calendar);
-        assert_eq!(false, config.display_date_of_easter);
-        assert_eq!(false, config.display_julian_days);
-        assert_eq!(false, config.display_date_orthodox_easter);
-        assert_eq!(false, config.print_country_codes);
-        assert_eq!(false, config.print_number_of_week);
-        assert_eq!(false, config.previous_current_next_month);
-        assert_eq!(false, config.only_current_month);
-        assert_eq!(false, config.cal_mode);
-        assert_eq!(false, config.weeks_start_monday);
-        assert_eq!(false, config.weeks_start_sunday);
-        assert_eq!(false, config.use_old_style_format);
+        assert!(!config.turn_off_highlight_today);
+        assert!(!config.display_julian_calendar);
+        assert!(!config.display_date_of_easter);
+        assert!(!config.display_julian_days);
+        assert!(!config.display_date_orthodox_easter);
+        assert!(!config.print_country_codes);
+        assert!(!config.print_number_of_week);
+        assert!(!config.previous_current_next_month);
+        assert!(!config.only_current_month);
+        assert!(!config.cal_mode);
+        assert!(!config.weeks_start_monday);
+        assert!(!config.weeks_start_sunday);
+        assert!(!config.use_old_style_format);
 
         assert_eq!("jan", config.month.clone().unwrap());
         assert_eq!(None, config.country_code);
-        assert_eq!(2019, config.year.clone().unwrap());
+        assert_eq!(2019, config.year.unwrap());
         assert_eq!(None, config.before);
         assert_eq!(None, config.after);
         assert_eq!(None, config.debug_current_date);
@@ -587,6 +745,7 @@ mod test {
             weeks_start_monday: false, \
             weeks_start_sunday: false, \
             use_old_style_format: false, \
+            span_months: false, \
             month: Some(\"jan\"), \
             country_code: None, \
             year: Some(2019), \
@@ -595,6 +754,10 @@ mod test {
             debug_current_date: None, \
             debug_highlighting: None, \
             first_week_has_at_least_days: None, \
+            months_per_row: None, \
+            months_count: None, \
+            output_format: Text, \
+            events_file: None, \
             unrecognized: [], \
             errors: [] \
             }",
@@ -612,19 +775,19 @@ mod test {
 
         let config = Config::new(&args);
 
-        assert_eq!(false, config.turn_off_highlight_today);
-        assert_eq!(false, config.display_julian_calendar);
-        assert_eq!(false, config.display_date_of_easter);
-        assert_eq!(false, config.display_julian_days);
-        assert_eq!(false, config.display_date_orthodox_easter);
-        assert_eq!(false, config.print_country_codes);
-        assert_eq!(false, config.print_number_of_week);
-        assert_eq!(false, config.previous_current_next_month);
-        assert_eq!(false, config.only_current_month);
-        assert_eq!(false, config.cal_mode);
-        assert_eq!(false, config.weeks_start_monday);
-        assert_eq!(false, config.weeks_start_sunday);
-        assert_eq!(false, config.use_old_style_format);
+        assert!(!config.turn_off_highlight_today);
+        assert!(!config.display_julian_calendar);
+        assert!(!config.display_date_of_easter);
+        assert!(!config.display_julian_days);
+        assert!(!config.display_date_orthodox_easter);
+        assert!(!config.print_country_codes);
+        assert!(!config.print_number_of_week);
+        assert!(!config.previous_current_next_month);
+        assert!(!config.only_current_month);
+        assert!(!config.cal_mode);
+        assert!(!config.weeks_start_monday);
+        assert!(!config.weeks_start_sunday);
+        assert!(!config.use_old_style_format);
 
         assert_eq!("2018", config.month.clone().unwrap());
         assert_eq!(None, config.country_code);
@@ -653,6 +816,7 @@ mod test {
             weeks_start_monday: false, \
             weeks_start_sunday: false, \
             use_old_style_format: false, \
+            span_months: false, \
             month: Some(\"2018\"), \
             country_code: None, \
             year: None, \
@@ -661,6 +825,10 @@ mod test {
             debug_current_date: None, \
             debug_highlighting: None, \
             first_week_has_at_least_days: None, \
+            months_per_row: None, \
+            months_count: None, \
+            output_format: Text, \
+            events_file: None, \
             unrecognized: [], \
             errors: [KnownError { code: 1, message: Some(\"rcal: not a valid year feb\") }] \
             }",
@@ -674,19 +842,19 @@ mod test {
 
         let config = Config::new(&args);
 
-        assert_eq!(false, config.turn_off_highlight_today);
-        assert_eq!(false, config.display_julian_calendar);
-        assert_eq!(false, config.display_date_of_easter);
-        assert_eq!(false, config.display_julian_days);
-        assert_eq!(false, config.display_date_orthodox_easter);
-        assert_eq!(false, config.print_country_codes);
-        assert_eq!(false, config.print_number_of_week);
-        assert_eq!(false, config.previous_current_next_month);
-        assert_eq!(false, config.only_current_month);
-        assert_eq!(false, config.cal_mode);
-        assert_eq!(false, config.weeks_start_monday);
-        assert_eq!(false, config.weeks_start_sunday);
-        assert_eq!(false, config.use_old_style_format);
+        assert!(!config.turn_off_highlight_today);
+        assert!(!config.display_julian_calendar);
+        assert!(!config.display_date_of_easter);
+        assert!(!config.display_julian_days);
+        assert!(!config.display_date_orthodox_easter);
+        assert!(!config.print_country_codes);
+        assert!(!config.print_number_of_week);
+        assert!(!config.previous_current_next_month);
+        assert!(!config.only_current_month);
+        assert!(!config.cal_mode);
+        assert!(!config.weeks_start_monday);
+        assert!(!config.weeks_start_sunday);
+        assert!(!config.use_old_style_format);
 
         assert_eq!(None, config.month);
         assert_eq!(None, config.country_code);
@@ -715,6 +883,7 @@ mod test {
             weeks_start_monday: false, \
             weeks_start_sunday: false, \
             use_old_style_format: false, \
+            span_months: false, \
             month: None, \
             country_code: None, \
             year: None, \
@@ -723,10 +892,84 @@ mod test {
             debug_current_date: None, \
             debug_highlighting: None, \
             first_week_has_at_least_days: None, \
+            months_per_row: None, \
+            months_count: None, \
+            output_format: Text, \
+            events_file: None, \
             unrecognized: [], \
             errors: [] \
             }",
             format!("{:?}", config)
         );
     }
+
+    #[test]
+    fn test_columns() {
+        let short = Config::new(&["rcal.exe".to_owned(), "-c".to_owned(), "4".to_owned()]);
+        let long = Config::new(&["rcal.exe".to_owned(), "--columns".to_owned(), "auto".to_owned()]);
+        let long_equals = Config::new(&["rcal.exe".to_owned(), "--columns=12".to_owned()]);
+        let invalid = Config::new(&["rcal.exe".to_owned(), "-c".to_owned(), "0".to_owned()]);
+
+        assert_eq!(Some(MonthsPerRow::Fixed(4)), short.months_per_row);
+        assert_eq!(Some(MonthsPerRow::Auto), long.months_per_row);
+        assert_eq!(Some(MonthsPerRow::Fixed(12)), long_equals.months_per_row);
+        assert_eq!(None, invalid.months_per_row);
+        assert_eq!(Some("rcal: not a valid number of columns 0".to_owned()), invalid.errors[0].message);
+    }
+
+    #[test]
+    fn test_months_and_span() {
+        let short = Config::new(&["rcal.exe".to_owned(), "-n".to_owned(), "5".to_owned(), "--span".to_owned()]);
+        let long = Config::new(&["rcal.exe".to_owned(), "--months=7".to_owned()]);
+        let invalid = Config::new(&["rcal.exe".to_owned(), "--months".to_owned(), "0".to_owned()]);
+
+        assert_eq!(Some(5), short.months_count);
+        assert!(short.span_months);
+        assert_eq!(Some(7), long.months_count);
+        assert!(!long.span_months);
+        assert_eq!(None, invalid.months_count);
+        assert_eq!(Some("rcal: option -n requires a number".to_owned()), invalid.errors[0].message);
+    }
+
+    #[test]
+    fn test_format() {
+        let long = Config::new(&["rcal.exe".to_owned(), "--format=json".to_owned()]);
+        let separate = Config::new(&["rcal.exe".to_owned(), "--format".to_owned(), "ics".to_owned(), "2024".to_owned()]);
+        let invalid = Config::new(&["rcal.exe".to_owned(), "--format=xml".to_owned()]);
+        let missing = Config::new(&["rcal.exe".to_owned(), "--format".to_owned()]);
+
+        assert_eq!(RenderStyle::Json, long.output_format);
+        assert_eq!(RenderStyle::Ics, separate.output_format);
+        assert_eq!(Some(2024), separate.year.map(|year| year.value()));
+        assert_eq!(RenderStyle::Text, invalid.output_format);
+        assert_eq!(Some("rcal: xml is not a valid format; use text, json or ics".to_owned()), invalid.errors[0].message);
+        assert_eq!(Some("rcal: option --format requires an argument".to_owned()), missing.errors[0].message);
+    }
+
+    #[test]
+    fn test_events() {
+        let long = Config::new(&["rcal.exe".to_owned(), "--events=holidays.txt".to_owned()]);
+        let separate = Config::new(&["rcal.exe".to_owned(), "--events".to_owned(), "holidays.txt".to_owned(), "2024".to_owned()]);
+        let missing = Config::new(&["rcal.exe".to_owned(), "--events".to_owned()]);
+
+        assert_eq!(Some("holidays.txt".to_owned()), long.events_file);
+        assert_eq!(Some("holidays.txt".to_owned()), separate.events_file);
+        assert_eq!(Some(2024), separate.year.map(|year| year.value()));
+        assert_eq!(Some("rcal: option --events requires an argument".to_owned()), missing.errors[0].message);
+    }
+
+    #[test]
+    fn test_end_of_options() {
+        let month_year = Config::new(&["rcal.exe".to_owned(), "--".to_owned(), "mar".to_owned(), "-44".to_owned()]);
+        let year = Config::new(&["rcal.exe".to_owned(), "-j".to_owned(), "--".to_owned(), "-44".to_owned()]);
+        let flag_after = Config::new(&["rcal.exe".to_owned(), "--".to_owned(), "-j".to_owned()]);
+
+        assert!(month_year.errors.is_empty());
+        assert_eq!(Some("mar".to_owned()), month_year.month);
+        assert_eq!(Some(-44), month_year.year.map(|year| year.value()));
+        assert!(year.display_julian_days);
+        assert_eq!(Some(-44), year.year.map(|year| year.value()));
+        assert!(!flag_after.display_julian_days);
+        assert_eq!(Some("rcal: not a valid year -j".to_owned()), flag_after.errors[0].message);
+    }
 }
@@ -1,5 +1,10 @@
 
-pub(crate) mod month;
-pub(crate) mod name;
-pub(crate) mod days;
-pub(crate) mod today;
+pub mod month;
+pub mod name;
+pub mod locale;
+pub mod days;
+pub mod today;
+pub mod easter;
+pub mod holiday;
+pub mod events;
+pub mod year;
@@ -0,0 +1,76 @@
+use chrono::{Days, NaiveDate};
+
+///
+/// Date of Easter Sunday for western churches, in the gregorian calendar.
+///
+pub fn western_easter(year: i32) -> NaiveDate {
+    /* anonymous gregorian algorithm (meeus/jones/butcher) */
+    let a = year.rem_euclid(19);
+    let b = year.div_euclid(100);
+    let c = year.rem_euclid(100);
+    let d = b.div_euclid(4);
+    let e = b.rem_euclid(4);
+    let f = (b + 8).div_euclid(25);
+    let g = (b - f + 1).div_euclid(3);
+    let h = (19 * a + b - d - g + 15).rem_euclid(30);
+    let i = c.div_euclid(4);
+    let k = c.rem_euclid(4);
+    let l = (32 + 2 * e + 2 * i - h - k).rem_euclid(7);
+    let m = (a + 11 * h + 22 * l).div_euclid(451);
+    let month = (h + l - 7 * m + 114).div_euclid(31);
+    let day = (h + l - 7 * m + 114).rem_euclid(31) + 1;
+
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32).unwrap()
+}
+
+///
+/// Date of Easter Sunday for the orthodox churches; computed in the julian calendar, and returned as a gregorian date.
+///
+pub fn orthodox_easter(year: i32) -> NaiveDate {
+    /* meeus' julian algorithm */
+    let a = year.rem_euclid(4);
+    let b = year.rem_euclid(7);
+    let c = year.rem_euclid(19);
+    let d = (19 * c + 15).rem_euclid(30);
+    let e = (2 * a + 4 * b - d + 34).rem_euclid(7);
+    let month = (d + e + 114).div_euclid(31);
+    let day = (d + e + 114).rem_euclid(31) + 1;
+
+    /* easter is always after february, so the calendars differ by this year's count of dropped leap days */
+    let julian = NaiveDate::from_ymd_opt(year, month as u32, day as u32).unwrap();
+    let difference = year.div_euclid(100) - year.div_euclid(400) - 2;
+    if difference >= 0 {
+        julian.checked_add_days(Days::new(difference as u64)).unwrap()
+    } else {
+        julian.checked_sub_days(Days::new(difference.unsigned_abs() as u64)).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::time::easter::{orthodox_easter, western_easter};
+    use chrono::NaiveDate;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_western() {
+        assert_eq!(date(1818, 3, 22), western_easter(1818));
+        assert_eq!(date(1943, 4, 25), western_easter(1943));
+        assert_eq!(date(2000, 4, 23), western_easter(2000));
+        assert_eq!(date(2024, 3, 31), western_easter(2024));
+        assert_eq!(date(2025, 4, 20), western_easter(2025));
+        assert_eq!(date(2038, 4, 25), western_easter(2038));
+    }
+
+    #[test]
+    fn test_orthodox() {
+        assert_eq!(date(2000, 4, 30), orthodox_easter(2000));
+        assert_eq!(date(2023, 4, 16), orthodox_easter(2023));
+        assert_eq!(date(2024, 5, 5), orthodox_easter(2024));
+        assert_eq!(date(2025, 4, 20), orthodox_easter(2025));
+        assert_eq!(date(2100, 5, 2), orthodox_easter(2100));
+    }
+}
@@ -0,0 +1,232 @@
+use crate::errors::{invalid_event, unreadable_events};
+use crate::time::easter::{orthodox_easter, western_easter};
+use crate::time::locale::Locale;
+use crate::time::name::month_arg_match;
+use chrono::{Datelike, NaiveDate, Weekday};
+use common::input::known_error::KnownError;
+
+const WILDCARD: &str = "*";
+const EASTER: &str = "easter";
+const PASKHA: &str = "paskha";
+const COMMENT: char = '#';
+const SEPARATOR: char = '\t';
+
+///
+/// When an event happens.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum Recurrence {
+    /// A day of a month; either can be a wildcard, as in `*/1` or `Jan *`.
+    Date { month: Option<u32>, day: Option<u32> },
+    /// Every week on this day, as in `Friday`.
+    Weekly(Weekday),
+    /// Days relative to western Easter Sunday, as in `Easter+2`.
+    Easter(i64),
+    /// Days relative to orthodox Easter Sunday, as in `Paskha-7`.
+    Paskha(i64),
+}
+
+///
+/// A single entry from an events file.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub recurrence: Recurrence,
+    pub description: String,
+}
+
+impl Recurrence {
+    pub fn matches(&self, date: NaiveDate) -> bool {
+        match self {
+            Recurrence::Date { month, day } => month.is_none_or(|m| m == date.month()) && day.is_none_or(|d| d == date.day()),
+            Recurrence::Weekly(weekday) => date.weekday() == *weekday,
+            Recurrence::Easter(offset) => (date - western_easter(date.year())).num_days() == *offset,
+            Recurrence::Paskha(offset) => (date - orthodox_easter(date.year())).num_days() == *offset,
+        }
+    }
+}
+
+///
+/// Read an events file, in BSD `calendar` syntax.
+///
+pub fn read_events(path: &str, locale: &Locale) -> Result<Vec<Event>, KnownError> {
+    let contents = std::fs::read_to_string(path).map_err(|error| unreadable_events(path, &error))?;
+    parse_events(path, &contents, locale)
+}
+
+///
+/// Parse events; each line is a date, a tab, and a description.  Lines starting with a tab continue the previous
+/// description, and blank lines and lines starting with `#` are ignored.
+///
+pub fn parse_events(path: &str, contents: &str, locale: &Locale) -> Result<Vec<Event>, KnownError> {
+    let mut events: Vec<Event> = vec![];
+
+    for (index, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with(COMMENT) {
+            continue;
+        }
+
+        /* continuation lines belong to the event before them */
+        if let Some(more) = line.strip_prefix(SEPARATOR) {
+            match events.last_mut() {
+                Some(event) => {
+                    event.description.push(' ');
+                    event.description.push_str(more.trim());
+                    continue;
+                },
+                None => return Err(invalid_event(path, index + 1, line)),
+            }
+        }
+
+        let (spec, description) = line.split_once(SEPARATOR).unwrap_or((line, ""));
+        let recurrence = parse_recurrence(spec.trim(), locale).ok_or_else(|| invalid_event(path, index + 1, spec.trim()))?;
+        events.push(Event { recurrence, description: description.trim().to_string() });
+    }
+
+    Ok(events)
+}
+
+fn parse_recurrence(spec: &str, locale: &Locale) -> Option<Recurrence> {
+    let lowered = spec.to_lowercase();
+
+    /* days relative to easter */
+    for (name, make) in [(EASTER, Recurrence::Easter as fn(i64) -> Recurrence), (PASKHA, Recurrence::Paskha)] {
+        if let Some(offset) = lowered.strip_prefix(name) {
+            return parse_offset(offset).map(make);
+        }
+    }
+
+    /* weekly events */
+    if let Ok(weekday) = lowered.parse::<Weekday>() {
+        return Some(Recurrence::Weekly(weekday));
+    }
+
+    /* month and day, as in 01/15, jan 15, 15 jan or a wildcard such as every first of the month */
+    let (month, day) = match spec.split_once('/') {
+        Some((month, day)) => (parse_wildcard(month, parse_month_number)?, parse_wildcard(day, parse_day)?),
+        None => match spec.split_whitespace().collect::<Vec<&str>>().as_slice() {
+            [day, month] if day.starts_with(|c: char| c.is_ascii_digit()) => (Some(parse_month_name(month, locale)?), Some(parse_day(day)?)),
+            [month, day] => (parse_wildcard(month, |m| parse_month_name(m, locale))?, parse_wildcard(day, parse_day)?),
+            _ => return None,
+        },
+    };
+
+    /* february 29th is fine; it just doesn't happen every year */
+    let valid = match (month, day) {
+        (Some(month), Some(day)) => NaiveDate::from_ymd_opt(2000, month, day).is_some(),
+        _ => true,
+    };
+    valid.then_some(Recurrence::Date { month, day })
+}
+
+fn parse_wildcard<T>(value: &str, parse: impl Fn(&str) -> Option<T>) -> Option<Option<T>> {
+    if value.trim() == WILDCARD {
+        Some(None)
+    } else {
+        parse(value.trim()).map(Some)
+    }
+}
+
+fn parse_month_number(value: &str) -> Option<u32> {
+    value.parse::<u32>().ok().filter(|month| (1..=12).contains(month))
+}
+
+fn parse_month_name(value: &str, locale: &Locale) -> Option<u32> {
+    if value.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    month_arg_match(value, locale).ok().map(|arg| arg.month as u32)
+}
+
+fn parse_day(value: &str) -> Option<u32> {
+    value.parse::<u32>().ok().filter(|day| (1..=31).contains(day))
+}
+
+fn parse_offset(value: &str) -> Option<i64> {
+    match value {
+        "" => Some(0),
+        _ if value.starts_with(['+', '-']) => value.parse::<i64>().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::time::events::{parse_events, Event, Recurrence};
+    use crate::time::locale::{Locale, ENGLISH};
+    use chrono::{NaiveDate, Weekday};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn recurrence(spec: &str) -> Recurrence {
+        parse_events("test", &format!("{}\tsomething", spec), &ENGLISH).unwrap().remove(0).recurrence
+    }
+
+    #[test]
+    fn test_specs() {
+        assert_eq!(Recurrence::Date { month: Some(1), day: Some(15) }, recurrence("Jan 15"));
+        assert_eq!(Recurrence::Date { month: Some(1), day: Some(15) }, recurrence("January 15"));
+        assert_eq!(Recurrence::Date { month: Some(1), day: Some(15) }, recurrence("15 jan"));
+        assert_eq!(Recurrence::Date { month: Some(1), day: Some(15) }, recurrence("01/15"));
+        assert_eq!(Recurrence::Date { month: None, day: Some(1) }, recurrence("*/1"));
+        assert_eq!(Recurrence::Date { month: Some(12), day: None }, recurrence("Dec *"));
+        assert_eq!(Recurrence::Date { month: Some(2), day: Some(29) }, recurrence("02/29"));
+        assert_eq!(Recurrence::Weekly(Weekday::Fri), recurrence("Friday"));
+        assert_eq!(Recurrence::Weekly(Weekday::Tue), recurrence("tue"));
+        assert_eq!(Recurrence::Easter(0), recurrence("Easter"));
+        assert_eq!(Recurrence::Easter(2), recurrence("Easter+2"));
+        assert_eq!(Recurrence::Easter(-47), recurrence("easter-47"));
+        assert_eq!(Recurrence::Paskha(-7), recurrence("Paskha-7"));
+    }
+
+    #[test]
+    fn test_localized_month() {
+        let events = parse_events("test", "15 März\tIden", Locale::find("de_DE")).unwrap();
+
+        assert_eq!(Recurrence::Date { month: Some(3), day: Some(15) }, events[0].recurrence);
+    }
+
+    #[test]
+    fn test_file_layout() {
+        let contents = "# holidays\n\nJan 1\tNew Year's Day\n*/15\tpay day\n\tdon't spend it all\nFriday\n";
+
+        let events = parse_events("test", contents, &ENGLISH).unwrap();
+
+        assert_eq!(vec![
+            Event { recurrence: Recurrence::Date { month: Some(1), day: Some(1) }, description: "New Year's Day".to_string() },
+            Event { recurrence: Recurrence::Date { month: None, day: Some(15) }, description: "pay day don't spend it all".to_string() },
+            Event { recurrence: Recurrence::Weekly(Weekday::Fri), description: String::new() },
+        ], events);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let cases = [
+            ("Jan 32\tx", "rcal: events file test, line 1: can't read date 'Jan 32'"),
+            ("02/30\tx", "rcal: events file test, line 1: can't read date '02/30'"),
+            ("13/01\tx", "rcal: events file test, line 1: can't read date '13/01'"),
+            ("Smarch 1\tx", "rcal: events file test, line 1: can't read date 'Smarch 1'"),
+            ("Easter+x\tx", "rcal: events file test, line 1: can't read date 'Easter+x'"),
+            ("Jan 1\tx\n\nsoon\tx", "rcal: events file test, line 3: can't read date 'soon'"),
+            ("\torphan", "rcal: events file test, line 1: can't read date '\torphan'"),
+        ];
+
+        for (contents, expected) in cases {
+            assert_eq!(Some(expected.to_owned()), parse_events("test", contents, &ENGLISH).unwrap_err().message, "contents: [{}]", contents);
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(Recurrence::Date { month: None, day: Some(1) }.matches(date(2024, 7, 1)));
+        assert!(!Recurrence::Date { month: None, day: Some(1) }.matches(date(2024, 7, 2)));
+        assert!(Recurrence::Date { month: Some(7), day: None }.matches(date(2024, 7, 19)));
+        assert!(Recurrence::Weekly(Weekday::Fri).matches(date(2024, 7, 19)));
+        assert!(!Recurrence::Weekly(Weekday::Fri).matches(date(2024, 7, 20)));
+        assert!(Recurrence::Easter(2).matches(date(2024, 4, 2)));
+        assert!(Recurrence::Easter(-2).matches(date(2025, 4, 18)));
+        assert!(Recurrence::Paskha(0).matches(date(2024, 5, 5)));
+    }
+}
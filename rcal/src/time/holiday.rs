@@ -0,0 +1,47 @@
+use crate::time::easter::{orthodox_easter, western_easter};
+use crate::time::month::Month;
+use chrono::{Datelike, NaiveDate};
+
+///
+/// A named, all-day holiday.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Holiday {
+    pub date: NaiveDate,
+    pub name: &'static str,
+}
+
+///
+/// Holidays that fall in the given month, in date order.
+///
+pub fn holidays_in(month: &Month) -> Vec<Holiday> {
+    let year = month.year.value();
+    let mut holidays: Vec<Holiday> = [
+        Holiday { date: western_easter(year), name: "Easter" },
+        Holiday { date: orthodox_easter(year), name: "Orthodox Easter" },
+    ]
+        .into_iter()
+        .filter(|holiday| holiday.date.month() == month.month as u32)
+        .collect();
+    holidays.sort_by_key(|holiday| holiday.date);
+    holidays
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::time::holiday::holidays_in;
+    use crate::time::month::Month;
+
+    #[test]
+    fn test_holidays_in() {
+        let names = |month, year| holidays_in(&Month::new(month, year).unwrap())
+            .iter()
+            .map(|holiday| format!("{} {}", holiday.date, holiday.name))
+            .collect::<Vec<String>>();
+
+        assert_eq!(vec!["2024-03-31 Easter"], names(3, 2024));
+        assert_eq!(vec!["2024-05-05 Orthodox Easter"], names(5, 2024));
+        assert_eq!(vec!["2025-04-20 Easter", "2025-04-20 Orthodox Easter"], names(4, 2025));
+        assert!(names(1, 2024).is_empty());
+    }
+}
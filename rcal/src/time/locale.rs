@@ -0,0 +1,239 @@
+use common::time::strftime::{MONTHS, MONTH_ABBREVIATIONS};
+
+///
+/// Month and weekday names for a single language.
+///
+/// Weekdays always start on Sunday, and are the short forms shown above each column of days.
+///
+#[derive(Debug, PartialEq)]
+pub struct Locale {
+    pub code: &'static str,
+    pub months: [&'static str; 12],
+    pub abbreviations: [&'static str; 12],
+    pub weekdays: [&'static str; 7],
+}
+
+/* environment variables that pick the locale, in order of precedence */
+const LOCALE_VARIABLES: [&str; 3] = ["LC_ALL", "LC_TIME", "LANG"];
+
+pub const ENGLISH: Locale = Locale {
+    code: "en",
+    months: MONTHS,
+    abbreviations: MONTH_ABBREVIATIONS,
+    weekdays: ["Su", "Mo", "Tu", "We", "Th", "Fr", "Sa"],
+};
+
+const GERMAN: Locale = Locale {
+    code: "de",
+    months: ["Januar", "Februar", "März", "April", "Mai", "Juni", "Juli", "August", "September", "Oktober", "November", "Dezember"],
+    abbreviations: ["Jan", "Feb", "Mär", "Apr", "Mai", "Jun", "Jul", "Aug", "Sep", "Okt", "Nov", "Dez"],
+    weekdays: ["So", "Mo", "Di", "Mi", "Do", "Fr", "Sa"],
+};
+
+const FRENCH: Locale = Locale {
+    code: "fr",
+    months: ["janvier", "février", "mars", "avril", "mai", "juin", "juillet", "août", "septembre", "octobre", "novembre", "décembre"],
+    abbreviations: ["janv", "févr", "mars", "avr", "mai", "juin", "juil", "août", "sept", "oct", "nov", "déc"],
+    weekdays: ["di", "lu", "ma", "me", "je", "ve", "sa"],
+};
+
+const SPANISH: Locale = Locale {
+    code: "es",
+    months: ["enero", "febrero", "marzo", "abril", "mayo", "junio", "julio", "agosto", "septiembre", "octubre", "noviembre", "diciembre"],
+    abbreviations: ["ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sep", "oct", "nov", "dic"],
+    weekdays: ["do", "lu", "ma", "mi", "ju", "vi", "sá"],
+};
+
+const ITALIAN: Locale = Locale {
+    code: "it",
+    months: ["gennaio", "febbraio", "marzo", "aprile", "maggio", "giugno", "luglio", "agosto", "settembre", "ottobre", "novembre", "dicembre"],
+    abbreviations: ["gen", "feb", "mar", "apr", "mag", "giu", "lug", "ago", "set", "ott", "nov", "dic"],
+    weekdays: ["do", "lu", "ma", "me", "gi", "ve", "sa"],
+};
+
+const PORTUGUESE: Locale = Locale {
+    code: "pt",
+    months: ["janeiro", "fevereiro", "março", "abril", "maio", "junho", "julho", "agosto", "setembro", "outubro", "novembro", "dezembro"],
+    abbreviations: ["jan", "fev", "mar", "abr", "mai", "jun", "jul", "ago", "set", "out", "nov", "dez"],
+    weekdays: ["do", "se", "te", "qu", "qu", "se", "sá"],
+};
+
+const DUTCH: Locale = Locale {
+    code: "nl",
+    months: ["januari", "februari", "maart", "april", "mei", "juni", "juli", "augustus", "september", "oktober", "november", "december"],
+    abbreviations: ["jan", "feb", "mrt", "apr", "mei", "jun", "jul", "aug", "sep", "okt", "nov", "dec"],
+    weekdays: ["zo", "ma", "di", "wo", "do", "vr", "za"],
+};
+
+const SWEDISH: Locale = Locale {
+    code: "sv",
+    months: ["januari", "februari", "mars", "april", "maj", "juni", "juli", "augusti", "september", "oktober", "november", "december"],
+    abbreviations: ["jan", "feb", "mar", "apr", "maj", "jun", "jul", "aug", "sep", "okt", "nov", "dec"],
+    weekdays: ["sö", "må", "ti", "on", "to", "fr", "lö"],
+};
+
+const DANISH: Locale = Locale {
+    code: "da",
+    months: ["januar", "februar", "marts", "april", "maj", "juni", "juli", "august", "september", "oktober", "november", "december"],
+    abbreviations: ["jan", "feb", "mar", "apr", "maj", "jun", "jul", "aug", "sep", "okt", "nov", "dec"],
+    weekdays: ["sø", "ma", "ti", "on", "to", "fr", "lø"],
+};
+
+const NORWEGIAN: Locale = Locale {
+    code: "nb",
+    months: ["januar", "februar", "mars", "april", "mai", "juni", "juli", "august", "september", "oktober", "november", "desember"],
+    abbreviations: ["jan", "feb", "mar", "apr", "mai", "jun", "jul", "aug", "sep", "okt", "nov", "des"],
+    weekdays: ["sø", "ma", "ti", "on", "to", "fr", "lø"],
+};
+
+const FINNISH: Locale = Locale {
+    code: "fi",
+    months: ["tammikuu", "helmikuu", "maaliskuu", "huhtikuu", "toukokuu", "kesäkuu", "heinäkuu", "elokuu", "syyskuu", "lokakuu", "marraskuu", "joulukuu"],
+    abbreviations: ["tammi", "helmi", "maalis", "huhti", "touko", "kesä", "heinä", "elo", "syys", "loka", "marras", "joulu"],
+    weekdays: ["su", "ma", "ti", "ke", "to", "pe", "la"],
+};
+
+const POLISH: Locale = Locale {
+    code: "pl",
+    months: ["styczeń", "luty", "marzec", "kwiecień", "maj", "czerwiec", "lipiec", "sierpień", "wrzesień", "październik", "listopad", "grudzień"],
+    abbreviations: ["sty", "lut", "mar", "kwi", "maj", "cze", "lip", "sie", "wrz", "paź", "lis", "gru"],
+    weekdays: ["nd", "pn", "wt", "śr", "cz", "pt", "so"],
+};
+
+const CZECH: Locale = Locale {
+    code: "cs",
+    months: ["leden", "únor", "březen", "duben", "květen", "červen", "červenec", "srpen", "září", "říjen", "listopad", "prosinec"],
+    abbreviations: ["led", "úno", "bře", "dub", "kvě", "čvn", "čvc", "srp", "zář", "říj", "lis", "pro"],
+    weekdays: ["ne", "po", "út", "st", "čt", "pá", "so"],
+};
+
+const TURKISH: Locale = Locale {
+    code: "tr",
+    months: ["Ocak", "Şubat", "Mart", "Nisan", "Mayıs", "Haziran", "Temmuz", "Ağustos", "Eylül", "Ekim", "Kasım", "Aralık"],
+    abbreviations: ["Oca", "Şub", "Mar", "Nis", "May", "Haz", "Tem", "Ağu", "Eyl", "Eki", "Kas", "Ara"],
+    weekdays: ["Pz", "Pt", "Sa", "Ça", "Pe", "Cu", "Ct"],
+};
+
+const RUSSIAN: Locale = Locale {
+    code: "ru",
+    months: ["Январь", "Февраль", "Март", "Апрель", "Май", "Июнь", "Июль", "Август", "Сентябрь", "Октябрь", "Ноябрь", "Декабрь"],
+    abbreviations: ["янв", "фев", "мар", "апр", "май", "июн", "июл", "авг", "сен", "окт", "ноя", "дек"],
+    weekdays: ["Вс", "Пн", "Вт", "Ср", "Чт", "Пт", "Сб"],
+};
+
+const JAPANESE: Locale = Locale {
+    code: "ja",
+    months: ["1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月"],
+    abbreviations: ["1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月"],
+    weekdays: ["日", "月", "火", "水", "木", "金", "土"],
+};
+
+const CHINESE: Locale = Locale {
+    code: "zh",
+    months: ["一月", "二月", "三月", "四月", "五月", "六月", "七月", "八月", "九月", "十月", "十一月", "十二月"],
+    abbreviations: ["1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月"],
+    weekdays: ["日", "一", "二", "三", "四", "五", "六"],
+};
+
+const KOREAN: Locale = Locale {
+    code: "ko",
+    months: ["1월", "2월", "3월", "4월", "5월", "6월", "7월", "8월", "9월", "10월", "11월", "12월"],
+    abbreviations: ["1월", "2월", "3월", "4월", "5월", "6월", "7월", "8월", "9월", "10월", "11월", "12월"],
+    weekdays: ["일", "월", "화", "수", "목", "금", "토"],
+};
+
+static LOCALES: [&Locale; 19] = [
+    &ENGLISH, &GERMAN, &FRENCH, &SPANISH, &ITALIAN, &PORTUGUESE, &DUTCH, &SWEDISH, &DANISH, &NORWEGIAN,
+    &FINNISH, &POLISH, &CZECH, &TURKISH, &RUSSIAN, &JAPANESE, &CHINESE, &KOREAN,
+    /* norwegian is commonly configured as "no" as well as "nb" */
+    &Locale { code: "no", ..NORWEGIAN },
+];
+
+impl Locale {
+    ///
+    /// Pick the locale named by `LC_ALL`, `LC_TIME` or `LANG`, falling back to [ENGLISH].
+    ///
+    pub fn from_environment() -> &'static Locale {
+        Self::from_variables(|name| std::env::var(name).ok())
+    }
+
+    fn from_variables(lookup: impl Fn(&str) -> Option<String>) -> &'static Locale {
+        LOCALE_VARIABLES.iter()
+            .filter_map(|name| lookup(name))
+            .find(|value| !value.is_empty())
+            .map(|value| Self::find(&value))
+            .unwrap_or(&ENGLISH)
+    }
+
+    ///
+    /// Find a locale by a name such as `de_DE.UTF-8`, `pt_BR` or `ja`; unknown names, `C` and `POSIX` give [ENGLISH].
+    ///
+    pub fn find(name: &str) -> &'static Locale {
+        /* strip off the encoding and modifier; only the language matters for names */
+        let language = name
+            .split(['.', '@'])
+            .next()
+            .unwrap_or_default()
+            .split(['_', '-'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+
+        LOCALES.iter()
+            .find(|locale| locale.code == language)
+            .copied()
+            .unwrap_or(&ENGLISH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::time::locale::{Locale, ENGLISH, LOCALES};
+
+    #[test]
+    fn test_find() {
+        assert_eq!("de", Locale::find("de_DE.UTF-8").code);
+        assert_eq!("pt", Locale::find("pt_BR").code);
+        assert_eq!("fr", Locale::find("fr_FR@euro").code);
+        assert_eq!("ja", Locale::find("ja").code);
+        assert_eq!("no", Locale::find("no_NO.utf8").code);
+        assert_eq!(&ENGLISH, Locale::find("C"));
+        assert_eq!(&ENGLISH, Locale::find("POSIX"));
+        assert_eq!(&ENGLISH, Locale::find("xx_YY"));
+        assert_eq!(&ENGLISH, Locale::find(""));
+    }
+
+    #[test]
+    fn test_precedence() {
+        let all = |name: &str| match name {
+            "LC_ALL" => Some("fr_FR.UTF-8".to_owned()),
+            "LC_TIME" => Some("de_DE.UTF-8".to_owned()),
+            _ => Some("es_ES.UTF-8".to_owned()),
+        };
+        let time = |name: &str| match name {
+            "LC_ALL" => Some(String::new()),
+            "LC_TIME" => Some("de_DE.UTF-8".to_owned()),
+            _ => Some("es_ES.UTF-8".to_owned()),
+        };
+        let lang = |name: &str| if name == "LANG" { Some("es_ES.UTF-8".to_owned()) } else { None };
+
+        assert_eq!("fr", Locale::from_variables(all).code);
+        assert_eq!("de", Locale::from_variables(time).code);
+        assert_eq!("es", Locale::from_variables(lang).code);
+        assert_eq!("en", Locale::from_variables(|_| None).code);
+    }
+
+    #[test]
+    fn test_weekdays_fit_their_column() {
+        use unicode_width::UnicodeWidthStr;
+
+        for locale in LOCALES {
+            for weekday in locale.weekdays {
+                assert!(weekday.width() <= 2, "{}: {}", locale.code, weekday);
+            }
+            for month in locale.months {
+                assert!(month.width() <= 16, "{}: {}", locale.code, month);
+            }
+        }
+    }
+}
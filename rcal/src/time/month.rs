@@ -1,37 +1,42 @@
 use std::fmt;
+use common::input::known_error::KnownError;
+use crate::errors::invalid_month;
+use crate::time::year::Year;
 
-#[derive(Copy, Clone, Eq, Ord, PartialEq, PartialOrd)]
+#[derive(Copy, Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
 pub struct Month {
-    pub year: u16,
+    pub year: Year,
     pub month: u16,
 }
 
 impl Month {
-    pub fn new(month_input: u16, year_input: u16) -> Month {
+    pub fn new(month_input: u16, year_input: i32) -> Result<Month, KnownError> {
         if !(1..=12).contains(&month_input) {
-            panic!("bad month input: {}", month_input);
+            return Err(invalid_month(&month_input.to_string()));
         }
 
-        if year_input < 1 {
-            panic!("bad year input: {}", year_input);
-        }
-
-        Month { month: month_input, year: year_input }
+        Ok(Month { month: month_input, year: Year::new(year_input)? })
     }
 
-    pub fn prev(&self) -> Month {
+    ///
+    /// The month before this one; [None] before January of the first supported year.
+    ///
+    pub fn prev(&self) -> Option<Month> {
         if self.month == 1 {
-            Month { month: 12, year: self.year - 1 }
+            Some(Month { month: 12, year: self.year.prev()? })
         } else {
-            Month { month: self.month - 1, year: self.year }
+            Some(Month { month: self.month - 1, year: self.year })
         }
     }
 
-    pub fn next(&self) -> Month {
+    ///
+    /// The month after this one; [None] after December of the last supported year.
+    ///
+    pub fn next(&self) -> Option<Month> {
         if self.month == 12 {
-            Month { month: 1, year: self.year + 1 }
+            Some(Month { month: 1, year: self.year.next()? })
         } else {
-            Month { month: self.month + 1, year: self.year }
+            Some(Month { month: self.month + 1, year: self.year })
         }
     }
 }
@@ -48,17 +53,15 @@ mod tests_sort {
 
     #[test]
     fn test_month_config_vector_sort() {
-        let mut unsorted = vec!(
-            Month::new(4, 2023),
-            Month::new(3, 2021),
-            Month::new(2, 2022),
-            Month::new(1, 2024),
-        );
+        let mut unsorted = [Month::new(4, 2023).unwrap(),
+            Month::new(3, 2021).unwrap(),
+            Month::new(2, 2022).unwrap(),
+            Month::new(1, 2024).unwrap()];
 
         unsorted.sort();
 
         assert_eq!(4, unsorted.len());
-        assert_eq!("3/2021", format!("{}", unsorted.get(0).unwrap()));
+        assert_eq!("3/2021", format!("{}", unsorted.first().unwrap()));
         assert_eq!("2/2022", format!("{}", unsorted.get(1).unwrap()));
         assert_eq!("4/2023", format!("{}", unsorted.get(2).unwrap()));
         assert_eq!("1/2024", format!("{}", unsorted.get(3).unwrap()));
@@ -71,8 +74,8 @@ mod tests_next {
 
     #[test]
     fn test_next_month1() {
-        let input = Month::new(1, 2020);
-        let output = input.next();
+        let input = Month::new(1, 2020).unwrap();
+        let output = input.next().unwrap();
 
         assert_eq!(2, output.month);
         assert_eq!(2020, output.year);
@@ -80,8 +83,8 @@ mod tests_next {
 
     #[test]
     fn test_next_month6() {
-        let input = Month::new(6, 2020);
-        let output = input.next();
+        let input = Month::new(6, 2020).unwrap();
+        let output = input.next().unwrap();
 
         assert_eq!(7, output.month);
         assert_eq!(2020, output.year);
@@ -89,8 +92,8 @@ mod tests_next {
 
     #[test]
     fn test_next_month12() {
-        let input = Month::new(12, 2020);
-        let output = input.next();
+        let input = Month::new(12, 2020).unwrap();
+        let output = input.next().unwrap();
 
         assert_eq!(1, output.month);
         assert_eq!(2021, output.year);
@@ -103,8 +106,8 @@ mod tests_prev {
 
     #[test]
     fn test_prev_month1() {
-        let input = Month::new(1, 2020);
-        let output = input.prev();
+        let input = Month::new(1, 2020).unwrap();
+        let output = input.prev().unwrap();
 
         assert_eq!(12, output.month);
         assert_eq!(2019, output.year);
@@ -112,8 +115,8 @@ mod tests_prev {
 
     #[test]
     fn test_prev_month6() {
-        let input = Month::new(6, 2020);
-        let output = input.prev();
+        let input = Month::new(6, 2020).unwrap();
+        let output = input.prev().unwrap();
 
         assert_eq!(5, output.month);
         assert_eq!(2020, output.year);
@@ -121,10 +124,36 @@ mod tests_prev {
 
     #[test]
     fn test_prev_month12() {
-        let input = Month::new(12, 2020);
-        let output = input.prev();
+        let input = Month::new(12, 2020).unwrap();
+        let output = input.prev().unwrap();
 
         assert_eq!(11, output.month);
         assert_eq!(2020, output.year);
     }
 }
+
+#[cfg(test)]
+mod tests_bounds {
+    use crate::time::month::Month;
+
+    #[test]
+    fn test_new_invalid() {
+        assert_eq!(Some("rcal: 0 is not a valid month".to_owned()), Month::new(0, 2020).unwrap_err().message);
+        assert_eq!(Some("rcal: 13 is not a valid month".to_owned()), Month::new(13, 2020).unwrap_err().message);
+        assert_eq!(Some("rcal: year -10000 not in range -9999..9999".to_owned()), Month::new(1, -10000).unwrap_err().message);
+        assert_eq!(Some("rcal: year 10000 not in range -9999..9999".to_owned()), Month::new(1, 10000).unwrap_err().message);
+    }
+
+    #[test]
+    fn test_prev_first_month() {
+        assert!(Month::new(1, -9999).unwrap().prev().is_none());
+        assert_eq!("12/0", format!("{}", Month::new(1, 1).unwrap().prev().unwrap()));
+        assert_eq!("12/-1", format!("{}", Month::new(1, 0).unwrap().prev().unwrap()));
+    }
+
+    #[test]
+    fn test_next_last_month() {
+        assert!(Month::new(12, 9999).unwrap().next().is_none());
+        assert_eq!("12/9999", format!("{}", Month::new(11, 9999).unwrap().next().unwrap()));
+    }
+}
@@ -1,12 +1,12 @@
+use crate::errors::{ambiguous_month, invalid_month};
+use crate::time::locale::{Locale, ENGLISH};
 use crate::time::month::Month;
+use crate::time::year::Year;
+use common::input::known_error::KnownError;
 
-const MONTH_DISPLAY_NAMES: [&str; 12] = ["January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November", "December"];
-const MONTH_FULL_ARGS: [&str; 12] = [    "january", "february", "march", "april", "may", "june", "july", "august", "september", "october", "november", "december"];
-const MONTH_ABBR_ARGS: [&str; 12] = [    "jan",     "feb",      "mar",   "apr",   "may", "jun",  "jul",  "aug",    "sep",       "oct",     "nov",      "dec"];
-
-pub fn month_display_name(config: &Month, include_year: bool) -> String {
+pub fn month_display_name(config: &Month, include_year: bool, locale: &Locale) -> String {
     let index: usize = (config.month - 1) as usize;
-    let month = MONTH_DISPLAY_NAMES[index];
+    let month = locale.months[index];
 
     if include_year {
         format!("{} {}", month, config.year)
@@ -15,64 +15,271 @@ pub fn month_display_name(config: &Month, include_year: bool) -> String {
     }
 }
 
-pub fn month_arg_match(month_arg: &str) -> Option<u16> {
-    /* look for full month names */
-    for (index, value) in MONTH_FULL_ARGS.iter().enumerate() {
-        if month_arg.to_lowercase() == *value {
-            return Some((index+1) as u16);
+///
+/// Whether a month argument asks for a specific month, or the next/previous occurrence of it.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MonthOffset {
+    Exact,
+    Following,
+    Previous,
+}
+
+///
+/// A parsed month argument, such as `march`, `3`, `mar` or `3f`.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonthArg {
+    pub month: u16,
+    pub offset: MonthOffset,
+}
+
+impl MonthArg {
+    ///
+    /// The year to display this month in, when no year is given on the command line.
+    ///
+    pub fn year_relative_to(&self, today: &Month) -> Year {
+        match self.offset {
+            MonthOffset::Exact => today.year,
+            MonthOffset::Following if self.month <= today.month => today.year.next().unwrap_or(today.year),
+            MonthOffset::Previous if self.month >= today.month => today.year.prev().unwrap_or(today.year),
+            _ => today.year,
         }
     }
+}
+
+///
+/// Parse a month argument; names are accepted in the given locale, and always in english.
+///
+pub fn month_arg_match(month_arg: &str, locale: &Locale) -> Result<MonthArg, KnownError> {
+    let lowered = month_arg.to_lowercase();
 
-    /* look for partial month names */
-    for (index, value) in MONTH_ABBR_ARGS.iter().enumerate() {
-        if month_arg.to_lowercase() == *value {
-            return Some((index+1) as u16);
+    /* look for full month names, then abbreviated month names; some locales name months with digits, so these go first */
+    for names in [&locale.months, &locale.abbreviations, &ENGLISH.months, &ENGLISH.abbreviations] {
+        if let Some(index) = names.iter().position(|name| name.to_lowercase() == lowered) {
+            return Ok(MonthArg { month: (index + 1) as u16, offset: MonthOffset::Exact });
         }
     }
 
-    /* none found */
-    None
+    /* numeric months, optionally followed by 'f' or 'p' */
+    if lowered.starts_with(|c: char| c.is_ascii_digit()) {
+        return numeric_month_arg(month_arg, &lowered);
+    }
+
+    /* look for unambiguous prefixes of full month names */
+    let mut candidates: Vec<usize> = [&locale.months, &ENGLISH.months].iter()
+        .flat_map(|names| names.iter().enumerate())
+        .filter(|(_, name)| !lowered.is_empty() && name.to_lowercase().starts_with(&lowered))
+        .map(|(index, _)| index)
+        .collect();
+    candidates.sort_unstable();
+    candidates.dedup();
+    match candidates.as_slice() {
+        [index] => Ok(MonthArg { month: (index + 1) as u16, offset: MonthOffset::Exact }),
+        [] => Err(invalid_month(month_arg)),
+        _ => Err(ambiguous_month(month_arg)),
+    }
+}
+
+fn numeric_month_arg(month_arg: &str, lowered: &str) -> Result<MonthArg, KnownError> {
+    let digits_end = lowered.find(|c: char| !c.is_ascii_digit()).unwrap_or(lowered.len());
+    let (digits, suffix) = lowered.split_at(digits_end);
+    let offset = match suffix {
+        "" => MonthOffset::Exact,
+        "f" => MonthOffset::Following,
+        "p" => MonthOffset::Previous,
+        _ => return Err(invalid_month(month_arg)),
+    };
+
+    match digits.parse::<u16>() {
+        Ok(month) if (1..=12).contains(&month) => Ok(MonthArg { month, offset }),
+        _ => Err(invalid_month(month_arg)),
+    }
+}
+
+///
+/// Month abbreviations, lowercased once so they can be matched against the start of many lines,
+/// as `sort -M` does.  Abbreviations in the given locale are tried as well as english ones.
+///
+pub struct MonthAbbreviations {
+    /* month numbers and lowercased names, longest first, so the first match is the longest */
+    names: Vec<(u16, String)>,
 }
 
-// todo: unit test
-pub fn month_num_to_name(month_num: u16) -> String {
-    if !(1..=12).contains(&month_num) {
-        panic!("invalid month: [{}]", month_num);
+impl MonthAbbreviations {
+    pub fn new(locale: &Locale) -> MonthAbbreviations {
+        let mut names: Vec<(u16, String)> = [&locale.abbreviations, &ENGLISH.abbreviations].iter()
+            .flat_map(|names| names.iter().enumerate())
+            .map(|(index, name)| ((index + 1) as u16, name.to_lowercase()))
+            .collect();
+        names.sort_by(|(a_month, a), (b_month, b)| b.len().cmp(&a.len()).then_with(|| (a_month, a).cmp(&(b_month, b))));
+        names.dedup();
+        MonthAbbreviations { names }
+    }
+
+    ///
+    /// Find the month whose abbreviation starts `text`, ignoring case and leading blanks: `JAN` and
+    /// `January` are both january.  Text that isn't UTF-8 is only looked at up to where it stops
+    /// being so.
+    ///
+    pub fn find(&self, text: &[u8]) -> Option<u16> {
+        let blanks = text.iter().take_while(|&&byte| byte == b' ' || byte == b'\t').count();
+        let text = match std::str::from_utf8(&text[blanks..]) {
+            Ok(text) => text,
+            Err(error) => std::str::from_utf8(&text[blanks..blanks + error.valid_up_to()]).unwrap_or_default(),
+        };
+
+        self.names.iter()
+            .find(|(_, name)| starts_with_lowercase(text, name))
+            .map(|&(month, _)| month)
     }
-    
-    MONTH_ABBR_ARGS[month_num as usize - 1].to_string()
+}
+
+///
+/// Whether `text`, lowercased, starts with `prefix`, which already is.  Text that's ASCII as far
+/// as the prefix goes, as most is, is compared byte by byte.
+///
+fn starts_with_lowercase(text: &str, prefix: &str) -> bool {
+    let head = &text.as_bytes()[..prefix.len().min(text.len())];
+    if head.is_ascii() {
+        return head.eq_ignore_ascii_case(prefix.as_bytes());
+    }
+    let mut lowered = text.chars().flat_map(char::to_lowercase);
+    prefix.chars().all(|c| lowered.next() == Some(c))
 }
 
 #[cfg(test)]
 mod happy_path_tests {
     use crate::time::month::Month;
+    use crate::time::locale::ENGLISH;
     use crate::time::name::month_display_name;
 
     #[test]
     fn test_month_1() {
-        assert_eq!("January 2024", month_display_name(&Month{month: 1, year: 2024}, true));
+        assert_eq!("January 2024", month_display_name(&Month::new(1, 2024).unwrap(), true, &ENGLISH));
     }
 
     #[test]
     fn test_month_12() {
-        assert_eq!("December", month_display_name(&Month { month: 12, year: 2024 }, false));
+        assert_eq!("December", month_display_name(&Month::new(12, 2024).unwrap(), false, &ENGLISH));
     }
 }
 
 #[cfg(test)]
 mod panic_tests {
     use crate::time::month::Month;
+    use crate::time::locale::ENGLISH;
     use crate::time::name::month_display_name;
 
     #[test]
     #[should_panic]
     fn test_month_0() {
-        month_display_name(&Month{month: 0, year: 2024}, true);
+        month_display_name(&Month { month: 0, ..Month::new(1, 2024).unwrap() }, true, &ENGLISH);
     }
 
     #[test]
     #[should_panic]
     fn test_month_13() {
-        month_display_name(&Month{month: 13, year: 2024}, false);
+        month_display_name(&Month { month: 13, ..Month::new(1, 2024).unwrap() }, false, &ENGLISH);
+    }
+}
+
+#[cfg(test)]
+mod month_arg_tests {
+    use crate::time::month::Month;
+    use crate::time::locale::{Locale, ENGLISH};
+    use crate::time::name::{month_arg_match, MonthAbbreviations, MonthArg, MonthOffset};
+
+    fn exact(month: u16) -> MonthArg {
+        MonthArg { month, offset: MonthOffset::Exact }
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(exact(1), month_arg_match("january", &ENGLISH).unwrap());
+        assert_eq!(exact(1), month_arg_match("Jan", &ENGLISH).unwrap());
+        assert_eq!(exact(5), month_arg_match("MAY", &ENGLISH).unwrap());
+        assert_eq!(exact(9), month_arg_match("sept", &ENGLISH).unwrap());
+        assert_eq!(exact(10), month_arg_match("o", &ENGLISH).unwrap());
+        assert_eq!(exact(12), month_arg_match("decem", &ENGLISH).unwrap());
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(exact(1), month_arg_match("1", &ENGLISH).unwrap());
+        assert_eq!(exact(3), month_arg_match("03", &ENGLISH).unwrap());
+        assert_eq!(exact(12), month_arg_match("12", &ENGLISH).unwrap());
+        assert_eq!(MonthArg { month: 4, offset: MonthOffset::Following }, month_arg_match("4f", &ENGLISH).unwrap());
+        assert_eq!(MonthArg { month: 4, offset: MonthOffset::Previous }, month_arg_match("4P", &ENGLISH).unwrap());
+    }
+
+    #[test]
+    fn test_invalid() {
+        let cases = [
+            ("0", "rcal: 0 is not a valid month"),
+            ("13", "rcal: 13 is not a valid month"),
+            ("4x", "rcal: 4x is not a valid month"),
+            ("4ff", "rcal: 4ff is not a valid month"),
+            ("", "rcal:  is not a valid month"),
+            ("smarch", "rcal: smarch is not a valid month"),
+            ("ju", "rcal: ju is ambiguous; it could be more than one month"),
+            ("ma", "rcal: ma is ambiguous; it could be more than one month"),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(Some(expected.to_owned()), month_arg_match(input, &ENGLISH).unwrap_err().message, "input: [{}]", input);
+        }
+    }
+
+    #[test]
+    fn test_relative_years() {
+        let today = Month::new(6, 2024).unwrap();
+
+        assert_eq!(2024, month_arg_match("4", &ENGLISH).unwrap().year_relative_to(&today));
+        assert_eq!(2025, month_arg_match("4f", &ENGLISH).unwrap().year_relative_to(&today));
+        assert_eq!(2025, month_arg_match("6f", &ENGLISH).unwrap().year_relative_to(&today));
+        assert_eq!(2024, month_arg_match("8f", &ENGLISH).unwrap().year_relative_to(&today));
+        assert_eq!(2024, month_arg_match("4p", &ENGLISH).unwrap().year_relative_to(&today));
+        assert_eq!(2023, month_arg_match("6p", &ENGLISH).unwrap().year_relative_to(&today));
+        assert_eq!(2023, month_arg_match("8p", &ENGLISH).unwrap().year_relative_to(&today));
+    }
+
+    #[test]
+    fn test_abbreviation_prefixes() {
+        let english = MonthAbbreviations::new(&ENGLISH);
+        let french = MonthAbbreviations::new(Locale::find("fr_FR"));
+
+        assert_eq!(Some(1), english.find("JAN".as_bytes()));
+        assert_eq!(Some(1), english.find("  january 3".as_bytes()));
+        assert_eq!(Some(9), english.find("Sep".as_bytes()));
+        assert_eq!(None, english.find("ja".as_bytes()));
+        assert_eq!(None, english.find("".as_bytes()));
+        assert_eq!(Some(6), french.find("juin".as_bytes()));
+        assert_eq!(Some(7), french.find("juillet".as_bytes()));
+        assert_eq!(Some(11), MonthAbbreviations::new(Locale::find("ja_JP")).find("11月".as_bytes()));
+        assert_eq!(Some(10), french.find("oct".as_bytes()));
+        assert_eq!(Some(2), french.find("\tFÉVRIER".as_bytes()));
+        assert_eq!(Some(3), english.find(b"mar\xff"));
+        assert_eq!(None, english.find(b"ma\xffr"));
+    }
+
+    #[test]
+    fn test_localized_names() {
+        let german = Locale::find("de_DE.UTF-8");
+        let japanese = Locale::find("ja_JP.UTF-8");
+
+        assert_eq!(exact(3), month_arg_match("März", german).unwrap());
+        assert_eq!(exact(3), month_arg_match("mär", german).unwrap());
+        assert_eq!(exact(3), month_arg_match("mä", german).unwrap());
+        assert_eq!(exact(12), month_arg_match("Dezember", german).unwrap());
+        assert_eq!(exact(10), month_arg_match("okt", german).unwrap());
+        assert_eq!(exact(3), month_arg_match("march", german).unwrap());
+        assert_eq!(exact(1), month_arg_match("1", german).unwrap());
+        assert_eq!(exact(2), month_arg_match("février", Locale::find("fr_FR")).unwrap());
+        assert_eq!(exact(1), month_arg_match("1月", japanese).unwrap());
+        assert_eq!(exact(11), month_arg_match("11月", japanese).unwrap());
+        assert_eq!(exact(11), month_arg_match("11", japanese).unwrap());
+        assert_eq!(exact(8), month_arg_match("АВГУСТ", Locale::find("ru_RU")).unwrap());
+        assert!(month_arg_match("1月", &ENGLISH).is_err());
     }
 }
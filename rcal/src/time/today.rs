@@ -1,47 +1,138 @@
-use chrono::Datelike;
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc};
+use common::input::known_error::KnownError;
 use crate::time::month::Month;
 
+///
+/// A source for the current date; implement this to give `cal` a clock of your own.  A month
+/// `cal` can't show, such as one past year 9999, is an error.
+///
 pub trait Today {
-    fn make_today(&self) -> Month;
+    fn make_today(&self) -> Result<Month, KnownError>;
+
+    ///
+    /// The full date of today; sources that only know the month use its first day.
+    ///
+    fn make_today_date(&self) -> Option<NaiveDate> {
+        let today = self.make_today().ok()?;
+        NaiveDate::from_ymd_opt(today.year.value(), today.month as u32, 1)
+    }
 }
 
-#[allow(dead_code)]
-pub(crate) enum TodayFactory {
+///
+/// Where `cal` gets the current date from: the system clock, a fixed month or date, or any other [Today].
+///
+pub enum TodayFactory {
     Actual,
-    Other { y: u16, m: u16 },
+    Other { y: i32, m: u16 },
+    Date(NaiveDate),
+    Custom(Box<dyn Today>),
 }
 
 impl TodayFactory {
-    pub(crate) fn create(&self) -> Box<dyn Today> {
+    pub fn create(self) -> Box<dyn Today> {
         match self {
             TodayFactory::Actual => {
                 Box::new(ActualToday {})
             },
             TodayFactory::Other { y, m } => {
-                Box::new(OtherToday { year: *y, month: *m })
-            }
+                Box::new(OtherToday { year: y, month: m })
+            },
+            TodayFactory::Date(date) => {
+                Box::new(DateToday { date })
+            },
+            TodayFactory::Custom(today) => today,
+        }
+    }
+
+    ///
+    /// Read a current date given as `yyyy-mm` or `yyyy-mm-dd`, as with `cal -d`.
+    ///
+    pub fn parse(value: &str) -> Option<TodayFactory> {
+        if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            return Month::new(date.month() as u16, date.year())
+                .ok()
+                .map(|_| TodayFactory::Date(date));
         }
+
+        let (year, month) = value.split_once('-')?;
+        let month = Month::new(month.parse().ok()?, year.parse().ok()?).ok()?;
+        Some(TodayFactory::Other { y: month.year.value(), m: month.month })
     }
 }
 
+///
+/// The system clock, in the local timezone; `TZ` is honored when set.
+///
 pub struct ActualToday {}
 
 impl Today for ActualToday {
-    fn make_today(&self) -> Month {
-        let current_date = chrono::Utc::now();
-        let the_year = current_date.year() as u16;
-        let the_month = current_date.month() as u16;
-        Month { year: the_year, month: the_month }
+    fn make_today(&self) -> Result<Month, KnownError> {
+        let current_date = local_date(Utc::now(), &chrono::Local);
+        Month::new(current_date.month() as u16, current_date.year())
+    }
+
+    fn make_today_date(&self) -> Option<NaiveDate> {
+        Some(local_date(Utc::now(), &chrono::Local))
     }
 }
 
+fn local_date<Tz: TimeZone>(now: DateTime<Utc>, zone: &Tz) -> NaiveDate {
+    now.with_timezone(zone).date_naive()
+}
+
 pub(crate) struct OtherToday {
-    pub(crate) year: u16,
+    pub(crate) year: i32,
     pub(crate) month: u16,
 }
 
 impl Today for OtherToday {
-    fn make_today(&self) -> Month {
-        Month { year: self.year, month: self.month }
+    fn make_today(&self) -> Result<Month, KnownError> {
+        Month::new(self.month, self.year)
+    }
+}
+
+pub(crate) struct DateToday {
+    pub(crate) date: NaiveDate,
+}
+
+impl Today for DateToday {
+    fn make_today(&self) -> Result<Month, KnownError> {
+        Month::new(self.date.month() as u16, self.date.year())
+    }
+
+    fn make_today_date(&self) -> Option<NaiveDate> {
+        Some(self.date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::time::month::Month;
+    use crate::time::today::{local_date, TodayFactory};
+    use chrono::{FixedOffset, NaiveDate, TimeZone, Utc};
+
+    #[test]
+    fn test_local_date_near_midnight() {
+        let now = Utc.with_ymd_and_hms(2024, 1, 31, 23, 30, 0).unwrap();
+
+        assert_eq!(NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(), local_date(now, &Utc));
+        assert_eq!(NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(), local_date(now, &FixedOffset::east_opt(3600).unwrap()));
+        assert_eq!(NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(), local_date(now, &FixedOffset::west_opt(5 * 3600).unwrap()));
+    }
+
+    #[test]
+    fn test_parse() {
+        let today = |value: &str| TodayFactory::parse(value).map(|factory| {
+            let today = factory.create();
+            (today.make_today().unwrap(), today.make_today_date())
+        });
+
+        assert_eq!(Some((Month::new(2, 2024).unwrap(), NaiveDate::from_ymd_opt(2024, 2, 14))), today("2024-02-14"));
+        assert_eq!(Some((Month::new(11, 2012).unwrap(), NaiveDate::from_ymd_opt(2012, 11, 1))), today("2012-11"));
+        assert_eq!(None, today("2024-13"));
+        assert_eq!(None, today("2024-02-30"));
+        assert_eq!(None, today("10000-01"));
+        assert_eq!(None, today("2024"));
+        assert_eq!(None, today("yesterday"));
     }
 }
@@ -0,0 +1,94 @@
+use crate::errors::year_out_of_range;
+use common::input::known_error::KnownError;
+use std::fmt;
+
+///
+/// A year in the proleptic gregorian calendar, with astronomical numbering: year 0 is 1 BC, year -1 is 2 BC, and so on.
+///
+#[derive(Copy, Clone, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Year(i32);
+
+impl Year {
+    pub const MIN: Year = Year(-9999);
+    pub const MAX: Year = Year(9999);
+
+    pub fn new(value: i32) -> Result<Year, KnownError> {
+        if (Self::MIN.0..=Self::MAX.0).contains(&value) {
+            Ok(Year(value))
+        } else {
+            Err(year_out_of_range(&value.to_string()))
+        }
+    }
+
+    pub fn value(&self) -> i32 {
+        self.0
+    }
+
+    ///
+    /// The year before this one; [None] before [Year::MIN].
+    ///
+    pub fn prev(&self) -> Option<Year> {
+        Year::new(self.0 - 1).ok()
+    }
+
+    ///
+    /// The year after this one; [None] after [Year::MAX].
+    ///
+    pub fn next(&self) -> Option<Year> {
+        Year::new(self.0 + 1).ok()
+    }
+}
+
+impl fmt::Display for Year {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        /* forward, so width and alignment are honored */
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Debug for Year {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl PartialEq<i32> for Year {
+    fn eq(&self, other: &i32) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<Year> for i32 {
+    fn eq(&self, other: &Year) -> bool {
+        *self == other.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::time::year::Year;
+
+    #[test]
+    fn test_range() {
+        assert_eq!(2024, Year::new(2024).unwrap());
+        assert_eq!(0, Year::new(0).unwrap());
+        assert_eq!(-9999, Year::new(-9999).unwrap());
+        assert_eq!(Some("rcal: year 10000 not in range -9999..9999".to_owned()), Year::new(10000).unwrap_err().message);
+        assert_eq!(Some("rcal: year -10000 not in range -9999..9999".to_owned()), Year::new(-10000).unwrap_err().message);
+    }
+
+    #[test]
+    fn test_prev_next() {
+        assert_eq!(Some(Year::new(0).unwrap()), Year::new(1).unwrap().prev());
+        assert_eq!(Some(Year::new(-1).unwrap()), Year::new(0).unwrap().prev());
+        assert_eq!(None, Year::MIN.prev());
+        assert_eq!(None, Year::MAX.next());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!("-44", Year::new(-44).unwrap().to_string());
+        assert_eq!("  2024  ", format!("{:^8}", Year::new(2024).unwrap()));
+        assert_eq!("Some(1986)", format!("{:?}", Some(Year::new(1986).unwrap())));
+    }
+}
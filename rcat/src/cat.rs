@@ -0,0 +1,241 @@
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::os::fd::{AsRawFd, RawFd};
+use common::input::known_error::{report, KnownError};
+use common::input::source::InputSource;
+use crate::config::Config;
+use crate::copy::{copy_in_kernel, regular_file};
+use crate::errors::{input_error, input_is_output, write_error};
+use crate::format::Formatter;
+use crate::help::{help, version};
+
+const SUCCESS: i32 = 0;
+const FAILURE: i32 = 1;
+const BUFFER_SIZE: usize = 128 * 1024;
+
+///
+/// Where output goes: a writer, and the descriptor underneath it when there is one, so that plain
+/// copies can bypass the writer.
+///
+pub(crate) struct Output<'a> {
+    pub(crate) writer: &'a mut dyn Write,
+    pub(crate) descriptor: Option<RawFd>,
+}
+
+///
+/// Why a single input couldn't be copied: the input is at fault, and the next one may still work;
+/// or output failed, and nothing more can be written.
+///
+enum CatError {
+    Input(KnownError),
+    Output(io::Error),
+}
+
+///
+/// Run `cat` with command line arguments; the first argument is the program name.  Returns the
+/// exit status.
+///
+pub(crate) fn cat(args: &[String], mut out: Output, err: &mut dyn Write) -> i32 {
+    let config = Config::new(args);
+    if let Some(first) = config.errors.first() {
+        report(err, &config.errors);
+        return first.code;
+    }
+
+    if config.print_help_and_exit {
+        return help(out.writer).map_or(FAILURE, |_| SUCCESS);
+    }
+    if config.print_version_and_exit {
+        return version(out.writer).map_or(FAILURE, |_| SUCCESS);
+    }
+
+    let mut status = SUCCESS;
+    let mut formatter = Formatter::new(config.formatting);
+    for source in config.sources.iter() {
+        match cat_source(&config, source, &mut formatter, &mut out) {
+            Ok(()) => {}
+            Err(CatError::Input(error)) => {
+                report(err, std::slice::from_ref(&error));
+                status = error.code;
+            }
+            /* output went away, e.g. a closed pipe; nothing more can be written */
+            Err(CatError::Output(error)) => {
+                report(err, &[write_error(&error)]);
+                return FAILURE;
+            }
+        }
+    }
+
+    match out.writer.flush() {
+        Ok(()) => status,
+        Err(error) => {
+            report(err, &[write_error(&error)]);
+            FAILURE
+        }
+    }
+}
+
+fn cat_source(config: &Config, source: &InputSource, formatter: &mut Formatter, out: &mut Output) -> Result<(), CatError> {
+    let name = source.name();
+    let to_input_error = |error: io::Error| CatError::Input(input_error(name, &error));
+
+    match source {
+        InputSource::Stdin => copy(config, io::stdin().lock(), libc::STDIN_FILENO, name, formatter, out),
+        InputSource::File(path) => {
+            let file = File::open(path).map_err(to_input_error)?;
+            let descriptor = file.as_raw_fd();
+            copy(config, file, descriptor, name, formatter, out)
+        }
+    }
+}
+
+fn copy(config: &Config, mut input: impl Read, descriptor: RawFd, name: &str, formatter: &mut Formatter, out: &mut Output) -> Result<(), CatError> {
+    let to_input_error = |error: io::Error| CatError::Input(input_error(name, &error));
+
+    /* reading a file while appending it to itself never reaches the end */
+    if let (Some(output), Some(input)) = (out.descriptor.and_then(regular_file), regular_file(descriptor))
+        && output.device == input.device && output.inode == input.inode && input.size > 0 {
+        return Err(CatError::Input(input_is_output(name)));
+    }
+
+    if config.formatting.is_plain() && config.fast_copy
+        && let Some(output) = out.descriptor {
+        out.writer.flush().map_err(CatError::Output)?;
+        if copy_in_kernel(descriptor, output) {
+            return Ok(());
+        }
+    }
+
+    let mut buffer = vec![0u8; BUFFER_SIZE];
+    let mut formatted = vec![];
+    loop {
+        let read = match input.read(&mut buffer) {
+            Ok(0) => return Ok(()),
+            Ok(read) => read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(to_input_error(error)),
+        };
+
+        if config.formatting.is_plain() {
+            out.writer.write_all(&buffer[..read]).map_err(CatError::Output)?;
+        } else {
+            formatted.clear();
+            formatter.format(&buffer[..read], &mut formatted);
+            out.writer.write_all(&formatted).map_err(CatError::Output)?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, File, OpenOptions};
+    use std::io::Write;
+    use std::os::fd::{AsRawFd, FromRawFd};
+    use common::test_support::TempDir;
+    use crate::cat::{cat, Output};
+
+    fn run(args: &[&str]) -> (i32, String, String) {
+        let args: Vec<String> = std::iter::once("cat").chain(args.iter().copied()).map(|s| s.to_string()).collect();
+        let mut out = vec![];
+        let mut err = vec![];
+        let status = cat(&args, Output { writer: &mut out, descriptor: None }, &mut err);
+        (status, String::from_utf8(out).unwrap(), String::from_utf8(err).unwrap())
+    }
+
+    #[test]
+    fn test_concatenate() {
+        let dir = TempDir::new("concatenate");
+        let first = dir.file("first", b"one\ntwo\n");
+        let second = dir.file("second", b"three");
+
+        assert_eq!((0, "one\ntwo\nthree".to_string(), String::new()), run(&[&first, &second]));
+        assert_eq!((0, "three".to_string(), String::new()), run(&["--", &second]));
+    }
+
+    #[test]
+    fn test_numbering_across_files() {
+        let dir = TempDir::new("numbering");
+        let first = dir.file("first", b"a\n\nb");
+        let second = dir.file("second", b"c\n\n\n\nd\n");
+
+        assert_eq!("     1\ta\n     2\t\n     3\tbc\n     4\t\n     5\t\n     6\t\n     7\td\n", run(&["-n", &first, &second]).1);
+        assert_eq!("     1\ta\n\n     2\tbc\n\n\n\n     3\td\n", run(&["-b", &first, &second]).1);
+        assert_eq!("     1\ta\n     2\t\n     3\tbc\n     4\t\n     5\td\n", run(&["-sn", &first, &second]).1);
+    }
+
+    #[test]
+    fn test_show_all() {
+        let dir = TempDir::new("show-all");
+        let file = dir.file("file", b"tab\there\r\n\x80\n");
+
+        assert_eq!("tab^Ihere^M$\nM-^@$\n", run(&["-A", &file]).1);
+        assert_eq!("tab\there^M$\nM-^@$\n", run(&["-e", &file]).1);
+        assert_eq!("tab^Ihere^M\nM-^@\n", run(&["-t", &file]).1);
+    }
+
+    #[test]
+    fn test_errors_continue() {
+        let dir = TempDir::new("errors");
+        let file = dir.file("file", b"still printed\n");
+        let missing = dir.join("missing");
+        let missing = missing.to_str().unwrap();
+        let directory = dir.join("");
+        let directory = directory.to_str().unwrap().trim_end_matches('/');
+
+        let (status, out, err) = run(&[missing, directory, &file]);
+        assert_eq!(1, status);
+        assert_eq!("still printed\n", out);
+        assert_eq!(format!("cat: {}: No such file or directory\ncat: {}: Is a directory\n", missing, directory), err);
+    }
+
+    #[test]
+    fn test_fast_copy_keeps_order() {
+        let dir = TempDir::new("fast");
+        let first = dir.file("first", b"first\n");
+        let second = dir.file("second", b"second\n");
+        let mut output = OpenOptions::new().create(true).write(true).truncate(true).open(dir.join("out")).unwrap();
+        let descriptor = output.as_raw_fd();
+
+        /* something still buffered in the writer has to land ahead of the kernel's copy */
+        let mut writer = std::io::BufWriter::new(&mut output);
+        writer.write_all(b"buffered\n").unwrap();
+        let args: Vec<String> = vec!["cat".to_string(), first, second];
+        assert_eq!(0, cat(&args, Output { writer: &mut writer, descriptor: Some(descriptor) }, &mut vec![]));
+        drop(writer);
+
+        assert_eq!("buffered\nfirst\nsecond\n", fs::read_to_string(dir.join("out")).unwrap());
+    }
+
+    #[test]
+    fn test_input_is_output() {
+        let dir = TempDir::new("same");
+        let path = dir.file("file", b"contents\n");
+        let mut output = OpenOptions::new().append(true).open(&path).unwrap();
+        let descriptor = output.as_raw_fd();
+
+        let mut err = vec![];
+        let args: Vec<String> = vec!["cat".to_string(), path.clone()];
+        assert_eq!(1, cat(&args, Output { writer: &mut output, descriptor: Some(descriptor) }, &mut err));
+        assert_eq!(format!("cat: {}: input file is output file\n", path), String::from_utf8(err).unwrap());
+        assert_eq!("contents\n", fs::read_to_string(&path).unwrap());
+    }
+
+    #[test]
+    fn test_closed_pipe() {
+        let dir = TempDir::new("closed");
+        let first = dir.file("first", b"first\n");
+        let second = dir.file("second", b"second\n");
+
+        let mut fds = [0; 2];
+        /* SAFETY: pipe fills in exactly two descriptors, owned by the Files below */
+        assert_eq!(0, unsafe { libc::pipe(fds.as_mut_ptr()) });
+        drop(unsafe { File::from_raw_fd(fds[0]) });
+        let mut writer = unsafe { File::from_raw_fd(fds[1]) };
+
+        /* a write error is told of once, and ends the copying, not blamed on each input */
+        let mut err = vec![];
+        let args: Vec<String> = vec!["cat".to_string(), first, second];
+        assert_eq!(1, cat(&args, Output { writer: &mut writer, descriptor: Some(fds[1]) }, &mut err));
+        assert_eq!("cat: write error: Broken pipe\n", String::from_utf8(err).unwrap());
+    }
+}
@@ -0,0 +1,202 @@
+use common::input::flags::flags_options::{parse_options, OptionSpec, ParsedArgument};
+use common::input::known_error::KnownError;
+use common::input::source::InputSource;
+use crate::errors::option_error;
+
+const OPTIONS: [OptionSpec; 13] = [
+    OptionSpec::flag("show-all", Some('A'), Some("show-all")),
+    OptionSpec::flag("number-nonblank", Some('b'), Some("number-nonblank")),
+    OptionSpec::flag("e", Some('e'), None),
+    OptionSpec::flag("show-ends", Some('E'), Some("show-ends")),
+    OptionSpec::flag("number", Some('n'), Some("number")),
+    OptionSpec::flag("squeeze-blank", Some('s'), Some("squeeze-blank")),
+    OptionSpec::flag("t", Some('t'), None),
+    OptionSpec::flag("show-tabs", Some('T'), Some("show-tabs")),
+    OptionSpec::flag("unbuffered", Some('u'), None),
+    OptionSpec::flag("show-nonprinting", Some('v'), Some("show-nonprinting")),
+    OptionSpec::flag("help", None, Some("help")),
+    OptionSpec::flag("version", None, Some("version")),
+    OptionSpec::flag("no-fast-copy", None, Some("no-fast-copy")),
+];
+
+///
+/// How lines are numbered, if at all.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Numbering {
+    None,
+    All,
+    NonBlank,
+}
+
+///
+/// Changes made to the input on its way to the output.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Formatting {
+    pub(crate) numbering: Numbering,
+    pub(crate) squeeze_blank: bool,
+    pub(crate) show_ends: bool,
+    pub(crate) show_tabs: bool,
+    pub(crate) show_nonprinting: bool,
+}
+
+impl Formatting {
+    ///
+    /// Whether input can be copied to output byte for byte.
+    ///
+    pub(crate) fn is_plain(&self) -> bool {
+        self.numbering == Numbering::None && !self.squeeze_blank && !self.show_ends && !self.show_tabs && !self.show_nonprinting
+    }
+}
+
+///
+/// Storage for the application configuration.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct Config {
+    pub(crate) print_help_and_exit: bool,
+    pub(crate) print_version_and_exit: bool,
+
+    pub(crate) formatting: Formatting,
+    pub(crate) fast_copy: bool,
+
+    pub(crate) sources: Vec<InputSource>,
+    pub(crate) errors: Vec<KnownError>,
+}
+
+impl Default for Config {
+    ///
+    /// Generate [Default] configuration.
+    ///
+    fn default() -> Self {
+        Config {
+            print_help_and_exit: false,
+            print_version_and_exit: false,
+
+            formatting: Formatting {
+                numbering: Numbering::None,
+                squeeze_blank: false,
+                show_ends: false,
+                show_tabs: false,
+                show_nonprinting: false,
+            },
+            fast_copy: true,
+
+            sources: vec![],
+            errors: vec![],
+        }
+    }
+}
+
+impl Config {
+    ///
+    /// Initialize the application configuration based on provided application arguments; the first
+    /// argument is the program name.
+    ///
+    pub(crate) fn new(args: &[String]) -> Config {
+        let mut config = Self::default();
+
+        let parsed = match parse_options(args.get(1..).unwrap_or_default(), &OPTIONS) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                config.errors.push(option_error(&error));
+                return config;
+            }
+        };
+
+        let mut operands = vec![];
+        for argument in parsed {
+            match argument {
+                ParsedArgument::Operand(operand) => operands.push(operand),
+                ParsedArgument::Option { id, .. } => config.apply(id),
+            }
+        }
+        config.sources = InputSource::from_operands(&operands);
+
+        config
+    }
+
+    fn apply(&mut self, id: &str) {
+        let formatting = &mut self.formatting;
+        match id {
+            "show-all" => {
+                formatting.show_nonprinting = true;
+                formatting.show_ends = true;
+                formatting.show_tabs = true;
+            }
+            "e" => {
+                formatting.show_nonprinting = true;
+                formatting.show_ends = true;
+            }
+            "t" => {
+                formatting.show_nonprinting = true;
+                formatting.show_tabs = true;
+            }
+            /* -b overrides -n, whichever comes first */
+            "number-nonblank" => formatting.numbering = Numbering::NonBlank,
+            "number" if formatting.numbering == Numbering::None => formatting.numbering = Numbering::All,
+            "number" => {}
+            "show-ends" => formatting.show_ends = true,
+            "squeeze-blank" => formatting.squeeze_blank = true,
+            "show-tabs" => formatting.show_tabs = true,
+            "show-nonprinting" => formatting.show_nonprinting = true,
+            /* output is never held back between reads, so -u has nothing to change */
+            "unbuffered" => {}
+            "no-fast-copy" => self.fast_copy = false,
+            "help" => self.print_help_and_exit = true,
+            "version" => self.print_version_and_exit = true,
+            _ => unreachable!("option {} has no handler", id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::input::source::InputSource;
+    use crate::config::{Config, Numbering};
+
+    fn config(line: &str) -> Config {
+        let args: Vec<String> = line.split_whitespace().map(|s| s.to_string()).collect();
+        Config::new(&args)
+    }
+
+    #[test]
+    fn test_defaults() {
+        let config = config("cat");
+
+        assert!(config.errors.is_empty());
+        assert!(config.formatting.is_plain());
+        assert!(config.fast_copy);
+        assert_eq!(vec![InputSource::Stdin], config.sources);
+    }
+
+    #[test]
+    fn test_combined_flags() {
+        let all = config("cat -A a").formatting;
+        assert!(all.show_nonprinting && all.show_ends && all.show_tabs);
+
+        let e = config("cat -e").formatting;
+        assert!(e.show_nonprinting && e.show_ends && !e.show_tabs);
+
+        let t = config("cat -t").formatting;
+        assert!(t.show_nonprinting && !t.show_ends && t.show_tabs);
+
+        assert!(config("cat -u").formatting.is_plain());
+        assert!(config("cat -s").formatting.squeeze_blank);
+    }
+
+    #[test]
+    fn test_numbering() {
+        assert_eq!(Numbering::All, config("cat -n").formatting.numbering);
+        assert_eq!(Numbering::NonBlank, config("cat -b").formatting.numbering);
+        assert_eq!(Numbering::NonBlank, config("cat -nb").formatting.numbering);
+        assert_eq!(Numbering::NonBlank, config("cat -bn").formatting.numbering);
+    }
+
+    #[test]
+    fn test_errors() {
+        let messages: Vec<String> = config("cat -x").errors.iter().map(|e| e.message.clone().unwrap()).collect();
+        assert_eq!(vec!["cat: invalid option -- 'x'"], messages);
+    }
+}
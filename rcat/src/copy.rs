@@ -0,0 +1,115 @@
+use std::io;
+use std::os::fd::RawFd;
+
+///
+/// Copy everything from `input` to `output` without bringing it into user space, when the kernel
+/// can: `copy_file_range` between files, `splice` when either end is a pipe.
+///
+/// Returns false when neither applies, or when the copy fails partway, so the caller copies the rest
+/// through a buffer instead.  The kernel's error doesn't say which end failed; reading and writing
+/// apart from each other do.
+///
+#[cfg(target_os = "linux")]
+pub(crate) fn copy_in_kernel(input: RawFd, output: RawFd) -> bool {
+    /* files in /proc and /sys claim to be empty; copy_file_range takes them at their word */
+    if regular_file(input).is_some_and(|file| file.size > 0) && copy_all(input, output, |i, o| {
+        /* SAFETY: null offsets make the kernel use and advance each descriptor's own offset */
+        unsafe { libc::copy_file_range(i, std::ptr::null_mut(), o, std::ptr::null_mut(), CHUNK, 0) }
+    }) {
+        return true;
+    }
+
+    copy_all(input, output, |i, o| {
+        /* SAFETY: as above; splice only moves data between the two descriptors */
+        unsafe { libc::splice(i, std::ptr::null_mut(), o, std::ptr::null_mut(), CHUNK, libc::SPLICE_F_MOVE) }
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn copy_in_kernel(_input: RawFd, _output: RawFd) -> bool {
+    false
+}
+
+///
+/// Identity and size of a regular file, read from its descriptor.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct RegularFile {
+    pub(crate) device: u64,
+    pub(crate) inode: u64,
+    pub(crate) size: u64,
+}
+
+///
+/// Describe the file open on `descriptor`; [None] unless it's a regular file.
+///
+pub(crate) fn regular_file(descriptor: RawFd) -> Option<RegularFile> {
+    /* SAFETY: fstat only writes into the stat struct we hand it */
+    let mut status: libc::stat = unsafe { std::mem::zeroed() };
+    let result = unsafe { libc::fstat(descriptor, &mut status) };
+    if result != 0 || (status.st_mode & libc::S_IFMT) != libc::S_IFREG {
+        return None;
+    }
+
+    Some(RegularFile { device: status.st_dev as u64, inode: status.st_ino as u64, size: status.st_size as u64 })
+}
+
+#[cfg(target_os = "linux")]
+const CHUNK: usize = 1 << 30;
+
+///
+/// Call `copy` until the input is exhausted.  Any error gives false; a failed call copies nothing,
+/// and each descriptor's offset is left just past what was copied, so another way can carry on.
+///
+#[cfg(target_os = "linux")]
+fn copy_all(input: RawFd, output: RawFd, copy: impl Fn(RawFd, RawFd) -> isize) -> bool {
+    loop {
+        match copy(input, output) {
+            0 => return true,
+            count if count > 0 => {}
+            _ if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted => {}
+            _ => return false,
+        }
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use std::fs::{self, File};
+    use std::io::Read;
+    use std::os::fd::{AsRawFd, FromRawFd};
+    use common::test_support::TempDir;
+    use crate::copy::copy_in_kernel;
+
+    #[test]
+    fn test_file_to_file() {
+        let dir = TempDir::new("copy-file");
+        let contents: String = (0..10000).map(|n| format!("{}\n", n)).collect();
+        fs::write(dir.join("in"), &contents).unwrap();
+
+        let input = File::open(dir.join("in")).unwrap();
+        let output = File::create(dir.join("out")).unwrap();
+        assert!(copy_in_kernel(input.as_raw_fd(), output.as_raw_fd()));
+        assert_eq!(contents, fs::read_to_string(dir.join("out")).unwrap());
+    }
+
+    #[test]
+    fn test_file_to_pipe() {
+        let dir = TempDir::new("copy-pipe");
+        fs::write(dir.join("in"), "through a pipe\n").unwrap();
+
+        let mut fds = [0; 2];
+        /* SAFETY: pipe fills in exactly two descriptors, owned by the Files below */
+        assert_eq!(0, unsafe { libc::pipe(fds.as_mut_ptr()) });
+        let mut reader = unsafe { File::from_raw_fd(fds[0]) };
+        let writer = unsafe { File::from_raw_fd(fds[1]) };
+
+        let input = File::open(dir.join("in")).unwrap();
+        assert!(copy_in_kernel(input.as_raw_fd(), writer.as_raw_fd()));
+        drop(writer);
+
+        let mut piped = String::new();
+        reader.read_to_string(&mut piped).unwrap();
+        assert_eq!("through a pipe\n", piped);
+    }
+}
@@ -0,0 +1,35 @@
+use std::io;
+use common::input::flags::flags_options::OptionError;
+use common::input::known_error::KnownError;
+use common::input::source::describe_io_error;
+
+const ERROR_CODE: i32 = 1;
+const PROGRAM: &str = "cat";
+
+///
+/// The command line couldn't be split into options and operands.
+///
+pub(crate) fn option_error(error: &OptionError) -> KnownError {
+    KnownError::new(ERROR_CODE, error.message(PROGRAM))
+}
+
+///
+/// An input couldn't be opened or read.
+///
+pub(crate) fn input_error(name: &str, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: {}: {}", PROGRAM, name, describe_io_error(error)))
+}
+
+///
+/// An input is the very file being written to; copying it would never end.
+///
+pub(crate) fn input_is_output(name: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: {}: input file is output file", PROGRAM, name))
+}
+
+///
+/// Standard output couldn't be written.
+///
+pub(crate) fn write_error(error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: write error: {}", PROGRAM, describe_io_error(error)))
+}
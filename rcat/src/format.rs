@@ -0,0 +1,178 @@
+use crate::config::{Formatting, Numbering};
+
+const NEWLINE: u8 = b'\n';
+const TAB: u8 = b'\t';
+const DELETE: u8 = 0x7f;
+const META: u8 = 0x80;
+const NUMBER_WIDTH: usize = 6;
+
+///
+/// Applies [Formatting] to input as it's read.
+///
+/// State is kept between calls, so one formatter used for every input numbers lines straight
+/// through, squeezes blank lines that span inputs, and continues a last line that has no newline.
+///
+pub(crate) struct Formatter {
+    formatting: Formatting,
+    line_number: u64,
+    at_line_start: bool,
+    blank_lines: usize,
+}
+
+impl Formatter {
+    pub(crate) fn new(formatting: Formatting) -> Formatter {
+        Formatter { formatting, line_number: 0, at_line_start: true, blank_lines: 0 }
+    }
+
+    ///
+    /// Format one chunk of input, appending to `output`.
+    ///
+    pub(crate) fn format(&mut self, input: &[u8], output: &mut Vec<u8>) {
+        for &byte in input {
+            if self.at_line_start {
+                if byte == NEWLINE {
+                    self.blank_line(output);
+                    continue;
+                }
+                self.blank_lines = 0;
+                if self.formatting.numbering != Numbering::None {
+                    self.number(output);
+                }
+                self.at_line_start = false;
+            }
+
+            if byte == NEWLINE {
+                self.end_line(output);
+                self.at_line_start = true;
+            } else {
+                self.visualize(byte, output);
+            }
+        }
+    }
+
+    fn blank_line(&mut self, output: &mut Vec<u8>) {
+        self.blank_lines += 1;
+        if self.formatting.squeeze_blank && self.blank_lines > 1 {
+            return;
+        }
+        if self.formatting.numbering == Numbering::All {
+            self.number(output);
+        }
+        self.end_line(output);
+    }
+
+    fn number(&mut self, output: &mut Vec<u8>) {
+        self.line_number += 1;
+        output.extend_from_slice(format!("{:>width$}\t", self.line_number, width = NUMBER_WIDTH).as_bytes());
+    }
+
+    fn end_line(&self, output: &mut Vec<u8>) {
+        if self.formatting.show_ends {
+            output.push(b'$');
+        }
+        output.push(NEWLINE);
+    }
+
+    ///
+    /// Write a byte, in `^` and `M-` notation if asked to.
+    ///
+    fn visualize(&self, byte: u8, output: &mut Vec<u8>) {
+        if byte == TAB {
+            if self.formatting.show_tabs {
+                output.extend_from_slice(b"^I");
+            } else {
+                output.push(byte);
+            }
+            return;
+        }
+        if !self.formatting.show_nonprinting {
+            output.push(byte);
+            return;
+        }
+
+        let low = if byte >= META {
+            output.extend_from_slice(b"M-");
+            byte - META
+        } else {
+            byte
+        };
+        match low {
+            DELETE => output.extend_from_slice(b"^?"),
+            control if control < b' ' => output.extend_from_slice(&[b'^', control + b'@']),
+            printable => output.push(printable),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{Formatting, Numbering};
+    use crate::format::Formatter;
+
+    const PLAIN: Formatting = Formatting {
+        numbering: Numbering::None,
+        squeeze_blank: false,
+        show_ends: false,
+        show_tabs: false,
+        show_nonprinting: false,
+    };
+
+    fn format(formatting: Formatting, chunks: &[&[u8]]) -> String {
+        let mut formatter = Formatter::new(formatting);
+        let mut output = vec![];
+        chunks.iter().for_each(|chunk| formatter.format(chunk, &mut output));
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_numbering() {
+        let all = Formatting { numbering: Numbering::All, ..PLAIN };
+        let non_blank = Formatting { numbering: Numbering::NonBlank, ..PLAIN };
+
+        assert_eq!("     1\ta\n     2\t\n     3\tb\n", format(all, &[b"a\n\nb\n"]));
+        assert_eq!("     1\ta\n\n     2\tb\n", format(non_blank, &[b"a\n\nb\n"]));
+        assert_eq!("     1\tab\n     2\tc\n", format(all, &[b"a", b"b\nc", b"\n"]));
+        assert_eq!("     1\tend", format(all, &[b"end"]));
+    }
+
+    #[test]
+    fn test_numbering_continues_across_inputs() {
+        let all = Formatting { numbering: Numbering::All, ..PLAIN };
+
+        assert_eq!("     1\ta\n     2\tb\n     3\tc\n", format(all, &[b"a\nb\n", b"c\n"]));
+        assert_eq!("     1\tab\n     2\tc\n", format(all, &[b"a", b"b\nc\n"]));
+    }
+
+    #[test]
+    fn test_squeeze() {
+        let squeeze = Formatting { squeeze_blank: true, ..PLAIN };
+        let numbered = Formatting { squeeze_blank: true, numbering: Numbering::All, ..PLAIN };
+
+        assert_eq!("a\n\nb\n\n", format(squeeze, &[b"a\n\n\n\nb\n\n\n"]));
+        assert_eq!("\na\n", format(squeeze, &[b"\n\n", b"\n", b"a\n"]));
+        assert_eq!("     1\ta\n     2\t\n     3\tb\n", format(numbered, &[b"a\n\n\n\nb\n"]));
+    }
+
+    #[test]
+    fn test_show_ends_and_tabs() {
+        assert_eq!("a$\n$\n", format(Formatting { show_ends: true, ..PLAIN }, &[b"a\n\n"]));
+        assert_eq!("a^Ib\n", format(Formatting { show_tabs: true, ..PLAIN }, &[b"a\tb\n"]));
+        assert_eq!("\x01\t\n", format(PLAIN, &[b"\x01\t\n"]));
+    }
+
+    #[test]
+    fn test_show_nonprinting() {
+        let nonprinting = Formatting { show_nonprinting: true, ..PLAIN };
+
+        assert_eq!("^@^A^[^?\t~\n", format(nonprinting, &[b"\x00\x01\x1b\x7f\t~\n"]));
+        assert_eq!("M-^@M-^IM- M-AM-^?\n", format(nonprinting, &[b"\x80\x89\xa0\xc1\xff\n"]));
+        assert_eq!("M-CM-)\n", format(nonprinting, &["é\n".as_bytes()]));
+    }
+
+    #[test]
+    fn test_show_all() {
+        let all = Formatting { numbering: Numbering::None, squeeze_blank: false, show_ends: true, show_tabs: true, show_nonprinting: true };
+
+        assert_eq!("a^Ib^M$\n", format(all, &[b"a\tb\r\n"]));
+    }
+}
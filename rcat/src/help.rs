@@ -0,0 +1,30 @@
+use std::io::{self, Write};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub(crate) fn version(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "rcat {}", VERSION)
+}
+
+pub(crate) fn help(out: &mut dyn Write) -> io::Result<()> {
+    version(out)?;
+    writeln!(out)?;
+    writeln!(out, "Usage: cat [OPTION]... [FILE]...")?;
+    writeln!(out, "Concatenate FILE(s) to standard output.")?;
+    writeln!(out)?;
+    writeln!(out, "With no FILE, or when FILE is -, read standard input.")?;
+    writeln!(out)?;
+    writeln!(out, "  -A, --show-all           equivalent to -vET")?;
+    writeln!(out, "  -b, --number-nonblank    number nonempty output lines, overrides -n")?;
+    writeln!(out, "  -e                       equivalent to -vE")?;
+    writeln!(out, "  -E, --show-ends          display $ at end of each line")?;
+    writeln!(out, "  -n, --number             number all output lines")?;
+    writeln!(out, "  -s, --squeeze-blank      suppress repeated empty output lines")?;
+    writeln!(out, "  -t                       equivalent to -vT")?;
+    writeln!(out, "  -T, --show-tabs          display TAB characters as ^I")?;
+    writeln!(out, "  -u                       (ignored)")?;
+    writeln!(out, "  -v, --show-nonprinting   use ^ and M- notation, except for LFD and TAB")?;
+    writeln!(out, "      --no-fast-copy       copy through a buffer, even when no option changes the output")?;
+    writeln!(out, "      --help               display this help and exit")?;
+    writeln!(out, "      --version            output version information and exit")
+}
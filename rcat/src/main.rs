@@ -0,0 +1,18 @@
+mod cat;
+mod config;
+mod copy;
+mod errors;
+mod format;
+mod help;
+
+use crate::cat::{cat, Output};
+
+///
+/// Main program entrypoint.
+///
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let output = Output { writer: &mut std::io::stdout(), descriptor: Some(libc::STDOUT_FILENO) };
+    let status = cat(&args, output, &mut std::io::stderr());
+    std::process::exit(status);
+}
@@ -0,0 +1,235 @@
+use std::fs::{self, Metadata, Permissions};
+use std::io::{self, ErrorKind, Write};
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::Path;
+use common::fs::permissions::Mode;
+use common::input::known_error::KnownError;
+use crate::config::{Config, Verbosity};
+use crate::errors::{cannot_access, cannot_read_directory, changed, changing_permissions, dangerous_root, dangling_symlink, failed_to_change,
+                    getting_new_attributes, new_permissions, not_accessed, not_applied, retained};
+
+const MODE_BITS: u32 = 0o7777;
+/* set-user-id, set-group-id and sticky; the system may quietly refuse to set these */
+const SPECIAL: u32 = 0o7000;
+
+///
+/// How far a change to one file got; the later, the better.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Outcome {
+    /// The file couldn't be looked at.
+    NotAccessed,
+    /// Its mode couldn't be set.
+    Failed,
+    /// It's a symbolic link, met while recursing, and left as it is.
+    NotApplied,
+    /// Its mode was set, and was what it was already.
+    Retained,
+    /// Its mode was set, and is different now.
+    Changed,
+}
+
+///
+/// Changes the modes of files, and of what's in directories with `-R`, reporting problems as
+/// they're met.
+///
+pub(crate) struct Changer<'a> {
+    config: &'a Config,
+    mode: &'a Mode,
+    umask: u32,
+    /* the device and inode of the root directory, when --preserve-root keeps -R from it */
+    root: Option<(u64, u64)>,
+    out: &'a mut dyn Write,
+    err: &'a mut dyn Write,
+}
+
+impl<'a> Changer<'a> {
+    pub(crate) fn new(config: &'a Config, mode: &'a Mode, umask: u32, root: Option<(u64, u64)>, out: &'a mut dyn Write, err: &'a mut dyn Write) -> Changer<'a> {
+        Changer { config, mode, umask, root, out, err }
+    }
+
+    ///
+    /// Change the mode of a file named on the command line; a symbolic link is followed.  Returns
+    /// whether it, and everything under it, could be given the mode.
+    ///
+    pub(crate) fn change_operand(&mut self, name: &Path) -> bool {
+        match fs::metadata(name) {
+            Ok(status) => self.change(name, &status),
+            Err(error) if error.kind() == ErrorKind::NotFound && fs::symlink_metadata(name).is_ok() => self.not_accessed(name, dangling_symlink(name)),
+            Err(error) => self.not_accessed(name, cannot_access(name, &error)),
+        }
+    }
+
+    fn change(&mut self, name: &Path, status: &Metadata) -> bool {
+        let config = self.config;
+        if config.recursive && self.root.is_some_and(|root| root == (status.dev(), status.ino())) {
+            return self.fail(dangerous_root(name));
+        }
+        if status.is_symlink() {
+            self.tell(Outcome::NotApplied, name, 0, 0);
+            return true;
+        }
+
+        let directory = status.is_dir();
+        let old = status.mode() & MODE_BITS;
+        let (new, _) = self.mode.adjust(old, directory, self.umask);
+        let mut outcome = match fs::set_permissions(name, Permissions::from_mode(new)) {
+            Ok(()) => Outcome::Changed,
+            Err(error) => {
+                self.complain(changing_permissions(name, &error));
+                Outcome::Failed
+            }
+        };
+        if config.verbosity != Verbosity::Off && outcome == Outcome::Changed && !self.mode_changed(name, old, new) {
+            outcome = Outcome::Retained;
+        }
+        self.tell(outcome, name, old, new);
+
+        /* a mode given as an option, as in `-w`, was likely meant for everyone, whatever the umask says */
+        if outcome >= Outcome::Retained && config.surprises {
+            let (expected, _) = self.mode.adjust(old, directory, 0);
+            if new & !expected != 0 {
+                outcome = Outcome::Failed;
+                self.fail(new_permissions(name, new, expected));
+            }
+        }
+
+        let mut success = outcome >= Outcome::NotApplied;
+        if config.recursive && directory {
+            success &= self.change_directory(name);
+        }
+        success
+    }
+
+    ///
+    /// Change the modes of what's in a directory, in order of name; symbolic links aren't followed.
+    ///
+    fn change_directory(&mut self, name: &Path) -> bool {
+        let mut names = match fs::read_dir(name).and_then(|entries| entries.map(|entry| entry.map(|entry| entry.file_name())).collect::<io::Result<Vec<_>>>()) {
+            Ok(names) => names,
+            Err(error) => return self.not_accessed(name, cannot_read_directory(name, &error)),
+        };
+        names.sort();
+        let mut success = true;
+        for entry in names {
+            let path = name.join(&entry);
+            success &= match fs::symlink_metadata(&path) {
+                Ok(status) => self.change(&path, &status),
+                Err(error) => self.not_accessed(&path, cannot_access(&path, &error)),
+            };
+        }
+        success
+    }
+
+    ///
+    /// Whether the mode really is different now; special bits the system may not have set are
+    /// looked for on the file.
+    ///
+    fn mode_changed(&mut self, name: &Path, old: u32, new: u32) -> bool {
+        if new & SPECIAL == 0 {
+            return (old ^ new) & MODE_BITS != 0;
+        }
+        match fs::metadata(name) {
+            Ok(status) => (old ^ status.mode()) & MODE_BITS != 0,
+            Err(error) => {
+                self.complain(getting_new_attributes(name, &error));
+                false
+            }
+        }
+    }
+
+    ///
+    /// What `-c` and `-v` say about a file; `-c` tells only of modes that were changed.
+    ///
+    fn tell(&mut self, outcome: Outcome, name: &Path, old: u32, new: u32) {
+        let verbosity = self.config.verbosity;
+        if verbosity == Verbosity::Off || (verbosity == Verbosity::Changes && outcome != Outcome::Changed) {
+            return;
+        }
+        let message = match outcome {
+            Outcome::NotAccessed => not_accessed(name),
+            Outcome::Failed => failed_to_change(name, old, new),
+            Outcome::NotApplied => not_applied(name),
+            Outcome::Retained => retained(name, new),
+            Outcome::Changed => changed(name, old, new),
+        };
+        let _ = writeln!(self.out, "{}", message);
+    }
+
+    fn not_accessed(&mut self, name: &Path, error: KnownError) -> bool {
+        self.complain(error);
+        self.tell(Outcome::NotAccessed, name, 0, 0);
+        false
+    }
+
+    ///
+    /// Report a problem with a file, unless `-f` asks for quiet.
+    ///
+    fn complain(&mut self, error: KnownError) {
+        if !self.config.silent {
+            self.fail(error);
+        }
+    }
+
+    fn fail(&mut self, error: KnownError) -> bool {
+        if let Some(message) = &error.message {
+            let _ = writeln!(self.err, "{}", message);
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+    use common::test_support::TempDir;
+    use crate::changer::Changer;
+    use crate::config::Config;
+
+    fn run(args: &[&str], umask: u32, root: Option<(u64, u64)>) -> (bool, String, String) {
+        let args: Vec<String> = std::iter::once("chmod").chain(args.iter().copied()).map(String::from).collect();
+        let config = Config::new(&args);
+        let mode = config.mode.clone().unwrap();
+        let mut out = vec![];
+        let mut err = vec![];
+        let mut changer = Changer::new(&config, &mode, umask, root, &mut out, &mut err);
+        let mut success = true;
+        for file in &config.files {
+            success &= changer.change_operand(file.as_ref());
+        }
+        (success, String::from_utf8(out).unwrap(), String::from_utf8(err).unwrap())
+    }
+
+    #[test]
+    fn test_umask() {
+        let dir = TempDir::new("umask");
+        let a = dir.file("a", b"");
+        fs::set_permissions(&a, fs::Permissions::from_mode(0o444)).unwrap();
+
+        assert_eq!((true, String::new(), String::new()), run(&["--", "+w", &a], 0o022, None));
+        assert_eq!(0o644, fs::metadata(&a).unwrap().mode() & 0o7777);
+        fs::set_permissions(&a, fs::Permissions::from_mode(0o666)).unwrap();
+
+        /* as an option, the umask still applies, but what it kept from changing is complained about */
+        let surprise = format!("chmod: {}: new permissions are r--rw-rw-, not r--r--r--\n", a);
+        assert_eq!((false, String::new(), surprise), run(&["-w", &a], 0o022, None));
+        assert_eq!(0o466, fs::metadata(&a).unwrap().mode() & 0o7777);
+        assert_eq!((true, String::new(), String::new()), run(&["-r", &a], 0, None));
+        assert_eq!(0o022, fs::metadata(&a).unwrap().mode() & 0o7777);
+    }
+
+    #[test]
+    fn test_root() {
+        let dir = TempDir::new("root");
+        let a = dir.file("a", b"");
+        fs::set_permissions(&a, fs::Permissions::from_mode(0o644)).unwrap();
+        let status = fs::metadata(dir.path()).unwrap();
+        let root = Some((status.dev(), status.ino()));
+
+        let refused = format!("chmod: it is dangerous to operate recursively on '{}' (same as '/')\nchmod: use --no-preserve-root to override this failsafe\n", dir.path());
+        assert_eq!((false, String::new(), refused), run(&["-Rv", "700", dir.path()], 0, root));
+        assert_eq!(0o644, fs::metadata(&a).unwrap().mode() & 0o7777);
+        assert_eq!((true, String::new(), String::new()), run(&["700", dir.path()], 0, root));
+    }
+}
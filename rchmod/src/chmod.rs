@@ -0,0 +1,170 @@
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+use common::fs::permissions::{umask, Mode};
+use common::input::known_error::{report, KnownError};
+use crate::changer::Changer;
+use crate::config::Config;
+use crate::errors::failed_to_get_attributes;
+use crate::help::{help, version};
+
+const SUCCESS: i32 = 0;
+const FAILURE: i32 = 1;
+const ROOT: &str = "/";
+
+///
+/// Run `chmod` with command line arguments; the first argument is the program name.  Returns the
+/// exit status: 0 when every file was given the mode, and 1 otherwise.
+///
+pub(crate) fn chmod(args: &[String], out: &mut dyn Write, err: &mut dyn Write) -> i32 {
+    let config = Config::new(args);
+    if let Some(first) = config.errors.first() {
+        report(err, &config.errors);
+        return first.code;
+    }
+
+    if config.print_help_and_exit {
+        return help(out).map_or(FAILURE, |_| SUCCESS);
+    }
+    if config.print_version_and_exit {
+        return version(out).map_or(FAILURE, |_| SUCCESS);
+    }
+
+    let mode = match mode(&config) {
+        Ok(mode) => mode,
+        Err(error) => {
+            report(err, &[error]);
+            return FAILURE;
+        }
+    };
+    let root = if config.recursive && config.preserve_root {
+        match fs::metadata(ROOT) {
+            Ok(status) => Some((status.dev(), status.ino())),
+            Err(error) => {
+                report(err, &[failed_to_get_attributes(Path::new(ROOT), &error)]);
+                return FAILURE;
+            }
+        }
+    } else {
+        None
+    };
+
+    let mut changer = Changer::new(&config, &mode, umask(), root, out, err);
+    let mut success = true;
+    for file in &config.files {
+        success &= changer.change_operand(Path::new(file));
+    }
+    if success { SUCCESS } else { FAILURE }
+}
+
+///
+/// The mode given, or the one the `--reference` file has.
+///
+fn mode(config: &Config) -> Result<Mode, KnownError> {
+    match (&config.reference, &config.mode) {
+        (Some(reference), _) => fs::metadata(reference)
+            .map(|status| Mode::from_reference(status.mode()))
+            .map_err(|error| failed_to_get_attributes(Path::new(reference), &error)),
+        (None, Some(mode)) => Ok(mode.clone()),
+        (None, None) => unreachable!("a mode or a reference is given once the arguments are right"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::Path;
+    use common::test_support::TempDir;
+    use crate::chmod::chmod;
+
+    fn run(args: &[&str]) -> (i32, String, String) {
+        let args: Vec<String> = std::iter::once("chmod").chain(args.iter().copied()).map(String::from).collect();
+        let mut out = vec![];
+        let mut err = vec![];
+        let status = chmod(&args, &mut out, &mut err);
+        (status, String::from_utf8(out).unwrap(), String::from_utf8(err).unwrap())
+    }
+
+    fn mode(path: impl AsRef<Path>) -> u32 {
+        fs::symlink_metadata(path).unwrap().permissions().mode() & 0o7777
+    }
+
+    fn set_mode(path: impl AsRef<Path>, mode: u32) {
+        fs::set_permissions(path, fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    #[test]
+    fn test_modes() {
+        let dir = TempDir::new("modes");
+        let a = dir.file("a", b"");
+        let b = dir.file("b", b"");
+        set_mode(&a, 0o644);
+
+        assert_eq!((0, String::new(), String::new()), run(&["755", &a, &b]));
+        assert_eq!((0o755, 0o755), (mode(&a), mode(&b)));
+        assert_eq!((0, String::new(), String::new()), run(&["u=rw,go=r", &a]));
+        assert_eq!(0o644, mode(&a));
+        assert_eq!((0, String::new(), String::new()), run(&["go=u,u+s,+t", &a]));
+        assert_eq!(0o5666, mode(&a));
+        assert_eq!((0, String::new(), String::new()), run(&["a-rwxst,u+rX", &a]));
+        assert_eq!(0o400, mode(&a));
+        assert_eq!((0, String::new(), String::new()), run(&["--reference", &b, &a]));
+        assert_eq!(0o755, mode(&a));
+    }
+
+    #[test]
+    fn test_verbose() {
+        let dir = TempDir::new("verbose");
+        let a = dir.file("a", b"");
+        set_mode(&a, 0o644);
+
+        assert_eq!((0, format!("mode of '{}' retained as 0644 (rw-r--r--)\n", a), String::new()), run(&["-v", "644", &a]));
+        assert_eq!((0, String::new(), String::new()), run(&["-c", "644", &a]));
+        assert_eq!((0, format!("mode of '{}' changed from 0644 (rw-r--r--) to 4755 (rwsr-xr-x)\n", a), String::new()), run(&["-c", "4755", &a]));
+        assert_eq!((0, format!("mode of '{}' changed from 4755 (rwsr-xr-x) to 0000 (---------)\n", a), String::new()), run(&["-v", "0", &a]));
+    }
+
+    #[test]
+    fn test_recursive() {
+        let dir = TempDir::new("recursive");
+        fs::create_dir_all(dir.join("d/sub")).unwrap();
+        dir.file("d/f", b"");
+        dir.file("d/sub/g", b"");
+        let outside = dir.file("outside", b"");
+        std::os::unix::fs::symlink(&outside, dir.join("d/l")).unwrap();
+        std::os::unix::fs::symlink("d", dir.join("dl")).unwrap();
+        set_mode(&outside, 0o600);
+        let name = |name: &str| dir.join(name).to_str().unwrap().to_string();
+
+        assert_eq!(0, run(&["-R", "755", &name("d")]).0);
+        let changed = format!("mode of '{0}/' changed from 0755 (rwxr-xr-x) to 0750 (rwxr-x---)\nmode of '{0}/f' changed from 0755 (rwxr-xr-x) to 0750 (rwxr-x---)\n\
+                               mode of '{0}/sub' changed from 0755 (rwxr-xr-x) to 0750 (rwxr-x---)\nmode of '{0}/sub/g' changed from 0755 (rwxr-xr-x) to 0750 (rwxr-x---)\n", name("d"));
+        assert_eq!((0, changed, String::new()), run(&["-Rc", "o-rx", &format!("{}/", name("d"))]));
+        assert_eq!(0o600, mode(&outside));
+
+        let verbose = format!("mode of '{0}' retained as 0750 (rwxr-x---)\nmode of '{0}/f' retained as 0750 (rwxr-x---)\n\
+                               neither symbolic link '{0}/l' nor referent has been changed\nmode of '{0}/sub' retained as 0750 (rwxr-x---)\n\
+                               mode of '{0}/sub/g' retained as 0750 (rwxr-x---)\n", name("dl"));
+        assert_eq!((0, verbose, String::new()), run(&["-Rv", "g-w", &name("dl")]));
+        assert_eq!((0, String::new(), String::new()), run(&["-R", "u-w", &name("d/l")]));
+        assert_eq!(0o400, mode(&outside));
+    }
+
+    #[test]
+    fn test_errors() {
+        let dir = TempDir::new("errors");
+        let a = dir.file("a", b"");
+        let missing = dir.join("missing").to_str().unwrap().to_string();
+        let dangling = dir.join("dangling").to_str().unwrap().to_string();
+        std::os::unix::fs::symlink("nowhere", &dangling).unwrap();
+
+        assert_eq!((1, String::new(), format!("chmod: cannot access '{}': No such file or directory\n", missing)), run(&["644", &missing, &a]));
+        assert_eq!((1, format!("'{}' could not be accessed\n", missing), String::new()), run(&["-fv", "644", &missing]));
+        assert_eq!((1, String::new(), format!("chmod: cannot operate on dangling symlink '{}'\n", dangling)), run(&["644", &dangling]));
+        assert_eq!((1, String::new(), String::new()), run(&["-f", "644", &dangling]));
+        assert_eq!((1, String::new(), format!("chmod: failed to get attributes of '{}': No such file or directory\n", missing)), run(&["--reference", &missing, &a]));
+        assert_eq!((1, String::new(), "chmod: invalid mode: 'u+q'\nTry 'chmod --help' for more information.\n".to_string()), run(&["u+q", &a]));
+    }
+}
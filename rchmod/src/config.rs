@@ -0,0 +1,268 @@
+use common::fs::permissions::Mode;
+use common::input::flags::flags_options::{parse_options, OptionSpec, OptionValue, ParsedArgument};
+use common::input::known_error::KnownError;
+use crate::errors::{invalid_mode, missing_operand, missing_operand_after, mode_and_reference, option_error};
+
+const OPTIONS: [OptionSpec; 10] = [
+    OptionSpec::flag("changes", Some('c'), Some("changes")),
+    OptionSpec::flag("silent", Some('f'), Some("silent")),
+    OptionSpec::flag("silent", None, Some("quiet")),
+    OptionSpec::flag("verbose", Some('v'), Some("verbose")),
+    OptionSpec::flag("no-preserve-root", None, Some("no-preserve-root")),
+    OptionSpec::flag("preserve-root", None, Some("preserve-root")),
+    OptionSpec::required("reference", None, Some("reference")),
+    OptionSpec::flag("recursive", Some('R'), Some("recursive")),
+    OptionSpec::flag("help", None, Some("help")),
+    OptionSpec::flag("version", None, Some("version")),
+];
+
+/* an argument like `-w` or `-755` is a mode, rather than options, once one of these is met in it */
+const MODE_CHARACTERS: &str = "rwxXstugoa,+=01234567";
+const DASH: char = '-';
+const END_OF_OPTIONS: &str = "--";
+
+///
+/// What's said about each file; of `-c` and `-v`, the last one given wins.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Verbosity {
+    /// Nothing.
+    Off,
+    /// Only the modes that were changed; `-c`.
+    Changes,
+    /// Every file, changed or not; `-v`.
+    All,
+}
+
+///
+/// Storage for the application configuration.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct Config {
+    pub(crate) print_help_and_exit: bool,
+    pub(crate) print_version_and_exit: bool,
+
+    pub(crate) verbosity: Verbosity,
+    /* most errors about files aren't told; `-f` */
+    pub(crate) silent: bool,
+    pub(crate) preserve_root: bool,
+    pub(crate) recursive: bool,
+    /* the file whose mode is copied, in place of a mode */
+    pub(crate) reference: Option<String>,
+    pub(crate) mode: Option<Mode>,
+    /* the mode was given as options, as in `chmod -w`; bits the umask keeps from changing are complained about */
+    pub(crate) surprises: bool,
+
+    pub(crate) files: Vec<String>,
+    pub(crate) errors: Vec<KnownError>,
+}
+
+impl Default for Config {
+    ///
+    /// Generate [Default] configuration.
+    ///
+    fn default() -> Self {
+        Config {
+            print_help_and_exit: false,
+            print_version_and_exit: false,
+
+            verbosity: Verbosity::Off,
+            silent: false,
+            preserve_root: false,
+            recursive: false,
+            reference: None,
+            mode: None,
+            surprises: false,
+
+            files: vec![],
+            errors: vec![],
+        }
+    }
+}
+
+impl Config {
+    ///
+    /// Initialize the application configuration based on provided application arguments; the first
+    /// argument is the program name.
+    ///
+    pub(crate) fn new(args: &[String]) -> Config {
+        let mut config = Self::default();
+
+        let (args, modes) = mode_arguments(args.get(1..).unwrap_or_default());
+        let parsed = match parse_options(&args, &OPTIONS) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                config.errors.push(option_error(&error));
+                return config;
+            }
+        };
+
+        for argument in parsed {
+            match argument {
+                ParsedArgument::Operand(operand) => config.files.push(operand),
+                ParsedArgument::Option { id, value } => config.apply(id, value),
+            }
+        }
+        if config.print_help_and_exit || config.print_version_and_exit {
+            return config;
+        }
+
+        /* modes given as options are made one after the other; without any, the first operand is the mode */
+        config.surprises = !modes.is_empty();
+        let mut mode = (!modes.is_empty()).then(|| modes.join(","));
+        if config.reference.is_some() && mode.is_some() {
+            config.errors.push(mode_and_reference());
+            return config;
+        }
+        let operand = config.reference.is_none() && mode.is_none() && !config.files.is_empty();
+        if operand {
+            mode = Some(config.files.remove(0));
+        }
+        if config.files.is_empty() {
+            let error = match &mode {
+                Some(mode) if operand => missing_operand_after(mode),
+                _ => missing_operand(),
+            };
+            config.errors.push(error);
+            return config;
+        }
+
+        if let Some(mode) = mode {
+            match Mode::parse(&mode) {
+                Some(parsed) => config.mode = Some(parsed),
+                None => config.errors.push(invalid_mode(&mode)),
+            }
+        }
+        config
+    }
+
+    fn apply(&mut self, id: &str, value: Option<String>) {
+        match id {
+            "changes" => self.verbosity = Verbosity::Changes,
+            "silent" => self.silent = true,
+            "verbose" => self.verbosity = Verbosity::All,
+            "no-preserve-root" => self.preserve_root = false,
+            "preserve-root" => self.preserve_root = true,
+            "reference" => self.reference = value,
+            "recursive" => self.recursive = true,
+            "help" => self.print_help_and_exit = true,
+            "version" => self.print_version_and_exit = true,
+            _ => unreachable!("option {} has no handler", id),
+        }
+    }
+}
+
+///
+/// Take out the arguments that are modes, as in `chmod -w file`, in the order they're given; the
+/// rest are left as options and operands.  A mode can follow options in the same argument, but
+/// the whole of it is the mode then, as `-Rw` is.
+///
+fn mode_arguments(args: &[String]) -> (Vec<String>, Vec<String>) {
+    let mut rest = vec![];
+    let mut modes = vec![];
+    let mut iter = args.iter();
+    while let Some(argument) = iter.next() {
+        if argument == END_OF_OPTIONS {
+            rest.push(argument.clone());
+            rest.extend(iter.by_ref().cloned());
+        } else if let Some(long) = argument.strip_prefix(END_OF_OPTIONS) {
+            rest.push(argument.clone());
+            /* a value given apart from its option isn't looked at */
+            if takes_next(long) && let Some(value) = iter.next() {
+                rest.push(value.clone());
+            }
+        } else if argument.len() > 1 && argument.starts_with(DASH) && is_mode(&argument[1..]) {
+            modes.push(argument.clone());
+        } else {
+            rest.push(argument.clone());
+        }
+    }
+    (rest, modes)
+}
+
+///
+/// Whether a long option, as it's spelled, is one that takes the next argument as its value.
+///
+fn takes_next(long: &str) -> bool {
+    if long.contains('=') {
+        return false;
+    }
+    let matches: Vec<&OptionSpec> = OPTIONS.iter().filter(|spec| spec.long.is_some_and(|name| name.starts_with(long))).collect();
+    let spec = matches.iter().find(|spec| spec.long == Some(long)).or(if matches.len() == 1 { matches.first() } else { None });
+    spec.is_some_and(|spec| spec.value == OptionValue::Required)
+}
+
+///
+/// Whether a cluster of short options turns into a mode before it ends.
+///
+fn is_mode(cluster: &str) -> bool {
+    cluster.chars()
+        .find(|&flag| !OPTIONS.iter().any(|spec| spec.short == Some(flag)))
+        .is_some_and(|flag| MODE_CHARACTERS.contains(flag))
+}
+
+#[cfg(test)]
+mod tests {
+    use common::fs::permissions::Mode;
+    use crate::config::{mode_arguments, Config, Verbosity};
+
+    fn config(args: &[&str]) -> Config {
+        let args: Vec<String> = std::iter::once("chmod").chain(args.iter().copied()).map(String::from).collect();
+        Config::new(&args)
+    }
+
+    fn message(args: &[&str]) -> String {
+        config(args).errors.first().and_then(|error| error.message.clone()).unwrap_or_default()
+    }
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().copied().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_mode_arguments() {
+        assert_eq!((strings(&["-v", "a"]), strings(&["-w"])), mode_arguments(&strings(&["-v", "-w", "a"])));
+        assert_eq!((strings(&["a"]), strings(&["-Rw", "-755", "-=", "-+x", "-,"])), mode_arguments(&strings(&["-Rw", "-755", "a", "-=", "-+x", "-,"])));
+        assert_eq!((strings(&["-Rv", "-q", "-", "a"]), vec![]), mode_arguments(&strings(&["-Rv", "-q", "-", "a"])));
+        assert_eq!((strings(&["--", "-w", "a"]), vec![]), mode_arguments(&strings(&["--", "-w", "a"])));
+        assert_eq!((strings(&["--reference", "-w", "--ref", "-x", "a"]), vec![]), mode_arguments(&strings(&["--reference", "-w", "--ref", "-x", "a"])));
+        assert_eq!((strings(&["--reference=r", "--verbose", "a"]), strings(&["-w"])), mode_arguments(&strings(&["--reference=r", "--verbose", "-w", "a"])));
+    }
+
+    #[test]
+    fn test_options() {
+        let parsed = config(&["-Rfv", "--preserve-root", "u+x", "a", "b"]);
+        assert!(parsed.errors.is_empty());
+        assert!(parsed.recursive && parsed.silent && parsed.preserve_root && !parsed.surprises);
+        assert_eq!(Verbosity::All, parsed.verbosity);
+        assert_eq!(Mode::parse("u+x"), parsed.mode);
+        assert_eq!(vec!["a", "b"], parsed.files);
+
+        assert_eq!(Verbosity::Changes, config(&["-vc", "644", "a"]).verbosity);
+        assert!(!config(&["--preserve-root", "--no-preserve-root", "644", "a"]).preserve_root);
+        assert!(config(&["--quiet", "644", "a"]).silent);
+
+        let options = config(&["-w", "a", "-x"]);
+        assert!(options.surprises);
+        assert_eq!(Mode::parse("-w,-x"), options.mode);
+        assert_eq!(vec!["a"], options.files);
+
+        let reference = config(&["--reference=r", "644", "a"]);
+        assert_eq!((Some("r".to_string()), None), (reference.reference, reference.mode));
+        assert_eq!(vec!["644", "a"], reference.files);
+    }
+
+    #[test]
+    fn test_errors() {
+        let try_help = "Try 'chmod --help' for more information.";
+        assert_eq!(format!("chmod: missing operand\n{}", try_help), message(&[]));
+        assert_eq!(format!("chmod: missing operand\n{}", try_help), message(&["-w"]));
+        assert_eq!(format!("chmod: missing operand\n{}", try_help), message(&["--reference=a"]));
+        assert_eq!(format!("chmod: missing operand after '644'\n{}", try_help), message(&["644"]));
+        assert_eq!(format!("chmod: invalid mode: 'bogus'\n{}", try_help), message(&["bogus", "a"]));
+        assert_eq!(format!("chmod: invalid mode: '-Rw'\n{}", try_help), message(&["-Rw", "a"]));
+        assert_eq!(format!("chmod: cannot combine mode and --reference options\n{}", try_help), message(&["--reference=a", "-w", "b"]));
+        assert_eq!(format!("chmod: invalid option -- 'q'\n{}", try_help), message(&["-q", "644", "a"]));
+        assert!(config(&["--help"]).errors.is_empty());
+    }
+}
@@ -0,0 +1,119 @@
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use common::fs::permissions::permission_string;
+use common::input::flags::flags_options::OptionError;
+use common::input::known_error::KnownError;
+use common::input::source::describe_io_error;
+use common::output::quote::{quote_name, quote_path, shell_escape};
+
+const ERROR_CODE: i32 = 1;
+const PROGRAM: &str = "chmod";
+
+///
+/// The command line couldn't be split into options and operands.
+///
+pub(crate) fn option_error(error: &OptionError) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}\n{}", error.message(PROGRAM), try_help()))
+}
+
+pub(crate) fn mode_and_reference() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot combine mode and --reference options\n{}", PROGRAM, try_help()))
+}
+
+pub(crate) fn missing_operand() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: missing operand\n{}", PROGRAM, try_help()))
+}
+
+///
+/// A mode was given as the first operand, with no files after it.
+///
+pub(crate) fn missing_operand_after(mode: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: missing operand after {}\n{}", PROGRAM, quote_name(mode.as_bytes()), try_help()))
+}
+
+pub(crate) fn invalid_mode(mode: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: invalid mode: {}\n{}", PROGRAM, quote_name(mode.as_bytes()), try_help()))
+}
+
+///
+/// The file given with `--reference`, or the root directory, couldn't be looked at.
+///
+pub(crate) fn failed_to_get_attributes(name: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: failed to get attributes of {}: {}", PROGRAM, quote_path(name), describe_io_error(error)))
+}
+
+pub(crate) fn cannot_access(name: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot access {}: {}", PROGRAM, quote_path(name), describe_io_error(error)))
+}
+
+pub(crate) fn dangling_symlink(name: &Path) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot operate on dangling symlink {}", PROGRAM, quote_path(name)))
+}
+
+pub(crate) fn cannot_read_directory(name: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot read directory {}: {}", PROGRAM, quote_path(name), describe_io_error(error)))
+}
+
+pub(crate) fn changing_permissions(name: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: changing permissions of {}: {}", PROGRAM, quote_path(name), describe_io_error(error)))
+}
+
+///
+/// A mode with special bits was set, but the file couldn't be looked at again to see if they took.
+///
+pub(crate) fn getting_new_attributes(name: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: getting new attributes of {}: {}", PROGRAM, quote_path(name), describe_io_error(error)))
+}
+
+///
+/// `-R` was asked to change the root directory, with `--preserve-root`.
+///
+pub(crate) fn dangerous_root(name: &Path) -> KnownError {
+    let target = if name.as_os_str() == "/" { quote_path(name) } else { format!("{} (same as '/')", quote_path(name)) };
+    KnownError::new(ERROR_CODE, format!("{}: it is dangerous to operate recursively on {}\n{}: use --no-preserve-root to override this failsafe", PROGRAM, target, PROGRAM))
+}
+
+///
+/// A mode given as an option, as in `chmod -w`, left bits the umask kept it from changing.
+///
+pub(crate) fn new_permissions(name: &Path, mode: u32, expected: u32) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: {}: new permissions are {}, not {}", PROGRAM, shell_escape(name.as_os_str().as_bytes()), permission_string(mode), permission_string(expected)))
+}
+
+///
+/// What `-c` and `-v` say about a mode that was changed.
+///
+pub(crate) fn changed(name: &Path, from: u32, to: u32) -> String {
+    format!("mode of {} changed from {} to {}", quote_path(name), described(from), described(to))
+}
+
+pub(crate) fn failed_to_change(name: &Path, from: u32, to: u32) -> String {
+    format!("failed to change mode of {} from {} to {}", quote_path(name), described(from), described(to))
+}
+
+pub(crate) fn retained(name: &Path, mode: u32) -> String {
+    format!("mode of {} retained as {}", quote_path(name), described(mode))
+}
+
+///
+/// What `-v` says about a symbolic link met while recursing; it's left as it is.
+///
+pub(crate) fn not_applied(name: &Path) -> String {
+    format!("neither symbolic link {} nor referent has been changed", quote_path(name))
+}
+
+pub(crate) fn not_accessed(name: &Path) -> String {
+    format!("{} could not be accessed", quote_path(name))
+}
+
+///
+/// A mode in octal and as `ls -l` shows it: `0755 (rwxr-xr-x)`.
+///
+fn described(mode: u32) -> String {
+    format!("{:04o} ({})", mode & 0o7777, permission_string(mode))
+}
+
+fn try_help() -> String {
+    format!("Try '{} --help' for more information.", PROGRAM)
+}
@@ -0,0 +1,29 @@
+use std::io::{self, Write};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub(crate) fn version(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "rchmod {}", VERSION)
+}
+
+pub(crate) fn help(out: &mut dyn Write) -> io::Result<()> {
+    version(out)?;
+    writeln!(out)?;
+    writeln!(out, "Usage: chmod [OPTION]... MODE[,MODE]... FILE...")?;
+    writeln!(out, "  or:  chmod [OPTION]... OCTAL-MODE FILE...")?;
+    writeln!(out, "  or:  chmod [OPTION]... --reference=RFILE FILE...")?;
+    writeln!(out, "Change the mode of each FILE to MODE.")?;
+    writeln!(out, "With --reference, change the mode of each FILE to that of RFILE.")?;
+    writeln!(out)?;
+    writeln!(out, "  -c, --changes          like verbose but report only when a change is made")?;
+    writeln!(out, "  -f, --silent, --quiet  suppress most error messages")?;
+    writeln!(out, "  -v, --verbose          output a diagnostic for every file processed")?;
+    writeln!(out, "      --no-preserve-root  do not treat '/' specially (the default)")?;
+    writeln!(out, "      --preserve-root    fail to operate recursively on '/'")?;
+    writeln!(out, "      --reference=RFILE  use RFILE's mode instead of MODE values")?;
+    writeln!(out, "  -R, --recursive        change files and directories recursively")?;
+    writeln!(out, "      --help        display this help and exit")?;
+    writeln!(out, "      --version     output version information and exit")?;
+    writeln!(out)?;
+    writeln!(out, "Each MODE is of the form '[ugoa]*([-+=]([rwxXst]*|[ugo]))+|[-+=][0-7]+'.")
+}
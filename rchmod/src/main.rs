@@ -0,0 +1,16 @@
+mod changer;
+mod chmod;
+mod config;
+mod errors;
+mod help;
+
+use crate::chmod::chmod;
+
+///
+/// Main program entrypoint.
+///
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let status = chmod(&args, &mut std::io::stdout(), &mut std::io::stderr());
+    std::process::exit(status);
+}
@@ -0,0 +1,121 @@
+use std::fs::{File, Metadata, Permissions};
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::Path;
+use common::fs::owner::{change_owner, privileged};
+use common::fs::permissions::umask;
+use common::fs::times::Times;
+use common::fs::xattr::{get_attribute, list_attributes, set_attribute};
+use common::input::known_error::KnownError;
+use crate::config::Preserve;
+use crate::errors::{listing_attributes, preserving_ownership, preserving_permissions, preserving_times, setting_attribute};
+
+/* the permission bits, with set-user-ID, set-group-ID and sticky */
+const MODE_BITS: u32 = 0o7777;
+/* set-user-ID and set-group-ID, which mustn't be given to a file that didn't get its owner */
+const SET_ID_BITS: u32 = 0o6000;
+
+///
+/// Give `destination` the attributes of its source that `preserve` asks for; `metadata` describes
+/// the source, and `file` is the destination when it's open.  Times go first, so nothing done
+/// after can disturb them, and the mode goes last, as changing the owner clears set-user-ID bits.
+///
+/// Problems are added to `errors`; those that needn't fail the copy have a code of zero.
+///
+pub(crate) fn preserve_attributes(source: &Path, metadata: &Metadata, destination: &Path, file: Option<&File>, preserve: Preserve, errors: &mut Vec<KnownError>) {
+    let symbolic_link = metadata.file_type().is_symlink();
+    if preserve.timestamps {
+        let times = Times::of(metadata);
+        let result = match file {
+            Some(file) => file.set_times(times.file_times()),
+            None => times.apply(destination, false),
+        };
+        if let Err(error) = result {
+            errors.push(preserving_times(destination, &error, preserve.required));
+        }
+    }
+
+    let mut owned = true;
+    if preserve.ownership
+        && let Err(error) = change_owner(destination, metadata.uid(), metadata.gid()) {
+        owned = false;
+        /* only the privileged can give files away, so it's no surprise when others can't */
+        let refused = matches!(error.raw_os_error(), Some(libc::EPERM) | Some(libc::EINVAL));
+        if !refused || privileged() {
+            errors.push(preserving_ownership(destination, &error, preserve.required));
+        }
+    }
+
+    if preserve.xattr {
+        copy_extended_attributes(source, destination, preserve.required, errors);
+    }
+
+    if preserve.mode && !symbolic_link {
+        let mode = metadata.mode() & if owned { MODE_BITS } else { MODE_BITS & !SET_ID_BITS };
+        if let Err(error) = std::fs::set_permissions(destination, Permissions::from_mode(mode)) {
+            errors.push(preserving_permissions(destination, &error));
+        }
+    }
+}
+
+///
+/// Give a directory made with more permissions than its source, so it could be filled, the mode it
+/// would have been made with.
+///
+pub(crate) fn restore_directory_mode(destination: &Path, metadata: &Metadata, errors: &mut Vec<KnownError>) {
+    let mode = metadata.mode() & 0o777 & !umask();
+    if let Err(error) = std::fs::set_permissions(destination, Permissions::from_mode(mode)) {
+        errors.push(preserving_permissions(destination, &error));
+    }
+}
+
+///
+/// Copy each extended attribute of the source.  Unless they're `required`, attributes that can't
+/// be read or set are let go quietly; file systems and users differ too much in which they allow.
+///
+fn copy_extended_attributes(source: &Path, destination: &Path, required: bool, errors: &mut Vec<KnownError>) {
+    let names = match list_attributes(source) {
+        Ok(names) => names,
+        Err(error) => {
+            if required {
+                errors.push(listing_attributes(source, &error));
+            }
+            return;
+        }
+    };
+    for name in names {
+        if let Err(error) = get_attribute(source, &name).and_then(|value| set_attribute(destination, &name, &value))
+            && required {
+            errors.push(setting_attribute(name.as_bytes(), destination, &error));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+    use std::time::{Duration, UNIX_EPOCH};
+    use common::fs::times::Times;
+    use common::test_support::TempDir;
+    use crate::attributes::preserve_attributes;
+    use crate::config::Preserve;
+
+    #[test]
+    fn test_preserve_attributes() {
+        let dir = TempDir::new("attributes");
+        dir.file("source", b"");
+        dir.file("destination", b"");
+        let modified = UNIX_EPOCH + Duration::new(1_000_000, 0);
+        Times { accessed: Some(modified), modified: Some(modified) }.apply(&dir.join("source"), true).unwrap();
+        std::fs::set_permissions(dir.join("source"), std::fs::Permissions::from_mode(0o741)).unwrap();
+
+        let metadata = std::fs::metadata(dir.join("source")).unwrap();
+        let mut errors = vec![];
+        let preserve = Preserve { mode: true, timestamps: true, ..Preserve::default() };
+        preserve_attributes(&dir.join("source"), &metadata, &dir.join("destination"), None, preserve, &mut errors);
+
+        assert!(errors.is_empty());
+        let copied = std::fs::metadata(dir.join("destination")).unwrap();
+        assert_eq!(0o741, copied.mode() & 0o7777);
+        assert_eq!(Ok(modified), copied.modified().map_err(|_| ()));
+    }
+}
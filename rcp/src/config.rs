@@ -0,0 +1,304 @@
+use common::input::flags::flags_options::{parse_options, OptionSpec, ParsedArgument};
+use common::input::known_error::KnownError;
+use crate::errors::{both_links, invalid_argument, missing_destination, missing_file_operand, option_error, reflink_not_sparse};
+
+const PRESERVE_ARGUMENTS: [&[&str]; 6] = [&["mode"], &["timestamps"], &["ownership"], &["links"], &["xattr"], &["all"]];
+const REFLINK_ARGUMENTS: [&[&str]; 3] = [&["auto"], &["always"], &["never"]];
+const SPARSE_ARGUMENTS: [&[&str]; 3] = [&["never"], &["auto"], &["always"]];
+
+const OPTIONS: [OptionSpec; 19] = [
+    OptionSpec::flag("archive", Some('a'), Some("archive")),
+    OptionSpec::flag("no-dereference-links", Some('d'), None),
+    OptionSpec::flag("force", Some('f'), Some("force")),
+    OptionSpec::flag("interactive", Some('i'), Some("interactive")),
+    OptionSpec::flag("no-clobber", Some('n'), Some("no-clobber")),
+    OptionSpec::flag("command-line", Some('H'), None),
+    OptionSpec::flag("dereference", Some('L'), Some("dereference")),
+    OptionSpec::flag("no-dereference", Some('P'), Some("no-dereference")),
+    OptionSpec::flag("link", Some('l'), Some("link")),
+    OptionSpec::flag("preserve-defaults", Some('p'), None),
+    OptionSpec::optional("preserve", None, Some("preserve")),
+    OptionSpec::flag("recursive", Some('r'), Some("recursive")),
+    OptionSpec::flag("recursive", Some('R'), None),
+    OptionSpec::optional("reflink", None, Some("reflink")),
+    OptionSpec::required("sparse", None, Some("sparse")),
+    OptionSpec::flag("symbolic-link", Some('s'), Some("symbolic-link")),
+    OptionSpec::flag("update", Some('u'), Some("update")),
+    OptionSpec::flag("help", None, Some("help")),
+    OptionSpec::flag("version", None, Some("version")),
+];
+
+///
+/// Which symbolic links among the sources are followed, rather than copied as links.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Dereference {
+    /// None of them; `-P`.
+    Never,
+    /// Those named on the command line; `-H`.
+    CommandLine,
+    /// All of them; `-L`.
+    Always,
+}
+
+///
+/// What's done when the destination exists already.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Overwrite {
+    /// Replace it.
+    Always,
+    /// Ask first; `-i`.
+    Ask,
+    /// Leave it be; `-n`.
+    Never,
+}
+
+///
+/// What's made of each source file.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Action {
+    /// A copy of its contents.
+    Copy,
+    /// A hard link to it; `-l`.
+    HardLink,
+    /// A symbolic link to it; `-s`.
+    SymbolicLink,
+}
+
+///
+/// When the copy of a file is given holes; `--sparse`.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Sparse {
+    /// Never; every byte is written.
+    Never,
+    /// Where the source has holes.
+    Auto,
+    /// Wherever the source has a block of zeros.
+    Always,
+}
+
+///
+/// When a copy shares its data with the source, on file systems that can; `--reflink`.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Reflink {
+    /// Never; the data is always copied.
+    Never,
+    /// When it can, copying the data otherwise.
+    Auto,
+    /// Always; a file that can't be cloned isn't copied.
+    Always,
+}
+
+///
+/// The attributes of a source given to its copy.  When `required`, as with `--preserve`, failing to
+/// give one is an error; otherwise, as with `-p`, some failures are let go.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct Preserve {
+    pub(crate) mode: bool,
+    pub(crate) ownership: bool,
+    pub(crate) timestamps: bool,
+    /* hard links between files in the copy, as between the sources */
+    pub(crate) links: bool,
+    pub(crate) xattr: bool,
+    pub(crate) required: bool,
+}
+
+///
+/// Storage for the application configuration.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct Config {
+    pub(crate) print_help_and_exit: bool,
+    pub(crate) print_version_and_exit: bool,
+
+    pub(crate) recursive: bool,
+    pub(crate) dereference: Dereference,
+    pub(crate) overwrite: Overwrite,
+    pub(crate) force: bool,
+    pub(crate) update: bool,
+    pub(crate) action: Action,
+    pub(crate) preserve: Preserve,
+    pub(crate) sparse: Sparse,
+    pub(crate) reflink: Reflink,
+
+    pub(crate) files: Vec<String>,
+    pub(crate) errors: Vec<KnownError>,
+}
+
+impl Default for Config {
+    ///
+    /// Generate [Default] configuration.
+    ///
+    fn default() -> Self {
+        Config {
+            print_help_and_exit: false,
+            print_version_and_exit: false,
+
+            recursive: false,
+            dereference: Dereference::Always,
+            overwrite: Overwrite::Always,
+            force: false,
+            update: false,
+            action: Action::Copy,
+            preserve: Preserve::default(),
+            sparse: Sparse::Auto,
+            reflink: Reflink::Auto,
+
+            files: vec![],
+            errors: vec![],
+        }
+    }
+}
+
+///
+/// Options that are settled only once all of them have been seen.
+///
+#[derive(Default)]
+struct Selection {
+    dereference: Option<Dereference>,
+    hard_link: bool,
+    symbolic_link: bool,
+}
+
+impl Config {
+    ///
+    /// Initialize the application configuration based on provided application arguments; the first
+    /// argument is the program name.
+    ///
+    pub(crate) fn new(args: &[String]) -> Config {
+        let mut config = Self::default();
+
+        let parsed = match parse_options(args.get(1..).unwrap_or_default(), &OPTIONS) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                config.errors.push(option_error(&error));
+                return config;
+            }
+        };
+
+        let mut selection = Selection::default();
+        for argument in parsed {
+            match argument {
+                ParsedArgument::Operand(operand) => config.files.push(operand),
+                ParsedArgument::Option { id, value } => {
+                    if let Err(error) = config.apply(&mut selection, id, value) {
+                        config.errors.push(error);
+                        return config;
+                    }
+                }
+            }
+        }
+        if config.print_help_and_exit || config.print_version_and_exit {
+            return config;
+        }
+
+        config.action = match (selection.hard_link, selection.symbolic_link) {
+            (true, true) => {
+                config.errors.push(both_links());
+                return config;
+            }
+            (true, false) => Action::HardLink,
+            (false, true) => Action::SymbolicLink,
+            (false, false) => Action::Copy,
+        };
+        /* a tree is copied as it is, links and all, unless its files are being linked to */
+        config.dereference = selection.dereference.unwrap_or(if config.recursive && !selection.hard_link { Dereference::Never } else { Dereference::Always });
+
+        if config.reflink == Reflink::Always && config.sparse != Sparse::Auto {
+            config.errors.push(reflink_not_sparse());
+        } else if config.files.is_empty() {
+            config.errors.push(missing_file_operand());
+        } else if config.files.len() == 1 {
+            config.errors.push(missing_destination(&config.files[0]));
+        }
+        config
+    }
+
+    fn apply(&mut self, selection: &mut Selection, id: &str, value: Option<String>) -> Result<(), KnownError> {
+        match id {
+            "archive" => {
+                self.recursive = true;
+                selection.dereference = Some(Dereference::Never);
+                self.preserve = Preserve { required: self.preserve.required, ..parse_preserve("all")? };
+            }
+            "no-dereference-links" => {
+                selection.dereference = Some(Dereference::Never);
+                self.preserve.links = true;
+            }
+            "force" => self.force = true,
+            "interactive" => self.overwrite = Overwrite::Ask,
+            "no-clobber" => self.overwrite = Overwrite::Never,
+            "command-line" => selection.dereference = Some(Dereference::CommandLine),
+            "dereference" => selection.dereference = Some(Dereference::Always),
+            "no-dereference" => selection.dereference = Some(Dereference::Never),
+            "link" => selection.hard_link = true,
+            "symbolic-link" => selection.symbolic_link = true,
+            "preserve-defaults" => {
+                self.preserve.mode = true;
+                self.preserve.ownership = true;
+                self.preserve.timestamps = true;
+            }
+            "preserve" => {
+                let preserve = parse_preserve(value.as_deref().unwrap_or("mode,ownership,timestamps"))?;
+                self.preserve = Preserve {
+                    mode: self.preserve.mode || preserve.mode,
+                    ownership: self.preserve.ownership || preserve.ownership,
+                    timestamps: self.preserve.timestamps || preserve.timestamps,
+                    links: self.preserve.links || preserve.links,
+                    xattr: self.preserve.xattr || preserve.xattr,
+                    required: true,
+                };
+            }
+            "recursive" => self.recursive = true,
+            "reflink" => self.reflink = parse_reflink(value.as_deref().unwrap_or("always"))?,
+            "sparse" => self.sparse = parse_sparse(value.as_deref().unwrap_or_default())?,
+            "update" => self.update = true,
+            "help" => self.print_help_and_exit = true,
+            "version" => self.print_version_and_exit = true,
+            _ => unreachable!("option {} has no handler", id),
+        }
+        Ok(())
+    }
+}
+
+///
+/// A `--preserve` list: attributes separated by commas, where `all` is every one of them.
+///
+fn parse_preserve(value: &str) -> Result<Preserve, KnownError> {
+    let mut preserve = Preserve::default();
+    for name in value.split(',') {
+        match name {
+            "mode" => preserve.mode = true,
+            "ownership" => preserve.ownership = true,
+            "timestamps" => preserve.timestamps = true,
+            "links" => preserve.links = true,
+            "xattr" => preserve.xattr = true,
+            "all" => preserve = Preserve { mode: true, ownership: true, timestamps: true, links: true, xattr: true, required: false },
+            _ => return Err(invalid_argument(name, "--preserve", &PRESERVE_ARGUMENTS)),
+        }
+    }
+    Ok(preserve)
+}
+
+fn parse_reflink(value: &str) -> Result<Reflink, KnownError> {
+    match value {
+        "auto" => Ok(Reflink::Auto),
+        "always" => Ok(Reflink::Always),
+        "never" => Ok(Reflink::Never),
+        _ => Err(invalid_argument(value, "--reflink", &REFLINK_ARGUMENTS)),
+    }
+}
+
+fn parse_sparse(value: &str) -> Result<Sparse, KnownError> {
+    match value {
+        "never" => Ok(Sparse::Never),
+        "auto" => Ok(Sparse::Auto),
+        "always" => Ok(Sparse::Always),
+        _ => Err(invalid_argument(value, "--sparse", &SPARSE_ARGUMENTS)),
+    }
+}
@@ -0,0 +1,216 @@
+use std::fs::File;
+use std::io;
+use std::os::fd::AsRawFd;
+use std::os::unix::fs::FileExt;
+use crate::config::{Reflink, Sparse};
+
+const BUFFER_SIZE: usize = 128 * 1024;
+/* the most the kernel is asked to copy at once */
+const OFFLOAD_SIZE: u64 = 1 << 30;
+/* st_blocks counts 512 byte blocks, whatever the file system's own block size */
+const BLOCK_BYTES: u64 = 512;
+
+///
+/// Where copying a file's data went wrong: the error came from the source, the destination, or
+/// from trying to share the source's data with `--reflink=always`.
+///
+#[derive(Debug)]
+pub(crate) enum Failure {
+    Clone(io::Error),
+    Read(io::Error),
+    Write(io::Error),
+}
+
+///
+/// The source, about to be copied, as its metadata describes it.
+///
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Shape {
+    pub(crate) size: u64,
+    pub(crate) blocks: u64,
+    pub(crate) block_size: u64,
+}
+
+///
+/// Copy the data of `source` into `destination`, which is open for writing and empty.  The data is
+/// shared when the file system can and `reflink` allows; otherwise it's copied in the kernel where
+/// that works, and through a buffer where it doesn't.  Holes are made where `sparse` says.
+///
+pub(crate) fn copy_contents(source: &File, destination: &File, shape: Shape, sparse: Sparse, reflink: Reflink) -> Result<(), Failure> {
+    if reflink != Reflink::Never {
+        match clone(source, destination) {
+            Ok(()) => return Ok(()),
+            Err(error) if reflink == Reflink::Always => return Err(Failure::Clone(error)),
+            Err(_) => {}
+        }
+    }
+
+    /* the kernel may share data when asked to copy it, which --reflink=never rules out */
+    let offload = reflink != Reflink::Never;
+    match sparse {
+        Sparse::Auto if shape.blocks * BLOCK_BYTES < shape.size => {
+            if copy_extents(source, destination, shape.size, offload)? {
+                return Ok(());
+            }
+            copy_range(source, destination, 0, None, offload)
+        }
+        Sparse::Always => copy_skipping_zeros(source, destination, shape),
+        /* files in /proc claim to be empty, so they're copied until they run out */
+        _ => copy_range(source, destination, 0, None, offload && shape.size > 0),
+    }
+}
+
+///
+/// Share the source's data with the destination; this only works within file systems with copy on write.
+///
+fn clone(source: &File, destination: &File) -> io::Result<()> {
+    /* SAFETY: FICLONE only reads the source descriptor passed as its argument */
+    let result = unsafe { libc::ioctl(destination.as_raw_fd(), libc::FICLONE, source.as_raw_fd()) };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+///
+/// Copy only the parts of the source that hold data, leaving holes where it has them.  Returns
+/// false, having copied nothing, when the file system can't tell where the data is.
+///
+fn copy_extents(source: &File, destination: &File, size: u64, offload: bool) -> Result<bool, Failure> {
+    let mut offset = 0;
+    while offset < size {
+        let start = match seek(source, offset, libc::SEEK_DATA) {
+            Ok(start) => start,
+            /* there's no more data, only a hole to the end */
+            Err(error) if error.raw_os_error() == Some(libc::ENXIO) => break,
+            Err(error) if offset == 0 && error.raw_os_error() == Some(libc::EINVAL) => return Ok(false),
+            Err(error) => return Err(Failure::Read(error)),
+        };
+        let end = seek(source, start, libc::SEEK_HOLE).map_err(Failure::Read)?;
+        copy_range(source, destination, start, Some(end), offload)?;
+        offset = end;
+    }
+    destination.set_len(size).map_err(Failure::Write)?;
+    Ok(true)
+}
+
+///
+/// Copy the source a block at a time, leaving a hole wherever a block is all zeros.
+///
+fn copy_skipping_zeros(source: &File, destination: &File, shape: Shape) -> Result<(), Failure> {
+    let block_size = usize::try_from(shape.block_size).unwrap_or(BUFFER_SIZE).clamp(1, BUFFER_SIZE);
+    let mut buffer = vec![0; BUFFER_SIZE - BUFFER_SIZE % block_size];
+    let mut offset = 0;
+    loop {
+        let count = read_at(source, &mut buffer, offset)?;
+        if count == 0 {
+            break;
+        }
+        for (index, block) in buffer[..count].chunks(block_size).enumerate() {
+            if block.iter().any(|&byte| byte != 0) {
+                destination.write_all_at(block, offset + (index * block_size) as u64).map_err(Failure::Write)?;
+            }
+        }
+        offset += count as u64;
+    }
+    destination.set_len(offset).map_err(Failure::Write)
+}
+
+///
+/// Copy from `start` up to `end`, or to the end of the source, to the same place in the
+/// destination.  When `offload` is set the kernel is asked to do the copying; whatever it won't
+/// copy goes through a buffer.
+///
+fn copy_range(source: &File, destination: &File, start: u64, end: Option<u64>, offload: bool) -> Result<(), Failure> {
+    let mut offset = start;
+    if offload {
+        while end.is_none_or(|end| offset < end) {
+            let length = end.map_or(OFFLOAD_SIZE, |end| end - offset).min(OFFLOAD_SIZE) as usize;
+            let mut input = offset as libc::loff_t;
+            let mut output = offset as libc::loff_t;
+            /* SAFETY: copy_file_range only reads and writes through the descriptors and the two offsets */
+            let copied = unsafe { libc::copy_file_range(source.as_raw_fd(), &mut input, destination.as_raw_fd(), &mut output, length, 0) };
+            match copied {
+                0 => return Ok(()),
+                copied if copied > 0 => offset += copied as u64,
+                /* whatever went wrong, the buffer is tried, which tells reading from writing */
+                _ => break,
+            }
+        }
+    }
+
+    let mut buffer = vec![0; BUFFER_SIZE];
+    while end.is_none_or(|end| offset < end) {
+        let length = end.map_or(BUFFER_SIZE as u64, |end| end - offset).min(BUFFER_SIZE as u64) as usize;
+        let count = read_at(source, &mut buffer[..length], offset)?;
+        if count == 0 {
+            break;
+        }
+        destination.write_all_at(&buffer[..count], offset).map_err(Failure::Write)?;
+        offset += count as u64;
+    }
+    Ok(())
+}
+
+fn read_at(source: &File, buffer: &mut [u8], offset: u64) -> Result<usize, Failure> {
+    loop {
+        match source.read_at(buffer, offset) {
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            result => return result.map_err(Failure::Read),
+        }
+    }
+}
+
+fn seek(file: &File, offset: u64, whence: libc::c_int) -> io::Result<u64> {
+    /* SAFETY: lseek only moves the descriptor's offset */
+    let result = unsafe { libc::lseek(file.as_raw_fd(), offset as libc::off_t, whence) };
+    if result < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(result as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::os::unix::fs::MetadataExt;
+    use common::test_support::TempDir;
+    use crate::config::{Reflink, Sparse};
+    use crate::contents::{copy_contents, Shape};
+
+    fn copied(dir: &TempDir, contents: &[u8], size: u64, sparse: Sparse, reflink: Reflink) -> (Vec<u8>, u64) {
+        dir.file("source", contents);
+        let source = File::options().read(true).write(true).open(dir.join("source")).unwrap();
+        source.set_len(size).unwrap();
+        let metadata = source.metadata().unwrap();
+        let destination = File::create(dir.join("destination")).unwrap();
+        let shape = Shape { size: metadata.len(), blocks: metadata.blocks(), block_size: metadata.blksize() };
+        copy_contents(&source, &destination, shape, sparse, reflink).unwrap();
+        let metadata = destination.metadata().unwrap();
+        (std::fs::read(dir.join("destination")).unwrap(), metadata.blocks())
+    }
+
+    #[test]
+    fn test_copy_contents() {
+        let dir = TempDir::new("contents");
+        let data: Vec<u8> = (0..300_000).map(|n| (n % 251) as u8).collect();
+
+        for (sparse, reflink) in [(Sparse::Auto, Reflink::Auto), (Sparse::Never, Reflink::Never), (Sparse::Always, Reflink::Never), (Sparse::Auto, Reflink::Never)] {
+            assert_eq!(data, copied(&dir, &data, data.len() as u64, sparse, reflink).0);
+        }
+    }
+
+    #[test]
+    fn test_sparse() {
+        let dir = TempDir::new("sparse");
+        let size = 4 * 1024 * 1024;
+
+        let (contents, blocks) = copied(&dir, b"start", size, Sparse::Auto, Reflink::Never);
+        assert_eq!(size as usize, contents.len());
+        assert!(contents.starts_with(b"start") && contents[5..].iter().all(|&byte| byte == 0));
+        assert!(blocks * 512 < size);
+
+        let (contents, _) = copied(&dir, b"start", size, Sparse::Never, Reflink::Never);
+        assert_eq!(size as usize, contents.len());
+    }
+}
@@ -0,0 +1,361 @@
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::fs::{self, DirBuilder, File, Metadata};
+use std::io::{self, BufRead, ErrorKind, Write};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, MetadataExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use common::input::known_error::{report, KnownError};
+use crate::attributes::{preserve_attributes, restore_directory_mode};
+use crate::config::{Action, Config, Dereference, Overwrite};
+use crate::contents::{copy_contents, Failure, Shape};
+use crate::errors::{cannot_access, cannot_create_directory, cannot_create_fifo, cannot_create_hard_link, cannot_create_regular_file,
+                    cannot_create_special_file, cannot_create_symbolic_link, cannot_open, cannot_open_for_writing, cannot_read_symbolic_link,
+                    cannot_remove, cannot_stat, clone_failed, into_itself, omitting_directory, overwrite_directory, overwrite_non_directory,
+                    overwrite_prompt, read_error, relative_symbolic_link, same_file, write_error};
+
+/* a directory is filled before it's given its mode, so it's made with at least these permissions */
+const OWNER_PERMISSIONS: u32 = 0o700;
+const MODE_BITS: u32 = 0o7777;
+const PERMISSION_BITS: u32 = 0o777;
+
+///
+/// Copies files and trees the way the options ask, reporting problems as they're met.
+///
+pub(crate) struct Copier<'a> {
+    config: &'a Config,
+    input: &'a mut dyn BufRead,
+    err: &'a mut dyn Write,
+    /* the copies of sources with more than one link, by device and inode, so later links to the same source are linked to them */
+    copies: HashMap<(u64, u64), PathBuf>,
+}
+
+impl<'a> Copier<'a> {
+    ///
+    /// `input` answers the questions `-i` asks, which are written to `err` along with any problems.
+    ///
+    pub(crate) fn new(config: &'a Config, input: &'a mut dyn BufRead, err: &'a mut dyn Write) -> Copier<'a> {
+        Copier { config, input, err, copies: HashMap::new() }
+    }
+
+    ///
+    /// Copy a source named on the command line to `destination`; returns whether all went well.
+    ///
+    pub(crate) fn copy_operand(&mut self, source: &Path, destination: &Path) -> bool {
+        self.copy(source, destination, true)
+    }
+
+    fn copy(&mut self, source: &Path, destination: &Path, command_line: bool) -> bool {
+        let config = self.config;
+        let follow = match config.dereference {
+            Dereference::Never => false,
+            Dereference::CommandLine => command_line,
+            Dereference::Always => true,
+        };
+        let metadata = match if follow { fs::metadata(source) } else { fs::symlink_metadata(source) } {
+            Ok(metadata) => metadata,
+            Err(error) => return self.fail(cannot_stat(source, &error)),
+        };
+        let directory = metadata.is_dir();
+        if directory && !config.recursive {
+            return self.fail(omitting_directory(source));
+        }
+
+        let existing = fs::symlink_metadata(destination).ok();
+        if let Some(existing) = &existing {
+            /* a link, or what's to be replaced by one, is compared with what's there; anything else with what that leads to */
+            let link = metadata.file_type().is_symlink() || config.action == Action::SymbolicLink;
+            let target = if link { Some(existing.clone()) } else { fs::metadata(destination).ok() };
+            if target.is_some_and(|target| same_inode(&metadata, &target)) {
+                /* asked to link a file to itself, there's nothing to do */
+                if config.action == Action::HardLink && !directory {
+                    return true;
+                }
+                return self.fail(same_file(source, destination));
+            }
+        }
+
+        if directory {
+            if command_line && let Some(error) = into_itself_error(source, destination) {
+                return self.fail(error);
+            }
+            return match existing {
+                Some(existing) if !existing.is_dir() && !fs::metadata(destination).is_ok_and(|target| target.is_dir()) => {
+                    self.fail(overwrite_non_directory(destination, source))
+                }
+                existing => self.copy_directory(source, &metadata, destination, existing.is_none()),
+            };
+        }
+
+        if let Some(existing) = &existing {
+            if existing.is_dir() {
+                return self.fail(overwrite_directory(destination));
+            }
+            if !self.replaces(&metadata, existing, destination) {
+                return true;
+            }
+        }
+
+        match config.action {
+            Action::HardLink => self.link(source, destination, follow, existing.is_some(), config.force),
+            Action::SymbolicLink => self.symbolic_link(source, destination, existing.is_some()),
+            Action::Copy if metadata.file_type().is_symlink() => self.copy_symbolic_link(source, &metadata, destination, existing.is_some()),
+            Action::Copy if config.recursive && is_special(&metadata) => self.copy_special(source, &metadata, destination, existing.is_some()),
+            Action::Copy => self.copy_file(source, &metadata, destination, existing.is_some()),
+        }
+    }
+
+    ///
+    /// Whether an existing destination is to be replaced: `-n` leaves it, `-u` leaves it when it's
+    /// no older than the source, and `-i` asks.
+    ///
+    fn replaces(&mut self, metadata: &Metadata, existing: &Metadata, destination: &Path) -> bool {
+        let config = self.config;
+        if config.overwrite == Overwrite::Never {
+            return false;
+        }
+        if config.update
+            && let (Ok(modified), Ok(source_modified)) = (existing.modified(), metadata.modified())
+            && modified >= source_modified {
+            return false;
+        }
+        if config.overwrite == Overwrite::Ask {
+            let _ = write!(self.err, "{}", overwrite_prompt(destination));
+            let _ = self.err.flush();
+            let mut answer = String::new();
+            if self.input.read_line(&mut answer).is_err() {
+                return false;
+            }
+            return answer.starts_with(['y', 'Y']);
+        }
+        true
+    }
+
+    fn copy_directory(&mut self, source: &Path, metadata: &Metadata, destination: &Path, create: bool) -> bool {
+        let config = self.config;
+        if create && let Err(error) = DirBuilder::new().mode(metadata.mode() & MODE_BITS | OWNER_PERMISSIONS).create(destination) {
+            return self.fail(cannot_create_directory(destination, &error));
+        }
+
+        let mut names = match fs::read_dir(source).and_then(|entries| entries.map(|entry| entry.map(|entry| entry.file_name())).collect::<io::Result<Vec<_>>>()) {
+            Ok(names) => names,
+            Err(error) => return self.fail(cannot_access(source, &error)),
+        };
+        names.sort();
+        let mut success = true;
+        for name in names {
+            success &= self.copy(&source.join(&name), &destination.join(&name), false);
+        }
+
+        let mut errors = vec![];
+        preserve_attributes(source, metadata, destination, None, config.preserve, &mut errors);
+        if create && !config.preserve.mode && metadata.mode() & OWNER_PERMISSIONS != OWNER_PERMISSIONS {
+            restore_directory_mode(destination, metadata, &mut errors);
+        }
+        self.report(&errors) && success
+    }
+
+    fn copy_file(&mut self, source: &Path, metadata: &Metadata, destination: &Path, exists: bool) -> bool {
+        let config = self.config;
+        if let Some(linked) = self.linked_copy(metadata) {
+            return self.link(&linked, destination, false, exists, true);
+        }
+
+        let input = match File::open(source) {
+            Ok(input) => input,
+            Err(error) => return self.fail(cannot_open(source, &error)),
+        };
+        let output = match self.create(destination, metadata, exists) {
+            Ok(output) => output,
+            Err(error) => return self.fail(error),
+        };
+
+        let shape = Shape { size: metadata.len(), blocks: metadata.blocks(), block_size: metadata.blksize() };
+        if let Err(failure) = copy_contents(&input, &output, shape, config.sparse, config.reflink) {
+            return self.fail(match failure {
+                Failure::Clone(error) => clone_failed(destination, source, &error),
+                Failure::Read(error) => read_error(source, &error),
+                Failure::Write(error) => write_error(destination, &error),
+            });
+        }
+
+        self.remember(metadata, destination);
+        let mut errors = vec![];
+        preserve_attributes(source, metadata, destination, Some(&output), config.preserve, &mut errors);
+        self.report(&errors)
+    }
+
+    ///
+    /// Open the destination for a file's copy.  A new file gets the source's permissions, less the
+    /// umask; an existing one is emptied, or with `-f`, replaced when it can't be opened.
+    ///
+    fn create(&mut self, destination: &Path, metadata: &Metadata, exists: bool) -> Result<File, KnownError> {
+        let create = |destination: &Path| File::options().write(true).create_new(true).mode(metadata.mode() & PERMISSION_BITS).open(destination);
+        if !exists {
+            return create(destination).map_err(|error| cannot_create_regular_file(destination, &error));
+        }
+        match File::options().write(true).truncate(true).open(destination) {
+            Ok(output) => Ok(output),
+            Err(_) if self.config.force && fs::remove_file(destination).is_ok() => {
+                create(destination).map_err(|error| cannot_create_regular_file(destination, &error))
+            }
+            Err(error) => Err(cannot_open_for_writing(destination, &error)),
+        }
+    }
+
+    fn copy_symbolic_link(&mut self, source: &Path, metadata: &Metadata, destination: &Path, exists: bool) -> bool {
+        let target = match fs::read_link(source) {
+            Ok(target) => target,
+            Err(error) => return self.fail(cannot_read_symbolic_link(source, &error)),
+        };
+        if exists && let Err(error) = fs::remove_file(destination) {
+            return self.fail(cannot_remove(destination, &error));
+        }
+        if let Err(error) = std::os::unix::fs::symlink(&target, destination) {
+            return self.fail(cannot_create_symbolic_link(destination, &target, &error));
+        }
+
+        let mut errors = vec![];
+        preserve_attributes(source, metadata, destination, None, self.config.preserve, &mut errors);
+        self.report(&errors)
+    }
+
+    ///
+    /// Make a new fifo, device or socket like the source; with `-r` these aren't read from.
+    ///
+    fn copy_special(&mut self, source: &Path, metadata: &Metadata, destination: &Path, exists: bool) -> bool {
+        if exists && let Err(error) = fs::remove_file(destination) {
+            return self.fail(cannot_remove(destination, &error));
+        }
+        if let Err(error) = make_node(destination, metadata.mode(), metadata.rdev()) {
+            let fifo = metadata.file_type().is_fifo();
+            return self.fail(if fifo { cannot_create_fifo(destination, &error) } else { cannot_create_special_file(destination, &error) });
+        }
+
+        let mut errors = vec![];
+        preserve_attributes(source, metadata, destination, None, self.config.preserve, &mut errors);
+        self.report(&errors)
+    }
+
+    ///
+    /// Link `destination` to `source`, first removing what's there when `replace` is set.
+    ///
+    fn link(&mut self, source: &Path, destination: &Path, follow: bool, exists: bool, replace: bool) -> bool {
+        if exists && replace && let Err(error) = fs::remove_file(destination) {
+            return self.fail(cannot_remove(destination, &error));
+        }
+        match hard_link(source, destination, follow) {
+            Ok(()) => true,
+            Err(error) => self.fail(cannot_create_hard_link(destination, source, &error)),
+        }
+    }
+
+    ///
+    /// A link leads to the source just as it's named, so a relative one only works from the
+    /// directory it was named in.
+    ///
+    fn symbolic_link(&mut self, source: &Path, destination: &Path, exists: bool) -> bool {
+        if source.is_relative() && !in_current_directory(destination) {
+            return self.fail(relative_symbolic_link(destination));
+        }
+        if exists && self.config.force && let Err(error) = fs::remove_file(destination) {
+            return self.fail(cannot_remove(destination, &error));
+        }
+        match std::os::unix::fs::symlink(source, destination) {
+            Ok(()) => true,
+            Err(error) => self.fail(cannot_create_symbolic_link(destination, source, &error)),
+        }
+    }
+
+    ///
+    /// The copy already made of another link to this file, when links are preserved.
+    ///
+    fn linked_copy(&self, metadata: &Metadata) -> Option<PathBuf> {
+        if !self.config.preserve.links || metadata.nlink() < 2 {
+            return None;
+        }
+        self.copies.get(&(metadata.dev(), metadata.ino())).cloned()
+    }
+
+    fn remember(&mut self, metadata: &Metadata, destination: &Path) {
+        if self.config.preserve.links && metadata.nlink() > 1 {
+            self.copies.insert((metadata.dev(), metadata.ino()), destination.to_path_buf());
+        }
+    }
+
+    fn fail(&mut self, error: KnownError) -> bool {
+        self.report(&[error])
+    }
+
+    ///
+    /// Write problems out; returns whether none of them fails the copy.
+    ///
+    fn report(&mut self, errors: &[KnownError]) -> bool {
+        report(self.err, errors);
+        errors.iter().all(|error| error.code == 0)
+    }
+}
+
+fn same_inode(first: &Metadata, second: &Metadata) -> bool {
+    first.dev() == second.dev() && first.ino() == second.ino()
+}
+
+fn is_special(metadata: &Metadata) -> bool {
+    let file_type = metadata.file_type();
+    file_type.is_fifo() || file_type.is_char_device() || file_type.is_block_device() || file_type.is_socket()
+}
+
+///
+/// A directory can't be copied into itself or anywhere below it; the copy would never end.
+///
+fn into_itself_error(source: &Path, destination: &Path) -> Option<KnownError> {
+    let source_path = fs::canonicalize(source).ok()?;
+    let destination_path = match fs::canonicalize(destination) {
+        Ok(path) => path,
+        Err(_) => {
+            let parent = destination.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."));
+            fs::canonicalize(parent).ok()?.join(destination.file_name()?)
+        }
+    };
+    destination_path.starts_with(&source_path).then(|| into_itself(source, destination))
+}
+
+///
+/// Whether a path names something in the current directory.
+///
+fn in_current_directory(path: &Path) -> bool {
+    let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) else {
+        return true;
+    };
+    match (fs::metadata("."), fs::metadata(parent)) {
+        (Ok(current), Ok(parent)) => same_inode(&current, &parent),
+        _ => false,
+    }
+}
+
+///
+/// Make a hard link; a symbolic link source is itself linked to, unless it's to be followed.
+///
+fn hard_link(source: &Path, destination: &Path, follow: bool) -> io::Result<()> {
+    let source = c_path(source)?;
+    let destination = c_path(destination)?;
+    let flags = if follow { libc::AT_SYMLINK_FOLLOW } else { 0 };
+    /* SAFETY: linkat only reads the two paths */
+    if unsafe { libc::linkat(libc::AT_FDCWD, source.as_ptr(), libc::AT_FDCWD, destination.as_ptr(), flags) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn make_node(path: &Path, mode: u32, device: u64) -> io::Result<()> {
+    let path = c_path(path)?;
+    /* SAFETY: mknod only reads the path */
+    if unsafe { libc::mknod(path.as_ptr(), mode as libc::mode_t, device as libc::dev_t) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn c_path(path: &Path) -> io::Result<CString> {
+    CString::new(path.as_os_str().as_bytes()).map_err(|_| io::Error::from(ErrorKind::InvalidInput))
+}
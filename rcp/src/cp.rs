@@ -0,0 +1,182 @@
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use common::fs::path::base_name;
+use common::input::known_error::report;
+use crate::config::Config;
+use crate::copy::Copier;
+use crate::errors::{cannot_create_regular_file, target_error, target_not_directory};
+use crate::help::{help, version};
+
+const SUCCESS: i32 = 0;
+const FAILURE: i32 = 1;
+
+///
+/// Run `cp` with command line arguments; the first argument is the program name.  `input` answers
+/// the questions `-i` asks.  Returns the exit status: 0 when everything was copied, 1 otherwise.
+///
+pub(crate) fn cp(args: &[String], input: &mut dyn BufRead, out: &mut dyn Write, err: &mut dyn Write) -> i32 {
+    let config = Config::new(args);
+    if let Some(first) = config.errors.first() {
+        report(err, &config.errors);
+        return first.code;
+    }
+
+    if config.print_help_and_exit {
+        return help(out).map_or(FAILURE, |_| SUCCESS);
+    }
+    if config.print_version_and_exit {
+        return version(out).map_or(FAILURE, |_| SUCCESS);
+    }
+
+    let Some((target, sources)) = config.files.split_last() else {
+        return FAILURE;
+    };
+    let target = Path::new(target);
+    let into_directory = fs::metadata(target).is_ok_and(|metadata| metadata.is_dir());
+    if !into_directory {
+        if sources.len() > 1 {
+            let error = match fs::metadata(target) {
+                Err(error) => target_error(target, &error),
+                Ok(_) => target_not_directory(target),
+            };
+            report(err, &[error]);
+            return FAILURE;
+        }
+        /* a name ending in a slash can only be a directory */
+        if target.as_os_str().to_string_lossy().ends_with('/') && fs::metadata(&sources[0]).is_ok_and(|metadata| !metadata.is_dir()) {
+            report(err, &[cannot_create_regular_file(target, &io::Error::from_raw_os_error(libc::ENOTDIR))]);
+            return FAILURE;
+        }
+    }
+
+    let mut copier = Copier::new(&config, input, err);
+    let mut success = true;
+    for source in sources {
+        let source = Path::new(source);
+        let destination = if into_directory { target.join(base_name(source)) } else { target.to_path_buf() };
+        success &= copier.copy_operand(source, &destination);
+    }
+    if success { SUCCESS } else { FAILURE }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+    use common::test_support::TempDir;
+    use crate::cp::cp;
+
+    fn run(args: &[&str], input: &str) -> (i32, String) {
+        let args: Vec<String> = std::iter::once("cp").chain(args.iter().copied()).map(String::from).collect();
+        let mut out = vec![];
+        let mut err = vec![];
+        let status = cp(&args, &mut input.as_bytes(), &mut out, &mut err);
+        (status, String::from_utf8(err).unwrap())
+    }
+
+    fn tree() -> TempDir {
+        let dir = TempDir::new("tree");
+        fs::create_dir_all(dir.join("source/sub")).unwrap();
+        dir.file("source/a", b"alpha");
+        dir.file("source/sub/b", b"beta");
+        fs::hard_link(dir.join("source/a"), dir.join("source/sub/linked")).unwrap();
+        std::os::unix::fs::symlink("a", dir.join("source/link")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_files() {
+        let dir = TempDir::new("files");
+        let a = dir.file("a", b"alpha");
+        let b = dir.join("b").to_str().unwrap().to_string();
+
+        assert_eq!((0, String::new()), run(&[&a, &b], ""));
+        assert_eq!(b"alpha".to_vec(), fs::read(&b).unwrap());
+
+        let target = dir.join("target");
+        fs::create_dir(&target).unwrap();
+        assert_eq!((0, String::new()), run(&[&a, &b, target.to_str().unwrap()], ""));
+        assert_eq!(b"alpha".to_vec(), fs::read(target.join("a")).unwrap());
+        assert_eq!(b"alpha".to_vec(), fs::read(target.join("b")).unwrap());
+    }
+
+    #[test]
+    fn test_overwrite() {
+        let dir = TempDir::new("overwrite");
+        let a = dir.file("a", b"new");
+        let b = dir.file("b", b"old");
+
+        assert_eq!((0, String::new()), run(&["-n", &a, &b], ""));
+        assert_eq!(b"old".to_vec(), fs::read(&b).unwrap());
+        assert_eq!((0, format!("cp: overwrite '{}'? ", b)), run(&["-i", &a, &b], "no\n"));
+        assert_eq!(b"old".to_vec(), fs::read(&b).unwrap());
+        assert_eq!((0, String::new()), run(&["-u", &a, &b], ""));
+        assert_eq!(b"old".to_vec(), fs::read(&b).unwrap());
+        assert_eq!((0, format!("cp: overwrite '{}'? ", b)), run(&["-i", &a, &b], "y\n"));
+        assert_eq!(b"new".to_vec(), fs::read(&b).unwrap());
+    }
+
+    #[test]
+    fn test_recursive() {
+        let dir = tree();
+        let source = dir.join("source").to_str().unwrap().to_string();
+        let copy = dir.join("copy");
+
+        assert_eq!((0, String::new()), run(&["-r", &source, copy.to_str().unwrap()], ""));
+        assert_eq!(b"beta".to_vec(), fs::read(copy.join("sub/b")).unwrap());
+        assert_eq!("a", fs::read_link(copy.join("link")).unwrap().to_str().unwrap());
+        assert_ne!(fs::metadata(copy.join("a")).unwrap().ino(), fs::metadata(copy.join("sub/linked")).unwrap().ino());
+
+        let archive = dir.join("archive");
+        fs::set_permissions(dir.join("source/sub/b"), fs::Permissions::from_mode(0o604)).unwrap();
+        assert_eq!((0, String::new()), run(&["-a", &source, archive.to_str().unwrap()], ""));
+        assert_eq!(fs::metadata(archive.join("a")).unwrap().ino(), fs::metadata(archive.join("sub/linked")).unwrap().ino());
+        assert_eq!(0o604, fs::metadata(archive.join("sub/b")).unwrap().mode() & 0o7777);
+        assert_eq!(fs::metadata(dir.join("source/sub/b")).unwrap().modified().unwrap(), fs::metadata(archive.join("sub/b")).unwrap().modified().unwrap());
+    }
+
+    #[test]
+    fn test_links() {
+        let dir = tree();
+        let a = dir.join("source/a").to_str().unwrap().to_string();
+        let link = dir.join("source/link").to_str().unwrap().to_string();
+
+        let hard = dir.join("hard");
+        assert_eq!((0, String::new()), run(&["-l", &a, hard.to_str().unwrap()], ""));
+        assert_eq!(fs::metadata(&a).unwrap().ino(), fs::metadata(&hard).unwrap().ino());
+        assert_eq!((0, String::new()), run(&["-l", &a, hard.to_str().unwrap()], ""));
+
+        let symbolic = dir.join("symbolic");
+        assert_eq!((0, String::new()), run(&["-s", &a, symbolic.to_str().unwrap()], ""));
+        assert_eq!(a, fs::read_link(&symbolic).unwrap().to_str().unwrap());
+        assert_eq!((1, format!("cp: cannot create symbolic link '{}' to '{}': File exists\n", symbolic.display(), a)), run(&["-s", &a, symbolic.to_str().unwrap()], ""));
+
+        let followed = dir.join("followed");
+        assert_eq!((0, String::new()), run(&[&link, followed.to_str().unwrap()], ""));
+        assert!(fs::symlink_metadata(&followed).unwrap().is_file());
+        let kept = dir.join("kept");
+        assert_eq!((0, String::new()), run(&["-P", &link, kept.to_str().unwrap()], ""));
+        assert!(fs::symlink_metadata(&kept).unwrap().file_type().is_symlink());
+    }
+
+    #[test]
+    fn test_errors() {
+        let dir = tree();
+        let source = dir.join("source").to_str().unwrap().to_string();
+        let a = dir.join("source/a").to_str().unwrap().to_string();
+        let missing = dir.join("missing").to_str().unwrap().to_string();
+
+        assert_eq!((1, format!("cp: -r not specified; omitting directory '{}'\n", source)), run(&[&source, dir.path()], ""));
+        assert_eq!((1, format!("cp: '{}' and '{}' are the same file\n", a, a)), run(&[&a, &a], ""));
+        assert_eq!((1, format!("cp: cannot stat '{}': No such file or directory\n", missing)), run(&[&missing, &a], ""));
+        assert_eq!((1, format!("cp: target '{}' is not a directory\n", a)), run(&[&source, &source, &a], ""));
+        let inside = format!("{}/sub/copy", source);
+        assert_eq!((1, format!("cp: cannot copy a directory, '{}', into itself, '{}'\n", source, inside)), run(&["-r", &source, &inside], ""));
+        let plain = dir.file("plain", b"");
+        assert_eq!((1, format!("cp: cannot overwrite non-directory '{}' with directory '{}'\n", plain, source)), run(&["-r", &source, &plain], ""));
+
+        assert_eq!((1, "cp: missing file operand\nTry 'cp --help' for more information.\n".to_string()), run(&[], ""));
+        assert_eq!((1, "cp: cannot make both hard and symbolic links\nTry 'cp --help' for more information.\n".to_string()), run(&["-ls", "a", "b"], ""));
+    }
+}
@@ -0,0 +1,203 @@
+use std::io;
+use std::path::Path;
+use common::input::flags::flags_options::OptionError;
+use common::input::known_error::KnownError;
+use common::input::source::describe_io_error;
+use common::output::quote::{quote_name, quote_path};
+
+const ERROR_CODE: i32 = 1;
+const PROGRAM: &str = "cp";
+
+///
+/// The command line couldn't be split into options and operands.
+///
+pub(crate) fn option_error(error: &OptionError) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}\n{}", error.message(PROGRAM), try_help()))
+}
+
+///
+/// An option was given a value that isn't one of its choices; each choice is listed with its synonyms.
+///
+pub(crate) fn invalid_argument(argument: &str, option: &str, choices: &[&[&str]]) -> KnownError {
+    let choices: Vec<String> = choices.iter()
+        .map(|synonyms| format!("  - {}", synonyms.iter().map(|name| format!("'{}'", name)).collect::<Vec<_>>().join(", ")))
+        .collect();
+    KnownError::new(ERROR_CODE, format!("{}: invalid argument '{}' for '{}'\nValid arguments are:\n{}\n{}", PROGRAM, argument, option, choices.join("\n"), try_help()))
+}
+
+///
+/// Both `-l` and `-s` were given.
+///
+pub(crate) fn both_links() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot make both hard and symbolic links\n{}", PROGRAM, try_help()))
+}
+
+///
+/// `--reflink=always` was given with a `--sparse` other than `auto`.
+///
+pub(crate) fn reflink_not_sparse() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: --reflink can be used only with --sparse=auto\n{}", PROGRAM, try_help()))
+}
+
+pub(crate) fn missing_file_operand() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: missing file operand\n{}", PROGRAM, try_help()))
+}
+
+pub(crate) fn missing_destination(source: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: missing destination file operand after {}\n{}", PROGRAM, quote_name(source.as_bytes()), try_help()))
+}
+
+///
+/// There's more than one source, and the target couldn't be looked at.
+///
+pub(crate) fn target_error(target: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: target {}: {}", PROGRAM, quote_path(target), describe_io_error(error)))
+}
+
+///
+/// There's more than one source, and the target isn't a directory to put them in.
+///
+pub(crate) fn target_not_directory(target: &Path) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: target {} is not a directory", PROGRAM, quote_path(target)))
+}
+
+pub(crate) fn cannot_stat(name: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot stat {}: {}", PROGRAM, quote_path(name), describe_io_error(error)))
+}
+
+///
+/// A directory was met without `-r`.
+///
+pub(crate) fn omitting_directory(name: &Path) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: -r not specified; omitting directory {}", PROGRAM, quote_path(name)))
+}
+
+pub(crate) fn same_file(source: &Path, destination: &Path) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: {} and {} are the same file", PROGRAM, quote_path(source), quote_path(destination)))
+}
+
+///
+/// A directory would be copied into a directory inside itself, which would never end.
+///
+pub(crate) fn into_itself(source: &Path, destination: &Path) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot copy a directory, {}, into itself, {}", PROGRAM, quote_path(source), quote_path(destination)))
+}
+
+pub(crate) fn overwrite_directory(destination: &Path) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot overwrite directory {} with non-directory", PROGRAM, quote_path(destination)))
+}
+
+pub(crate) fn overwrite_non_directory(destination: &Path, source: &Path) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot overwrite non-directory {} with directory {}", PROGRAM, quote_path(destination), quote_path(source)))
+}
+
+///
+/// `-s` was given a relative source for a link that isn't in the current directory, where it
+/// would lead somewhere else.
+///
+pub(crate) fn relative_symbolic_link(destination: &Path) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: {}: can make relative symbolic links only in current directory", PROGRAM, destination.display()))
+}
+
+pub(crate) fn cannot_create_hard_link(destination: &Path, source: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot create hard link {} to {}: {}", PROGRAM, quote_path(destination), quote_path(source), describe_io_error(error)))
+}
+
+pub(crate) fn cannot_create_symbolic_link(destination: &Path, target: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot create symbolic link {} to {}: {}", PROGRAM, quote_path(destination), quote_path(target), describe_io_error(error)))
+}
+
+pub(crate) fn cannot_read_symbolic_link(source: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot read symbolic link {}: {}", PROGRAM, quote_path(source), describe_io_error(error)))
+}
+
+pub(crate) fn cannot_open(source: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot open {} for reading: {}", PROGRAM, quote_path(source), describe_io_error(error)))
+}
+
+pub(crate) fn cannot_open_for_writing(destination: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot open {} for writing: {}", PROGRAM, quote_path(destination), describe_io_error(error)))
+}
+
+pub(crate) fn cannot_create_regular_file(destination: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot create regular file {}: {}", PROGRAM, quote_path(destination), describe_io_error(error)))
+}
+
+pub(crate) fn cannot_create_directory(destination: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot create directory {}: {}", PROGRAM, quote_path(destination), describe_io_error(error)))
+}
+
+pub(crate) fn cannot_create_special_file(destination: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot create special file {}: {}", PROGRAM, quote_path(destination), describe_io_error(error)))
+}
+
+pub(crate) fn cannot_create_fifo(destination: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot create fifo {}: {}", PROGRAM, quote_path(destination), describe_io_error(error)))
+}
+
+pub(crate) fn cannot_remove(destination: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot remove {}: {}", PROGRAM, quote_path(destination), describe_io_error(error)))
+}
+
+///
+/// A directory's entries couldn't be read.
+///
+pub(crate) fn cannot_access(source: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot access {}: {}", PROGRAM, quote_path(source), describe_io_error(error)))
+}
+
+pub(crate) fn read_error(source: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: error reading {}: {}", PROGRAM, quote_path(source), describe_io_error(error)))
+}
+
+pub(crate) fn write_error(destination: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: error writing {}: {}", PROGRAM, quote_path(destination), describe_io_error(error)))
+}
+
+///
+/// `--reflink=always` was given, and the file system couldn't share the data.
+///
+pub(crate) fn clone_failed(destination: &Path, source: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: failed to clone {} from {}: {}", PROGRAM, quote_path(destination), quote_path(source), describe_io_error(error)))
+}
+
+///
+/// The times couldn't be given to a copy; that fails it only when they were `required`.
+///
+pub(crate) fn preserving_times(destination: &Path, error: &io::Error, required: bool) -> KnownError {
+    KnownError::new(code(required), format!("{}: preserving times for {}: {}", PROGRAM, quote_path(destination), describe_io_error(error)))
+}
+
+///
+/// The owner couldn't be given to a copy; that fails it only when it was `required`.
+///
+pub(crate) fn preserving_ownership(destination: &Path, error: &io::Error, required: bool) -> KnownError {
+    KnownError::new(code(required), format!("{}: failed to preserve ownership for {}: {}", PROGRAM, quote_path(destination), describe_io_error(error)))
+}
+
+pub(crate) fn preserving_permissions(destination: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: preserving permissions for {}: {}", PROGRAM, quote_path(destination), describe_io_error(error)))
+}
+
+pub(crate) fn listing_attributes(source: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: listing attributes of {}: {}", PROGRAM, quote_path(source), describe_io_error(error)))
+}
+
+pub(crate) fn setting_attribute(name: &[u8], destination: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: setting attribute {} for {}: {}", PROGRAM, quote_name(name), quote_path(destination), describe_io_error(error)))
+}
+
+///
+/// The question `-i` asks before replacing a file.
+///
+pub(crate) fn overwrite_prompt(destination: &Path) -> String {
+    format!("{}: overwrite {}? ", PROGRAM, quote_path(destination))
+}
+
+fn code(required: bool) -> i32 {
+    if required { ERROR_CODE } else { 0 }
+}
+
+fn try_help() -> String {
+    format!("Try '{} --help' for more information.", PROGRAM)
+}
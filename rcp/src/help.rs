@@ -0,0 +1,54 @@
+use std::io::{self, Write};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub(crate) fn version(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "rcp {}", VERSION)
+}
+
+pub(crate) fn help(out: &mut dyn Write) -> io::Result<()> {
+    version(out)?;
+    writeln!(out)?;
+    writeln!(out, "Usage: cp [OPTION]... SOURCE DEST")?;
+    writeln!(out, "  or:  cp [OPTION]... SOURCE... DIRECTORY")?;
+    writeln!(out, "Copy SOURCE to DEST, or multiple SOURCE(s) to DIRECTORY.")?;
+    writeln!(out)?;
+    writeln!(out, "Mandatory arguments to long options are mandatory for short options too.")?;
+    writeln!(out, "  -a, --archive                same as -dR --preserve=all")?;
+    writeln!(out, "  -d                           same as --no-dereference --preserve=links")?;
+    writeln!(out, "  -f, --force                  if an existing destination file cannot be")?;
+    writeln!(out, "                                 opened, remove it and try again (this option")?;
+    writeln!(out, "                                 is ignored when the -n option is also used)")?;
+    writeln!(out, "  -i, --interactive            prompt before overwrite (overrides a previous -n")?;
+    writeln!(out, "                                  option)")?;
+    writeln!(out, "  -H                           follow command-line symbolic links in SOURCE")?;
+    writeln!(out, "  -l, --link                   hard link files instead of copying")?;
+    writeln!(out, "  -L, --dereference            always follow symbolic links in SOURCE")?;
+    writeln!(out, "  -n, --no-clobber             do not overwrite an existing file (overrides")?;
+    writeln!(out, "                                 a previous -i option)")?;
+    writeln!(out, "  -P, --no-dereference         never follow symbolic links in SOURCE")?;
+    writeln!(out, "  -p                           same as --preserve=mode,ownership,timestamps")?;
+    writeln!(out, "      --preserve[=ATTR_LIST]   preserve the specified attributes (default:")?;
+    writeln!(out, "                                 mode,ownership,timestamps), if possible")?;
+    writeln!(out, "                                 additional attributes: links, xattr, all")?;
+    writeln!(out, "  -R, -r, --recursive          copy directories recursively")?;
+    writeln!(out, "      --reflink[=WHEN]         control clone/CoW copies. See below")?;
+    writeln!(out, "      --sparse=WHEN            control creation of sparse files. See below")?;
+    writeln!(out, "  -s, --symbolic-link          make symbolic links instead of copying")?;
+    writeln!(out, "  -u, --update                 copy only when the SOURCE file is newer")?;
+    writeln!(out, "                                 than the destination file or when the")?;
+    writeln!(out, "                                 destination file is missing")?;
+    writeln!(out, "      --help        display this help and exit")?;
+    writeln!(out, "      --version     output version information and exit")?;
+    writeln!(out)?;
+    writeln!(out, "By default, sparse SOURCE files are detected by a crude heuristic and the")?;
+    writeln!(out, "corresponding DEST file is made sparse as well.  That is the behavior")?;
+    writeln!(out, "selected by --sparse=auto.  Specify --sparse=always to create a sparse DEST")?;
+    writeln!(out, "file whenever the SOURCE file contains a long enough sequence of zero bytes.")?;
+    writeln!(out, "Use --sparse=never to inhibit creation of sparse files.")?;
+    writeln!(out)?;
+    writeln!(out, "When --reflink[=always] is specified, perform a lightweight copy, where the")?;
+    writeln!(out, "data blocks are copied only when modified.  If this is not possible the copy")?;
+    writeln!(out, "fails, or if --reflink=auto is specified, fall back to a standard copy.")?;
+    writeln!(out, "Use --reflink=never to ensure a standard copy is performed.")
+}
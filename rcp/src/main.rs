@@ -0,0 +1,18 @@
+mod attributes;
+mod config;
+mod contents;
+mod copy;
+mod cp;
+mod errors;
+mod help;
+
+use crate::cp::cp;
+
+///
+/// Main program entrypoint.
+///
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let status = cp(&args, &mut std::io::stdin().lock(), &mut std::io::stdout(), &mut std::io::stderr());
+    std::process::exit(status);
+}
@@ -0,0 +1,137 @@
+use common::input::flags::flag_data::FlagValidator;
+use common::input::flags::flags_short::ShortFlags;
+
+const SHORT_OPTIONS: &str = "-neE";
+const HELP: &str = "--help";
+const VERSION: &str = "--version";
+
+///
+/// Storage for the application configuration.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct Config {
+    pub(crate) print_help_and_exit: bool,
+    pub(crate) print_version_and_exit: bool,
+
+    pub(crate) newline: bool,
+    pub(crate) escapes: bool,
+
+    pub(crate) strings: Vec<String>,
+}
+
+impl Default for Config {
+    ///
+    /// Generate [Default] configuration.
+    ///
+    fn default() -> Self {
+        Config {
+            print_help_and_exit: false,
+            print_version_and_exit: false,
+
+            newline: true,
+            escapes: false,
+
+            strings: vec![],
+        }
+    }
+}
+
+impl Config {
+    ///
+    /// Build configuration from command line arguments; the first argument is the program name.
+    /// Options are only the leading arguments made up entirely of `-n`, `-e` and `-E`, like
+    /// `-neE`; the first argument that isn't, and everything after it, is printed as is.  Long
+    /// options are only recognized on their own.
+    ///
+    pub(crate) fn new(args: &[String]) -> Config {
+        let mut config = Self::default();
+        let args = args.get(1..).unwrap_or_default();
+
+        if let [only] = args {
+            match only.as_str() {
+                HELP => config.print_help_and_exit = true,
+                VERSION => config.print_version_and_exit = true,
+                _ => {}
+            }
+            if config.print_help_and_exit || config.print_version_and_exit {
+                return config;
+            }
+        }
+
+        let flags = ShortFlags::new_strict_from_combined_string(SHORT_OPTIONS);
+        let options = args.iter().take_while(|argument| flags.is_valid_flag(argument)).count();
+        for argument in args[..options].iter() {
+            let (matched, _) = flags.find_matching_flags(argument);
+            for flag in matched {
+                config.apply(&flag.name);
+            }
+        }
+        config.strings = args[options..].to_vec();
+
+        config
+    }
+
+    fn apply(&mut self, name: &str) {
+        match name {
+            "n" => self.newline = false,
+            "e" => self.escapes = true,
+            "E" => self.escapes = false,
+            _ => unreachable!("option {} has no handler", name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+
+    fn config(args: &[&str]) -> Config {
+        let args: Vec<String> = std::iter::once("echo").chain(args.iter().copied()).map(|s| s.to_string()).collect();
+        Config::new(&args)
+    }
+
+    #[test]
+    fn test_defaults() {
+        let config = config(&["a", "b"]);
+        assert!(config.newline && !config.escapes);
+        assert_eq!(vec!["a", "b"], config.strings);
+    }
+
+    #[test]
+    fn test_options() {
+        let both = config(&["-n", "-e", "a"]);
+        assert!(!both.newline && both.escapes);
+        assert_eq!(vec!["a"], both.strings);
+
+        /* the last of -e and -E wins, within a cluster or across them */
+        assert!(!config(&["-neE", "a"]).escapes);
+        assert!(config(&["-E", "-e"]).escapes);
+        assert!(config(&["-E", "-e"]).strings.is_empty());
+    }
+
+    #[test]
+    fn test_literals() {
+        /* options stop at the first argument that isn't entirely valid flags */
+        let stopped = config(&["-n", "-x", "-e", "a"]);
+        assert!(!stopped.newline && !stopped.escapes);
+        assert_eq!(vec!["-x", "-e", "a"], stopped.strings);
+
+        assert_eq!(vec!["-nx"], config(&["-nx"]).strings);
+        assert_eq!(vec!["-"], config(&["-"]).strings);
+        assert_eq!(vec!["--", "-n"], config(&["--", "-n"]).strings);
+        assert_eq!(vec!["--n"], config(&["--n"]).strings);
+        assert_eq!(vec![" -n"], config(&[" -n"]).strings);
+        assert_eq!(vec!["a", "-n"], config(&["a", "-n"]).strings);
+    }
+
+    #[test]
+    fn test_long_options() {
+        assert!(config(&["--help"]).print_help_and_exit);
+        assert!(config(&["--version"]).print_version_and_exit);
+
+        let not_alone = config(&["--help", "x"]);
+        assert!(!not_alone.print_help_and_exit);
+        assert_eq!(vec!["--help", "x"], not_alone.strings);
+        assert_eq!(vec!["--version"], config(&["-n", "--version"]).strings);
+    }
+}
@@ -0,0 +1,93 @@
+use std::io::Write;
+use common::input::known_error::report;
+use crate::config::Config;
+use crate::errors::write_error;
+use crate::escape::unescape;
+use crate::help::{help, version};
+
+const SUCCESS: i32 = 0;
+const FAILURE: i32 = 1;
+
+///
+/// Run `echo` with command line arguments; the first argument is the program name.  Returns the
+/// exit status.
+///
+pub(crate) fn echo(args: &[String], out: &mut dyn Write, err: &mut dyn Write) -> i32 {
+    let config = Config::new(args);
+
+    if config.print_help_and_exit {
+        return help(out).map_or(FAILURE, |_| SUCCESS);
+    }
+    if config.print_version_and_exit {
+        return version(out).map_or(FAILURE, |_| SUCCESS);
+    }
+
+    match out.write_all(&render(&config)).and_then(|_| out.flush()) {
+        Ok(()) => SUCCESS,
+        Err(error) => {
+            report(err, &[write_error(&error)]);
+            FAILURE
+        }
+    }
+}
+
+///
+/// The strings, separated by spaces, and the newline, unless `\c` cut the output short.
+///
+fn render(config: &Config) -> Vec<u8> {
+    let mut output = vec![];
+    for (index, string) in config.strings.iter().enumerate() {
+        if index > 0 {
+            output.push(b' ');
+        }
+        if !config.escapes {
+            output.extend_from_slice(string.as_bytes());
+        } else if unescape(string.as_bytes(), &mut output).is_break() {
+            return output;
+        }
+    }
+    if config.newline {
+        output.push(b'\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use crate::echo::echo;
+
+    fn run(args: &[&str]) -> (i32, Vec<u8>) {
+        let args: Vec<String> = std::iter::once("echo").chain(args.iter().copied()).map(|s| s.to_string()).collect();
+        let mut out = vec![];
+        let status = echo(&args, &mut out, &mut io::sink());
+        (status, out)
+    }
+
+    fn output(args: &[&str]) -> String {
+        String::from_utf8(run(args).1).unwrap()
+    }
+
+    #[test]
+    fn test_echo() {
+        assert_eq!("\n", output(&[]));
+        assert_eq!("a b  c\n", output(&["a", "b ", "c"]));
+        assert_eq!("a", output(&["-n", "a"]));
+        assert_eq!("a\\tb\n", output(&["a\\tb"]));
+        assert_eq!("a\tb\n", output(&["-e", "a\\tb"]));
+        assert_eq!("-x -e a\\tb", output(&["-n", "-x", "-e", "a\\tb"]));
+    }
+
+    #[test]
+    fn test_stop() {
+        assert_eq!("a b", output(&["-e", "a", "b\\cc", "d"]));
+        assert_eq!("a \\c\n", output(&["a", "\\c"]));
+    }
+
+    #[test]
+    fn test_help() {
+        assert!(output(&["--help"]).contains("Usage: echo [SHORT-OPTION]... [STRING]..."));
+        assert!(output(&["--version"]).starts_with("recho "));
+        assert_eq!("--help", output(&["-n", "--help"]));
+    }
+}
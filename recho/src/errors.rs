@@ -0,0 +1,13 @@
+use std::io;
+use common::input::known_error::KnownError;
+use common::input::source::describe_io_error;
+
+const ERROR_CODE: i32 = 1;
+const PROGRAM: &str = "echo";
+
+///
+/// Standard output couldn't be written.
+///
+pub(crate) fn write_error(error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: write error: {}", PROGRAM, describe_io_error(error)))
+}
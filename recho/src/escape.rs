@@ -0,0 +1,118 @@
+use std::ops::ControlFlow;
+
+const ALERT: u8 = 0x07;
+const BACKSPACE: u8 = 0x08;
+const ESCAPE: u8 = 0x1b;
+const FORM_FEED: u8 = 0x0c;
+const VERTICAL_TAB: u8 = 0x0b;
+
+///
+/// Append `text` to `output` with its backslash escapes interpreted.  Breaks at `\c`, after which
+/// nothing more, not even the trailing newline, is written.  A backslash that doesn't start a
+/// known escape is kept as is.
+///
+pub(crate) fn unescape(text: &[u8], output: &mut Vec<u8>) -> ControlFlow<()> {
+    let mut index = 0;
+    while index < text.len() {
+        let byte = text[index];
+        index += 1;
+        if byte != b'\\' || index == text.len() {
+            output.push(byte);
+            continue;
+        }
+
+        let escaped = text[index];
+        index += 1;
+        match escaped {
+            b'a' => output.push(ALERT),
+            b'b' => output.push(BACKSPACE),
+            b'c' => return ControlFlow::Break(()),
+            b'e' => output.push(ESCAPE),
+            b'f' => output.push(FORM_FEED),
+            b'n' => output.push(b'\n'),
+            b'r' => output.push(b'\r'),
+            b't' => output.push(b'\t'),
+            b'v' => output.push(VERTICAL_TAB),
+            b'\\' => output.push(b'\\'),
+            b'x' if text.get(index).is_some_and(u8::is_ascii_hexdigit) => {
+                let (value, used) = number(&text[index..], 16, 2);
+                output.push(value);
+                index += used;
+            }
+            /* `\0` takes up to three more digits; `\1` to `\7` start the number themselves */
+            b'0' => {
+                let (value, used) = number(&text[index..], 8, 3);
+                output.push(value);
+                index += used;
+            }
+            b'1'..=b'7' => {
+                let (value, used) = number(&text[index - 1..], 8, 3);
+                output.push(value);
+                index += used - 1;
+            }
+            _ => output.extend_from_slice(&[b'\\', escaped]),
+        }
+    }
+    ControlFlow::Continue(())
+}
+
+///
+/// Read up to `limit` digits in `radix` from the start of `text`, returning the value, truncated
+/// to a byte, and how many digits were used.
+///
+fn number(text: &[u8], radix: u32, limit: usize) -> (u8, usize) {
+    let digits: Vec<u32> = text.iter()
+        .take(limit)
+        .map_while(|&byte| (byte as char).to_digit(radix))
+        .collect();
+    let value = digits.iter().fold(0u32, |value, digit| value * radix + digit);
+    (value as u8, digits.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::ControlFlow;
+    use crate::escape::unescape;
+
+    fn expand(text: &str) -> (Vec<u8>, ControlFlow<()>) {
+        let mut output = vec![];
+        let flow = unescape(text.as_bytes(), &mut output);
+        (output, flow)
+    }
+
+    fn expanded(text: &str) -> Vec<u8> {
+        expand(text).0
+    }
+
+    #[test]
+    fn test_characters() {
+        assert_eq!(b"\x07\x08\x1b\x0c\n\r\t\x0b\\".to_vec(), expanded("\\a\\b\\e\\f\\n\\r\\t\\v\\\\"));
+        assert_eq!(b"plain".to_vec(), expanded("plain"));
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(b"A".to_vec(), expanded("\\x41"));
+        assert_eq!(b"\x04g".to_vec(), expanded("\\x4g"));
+        assert_eq!(b"A1".to_vec(), expanded("\\x411"));
+        assert_eq!(b"A".to_vec(), expanded("\\0101"));
+        assert_eq!(b"A1".to_vec(), expanded("\\01011"));
+        assert_eq!(b"\0x".to_vec(), expanded("\\0x"));
+        assert_eq!(b"A".to_vec(), expanded("\\101"));
+        assert_eq!(b"\x018".to_vec(), expanded("\\18"));
+        assert_eq!(vec![0xff], expanded("\\0777"));
+    }
+
+    #[test]
+    fn test_unknown() {
+        assert_eq!(b"\\xg".to_vec(), expanded("\\xg"));
+        assert_eq!(b"\\q\\8".to_vec(), expanded("\\q\\8"));
+        assert_eq!(b"end\\".to_vec(), expanded("end\\"));
+    }
+
+    #[test]
+    fn test_stop() {
+        assert_eq!((b"a".to_vec(), ControlFlow::Break(())), expand("a\\cb"));
+        assert_eq!((b"ab".to_vec(), ControlFlow::Continue(())), expand("ab"));
+    }
+}
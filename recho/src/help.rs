@@ -0,0 +1,36 @@
+use std::io::{self, Write};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub(crate) fn version(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "recho {}", VERSION)
+}
+
+pub(crate) fn help(out: &mut dyn Write) -> io::Result<()> {
+    version(out)?;
+    writeln!(out)?;
+    writeln!(out, "Usage: echo [SHORT-OPTION]... [STRING]...")?;
+    writeln!(out, "  or:  echo LONG-OPTION")?;
+    writeln!(out, "Echo the STRING(s) to standard output.")?;
+    writeln!(out)?;
+    writeln!(out, "  -n             do not output the trailing newline")?;
+    writeln!(out, "  -e             enable interpretation of backslash escapes")?;
+    writeln!(out, "  -E             disable interpretation of backslash escapes (default)")?;
+    writeln!(out, "      --help     display this help and exit")?;
+    writeln!(out, "      --version  output version information and exit")?;
+    writeln!(out)?;
+    writeln!(out, "If -e is in effect, the following sequences are recognized:")?;
+    writeln!(out)?;
+    writeln!(out, "  \\\\      backslash")?;
+    writeln!(out, "  \\a      alert (BEL)")?;
+    writeln!(out, "  \\b      backspace")?;
+    writeln!(out, "  \\c      produce no further output")?;
+    writeln!(out, "  \\e      escape")?;
+    writeln!(out, "  \\f      form feed")?;
+    writeln!(out, "  \\n      new line")?;
+    writeln!(out, "  \\r      carriage return")?;
+    writeln!(out, "  \\t      horizontal tab")?;
+    writeln!(out, "  \\v      vertical tab")?;
+    writeln!(out, "  \\0NNN   byte with octal value NNN (1 to 3 digits)")?;
+    writeln!(out, "  \\xHH    byte with hexadecimal value HH (1 to 2 digits)")
+}
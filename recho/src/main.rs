@@ -0,0 +1,16 @@
+mod config;
+mod echo;
+mod errors;
+mod escape;
+mod help;
+
+use crate::echo::echo;
+
+///
+/// Main program entrypoint.
+///
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let status = echo(&args, &mut std::io::stdout().lock(), &mut std::io::stderr());
+    std::process::exit(status);
+}
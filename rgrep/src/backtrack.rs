@@ -0,0 +1,234 @@
+use std::collections::HashSet;
+use crate::nfa::Program;
+use crate::syntax::Node;
+use crate::text::{decode, holds, same};
+
+type Captures = Vec<Option<(usize, usize)>>;
+
+///
+/// A pattern with back-references, matched by trying every way through it.  That can take time
+/// exponential in the length of the text, which is why it's only used when a pattern needs it;
+/// repetitions remember where they've failed, so `\(a*\)*` doesn't try every way of splitting a
+/// run of `a`s.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct Backtracker {
+    node: Node,
+    groups: usize,
+    ignore_case: bool,
+    /* the pattern loosened, to rule out places no match can start, as long as it isn't too big */
+    filter: Option<Program>,
+}
+
+impl Backtracker {
+    pub(crate) fn new(node: Node, ignore_case: bool) -> Backtracker {
+        let groups = node.group_count();
+        let filter = Program::compile(&node.loosened(), ignore_case);
+        Backtracker { node, groups, ignore_case, filter }
+    }
+
+    ///
+    /// Find the leftmost match that starts at or after `from`, and of those the longest.
+    ///
+    pub(crate) fn find(&self, text: &[u8], from: usize) -> Option<(usize, usize)> {
+        let mut start = from;
+        loop {
+            if let Some(filter) = &self.filter {
+                start = filter.find(text, start)?.0;
+            }
+            if let Some(&end) = self.ends(text, start).last() {
+                return Some((start, end));
+            }
+            if start >= text.len() {
+                return None;
+            }
+            start += decode(text, start).1;
+        }
+    }
+
+    ///
+    /// Every position where a match starting at `start` can end, in increasing order; the end of
+    /// the text, when a match reaches it, is the last.
+    ///
+    pub(crate) fn ends(&self, text: &[u8], start: usize) -> Vec<usize> {
+        if self.filter.as_ref().is_some_and(|filter| filter.ends(text, start).is_empty()) {
+            return vec![];
+        }
+        let search = Search { text, ignore_case: self.ignore_case };
+        let mut ends = vec![];
+        let mut captures = vec![None; self.groups + 1];
+        /* nothing ends later than the end of the text, so once a match does, the search can stop */
+        search.walk(&self.node, start, &mut captures, &mut |end, _| {
+            ends.push(end);
+            end == text.len()
+        });
+        ends.sort_unstable();
+        ends.dedup();
+        ends
+    }
+}
+
+///
+/// One repetition being matched, and the states of it known to lead nowhere: iterations so far,
+/// position and captures.
+///
+struct Repetition<'n> {
+    node: &'n Node,
+    min: u32,
+    max: Option<u32>,
+    failures: HashSet<(u32, usize, Captures)>,
+}
+
+///
+/// One attempt to match, at one place in one line.
+///
+struct Search<'t> {
+    text: &'t [u8],
+    ignore_case: bool,
+}
+
+impl Search<'_> {
+    ///
+    /// Match a node at a position, handing each position it can end at to the continuation.  A
+    /// continuation returns true to stop the search.
+    ///
+    fn walk(&self, node: &Node, position: usize, captures: &mut Captures, next: &mut dyn FnMut(usize, &mut Captures) -> bool) -> bool {
+        match node {
+            Node::Empty => next(position, captures),
+            Node::Char(expected) => match self.advance(position, |c| same(*expected, c, self.ignore_case)) {
+                Some(end) => next(end, captures),
+                None => false,
+            },
+            Node::Any => match self.advance(position, |_| true) {
+                Some(end) => next(end, captures),
+                None => false,
+            },
+            Node::Bracket(bracket) => match self.advance(position, |c| bracket.matches(c, self.ignore_case)) {
+                Some(end) => next(end, captures),
+                None => false,
+            },
+            Node::Assert(assertion) => holds(*assertion, self.text, position) && next(position, captures),
+            Node::Group(inner, index) => self.walk(inner, position, captures, &mut |end, captures| {
+                let saved = captures[*index];
+                captures[*index] = Some((position, end));
+                let stop = next(end, captures);
+                captures[*index] = saved;
+                stop
+            }),
+            Node::Backreference(index) => match captures[*index] {
+                Some((start, end)) => {
+                    let length = end - start;
+                    let matches = self.text.get(position..position + length).is_some_and(|candidate| self.same_text(&self.text[start..end], candidate));
+                    matches && next(position + length, captures)
+                }
+                None => false,
+            },
+            Node::Concat(nodes) => self.sequence(nodes, position, captures, next),
+            Node::Alternate(nodes) => nodes.iter().any(|node| self.walk(node, position, captures, next)),
+            Node::Repeat { node, min, max } => {
+                let mut repetition = Repetition { node, min: *min, max: *max, failures: HashSet::new() };
+                self.repeat(&mut repetition, 0, position, captures, next)
+            }
+        }
+    }
+
+    fn sequence(&self, nodes: &[Node], position: usize, captures: &mut Captures, next: &mut dyn FnMut(usize, &mut Captures) -> bool) -> bool {
+        match nodes.split_first() {
+            None => next(position, captures),
+            Some((first, rest)) => self.walk(first, position, captures, &mut |end, captures| self.sequence(rest, end, captures, next)),
+        }
+    }
+
+    ///
+    /// Match the rest of a repetition, `count` iterations in.  Every iteration goes on to the same
+    /// continuation, so a state that failed once fails again, and is skipped.
+    ///
+    fn repeat(&self, repetition: &mut Repetition, count: u32, position: usize, captures: &mut Captures, next: &mut dyn FnMut(usize, &mut Captures) -> bool) -> bool {
+        let (node, min, max) = (repetition.node, repetition.min, repetition.max);
+        /* without an upper bound, all counts past the minimum behave alike */
+        let state = (if max.is_some() { count } else { count.min(min) }, position, captures.clone());
+        if repetition.failures.contains(&state) {
+            return false;
+        }
+
+        let stop = (count >= min && next(position, captures))
+            || (max.is_none_or(|max| count < max) && self.walk(node, position, captures, &mut |end, captures| {
+                /* once the minimum is met, an iteration that matches nothing ends the repetition, rather than going round forever */
+                if end == position && count >= min {
+                    return next(end, captures);
+                }
+                self.repeat(repetition, count + 1, end, captures, next)
+            }));
+        if !stop {
+            repetition.failures.insert(state);
+        }
+        stop
+    }
+
+    fn advance(&self, position: usize, accept: impl Fn(char) -> bool) -> Option<usize> {
+        if position >= self.text.len() {
+            return None;
+        }
+        match decode(self.text, position) {
+            (Some(c), width) if accept(c) => Some(position + width),
+            _ => None,
+        }
+    }
+
+    fn same_text(&self, expected: &[u8], candidate: &[u8]) -> bool {
+        if !self.ignore_case {
+            return expected == candidate;
+        }
+        match (std::str::from_utf8(expected), std::str::from_utf8(candidate)) {
+            (Ok(expected), Ok(candidate)) => expected.chars().count() == candidate.chars().count() && expected.chars().zip(candidate.chars()).all(|(a, b)| same(a, b, true)),
+            _ => expected == candidate,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backtrack::Backtracker;
+    use crate::syntax::{parse, Syntax};
+
+    fn found<'t>(pattern: &str, text: &'t str) -> Option<&'t str> {
+        let backtracker = Backtracker::new(parse(pattern, Syntax::Basic).unwrap(), false);
+        backtracker.find(text.as_bytes(), 0).map(|(start, end)| &text[start..end])
+    }
+
+    #[test]
+    fn test_backreferences() {
+        assert_eq!(Some("abab"), found("\\(ab\\)\\1", "xabab"));
+        assert_eq!(Some("aa"), found("\\(a\\)\\1", "abaa"));
+        assert_eq!(None, found("\\(a\\)\\1", "aba"));
+        assert_eq!(Some("abcabc"), found("\\(a.c\\)\\1", "abcabc"));
+        assert_eq!(Some("xyyx"), found("\\(.\\)\\(.\\)\\2\\1", "axyyx"));
+        assert_eq!(Some("aaaa"), found("\\(a*\\)\\1", "aaaaa"));
+    }
+
+    #[test]
+    fn test_longest() {
+        assert_eq!(Some("abab"), found("\\(a\\|ab\\)\\(c\\|bab\\)", "abab"));
+        assert_eq!(Some(""), found("\\(a*\\)*", "b"));
+        assert_eq!(Some("aaa"), found("a\\{1,3\\}", "aaaa"));
+    }
+
+    #[test]
+    fn test_nested_repetition() {
+        /* every way of splitting the run between iterations would take far too long to try */
+        let line = "a".repeat(300);
+        assert_eq!(None, found("\\(a*\\)*\\1b", &line));
+        assert_eq!(Some(format!("{}b", line).as_str()), found("\\(a*\\)*\\1b", &format!("{}b", line)));
+        /* here the loosened pattern matches from the start, so the backtracker itself has to give up quickly there */
+        assert_eq!(Some("b"), found("\\(a*\\)*\\1b", &format!("{}cb", "a".repeat(30))));
+        assert_eq!(Some("aaaa"), found("\\(a*\\)*\\1", "aaaa"));
+        /* an iteration that matches nothing still sets its group, once */
+        assert_eq!(Some("a"), found("\\(b*\\)*a\\1", "aaaa"));
+    }
+
+    #[test]
+    fn test_ignore_case() {
+        let backtracker = Backtracker::new(parse("\\(a\\)\\1", Syntax::Basic).unwrap(), true);
+        assert_eq!(Some((0, 2)), backtracker.find(b"Aa", 0));
+    }
+}
@@ -0,0 +1,450 @@
+use std::io::Read;
+use common::input::flags::flags_options::{parse_options, OptionSpec, ParsedArgument};
+use common::input::known_error::KnownError;
+use crate::errors::{conflicting_matchers, file_error, invalid_color, invalid_context, invalid_max_count, missing_pattern, option_error, unknown_binary_files};
+use crate::matcher::Whole;
+use crate::syntax::Syntax;
+
+const STDIN_OPERAND: &str = "-";
+const DUMB_TERMINAL: &str = "dumb";
+
+const OPTIONS: [OptionSpec; 38] = [
+    OptionSpec::flag("extended", Some('E'), Some("extended-regexp")),
+    OptionSpec::flag("fixed", Some('F'), Some("fixed-strings")),
+    OptionSpec::flag("basic", Some('G'), Some("basic-regexp")),
+    OptionSpec::required("regexp", Some('e'), Some("regexp")),
+    OptionSpec::required("file", Some('f'), Some("file")),
+    OptionSpec::flag("ignore-case", Some('i'), Some("ignore-case")),
+    OptionSpec::flag("ignore-case", Some('y'), None),
+    OptionSpec::flag("no-ignore-case", None, Some("no-ignore-case")),
+    OptionSpec::flag("word", Some('w'), Some("word-regexp")),
+    OptionSpec::flag("line", Some('x'), Some("line-regexp")),
+    OptionSpec::flag("invert", Some('v'), Some("invert-match")),
+    OptionSpec::flag("count", Some('c'), Some("count")),
+    OptionSpec::optional("color", None, Some("color")),
+    OptionSpec::optional("color", None, Some("colour")),
+    OptionSpec::flag("files-with-matches", Some('l'), Some("files-with-matches")),
+    OptionSpec::flag("files-without-match", Some('L'), Some("files-without-match")),
+    OptionSpec::required("max-count", Some('m'), Some("max-count")),
+    OptionSpec::flag("only-matching", Some('o'), Some("only-matching")),
+    OptionSpec::flag("quiet", Some('q'), Some("quiet")),
+    OptionSpec::flag("quiet", None, Some("silent")),
+    OptionSpec::flag("no-messages", Some('s'), Some("no-messages")),
+    OptionSpec::flag("byte-offset", Some('b'), Some("byte-offset")),
+    OptionSpec::flag("with-filename", Some('H'), Some("with-filename")),
+    OptionSpec::flag("no-filename", Some('h'), Some("no-filename")),
+    OptionSpec::flag("line-number", Some('n'), Some("line-number")),
+    OptionSpec::required("after", Some('A'), Some("after-context")),
+    OptionSpec::required("before", Some('B'), Some("before-context")),
+    OptionSpec::required("context", Some('C'), Some("context")),
+    OptionSpec::flag("text", Some('a'), Some("text")),
+    OptionSpec::flag("binary-without-match", Some('I'), None),
+    OptionSpec::required("binary-files", None, Some("binary-files")),
+    OptionSpec::flag("recursive", Some('r'), Some("recursive")),
+    OptionSpec::flag("dereference-recursive", Some('R'), Some("dereference-recursive")),
+    OptionSpec::required("include", None, Some("include")),
+    OptionSpec::required("exclude", None, Some("exclude")),
+    OptionSpec::required("exclude-dir", None, Some("exclude-dir")),
+    OptionSpec::flag("help", None, Some("help")),
+    OptionSpec::flag("version", Some('V'), Some("version")),
+];
+
+///
+/// What's written for each file searched.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Output {
+    /// Selected lines, with any context around them.
+    Lines,
+    /// Just the matched parts of selected lines; `-o`.
+    OnlyMatching,
+    /// How many lines were selected; `-c`.
+    Count,
+    /// The file's name, if a line was selected; `-l`.
+    FilesWithMatches,
+    /// The file's name, if no line was selected; `-L`.
+    FilesWithoutMatch,
+    /// Nothing; `-q`.
+    Quiet,
+}
+
+///
+/// What to do with a file that looks binary.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BinaryFiles {
+    /// Search it, but only say whether it matches.
+    Binary,
+    /// Search it like any other file; `-a`.
+    Text,
+    /// Skip it as though it doesn't match; `-I`.
+    WithoutMatch,
+}
+
+///
+/// Whether directories are searched, and which symbolic links are followed when they are.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Recursion {
+    None,
+    /// Links are followed only when named on the command line; `-r`.
+    Physical,
+    /// All links are followed; `-R`.
+    Logical,
+}
+
+///
+/// Storage for the application configuration.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct Config {
+    pub(crate) print_help_and_exit: bool,
+    pub(crate) print_version_and_exit: bool,
+
+    pub(crate) syntax: Syntax,
+    pub(crate) patterns: Vec<String>,
+    pub(crate) ignore_case: bool,
+    pub(crate) whole: Whole,
+    pub(crate) invert: bool,
+
+    pub(crate) output: Output,
+    pub(crate) max_count: Option<usize>,
+    pub(crate) line_number: bool,
+    pub(crate) byte_offset: bool,
+    pub(crate) with_filename: Option<bool>,
+    /* context is only grouped when asked for, even when no lines of it are */
+    pub(crate) before: Option<usize>,
+    pub(crate) after: Option<usize>,
+    pub(crate) color: bool,
+    pub(crate) no_messages: bool,
+
+    pub(crate) binary_files: BinaryFiles,
+    pub(crate) recursion: Recursion,
+    pub(crate) include: Vec<String>,
+    pub(crate) exclude: Vec<String>,
+    pub(crate) exclude_dir: Vec<String>,
+
+    pub(crate) files: Vec<String>,
+    pub(crate) errors: Vec<KnownError>,
+}
+
+impl Default for Config {
+    ///
+    /// Generate [Default] configuration.
+    ///
+    fn default() -> Self {
+        Config {
+            print_help_and_exit: false,
+            print_version_and_exit: false,
+
+            syntax: Syntax::Basic,
+            patterns: vec![],
+            ignore_case: false,
+            whole: Whole::Anything,
+            invert: false,
+
+            output: Output::Lines,
+            max_count: None,
+            line_number: false,
+            byte_offset: false,
+            with_filename: None,
+            before: None,
+            after: None,
+            color: false,
+            no_messages: false,
+
+            binary_files: BinaryFiles::Binary,
+            recursion: Recursion::None,
+            include: vec![],
+            exclude: vec![],
+            exclude_dir: vec![],
+
+            files: vec![],
+            errors: vec![],
+        }
+    }
+}
+
+///
+/// Options that interact, gathered before their effect is decided.
+///
+#[derive(Default)]
+struct Selection {
+    matcher: Option<Syntax>,
+    patterns: Option<Vec<String>>,
+    quiet: bool,
+    list: Option<Output>,
+    count: bool,
+    only_matching: bool,
+    after: Option<usize>,
+    before: Option<usize>,
+    context: Option<usize>,
+}
+
+impl Config {
+    ///
+    /// Initialize the application configuration based on provided application arguments; the first
+    /// argument is the program name.
+    ///
+    pub(crate) fn new(args: &[String]) -> Config {
+        let mut config = Self::default();
+
+        let parsed = match parse_options(args.get(1..).unwrap_or_default(), &OPTIONS) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                config.errors.push(option_error(&error));
+                return config;
+            }
+        };
+
+        let mut selection = Selection::default();
+        let mut operands = vec![];
+        for argument in parsed {
+            match argument {
+                ParsedArgument::Operand(operand) => operands.push(operand),
+                ParsedArgument::Option { id, value } => {
+                    if let Err(error) = config.apply(&mut selection, id, value.unwrap_or_default()) {
+                        config.errors.push(error);
+                    }
+                }
+            }
+        }
+        if config.print_help_and_exit || config.print_version_and_exit || !config.errors.is_empty() {
+            return config;
+        }
+
+        /* without -e or -f, the first operand is the pattern */
+        let mut operands = operands.into_iter();
+        config.patterns = match selection.patterns.take() {
+            Some(patterns) => patterns,
+            None => match operands.next() {
+                Some(pattern) => split_patterns(&pattern),
+                None => {
+                    config.errors.push(missing_pattern());
+                    return config;
+                }
+            },
+        };
+        config.files = operands.collect();
+
+        config.syntax = selection.matcher.unwrap_or(Syntax::Basic);
+        config.output = select_output(&selection);
+        config.after = selection.after.or(selection.context);
+        config.before = selection.before.or(selection.context);
+        config
+    }
+
+    fn apply(&mut self, selection: &mut Selection, id: &str, value: String) -> Result<(), KnownError> {
+        match id {
+            "extended" => select_matcher(selection, Syntax::Extended)?,
+            "fixed" => select_matcher(selection, Syntax::Fixed)?,
+            "basic" => select_matcher(selection, Syntax::Basic)?,
+            "regexp" => selection.patterns.get_or_insert_default().extend(split_patterns(&value)),
+            "file" => selection.patterns.get_or_insert_default().extend(read_patterns(&value)?),
+            "ignore-case" => self.ignore_case = true,
+            "no-ignore-case" => self.ignore_case = false,
+            "word" => self.whole = Whole::Word,
+            "line" => self.whole = Whole::Line,
+            "invert" => self.invert = true,
+            "count" => selection.count = true,
+            "color" => self.color = parse_color(&value)?,
+            "files-with-matches" => selection.list = Some(Output::FilesWithMatches),
+            "files-without-match" => selection.list = Some(Output::FilesWithoutMatch),
+            "max-count" => self.max_count = Some(parse_number(&value).ok_or_else(invalid_max_count)?),
+            "only-matching" => selection.only_matching = true,
+            "quiet" => selection.quiet = true,
+            "no-messages" => self.no_messages = true,
+            "byte-offset" => self.byte_offset = true,
+            "with-filename" => self.with_filename = Some(true),
+            "no-filename" => self.with_filename = Some(false),
+            "line-number" => self.line_number = true,
+            "after" => selection.after = Some(parse_context(&value)?),
+            "before" => selection.before = Some(parse_context(&value)?),
+            "context" => selection.context = Some(parse_context(&value)?),
+            "text" => self.binary_files = BinaryFiles::Text,
+            "binary-without-match" => self.binary_files = BinaryFiles::WithoutMatch,
+            "binary-files" => self.binary_files = parse_binary_files(&value)?,
+            "recursive" => self.recursion = Recursion::Physical,
+            "dereference-recursive" => self.recursion = Recursion::Logical,
+            "include" => self.include.push(value),
+            "exclude" => self.exclude.push(value),
+            "exclude-dir" => self.exclude_dir.push(value),
+            "help" => self.print_help_and_exit = true,
+            "version" => self.print_version_and_exit = true,
+            _ => unreachable!("option {} has no handler", id),
+        }
+        Ok(())
+    }
+}
+
+fn select_matcher(selection: &mut Selection, syntax: Syntax) -> Result<(), KnownError> {
+    match selection.matcher {
+        Some(chosen) if chosen != syntax => Err(conflicting_matchers()),
+        _ => {
+            selection.matcher = Some(syntax);
+            Ok(())
+        }
+    }
+}
+
+///
+/// `-q` says the least, then `-l` and `-L`, then `-c`, then `-o`.
+///
+fn select_output(selection: &Selection) -> Output {
+    if selection.quiet {
+        Output::Quiet
+    } else if let Some(list) = selection.list {
+        list
+    } else if selection.count {
+        Output::Count
+    } else if selection.only_matching {
+        Output::OnlyMatching
+    } else {
+        Output::Lines
+    }
+}
+
+///
+/// Each line of a pattern argument is a pattern of its own.
+///
+fn split_patterns(value: &str) -> Vec<String> {
+    value.split('\n').map(|pattern| pattern.to_string()).collect()
+}
+
+///
+/// Read patterns from a file, one per line; an empty file has none at all.
+///
+fn read_patterns(path: &str) -> Result<Vec<String>, KnownError> {
+    let mut bytes = vec![];
+    let result = if path == STDIN_OPERAND {
+        std::io::stdin().read_to_end(&mut bytes)
+    } else {
+        std::fs::File::open(path).and_then(|mut file| file.read_to_end(&mut bytes))
+    };
+    result.map_err(|error| file_error(path, &error))?;
+
+    if bytes.is_empty() {
+        return Ok(vec![]);
+    }
+    let text = String::from_utf8_lossy(&bytes);
+    Ok(split_patterns(text.strip_suffix('\n').unwrap_or(&text)))
+}
+
+fn parse_color(value: &str) -> Result<bool, KnownError> {
+    match value {
+        "always" | "yes" | "force" => Ok(true),
+        "never" | "no" | "none" => Ok(false),
+        "" | "auto" | "tty" | "if-tty" => Ok(color_terminal()),
+        _ => Err(invalid_color(value)),
+    }
+}
+
+///
+/// Whether standard output is a terminal that understands color.
+///
+fn color_terminal() -> bool {
+    /* SAFETY: isatty only inspects the descriptor */
+    let terminal = unsafe { libc::isatty(libc::STDOUT_FILENO) } == 1;
+    terminal && std::env::var("TERM").is_ok_and(|term| term != DUMB_TERMINAL)
+}
+
+fn parse_binary_files(value: &str) -> Result<BinaryFiles, KnownError> {
+    match value {
+        "binary" => Ok(BinaryFiles::Binary),
+        "text" => Ok(BinaryFiles::Text),
+        "without-match" => Ok(BinaryFiles::WithoutMatch),
+        _ => Err(unknown_binary_files()),
+    }
+}
+
+fn parse_context(value: &str) -> Result<usize, KnownError> {
+    parse_number(value).ok_or_else(|| invalid_context(value))
+}
+
+///
+/// Read a count; one too large to represent is as good as unlimited.
+///
+fn parse_number(value: &str) -> Option<usize> {
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some(value.parse().unwrap_or(usize::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use common::test_support::TempDir;
+    use crate::config::{BinaryFiles, Config, Output, Recursion};
+    use crate::matcher::Whole;
+    use crate::syntax::Syntax;
+
+    fn config(line: &str) -> Config {
+        let args: Vec<String> = line.split_whitespace().map(|s| s.to_string()).collect();
+        Config::new(&args)
+    }
+
+    fn messages(config: &Config) -> Vec<String> {
+        config.errors.iter().map(|e| e.message.clone().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_defaults() {
+        let config = config("grep foo a b");
+
+        assert!(config.errors.is_empty());
+        assert_eq!(Syntax::Basic, config.syntax);
+        assert_eq!(vec!["foo"], config.patterns);
+        assert_eq!(vec!["a", "b"], config.files);
+        assert_eq!(Output::Lines, config.output);
+        assert_eq!(Whole::Anything, config.whole);
+        assert_eq!(BinaryFiles::Binary, config.binary_files);
+        assert_eq!(Recursion::None, config.recursion);
+        assert_eq!((None, None, None, None), (config.before, config.after, config.max_count, config.with_filename));
+        assert!(!config.color);
+    }
+
+    #[test]
+    fn test_patterns() {
+        let config = config("grep -e a -e b -E file");
+        assert_eq!(vec!["a", "b"], config.patterns);
+        assert_eq!(vec!["file"], config.files);
+        assert_eq!(Syntax::Extended, config.syntax);
+
+        let args: Vec<String> = ["grep", "a\nb"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(vec!["a", "b"], Config::new(&args).patterns);
+
+        let dir = TempDir::new("patterns");
+        let listed = dir.file("listed", b"x\n\ny\n");
+        let empty = dir.file("empty", b"");
+        assert_eq!(vec!["x", "", "y"], self::config(&format!("grep -f {}", listed)).patterns);
+        assert!(self::config(&format!("grep -f {} a", empty)).patterns.is_empty());
+    }
+
+    #[test]
+    fn test_output_and_context() {
+        assert_eq!(Output::Quiet, config("grep -lcq a").output);
+        assert_eq!(Output::FilesWithoutMatch, config("grep -c -l -L a").output);
+        assert_eq!(Output::Count, config("grep -oc a").output);
+        assert_eq!(Output::OnlyMatching, config("grep -o a").output);
+
+        let context = config("grep -A 1 -C3 a");
+        assert_eq!((Some(3), Some(1)), (context.before, context.after));
+        let max = config("grep -m2 -n -b -H -ri --color=always a");
+        assert_eq!(Some(2), max.max_count);
+        assert!(max.line_number && max.byte_offset && max.ignore_case && max.color);
+        assert_eq!((Some(true), Recursion::Physical), (max.with_filename, max.recursion));
+        assert_eq!(BinaryFiles::WithoutMatch, config("grep --binary-files=without-match a").binary_files);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(vec!["Usage: grep [OPTION]... PATTERNS [FILE]...\nTry 'grep --help' for more information."], messages(&config("grep")));
+        assert_eq!(vec!["grep: conflicting matchers specified"], messages(&config("grep -E -F a")));
+        assert_eq!(vec!["grep: x: invalid context length argument"], messages(&config("grep -A x a")));
+        assert_eq!(vec!["grep: invalid max count"], messages(&config("grep -m -1 a")));
+        assert_eq!(vec!["grep: unknown binary-files type"], messages(&config("grep --binary-files=maybe a")));
+        assert_eq!(vec!["grep: invalid argument 'sometimes' for '--color'"], messages(&config("grep --color=sometimes a")));
+        assert_eq!(vec!["grep: nope: No such file or directory"], messages(&config("grep -f nope")));
+        assert_eq!(vec!["grep: invalid option -- 'P'"], messages(&config("grep -P a")));
+    }
+}
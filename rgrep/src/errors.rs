@@ -0,0 +1,100 @@
+use std::io;
+use common::input::flags::flags_options::OptionError;
+use common::input::known_error::KnownError;
+use common::input::source::describe_io_error;
+
+const ERROR_CODE: i32 = 2;
+const PROGRAM: &str = "grep";
+
+///
+/// The command line couldn't be split into options and operands.
+///
+pub(crate) fn option_error(error: &OptionError) -> KnownError {
+    KnownError::new(ERROR_CODE, error.message(PROGRAM))
+}
+
+///
+/// No pattern was given, neither as an operand nor with `-e` or `-f`.
+///
+pub(crate) fn missing_pattern() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("Usage: {} [OPTION]... PATTERNS [FILE]...\nTry '{} --help' for more information.", PROGRAM, PROGRAM))
+}
+
+///
+/// More than one of `-G`, `-E` and `-F` was given.
+///
+pub(crate) fn conflicting_matchers() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: conflicting matchers specified", PROGRAM))
+}
+
+///
+/// `-A`, `-B` or `-C` was given something other than a non-negative number.
+///
+pub(crate) fn invalid_context(argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: {}: invalid context length argument", PROGRAM, argument))
+}
+
+///
+/// `-m` was given something other than a non-negative number.
+///
+pub(crate) fn invalid_max_count() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: invalid max count", PROGRAM))
+}
+
+///
+/// `--binary-files` was given a type it doesn't know.
+///
+pub(crate) fn unknown_binary_files() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: unknown binary-files type", PROGRAM))
+}
+
+///
+/// `--color` was given a value it doesn't know.
+///
+pub(crate) fn invalid_color(argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: invalid argument '{}' for '--color'", PROGRAM, argument))
+}
+
+///
+/// A pattern isn't a valid regular expression; `reason` is what's wrong with it.
+///
+pub(crate) fn invalid_pattern(reason: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: {}", PROGRAM, reason))
+}
+
+///
+/// A file couldn't be opened, read or listed.
+///
+pub(crate) fn file_error(name: &str, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: {}: {}", PROGRAM, name, describe_io_error(error)))
+}
+
+///
+/// A directory was named without `-r`.
+///
+pub(crate) fn is_a_directory(name: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: {}: Is a directory", PROGRAM, name))
+}
+
+///
+/// Following symbolic links with `-R` led back into a directory that's already being searched.
+///
+pub(crate) fn directory_loop(name: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: {}: warning: recursive directory loop", PROGRAM, name))
+}
+
+///
+/// A binary file has a selected line, which is said in place of writing the line; it's reported
+/// like an error, but doesn't change the exit status.
+///
+pub(crate) fn binary_file_matches(name: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: {}: binary file matches", PROGRAM, name))
+}
+
+///
+/// Standard output couldn't be written.
+///
+pub(crate) fn write_error(error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: write error: {}", PROGRAM, describe_io_error(error)))
+}
+
@@ -0,0 +1,176 @@
+use std::io::{BufWriter, Write};
+use std::ops::ControlFlow;
+use common::input::known_error::report;
+use crate::config::{Config, Output};
+use crate::errors::{binary_file_matches, file_error, write_error};
+use crate::help::{help, version};
+use crate::matcher::Matcher;
+use crate::search::{Failure, Searcher};
+use crate::walk::{walk, Input};
+
+const SELECTED: i32 = 0;
+const NOT_SELECTED: i32 = 1;
+const TROUBLE: i32 = 2;
+
+///
+/// Run `grep` with command line arguments; the first argument is the program name.  Returns the
+/// exit status: 0 when a line was selected, 1 when none was, and 2 when there was trouble.
+///
+pub(crate) fn grep(args: &[String], out: &mut dyn Write, err: &mut dyn Write) -> i32 {
+    let config = Config::new(args);
+    if let Some(first) = config.errors.first() {
+        report(err, &config.errors);
+        return first.code;
+    }
+
+    if config.print_help_and_exit {
+        return help(out).map_or(TROUBLE, |_| SELECTED);
+    }
+    if config.print_version_and_exit {
+        return version(out).map_or(TROUBLE, |_| SELECTED);
+    }
+
+    let matcher = match Matcher::new(&config.patterns, config.syntax, config.ignore_case, config.whole) {
+        Ok(matcher) => matcher,
+        Err(error) => {
+            report(err, std::slice::from_ref(&error));
+            return error.code;
+        }
+    };
+
+    let mut out = BufWriter::new(out);
+    let mut searcher = Searcher::new(&config, &matcher);
+    let mut success = false;
+    let mut trouble = false;
+    let mut write_failure = None;
+
+    let _ = walk(&config, &mut |found| {
+        let input = match found {
+            Ok(input) => input,
+            Err(error) => {
+                trouble = true;
+                if !config.no_messages {
+                    report(err, std::slice::from_ref(&error));
+                }
+                return ControlFlow::Continue(());
+            }
+        };
+
+        match search(&config, &mut searcher, &input, &mut out, err) {
+            Ok(selected) => {
+                /* with -L, success is a file listed */
+                success |= if config.output == Output::FilesWithoutMatch { selected == 0 } else { selected > 0 };
+                if config.output == Output::Quiet && success {
+                    return ControlFlow::Break(());
+                }
+            }
+            Err(Failure::Read(error)) => {
+                trouble = true;
+                if !config.no_messages {
+                    report(err, &[file_error(input.name(), &error)]);
+                }
+            }
+            Err(Failure::Write(error)) => {
+                write_failure = Some(write_error(&error));
+                return ControlFlow::Break(());
+            }
+        }
+        ControlFlow::Continue(())
+    });
+
+    if let Some(error) = write_failure.or_else(|| out.flush().err().map(|error| write_error(&error))) {
+        report(err, &[error]);
+        return TROUBLE;
+    }
+
+    /* -q asks only whether something matched, so trouble elsewhere doesn't matter once it has */
+    if trouble && !(config.output == Output::Quiet && success) {
+        TROUBLE
+    } else if success {
+        SELECTED
+    } else {
+        NOT_SELECTED
+    }
+}
+
+///
+/// Search one input, returning how many lines were selected.
+///
+fn search(config: &Config, searcher: &mut Searcher, input: &Input, out: &mut dyn Write, err: &mut dyn Write) -> Result<u64, Failure> {
+    let mut reader = input.open().map_err(Failure::Read)?;
+    let show_name = config.with_filename.unwrap_or(config.files.len() > 1 || input.nested());
+    let summary = searcher.search(&mut reader, input.name(), show_name, out)?;
+
+    if summary.binary && summary.selected > 0 && matches!(config.output, Output::Lines | Output::OnlyMatching) {
+        out.flush().map_err(Failure::Write)?;
+        report(err, &[binary_file_matches(input.name())]);
+    }
+    Ok(summary.selected)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::test_support::TempDir;
+    use crate::grep::grep;
+
+    fn run(args: &[&str]) -> (i32, String, String) {
+        let args: Vec<String> = std::iter::once("grep").chain(args.iter().copied()).map(|s| s.to_string()).collect();
+        let mut out = vec![];
+        let mut err = vec![];
+        let status = grep(&args, &mut out, &mut err);
+        (status, String::from_utf8(out).unwrap(), String::from_utf8(err).unwrap())
+    }
+
+    #[test]
+    fn test_files() {
+        let dir = TempDir::new("files");
+        let a = dir.file("a", b"one\ntwo\n");
+        let b = dir.file("b", b"three\n");
+
+        assert_eq!((0, "two\n".to_string(), String::new()), run(&["tw", &a]));
+        assert_eq!((0, format!("{}:one\n{}:three\n", a, b), String::new()), run(&["-E", "one|three", &a, &b]));
+        assert_eq!((1, String::new(), String::new()), run(&["four", &a, &b]));
+        assert_eq!((0, "one\n".to_string(), String::new()), run(&["-h", "-x", "one", &a, &b]));
+    }
+
+    #[test]
+    fn test_status() {
+        let dir = TempDir::new("status");
+        let a = dir.file("a", b"one\n");
+        let missing = dir.join("missing");
+        let missing = missing.to_str().unwrap();
+
+        let message = format!("grep: {}: No such file or directory\n", missing);
+        assert_eq!((2, format!("{}:one\n", a), message.clone()), run(&["one", &a, missing]));
+        assert_eq!((2, String::new(), String::new()), run(&["-s", "one", missing]));
+        assert_eq!((0, String::new(), message), run(&["-q", "one", missing, &a]));
+        assert_eq!((0, format!("{}\n", a), String::new()), run(&["-L", "two", &a]));
+        assert_eq!((1, String::new(), String::new()), run(&["-L", "one", &a]));
+    }
+
+    #[test]
+    fn test_recursive() {
+        let dir = TempDir::new("recursive");
+        dir.file("a", b"hit\n");
+        std::fs::create_dir(dir.join("sub")).unwrap();
+        dir.file("sub/b", b"miss\nhit\n");
+
+        assert_eq!((0, format!("{0}/a:1:hit\n{0}/sub/b:2:hit\n", dir.path()), String::new()), run(&["-rn", "hit", dir.path()]));
+        assert_eq!((0, format!("{}/sub/b\n", dir.path()), String::new()), run(&["-rl", "--exclude=a", "hit", dir.path()]));
+    }
+
+    #[test]
+    fn test_binary() {
+        let dir = TempDir::new("binary");
+        let file = dir.file("bin", b"hit\0\n");
+
+        assert_eq!((0, String::new(), format!("grep: {}: binary file matches\n", file)), run(&["hit", &file]));
+        assert_eq!((1, String::new(), String::new()), run(&["-I", "hit", &file]));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!((2, String::new(), "grep: Unmatched ( or \\(\n".to_string()), run(&["-E", "(a"]));
+        assert_eq!((2, String::new(), "grep: invalid option -- 'P'\n".to_string()), run(&["-P", "a"]));
+    }
+}
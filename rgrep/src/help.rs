@@ -0,0 +1,67 @@
+use std::io::{self, Write};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub(crate) fn version(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "rgrep {}", VERSION)
+}
+
+pub(crate) fn help(out: &mut dyn Write) -> io::Result<()> {
+    version(out)?;
+    writeln!(out)?;
+    writeln!(out, "Usage: grep [OPTION]... PATTERNS [FILE]...")?;
+    writeln!(out, "Search for PATTERNS in each FILE.")?;
+    writeln!(out, "Example: grep -i 'hello world' menu.h main.c")?;
+    writeln!(out, "PATTERNS can contain multiple patterns separated by newlines.")?;
+    writeln!(out)?;
+    writeln!(out, "Pattern selection and interpretation:")?;
+    writeln!(out, "  -E, --extended-regexp     PATTERNS are extended regular expressions")?;
+    writeln!(out, "  -F, --fixed-strings       PATTERNS are strings")?;
+    writeln!(out, "  -G, --basic-regexp        PATTERNS are basic regular expressions")?;
+    writeln!(out, "  -e, --regexp=PATTERNS     use PATTERNS for matching")?;
+    writeln!(out, "  -f, --file=FILE           take PATTERNS from FILE")?;
+    writeln!(out, "  -i, --ignore-case         ignore case distinctions in patterns and data")?;
+    writeln!(out, "      --no-ignore-case      do not ignore case distinctions (default)")?;
+    writeln!(out, "  -w, --word-regexp         match only whole words")?;
+    writeln!(out, "  -x, --line-regexp         match only whole lines")?;
+    writeln!(out)?;
+    writeln!(out, "Miscellaneous:")?;
+    writeln!(out, "  -s, --no-messages         suppress error messages")?;
+    writeln!(out, "  -v, --invert-match        select non-matching lines")?;
+    writeln!(out, "  -V, --version             display version information and exit")?;
+    writeln!(out, "      --help                display this help text and exit")?;
+    writeln!(out)?;
+    writeln!(out, "Output control:")?;
+    writeln!(out, "  -m, --max-count=NUM       stop after NUM selected lines")?;
+    writeln!(out, "  -b, --byte-offset         print the byte offset with output lines")?;
+    writeln!(out, "  -n, --line-number         print line number with output lines")?;
+    writeln!(out, "  -H, --with-filename       print file name with output lines")?;
+    writeln!(out, "  -h, --no-filename         suppress the file name prefix on output")?;
+    writeln!(out, "  -o, --only-matching       show only nonempty parts of lines that match")?;
+    writeln!(out, "  -q, --quiet, --silent     suppress all normal output")?;
+    writeln!(out, "      --binary-files=TYPE   assume that binary files are TYPE;")?;
+    writeln!(out, "                            TYPE is 'binary', 'text', or 'without-match'")?;
+    writeln!(out, "  -a, --text                equivalent to --binary-files=text")?;
+    writeln!(out, "  -I                        equivalent to --binary-files=without-match")?;
+    writeln!(out, "  -r, --recursive           search directories, following only command-line links")?;
+    writeln!(out, "  -R, --dereference-recursive  likewise, but follow all symlinks")?;
+    writeln!(out, "      --include=GLOB        search only files that match GLOB (a file pattern)")?;
+    writeln!(out, "      --exclude=GLOB        skip files that match GLOB")?;
+    writeln!(out, "      --exclude-dir=GLOB    skip directories that match GLOB")?;
+    writeln!(out, "  -L, --files-without-match  print only names of FILEs with no selected lines")?;
+    writeln!(out, "  -l, --files-with-matches  print only names of FILEs with selected lines")?;
+    writeln!(out, "  -c, --count               print only a count of selected lines per FILE")?;
+    writeln!(out)?;
+    writeln!(out, "Context control:")?;
+    writeln!(out, "  -B, --before-context=NUM  print NUM lines of leading context")?;
+    writeln!(out, "  -A, --after-context=NUM   print NUM lines of trailing context")?;
+    writeln!(out, "  -C, --context=NUM         print NUM lines of output context")?;
+    writeln!(out, "      --color[=WHEN],")?;
+    writeln!(out, "      --colour[=WHEN]       use markers to highlight the matching strings;")?;
+    writeln!(out, "                            WHEN is 'always', 'never', or 'auto'")?;
+    writeln!(out)?;
+    writeln!(out, "When FILE is '-', read standard input.  With no FILE, read '.' if")?;
+    writeln!(out, "recursive, '-' otherwise.  With fewer than two FILEs, assume -h.")?;
+    writeln!(out, "Exit status is 0 if any line is selected, 1 otherwise;")?;
+    writeln!(out, "if any error occurs and -q is not given, the exit status is 2.")
+}
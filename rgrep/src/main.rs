@@ -0,0 +1,23 @@
+mod backtrack;
+mod config;
+mod errors;
+mod grep;
+mod help;
+mod matcher;
+mod nfa;
+mod printer;
+mod search;
+mod syntax;
+mod text;
+mod walk;
+
+use crate::grep::grep;
+
+///
+/// Main program entrypoint.
+///
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let status = grep(&args, &mut std::io::stdout(), &mut std::io::stderr());
+    std::process::exit(status);
+}
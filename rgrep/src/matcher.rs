@@ -0,0 +1,169 @@
+use common::input::known_error::KnownError;
+use crate::backtrack::Backtracker;
+use crate::errors::invalid_pattern;
+use crate::nfa::Program;
+use crate::syntax::{parse, Syntax};
+use crate::text::{decode, is_word, previous};
+
+const TOO_BIG: &str = "Regular expression too big";
+
+///
+/// How one pattern is matched: by automaton where possible, by backtracking where it has to be.
+///
+#[derive(Debug, Clone)]
+enum Engine {
+    Automaton(Program),
+    Backtracking(Backtracker),
+}
+
+impl Engine {
+    fn find(&self, text: &[u8], from: usize) -> Option<(usize, usize)> {
+        match self {
+            Engine::Automaton(program) => program.find(text, from),
+            Engine::Backtracking(backtracker) => backtracker.find(text, from),
+        }
+    }
+
+    fn ends(&self, text: &[u8], start: usize) -> Vec<usize> {
+        match self {
+            Engine::Automaton(program) => program.ends(text, start),
+            Engine::Backtracking(backtracker) => backtracker.ends(text, start),
+        }
+    }
+}
+
+///
+/// Where a match must sit in a line.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Whole {
+    /// Anywhere.
+    Anything,
+    /// With no word character on either side; `-w`.
+    Word,
+    /// From the start of the line to its end; `-x`.
+    Line,
+}
+
+///
+/// Every pattern given, ready to search lines with.  A line matches if any pattern does.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct Matcher {
+    engines: Vec<Engine>,
+    whole: Whole,
+}
+
+impl Matcher {
+    pub(crate) fn new(patterns: &[String], syntax: Syntax, ignore_case: bool, whole: Whole) -> Result<Matcher, KnownError> {
+        let mut engines = vec![];
+        for pattern in patterns {
+            let node = parse(pattern, syntax)?;
+            let engine = if node.has_backreference() {
+                Engine::Backtracking(Backtracker::new(node, ignore_case))
+            } else {
+                Engine::Automaton(Program::compile(&node, ignore_case).ok_or_else(|| invalid_pattern(TOO_BIG))?)
+            };
+            engines.push(engine);
+        }
+        Ok(Matcher { engines, whole })
+    }
+
+    ///
+    /// Whether any pattern matches somewhere in the line.
+    ///
+    pub(crate) fn is_match(&self, line: &[u8]) -> bool {
+        self.find(line, 0).is_some()
+    }
+
+    ///
+    /// Find the leftmost match at or after `from`, and of those the longest, that sits where `-w`
+    /// or `-x` say it must.
+    ///
+    pub(crate) fn find(&self, line: &[u8], from: usize) -> Option<(usize, usize)> {
+        match self.whole {
+            Whole::Anything => self.find_anywhere(line, from),
+            Whole::Line => (from == 0 && self.ends(line, 0).contains(&line.len())).then_some((0, line.len())),
+            Whole::Word => self.find_word(line, from),
+        }
+    }
+
+    fn find_anywhere(&self, line: &[u8], from: usize) -> Option<(usize, usize)> {
+        self.engines.iter()
+            .filter_map(|engine| engine.find(line, from))
+            .min_by(|(a_start, a_end), (b_start, b_end)| a_start.cmp(b_start).then(b_end.cmp(a_end)))
+    }
+
+    fn ends(&self, line: &[u8], start: usize) -> Vec<usize> {
+        let mut ends: Vec<usize> = self.engines.iter().flat_map(|engine| engine.ends(line, start)).collect();
+        ends.sort_unstable();
+        ends.dedup();
+        ends
+    }
+
+    ///
+    /// Try each place a match starts; the longest match there that ends at a word boundary wins,
+    /// and if there's none, the search moves on.
+    ///
+    fn find_word(&self, line: &[u8], from: usize) -> Option<(usize, usize)> {
+        let mut from = from;
+        while from <= line.len() {
+            let (start, _) = self.find_anywhere(line, from)?;
+            if !is_word(previous(line, start)) {
+                let end = self.ends(line, start).into_iter()
+                    .rev()
+                    .find(|&end| end == line.len() || !is_word(decode(line, end).0));
+                if let Some(end) = end {
+                    return Some((start, end));
+                }
+            }
+            if start >= line.len() {
+                return None;
+            }
+            from = start + decode(line, start).1;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::matcher::{Matcher, Whole};
+    use crate::syntax::Syntax;
+
+    fn matcher(patterns: &[&str], syntax: Syntax, whole: Whole) -> Matcher {
+        let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+        Matcher::new(&patterns, syntax, false, whole).unwrap()
+    }
+
+    fn found<'t>(matcher: &Matcher, text: &'t str) -> Option<&'t str> {
+        matcher.find(text.as_bytes(), 0).map(|(start, end)| &text[start..end])
+    }
+
+    #[test]
+    fn test_several_patterns() {
+        let matcher = matcher(&["c", "ab", "abc"], Syntax::Fixed, Whole::Anything);
+        assert_eq!(Some("abc"), found(&matcher, "xabcd"));
+        assert_eq!(Some((3, 4)), matcher.find(b"abcc", 3));
+        assert!(!matcher.is_match(b"xyz"));
+        assert!(!self::matcher(&[], Syntax::Basic, Whole::Anything).is_match(b"abc"));
+    }
+
+    #[test]
+    fn test_word() {
+        let matcher = matcher(&["foo.*"], Syntax::Basic, Whole::Word);
+        assert_eq!(Some("foo bar"), found(&matcher, "foo bar"));
+        assert_eq!(Some("foo bar baz"), found(&matcher, "xfoo foo bar baz"));
+        assert_eq!(None, found(&self::matcher(&["foo"], Syntax::Basic, Whole::Word), "foobar food"));
+        assert_eq!(Some("foo"), found(&self::matcher(&["foo"], Syntax::Basic, Whole::Word), "foobar foo"));
+        assert_eq!(Some(""), found(&self::matcher(&[""], Syntax::Basic, Whole::Word), ""));
+        assert_eq!(None, found(&self::matcher(&[""], Syntax::Basic, Whole::Word), "abc"));
+    }
+
+    #[test]
+    fn test_line() {
+        let matcher = matcher(&["a*", "b\\(c\\)\\1"], Syntax::Basic, Whole::Line);
+        assert!(matcher.is_match(b"aaa") && matcher.is_match(b"") && matcher.is_match(b"bcc"));
+        assert!(!matcher.is_match(b"aab") && !matcher.is_match(b"bccc"));
+    }
+}
@@ -0,0 +1,304 @@
+use crate::syntax::{Assertion, Bracket, Node};
+use crate::text::{decode, holds, same};
+
+/* keeps nested intervals like `((a{100}){100}){100}` from eating all memory */
+const INSTRUCTION_LIMIT: usize = 1 << 20;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Instruction {
+    Char(char),
+    Any,
+    Bracket(Bracket),
+    Assert(Assertion),
+    Split(usize, usize),
+    Jump(usize),
+    Match,
+}
+
+///
+/// A pattern compiled for simulation as a nondeterministic automaton, which takes time in
+/// proportion to the length of the text times the size of the pattern, whatever the pattern.
+/// It can't handle back-references.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct Program {
+    instructions: Vec<Instruction>,
+    ignore_case: bool,
+}
+
+///
+/// One path through the automaton: where it is in the program, and where in the text its match
+/// began.
+///
+#[derive(Debug, Clone, Copy)]
+struct Thread {
+    pc: usize,
+    start: usize,
+}
+
+///
+/// The threads at one position in the text, with at most one per instruction.  When two paths
+/// meet, the one that began earlier is kept, since that's the one that makes a match leftmost.
+///
+struct Threads {
+    threads: Vec<Thread>,
+    /* every instruction visited at this position, including the ones that consume nothing */
+    visited: Vec<usize>,
+    present: Vec<bool>,
+}
+
+impl Threads {
+    fn new(size: usize) -> Threads {
+        Threads { threads: Vec::with_capacity(size), visited: Vec::with_capacity(size), present: vec![false; size] }
+    }
+
+    fn visit(&mut self, pc: usize) -> bool {
+        if self.present[pc] {
+            return false;
+        }
+        self.present[pc] = true;
+        self.visited.push(pc);
+        true
+    }
+
+    fn clear(&mut self) {
+        self.visited.iter().for_each(|pc| self.present[*pc] = false);
+        self.visited.clear();
+        self.threads.clear();
+    }
+}
+
+impl Program {
+    ///
+    /// Compile a pattern; [None] when it's too big.
+    ///
+    pub(crate) fn compile(node: &Node, ignore_case: bool) -> Option<Program> {
+        let mut program = Program { instructions: vec![], ignore_case };
+        program.emit(node)?;
+        program.instructions.push(Instruction::Match);
+        Some(program)
+    }
+
+    fn push(&mut self, instruction: Instruction) -> Option<usize> {
+        if self.instructions.len() >= INSTRUCTION_LIMIT {
+            return None;
+        }
+        self.instructions.push(instruction);
+        Some(self.instructions.len() - 1)
+    }
+
+    fn emit(&mut self, node: &Node) -> Option<()> {
+        match node {
+            Node::Empty => {}
+            Node::Char(c) => {
+                self.push(Instruction::Char(*c))?;
+            }
+            Node::Any => {
+                self.push(Instruction::Any)?;
+            }
+            Node::Bracket(bracket) => {
+                self.push(Instruction::Bracket(bracket.clone()))?;
+            }
+            Node::Assert(assertion) => {
+                self.push(Instruction::Assert(*assertion))?;
+            }
+            Node::Group(node, _) => self.emit(node)?,
+            Node::Backreference(_) => unreachable!("back-references need backtracking"),
+            Node::Concat(nodes) => {
+                for node in nodes {
+                    self.emit(node)?;
+                }
+            }
+            Node::Alternate(nodes) => {
+                /* each split tries one branch or moves on to the next split; each branch jumps to the end */
+                let mut jumps = vec![];
+                for (index, node) in nodes.iter().enumerate() {
+                    if index + 1 < nodes.len() {
+                        let split = self.push(Instruction::Split(0, 0))?;
+                        self.emit(node)?;
+                        jumps.push(self.push(Instruction::Jump(0))?);
+                        let next = self.instructions.len();
+                        self.instructions[split] = Instruction::Split(split + 1, next);
+                    } else {
+                        self.emit(node)?;
+                    }
+                }
+                let end = self.instructions.len();
+                jumps.into_iter().for_each(|jump| self.instructions[jump] = Instruction::Jump(end));
+            }
+            Node::Repeat { node, min, max } => {
+                for _ in 0..*min {
+                    self.emit(node)?;
+                }
+                match max {
+                    None => {
+                        let split = self.push(Instruction::Split(0, 0))?;
+                        self.emit(node)?;
+                        self.push(Instruction::Jump(split))?;
+                        let end = self.instructions.len();
+                        self.instructions[split] = Instruction::Split(split + 1, end);
+                    }
+                    Some(max) => {
+                        let mut splits = vec![];
+                        for _ in *min..*max {
+                            splits.push(self.push(Instruction::Split(0, 0))?);
+                            self.emit(node)?;
+                        }
+                        let end = self.instructions.len();
+                        splits.into_iter().for_each(|split| self.instructions[split] = Instruction::Split(split + 1, end));
+                    }
+                }
+            }
+        }
+        Some(())
+    }
+
+    ///
+    /// Find the leftmost match that starts at or after `from`, and of those the longest.
+    ///
+    pub(crate) fn find(&self, text: &[u8], from: usize) -> Option<(usize, usize)> {
+        let mut best: Option<(usize, usize)> = None;
+        self.run(text, from, false, &mut |start, end| {
+            if best.is_none_or(|(best_start, best_end)| start < best_start || (start == best_start && end > best_end)) {
+                best = Some((start, end));
+            }
+            best.map(|(start, _)| start)
+        });
+        best
+    }
+
+    ///
+    /// Every position where a match starting at `start` can end, in increasing order.
+    ///
+    pub(crate) fn ends(&self, text: &[u8], start: usize) -> Vec<usize> {
+        let mut ends = vec![];
+        self.run(text, start, true, &mut |_, end| {
+            if ends.last() != Some(&end) {
+                ends.push(end);
+            }
+            None
+        });
+        ends
+    }
+
+    ///
+    /// Step every thread through the text together.  Each match is reported as it's found; the
+    /// report returns the start of the match being kept, if any, after which threads that began
+    /// later are dropped and no new ones begin.
+    ///
+    fn run(&self, text: &[u8], from: usize, anchored: bool, report: &mut dyn FnMut(usize, usize) -> Option<usize>) {
+        let mut current = Threads::new(self.instructions.len());
+        let mut next = Threads::new(self.instructions.len());
+        let mut kept: Option<usize> = None;
+        let mut position = from;
+
+        loop {
+            if kept.is_none() && (!anchored || position == from) {
+                self.add(&mut current, Thread { pc: 0, start: position }, text, position, report, &mut kept);
+            }
+            if position >= text.len() || (current.threads.is_empty() && (anchored || kept.is_some())) {
+                break;
+            }
+
+            let (c, width) = decode(text, position);
+            for index in 0..current.threads.len() {
+                let thread = current.threads[index];
+                if kept.is_some_and(|start| thread.start > start) {
+                    continue;
+                }
+                let advances = match (&self.instructions[thread.pc], c) {
+                    (Instruction::Char(expected), Some(c)) => same(*expected, c, self.ignore_case),
+                    (Instruction::Any, Some(_)) => true,
+                    (Instruction::Bracket(bracket), Some(c)) => bracket.matches(c, self.ignore_case),
+                    _ => false,
+                };
+                if advances {
+                    self.add(&mut next, Thread { pc: thread.pc + 1, start: thread.start }, text, position + width, report, &mut kept);
+                }
+            }
+
+            std::mem::swap(&mut current, &mut next);
+            next.clear();
+            position += width;
+        }
+    }
+
+    ///
+    /// Add a thread, following jumps, splits and assertions through to the instructions that
+    /// consume a character, or to a match.
+    ///
+    fn add(&self, threads: &mut Threads, thread: Thread, text: &[u8], position: usize, report: &mut dyn FnMut(usize, usize) -> Option<usize>, kept: &mut Option<usize>) {
+        let mut stack = vec![thread.pc];
+        while let Some(pc) = stack.pop() {
+            if !threads.visit(pc) {
+                continue;
+            }
+
+            match &self.instructions[pc] {
+                Instruction::Jump(target) => stack.push(*target),
+                Instruction::Split(first, second) => {
+                    stack.push(*second);
+                    stack.push(*first);
+                }
+                Instruction::Assert(assertion) => {
+                    if holds(*assertion, text, position) {
+                        stack.push(pc + 1);
+                    }
+                }
+                Instruction::Match => {
+                    *kept = report(thread.start, position);
+                }
+                _ => threads.threads.push(Thread { pc, start: thread.start }),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nfa::Program;
+    use crate::syntax::{parse, Syntax};
+
+    fn program(pattern: &str) -> Program {
+        Program::compile(&parse(pattern, Syntax::Extended).unwrap(), false).unwrap()
+    }
+
+    fn found<'t>(pattern: &str, text: &'t str) -> Option<&'t str> {
+        program(pattern).find(text.as_bytes(), 0).map(|(start, end)| &text[start..end])
+    }
+
+    #[test]
+    fn test_leftmost_longest() {
+        assert_eq!(Some("ab"), found("a|ab", "xab"));
+        assert_eq!(Some("abcd"), found("(a|ab)(c|bcd)", "abcd"));
+        assert_eq!(Some("aaa"), found("a*", "aaab"));
+        assert_eq!(Some(""), found("x*", "aaa"));
+        assert_eq!(Some("bbb"), found("b+", "abbbc"));
+        assert_eq!(Some("xyz"), found("x.z|y", "axyz"));
+        assert_eq!(None, found("ac", "abc"));
+    }
+
+    #[test]
+    fn test_repeats_and_anchors() {
+        assert_eq!(Some("aaa"), found("a{2,3}", "aaaa"));
+        assert_eq!(Some("aa"), found("^a{2}", "aaaa"));
+        assert_eq!(None, found("^b", "ab"));
+        assert_eq!(Some("b"), found("b$", "abb"));
+        assert_eq!(Some("foo"), found("\\<f\\w*", "xfoo foo"));
+        assert_eq!(Some(""), found("(a*)*", "b"));
+        assert_eq!(Some("ééé"), found("é+", "aééé"));
+    }
+
+    #[test]
+    fn test_ends() {
+        assert_eq!(vec![0, 1, 2], program("a*").ends(b"aab", 0));
+        assert_eq!(vec![1, 3], program("a|abc").ends(b"abc", 0));
+        assert!(program("b").ends(b"ab", 0).is_empty());
+    }
+
+    #[test]
+    fn test_ignore_case() {
+        let program = Program::compile(&parse("ab[c-e]", Syntax::Basic).unwrap(), true).unwrap();
+        assert_eq!(Some((1, 4)), program.find(b"xABD", 0));
+    }
+}
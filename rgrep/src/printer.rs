@@ -0,0 +1,202 @@
+use std::io::{self, Write};
+use crate::matcher::Matcher;
+use crate::text::decode;
+
+/* the colors grep uses when GREP_COLORS doesn't say otherwise */
+const MATCH_COLOR: &str = "01;31";
+const FILE_NAME_COLOR: &str = "35";
+const LINE_NUMBER_COLOR: &str = "32";
+const BYTE_OFFSET_COLOR: &str = "32";
+const SEPARATOR_COLOR: &str = "36";
+
+const GROUP_SEPARATOR: &str = "--";
+pub(crate) const SELECTED: u8 = b':';
+pub(crate) const CONTEXT: u8 = b'-';
+
+///
+/// Where a line came from, for the prefix written before it.
+///
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Origin<'n> {
+    pub(crate) name: Option<&'n str>,
+    pub(crate) line_number: u64,
+    pub(crate) byte_offset: u64,
+}
+
+///
+/// Writes lines, names and counts the way the options ask, and keeps track of what's been written
+/// so groups of context can be set apart.
+///
+pub(crate) struct Printer {
+    color: bool,
+    line_number: bool,
+    byte_offset: bool,
+    /* whether anything with context has been written, in any file */
+    grouped: bool,
+}
+
+impl Printer {
+    pub(crate) fn new(color: bool, line_number: bool, byte_offset: bool) -> Printer {
+        Printer { color, line_number, byte_offset, grouped: false }
+    }
+
+    ///
+    /// Write `--` between groups of lines that aren't next to each other; the first group has
+    /// nothing before it.
+    ///
+    pub(crate) fn group_separator(&mut self, out: &mut dyn Write) -> io::Result<()> {
+        if self.grouped {
+            self.colored(out, SEPARATOR_COLOR, GROUP_SEPARATOR.as_bytes())?;
+            out.write_all(b"\n")?;
+        }
+        self.grouped = true;
+        Ok(())
+    }
+
+    ///
+    /// Write a whole line, with its matches highlighted when coloring.
+    ///
+    pub(crate) fn line(&self, out: &mut dyn Write, origin: Origin, separator: u8, line: &[u8], matcher: &Matcher) -> io::Result<()> {
+        self.prefix(out, origin, separator)?;
+        if !self.color {
+            out.write_all(line)?;
+            return out.write_all(b"\n");
+        }
+
+        let mut written = 0;
+        for (start, end) in matches(matcher, line) {
+            out.write_all(&line[written..start])?;
+            self.colored(out, MATCH_COLOR, &line[start..end])?;
+            written = end;
+        }
+        out.write_all(&line[written..])?;
+        out.write_all(b"\n")
+    }
+
+    ///
+    /// Write each non-empty match in a line on a line of its own; `-o`.  The byte offset is the
+    /// match's, not the line's.
+    ///
+    pub(crate) fn only_matching(&self, out: &mut dyn Write, origin: Origin, line: &[u8], matcher: &Matcher) -> io::Result<()> {
+        for (start, end) in matches(matcher, line) {
+            let origin = Origin { byte_offset: origin.byte_offset + start as u64, ..origin };
+            self.prefix(out, origin, SELECTED)?;
+            self.colored(out, MATCH_COLOR, &line[start..end])?;
+            out.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Write the number of selected lines in a file; `-c`.
+    ///
+    pub(crate) fn count(&self, out: &mut dyn Write, name: Option<&str>, count: u64) -> io::Result<()> {
+        if let Some(name) = name {
+            self.colored(out, FILE_NAME_COLOR, name.as_bytes())?;
+            self.colored(out, SEPARATOR_COLOR, &[SELECTED])?;
+        }
+        writeln!(out, "{}", count)
+    }
+
+    ///
+    /// Write a file's name on a line of its own; `-l` and `-L`.
+    ///
+    pub(crate) fn name(&self, out: &mut dyn Write, name: &str) -> io::Result<()> {
+        self.colored(out, FILE_NAME_COLOR, name.as_bytes())?;
+        out.write_all(b"\n")
+    }
+
+    fn prefix(&self, out: &mut dyn Write, origin: Origin, separator: u8) -> io::Result<()> {
+        if let Some(name) = origin.name {
+            self.colored(out, FILE_NAME_COLOR, name.as_bytes())?;
+            self.colored(out, SEPARATOR_COLOR, &[separator])?;
+        }
+        if self.line_number {
+            self.colored(out, LINE_NUMBER_COLOR, origin.line_number.to_string().as_bytes())?;
+            self.colored(out, SEPARATOR_COLOR, &[separator])?;
+        }
+        if self.byte_offset {
+            self.colored(out, BYTE_OFFSET_COLOR, origin.byte_offset.to_string().as_bytes())?;
+            self.colored(out, SEPARATOR_COLOR, &[separator])?;
+        }
+        Ok(())
+    }
+
+    fn colored(&self, out: &mut dyn Write, color: &str, text: &[u8]) -> io::Result<()> {
+        if self.color {
+            write!(out, "\x1b[{}m\x1b[K", color)?;
+            out.write_all(text)?;
+            out.write_all(b"\x1b[m\x1b[K")
+        } else {
+            out.write_all(text)
+        }
+    }
+}
+
+///
+/// Every non-empty match in a line, left to right; an empty match just moves the search along.
+///
+fn matches(matcher: &Matcher, line: &[u8]) -> Vec<(usize, usize)> {
+    let mut found = vec![];
+    let mut from = 0;
+    while from <= line.len() {
+        let Some((start, end)) = matcher.find(line, from) else {
+            break;
+        };
+        if end > start {
+            found.push((start, end));
+            from = end;
+        } else if start < line.len() {
+            from = start + decode(line, start).1;
+        } else {
+            break;
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::matcher::{Matcher, Whole};
+    use crate::printer::{matches, Origin, Printer, CONTEXT, SELECTED};
+    use crate::syntax::Syntax;
+
+    fn matcher(pattern: &str) -> Matcher {
+        Matcher::new(&[pattern.to_string()], Syntax::Extended, false, Whole::Anything).unwrap()
+    }
+
+    #[test]
+    fn test_matches() {
+        assert_eq!(vec![(1, 2), (3, 5)], matches(&matcher("b+"), b"abcbb"));
+        assert_eq!(vec![(0, 1), (2, 3)], matches(&matcher("x*"), b"xax"));
+        assert!(matches(&matcher("q"), b"abc").is_empty());
+    }
+
+    #[test]
+    fn test_prefixes() {
+        let printer = Printer::new(false, true, true);
+        let origin = Origin { name: Some("f"), line_number: 3, byte_offset: 10 };
+
+        let mut out = vec![];
+        printer.line(&mut out, origin, CONTEXT, b"abc", &matcher("b")).unwrap();
+        printer.only_matching(&mut out, origin, b"abcb", &matcher("b")).unwrap();
+        assert_eq!("f-3-10-abc\nf:3:11:b\nf:3:13:b\n", String::from_utf8(out).unwrap());
+
+        let mut out = vec![];
+        Printer::new(false, false, false).line(&mut out, Origin { name: None, ..origin }, SELECTED, b"abc", &matcher("b")).unwrap();
+        assert_eq!("abc\n", String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn test_color() {
+        let mut printer = Printer::new(true, false, false);
+        let mut out = vec![];
+        printer.group_separator(&mut out).unwrap();
+        printer.line(&mut out, Origin { name: Some("f"), line_number: 1, byte_offset: 0 }, SELECTED, b"abc", &matcher("b")).unwrap();
+        printer.group_separator(&mut out).unwrap();
+        assert_eq!(
+            "\x1b[35m\x1b[Kf\x1b[m\x1b[K\x1b[36m\x1b[K:\x1b[m\x1b[Ka\x1b[01;31m\x1b[Kb\x1b[m\x1b[Kc\n\x1b[36m\x1b[K--\x1b[m\x1b[K\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+}
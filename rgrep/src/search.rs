@@ -0,0 +1,223 @@
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use crate::config::{BinaryFiles, Config, Output};
+use crate::matcher::Matcher;
+use crate::printer::{Origin, Printer, CONTEXT, SELECTED};
+
+const BUFFER_SIZE: usize = 32 * 1024;
+const NUL: u8 = 0;
+
+///
+/// What searching one file came to.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Summary {
+    pub(crate) selected: u64,
+    pub(crate) binary: bool,
+}
+
+///
+/// Why searching a file stopped short: reading it failed, which only affects that file, or
+/// writing failed, which ends everything.
+///
+#[derive(Debug)]
+pub(crate) enum Failure {
+    Read(io::Error),
+    Write(io::Error),
+}
+
+///
+/// A line kept back in case a later line is selected and wants it as leading context.
+///
+struct Held {
+    line_number: u64,
+    byte_offset: u64,
+    line: Vec<u8>,
+}
+
+///
+/// Searches files one after another, line by line.
+///
+pub(crate) struct Searcher<'a> {
+    config: &'a Config,
+    matcher: &'a Matcher,
+    printer: Printer,
+}
+
+impl<'a> Searcher<'a> {
+    pub(crate) fn new(config: &'a Config, matcher: &'a Matcher) -> Searcher<'a> {
+        Searcher { config, matcher, printer: Printer::new(config.color, config.line_number, config.byte_offset) }
+    }
+
+    ///
+    /// Search one file, writing what the output mode calls for.  A file is binary if it holds a NUL
+    /// byte; once that's seen, no more of its lines are written.
+    ///
+    pub(crate) fn search(&mut self, input: &mut dyn Read, name: &str, show_name: bool, out: &mut dyn Write) -> Result<Summary, Failure> {
+        let config = self.config;
+        let mut reader = BufReader::with_capacity(BUFFER_SIZE, input);
+        let detect = config.binary_files != BinaryFiles::Text;
+        let mut binary = detect && reader.fill_buf().map_err(Failure::Read)?.contains(&NUL);
+        if binary && config.binary_files == BinaryFiles::WithoutMatch {
+            return Ok(Summary { selected: 0, binary });
+        }
+
+        let lines = config.output == Output::Lines;
+        let grouping = lines && (config.before.is_some() || config.after.is_some());
+        let before = if lines { config.before.unwrap_or(0) } else { 0 };
+        let after = if lines { config.after.unwrap_or(0) } else { 0 };
+        let limit = config.max_count.map_or(u64::MAX, |max| max as u64);
+        let name_shown = show_name.then_some(name);
+
+        let mut held: VecDeque<Held> = VecDeque::with_capacity(before);
+        let mut after_left = 0;
+        let mut last_written: Option<u64> = None;
+        let mut selected = 0u64;
+        let mut line_number = 0u64;
+        let mut byte_offset = 0u64;
+        let mut line = vec![];
+
+        /* once the limit is reached, only trailing context is left to write */
+        while selected < limit || after_left > 0 {
+            line.clear();
+            let read = match reader.read_until(b'\n', &mut line) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(Failure::Read(error)),
+            };
+            line_number += 1;
+            let origin = Origin { name: name_shown, line_number, byte_offset };
+            byte_offset += read as u64;
+            if line.last() == Some(&b'\n') {
+                line.pop();
+            }
+            if detect && line.contains(&NUL) {
+                binary = true;
+            }
+
+            let is_selected = selected < limit && self.matcher.is_match(&line) != config.invert;
+            if is_selected {
+                selected += 1;
+                match config.output {
+                    Output::Quiet | Output::FilesWithMatches | Output::FilesWithoutMatch => break,
+                    Output::Count => {}
+                    Output::Lines | Output::OnlyMatching if binary => break,
+                    Output::OnlyMatching => {
+                        if !config.invert {
+                            self.printer.only_matching(out, origin, &line, self.matcher).map_err(Failure::Write)?;
+                        }
+                    }
+                    Output::Lines => {
+                        while let Some(kept) = held.pop_front() {
+                            let origin = Origin { name: name_shown, line_number: kept.line_number, byte_offset: kept.byte_offset };
+                            self.write(out, origin, CONTEXT, &kept.line, grouping, &mut last_written)?;
+                        }
+                        self.write(out, origin, SELECTED, &line, grouping, &mut last_written)?;
+                        after_left = after;
+                    }
+                }
+            } else if after_left > 0 && !binary {
+                self.write(out, origin, CONTEXT, &line, grouping, &mut last_written)?;
+                after_left -= 1;
+            } else if before > 0 {
+                if held.len() == before {
+                    held.pop_front();
+                }
+                held.push_back(Held { line_number, byte_offset: origin.byte_offset, line: line.clone() });
+            }
+        }
+
+        let listed = match config.output {
+            Output::Count => self.printer.count(out, name_shown, selected),
+            Output::FilesWithMatches if selected > 0 => self.printer.name(out, name),
+            Output::FilesWithoutMatch if selected == 0 => self.printer.name(out, name),
+            _ => Ok(()),
+        };
+        listed.map_err(Failure::Write)?;
+
+        Ok(Summary { selected, binary })
+    }
+
+    ///
+    /// Write a line, after a group separator if it doesn't follow on from the last line written.
+    ///
+    fn write(&mut self, out: &mut dyn Write, origin: Origin, separator: u8, line: &[u8], grouping: bool, last_written: &mut Option<u64>) -> Result<(), Failure> {
+        if grouping && last_written.is_none_or(|last| origin.line_number > last + 1) {
+            self.printer.group_separator(out).map_err(Failure::Write)?;
+        }
+        *last_written = Some(origin.line_number);
+        self.printer.line(out, origin, separator, line, self.matcher).map_err(Failure::Write)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::matcher::Matcher;
+    use crate::search::{Searcher, Summary};
+
+    fn search(line: &str, files: &[(&str, &str)]) -> (String, Vec<Summary>) {
+        let args: Vec<String> = line.split_whitespace().map(|s| s.to_string()).collect();
+        let config = Config::new(&args);
+        let matcher = Matcher::new(&config.patterns, config.syntax, config.ignore_case, config.whole).unwrap();
+        let mut searcher = Searcher::new(&config, &matcher);
+
+        let mut out = vec![];
+        let summaries = files.iter()
+            .map(|(name, text)| searcher.search(&mut text.as_bytes(), name, files.len() > 1, &mut out).unwrap())
+            .collect();
+        (String::from_utf8(out).unwrap(), summaries)
+    }
+
+    fn output(line: &str, text: &str) -> String {
+        search(line, &[("f", text)]).0
+    }
+
+    #[test]
+    fn test_lines() {
+        assert_eq!("b\n", output("grep b", "a\nb\nc"));
+        assert_eq!("a\nc\n", output("grep -v b", "a\nb\nc"));
+        assert_eq!("2:b\n", output("grep -n b", "a\nb\nc"));
+        assert_eq!("2:bb\n5:b\n", output("grep -b b", "a\nbb\nb"));
+        assert_eq!("b\nB\n", output("grep -i b", "b\nB\nc"));
+        assert_eq!("a\nab\n", output("grep -m2 a", "a\nab\nabc"));
+        assert_eq!("", output("grep -m0 a", "a"));
+    }
+
+    #[test]
+    fn test_context() {
+        let text = "1\n2\nx\n4\n5\n6\n7\nx\n9";
+        assert_eq!("2\nx\n4\n--\n7\nx\n9\n", output("grep -C1 x", text));
+        assert_eq!("2-2\n3:x\n--\n7-7\n8:x\n", output("grep -n -B1 x", text));
+        assert_eq!("x\n--\nx\n", output("grep -A0 x", text));
+        assert_eq!("1\n2\nx\n4\n5\n6\n7\nx\n9\n", output("grep -C3 x", text));
+        assert_eq!("a\na\nb\n", output("grep -m1 -A2 a", "a\na\nb\nc"));
+    }
+
+    #[test]
+    fn test_context_across_files() {
+        let (out, _) = search("grep -A1 a", &[("f", "a\nb\nc"), ("g", "a")]);
+        assert_eq!("f:a\nf-b\n--\ng:a\n", out);
+    }
+
+    #[test]
+    fn test_modes() {
+        assert_eq!("ab\nb\n", output("grep -o a*b", "xabx\nb\nc"));
+        assert_eq!("f:2\ng:0\n", search("grep -c a", &[("f", "a\nba\nc"), ("g", "b")]).0);
+        assert_eq!("f\n", search("grep -l a", &[("f", "a"), ("g", "b")]).0);
+        assert_eq!("g\n", search("grep -L a", &[("f", "a"), ("g", "b")]).0);
+        assert_eq!("", output("grep -q a", "a"));
+        assert_eq!("", output("grep -ov a", "b"));
+    }
+
+    #[test]
+    fn test_binary() {
+        let (out, summaries) = search("grep a", &[("f", "a\0\na\n")]);
+        assert_eq!(("".to_string(), vec![Summary { selected: 1, binary: true }]), (out, summaries));
+
+        assert_eq!("a\0\n", output("grep -a a", "a\0\nb\n"));
+        assert_eq!(vec![Summary { selected: 0, binary: true }], search("grep -I a", &[("f", "a\0")]).1);
+        assert_eq!("1\n", output("grep -c b", "a\0\nb\n"));
+    }
+}
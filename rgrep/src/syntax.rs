@@ -0,0 +1,645 @@
+use common::input::known_error::KnownError;
+use crate::errors::invalid_pattern;
+
+/* POSIX's RE_DUP_MAX; counts above it are refused rather than expanded */
+pub(crate) const REPEAT_LIMIT: u32 = 32767;
+const BACKREFERENCE_LIMIT: usize = 9;
+
+const UNMATCHED_BRACKET: &str = "Unmatched [, [^, [:, [., or [=";
+const UNMATCHED_OPEN: &str = "Unmatched ( or \\(";
+const UNMATCHED_CLOSE: &str = "Unmatched ) or \\)";
+const UNMATCHED_BRACE: &str = "Unmatched \\{";
+const INVALID_INTERVAL: &str = "Invalid content of \\{\\}";
+const INVALID_PRECEDING: &str = "Invalid preceding regular expression";
+const INVALID_BACKREFERENCE: &str = "Invalid back reference";
+const INVALID_RANGE_END: &str = "Invalid range end";
+const INVALID_CLASS: &str = "Invalid character class name";
+const TRAILING_BACKSLASH: &str = "Trailing backslash";
+const TOO_BIG: &str = "Regular expression too big";
+const INVALID_COLLATION: &str = "Invalid collation character";
+const CLASS_SYNTAX: &str = "character class syntax is [[:space:]], not [:space:]";
+
+///
+/// Which grammar a pattern is written in.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Syntax {
+    Basic,
+    Extended,
+    Fixed,
+}
+
+///
+/// Zero-width conditions on the text around a position.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Assertion {
+    LineStart,
+    LineEnd,
+    WordStart,
+    WordEnd,
+    WordBoundary,
+    NotWordBoundary,
+}
+
+///
+/// The named classes usable in bracket expressions, as `[:name:]`.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Class {
+    Alnum,
+    Alpha,
+    Blank,
+    Cntrl,
+    Digit,
+    Graph,
+    Lower,
+    Print,
+    Punct,
+    Space,
+    Upper,
+    Xdigit,
+}
+
+impl Class {
+    fn from_name(name: &str) -> Option<Class> {
+        match name {
+            "alnum" => Some(Class::Alnum),
+            "alpha" => Some(Class::Alpha),
+            "blank" => Some(Class::Blank),
+            "cntrl" => Some(Class::Cntrl),
+            "digit" => Some(Class::Digit),
+            "graph" => Some(Class::Graph),
+            "lower" => Some(Class::Lower),
+            "print" => Some(Class::Print),
+            "punct" => Some(Class::Punct),
+            "space" => Some(Class::Space),
+            "upper" => Some(Class::Upper),
+            "xdigit" => Some(Class::Xdigit),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn contains(&self, c: char) -> bool {
+        match self {
+            Class::Alnum => c.is_alphanumeric(),
+            Class::Alpha => c.is_alphabetic(),
+            Class::Blank => c == ' ' || c == '\t',
+            Class::Cntrl => c.is_control(),
+            Class::Digit => c.is_ascii_digit(),
+            Class::Graph => !c.is_control() && !c.is_whitespace(),
+            Class::Lower => c.is_lowercase(),
+            Class::Print => !c.is_control(),
+            Class::Punct => c.is_ascii_punctuation(),
+            Class::Space => c.is_whitespace(),
+            Class::Upper => c.is_uppercase(),
+            Class::Xdigit => c.is_ascii_hexdigit(),
+        }
+    }
+}
+
+///
+/// One member of a bracket expression.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Item {
+    Char(char),
+    Range(char, char),
+    Class(Class),
+}
+
+///
+/// A bracket expression, `[...]`, or one of the shorthands like `\w` that stand for one.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Bracket {
+    pub(crate) negated: bool,
+    pub(crate) items: Vec<Item>,
+}
+
+impl Bracket {
+    ///
+    /// Whether the character is matched; ignoring case, either case of it will do.
+    ///
+    pub(crate) fn matches(&self, c: char, ignore_case: bool) -> bool {
+        let contains = |c: char| self.items.iter().any(|item| match item {
+            Item::Char(member) => *member == c,
+            Item::Range(start, end) => (*start..=*end).contains(&c),
+            Item::Class(class) => class.contains(c),
+        });
+
+        let found = contains(c) || (ignore_case && (contains(lower(c)) || contains(upper(c))));
+        found != self.negated
+    }
+
+    fn word(negated: bool) -> Bracket {
+        Bracket { negated, items: vec![Item::Class(Class::Alnum), Item::Char('_')] }
+    }
+
+    fn space(negated: bool) -> Bracket {
+        Bracket { negated, items: vec![Item::Class(Class::Space)] }
+    }
+}
+
+///
+/// A parsed pattern.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Node {
+    Empty,
+    Char(char),
+    Any,
+    Bracket(Bracket),
+    Assert(Assertion),
+    /// A parenthesized subexpression, numbered from 1 in order of its opening parenthesis.
+    Group(Box<Node>, usize),
+    Backreference(usize),
+    Concat(Vec<Node>),
+    Alternate(Vec<Node>),
+    Repeat { node: Box<Node>, min: u32, max: Option<u32> },
+}
+
+impl Node {
+    ///
+    /// Whether matching needs to remember what groups matched.
+    ///
+    pub(crate) fn has_backreference(&self) -> bool {
+        match self {
+            Node::Backreference(_) => true,
+            Node::Group(node, _) | Node::Repeat { node, .. } => node.has_backreference(),
+            Node::Concat(nodes) | Node::Alternate(nodes) => nodes.iter().any(Node::has_backreference),
+            _ => false,
+        }
+    }
+
+    ///
+    /// The pattern with each back-reference turned into `.*`; it matches wherever the pattern
+    /// does, and more, but needs no backtracking.
+    ///
+    pub(crate) fn loosened(&self) -> Node {
+        match self {
+            Node::Backreference(_) => Node::Repeat { node: Box::new(Node::Any), min: 0, max: None },
+            Node::Group(node, index) => Node::Group(Box::new(node.loosened()), *index),
+            Node::Repeat { node, min, max } => Node::Repeat { node: Box::new(node.loosened()), min: *min, max: *max },
+            Node::Concat(nodes) => Node::Concat(nodes.iter().map(Node::loosened).collect()),
+            Node::Alternate(nodes) => Node::Alternate(nodes.iter().map(Node::loosened).collect()),
+            node => node.clone(),
+        }
+    }
+
+    ///
+    /// How many groups the pattern has.
+    ///
+    pub(crate) fn group_count(&self) -> usize {
+        match self {
+            Node::Group(node, index) => (*index).max(node.group_count()),
+            Node::Repeat { node, .. } => node.group_count(),
+            Node::Concat(nodes) | Node::Alternate(nodes) => nodes.iter().map(Node::group_count).max().unwrap_or(0),
+            _ => 0,
+        }
+    }
+}
+
+///
+/// Parse one pattern, which holds no newlines, in the given syntax.
+///
+pub(crate) fn parse(pattern: &str, syntax: Syntax) -> Result<Node, KnownError> {
+    if syntax == Syntax::Fixed {
+        return Ok(Node::Concat(pattern.chars().map(Node::Char).collect()));
+    }
+
+    let mut parser = Parser { chars: pattern.chars().collect(), position: 0, extended: syntax == Syntax::Extended, groups: 0, closed: vec![] };
+    let node = parser.alternation(0).map_err(invalid_pattern)?;
+    if parser.position < parser.chars.len() {
+        /* only a close that matches no open can stop the top level early */
+        return Err(invalid_pattern(UNMATCHED_CLOSE));
+    }
+    Ok(node)
+}
+
+pub(crate) fn lower(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+pub(crate) fn upper(c: char) -> char {
+    c.to_uppercase().next().unwrap_or(c)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+    extended: bool,
+    groups: usize,
+    /* groups that are complete, and so may be referred back to */
+    closed: Vec<usize>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.position + offset).copied()
+    }
+
+    ///
+    /// Whether an operator comes next: a bare character in an ERE, or an escaped one in a BRE.
+    ///
+    fn at_operator(&self, operator: char) -> bool {
+        if self.extended {
+            self.peek() == Some(operator)
+        } else {
+            self.peek() == Some('\\') && self.peek_at(1) == Some(operator)
+        }
+    }
+
+    fn skip_operator(&mut self) {
+        self.position += if self.extended { 1 } else { 2 };
+    }
+
+    fn at_alternation(&self) -> bool {
+        self.at_operator('|')
+    }
+
+    fn at_close(&self, depth: usize) -> bool {
+        /* an ERE `)` with nothing open is an ordinary character */
+        self.at_operator(')') && (depth > 0 || !self.extended)
+    }
+
+    fn alternation(&mut self, depth: usize) -> Result<Node, &'static str> {
+        let mut branches = vec![self.branch(depth)?];
+        while self.at_alternation() {
+            self.skip_operator();
+            branches.push(self.branch(depth)?);
+        }
+
+        Ok(if branches.len() == 1 { branches.pop().unwrap() } else { Node::Alternate(branches) })
+    }
+
+    fn branch(&mut self, depth: usize) -> Result<Node, &'static str> {
+        let mut pieces = vec![];
+        let start = self.position;
+        while self.peek().is_some() && !self.at_alternation() && !self.at_close(depth) {
+            let atom = self.atom(depth, self.position == start)?;
+            pieces.push(self.repeats(atom)?);
+        }
+
+        Ok(match pieces.len() {
+            0 => Node::Empty,
+            1 => pieces.pop().unwrap(),
+            _ => Node::Concat(pieces),
+        })
+    }
+
+    ///
+    /// Whether the current branch ends here, which decides if a BRE `$` just before is an anchor.
+    ///
+    fn at_branch_end(&self) -> bool {
+        self.peek().is_none() || self.at_alternation() || self.at_operator(')')
+    }
+
+    fn atom(&mut self, depth: usize, first: bool) -> Result<Node, &'static str> {
+        let c = self.peek().unwrap();
+        self.position += 1;
+
+        match c {
+            '.' => Ok(Node::Any),
+            '[' => self.bracket(),
+            '^' if self.extended || first => Ok(Node::Assert(Assertion::LineStart)),
+            '$' if self.extended || self.at_branch_end() => Ok(Node::Assert(Assertion::LineEnd)),
+            '(' if self.extended => self.group(depth),
+            '\\' => self.escape(depth, first),
+            _ => Ok(Node::Char(c)),
+        }
+    }
+
+    fn escape(&mut self, depth: usize, first: bool) -> Result<Node, &'static str> {
+        let Some(c) = self.peek() else {
+            return Err(TRAILING_BACKSLASH);
+        };
+        self.position += 1;
+
+        match c {
+            '(' if !self.extended => self.group(depth),
+            '{' if !self.extended && first => Err(INVALID_PRECEDING),
+            '+' | '?' if !self.extended && first => Ok(Node::Char(c)),
+            '1'..='9' => {
+                let index = c.to_digit(10).unwrap() as usize;
+                if index > BACKREFERENCE_LIMIT || !self.closed.contains(&index) {
+                    return Err(INVALID_BACKREFERENCE);
+                }
+                Ok(Node::Backreference(index))
+            }
+            '<' => Ok(Node::Assert(Assertion::WordStart)),
+            '>' => Ok(Node::Assert(Assertion::WordEnd)),
+            'b' => Ok(Node::Assert(Assertion::WordBoundary)),
+            'B' => Ok(Node::Assert(Assertion::NotWordBoundary)),
+            '`' => Ok(Node::Assert(Assertion::LineStart)),
+            '\'' => Ok(Node::Assert(Assertion::LineEnd)),
+            'w' => Ok(Node::Bracket(Bracket::word(false))),
+            'W' => Ok(Node::Bracket(Bracket::word(true))),
+            's' => Ok(Node::Bracket(Bracket::space(false))),
+            'S' => Ok(Node::Bracket(Bracket::space(true))),
+            _ => Ok(Node::Char(c)),
+        }
+    }
+
+    fn group(&mut self, depth: usize) -> Result<Node, &'static str> {
+        self.groups += 1;
+        let index = self.groups;
+
+        let inner = self.alternation(depth + 1)?;
+        if !self.at_operator(')') {
+            return Err(UNMATCHED_OPEN);
+        }
+        self.skip_operator();
+
+        self.closed.push(index);
+        Ok(Node::Group(Box::new(inner), index))
+    }
+
+    ///
+    /// Apply any repetition operators that follow an atom; several may be stacked.
+    ///
+    fn repeats(&mut self, mut atom: Node) -> Result<Node, &'static str> {
+        /* a BRE `*` straight after a leading `^` is an ordinary character */
+        if !self.extended && atom == Node::Assert(Assertion::LineStart) {
+            return Ok(atom);
+        }
+
+        loop {
+            let (min, max) = match self.peek() {
+                Some('*') => {
+                    self.position += 1;
+                    (0, None)
+                }
+                Some('+') if self.extended => {
+                    self.position += 1;
+                    (1, None)
+                }
+                Some('?') if self.extended => {
+                    self.position += 1;
+                    (0, Some(1))
+                }
+                Some('{') if self.extended && self.interval_follows() => {
+                    self.position += 1;
+                    self.interval()?
+                }
+                Some('\\') if !self.extended => match self.peek_at(1) {
+                    Some('+') => {
+                        self.position += 2;
+                        (1, None)
+                    }
+                    Some('?') => {
+                        self.position += 2;
+                        (0, Some(1))
+                    }
+                    Some('{') => {
+                        self.position += 2;
+                        self.interval()?
+                    }
+                    _ => return Ok(atom),
+                },
+                _ => return Ok(atom),
+            };
+            atom = Node::Repeat { node: Box::new(atom), min, max };
+        }
+    }
+
+    ///
+    /// Whether an ERE `{` starts a well-formed interval; if not, it's an ordinary character.
+    ///
+    fn interval_follows(&self) -> bool {
+        let mut offset = 1;
+        let mut digits = 0;
+        let mut comma = false;
+        while let Some(c) = self.peek_at(offset) {
+            match c {
+                '0'..='9' => digits += 1,
+                ',' if !comma => comma = true,
+                '}' => return digits > 0,
+                _ => return false,
+            }
+            offset += 1;
+        }
+        false
+    }
+
+    ///
+    /// Read the inside of an interval, after its opening brace, through its closing brace.
+    ///
+    fn interval(&mut self) -> Result<(u32, Option<u32>), &'static str> {
+        let min = self.number();
+        let max = if self.peek() == Some(',') {
+            self.position += 1;
+            self.number()
+        } else {
+            Some(min.ok_or(INVALID_INTERVAL)?)
+        };
+
+        if !self.at_operator('}') {
+            return Err(if self.peek().is_none() { UNMATCHED_BRACE } else { INVALID_INTERVAL });
+        }
+        self.skip_operator();
+
+        let min = min.unwrap_or(0);
+        if max.is_some_and(|max| max < min) {
+            return Err(INVALID_INTERVAL);
+        }
+        if min > REPEAT_LIMIT || max.is_some_and(|max| max > REPEAT_LIMIT) {
+            return Err(TOO_BIG);
+        }
+        Ok((min, max))
+    }
+
+    fn number(&mut self) -> Option<u32> {
+        let start = self.position;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.position += 1;
+        }
+        if start == self.position {
+            return None;
+        }
+        /* anything too long to hold is too big to repeat */
+        let digits: String = self.chars[start..self.position].iter().collect();
+        Some(digits.parse().unwrap_or(u32::MAX))
+    }
+
+    ///
+    /// Read a bracket expression, after its opening bracket, through its closing bracket.
+    /// Backslashes are ordinary inside one, and a `]` right at the start is a member.
+    ///
+    fn bracket(&mut self) -> Result<Node, &'static str> {
+        let start = self.position;
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.position += 1;
+        }
+
+        let mut items = vec![];
+        let mut first = true;
+        loop {
+            let Some(c) = self.peek() else {
+                return Err(UNMATCHED_BRACKET);
+            };
+            if c == ']' && !first {
+                self.position += 1;
+                break;
+            }
+            first = false;
+
+            let low = match self.bracket_term()? {
+                Term::Class(class) => {
+                    items.push(Item::Class(class));
+                    continue;
+                }
+                Term::Char(low) => low,
+            };
+
+            /* a `-` before the closing bracket is a member, not a range */
+            if self.peek() == Some('-') && self.peek_at(1).is_some_and(|next| next != ']') {
+                self.position += 1;
+                let Term::Char(high) = self.bracket_term()? else {
+                    return Err(INVALID_RANGE_END);
+                };
+                if high < low {
+                    return Err(INVALID_RANGE_END);
+                }
+                items.push(Item::Range(low, high));
+            } else {
+                items.push(Item::Char(low));
+            }
+        }
+
+        /* `[:space:]` is almost always a mistake for `[[:space:]]` */
+        let text = &self.chars[start..self.position - 1];
+        if text.len() >= 2 && text[0] == ':' && text[text.len() - 1] == ':' {
+            return Err(CLASS_SYNTAX);
+        }
+        Ok(Node::Bracket(Bracket { negated, items }))
+    }
+
+    ///
+    /// Read one member of a bracket expression: a character, `[:class:]`, `[=c=]` or `[.c.]`.
+    ///
+    fn bracket_term(&mut self) -> Result<Term, &'static str> {
+        let c = self.peek().ok_or(UNMATCHED_BRACKET)?;
+        self.position += 1;
+        if c != '[' {
+            return Ok(Term::Char(c));
+        }
+
+        let Some(kind @ (':' | '=' | '.')) = self.peek() else {
+            return Ok(Term::Char('['));
+        };
+        let content_start = self.position + 1;
+        let close = (content_start..self.chars.len().saturating_sub(1))
+            .find(|&index| self.chars[index] == kind && self.chars[index + 1] == ']')
+            .ok_or(UNMATCHED_BRACKET)?;
+        let content: String = self.chars[content_start..close].iter().collect();
+        self.position = close + 2;
+
+        match kind {
+            ':' => Class::from_name(&content).map(Term::Class).ok_or(INVALID_CLASS),
+            /* equivalence classes and collating symbols only name single characters here */
+            _ => {
+                let mut chars = content.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(Term::Char(c)),
+                    _ => Err(INVALID_COLLATION),
+                }
+            }
+        }
+    }
+}
+
+enum Term {
+    Char(char),
+    Class(Class),
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syntax::{parse, Assertion, Bracket, Class, Item, Node, Syntax};
+
+    fn basic(pattern: &str) -> Node {
+        parse(pattern, Syntax::Basic).unwrap()
+    }
+
+    fn extended(pattern: &str) -> Node {
+        parse(pattern, Syntax::Extended).unwrap()
+    }
+
+    fn error(pattern: &str, syntax: Syntax) -> String {
+        parse(pattern, syntax).unwrap_err().message.unwrap()
+    }
+
+    fn chars(text: &str) -> Vec<Node> {
+        text.chars().map(Node::Char).collect()
+    }
+
+    #[test]
+    fn test_basic() {
+        assert_eq!(Node::Concat(chars("a+?|")), basic("a+?|"));
+        assert_eq!(Node::Concat(vec![Node::Char('*'), Node::Char('a')]), basic("*a"));
+        assert_eq!(Node::Concat(vec![Node::Char('a'), Node::Char('^'), Node::Char('$'), Node::Char('b')]), basic("a^$b"));
+        assert_eq!(Node::Concat(vec![Node::Assert(Assertion::LineStart), Node::Char('a'), Node::Assert(Assertion::LineEnd)]), basic("^a$"));
+        assert_eq!(
+            Node::Concat(vec![Node::Group(Box::new(Node::Char('a')), 1), Node::Backreference(1)]),
+            basic("\\(a\\)\\1")
+        );
+        assert_eq!(
+            Node::Alternate(vec![Node::Repeat { node: Box::new(Node::Char('a')), min: 2, max: Some(3) }, Node::Repeat { node: Box::new(Node::Char('b')), min: 1, max: None }]),
+            basic("a\\{2,3\\}\\|b\\+")
+        );
+    }
+
+    #[test]
+    fn test_extended() {
+        assert_eq!(Node::Alternate(vec![Node::Char('a'), Node::Empty, Node::Char('b')]), extended("a||b"));
+        assert_eq!(Node::Concat(vec![Node::Char('a'), Node::Char('{'), Node::Char('1')]), extended("a{1"));
+        assert_eq!(Node::Repeat { node: Box::new(Node::Char('a')), min: 0, max: Some(2) }, extended("a{,2}"));
+        assert_eq!(Node::Concat(vec![Node::Char('x'), Node::Char(')')]), extended("x)"));
+        assert_eq!(Node::Concat(vec![Node::Char('*'), Node::Char('a')]), extended("*a"));
+        assert_eq!(
+            Node::Repeat { node: Box::new(Node::Repeat { node: Box::new(Node::Group(Box::new(Node::Any), 1)), min: 1, max: None }), min: 0, max: Some(1) },
+            extended("(.)+?")
+        );
+    }
+
+    #[test]
+    fn test_brackets() {
+        assert_eq!(Node::Bracket(Bracket { negated: true, items: vec![Item::Char(']'), Item::Range('a', 'c'), Item::Char('-')] }), basic("[^]a-c-]"));
+        assert_eq!(Node::Bracket(Bracket { negated: false, items: vec![Item::Class(Class::Digit), Item::Char('x'), Item::Char('\\')] }), basic("[[:digit:][=x=]\\]"));
+
+        let word = parse("\\w", Syntax::Basic).unwrap();
+        let Node::Bracket(bracket) = word else { panic!() };
+        assert!(bracket.matches('_', false) && bracket.matches('é', false) && !bracket.matches('-', false));
+        assert!(Bracket { negated: false, items: vec![Item::Range('a', 'z')] }.matches('Q', true));
+    }
+
+    #[test]
+    fn test_fixed() {
+        assert_eq!(Node::Concat(chars("a.*[")), parse("a.*[", Syntax::Fixed).unwrap());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!("grep: Unmatched [, [^, [:, [., or [=", error("[a", Syntax::Basic));
+        assert_eq!("grep: Unmatched ( or \\(", error("\\(a", Syntax::Basic));
+        assert_eq!("grep: Unmatched ( or \\(", error("(a", Syntax::Extended));
+        assert_eq!("grep: Unmatched ) or \\)", error("a\\)", Syntax::Basic));
+        assert_eq!("grep: Unmatched \\{", error("a\\{1", Syntax::Basic));
+        assert_eq!("grep: Invalid content of \\{\\}", error("a\\{2,1\\}", Syntax::Basic));
+        assert_eq!("grep: Invalid content of \\{\\}", error("a{2,1}", Syntax::Extended));
+        assert_eq!("grep: Regular expression too big", error("a{99999}", Syntax::Extended));
+        assert_eq!("grep: Invalid back reference", error("\\(a\\1\\)", Syntax::Basic));
+        assert_eq!("grep: Invalid back reference", error("a\\2", Syntax::Basic));
+        assert_eq!("grep: Invalid range end", error("[z-a]", Syntax::Basic));
+        assert_eq!("grep: Invalid character class name", error("[[:nope:]]", Syntax::Basic));
+        assert_eq!("grep: Trailing backslash", error("a\\", Syntax::Basic));
+        assert_eq!("grep: Invalid preceding regular expression", error("\\{1\\}", Syntax::Basic));
+        assert_eq!("grep: character class syntax is [[:space:]], not [:space:]", error("[:space:]", Syntax::Basic));
+    }
+}
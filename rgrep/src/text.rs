@@ -0,0 +1,103 @@
+use crate::syntax::{lower, Assertion};
+
+const MAX_UTF8_WIDTH: usize = 4;
+
+///
+/// The character starting at a byte position, and how many bytes it takes.  A byte that doesn't
+/// start valid UTF-8 is no character at all, but still takes one byte.
+///
+pub(crate) fn decode(text: &[u8], position: usize) -> (Option<char>, usize) {
+    let width = match text[position] {
+        0x00..=0x7f => return (Some(text[position] as char), 1),
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => return (None, 1),
+    };
+
+    match text.get(position..position + width).and_then(|bytes| std::str::from_utf8(bytes).ok()) {
+        Some(decoded) => (decoded.chars().next(), width),
+        None => (None, 1),
+    }
+}
+
+///
+/// The character ending at a byte position, if there is one.
+///
+pub(crate) fn previous(text: &[u8], position: usize) -> Option<char> {
+    (1..=MAX_UTF8_WIDTH.min(position))
+        .map(|width| position - width)
+        .find(|&start| text[start] & 0xc0 != 0x80)
+        .and_then(|start| match decode(text, start) {
+            (c, width) if start + width == position => c,
+            _ => None,
+        })
+}
+
+///
+/// Whether a character may be part of a word, for `-w`, `\<`, `\b` and friends.
+///
+pub(crate) fn is_word(c: Option<char>) -> bool {
+    c.is_some_and(|c| c.is_alphanumeric() || c == '_')
+}
+
+///
+/// Whether two characters are the same, perhaps ignoring case.
+///
+pub(crate) fn same(a: char, b: char, ignore_case: bool) -> bool {
+    a == b || (ignore_case && lower(a) == lower(b))
+}
+
+///
+/// Whether an assertion holds at a byte position in a line.
+///
+pub(crate) fn holds(assertion: Assertion, text: &[u8], position: usize) -> bool {
+    let before = || is_word(previous(text, position));
+    let after = || position < text.len() && is_word(decode(text, position).0);
+
+    match assertion {
+        Assertion::LineStart => position == 0,
+        Assertion::LineEnd => position == text.len(),
+        Assertion::WordStart => !before() && after(),
+        Assertion::WordEnd => before() && !after(),
+        Assertion::WordBoundary => before() != after(),
+        Assertion::NotWordBoundary => before() == after(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syntax::Assertion;
+    use crate::text::{decode, holds, previous};
+
+    #[test]
+    fn test_decode() {
+        let text = "aé€\u{1F600}".as_bytes();
+        assert_eq!((Some('a'), 1), decode(text, 0));
+        assert_eq!((Some('é'), 2), decode(text, 1));
+        assert_eq!((Some('€'), 3), decode(text, 3));
+        assert_eq!((Some('\u{1F600}'), 4), decode(text, 6));
+        assert_eq!((None, 1), decode(text, 2));
+        assert_eq!((None, 1), decode(b"\xff", 0));
+        assert_eq!((None, 1), decode(b"\xe2\x82", 0));
+    }
+
+    #[test]
+    fn test_previous() {
+        let text = "aé€".as_bytes();
+        assert_eq!(None, previous(text, 0));
+        assert_eq!(Some('a'), previous(text, 1));
+        assert_eq!(Some('é'), previous(text, 3));
+        assert_eq!(Some('€'), previous(text, 6));
+        assert_eq!(None, previous(b"a\xff", 2));
+    }
+
+    #[test]
+    fn test_holds() {
+        let text = b"foo bar";
+        assert!(holds(Assertion::WordStart, text, 4) && !holds(Assertion::WordStart, text, 5));
+        assert!(holds(Assertion::WordEnd, text, 3) && holds(Assertion::WordEnd, text, 7));
+        assert!(holds(Assertion::WordBoundary, text, 0) && holds(Assertion::NotWordBoundary, text, 1));
+        assert!(holds(Assertion::LineStart, text, 0) && holds(Assertion::LineEnd, text, 7));
+    }
+}
@@ -0,0 +1,269 @@
+use std::ffi::CString;
+use std::fs::{self, File, Metadata};
+use std::io::{self, Read};
+use std::ops::ControlFlow;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use common::input::known_error::KnownError;
+use crate::config::{Config, Recursion};
+use crate::errors::{directory_loop, file_error, is_a_directory};
+
+const STDIN_OPERAND: &str = "-";
+const STDIN_NAME: &str = "(standard input)";
+const CURRENT_DIRECTORY: &str = ".";
+
+///
+/// Something to search.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Input {
+    Stdin,
+    /// A file, with the name it's reported under, and whether it was found inside a directory.
+    File { path: PathBuf, name: String, nested: bool },
+}
+
+impl Input {
+    pub(crate) fn name(&self) -> &str {
+        match self {
+            Input::Stdin => STDIN_NAME,
+            Input::File { name, .. } => name,
+        }
+    }
+
+    pub(crate) fn nested(&self) -> bool {
+        matches!(self, Input::File { nested: true, .. })
+    }
+
+    pub(crate) fn open(&self) -> io::Result<Box<dyn Read>> {
+        match self {
+            Input::Stdin => Ok(Box::new(io::stdin())),
+            Input::File { path, .. } => Ok(Box::new(File::open(path)?)),
+        }
+    }
+}
+
+///
+/// Hand each input the operands name to the visitor, in order, along with problems finding them.
+/// Directories are searched when recursing; with no operands, that's the current directory,
+/// whose files are named without a leading `./`.
+///
+pub(crate) fn walk(config: &Config, visit: &mut dyn FnMut(Result<Input, KnownError>) -> ControlFlow<()>) -> ControlFlow<()> {
+    if config.files.is_empty() {
+        return match config.recursion {
+            Recursion::None => visit(Ok(Input::Stdin)),
+            _ => directory(config, Path::new(CURRENT_DIRECTORY), "", &mut vec![], visit),
+        };
+    }
+
+    for operand in config.files.iter() {
+        walk_operand(config, operand, visit)?;
+    }
+    ControlFlow::Continue(())
+}
+
+fn walk_operand(config: &Config, operand: &str, visit: &mut dyn FnMut(Result<Input, KnownError>) -> ControlFlow<()>) -> ControlFlow<()> {
+    if operand == STDIN_OPERAND {
+        return visit(Ok(Input::Stdin));
+    }
+
+    /* links named on the command line are always followed */
+    let metadata = match fs::metadata(operand) {
+        Ok(metadata) => metadata,
+        Err(error) => return visit(Err(file_error(operand, &error))),
+    };
+
+    if metadata.is_dir() {
+        if config.recursion == Recursion::None {
+            return visit(Err(is_a_directory(operand)));
+        }
+        if suffixes(operand).any(|suffix| matches_any(&config.exclude_dir, suffix.as_bytes())) {
+            return ControlFlow::Continue(());
+        }
+        return directory(config, Path::new(operand), operand, &mut vec![identity(&metadata)], visit);
+    }
+
+    if suffixes(operand).any(|suffix| skipped(config, suffix.as_bytes())) {
+        return ControlFlow::Continue(());
+    }
+    visit(Ok(Input::File { path: PathBuf::from(operand), name: operand.to_string(), nested: false }))
+}
+
+///
+/// Search a directory's entries in name order, recursing into subdirectories.  `ancestors`
+/// identifies the directories being searched, so that following a link can't go round forever.
+///
+fn directory(config: &Config, path: &Path, name: &str, ancestors: &mut Vec<(u64, u64)>, visit: &mut dyn FnMut(Result<Input, KnownError>) -> ControlFlow<()>) -> ControlFlow<()> {
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(error) => return visit(Err(file_error(display_name(name), &error))),
+    };
+    let mut entries: Vec<fs::DirEntry> = entries.filter_map(Result::ok).collect();
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let base = entry.file_name();
+        let child_name = join(name, &base.to_string_lossy());
+        let metadata = match entry.file_type() {
+            Ok(file_type) if file_type.is_symlink() && config.recursion != Recursion::Logical => continue,
+            Ok(file_type) if file_type.is_symlink() => fs::metadata(entry.path()),
+            Ok(_) => entry.metadata(),
+            Err(error) => Err(error),
+        };
+        let metadata = match metadata {
+            Ok(metadata) => metadata,
+            Err(error) => {
+                visit(Err(file_error(&child_name, &error)))?;
+                continue;
+            }
+        };
+
+        if metadata.is_dir() {
+            if matches_any(&config.exclude_dir, base.as_bytes()) {
+                continue;
+            }
+            let id = identity(&metadata);
+            if ancestors.contains(&id) {
+                visit(Err(directory_loop(&child_name)))?;
+                continue;
+            }
+            ancestors.push(id);
+            let flow = directory(config, &entry.path(), &child_name, ancestors, visit);
+            ancestors.pop();
+            flow?;
+        } else if metadata.is_file() && !skipped(config, base.as_bytes()) {
+            /* devices, pipes and sockets are left alone when recursing */
+            visit(Ok(Input::File { path: entry.path(), name: child_name, nested: true }))?;
+        }
+    }
+    ControlFlow::Continue(())
+}
+
+fn identity(metadata: &Metadata) -> (u64, u64) {
+    (metadata.dev(), metadata.ino())
+}
+
+fn display_name(name: &str) -> &str {
+    if name.is_empty() { CURRENT_DIRECTORY } else { name }
+}
+
+fn join(directory: &str, name: &str) -> String {
+    if directory.is_empty() {
+        name.to_string()
+    } else if directory.ends_with('/') {
+        format!("{}{}", directory, name)
+    } else {
+        format!("{}/{}", directory, name)
+    }
+}
+
+///
+/// A command line name and each trailing part of it that follows a slash, which is what
+/// `--include`, `--exclude` and `--exclude-dir` are matched against.
+///
+fn suffixes(name: &str) -> impl Iterator<Item = &str> {
+    std::iter::once(name).chain(name.match_indices('/').map(move |(index, _)| &name[index + 1..]).filter(|suffix| !suffix.is_empty() && !suffix.starts_with('/')))
+}
+
+///
+/// Whether `--include` and `--exclude` leave a file out.
+///
+fn skipped(config: &Config, name: &[u8]) -> bool {
+    matches_any(&config.exclude, name) || (!config.include.is_empty() && !matches_any(&config.include, name))
+}
+
+fn matches_any(globs: &[String], name: &[u8]) -> bool {
+    globs.iter().any(|glob| glob_matches(glob, name))
+}
+
+fn glob_matches(glob: &str, name: &[u8]) -> bool {
+    let (Ok(glob), Ok(name)) = (CString::new(glob), CString::new(name)) else {
+        return false;
+    };
+    /* SAFETY: both strings are NUL terminated and outlive the call */
+    unsafe { libc::fnmatch(glob.as_ptr(), name.as_ptr(), 0) == 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::ops::ControlFlow;
+    use std::os::unix::fs::symlink;
+    use common::test_support::TempDir;
+    use crate::config::Config;
+    use crate::walk::{glob_matches, suffixes, walk, Input};
+
+    fn names(line: &str) -> (Vec<String>, Vec<String>) {
+        let args: Vec<String> = line.split_whitespace().map(|s| s.to_string()).collect();
+        let config = Config::new(&args);
+        let mut names = vec![];
+        let mut errors = vec![];
+        let _ = walk(&config, &mut |found| {
+            match found {
+                Ok(input) => names.push(format!("{}{}", input.name(), if input.nested() { "" } else { " (operand)" })),
+                Err(error) => errors.push(error.message.unwrap()),
+            }
+            ControlFlow::Continue(())
+        });
+        (names, errors)
+    }
+
+    fn tree() -> TempDir {
+        let dir = TempDir::new("tree");
+        dir.file("b.txt", b"x");
+        dir.file("a.rs", b"x");
+        fs::create_dir_all(dir.join("sub/skip")).unwrap();
+        dir.file("sub/c.txt", b"x");
+        dir.file("sub/skip/d.txt", b"x");
+        dir
+    }
+
+    #[test]
+    fn test_operands() {
+        let dir = tree();
+        let root = dir.path();
+        assert_eq!(vec!["(standard input) (operand)"], names("grep x").0);
+        assert_eq!(vec![format!("{}/a.rs (operand)", root), "(standard input) (operand)".to_string()], names(&format!("grep x {}/a.rs -", root)).0);
+        assert_eq!(vec![format!("grep: {}/sub: Is a directory", root)], names(&format!("grep x {}/sub", root)).1);
+        assert_eq!(vec![format!("grep: {}/nope: No such file or directory", root)], names(&format!("grep x {}/nope", root)).1);
+    }
+
+    #[test]
+    fn test_recursion() {
+        let dir = tree();
+        let root = dir.path();
+        assert_eq!(
+            vec![format!("{}/a.rs", root), format!("{}/b.txt", root), format!("{}/sub/c.txt", root), format!("{}/sub/skip/d.txt", root)],
+            names(&format!("grep -r x {}", root)).0
+        );
+        assert_eq!(vec![format!("{}/sub/c.txt", root)], names(&format!("grep -r x {}/sub/ --exclude-dir=skip", root)).0);
+        assert_eq!(vec![format!("{}/b.txt", root), format!("{}/sub/c.txt", root)], names(&format!("grep -r --include=*.txt --exclude=d* x {}", root)).0);
+        assert!(names(&format!("grep --exclude=*.rs x {}/a.rs", root)).0.is_empty());
+    }
+
+    #[test]
+    fn test_links() {
+        let dir = tree();
+        let root = dir.path();
+        symlink(dir.join("b.txt"), dir.join("sub/link.txt")).unwrap();
+        symlink(dir.join("sub"), dir.join("sub/skip/loop")).unwrap();
+
+        assert_eq!(4, names(&format!("grep -r x {}", root)).0.len());
+        let (followed, errors) = names(&format!("grep -R x {}", root));
+        assert_eq!(5, followed.len());
+        assert_eq!(vec![format!("grep: {}/sub/skip/loop: warning: recursive directory loop", root)], errors);
+    }
+
+    #[test]
+    fn test_globs() {
+        assert!(glob_matches("*.txt", b"a.txt") && !glob_matches("*.txt", b"a.rs"));
+        assert!(glob_matches("[ab]?", b"bc"));
+        assert_eq!(vec!["a/b//c", "b//c", "c"], suffixes("a/b//c").collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_input() {
+        assert_eq!("(standard input)", Input::Stdin.name());
+        assert!(!Input::Stdin.nested());
+    }
+}
@@ -0,0 +1,216 @@
+use common::fs::backup::{backup_suffix, backup_type, Backup};
+use common::input::flags::flags_options::{parse_options, OptionSpec, ParsedArgument};
+use common::input::known_error::KnownError;
+use crate::errors::{backup_error, both_target_options, extra_operand, missing_destination, missing_file_operand, option_error,
+                    relative_without_symbolic};
+
+const OPTIONS: [OptionSpec; 14] = [
+    OptionSpec::optional("backup", Some('b'), Some("backup")),
+    OptionSpec::flag("force", Some('f'), Some("force")),
+    OptionSpec::flag("interactive", Some('i'), Some("interactive")),
+    OptionSpec::flag("logical", Some('L'), Some("logical")),
+    OptionSpec::flag("no-dereference", Some('n'), Some("no-dereference")),
+    OptionSpec::flag("physical", Some('P'), Some("physical")),
+    OptionSpec::flag("relative", Some('r'), Some("relative")),
+    OptionSpec::flag("symbolic", Some('s'), Some("symbolic")),
+    OptionSpec::required("suffix", Some('S'), Some("suffix")),
+    OptionSpec::required("target-directory", Some('t'), Some("target-directory")),
+    OptionSpec::flag("no-target-directory", Some('T'), Some("no-target-directory")),
+    OptionSpec::flag("verbose", Some('v'), Some("verbose")),
+    OptionSpec::flag("help", None, Some("help")),
+    OptionSpec::flag("version", None, Some("version")),
+];
+
+///
+/// What's done when a link's name is taken already; of `-f` and `-i`, the last one given wins.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Replace {
+    /// Nothing; the link isn't made.
+    Never,
+    /// What's there is replaced; `-f`.
+    Force,
+    /// The user is asked first; `-i`.
+    Ask,
+}
+
+///
+/// Storage for the application configuration.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct Config {
+    pub(crate) print_help_and_exit: bool,
+    pub(crate) print_version_and_exit: bool,
+
+    pub(crate) symbolic: bool,
+    /* symbolic links hold the way to their target from where they are, rather than what was given */
+    pub(crate) relative: bool,
+    /* hard links are made to what a symbolic link target points to, rather than the link; `-L` */
+    pub(crate) logical: bool,
+    /* a last operand that's a symbolic link to a directory is a name to replace, not a directory; `-n` */
+    pub(crate) no_dereference: bool,
+    pub(crate) replace: Replace,
+    pub(crate) backup: Backup,
+    pub(crate) suffix: String,
+    pub(crate) target_directory: Option<String>,
+    pub(crate) no_target_directory: bool,
+    pub(crate) verbose: bool,
+
+    pub(crate) files: Vec<String>,
+    pub(crate) errors: Vec<KnownError>,
+}
+
+impl Default for Config {
+    ///
+    /// Generate [Default] configuration.
+    ///
+    fn default() -> Self {
+        Config {
+            print_help_and_exit: false,
+            print_version_and_exit: false,
+
+            symbolic: false,
+            relative: false,
+            logical: false,
+            no_dereference: false,
+            replace: Replace::Never,
+            backup: Backup::None,
+            suffix: String::new(),
+            target_directory: None,
+            no_target_directory: false,
+            verbose: false,
+
+            files: vec![],
+            errors: vec![],
+        }
+    }
+}
+
+///
+/// Options that are settled only once all of them have been seen.
+///
+#[derive(Default)]
+struct Selection {
+    /* whether backups were asked for, and with which control, if any */
+    backup: Option<Option<String>>,
+    suffix: Option<String>,
+}
+
+impl Config {
+    ///
+    /// Initialize the application configuration based on provided application arguments; the first
+    /// argument is the program name.
+    ///
+    pub(crate) fn new(args: &[String]) -> Config {
+        let mut config = Self::default();
+
+        let parsed = match parse_options(args.get(1..).unwrap_or_default(), &OPTIONS) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                config.errors.push(option_error(&error));
+                return config;
+            }
+        };
+
+        let mut selection = Selection::default();
+        for argument in parsed {
+            match argument {
+                ParsedArgument::Operand(operand) => config.files.push(operand),
+                ParsedArgument::Option { id, value } => config.apply(&mut selection, id, value),
+            }
+        }
+        if config.print_help_and_exit || config.print_version_and_exit {
+            return config;
+        }
+
+        if config.relative && !config.symbolic {
+            config.errors.push(relative_without_symbolic());
+            return config;
+        }
+
+        /* a suffix alone asks for backups, of the type VERSION_CONTROL picks */
+        if let Some(control) = selection.backup.or(selection.suffix.as_ref().map(|_| None)) {
+            config.backup = match backup_type(control.as_deref()) {
+                Ok(backup) => backup,
+                Err(error) => {
+                    config.errors.push(backup_error(&error));
+                    return config;
+                }
+            };
+        }
+        config.suffix = backup_suffix(selection.suffix.as_deref());
+
+        if config.target_directory.is_some() && config.no_target_directory {
+            config.errors.push(both_target_options());
+        } else if config.files.is_empty() {
+            config.errors.push(missing_file_operand());
+        } else if config.no_target_directory && config.files.len() == 1 {
+            config.errors.push(missing_destination(&config.files[0]));
+        } else if config.no_target_directory && config.files.len() > 2 {
+            config.errors.push(extra_operand(&config.files[2]));
+        }
+        config
+    }
+
+    fn apply(&mut self, selection: &mut Selection, id: &str, value: Option<String>) {
+        match id {
+            "backup" => selection.backup = Some(value),
+            "force" => self.replace = Replace::Force,
+            "interactive" => self.replace = Replace::Ask,
+            "logical" => self.logical = true,
+            "no-dereference" => self.no_dereference = true,
+            "physical" => self.logical = false,
+            "relative" => self.relative = true,
+            "symbolic" => self.symbolic = true,
+            "suffix" => selection.suffix = value,
+            "target-directory" => self.target_directory = value,
+            "no-target-directory" => self.no_target_directory = true,
+            "verbose" => self.verbose = true,
+            "help" => self.print_help_and_exit = true,
+            "version" => self.print_version_and_exit = true,
+            _ => unreachable!("option {} has no handler", id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::fs::backup::Backup;
+    use crate::config::{Config, Replace};
+
+    fn config(args: &[&str]) -> Config {
+        let args: Vec<String> = std::iter::once("ln").chain(args.iter().copied()).map(String::from).collect();
+        Config::new(&args)
+    }
+
+    fn message(args: &[&str]) -> String {
+        config(args).errors.first().and_then(|error| error.message.clone()).unwrap_or_default()
+    }
+
+    #[test]
+    fn test_options() {
+        assert_eq!(Replace::Never, config(&["a", "b"]).replace);
+        assert_eq!(Replace::Ask, config(&["-fi", "a", "b"]).replace);
+        assert_eq!(Replace::Force, config(&["-i", "--force", "a", "b"]).replace);
+        assert!(config(&["-PL", "a", "b"]).logical);
+        assert!(!config(&["-LP", "a", "b"]).logical);
+        assert_eq!(Backup::Numbered, config(&["--backup=t", "a", "b"]).backup);
+        let suffixed = config(&["-S", ".orig", "--backup=simple", "a", "b"]);
+        assert_eq!((Backup::Simple, ".orig"), (suffixed.backup, suffixed.suffix.as_str()));
+        assert_ne!(Backup::None, config(&["-S", ".orig", "a", "b"]).backup);
+    }
+
+    #[test]
+    fn test_errors() {
+        let try_help = "Try 'ln --help' for more information.";
+        assert_eq!("ln: cannot do --relative without --symbolic", message(&["-r", "a", "b"]));
+        assert_eq!(format!("ln: missing file operand\n{}", try_help), message(&[]));
+        assert_eq!(format!("ln: missing file operand\n{}", try_help), message(&["-t", "d"]));
+        assert_eq!(format!("ln: missing destination file operand after 'a'\n{}", try_help), message(&["-T", "a"]));
+        assert_eq!(format!("ln: extra operand 'c'\n{}", try_help), message(&["-T", "a", "b", "c"]));
+        assert_eq!("ln: cannot combine --target-directory and --no-target-directory", message(&["-T", "-t", "d", "a"]));
+        assert!(message(&["--backup=bogus", "a", "b"]).starts_with("ln: invalid argument 'bogus' for 'backup type'\nValid arguments are:\n"));
+        assert!(config(&["a"]).errors.is_empty());
+        assert!(config(&["-sr", "-t", "d", "a", "b"]).errors.is_empty());
+    }
+}
@@ -0,0 +1,138 @@
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use common::fs::backup::BackupError;
+use common::input::flags::flags_options::OptionError;
+use common::input::known_error::KnownError;
+use common::input::source::describe_io_error;
+use common::output::quote::{quote_name, quote_path, shell_escape};
+
+const ERROR_CODE: i32 = 1;
+const PROGRAM: &str = "ln";
+
+///
+/// The command line couldn't be split into options and operands.
+///
+pub(crate) fn option_error(error: &OptionError) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}\n{}", error.message(PROGRAM), try_help()))
+}
+
+///
+/// The backup type, from `--backup` or `VERSION_CONTROL`, isn't one of the names.
+///
+pub(crate) fn backup_error(error: &BackupError) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}\n{}", error.message(PROGRAM), try_help()))
+}
+
+///
+/// `-r` makes the name a symbolic link holds relative; hard links hold no name.
+///
+pub(crate) fn relative_without_symbolic() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot do --relative without --symbolic", PROGRAM))
+}
+
+pub(crate) fn both_target_options() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot combine --target-directory and --no-target-directory", PROGRAM))
+}
+
+pub(crate) fn missing_file_operand() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: missing file operand\n{}", PROGRAM, try_help()))
+}
+
+pub(crate) fn missing_destination(target: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: missing destination file operand after {}\n{}", PROGRAM, quote_name(target.as_bytes()), try_help()))
+}
+
+///
+/// `-T` takes exactly one target and one link name.
+///
+pub(crate) fn extra_operand(operand: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: extra operand {}\n{}", PROGRAM, quote_name(operand.as_bytes()), try_help()))
+}
+
+///
+/// The last operand, with more than one target before it, isn't a directory to put links in.
+///
+pub(crate) fn target_error(target: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: target {}: {}", PROGRAM, quote_path(target), describe_io_error(error)))
+}
+
+///
+/// The directory given with `-t` isn't one.
+///
+pub(crate) fn target_not_directory(target: &Path) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: target {} is not a directory", PROGRAM, quote_path(target)))
+}
+
+pub(crate) fn failed_to_access(name: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: failed to access {}: {}", PROGRAM, quote_path(name), describe_io_error(error)))
+}
+
+pub(crate) fn hard_link_directory(target: &Path) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: {}: hard link not allowed for directory", PROGRAM, shell_escape(target.as_os_str().as_bytes())))
+}
+
+pub(crate) fn cannot_overwrite_directory(name: &Path) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: {}: cannot overwrite directory", PROGRAM, shell_escape(name.as_os_str().as_bytes())))
+}
+
+///
+/// A link made to one target was about to be replaced with a link to another, named the same.
+///
+pub(crate) fn just_created(name: &Path, target: &Path) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: will not overwrite just-created {} with {}", PROGRAM, quote_path(name), quote_path(target)))
+}
+
+pub(crate) fn same_file(target: &Path, name: &Path) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: {} and {} are the same file", PROGRAM, quote_path(target), quote_path(name)))
+}
+
+pub(crate) fn cannot_backup(name: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot backup {}: {}", PROGRAM, quote_path(name), describe_io_error(error)))
+}
+
+///
+/// A symbolic link couldn't be made; what it would have held is told too when that's likely the
+/// trouble.
+///
+pub(crate) fn cannot_create_symbolic_link(name: &Path, target: &Path, error: &io::Error) -> KnownError {
+    let link = if error.raw_os_error() == Some(libc::ENAMETOOLONG) || target.as_os_str().is_empty() {
+        format!("{} -> {}", quote_path(name), quote_path(target))
+    } else {
+        quote_path(name)
+    };
+    KnownError::new(ERROR_CODE, format!("{}: failed to create symbolic link {}: {}", PROGRAM, link, describe_io_error(error)))
+}
+
+///
+/// A hard link couldn't be made; the error says which of the two names to tell.
+///
+pub(crate) fn cannot_create_hard_link(name: &Path, target: &Path, error: &io::Error) -> KnownError {
+    let link = match error.raw_os_error() {
+        /* the target has as many links as it can */
+        Some(libc::EMLINK) => format!("to {}", quote_path(target)),
+        Some(libc::EDQUOT | libc::EEXIST | libc::ENOSPC | libc::EROFS) => quote_path(name),
+        _ => format!("{} => {}", quote_path(name), quote_path(target)),
+    };
+    KnownError::new(ERROR_CODE, format!("{}: failed to create hard link {}: {}", PROGRAM, link, describe_io_error(error)))
+}
+
+///
+/// The question `-i` asks before replacing a file.
+///
+pub(crate) fn replace_prompt(name: &Path) -> String {
+    format!("{}: replace {}? ", PROGRAM, quote_path(name))
+}
+
+///
+/// What `-v` says about a link: `=>` for a hard link and `->` for a symbolic one, after the backup
+/// made of what was there, if one was.
+///
+pub(crate) fn linked(backup: Option<&Path>, name: &Path, target: &Path, symbolic: bool) -> String {
+    let backup = backup.map(|backup| format!("{} ~ ", quote_path(backup))).unwrap_or_default();
+    format!("{}{} {} {}", backup, quote_path(name), if symbolic { "->" } else { "=>" }, quote_path(target))
+}
+
+fn try_help() -> String {
+    format!("Try '{} --help' for more information.", PROGRAM)
+}
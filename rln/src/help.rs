@@ -0,0 +1,55 @@
+use std::io::{self, Write};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub(crate) fn version(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "rln {}", VERSION)
+}
+
+pub(crate) fn help(out: &mut dyn Write) -> io::Result<()> {
+    version(out)?;
+    writeln!(out)?;
+    writeln!(out, "Usage: ln [OPTION]... [-T] TARGET LINK_NAME")?;
+    writeln!(out, "  or:  ln [OPTION]... TARGET")?;
+    writeln!(out, "  or:  ln [OPTION]... TARGET... DIRECTORY")?;
+    writeln!(out, "  or:  ln [OPTION]... -t DIRECTORY TARGET...")?;
+    writeln!(out, "In the 1st form, create a link to TARGET with the name LINK_NAME.")?;
+    writeln!(out, "In the 2nd form, create a link to TARGET in the current directory.")?;
+    writeln!(out, "In the 3rd and 4th forms, create links to each TARGET in DIRECTORY.")?;
+    writeln!(out, "Create hard links by default, symbolic links with --symbolic.")?;
+    writeln!(out, "By default, each destination (name of new link) should not already exist.")?;
+    writeln!(out, "When creating hard links, each TARGET must exist.  Symbolic links")?;
+    writeln!(out, "can hold arbitrary text; if later resolved, a relative link is")?;
+    writeln!(out, "interpreted in relation to its parent directory.")?;
+    writeln!(out)?;
+    writeln!(out, "Mandatory arguments to long options are mandatory for short options too.")?;
+    writeln!(out, "      --backup[=CONTROL]      make a backup of each existing destination file")?;
+    writeln!(out, "  -b                          like --backup but does not accept an argument")?;
+    writeln!(out, "  -f, --force                 remove existing destination files")?;
+    writeln!(out, "  -i, --interactive           prompt whether to remove destinations")?;
+    writeln!(out, "  -L, --logical               dereference TARGETs that are symbolic links")?;
+    writeln!(out, "  -n, --no-dereference        treat LINK_NAME as a normal file if")?;
+    writeln!(out, "                                it is a symbolic link to a directory")?;
+    writeln!(out, "  -P, --physical              make hard links directly to symbolic links")?;
+    writeln!(out, "  -r, --relative              with -s, create links relative to link location")?;
+    writeln!(out, "  -s, --symbolic              make symbolic links instead of hard links")?;
+    writeln!(out, "  -S, --suffix=SUFFIX         override the usual backup suffix")?;
+    writeln!(out, "  -t, --target-directory=DIRECTORY  specify the DIRECTORY in which to create")?;
+    writeln!(out, "                                the links")?;
+    writeln!(out, "  -T, --no-target-directory   treat LINK_NAME as a normal file always")?;
+    writeln!(out, "  -v, --verbose               print name of each linked file")?;
+    writeln!(out, "      --help        display this help and exit")?;
+    writeln!(out, "      --version     output version information and exit")?;
+    writeln!(out)?;
+    writeln!(out, "The backup suffix is '~', unless set with --suffix or SIMPLE_BACKUP_SUFFIX.")?;
+    writeln!(out, "The version control method may be selected via the --backup option or through")?;
+    writeln!(out, "the VERSION_CONTROL environment variable.  Here are the values:")?;
+    writeln!(out)?;
+    writeln!(out, "  none, off       never make backups (even if --backup is given)")?;
+    writeln!(out, "  numbered, t     make numbered backups")?;
+    writeln!(out, "  existing, nil   numbered if numbered backups exist, simple otherwise")?;
+    writeln!(out, "  simple, never   always make simple backups")?;
+    writeln!(out)?;
+    writeln!(out, "Using -s ignores -L and -P.  Otherwise, the last option specified controls")?;
+    writeln!(out, "behavior when a TARGET is a symbolic link, defaulting to -P.")
+}
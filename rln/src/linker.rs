@@ -0,0 +1,248 @@
+use std::collections::HashSet;
+use std::ffi::CString;
+use std::fs::{self, Metadata};
+use std::io::{self, BufRead, ErrorKind, Write};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use common::fs::backup::{make_backup, Backup};
+use common::fs::path::{base_name, canonicalize_missing, dir_name, relative_path};
+use common::input::known_error::KnownError;
+use crate::config::{Config, Replace};
+use crate::errors::{cannot_backup, cannot_create_hard_link, cannot_create_symbolic_link, cannot_overwrite_directory, failed_to_access,
+                    hard_link_directory, just_created, linked, replace_prompt, same_file};
+
+/* a link that replaces a name is made beside it first, as `CuPIDN`, and renamed over it */
+const TEMPORARY_PREFIX: &str = "Cu";
+const TEMPORARY_ATTEMPTS: u32 = 100;
+
+///
+/// Makes links the way the options ask, reporting problems as they're met.
+///
+pub(crate) struct Linker<'a> {
+    config: &'a Config,
+    input: &'a mut dyn BufRead,
+    out: &'a mut dyn Write,
+    err: &'a mut dyn Write,
+    /* the hard links made so far, by name and file, when a later one mustn't replace them */
+    created: Option<HashSet<(PathBuf, u64, u64)>>,
+}
+
+impl<'a> Linker<'a> {
+    ///
+    /// With `guard`, a hard link isn't replaced by a later one, to another target, of the same
+    /// name; as with `ln -f a dir/a b/a dir`, which would otherwise lose the first.
+    ///
+    pub(crate) fn new(config: &'a Config, input: &'a mut dyn BufRead, out: &'a mut dyn Write, err: &'a mut dyn Write, guard: bool) -> Linker<'a> {
+        let created = if guard { Some(HashSet::new()) } else { None };
+        Linker { config, input, out, err, created }
+    }
+
+    ///
+    /// Make a link called `name` to `target`; returns whether it was made, or left be as asked.
+    ///
+    pub(crate) fn link_operand(&mut self, target: &Path, name: &Path) -> bool {
+        let config = self.config;
+        /* what a relative link holds depends on where it is, so it's worked out first */
+        let first = if config.relative { None } else { Some(self.make(target, name)) };
+        let made = matches!(first, Some(Ok(())));
+
+        let status = if !config.symbolic && (!made || self.created.is_some()) {
+            let status = if config.logical { fs::metadata(target) } else { fs::symlink_metadata(target) };
+            match status {
+                Ok(status) => Some(status),
+                Err(error) => return self.fail(failed_to_access(target, &error)),
+            }
+        } else {
+            None
+        };
+
+        let mut target = target.to_path_buf();
+        let mut backup = None;
+        if !made {
+            if status.as_ref().is_some_and(Metadata::is_dir) {
+                return self.fail(hard_link_directory(&target));
+            }
+            if config.relative {
+                target = relative_target(&target, name);
+            }
+
+            let mut force = config.replace != Replace::Never || config.backup != Backup::None;
+            if force {
+                match fs::symlink_metadata(name) {
+                    Err(error) if error.kind() == ErrorKind::NotFound => force = false,
+                    Err(error) => return self.fail(failed_to_access(name, &error)),
+                    Ok(existing) if existing.is_dir() => return self.fail(cannot_overwrite_directory(name)),
+                    Ok(existing) if self.created.as_ref().is_some_and(|created| created.contains(&(name.to_path_buf(), existing.dev(), existing.ino()))) => {
+                        return self.fail(just_created(name, &target));
+                    }
+                    Ok(existing) => {
+                        if self.same_entry(&target, status.as_ref(), &existing, name) {
+                            return self.fail(same_file(&target, name));
+                        }
+                    }
+                }
+            }
+            if force {
+                if config.replace == Replace::Ask && !self.ask(&replace_prompt(name)) {
+                    return true;
+                }
+                match make_backup(name, config.backup, &config.suffix) {
+                    Ok(made) => backup = made,
+                    Err(error) if error.kind() == ErrorKind::NotFound => force = false,
+                    Err(error) => return self.fail(cannot_backup(name, &error)),
+                }
+            }
+
+            let result = match first {
+                Some(result) => result,
+                None => self.make(&target, name),
+            };
+            let result = match result {
+                Err(error) if force && error.kind() == ErrorKind::AlreadyExists => self.replace(&target, name),
+                result => result,
+            };
+            if let Err(error) = result {
+                let error = if config.symbolic { cannot_create_symbolic_link(name, &target, &error) } else { cannot_create_hard_link(name, &target, &error) };
+                return self.fail(error);
+            }
+        }
+
+        if let Some(created) = &mut self.created
+            && let Some(status) = &status {
+            created.insert((name.to_path_buf(), status.dev(), status.ino()));
+        }
+        if config.verbose {
+            let _ = writeln!(self.out, "{}", linked(backup.as_deref(), name, &target, config.symbolic));
+        }
+        true
+    }
+
+    fn make(&self, target: &Path, name: &Path) -> io::Result<()> {
+        if self.config.symbolic {
+            std::os::unix::fs::symlink(target, name)
+        } else {
+            hard_link(target, name, self.config.logical)
+        }
+    }
+
+    ///
+    /// Make the link beside `name` and rename it over it, so there's never a moment without one.
+    ///
+    fn replace(&self, target: &Path, name: &Path) -> io::Result<()> {
+        let directory = dir_name(name);
+        for attempt in 0..TEMPORARY_ATTEMPTS {
+            let temporary = directory.join(format!("{}{}{}", TEMPORARY_PREFIX, std::process::id(), attempt));
+            match self.make(target, &temporary) {
+                Ok(()) => {}
+                Err(error) if error.kind() == ErrorKind::AlreadyExists => continue,
+                Err(error) => return Err(error),
+            }
+            let result = fs::rename(&temporary, name);
+            /* a rename between two links to the same file does nothing, so the temporary may be left */
+            let _ = fs::remove_file(&temporary);
+            return result;
+        }
+        Err(io::Error::from(ErrorKind::AlreadyExists))
+    }
+
+    ///
+    /// Whether removing `name` would take `target` with it, so the link couldn't be made after; a
+    /// self-referential symbolic link is allowed when there's a backup of what it replaces.
+    ///
+    fn same_entry(&self, target: &Path, status: Option<&Metadata>, existing: &Metadata, name: &Path) -> bool {
+        let config = self.config;
+        let removes = config.replace == Replace::Force || (!config.symbolic && config.backup != Backup::None);
+        if !removes || (config.symbolic && config.backup != Backup::None) {
+            return false;
+        }
+        let status = if config.symbolic { fs::metadata(target).ok() } else { status.cloned() };
+        status.is_some_and(|status| {
+            status.dev() == existing.dev() && status.ino() == existing.ino() && (status.nlink() == 1 || same_name(target, name))
+        })
+    }
+
+    fn ask(&mut self, question: &str) -> bool {
+        let _ = write!(self.err, "{}", question);
+        let _ = self.err.flush();
+        let mut answer = String::new();
+        if self.input.read_line(&mut answer).is_err() {
+            return false;
+        }
+        answer.starts_with(['y', 'Y'])
+    }
+
+    fn fail(&mut self, error: KnownError) -> bool {
+        if let Some(message) = &error.message {
+            let _ = writeln!(self.err, "{}", message);
+        }
+        false
+    }
+}
+
+///
+/// Make a hard link; `follow` links to what a symbolic link target points to, rather than to it.
+///
+fn hard_link(target: &Path, name: &Path, follow: bool) -> io::Result<()> {
+    let c_path = |path: &Path| CString::new(path.as_os_str().as_bytes()).map_err(|_| io::Error::from(ErrorKind::InvalidInput));
+    let (target, name) = (c_path(target)?, c_path(name)?);
+    let flags = if follow { libc::AT_SYMLINK_FOLLOW } else { 0 };
+    /* SAFETY: linkat only reads the names */
+    if unsafe { libc::linkat(libc::AT_FDCWD, target.as_ptr(), libc::AT_FDCWD, name.as_ptr(), flags) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+///
+/// What a symbolic link called `name` holds to get to `target` from where it is, with every
+/// symbolic link along both ways followed; the target as it's given when that can't be worked out.
+///
+fn relative_target(target: &Path, name: &Path) -> PathBuf {
+    match (canonicalize_missing(target), canonicalize_missing(dir_name(name))) {
+        (Ok(target), Ok(directory)) => relative_path(&target, &directory),
+        _ => target.to_path_buf(),
+    }
+}
+
+///
+/// Whether two names are the same entry of the same directory.
+///
+fn same_name(a: &Path, b: &Path) -> bool {
+    if base_name(a) != base_name(b) {
+        return false;
+    }
+    match (fs::metadata(dir_name(a)), fs::metadata(dir_name(b))) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use common::test_support::TempDir;
+    use crate::linker::{relative_target, same_name};
+
+    #[test]
+    fn test_relative_target() {
+        let dir = TempDir::new("relative");
+        std::fs::create_dir_all(dir.join("d/sub")).unwrap();
+        std::os::unix::fs::symlink("d/sub", dir.join("link")).unwrap();
+        let name = |name: &str| dir.join(name);
+
+        assert_eq!(Path::new("../../a"), relative_target(&name("a"), &name("d/sub/rel")));
+        assert_eq!(Path::new("../../../a"), relative_target(&name("d/sub/../../a"), &name("link/x/rel")));
+        assert_eq!(Path::new("sub"), relative_target(&name("link"), &name("d/rel")));
+        assert_eq!(Path::new("."), relative_target(&name("d"), &name("d/self")));
+    }
+
+    #[test]
+    fn test_same_name() {
+        let dir = TempDir::new("same");
+        std::fs::create_dir(dir.join("d")).unwrap();
+        assert!(same_name(&dir.join("d/../a"), &dir.join("a")));
+        assert!(!same_name(&dir.join("d/a"), &dir.join("a")));
+        assert!(!same_name(&dir.join("a"), &dir.join("b")));
+    }
+}
@@ -0,0 +1,226 @@
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use common::fs::backup::Backup;
+use common::fs::path::base_name;
+use common::input::known_error::{report, KnownError};
+use crate::config::{Config, Replace};
+use crate::errors::{failed_to_access, target_error, target_not_directory};
+use crate::help::{help, version};
+use crate::linker::Linker;
+
+const SUCCESS: i32 = 0;
+const FAILURE: i32 = 1;
+
+///
+/// Run `ln` with command line arguments; the first argument is the program name.  `input` answers
+/// the questions `-i` asks before replacing files.  Returns the exit status: 0 when every link was
+/// made, or left be as asked, and 1 otherwise.
+///
+pub(crate) fn ln(args: &[String], input: &mut dyn BufRead, out: &mut dyn Write, err: &mut dyn Write) -> i32 {
+    let config = Config::new(args);
+    if let Some(first) = config.errors.first() {
+        report(err, &config.errors);
+        return first.code;
+    }
+
+    if config.print_help_and_exit {
+        return help(out).map_or(FAILURE, |_| SUCCESS);
+    }
+    if config.print_version_and_exit {
+        return version(out).map_or(FAILURE, |_| SUCCESS);
+    }
+
+    let links = match links(&config) {
+        Ok(links) => links,
+        Err(error) => {
+            report(err, &[error]);
+            return FAILURE;
+        }
+    };
+
+    /* a forced hard link could replace one made moments before, unless numbered backups keep both */
+    let guard = links.len() > 1 && config.replace == Replace::Force && !config.symbolic && config.backup != Backup::Numbered;
+    let mut linker = Linker::new(&config, input, out, err, guard);
+    let mut success = true;
+    for (target, name) in links {
+        success &= linker.link_operand(&target, &name);
+    }
+    if success { SUCCESS } else { FAILURE }
+}
+
+///
+/// Each target with the name of its link: in the directory given with `-t`, or the last operand,
+/// when that's a directory and `-T` wasn't given; the last operand itself otherwise.  A target on
+/// its own is linked to from the current directory.
+///
+fn links(config: &Config) -> Result<Vec<(PathBuf, PathBuf)>, KnownError> {
+    let into = |directory: &Path, targets: &[String]| {
+        targets.iter().map(|target| (PathBuf::from(target), directory.join(base_name(Path::new(target))))).collect()
+    };
+
+    if let Some(directory) = &config.target_directory {
+        let directory = Path::new(directory);
+        return match fs::metadata(directory) {
+            Ok(metadata) if !metadata.is_dir() => Err(target_not_directory(directory)),
+            Ok(_) if config.no_dereference && fs::symlink_metadata(directory).is_ok_and(|metadata| metadata.is_symlink()) => {
+                Err(target_error(directory, &io::Error::from_raw_os_error(libc::ENOTDIR)))
+            }
+            Ok(_) => Ok(into(directory, &config.files)),
+            Err(error) => Err(failed_to_access(directory, &error)),
+        };
+    }
+
+    if let [target] = config.files.as_slice() {
+        return Ok(into(Path::new("."), std::slice::from_ref(target)));
+    }
+    let Some((name, targets)) = config.files.split_last() else {
+        return Ok(vec![]);
+    };
+    let name = Path::new(name);
+    let single = || vec![(PathBuf::from(&targets[0]), name.to_path_buf())];
+    if config.no_target_directory {
+        return Ok(single());
+    }
+    /* with -n, a symbolic link to a directory is a name to replace, not a place to put links */
+    let status = if config.no_dereference { fs::symlink_metadata(name) } else { fs::metadata(name) };
+    match status {
+        Ok(metadata) if metadata.is_dir() => Ok(into(name, targets)),
+        Ok(_) if targets.len() > 1 => Err(target_error(name, &io::Error::from_raw_os_error(libc::ENOTDIR))),
+        Err(error) if targets.len() > 1 => Err(target_error(name, &error)),
+        _ => Ok(single()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::os::unix::fs::MetadataExt;
+    use std::path::Path;
+    use common::test_support::TempDir;
+    use crate::ln::ln;
+
+    fn run(args: &[&str], input: &str) -> (i32, String, String) {
+        let args: Vec<String> = std::iter::once("ln").chain(args.iter().copied()).map(String::from).collect();
+        let mut out = vec![];
+        let mut err = vec![];
+        let status = ln(&args, &mut input.as_bytes(), &mut out, &mut err);
+        (status, String::from_utf8(out).unwrap(), String::from_utf8(err).unwrap())
+    }
+
+    fn inode(path: &Path) -> u64 {
+        fs::symlink_metadata(path).unwrap().ino()
+    }
+
+    #[test]
+    fn test_links() {
+        let dir = TempDir::new("links");
+        let a = dir.file("a", b"alpha");
+        let name = |name: &str| dir.join(name).to_str().unwrap().to_string();
+
+        assert_eq!((0, format!("'{}' => '{}'\n", name("h"), a), String::new()), run(&["-v", &a, &name("h")], ""));
+        assert_eq!(inode(&dir.join("a")), inode(&dir.join("h")));
+        assert_eq!((0, format!("'{}' -> '{}'\n", name("s"), a), String::new()), run(&["-sv", &a, &name("s")], ""));
+        assert_eq!(Path::new(&a), fs::read_link(dir.join("s")).unwrap());
+        assert_eq!((1, String::new(), format!("ln: failed to create hard link '{}': File exists\n", name("h"))), run(&[&a, &name("h")], ""));
+        assert_eq!((1, String::new(), format!("ln: failed to access '{}': No such file or directory\n", name("missing"))), run(&[&name("missing"), &name("m")], ""));
+
+        fs::create_dir(dir.join("d")).unwrap();
+        assert_eq!((1, String::new(), format!("ln: {}: hard link not allowed for directory\n", name("d"))), run(&[&name("d"), &name("hd")], ""));
+        assert_eq!((0, String::new(), String::new()), run(&[&a, &name("s"), &name("d")], ""));
+        assert_eq!(inode(&dir.join("a")), inode(&dir.join("d/a")));
+        assert!(fs::symlink_metadata(dir.join("d/s")).unwrap().is_symlink());
+        assert_eq!((1, String::new(), format!("ln: failed to create symbolic link '{}' -> '': No such file or directory\n", name("e"))), run(&["-s", "", &name("e")], ""));
+    }
+
+    #[test]
+    fn test_targets() {
+        let dir = TempDir::new("targets");
+        let a = dir.file("a", b"alpha");
+        let b = dir.file("b", b"beta");
+        fs::create_dir(dir.join("d")).unwrap();
+        std::os::unix::fs::symlink("d", dir.join("dl")).unwrap();
+        let name = |name: &str| dir.join(name).to_str().unwrap().to_string();
+
+        assert_eq!((1, String::new(), format!("ln: target '{}': No such file or directory\n", name("c"))), run(&[&a, &b, &name("c")], ""));
+        assert_eq!((1, String::new(), format!("ln: target '{}': Not a directory\n", b)), run(&[&a, &a, &b], ""));
+        assert_eq!((1, String::new(), format!("ln: target '{}' is not a directory\n", a)), run(&["-t", &a, &b], ""));
+        assert_eq!((1, String::new(), format!("ln: failed to access '{}': No such file or directory\n", name("c"))), run(&["-t", &name("c"), &b], ""));
+        assert_eq!((1, String::new(), format!("ln: target '{}': Not a directory\n", name("dl"))), run(&["-n", "-t", &name("dl"), &b], ""));
+
+        assert_eq!((0, String::new(), String::new()), run(&["-t", &name("dl"), &a, &b], ""));
+        assert!(dir.join("d/a").exists() && dir.join("d/b").exists());
+        assert_eq!((0, String::new(), String::new()), run(&["-sfn", &name("d"), &name("dl")], ""));
+        assert_eq!(dir.join("d"), fs::read_link(dir.join("dl")).unwrap());
+        assert_eq!((1, String::new(), format!("ln: failed to create hard link '{}': File exists\n", name("d"))), run(&["-T", &a, &name("d")], ""));
+    }
+
+    #[test]
+    fn test_relative() {
+        let dir = TempDir::new("relative");
+        let a = dir.file("a", b"alpha");
+        fs::create_dir_all(dir.join("d/sub")).unwrap();
+        let name = |name: &str| dir.join(name).to_str().unwrap().to_string();
+
+        assert_eq!((0, format!("'{}' -> '../../a'\n", name("d/sub/rel")), String::new()), run(&["-srv", &a, &name("d/sub/rel")], ""));
+        assert_eq!(b"alpha".to_vec(), fs::read(dir.join("d/sub/rel")).unwrap());
+        assert_eq!((0, String::new(), String::new()), run(&["-sr", &name("missing/x"), &name("d/m")], ""));
+        assert_eq!(Path::new("../missing/x"), fs::read_link(dir.join("d/m")).unwrap());
+        assert_eq!((0, String::new(), String::new()), run(&["-srf", &name("d"), &name("d/self")], ""));
+        assert_eq!(Path::new("."), fs::read_link(dir.join("d/self")).unwrap());
+        assert_eq!((1, String::new(), "ln: cannot do --relative without --symbolic\n".to_string()), run(&["-r", &a, &name("b")], ""));
+    }
+
+    #[test]
+    fn test_replace() {
+        let dir = TempDir::new("replace");
+        let a = dir.file("a", b"alpha");
+        let b = dir.file("b", b"beta");
+        fs::create_dir(dir.join("d")).unwrap();
+        let name = |name: &str| dir.join(name).to_str().unwrap().to_string();
+
+        assert_eq!((0, String::new(), String::new()), run(&["-f", &a, &b], ""));
+        assert_eq!(inode(&dir.join("a")), inode(&dir.join("b")));
+        assert_eq!((1, String::new(), format!("ln: '{}' and '{}' are the same file\n", a, a)), run(&["-f", &a, &a], ""));
+        assert_eq!((1, String::new(), format!("ln: {}: cannot overwrite directory\n", name("d"))), run(&["-sfT", &a, &name("d")], ""));
+        assert_eq!((1, String::new(), format!("ln: '{}' and '{}/d/../a' are the same file\n", a, dir.path())), run(&["-sf", &a, &name("d/..")], ""));
+
+        let c = dir.file("c", b"gamma");
+        assert_eq!((0, String::new(), format!("ln: replace '{}'? ", b)), run(&["-i", &c, &b], "n\n"));
+        assert_eq!(inode(&dir.join("a")), inode(&dir.join("b")));
+        assert_eq!((0, String::new(), format!("ln: replace '{}'? ", b)), run(&["-i", &c, &b], "y\n"));
+        assert_eq!(inode(&dir.join("c")), inode(&dir.join("b")));
+
+        fs::write(dir.join("d/a"), b"other").unwrap();
+        let created = format!("ln: will not overwrite just-created '{}' with '{}'\n", name("d/a"), name("d/a"));
+        assert_eq!((1, String::new(), created), run(&["-f", &a, &name("d/a"), &name("d")], ""));
+        assert_eq!(inode(&dir.join("a")), inode(&dir.join("d/a")));
+    }
+
+    #[test]
+    fn test_backup() {
+        let dir = TempDir::new("backup");
+        let a = dir.file("a", b"alpha");
+        dir.file("b", b"beta");
+        let name = |name: &str| dir.join(name).to_str().unwrap().to_string();
+
+        assert_eq!((0, format!("'{}' ~ '{}' => '{}'\n", name("b~"), name("b"), a), String::new()), run(&["-bv", &a, &name("b")], ""));
+        assert_eq!(b"beta".to_vec(), fs::read(dir.join("b~")).unwrap());
+        assert_eq!((0, format!("'{}' ~ '{}' -> '{}'\n", name("b.~1~"), name("b"), a), String::new()), run(&["-sv", "--backup=numbered", &a, &name("b")], ""));
+        assert_eq!(inode(&dir.join("a")), inode(&dir.join("b.~1~")));
+        assert_eq!((1, String::new(), format!("ln: '{}' and '{}' are the same file\n", a, a)), run(&["-b", &a, &a], ""));
+    }
+
+    #[test]
+    fn test_dereference() {
+        let dir = TempDir::new("dereference");
+        let a = dir.file("a", b"alpha");
+        std::os::unix::fs::symlink(&a, dir.join("s")).unwrap();
+        let name = |name: &str| dir.join(name).to_str().unwrap().to_string();
+
+        assert_eq!((0, String::new(), String::new()), run(&["-L", &name("s"), &name("logical")], ""));
+        assert_eq!(inode(&dir.join("a")), inode(&dir.join("logical")));
+        assert_eq!((0, String::new(), String::new()), run(&["-P", &name("s"), &name("physical")], ""));
+        assert_eq!(inode(&dir.join("s")), inode(&dir.join("physical")));
+    }
+}
@@ -0,0 +1,16 @@
+mod config;
+mod errors;
+mod help;
+mod linker;
+mod ln;
+
+use crate::ln::ln;
+
+///
+/// Main program entrypoint.
+///
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let status = ln(&args, &mut std::io::stdin().lock(), &mut std::io::stdout(), &mut std::io::stderr());
+    std::process::exit(status);
+}
@@ -0,0 +1,505 @@
+use std::fs::{FileType, Metadata};
+use std::os::unix::fs::{FileTypeExt, MetadataExt, PermissionsExt};
+use common::input::known_error::KnownError;
+use crate::entry::Entry;
+use crate::errors::{unparsable_colors, unrecognized_prefix};
+
+const TARGET: &[u8] = b"target";
+const DEFAULT_LEFT: &[u8] = b"\x1b[";
+const DEFAULT_RIGHT: &[u8] = b"m";
+/* terminals the built-in colors are used for when LS_COLORS isn't set; `*` matches anything */
+const KNOWN_TERMINALS: [&str; 24] = [
+    "Eterm", "ansi", "*color*", "con[0-9]*x[0-9]*", "cons25", "console", "cygwin", "*direct*", "dtterm", "gnome", "hurd", "jfbterm",
+    "konsole", "kterm", "linux", "linux-c", "mlterm", "putty*", "rxvt*", "screen*", "st", "terminator", "tmux*", "vt100",
+];
+const KNOWN_XTERMS: &str = "xterm*";
+
+const SETUID: u32 = 0o4000;
+const SETGID: u32 = 0o2000;
+const STICKY: u32 = 0o1000;
+const EXECUTABLE: u32 = 0o111;
+const OTHER_WRITABLE: u32 = 0o002;
+
+///
+/// The kinds of text `LS_COLORS` gives sequences for, in the order of [NAMES].  Doors and
+/// capabilities are named there too, but never looked for.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Indicator {
+    Left,
+    Right,
+    End,
+    Reset,
+    Normal,
+    File,
+    Directory,
+    Link,
+    Fifo,
+    Socket,
+    Block,
+    Character,
+    Missing,
+    Orphan,
+    Executable,
+    Setuid,
+    Setgid,
+    Sticky,
+    OtherWritable,
+    StickyOtherWritable,
+    MultipleLinks,
+    ClearToEnd,
+}
+
+const NAMES: [&str; 24] = ["lc", "rc", "ec", "rs", "no", "fi", "di", "ln", "pi", "so", "bd", "cd", "mi", "or", "ex", "su", "sg", "st", "ow", "tw", "mh", "cl", "do", "ca"];
+const DEFAULTS: [Option<&[u8]>; 24] = [
+    Some(DEFAULT_LEFT), Some(DEFAULT_RIGHT), None, Some(b"0"), None, None, Some(b"01;34"), Some(b"01;36"),
+    Some(b"33"), Some(b"01;35"), Some(b"01;33"), Some(b"01;33"), None, None, Some(b"01;32"),
+    Some(b"37;41"), Some(b"30;43"), Some(b"37;44"), Some(b"34;42"), Some(b"30;42"), None, Some(b"\x1b[K"), Some(b"01;35"), None,
+];
+
+///
+/// A sequence for names ending in a suffix.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Extension {
+    suffix: Vec<u8>,
+    sequence: Vec<u8>,
+    /* set when another entry has the same suffix in a different case */
+    exact_case: bool,
+}
+
+///
+/// The escape sequences names are colored with, from `LS_COLORS` or built in.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Colors {
+    indicators: Vec<Option<Vec<u8>>>,
+    extensions: Vec<Extension>,
+    /* `ln=target`: links are colored as what they lead to */
+    link_as_target: bool,
+}
+
+impl Colors {
+    ///
+    /// The colors to use, or [None] when names aren't colored after all: `LS_COLORS` couldn't be
+    /// read, or isn't set and the terminal isn't one known to take colors.
+    ///
+    pub(crate) fn from_environment(warnings: &mut Vec<KnownError>) -> Option<Colors> {
+        match std::env::var_os("LS_COLORS").filter(|value| !value.is_empty()) {
+            Some(value) => Colors::parse(value.as_encoded_bytes(), warnings),
+            None => {
+                let color_terminal = std::env::var_os("COLORTERM").is_some_and(|value| !value.is_empty());
+                let known = std::env::var("TERM").is_ok_and(|term| known_terminal(&term));
+                (color_terminal || known).then(Colors::built_in)
+            }
+        }
+    }
+
+    fn built_in() -> Colors {
+        Colors { indicators: DEFAULTS.iter().map(|sequence| sequence.map(<[u8]>::to_vec)).collect(), extensions: vec![], link_as_target: false }
+    }
+
+    ///
+    /// Read `LS_COLORS`: `:`-separated entries, each a two letter indicator or a `*SUFFIX`, `=`,
+    /// and a sequence.  Entries override the built-in colors; the first bad one spoils them all.
+    ///
+    fn parse(value: &[u8], warnings: &mut Vec<KnownError>) -> Option<Colors> {
+        let mut colors = Colors::built_in();
+        let mut position = 0;
+
+        while position < value.len() {
+            match value[position] {
+                b':' => position += 1,
+                b'*' => {
+                    position += 1;
+                    let suffix = funky_string(value, &mut position, true);
+                    if value.get(position) != Some(&b'=') {
+                        warnings.push(unparsable_colors());
+                        return None;
+                    }
+                    position += 1;
+                    let Some(suffix) = suffix else { warnings.push(unparsable_colors()); return None };
+                    let Some(sequence) = funky_string(value, &mut position, false) else { warnings.push(unparsable_colors()); return None };
+                    colors.extensions.push(Extension { suffix, sequence, exact_case: false });
+                }
+                _ => {
+                    if value.len() < position + 3 || value[position + 2] != b'=' {
+                        warnings.push(unparsable_colors());
+                        return None;
+                    }
+                    let label = &value[position..position + 2];
+                    position += 3;
+                    let Some(index) = NAMES.iter().position(|name| name.as_bytes() == label) else {
+                        warnings.push(unrecognized_prefix(&String::from_utf8_lossy(label)));
+                        warnings.push(unparsable_colors());
+                        return None;
+                    };
+                    let Some(sequence) = funky_string(value, &mut position, false) else { warnings.push(unparsable_colors()); return None };
+                    if index == Indicator::Link as usize && sequence == TARGET {
+                        colors.link_as_target = true;
+                    } else {
+                        colors.indicators[index] = Some(sequence);
+                    }
+                }
+            }
+        }
+
+        let extensions = colors.extensions.clone();
+        for extension in colors.extensions.iter_mut() {
+            extension.exact_case = extensions.iter()
+                .any(|other| other.suffix != extension.suffix && other.suffix.eq_ignore_ascii_case(&extension.suffix));
+        }
+        Some(colors)
+    }
+
+    ///
+    /// The sequence an entry's name is colored with, if any.
+    ///
+    pub(crate) fn name_sequence(&self, entry: &Entry) -> Option<&[u8]> {
+        let link = entry.link.as_ref();
+        let referent = link.and_then(|link| link.metadata.as_ref()).filter(|_| self.link_as_target);
+        let metadata = referent.or(entry.metadata.as_ref());
+        let indicator = match metadata {
+            Some(metadata) => self.classify(metadata),
+            None => type_indicator(entry.file_type),
+        };
+
+        if indicator == Indicator::Link && link.is_some_and(|link| link.metadata.is_none())
+            && (self.link_as_target || self.colored(Indicator::Orphan)) {
+            return self.sequence(Indicator::Orphan);
+        }
+        self.sequence_for(indicator, &entry.name, metadata.is_some())
+    }
+
+    ///
+    /// The sequence a symbolic link's target is colored with, in a long listing: what it leads to
+    /// decides, and a broken link's target is missing.
+    ///
+    pub(crate) fn target_sequence(&self, entry: &Entry) -> Option<&[u8]> {
+        let link = entry.link.as_ref()?;
+        let target = link.target.as_deref().unwrap_or_default();
+        match &link.metadata {
+            Some(metadata) => self.sequence_for(self.classify(metadata), target, true),
+            None if self.colored(Indicator::Missing) => self.sequence(Indicator::Missing),
+            None => self.sequence(Indicator::Orphan),
+        }
+    }
+
+    fn classify(&self, metadata: &Metadata) -> Indicator {
+        let file_type = metadata.file_type();
+        let mode = metadata.permissions().mode();
+        let picked = |candidates: &[(bool, Indicator)], fallback| {
+            candidates.iter().find(|(applies, indicator)| *applies && self.colored(*indicator)).map_or(fallback, |(_, indicator)| *indicator)
+        };
+
+        if file_type.is_file() {
+            picked(&[
+                (mode & SETUID != 0, Indicator::Setuid),
+                (mode & SETGID != 0, Indicator::Setgid),
+                (mode & EXECUTABLE != 0, Indicator::Executable),
+                (metadata.nlink() > 1, Indicator::MultipleLinks),
+            ], Indicator::File)
+        } else if file_type.is_dir() {
+            picked(&[
+                (mode & STICKY != 0 && mode & OTHER_WRITABLE != 0, Indicator::StickyOtherWritable),
+                (mode & OTHER_WRITABLE != 0, Indicator::OtherWritable),
+                (mode & STICKY != 0, Indicator::Sticky),
+            ], Indicator::Directory)
+        } else {
+            type_indicator(Some(file_type))
+        }
+    }
+
+    ///
+    /// The sequence for a kind of file; a plain file's name can pick one by its suffix instead.
+    /// The suffix isn't looked at for files that couldn't be looked at.
+    ///
+    fn sequence_for(&self, indicator: Indicator, name: &[u8], known: bool) -> Option<&[u8]> {
+        if indicator == Indicator::File && known
+            && let Some(extension) = self.extensions.iter().rev().find(|extension| extension.matches(name)) {
+            return Some(&extension.sequence);
+        }
+        self.sequence(indicator)
+    }
+
+    fn sequence(&self, indicator: Indicator) -> Option<&[u8]> {
+        self.indicators[indicator as usize].as_deref().filter(|_| self.colored(indicator))
+    }
+
+    ///
+    /// Whether an indicator is set to anything but nothing, or the plain `0`.
+    ///
+    fn colored(&self, indicator: Indicator) -> bool {
+        self.indicators[indicator as usize].as_deref().is_some_and(|sequence| !matches!(sequence, b"" | b"0" | b"00"))
+    }
+
+    ///
+    /// The text that starts a colored name.
+    ///
+    pub(crate) fn start(&self, sequence: &[u8]) -> Vec<u8> {
+        let mut start = vec![];
+        if self.colored(Indicator::Normal) {
+            start.extend(self.restore());
+        }
+        start.extend(self.raw(Indicator::Left));
+        start.extend(sequence);
+        start.extend(self.raw(Indicator::Right));
+        start
+    }
+
+    ///
+    /// The text that ends a colored name, and comes once before the first of them.
+    ///
+    pub(crate) fn end(&self) -> Vec<u8> {
+        match &self.indicators[Indicator::End as usize] {
+            Some(end) => end.clone(),
+            None => [self.raw(Indicator::Left), self.raw(Indicator::Reset), self.raw(Indicator::Right)].concat(),
+        }
+    }
+
+    ///
+    /// The text that sets the color for what isn't a name, when `no` asks for one.
+    ///
+    pub(crate) fn normal(&self) -> Option<Vec<u8>> {
+        self.colored(Indicator::Normal).then(|| [self.raw(Indicator::Left), self.raw(Indicator::Normal), self.raw(Indicator::Right)].concat())
+    }
+
+    ///
+    /// Whether text that isn't a name gets colored, so every name ends with [Colors::end].
+    ///
+    pub(crate) fn colors_normal(&self) -> bool {
+        self.colored(Indicator::Normal)
+    }
+
+    pub(crate) fn clear_to_end(&self) -> &[u8] {
+        self.raw(Indicator::ClearToEnd)
+    }
+
+    ///
+    /// The text that puts the terminal back at the end, unless the usual sequences make it needless.
+    ///
+    pub(crate) fn finish(&self) -> Option<Vec<u8>> {
+        let usual = self.raw(Indicator::Left) == DEFAULT_LEFT && self.raw(Indicator::Right) == DEFAULT_RIGHT;
+        (!usual).then(|| self.restore())
+    }
+
+    fn restore(&self) -> Vec<u8> {
+        [self.raw(Indicator::Left), self.raw(Indicator::Right)].concat()
+    }
+
+    fn raw(&self, indicator: Indicator) -> &[u8] {
+        self.indicators[indicator as usize].as_deref().unwrap_or_default()
+    }
+}
+
+impl Extension {
+    fn matches(&self, name: &[u8]) -> bool {
+        if self.exact_case {
+            return name.ends_with(&self.suffix);
+        }
+        name.len() >= self.suffix.len() && name[name.len() - self.suffix.len()..].eq_ignore_ascii_case(&self.suffix)
+    }
+}
+
+///
+/// The indicator for a kind of file, without looking at its permissions.
+///
+fn type_indicator(file_type: Option<FileType>) -> Indicator {
+    match file_type {
+        Some(file_type) if file_type.is_file() => Indicator::File,
+        Some(file_type) if file_type.is_dir() => Indicator::Directory,
+        Some(file_type) if file_type.is_symlink() => Indicator::Link,
+        Some(file_type) if file_type.is_fifo() => Indicator::Fifo,
+        Some(file_type) if file_type.is_socket() => Indicator::Socket,
+        Some(file_type) if file_type.is_block_device() => Indicator::Block,
+        Some(file_type) if file_type.is_char_device() => Indicator::Character,
+        _ => Indicator::Orphan,
+    }
+}
+
+///
+/// Read a sequence up to `:` or the end, or up to `=` as well when reading a suffix.  `\` starts
+/// a C-like escape, `^` a control character; [None] when either is malformed.
+///
+fn funky_string(value: &[u8], position: &mut usize, stop_at_equals: bool) -> Option<Vec<u8>> {
+    let mut sequence = vec![];
+    while let Some(&byte) = value.get(*position) {
+        match byte {
+            b':' => break,
+            b'=' if stop_at_equals => break,
+            b'\\' => {
+                *position += 1;
+                let &escaped = value.get(*position)?;
+                *position += 1;
+                sequence.push(match escaped {
+                    b'0'..=b'7' => number(value, position, escaped, 8),
+                    b'x' | b'X' => number(value, position, b'0', 16),
+                    b'a' => 0x07,
+                    b'b' => 0x08,
+                    b'e' => 0x1b,
+                    b'f' => 0x0c,
+                    b'n' => b'\n',
+                    b'r' => b'\r',
+                    b't' => b'\t',
+                    b'v' => 0x0b,
+                    b'?' => 0x7f,
+                    b'_' => b' ',
+                    other => other,
+                });
+            }
+            b'^' => {
+                *position += 1;
+                let &control = value.get(*position)?;
+                *position += 1;
+                sequence.push(match control {
+                    b'@'..=b'~' => control & 0o37,
+                    b'?' => 0x7f,
+                    _ => return None,
+                });
+            }
+            _ => {
+                sequence.push(byte);
+                *position += 1;
+            }
+        }
+    }
+    Some(sequence)
+}
+
+///
+/// The rest of an octal or hexadecimal escape, whose first digit has been read already; the
+/// value wraps to a byte.
+///
+fn number(value: &[u8], position: &mut usize, first: u8, radix: u32) -> u8 {
+    let mut number = (first as char).to_digit(radix).unwrap_or(0);
+    while let Some(digit) = value.get(*position).and_then(|&byte| (byte as char).to_digit(radix)) {
+        number = (number * radix + digit) & 0xff;
+        *position += 1;
+    }
+    number as u8
+}
+
+///
+/// Whether `TERM` names a terminal known to take colors; a pattern's `*` matches any text, and
+/// `[0-9]` any digit.
+///
+fn known_terminal(term: &str) -> bool {
+    KNOWN_TERMINALS.iter().chain([&KNOWN_XTERMS]).any(|pattern| matches_pattern(pattern.as_bytes(), term.as_bytes()))
+}
+
+fn matches_pattern(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|skip| matches_pattern(rest, &text[skip..])),
+        Some((b'[', rest)) if rest.starts_with(b"0-9]") => {
+            text.first().is_some_and(u8::is_ascii_digit) && matches_pattern(&rest[4..], &text[1..])
+        }
+        Some((first, rest)) => text.first() == Some(first) && matches_pattern(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+    use common::test_support::TempDir;
+    use crate::color::{funky_string, known_terminal, Colors, Indicator};
+    use crate::config::Hidden;
+    use crate::entry::read_directory;
+
+    fn parse(value: &str) -> (Option<Colors>, Vec<String>) {
+        let mut warnings = vec![];
+        let colors = Colors::parse(value.as_bytes(), &mut warnings);
+        (colors, warnings.iter().map(|warning| warning.message.clone().unwrap()).collect())
+    }
+
+    fn funky(value: &str) -> Option<Vec<u8>> {
+        funky_string(value.as_bytes(), &mut 0, false)
+    }
+
+    #[test]
+    fn test_funky_string() {
+        assert_eq!(Some(b"01;34".to_vec()), funky("01;34:di=1"));
+        assert_eq!(Some(b"\x1b[K\x07\x7f \\".to_vec()), funky("\\e[K\\a\\?\\_\\\\"));
+        assert_eq!(Some(b"\x1b\x1b\x01\x7f".to_vec()), funky("\\033\\x1b^A^?"));
+        assert_eq!(None, funky("abc\\"));
+        assert_eq!(None, funky("^1"));
+        assert_eq!(Some(b".tar".to_vec()), funky_string(b".tar=01;31", &mut 0, true));
+    }
+
+    #[test]
+    fn test_parse() {
+        let (colors, warnings) = parse("di=01;31:ln=target:*.tar=01;31:*.C=32:*.c=33::*.TGZ=34");
+        let colors = colors.unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(Some(&b"01;31"[..]), colors.sequence(Indicator::Directory));
+        assert_eq!(Some(&b"01;36"[..]), colors.sequence(Indicator::Link));
+        assert!(colors.link_as_target);
+        assert_eq!(4, colors.extensions.len());
+        assert!(!colors.extensions[0].exact_case);
+        assert!(colors.extensions[1].exact_case);
+        assert!(colors.extensions[1].matches(b"x.C"));
+        assert!(!colors.extensions[1].matches(b"x.c"));
+        assert!(colors.extensions[3].matches(b"x.tgz"));
+
+        let (colors, warnings) = parse("di=01;31:xx=1");
+        assert!(colors.is_none());
+        assert_eq!(vec!["ls: unrecognized prefix: 'xx'", "ls: unparsable value for LS_COLORS environment variable"], warnings);
+        let (colors, warnings) = parse("di");
+        assert!(colors.is_none());
+        assert_eq!(vec!["ls: unparsable value for LS_COLORS environment variable"], warnings);
+    }
+
+    #[test]
+    fn test_sequences() {
+        let dir = TempDir::new("sequences");
+        dir.file("plain", b"");
+        dir.file("run", b"");
+        dir.file("archive.TAR", b"");
+        std::fs::set_permissions(dir.join("run"), std::fs::Permissions::from_mode(0o755)).unwrap();
+        std::fs::create_dir(dir.join("sub")).unwrap();
+        std::os::unix::fs::symlink("plain", dir.join("good")).unwrap();
+        std::os::unix::fs::symlink("nope", dir.join("broken")).unwrap();
+        let entries = read_directory(dir.path().as_ref(), b"dir", Hidden::Omit, true, true, &mut vec![]).unwrap();
+        let find = |name: &str| entries.iter().find(|entry| entry.name == name.as_bytes()).unwrap();
+
+        let colors = parse("*.tar=01;31").0.unwrap();
+        assert_eq!(None, colors.name_sequence(find("plain")));
+        assert_eq!(Some(&b"01;32"[..]), colors.name_sequence(find("run")));
+        assert_eq!(Some(&b"01;31"[..]), colors.name_sequence(find("archive.TAR")));
+        assert_eq!(Some(&b"01;34"[..]), colors.name_sequence(find("sub")));
+        assert_eq!(Some(&b"01;36"[..]), colors.name_sequence(find("good")));
+        assert_eq!(Some(&b"01;36"[..]), colors.name_sequence(find("broken")));
+        assert_eq!(None, colors.target_sequence(find("good")));
+        assert_eq!(None, colors.target_sequence(find("broken")));
+
+        let colors = parse("ln=target:or=31:mi=05").0.unwrap();
+        assert_eq!(None, colors.name_sequence(find("good")));
+        assert_eq!(Some(&b"31"[..]), colors.name_sequence(find("broken")));
+        assert_eq!(Some(&b"05"[..]), colors.target_sequence(find("broken")));
+    }
+
+    #[test]
+    fn test_text() {
+        let colors = Colors::built_in();
+        assert_eq!(b"\x1b[01;34m".to_vec(), colors.start(b"01;34"));
+        assert_eq!(b"\x1b[0m".to_vec(), colors.end());
+        assert_eq!(None, colors.normal());
+        assert_eq!(None, colors.finish());
+
+        let colors = parse("lc=<:rc=>:ec=END:no=1").0.unwrap();
+        assert_eq!(b"<><01;34>".to_vec(), colors.start(b"01;34"));
+        assert_eq!(b"END".to_vec(), colors.end());
+        assert_eq!(Some(b"<1>".to_vec()), colors.normal());
+        assert_eq!(Some(b"<>".to_vec()), colors.finish());
+    }
+
+    #[test]
+    fn test_known_terminal() {
+        assert!(known_terminal("xterm-256color"));
+        assert!(known_terminal("screen"));
+        assert!(known_terminal("con80x25"));
+        assert!(known_terminal("linux"));
+        assert!(!known_terminal("dumb"));
+        assert!(!known_terminal("linux2"));
+    }
+}
@@ -0,0 +1,474 @@
+use common::input::flags::flags_options::{parse_options, OptionSpec, ParsedArgument};
+use common::input::known_error::KnownError;
+use common::output::quote::QuotingStyle;
+use common::output::terminal::terminal_width;
+use crate::errors::{invalid_argument, invalid_line_width, invalid_quoting_style_variable, invalid_tab_size, invalid_time_style, option_error};
+
+const DEFAULT_LINE_LENGTH: usize = 80;
+const DEFAULT_TAB_SIZE: usize = 8;
+const POSIX_PREFIX: &str = "posix-";
+/* environment variables that pick the locale for times, in order of precedence */
+const LOCALE_VARIABLES: [&str; 3] = ["LC_ALL", "LC_TIME", "LANG"];
+
+const SORT_ARGUMENTS: [&[&str]; 6] = [&["none"], &["time"], &["size"], &["extension"], &["version"], &["width"]];
+const COLOR_ARGUMENTS: [&[&str]; 3] = [&["always", "yes", "force"], &["never", "no", "none"], &["auto", "tty", "if-tty"]];
+const QUOTING_ARGUMENTS: [&[&str]; 7] = [&["literal"], &["shell"], &["shell-always"], &["shell-escape"], &["shell-escape-always"], &["c"], &["escape"]];
+
+const OPTIONS: [OptionSpec; 30] = [
+    OptionSpec::flag("all", Some('a'), Some("all")),
+    OptionSpec::flag("almost-all", Some('A'), Some("almost-all")),
+    OptionSpec::flag("directory", Some('d'), Some("directory")),
+    OptionSpec::flag("recursive", Some('R'), Some("recursive")),
+    OptionSpec::flag("long", Some('l'), None),
+    OptionSpec::flag("human-readable", Some('h'), Some("human-readable")),
+    OptionSpec::flag("inode", Some('i'), Some("inode")),
+    OptionSpec::flag("size", Some('s'), Some("size")),
+    OptionSpec::flag("sort-time", Some('t'), None),
+    OptionSpec::flag("sort-size", Some('S'), None),
+    OptionSpec::flag("sort-extension", Some('X'), None),
+    OptionSpec::flag("sort-version", Some('v'), None),
+    OptionSpec::flag("sort-none", Some('U'), None),
+    OptionSpec::required("sort", None, Some("sort")),
+    OptionSpec::flag("reverse", Some('r'), Some("reverse")),
+    OptionSpec::flag("columns", Some('C'), None),
+    OptionSpec::flag("across", Some('x'), None),
+    OptionSpec::flag("one-per-line", Some('1'), None),
+    OptionSpec::required("width", Some('w'), Some("width")),
+    OptionSpec::required("tabsize", Some('T'), Some("tabsize")),
+    OptionSpec::required("time-style", None, Some("time-style")),
+    OptionSpec::optional("color", None, Some("color")),
+    OptionSpec::required("quoting-style", None, Some("quoting-style")),
+    OptionSpec::flag("literal", Some('N'), Some("literal")),
+    OptionSpec::flag("quote-name", Some('Q'), Some("quote-name")),
+    OptionSpec::flag("escape", Some('b'), Some("escape")),
+    OptionSpec::flag("hide-control-chars", Some('q'), Some("hide-control-chars")),
+    OptionSpec::flag("show-control-chars", None, Some("show-control-chars")),
+    OptionSpec::flag("help", None, Some("help")),
+    OptionSpec::flag("version", None, Some("version")),
+];
+
+///
+/// Which names starting with `.` are listed.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Hidden {
+    /// None of them.
+    Omit,
+    /// All but `.` and `..`; `-A`.
+    AlmostAll,
+    /// All of them, with `.` and `..`; `-a`.
+    All,
+}
+
+///
+/// How the names in a listing are laid out.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Format {
+    /// One file per line, with its details; `-l`.
+    Long,
+    /// One name per line; `-1`.
+    OnePerLine,
+    /// Names in columns, sorted down each column; `-C`.
+    Columns,
+    /// Names in columns, sorted across each row; `-x`.
+    Across,
+}
+
+///
+/// What a listing is sorted by; `-r` reverses any of them but [Sort::None].
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Sort {
+    Name,
+    /// Directory order; `-U`.
+    None,
+    /// Newest first; `-t`.
+    Time,
+    /// Largest first; `-S`.
+    Size,
+    /// By what follows the last `.`; `-X`.
+    Extension,
+    /// By version numbers in the name; `-v`.
+    Version,
+    /// Narrowest name first.
+    Width,
+}
+
+///
+/// The `strftime` formats for times in a long listing: one for the last six months, one for
+/// anything older or in the future.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TimeFormats {
+    pub(crate) recent: String,
+    pub(crate) old: String,
+}
+
+///
+/// Storage for the application configuration.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct Config {
+    pub(crate) print_help_and_exit: bool,
+    pub(crate) print_version_and_exit: bool,
+
+    pub(crate) hidden: Hidden,
+    pub(crate) directory: bool,
+    pub(crate) recursive: bool,
+
+    pub(crate) format: Format,
+    pub(crate) human_readable: bool,
+    pub(crate) inode: bool,
+    pub(crate) size: bool,
+    pub(crate) sort: Sort,
+    pub(crate) reverse: bool,
+    /* zero means there's no limit */
+    pub(crate) line_length: usize,
+    pub(crate) tab_size: usize,
+    pub(crate) time_formats: TimeFormats,
+    pub(crate) color: bool,
+    pub(crate) quoting: QuotingStyle,
+    pub(crate) hide_control: bool,
+
+    pub(crate) files: Vec<String>,
+    pub(crate) warnings: Vec<KnownError>,
+    pub(crate) errors: Vec<KnownError>,
+}
+
+impl Default for Config {
+    ///
+    /// Generate [Default] configuration.
+    ///
+    fn default() -> Self {
+        Config {
+            print_help_and_exit: false,
+            print_version_and_exit: false,
+
+            hidden: Hidden::Omit,
+            directory: false,
+            recursive: false,
+
+            format: Format::OnePerLine,
+            human_readable: false,
+            inode: false,
+            size: false,
+            sort: Sort::Name,
+            reverse: false,
+            line_length: DEFAULT_LINE_LENGTH,
+            tab_size: DEFAULT_TAB_SIZE,
+            time_formats: time_formats("locale").unwrap(),
+            color: false,
+            quoting: QuotingStyle::Literal,
+            hide_control: false,
+
+            files: vec![],
+            warnings: vec![],
+            errors: vec![],
+        }
+    }
+}
+
+///
+/// Options whose defaults depend on where output goes, gathered before they're settled.
+///
+#[derive(Default)]
+struct Selection {
+    format: Option<Format>,
+    line_length: Option<usize>,
+    time_style: Option<String>,
+    quoting: Option<QuotingStyle>,
+    hide_control: Option<bool>,
+}
+
+impl Config {
+    ///
+    /// Initialize the application configuration based on provided application arguments; the first
+    /// argument is the program name.
+    ///
+    pub(crate) fn new(args: &[String]) -> Config {
+        let mut config = Self::default();
+        let terminal = stdout_terminal();
+
+        let parsed = match parse_options(args.get(1..).unwrap_or_default(), &OPTIONS) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                config.errors.push(option_error(&error));
+                return config;
+            }
+        };
+
+        let mut selection = Selection::default();
+        for argument in parsed {
+            match argument {
+                ParsedArgument::Operand(operand) => config.files.push(operand),
+                ParsedArgument::Option { id, value } => {
+                    if let Err(error) = config.apply(&mut selection, id, value, terminal) {
+                        config.errors.push(error);
+                        return config;
+                    }
+                }
+            }
+        }
+        if config.print_help_and_exit || config.print_version_and_exit {
+            return config;
+        }
+
+        config.format = selection.format.unwrap_or(if terminal { Format::Columns } else { Format::OnePerLine });
+        config.line_length = selection.line_length.or_else(terminal_width).unwrap_or(DEFAULT_LINE_LENGTH);
+        config.hide_control = selection.hide_control.unwrap_or(terminal);
+        config.quoting = match selection.quoting {
+            Some(quoting) => quoting,
+            None => config.quoting_from_environment().unwrap_or(if terminal { QuotingStyle::ShellEscape } else { QuotingStyle::Literal }),
+        };
+
+        /* the time style only matters, and is only checked, when times are shown */
+        if config.format == Format::Long {
+            let style = selection.time_style.or_else(|| std::env::var("TIME_STYLE").ok()).unwrap_or_else(|| "locale".to_string());
+            match time_formats(&style) {
+                Some(formats) => config.time_formats = formats,
+                None => config.errors.push(invalid_time_style(&style)),
+            }
+        }
+        config
+    }
+
+    fn apply(&mut self, selection: &mut Selection, id: &str, value: Option<String>, terminal: bool) -> Result<(), KnownError> {
+        let value = value.unwrap_or_default();
+        match id {
+            "all" => self.hidden = Hidden::All,
+            "almost-all" => self.hidden = Hidden::AlmostAll,
+            "directory" => self.directory = true,
+            "recursive" => self.recursive = true,
+            "long" => selection.format = Some(Format::Long),
+            "human-readable" => self.human_readable = true,
+            "inode" => self.inode = true,
+            "size" => self.size = true,
+            "sort-time" => self.sort = Sort::Time,
+            "sort-size" => self.sort = Sort::Size,
+            "sort-extension" => self.sort = Sort::Extension,
+            "sort-version" => self.sort = Sort::Version,
+            "sort-none" => self.sort = Sort::None,
+            "sort" => self.sort = parse_sort(&value)?,
+            "reverse" => self.reverse = true,
+            "columns" => selection.format = Some(Format::Columns),
+            "across" => selection.format = Some(Format::Across),
+            "one-per-line" => selection.format = Some(Format::OnePerLine),
+            "width" => selection.line_length = Some(parse_number(&value).ok_or_else(|| invalid_line_width(&value))?),
+            "tabsize" => self.tab_size = parse_number(&value).ok_or_else(|| invalid_tab_size(&value))?,
+            "time-style" => selection.time_style = Some(value),
+            "color" => self.color = parse_color(&value, terminal)?,
+            "quoting-style" => selection.quoting = Some(parse_quoting_style(&value).ok_or_else(|| invalid_argument(&value, "--quoting-style", &QUOTING_ARGUMENTS))?),
+            "literal" => selection.quoting = Some(QuotingStyle::Literal),
+            "quote-name" => selection.quoting = Some(QuotingStyle::C),
+            "escape" => selection.quoting = Some(QuotingStyle::Escape),
+            "hide-control-chars" => selection.hide_control = Some(true),
+            "show-control-chars" => selection.hide_control = Some(false),
+            "help" => self.print_help_and_exit = true,
+            "version" => self.print_version_and_exit = true,
+            _ => unreachable!("option {} has no handler", id),
+        }
+        Ok(())
+    }
+
+    ///
+    /// The quoting style named by `QUOTING_STYLE`; a name that isn't known is warned about and ignored.
+    ///
+    fn quoting_from_environment(&mut self) -> Option<QuotingStyle> {
+        let value = std::env::var("QUOTING_STYLE").ok()?;
+        let style = parse_quoting_style(&value);
+        if style.is_none() {
+            self.warnings.push(invalid_quoting_style_variable(&value));
+        }
+        style
+    }
+}
+
+fn parse_sort(value: &str) -> Result<Sort, KnownError> {
+    match value {
+        "none" => Ok(Sort::None),
+        "time" => Ok(Sort::Time),
+        "size" => Ok(Sort::Size),
+        "extension" => Ok(Sort::Extension),
+        "version" => Ok(Sort::Version),
+        "width" => Ok(Sort::Width),
+        _ => Err(invalid_argument(value, "--sort", &SORT_ARGUMENTS)),
+    }
+}
+
+///
+/// Unlike `grep`, a bare `--color` means always.
+///
+fn parse_color(value: &str, terminal: bool) -> Result<bool, KnownError> {
+    match value {
+        "" | "always" | "yes" | "force" => Ok(true),
+        "never" | "no" | "none" => Ok(false),
+        "auto" | "tty" | "if-tty" => Ok(terminal),
+        _ => Err(invalid_argument(value, "--color", &COLOR_ARGUMENTS)),
+    }
+}
+
+fn parse_quoting_style(value: &str) -> Option<QuotingStyle> {
+    match value {
+        "literal" => Some(QuotingStyle::Literal),
+        "shell" => Some(QuotingStyle::Shell),
+        "shell-always" => Some(QuotingStyle::ShellAlways),
+        "shell-escape" => Some(QuotingStyle::ShellEscape),
+        "shell-escape-always" => Some(QuotingStyle::ShellEscapeAlways),
+        "c" => Some(QuotingStyle::C),
+        "escape" => Some(QuotingStyle::Escape),
+        _ => None,
+    }
+}
+
+///
+/// Turn a `--time-style` into its formats.  A `posix-` style is the `locale` style when times are
+/// shown in the `C` locale, which the locale variables decide.
+///
+fn time_formats(style: &str) -> Option<TimeFormats> {
+    let both = |format: &str| Some(TimeFormats { recent: format.to_string(), old: format.to_string() });
+    if let Some(format) = style.strip_prefix('+') {
+        return match format.split_once('\n') {
+            Some((old, recent)) => Some(TimeFormats { recent: recent.to_string(), old: old.to_string() }),
+            None => both(format),
+        };
+    }
+    if let Some(style) = style.strip_prefix(POSIX_PREFIX) {
+        return time_formats(if c_locale() { "locale" } else { style });
+    }
+
+    match style {
+        "full-iso" => both("%Y-%m-%d %H:%M:%S.%N %z"),
+        "long-iso" => both("%Y-%m-%d %H:%M"),
+        "iso" => Some(TimeFormats { recent: "%m-%d %H:%M".to_string(), old: "%Y-%m-%d ".to_string() }),
+        "locale" => Some(TimeFormats { recent: "%b %e %H:%M".to_string(), old: "%b %e  %Y".to_string() }),
+        _ => None,
+    }
+}
+
+fn c_locale() -> bool {
+    LOCALE_VARIABLES.iter()
+        .find_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty()))
+        .is_none_or(|locale| locale == "C" || locale == "POSIX")
+}
+
+///
+/// Whether standard output is a terminal; it decides the default layout, quoting and `--color=auto`.
+///
+fn stdout_terminal() -> bool {
+    /* SAFETY: isatty only inspects the descriptor */
+    unsafe { libc::isatty(libc::STDOUT_FILENO) == 1 }
+}
+
+fn parse_number(value: &str) -> Option<usize> {
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use common::output::quote::QuotingStyle;
+    use crate::config::{Config, Format, Hidden, Sort, TimeFormats};
+
+    fn config(line: &str) -> Config {
+        let args: Vec<String> = line.split_whitespace().map(|s| s.to_string()).collect();
+        Config::new(&args)
+    }
+
+    fn messages(config: &Config) -> Vec<String> {
+        config.errors.iter().map(|e| e.message.clone().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_listing() {
+        let config = config("ls -a -dR a -- -b");
+
+        assert!(config.errors.is_empty());
+        assert_eq!(Hidden::All, config.hidden);
+        assert!(config.directory);
+        assert!(config.recursive);
+        assert_eq!(vec!["a", "-b"], config.files);
+        assert_eq!(Hidden::AlmostAll, self::config("ls -aA").hidden);
+        assert_eq!(Hidden::All, self::config("ls -Aa").hidden);
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(Format::Long, config("ls -Cl").format);
+        assert_eq!(Format::Columns, config("ls -lC").format);
+        assert_eq!(Format::Across, config("ls -x").format);
+        assert_eq!(Format::OnePerLine, config("ls -l1").format);
+        assert_eq!(100, config("ls -w 100").line_length);
+        assert_eq!(0, config("ls --width=0").line_length);
+        assert_eq!(4, config("ls -T4").tab_size);
+        let config = config("ls -lhis");
+        assert!(config.human_readable && config.inode && config.size);
+    }
+
+    #[test]
+    fn test_sort() {
+        assert_eq!(Sort::Name, config("ls").sort);
+        assert_eq!(Sort::Size, config("ls -tS").sort);
+        assert_eq!(Sort::Time, config("ls -St").sort);
+        assert_eq!(Sort::Extension, config("ls -X").sort);
+        assert_eq!(Sort::Version, config("ls -v").sort);
+        assert_eq!(Sort::None, config("ls -vU").sort);
+        assert_eq!(Sort::Width, config("ls --sort=width").sort);
+        assert_eq!(Sort::Time, config("ls --sort time").sort);
+        assert!(config("ls -r").reverse);
+    }
+
+    #[test]
+    fn test_time_style() {
+        let formats = |recent: &str, old: &str| TimeFormats { recent: recent.to_string(), old: old.to_string() };
+
+        assert_eq!(formats("%b %e %H:%M", "%b %e  %Y"), config("ls -l").time_formats);
+        assert_eq!(formats("%Y-%m-%d %H:%M", "%Y-%m-%d %H:%M"), config("ls -l --time-style=long-iso").time_formats);
+        assert_eq!(formats("%m-%d %H:%M", "%Y-%m-%d "), config("ls -l --time-style=iso").time_formats);
+        assert_eq!(formats("%Y-%m-%d %H:%M:%S.%N %z", "%Y-%m-%d %H:%M:%S.%N %z"), config("ls -l --time-style=full-iso").time_formats);
+        assert_eq!(formats("%H", "%Y"), Config::new(&["ls".to_string(), "-l".to_string(), "--time-style=+%Y\n%H".to_string()]).time_formats);
+        assert_eq!(formats("<%s>", "<%s>"), config("ls -l --time-style=+<%s>").time_formats);
+        assert!(config("ls --time-style=bogus").errors.is_empty());
+    }
+
+    #[test]
+    fn test_quoting() {
+        assert_eq!(QuotingStyle::ShellAlways, config("ls --quoting-style=shell-always").quoting);
+        assert_eq!(QuotingStyle::Literal, config("ls -QN").quoting);
+        assert_eq!(QuotingStyle::C, config("ls -Q").quoting);
+        assert_eq!(QuotingStyle::Escape, config("ls -b").quoting);
+        assert!(config("ls -q").hide_control);
+        assert!(!config("ls -q --show-control-chars").hide_control);
+        assert!(config("ls --color").color);
+        assert!(!config("ls --color=never").color);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(vec!["ls: invalid option -- 'z'\nTry 'ls --help' for more information."], messages(&config("ls -z")));
+        assert_eq!(vec!["ls: invalid line width: 'x'"], messages(&config("ls -w x")));
+        assert_eq!(vec!["ls: invalid tab size: '-1'"], messages(&config("ls -T -1")));
+        assert_eq!(
+            vec!["ls: invalid argument 'foo' for '--sort'\nValid arguments are:\n  - 'none'\n  - 'time'\n  - 'size'\n  - 'extension'\n  - 'version'\n  - 'width'\nTry 'ls --help' for more information."],
+            messages(&config("ls --sort=foo"))
+        );
+        assert_eq!(
+            vec!["ls: invalid argument 'foo' for '--color'\nValid arguments are:\n  - 'always', 'yes', 'force'\n  - 'never', 'no', 'none'\n  - 'auto', 'tty', 'if-tty'\nTry 'ls --help' for more information."],
+            messages(&config("ls --color=foo"))
+        );
+        assert_eq!(1, config("ls --quoting-style=locale").errors[0].code);
+        assert_eq!(
+            vec!["ls: invalid argument 'foo' for 'time style'\nValid arguments are:\n  - [posix-]full-iso\n  - [posix-]long-iso\n  - [posix-]iso\n  - [posix-]locale\n  - +FORMAT (e.g., +%H:%M) for a 'date'-style format\nTry 'ls --help' for more information."],
+            messages(&config("ls -l --time-style=foo"))
+        );
+        assert_eq!(2, config("ls -l --time-style=foo").errors[0].code);
+    }
+
+    #[test]
+    fn test_help_and_version() {
+        assert!(config("ls --help").print_help_and_exit);
+        assert!(config("ls --version").print_version_and_exit);
+    }
+}
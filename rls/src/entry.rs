@@ -0,0 +1,211 @@
+use std::ffi::OsStr;
+use std::fs::{self, FileType, Metadata};
+use std::io;
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::path::{Path, PathBuf};
+use common::input::known_error::KnownError;
+use crate::config::Hidden;
+use crate::errors::{cannot_access, cannot_open_directory, cannot_read_link, reading_directory};
+
+const CURRENT: &[u8] = b".";
+const PARENT: &[u8] = b"..";
+pub(crate) const CURRENT_DIRECTORY: &str = ".";
+
+///
+/// A file to be listed: its name as shown, and what could be learned about it.
+///
+#[derive(Debug)]
+pub(crate) struct Entry {
+    pub(crate) name: Vec<u8>,
+    /* what a directory entry says the file is, for when it can't be looked at */
+    pub(crate) file_type: Option<FileType>,
+    pub(crate) metadata: Option<Metadata>,
+    pub(crate) link: Option<Link>,
+}
+
+///
+/// What's known about where a symbolic link leads.
+///
+#[derive(Debug)]
+pub(crate) struct Link {
+    /* what the link holds; it's only read for a long listing */
+    pub(crate) target: Option<Vec<u8>>,
+    /* the file it leads to, or [None] when it's broken */
+    pub(crate) metadata: Option<Metadata>,
+}
+
+impl Entry {
+    ///
+    /// Look at a file named on the command line.  When `follow` is set, a symbolic link to a
+    /// directory is taken to be that directory.
+    ///
+    pub(crate) fn operand(name: &str, follow: bool, long: bool, problems: &mut Vec<KnownError>) -> Result<Entry, KnownError> {
+        let path = Path::new(name);
+        let metadata = fs::symlink_metadata(path).map_err(|error| cannot_access(name.as_bytes(), &error, true))?;
+
+        if follow && metadata.file_type().is_symlink()
+            && let Ok(followed) = fs::metadata(path)
+            && followed.is_dir() {
+            return Ok(Entry { name: name.as_bytes().to_vec(), file_type: Some(followed.file_type()), metadata: Some(followed), link: None });
+        }
+        Ok(Entry::found(name.as_bytes().to_vec(), path, Ok(metadata), None, long, problems))
+    }
+
+    ///
+    /// Put together what's known about a file; a symbolic link's target is looked at as well.
+    ///
+    fn found(name: Vec<u8>, path: &Path, metadata: io::Result<Metadata>, file_type: Option<FileType>, long: bool, problems: &mut Vec<KnownError>) -> Entry {
+        let metadata = match metadata {
+            Ok(metadata) => Some(metadata),
+            Err(error) => {
+                problems.push(cannot_access(path.as_os_str().as_bytes(), &error, false));
+                None
+            }
+        };
+        let file_type = metadata.as_ref().map(|metadata| metadata.file_type()).or(file_type);
+
+        let link = file_type.filter(|file_type| file_type.is_symlink()).map(|_| {
+            let target = if long {
+                fs::read_link(path)
+                    .map_err(|error| problems.push(cannot_read_link(path.as_os_str().as_bytes(), &error)))
+                    .ok()
+                    .map(|target| target.into_os_string().into_vec())
+            } else {
+                None
+            };
+            Link { target, metadata: fs::metadata(path).ok() }
+        });
+        Entry { name, file_type, metadata, link }
+    }
+
+    ///
+    /// Whether the file is a directory; a symbolic link to one isn't.
+    ///
+    pub(crate) fn is_directory(&self) -> bool {
+        self.file_type.is_some_and(|file_type| file_type.is_dir())
+    }
+
+    ///
+    /// Whether this is a directory's `.` or `..`, which `-R` never descends into.
+    ///
+    pub(crate) fn is_dot(&self) -> bool {
+        self.name == CURRENT || self.name == PARENT
+    }
+}
+
+///
+/// Read a directory's entries, leaving out the hidden ones that aren't wanted.  `name` is the
+/// directory as it's shown; `operand` is whether it was named on the command line.  Entries that
+/// can't be looked at are kept, and the problem noted.  Entries of `.` are found by name alone, as
+/// they're named in messages.
+///
+pub(crate) fn read_directory(path: &Path, name: &[u8], hidden: Hidden, long: bool, operand: bool, problems: &mut Vec<KnownError>) -> Result<Vec<Entry>, KnownError> {
+    let reader = fs::read_dir(path).map_err(|error| cannot_open_directory(name, &error, operand))?;
+    let mut entries = vec![];
+
+    if hidden == Hidden::All {
+        for dot in [CURRENT, PARENT] {
+            let path = path.join(Path::new(OsStr::from_bytes(dot)));
+            let metadata = fs::symlink_metadata(&path);
+            entries.push(Entry::found(dot.to_vec(), &path, metadata, None, long, problems));
+        }
+    }
+
+    for item in reader {
+        let item = match item {
+            Ok(item) => item,
+            Err(error) => {
+                problems.push(reading_directory(name, &error, operand));
+                break;
+            }
+        };
+        let file_name = item.file_name().into_vec();
+        if hidden == Hidden::Omit && file_name.starts_with(CURRENT) {
+            continue;
+        }
+        let path = if path == Path::new(CURRENT_DIRECTORY) { PathBuf::from(item.file_name()) } else { item.path() };
+        let metadata = fs::symlink_metadata(&path);
+        entries.push(Entry::found(file_name, &path, metadata, item.file_type().ok(), long, problems));
+    }
+    Ok(entries)
+}
+
+///
+/// The path to an entry under a directory as it's shown in a header; `.` isn't dropped, and
+/// no second `/` is added.
+///
+pub(crate) fn join(directory: &[u8], name: &[u8]) -> Vec<u8> {
+    let mut joined = directory.to_vec();
+    if !joined.ends_with(b"/") {
+        joined.push(b'/');
+    }
+    joined.extend_from_slice(name);
+    joined
+}
+
+#[cfg(test)]
+mod tests {
+    use common::test_support::TempDir;
+    use crate::config::Hidden;
+    use crate::entry::{join, read_directory, Entry};
+
+    fn names(entries: &[Entry]) -> Vec<String> {
+        let mut names: Vec<String> = entries.iter().map(|entry| String::from_utf8_lossy(&entry.name).into_owned()).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_hidden() {
+        let dir = TempDir::new("hidden");
+        dir.file("a", b"");
+        dir.file(".b", b"");
+        let read = |hidden| names(&read_directory(dir.path().as_ref(), b"dir", hidden, false, true, &mut vec![]).unwrap());
+
+        assert_eq!(vec!["a"], read(Hidden::Omit));
+        assert_eq!(vec![".b", "a"], read(Hidden::AlmostAll));
+        assert_eq!(vec![".", "..", ".b", "a"], read(Hidden::All));
+    }
+
+    #[test]
+    fn test_links() {
+        let dir = TempDir::new("links");
+        dir.file("a", b"abc");
+        std::fs::create_dir(dir.join("sub")).unwrap();
+        std::os::unix::fs::symlink("a", dir.join("good")).unwrap();
+        std::os::unix::fs::symlink("nope", dir.join("broken")).unwrap();
+        std::os::unix::fs::symlink("sub", dir.join("to-sub")).unwrap();
+
+        let entries = read_directory(dir.path().as_ref(), b"dir", Hidden::Omit, true, true, &mut vec![]).unwrap();
+        let find = |name: &str| entries.iter().find(|entry| entry.name == name.as_bytes()).unwrap();
+        let good = find("good").link.as_ref().unwrap();
+        assert_eq!(Some(b"a".to_vec()), good.target);
+        assert_eq!(3, good.metadata.as_ref().unwrap().len());
+        assert!(find("broken").link.as_ref().unwrap().metadata.is_none());
+        assert!(!find("to-sub").is_directory());
+        assert!(find("sub").is_directory());
+
+        let to_sub = dir.join("to-sub").to_str().unwrap().to_string();
+        assert!(Entry::operand(&to_sub, true, false, &mut vec![]).unwrap().is_directory());
+        assert!(!Entry::operand(&to_sub, false, true, &mut vec![]).unwrap().is_directory());
+    }
+
+    #[test]
+    fn test_errors() {
+        let dir = TempDir::new("errors");
+        let missing = dir.join("missing").to_str().unwrap().to_string();
+
+        let error = Entry::operand(&missing, true, false, &mut vec![]).unwrap_err();
+        assert_eq!(2, error.code);
+        assert_eq!(format!("ls: cannot access '{}': No such file or directory", missing), error.message.unwrap());
+        let error = read_directory(dir.join("missing").as_path(), b"missing", Hidden::Omit, false, false, &mut vec![]).unwrap_err();
+        assert_eq!((1, Some("ls: cannot open directory 'missing': No such file or directory".to_string())), (error.code, error.message));
+    }
+
+    #[test]
+    fn test_join() {
+        assert_eq!(b"./sub".to_vec(), join(b".", b"sub"));
+        assert_eq!(b"a/b/sub".to_vec(), join(b"a/b/", b"sub"));
+        assert_eq!(b"/etc".to_vec(), join(b"/", b"etc"));
+    }
+}
@@ -0,0 +1,118 @@
+use std::io;
+use common::input::flags::flags_options::OptionError;
+use common::input::known_error::KnownError;
+use common::input::source::describe_io_error;
+use common::output::quote::quote_name;
+
+/* a problem with the command line, or with a file named on it */
+const ERROR_CODE: i32 = 2;
+/* a problem with a file found while listing */
+const MINOR_ERROR_CODE: i32 = 1;
+/* an argument that isn't one of an option's choices */
+const ARGUMENT_ERROR_CODE: i32 = 1;
+const PROGRAM: &str = "ls";
+
+///
+/// The command line couldn't be split into options and operands.
+///
+pub(crate) fn option_error(error: &OptionError) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}\n{}", error.message(PROGRAM), try_help()))
+}
+
+///
+/// An option was given a value that isn't one of its choices; each choice is listed with its synonyms.
+///
+pub(crate) fn invalid_argument(argument: &str, option: &str, choices: &[&[&str]]) -> KnownError {
+    let choices: Vec<String> = choices.iter()
+        .map(|synonyms| format!("  - {}", synonyms.iter().map(|name| format!("'{}'", name)).collect::<Vec<_>>().join(", ")))
+        .collect();
+    KnownError::new(ARGUMENT_ERROR_CODE, format!("{}: invalid argument '{}' for '{}'\nValid arguments are:\n{}\n{}", PROGRAM, argument, option, choices.join("\n"), try_help()))
+}
+
+///
+/// `--time-style`, or `TIME_STYLE`, names a style that isn't known.
+///
+pub(crate) fn invalid_time_style(argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!(
+        "{}: invalid argument '{}' for 'time style'\nValid arguments are:\n  - [posix-]full-iso\n  - [posix-]long-iso\n  - [posix-]iso\n  - [posix-]locale\n  - +FORMAT (e.g., +%H:%M) for a 'date'-style format\n{}",
+        PROGRAM, argument, try_help()
+    ))
+}
+
+///
+/// `-w` was given something other than a non-negative number.
+///
+pub(crate) fn invalid_line_width(argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: invalid line width: '{}'", PROGRAM, argument))
+}
+
+///
+/// `-T` was given something other than a non-negative number.
+///
+pub(crate) fn invalid_tab_size(argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: invalid tab size: '{}'", PROGRAM, argument))
+}
+
+///
+/// `QUOTING_STYLE` names a style that isn't known; it's ignored, so this is only a warning.
+///
+pub(crate) fn invalid_quoting_style_variable(value: &str) -> KnownError {
+    KnownError::new(0, format!("{}: ignoring invalid value of environment variable QUOTING_STYLE: '{}'", PROGRAM, value))
+}
+
+///
+/// `LS_COLORS` has an entry with a name that isn't known.
+///
+pub(crate) fn unrecognized_prefix(prefix: &str) -> KnownError {
+    KnownError::new(0, format!("{}: unrecognized prefix: {}", PROGRAM, quote_name(prefix.as_bytes())))
+}
+
+///
+/// `LS_COLORS` couldn't be read, so nothing is colored.
+///
+pub(crate) fn unparsable_colors() -> KnownError {
+    KnownError::new(0, format!("{}: unparsable value for LS_COLORS environment variable", PROGRAM))
+}
+
+///
+/// A file couldn't be found or looked at; `operand` is whether it was named on the command line.
+///
+pub(crate) fn cannot_access(name: &[u8], error: &io::Error, operand: bool) -> KnownError {
+    KnownError::new(code(operand), format!("{}: cannot access {}: {}", PROGRAM, quote_name(name), describe_io_error(error)))
+}
+
+///
+/// A directory couldn't be opened for listing.
+///
+pub(crate) fn cannot_open_directory(name: &[u8], error: &io::Error, operand: bool) -> KnownError {
+    KnownError::new(code(operand), format!("{}: cannot open directory {}: {}", PROGRAM, quote_name(name), describe_io_error(error)))
+}
+
+///
+/// A directory was opened, but listing it failed part way.
+///
+pub(crate) fn reading_directory(name: &[u8], error: &io::Error, operand: bool) -> KnownError {
+    KnownError::new(code(operand), format!("{}: reading directory {}: {}", PROGRAM, quote_name(name), describe_io_error(error)))
+}
+
+///
+/// A symbolic link's target couldn't be read.
+///
+pub(crate) fn cannot_read_link(name: &[u8], error: &io::Error) -> KnownError {
+    KnownError::new(MINOR_ERROR_CODE, format!("{}: cannot read symbolic link {}: {}", PROGRAM, quote_name(name), describe_io_error(error)))
+}
+
+///
+/// Standard output couldn't be written.
+///
+pub(crate) fn write_error(error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: write error: {}", PROGRAM, describe_io_error(error)))
+}
+
+fn code(operand: bool) -> i32 {
+    if operand { ERROR_CODE } else { MINOR_ERROR_CODE }
+}
+
+fn try_help() -> String {
+    format!("Try '{} --help' for more information.", PROGRAM)
+}
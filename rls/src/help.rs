@@ -0,0 +1,74 @@
+use std::io::{self, Write};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub(crate) fn version(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "rls {}", VERSION)
+}
+
+pub(crate) fn help(out: &mut dyn Write) -> io::Result<()> {
+    version(out)?;
+    writeln!(out)?;
+    writeln!(out, "Usage: ls [OPTION]... [FILE]...")?;
+    writeln!(out, "List information about the FILEs (the current directory by default).")?;
+    writeln!(out, "Sort entries alphabetically if none of -tvSUX nor --sort is specified.")?;
+    writeln!(out)?;
+    writeln!(out, "Mandatory arguments to long options are mandatory for short options too.")?;
+    writeln!(out, "  -a, --all                  do not ignore entries starting with .")?;
+    writeln!(out, "  -A, --almost-all           do not list implied . and ..")?;
+    writeln!(out, "  -b, --escape               print C-style escapes for nongraphic characters")?;
+    writeln!(out, "  -C                         list entries by columns")?;
+    writeln!(out, "      --color[=WHEN]         color the output WHEN; more info below")?;
+    writeln!(out, "  -d, --directory            list directories themselves, not their contents")?;
+    writeln!(out, "  -h, --human-readable       with -l and -s, print sizes like 1K 234M 2G etc.")?;
+    writeln!(out, "  -i, --inode                print the index number of each file")?;
+    writeln!(out, "  -l                         use a long listing format")?;
+    writeln!(out, "  -N, --literal              print entry names without quoting")?;
+    writeln!(out, "  -q, --hide-control-chars   print ? instead of nongraphic characters")?;
+    writeln!(out, "      --show-control-chars   show nongraphic characters as-is (the default,")?;
+    writeln!(out, "                             unless program is 'ls' and output is a terminal)")?;
+    writeln!(out)?;
+    writeln!(out, "  -Q, --quote-name           enclose entry names in double quotes")?;
+    writeln!(out, "      --quoting-style=WORD   use quoting style WORD for entry names:")?;
+    writeln!(out, "                             literal, shell, shell-always,")?;
+    writeln!(out, "                             shell-escape, shell-escape-always, c, escape")?;
+    writeln!(out, "                             (overrides QUOTING_STYLE environment variable)")?;
+    writeln!(out)?;
+    writeln!(out, "  -r, --reverse              reverse order while sorting")?;
+    writeln!(out, "  -R, --recursive            list subdirectories recursively")?;
+    writeln!(out, "  -s, --size                 print the allocated size of each file, in blocks")?;
+    writeln!(out, "  -S                         sort by file size, largest first")?;
+    writeln!(out, "      --sort=WORD            sort by WORD instead of name: none (-U), size (-S),")?;
+    writeln!(out, "                             time (-t), version (-v), extension (-X), width")?;
+    writeln!(out)?;
+    writeln!(out, "      --time-style=TIME_STYLE")?;
+    writeln!(out, "                             time/date format with -l; see TIME_STYLE below")?;
+    writeln!(out, "  -t                         sort by time, newest first")?;
+    writeln!(out, "  -T, --tabsize=COLS         assume tab stops at each COLS instead of 8")?;
+    writeln!(out, "  -U                         do not sort; list entries in directory order")?;
+    writeln!(out, "  -v                         natural sort of (version) numbers within text")?;
+    writeln!(out, "  -w, --width=COLS           set output width to COLS.  0 means no limit")?;
+    writeln!(out, "  -x                         list entries by lines instead of by columns")?;
+    writeln!(out, "  -X                         sort alphabetically by entry extension")?;
+    writeln!(out, "  -1                         list one file per line")?;
+    writeln!(out, "      --help        display this help and exit")?;
+    writeln!(out, "      --version     output version information and exit")?;
+    writeln!(out)?;
+    writeln!(out, "The TIME_STYLE argument can be full-iso, long-iso, iso, locale, or +FORMAT.")?;
+    writeln!(out, "FORMAT is interpreted like in date(1).  If FORMAT is FORMAT1<newline>FORMAT2,")?;
+    writeln!(out, "then FORMAT1 applies to non-recent files and FORMAT2 to recent files.")?;
+    writeln!(out, "TIME_STYLE prefixed with 'posix-' takes effect only outside the POSIX locale.")?;
+    writeln!(out, "Also the TIME_STYLE environment variable sets the default style to use.")?;
+    writeln!(out)?;
+    writeln!(out, "The WHEN argument defaults to 'always' and can also be 'auto' or 'never'.")?;
+    writeln!(out)?;
+    writeln!(out, "Using color to distinguish file types is disabled both by default and")?;
+    writeln!(out, "with --color=never.  With --color=auto, ls emits color codes only when")?;
+    writeln!(out, "standard output is connected to a terminal.  The LS_COLORS environment")?;
+    writeln!(out, "variable can change the settings.  Use the dircolors(1) command to set it.")?;
+    writeln!(out)?;
+    writeln!(out, "Exit status:")?;
+    writeln!(out, " 0  if OK,")?;
+    writeln!(out, " 1  if minor problems (e.g., cannot access subdirectory),")?;
+    writeln!(out, " 2  if serious trouble (e.g., cannot access command-line argument).")
+}
@@ -0,0 +1,94 @@
+use std::io::{self, Write};
+
+/* the narrowest a column can be: a one character name and the two spaces after it */
+const MIN_COLUMN_WIDTH: usize = 3;
+const SEPARATOR_WIDTH: usize = 2;
+
+///
+/// How names laid out in columns are arranged.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Columns {
+    /* the width of each column, counting the space after it for all but the last */
+    pub(crate) widths: Vec<usize>,
+    pub(crate) rows: usize,
+}
+
+///
+/// Fit names of the given widths into as many columns as fit in a line, the way `ls` does: each
+/// column is as wide as its widest name plus two spaces, and the line must stay narrower than
+/// `line_length`; zero means there's no limit.  `by_columns` sorts down the columns (`-C`) rather
+/// than across the rows (`-x`).
+///
+pub(crate) fn columns(widths: &[usize], line_length: usize, by_columns: bool) -> Columns {
+    let count = widths.len();
+    let most = if line_length == 0 { count } else { (line_length / MIN_COLUMN_WIDTH).max(1).min(count) }.max(1);
+
+    let mut best = vec![];
+    for columns in 1..=most {
+        let rows = count.div_ceil(columns);
+        let mut column_widths = vec![MIN_COLUMN_WIDTH; columns];
+        let mut line = columns * MIN_COLUMN_WIDTH;
+        for (index, width) in widths.iter().enumerate() {
+            let column = if by_columns { index / rows } else { index % columns };
+            let width = width + if column == columns - 1 { 0 } else { SEPARATOR_WIDTH };
+            if column_widths[column] < width {
+                line += width - column_widths[column];
+                column_widths[column] = width;
+            }
+        }
+        /* one column is used when nothing fits */
+        if columns == 1 || line_length == 0 || line < line_length {
+            best = column_widths;
+        }
+    }
+
+    let rows = if count == 0 { 0 } else { count.div_ceil(best.len()) };
+    Columns { widths: best, rows }
+}
+
+///
+/// Move from column `from` to column `to`, with tabs as far as they go when `tab_size` isn't zero.
+///
+pub(crate) fn indent(out: &mut dyn Write, mut from: usize, to: usize, tab_size: usize) -> io::Result<()> {
+    while from < to {
+        if tab_size != 0 && to / tab_size > (from + 1) / tab_size {
+            out.write_all(b"\t")?;
+            from += tab_size - from % tab_size;
+        } else {
+            out.write_all(b" ")?;
+            from += 1;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::layout::{columns, indent, Columns};
+
+    #[test]
+    fn test_columns() {
+        assert_eq!(Columns { widths: vec![4, 4, 3], rows: 1 }, columns(&[2, 2, 2], 80, true));
+        assert_eq!(Columns { widths: vec![4, 3], rows: 2 }, columns(&[2, 1, 2, 1], 8, true));
+        assert_eq!(Columns { widths: vec![4, 3], rows: 2 }, columns(&[2, 2, 1, 1], 8, false));
+        assert_eq!(Columns { widths: vec![12], rows: 2 }, columns(&[10, 12], 10, true));
+        assert_eq!(Columns { widths: vec![12, 10], rows: 1 }, columns(&[10, 10], 0, true));
+        assert_eq!(Columns { widths: vec![3], rows: 0 }, columns(&[], 80, true));
+    }
+
+    #[test]
+    fn test_indent() {
+        let indented = |from, to, tab_size| {
+            let mut out = vec![];
+            indent(&mut out, from, to, tab_size).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        assert_eq!("\t\t", indented(3, 16, 8));
+        assert_eq!("\t  ", indented(3, 10, 8));
+        assert_eq!("\t", indented(6, 8, 8));
+        assert_eq!(" ", indented(6, 7, 8));
+        assert_eq!("       ", indented(3, 10, 0));
+    }
+}
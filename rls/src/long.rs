@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::fs::{FileType, Metadata};
+use std::os::unix::fs::{FileTypeExt, MetadataExt, PermissionsExt};
+use std::time::{Duration, SystemTime};
+use common::fs::permissions::permission_string;
+use common::time::local::{epoch_seconds, LocalTime};
+use common::time::strftime::strftime;
+use crate::config::TimeFormats;
+
+const HUMAN_SUFFIXES: [char; 10] = ['K', 'M', 'G', 'T', 'P', 'E', 'Z', 'Y', 'R', 'Q'];
+const HUMAN_BASE: u128 = 1024;
+/* st_blocks counts 512 byte blocks; sizes are shown in 1024 byte blocks */
+const BLOCK_BYTES: u64 = 512;
+const SHOWN_BLOCK_BYTES: u64 = 1024;
+/* half of an average Gregorian year */
+const RECENT: Duration = Duration::from_secs(31_556_952 / 2);
+const UNKNOWN: &str = "?";
+const NAME_BUFFER: usize = 1024;
+
+///
+/// `ls -l`'s permissions column: the kind of file, then read, write and execute for its owner, its
+/// group and everyone else.  What couldn't be looked at shows as `?`.
+///
+pub(crate) fn mode_string(metadata: Option<&Metadata>, file_type: Option<FileType>) -> String {
+    let Some(metadata) = metadata else {
+        return format!("{}?????????", type_character(file_type));
+    };
+    format!("{}{}", type_character(Some(metadata.file_type())), permission_string(metadata.permissions().mode()))
+}
+
+fn type_character(file_type: Option<FileType>) -> char {
+    match file_type {
+        Some(file_type) if file_type.is_file() => '-',
+        Some(file_type) if file_type.is_dir() => 'd',
+        Some(file_type) if file_type.is_symlink() => 'l',
+        Some(file_type) if file_type.is_fifo() => 'p',
+        Some(file_type) if file_type.is_socket() => 's',
+        Some(file_type) if file_type.is_block_device() => 'b',
+        Some(file_type) if file_type.is_char_device() => 'c',
+        _ => '?',
+    }
+}
+
+///
+/// A size in bytes, the way `-h` shows it: powers of 1024, rounded up, with one decimal while
+/// that's a single digit.
+///
+pub(crate) fn human_size(bytes: u64) -> String {
+    let bytes = bytes as u128;
+    if bytes < HUMAN_BASE {
+        return bytes.to_string();
+    }
+
+    let mut unit = 0;
+    let mut divisor = HUMAN_BASE;
+    while bytes >= divisor * HUMAN_BASE && unit + 1 < HUMAN_SUFFIXES.len() {
+        divisor *= HUMAN_BASE;
+        unit += 1;
+    }
+    let tenths = (bytes * 10).div_ceil(divisor);
+    if tenths < 100 {
+        return format!("{}.{}{}", tenths / 10, tenths % 10, HUMAN_SUFFIXES[unit]);
+    }
+    let whole = bytes.div_ceil(divisor);
+    if whole >= HUMAN_BASE && unit + 1 < HUMAN_SUFFIXES.len() {
+        return format!("1.0{}", HUMAN_SUFFIXES[unit + 1]);
+    }
+    format!("{}{}", whole, HUMAN_SUFFIXES[unit])
+}
+
+///
+/// Space taken on disk, given in 512 byte blocks: whole kilobytes, rounded up, or a `-h` size.
+///
+pub(crate) fn block_size(blocks: u64, human_readable: bool) -> String {
+    let bytes = blocks * BLOCK_BYTES;
+    if human_readable { human_size(bytes) } else { bytes.div_ceil(SHOWN_BLOCK_BYTES).to_string() }
+}
+
+///
+/// The blocks column for a file, `?` when it couldn't be looked at.
+///
+pub(crate) fn blocks(metadata: Option<&Metadata>, human_readable: bool) -> String {
+    metadata.map_or_else(|| UNKNOWN.to_string(), |metadata| block_size(metadata.blocks(), human_readable))
+}
+
+///
+/// A modification time in a long listing.  Times from the last six months use the recent format;
+/// `now` is moved forward if a time turns out to be past it, as the clock moves while listing.
+///
+pub(crate) fn format_time(time: SystemTime, formats: &TimeFormats, now: &mut SystemTime) -> String {
+    if time > *now {
+        *now = SystemTime::now();
+    }
+    let recent = now.checked_sub(RECENT).is_none_or(|limit| limit < time) && time < *now;
+    match LocalTime::new(time) {
+        Some(local) => strftime(if recent { &formats.recent } else { &formats.old }, &local),
+        None => epoch_seconds(time).0.to_string(),
+    }
+}
+
+///
+/// The width of the time column, for lining up the `?` of a file whose time is unknown.
+///
+pub(crate) fn time_width(formats: &TimeFormats) -> usize {
+    LocalTime::new(SystemTime::UNIX_EPOCH).map_or(0, |local| strftime(&formats.old, &local).chars().count())
+}
+
+///
+/// Names of users and groups, looked up once each; an id without a name is shown as a number.
+///
+#[derive(Default)]
+pub(crate) struct Owners {
+    users: HashMap<u32, String>,
+    groups: HashMap<u32, String>,
+}
+
+impl Owners {
+    pub(crate) fn user(&mut self, uid: u32) -> &str {
+        self.users.entry(uid).or_insert_with(|| user_name(uid).unwrap_or_else(|| uid.to_string()))
+    }
+
+    pub(crate) fn group(&mut self, gid: u32) -> &str {
+        self.groups.entry(gid).or_insert_with(|| group_name(gid).unwrap_or_else(|| gid.to_string()))
+    }
+}
+
+fn user_name(uid: u32) -> Option<String> {
+    let mut buffer = vec![0 as libc::c_char; NAME_BUFFER];
+    loop {
+        /* SAFETY: getpwuid_r only writes into the struct and buffer we hand it */
+        let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+        let mut result = std::ptr::null_mut();
+        let status = unsafe { libc::getpwuid_r(uid, &mut passwd, buffer.as_mut_ptr(), buffer.len(), &mut result) };
+        if status == libc::ERANGE {
+            buffer.resize(buffer.len() * 2, 0);
+            continue;
+        }
+        if status != 0 || result.is_null() {
+            return None;
+        }
+        /* SAFETY: a found entry's name points into the buffer, which is still alive */
+        return Some(unsafe { CStr::from_ptr(passwd.pw_name) }.to_string_lossy().into_owned());
+    }
+}
+
+fn group_name(gid: u32) -> Option<String> {
+    let mut buffer = vec![0 as libc::c_char; NAME_BUFFER];
+    loop {
+        /* SAFETY: getgrgid_r only writes into the struct and buffer we hand it */
+        let mut group: libc::group = unsafe { std::mem::zeroed() };
+        let mut result = std::ptr::null_mut();
+        let status = unsafe { libc::getgrgid_r(gid, &mut group, buffer.as_mut_ptr(), buffer.len(), &mut result) };
+        if status == libc::ERANGE {
+            buffer.resize(buffer.len() * 2, 0);
+            continue;
+        }
+        if status != 0 || result.is_null() {
+            return None;
+        }
+        /* SAFETY: a found entry's name points into the buffer, which is still alive */
+        return Some(unsafe { CStr::from_ptr(group.gr_name) }.to_string_lossy().into_owned());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+    use std::time::{Duration, SystemTime};
+    use common::test_support::TempDir;
+    use crate::config::TimeFormats;
+    use crate::long::{block_size, format_time, human_size, mode_string, Owners};
+
+    #[test]
+    fn test_mode_string() {
+        let dir = TempDir::new("mode");
+        dir.file("a", b"");
+        let mode = |mode: u32| {
+            std::fs::set_permissions(dir.join("a"), std::fs::Permissions::from_mode(mode)).unwrap();
+            mode_string(Some(&std::fs::metadata(dir.join("a")).unwrap()), None)
+        };
+
+        assert_eq!("-rw-r--r--", mode(0o644));
+        assert_eq!("-rwsr-S---", mode(0o6740));
+        assert_eq!("-rwxr-x--T", mode(0o1750));
+        assert_eq!("-rwx--x--t", mode(0o1711));
+        assert!(mode_string(Some(&std::fs::metadata(dir.path()).unwrap()), None).starts_with('d'));
+        assert_eq!("??????????", mode_string(None, None));
+    }
+
+    #[test]
+    fn test_human_size() {
+        assert_eq!("0", human_size(0));
+        assert_eq!("1023", human_size(1023));
+        assert_eq!("1.0K", human_size(1024));
+        assert_eq!("1.1K", human_size(1025));
+        assert_eq!("4.0K", human_size(4096));
+        assert_eq!("10K", human_size(10 * 1024));
+        assert_eq!("10K", human_size(10188));
+        assert_eq!("1.3M", human_size(1_300_000));
+        assert_eq!("1.0M", human_size(1024 * 1024 - 1));
+        assert_eq!("1.0G", human_size(1 << 30));
+    }
+
+    #[test]
+    fn test_block_size() {
+        assert_eq!("4", block_size(8, false));
+        assert_eq!("1", block_size(1, false));
+        assert_eq!("4.0K", block_size(8, true));
+        assert_eq!("0", block_size(0, true));
+    }
+
+    #[test]
+    fn test_format_time() {
+        let formats = TimeFormats { recent: "recent".to_string(), old: "old".to_string() };
+        let now = SystemTime::now();
+
+        assert_eq!("recent", format_time(now - Duration::from_secs(60), &formats, &mut now.clone()));
+        assert_eq!("old", format_time(now - Duration::from_secs(200 * 86400), &formats, &mut now.clone()));
+        assert_eq!("old", format_time(now + Duration::from_secs(86400), &formats, &mut now.clone()));
+    }
+
+    #[test]
+    fn test_owners() {
+        let mut owners = Owners::default();
+        assert_eq!("root", owners.user(0));
+        assert_eq!("root", owners.group(0));
+        assert_eq!("4000000000", owners.user(4_000_000_000));
+    }
+}
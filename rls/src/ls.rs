@@ -0,0 +1,243 @@
+use std::ffi::OsStr;
+use std::io::{self, BufWriter, Write};
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use common::input::known_error::{report, KnownError};
+use crate::color::Colors;
+use crate::config::{Config, Format};
+use crate::entry::{join, read_directory, Entry, CURRENT_DIRECTORY};
+use crate::errors::write_error;
+use crate::help::{help, version};
+use crate::name::render_name;
+use crate::printer::Printer;
+use crate::sort::sort_entries;
+
+const SUCCESS: i32 = 0;
+const TROUBLE: i32 = 2;
+
+///
+/// Run `ls` with command line arguments; the first argument is the program name.  Returns the exit
+/// status: 0 when all went well, 1 for trouble with a file found while listing, and 2 for trouble
+/// with the command line or a file named on it.
+///
+pub(crate) fn ls(args: &[String], out: &mut dyn Write, err: &mut dyn Write) -> i32 {
+    let config = Config::new(args);
+    if let Some(first) = config.errors.first() {
+        report(err, &config.errors);
+        return first.code;
+    }
+
+    if config.print_help_and_exit {
+        return help(out).map_or(TROUBLE, |_| SUCCESS);
+    }
+    if config.print_version_and_exit {
+        return version(out).map_or(TROUBLE, |_| SUCCESS);
+    }
+
+    report(err, &config.warnings);
+    let mut warnings = vec![];
+    let colors = if config.color { Colors::from_environment(&mut warnings) } else { None };
+    report(err, &warnings);
+
+    let mut out = BufWriter::new(out);
+    let mut lister = Lister { config: &config, printer: Printer::new(&config, colors), status: SUCCESS };
+    if let Err(error) = lister.run(&mut out, err).and_then(|_| out.flush()) {
+        report(err, &[write_error(&error)]);
+        return TROUBLE;
+    }
+    lister.status
+}
+
+///
+/// Lists what's named on the command line, and keeps the worst status met along the way.
+///
+struct Lister<'c> {
+    config: &'c Config,
+    printer: Printer<'c>,
+    status: i32,
+}
+
+impl Lister<'_> {
+    ///
+    /// Files named on the command line are listed together first, then each directory in turn.
+    ///
+    fn run(&mut self, out: &mut dyn Write, err: &mut dyn Write) -> io::Result<()> {
+        let config = self.config;
+        let long = config.format == Format::Long;
+        /* a link to a directory is that directory, unless the link itself is to be shown */
+        let follow = !config.directory && !long;
+
+        let operands = if config.files.is_empty() { vec![CURRENT_DIRECTORY.to_string()] } else { config.files.clone() };
+        let mut files = vec![];
+        let mut directories = vec![];
+        for operand in &operands {
+            let mut problems = vec![];
+            let found = Entry::operand(operand, follow, long, &mut problems);
+            self.problems(out, err, &problems)?;
+            match found {
+                Ok(entry) if entry.is_directory() && !config.directory => directories.push(entry),
+                Ok(entry) => files.push(entry),
+                Err(error) => self.problems(out, err, &[error])?,
+            }
+        }
+
+        self.sort(&mut files);
+        self.sort(&mut directories);
+        if !files.is_empty() {
+            self.printer.list(out, &files, false)?;
+            if !directories.is_empty() {
+                out.write_all(b"\n")?;
+            }
+        }
+
+        /* a lone directory isn't named, unless there's more to come from -R */
+        let headers = config.recursive || !files.is_empty() || config.files.len() > 1 || directories.len() > 1;
+        for directory in &directories {
+            self.directory(out, err, &directory.name, true, headers)?;
+        }
+        self.printer.finish(out)
+    }
+
+    ///
+    /// List a directory's contents, then, with `-R`, each directory among them.
+    ///
+    fn directory(&mut self, out: &mut dyn Write, err: &mut dyn Write, name: &[u8], operand: bool, header: bool) -> io::Result<()> {
+        let config = self.config;
+        let mut problems = vec![];
+        let mut entries = match read_directory(Path::new(OsStr::from_bytes(name)), name, config.hidden, config.format == Format::Long, operand, &mut problems) {
+            Ok(entries) => entries,
+            Err(error) => return self.problems(out, err, &[error]),
+        };
+
+        if header {
+            self.printer.header(out, name)?;
+        }
+        self.problems(out, err, &problems)?;
+        self.sort(&mut entries);
+        self.printer.list(out, &entries, true)?;
+
+        if config.recursive {
+            for entry in entries.iter().filter(|entry| entry.is_directory() && !entry.is_dot()) {
+                self.directory(out, err, &join(name, &entry.name), false, true)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn sort(&self, entries: &mut [Entry]) {
+        let config = self.config;
+        sort_entries(entries, config.sort, config.reverse, |entry| render_name(&entry.name, config).width);
+    }
+
+    ///
+    /// Report problems where they happen among what's written, and remember the worst of them.
+    ///
+    fn problems(&mut self, out: &mut dyn Write, err: &mut dyn Write, problems: &[KnownError]) -> io::Result<()> {
+        if problems.is_empty() {
+            return Ok(());
+        }
+        out.flush()?;
+        report(err, problems);
+        self.status = problems.iter().map(|problem| problem.code).fold(self.status, i32::max);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+    use common::test_support::TempDir;
+    use crate::ls::ls;
+
+    fn run(args: &[&str]) -> (i32, String, String) {
+        let args: Vec<String> = std::iter::once("ls").chain(args.iter().copied()).map(String::from).collect();
+        let mut out = vec![];
+        let mut err = vec![];
+        let status = ls(&args, &mut out, &mut err);
+        (status, String::from_utf8(out).unwrap(), String::from_utf8(err).unwrap())
+    }
+
+    fn tree() -> TempDir {
+        let dir = TempDir::new("tree");
+        dir.file("b", b"");
+        dir.file("a", b"12345");
+        dir.file("c d", b"");
+        dir.file(".hidden", b"");
+        std::fs::create_dir(dir.join("sub")).unwrap();
+        dir.file("sub/x", b"");
+        std::fs::create_dir(dir.join("sub/deeper")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_listing() {
+        let dir = tree();
+        let path = dir.path();
+
+        assert_eq!((0, "a\nb\nc d\nsub\n".to_string(), String::new()), run(&["-1", path]));
+        assert_eq!((0, ".\n..\n.hidden\na\nb\nc d\nsub\n".to_string(), String::new()), run(&["-1a", path]));
+        assert_eq!((0, "sub\nc d\nb\na\n".to_string(), String::new()), run(&["-1r", path]));
+        assert_eq!((0, "a  b  c d  sub\n".to_string(), String::new()), run(&["-C", "-w80", path]));
+        assert_eq!((0, " a   b  'c d'   sub\n".to_string(), String::new()), run(&["-x", "-w80", "-T0", "--quoting-style=shell-escape", path]));
+        assert_eq!((0, "a  c d\nb  sub\n".to_string(), String::new()), run(&["-C", "-w10", path]));
+    }
+
+    #[test]
+    fn test_sections() {
+        let dir = tree();
+        let a = dir.join("a").to_str().unwrap().to_string();
+        let sub = dir.join("sub").to_str().unwrap().to_string();
+
+        let expected = format!("{}\n\n{}:\ndeeper\nx\n", a, sub);
+        assert_eq!((0, expected, String::new()), run(&["-1", &sub, &a]));
+        let expected = format!("{}:\ndeeper\nx\n\n{}/deeper:\n", sub, sub);
+        assert_eq!((0, expected, String::new()), run(&["-1R", &sub]));
+        assert_eq!((0, format!("{}\n", sub), String::new()), run(&["-1d", &sub]));
+    }
+
+    #[test]
+    fn test_long() {
+        let dir = tree();
+        std::fs::set_permissions(dir.join("a"), std::fs::Permissions::from_mode(0o640)).unwrap();
+        let (status, out, err) = run(&["-l", "--time-style=+T", dir.join("a").to_str().unwrap()]);
+
+        assert_eq!((0, String::new()), (status, err));
+        let fields: Vec<&str> = out.split_whitespace().collect();
+        assert_eq!("-rw-r-----", fields[0]);
+        assert_eq!(vec!["1", "5", "T"], vec![fields[1], fields[4], fields[5]]);
+        assert!(out.ends_with(&format!(" 5 T {}\n", dir.join("a").to_str().unwrap())));
+
+        let (status, out, _) = run(&["-ls", dir.join("sub").to_str().unwrap()]);
+        assert_eq!(0, status);
+        assert!(out.starts_with("total "));
+    }
+
+    #[test]
+    fn test_errors() {
+        let dir = tree();
+        let missing = dir.join("missing").to_str().unwrap().to_string();
+
+        let (status, out, err) = run(&["-1", &missing, dir.join("a").to_str().unwrap()]);
+        assert_eq!(2, status);
+        assert_eq!(format!("{}\n", dir.join("a").to_str().unwrap()), out);
+        assert_eq!(format!("ls: cannot access '{}': No such file or directory\n", missing), err);
+
+        assert_eq!((2, String::new(), "ls: invalid line width: 'x'\n".to_string()), run(&["-w", "x"]));
+        let (status, _, err) = run(&["--sort=nope"]);
+        assert_eq!(1, status);
+        assert!(err.starts_with("ls: invalid argument 'nope' for '--sort'\n"));
+        let (status, _, err) = run(&["-y"]);
+        assert_eq!(2, status);
+        assert_eq!("ls: invalid option -- 'y'\nTry 'ls --help' for more information.\n", err);
+    }
+
+    #[test]
+    fn test_help_and_version() {
+        let (status, out, _) = run(&["--version"]);
+        assert_eq!(0, status);
+        assert!(out.starts_with("rls "));
+        let (status, out, _) = run(&["--help"]);
+        assert_eq!(0, status);
+        assert!(out.contains("Usage: ls [OPTION]... [FILE]..."));
+    }
+}
@@ -0,0 +1,23 @@
+mod color;
+mod config;
+mod entry;
+mod errors;
+mod help;
+mod layout;
+mod long;
+mod ls;
+mod name;
+mod printer;
+mod sort;
+mod version;
+
+use crate::ls::ls;
+
+///
+/// Main program entrypoint.
+///
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let status = ls(&args, &mut std::io::stdout(), &mut std::io::stderr());
+    std::process::exit(status);
+}
@@ -0,0 +1,126 @@
+use common::output::quote::{quote, QuotingStyle};
+use unicode_width::UnicodeWidthChar;
+use crate::config::Config;
+
+const HIDDEN_CONTROL: u8 = b'?';
+/* quoted in headers, which end with one */
+const HEADER_END: u8 = b':';
+
+///
+/// A name ready to be written: quoted, with its width on the screen.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Name {
+    pub(crate) bytes: Vec<u8>,
+    pub(crate) width: usize,
+    /* whether quotes were put around it */
+    pub(crate) quoted: bool,
+}
+
+///
+/// Quote a name in the configured style, then, with `-q`, hide whatever still can't be shown.
+///
+pub(crate) fn render_name(name: &[u8], config: &Config) -> Name {
+    let mut bytes = quote(name, config.quoting);
+    if config.hide_control {
+        bytes = hide_control(&bytes);
+    }
+    let quoted = bytes.first().is_some_and(|&first| (first == b'\'' || first == b'"') && name.first() != Some(&first));
+    Name { width: display_width(&bytes), bytes, quoted }
+}
+
+///
+/// Quote a directory's name for its header; a `:` in it is quoted too, so it can't be taken for
+/// the one that ends the header.
+///
+pub(crate) fn render_header(name: &[u8], config: &Config) -> Vec<u8> {
+    let colon = name.contains(&HEADER_END);
+    let mut bytes = match config.quoting {
+        QuotingStyle::Shell if colon => quote(name, QuotingStyle::ShellAlways),
+        QuotingStyle::ShellEscape if colon => quote(name, QuotingStyle::ShellEscapeAlways),
+        QuotingStyle::C | QuotingStyle::Escape if colon => quote(name, config.quoting).into_iter()
+            .flat_map(|byte| if byte == HEADER_END { vec![b'\\', byte] } else { vec![byte] })
+            .collect(),
+        style => quote(name, style),
+    };
+    if config.hide_control {
+        bytes = hide_control(&bytes);
+    }
+    bytes
+}
+
+///
+/// Whether names that aren't quoted get a space before them, so they line up with the ones that
+/// are; only in styles that quote some names and not others, and only where names are aligned.
+///
+pub(crate) fn aligns_quotes(config: &Config, aligned: bool) -> bool {
+    aligned && matches!(config.quoting, QuotingStyle::Shell | QuotingStyle::ShellEscape)
+}
+
+///
+/// Write each unprintable character, and each byte that isn't `UTF-8`, as `?`.
+///
+fn hide_control(text: &[u8]) -> Vec<u8> {
+    let mut hidden = vec![];
+    for chunk in text.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            if c.is_control() {
+                hidden.push(HIDDEN_CONTROL);
+            } else {
+                let mut buffer = [0u8; 4];
+                hidden.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+            }
+        }
+        hidden.extend(chunk.invalid().iter().map(|_| HIDDEN_CONTROL));
+    }
+    hidden
+}
+
+///
+/// The columns text takes on the screen: control characters take none, and a byte that isn't
+/// `UTF-8` takes one.
+///
+pub(crate) fn display_width(text: &[u8]) -> usize {
+    text.utf8_chunks()
+        .map(|chunk| chunk.valid().chars().map(|c| c.width().unwrap_or(0)).sum::<usize>() + chunk.invalid().len())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use common::output::quote::QuotingStyle;
+    use crate::config::Config;
+    use crate::name::{display_width, render_header, render_name, Name};
+
+    fn render(name: &[u8], quoting: QuotingStyle, hide_control: bool) -> Name {
+        render_name(name, &Config { quoting, hide_control, ..Config::default() })
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(Name { bytes: b"a\nb".to_vec(), width: 2, quoted: false }, render(b"a\nb", QuotingStyle::Literal, false));
+        assert_eq!(Name { bytes: b"a?b?".to_vec(), width: 4, quoted: false }, render(b"a\nb\xff", QuotingStyle::Literal, true));
+        assert_eq!(Name { bytes: b"'a?b'".to_vec(), width: 5, quoted: true }, render(b"a\nb", QuotingStyle::Shell, true));
+        assert_eq!(Name { bytes: b"'a'$'\\n''b'".to_vec(), width: 11, quoted: true }, render(b"a\nb", QuotingStyle::ShellEscape, true));
+        assert_eq!(Name { bytes: b"\"e'f\"".to_vec(), width: 5, quoted: true }, render(b"e'f", QuotingStyle::ShellEscape, true));
+        assert_eq!(Name { bytes: b"'x'".to_vec(), width: 3, quoted: false }, render(b"'x'", QuotingStyle::Literal, true));
+    }
+
+    #[test]
+    fn test_render_header() {
+        let header = |name: &[u8], quoting| String::from_utf8(render_header(name, &Config { quoting, ..Config::default() })).unwrap();
+
+        assert_eq!("./a:b", header(b"./a:b", QuotingStyle::Literal));
+        assert_eq!("'./a:b'", header(b"./a:b", QuotingStyle::ShellEscape));
+        assert_eq!("./ab", header(b"./ab", QuotingStyle::ShellEscape));
+        assert_eq!("\"./a\\:b\"", header(b"./a:b", QuotingStyle::C));
+    }
+
+    #[test]
+    fn test_display_width() {
+        assert_eq!(3, display_width("abc".as_bytes()));
+        assert_eq!(4, display_width("日本".as_bytes()));
+        assert_eq!(2, display_width(b"a\xff"));
+        assert_eq!(1, display_width(b"\x01a"));
+    }
+}
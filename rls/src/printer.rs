@@ -0,0 +1,306 @@
+use std::io::{self, Write};
+use std::os::unix::fs::{FileTypeExt, MetadataExt};
+use std::time::SystemTime;
+use crate::color::Colors;
+use crate::config::{Config, Format};
+use crate::entry::Entry;
+use crate::layout::{columns, indent};
+use crate::long::{block_size, blocks, format_time, human_size, mode_string, time_width, Owners};
+use crate::name::{aligns_quotes, display_width, render_header, render_name, Name};
+
+const UNKNOWN: &str = "?";
+const LINK_ARROW: &[u8] = b" -> ";
+
+///
+/// A file about to be listed, with the columns that go before its name.
+///
+struct Item<'e> {
+    entry: &'e Entry,
+    name: Name,
+    inode: String,
+    blocks: String,
+}
+
+///
+/// The columns of a long listing, before they're lined up.
+///
+struct Row {
+    mode: String,
+    links: String,
+    owner: String,
+    group: String,
+    size: Size,
+    time: Option<String>,
+}
+
+enum Size {
+    Bytes(String),
+    Device(String, String),
+}
+
+///
+/// Writes listings the way the options ask, and keeps track of what's been written so sections are
+/// set apart and colors are put back at the end.
+///
+pub(crate) struct Printer<'c> {
+    config: &'c Config,
+    colors: Option<Colors>,
+    /* whether any color has been written; the first is preceded by a reset */
+    used_color: bool,
+    owners: Owners,
+    now: SystemTime,
+    /* whether a header has been written, so the next is set apart */
+    headed: bool,
+}
+
+impl<'c> Printer<'c> {
+    pub(crate) fn new(config: &'c Config, colors: Option<Colors>) -> Printer<'c> {
+        Printer { config, colors, used_color: false, owners: Owners::default(), now: SystemTime::now(), headed: false }
+    }
+
+    ///
+    /// Write the name of a directory before its contents; every header but the first has a blank
+    /// line before it.
+    ///
+    pub(crate) fn header(&mut self, out: &mut dyn Write, name: &[u8]) -> io::Result<()> {
+        if self.headed {
+            out.write_all(b"\n")?;
+        }
+        self.headed = true;
+        out.write_all(&render_header(name, self.config))?;
+        out.write_all(b":\n")
+    }
+
+    ///
+    /// Write a listing: a directory's contents, with their total size when sizes are shown, or the
+    /// files named on the command line.
+    ///
+    pub(crate) fn list(&mut self, out: &mut dyn Write, entries: &[Entry], directory: bool) -> io::Result<()> {
+        let config = self.config;
+        let long = config.format == Format::Long;
+        if directory && (long || config.size) {
+            let total: u64 = entries.iter().filter_map(|entry| entry.metadata.as_ref()).map(MetadataExt::blocks).sum();
+            writeln!(out, "total {}", block_size(total, config.human_readable))?;
+        }
+
+        let items: Vec<Item> = entries.iter()
+            .map(|entry| Item {
+                entry,
+                name: render_name(&entry.name, config),
+                inode: entry.metadata.as_ref().map_or_else(|| UNKNOWN.to_string(), |metadata| metadata.ino().to_string()),
+                blocks: blocks(entry.metadata.as_ref(), config.human_readable),
+            })
+            .collect();
+        let aligned = long || (matches!(config.format, Format::Columns | Format::Across) && config.line_length != 0);
+        let pad = aligns_quotes(config, aligned) && items.iter().any(|item| item.name.quoted);
+        let inode_width = items.iter().map(|item| item.inode.len()).max().unwrap_or(0);
+        let block_width = items.iter().map(|item| item.blocks.len()).max().unwrap_or(0);
+        let frills = |item: &Item| {
+            let mut frills = String::new();
+            if config.inode {
+                frills += &format!("{:>width$} ", item.inode, width = inode_width);
+            }
+            if config.size {
+                frills += &format!("{:>width$} ", item.blocks, width = block_width);
+            }
+            frills
+        };
+
+        match config.format {
+            Format::Long => self.long(out, &items, &frills, pad),
+            Format::OnePerLine => {
+                for item in &items {
+                    self.normal(out)?;
+                    let frills = frills(item);
+                    out.write_all(frills.as_bytes())?;
+                    self.name(out, item, pad, frills.len())?;
+                    out.write_all(b"\n")?;
+                }
+                Ok(())
+            }
+            Format::Columns | Format::Across => {
+                let widths: Vec<usize> = items.iter().map(|item| frills(item).len() + item.name.width + usize::from(pad && !item.name.quoted)).collect();
+                self.columns(out, &items, &widths, &frills, pad)
+            }
+        }
+    }
+
+    fn columns(&mut self, out: &mut dyn Write, items: &[Item], widths: &[usize], frills: &dyn Fn(&Item) -> String, pad: bool) -> io::Result<()> {
+        let by_columns = self.config.format == Format::Columns;
+        let layout = columns(widths, self.config.line_length, by_columns);
+        /* tabs would be colored along with the names around them */
+        let tab_size = if self.config.color { 0 } else { self.config.tab_size };
+
+        for row in 0..layout.rows {
+            let mut position = 0;
+            let in_row: Vec<usize> = if by_columns {
+                (row..items.len()).step_by(layout.rows).collect()
+            } else {
+                (row * layout.widths.len()..items.len().min((row + 1) * layout.widths.len())).collect()
+            };
+            for (column, &index) in in_row.iter().enumerate() {
+                if column > 0 {
+                    let previous = in_row[column - 1];
+                    indent(out, position + widths[previous], position + layout.widths[column - 1], tab_size)?;
+                    position += layout.widths[column - 1];
+                }
+                self.normal(out)?;
+                let item = &items[index];
+                let frills = frills(item);
+                out.write_all(frills.as_bytes())?;
+                self.name(out, item, pad, position + frills.len())?;
+            }
+            out.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    fn long(&mut self, out: &mut dyn Write, items: &[Item], frills: &dyn Fn(&Item) -> String, pad: bool) -> io::Result<()> {
+        let config = self.config;
+        let rows: Vec<Row> = items.iter().map(|item| self.row(item.entry)).collect();
+        let links_width = rows.iter().map(|row| row.links.len()).max().unwrap_or(0);
+        let owner_width = rows.iter().map(|row| display_width(row.owner.as_bytes())).max().unwrap_or(0);
+        let group_width = rows.iter().map(|row| display_width(row.group.as_bytes())).max().unwrap_or(0);
+        let major_width = rows.iter().filter_map(|row| match &row.size { Size::Device(major, _) => Some(major.len()), _ => None }).max().unwrap_or(0);
+        let minor_width = rows.iter().filter_map(|row| match &row.size { Size::Device(_, minor) => Some(minor.len()), _ => None }).max().unwrap_or(0);
+        let size_width = rows.iter()
+            .map(|row| match &row.size { Size::Bytes(size) => size.len(), Size::Device(..) => major_width + 2 + minor_width })
+            .max()
+            .unwrap_or(0);
+        let time_width = if rows.iter().any(|row| row.time.is_none()) { time_width(&config.time_formats) } else { 0 };
+
+        for (item, row) in items.iter().zip(rows) {
+            let mut line = frills(item);
+            line += &format!("{} {:>width$} ", row.mode, row.links, width = links_width);
+            line += &format!("{}{} ", row.owner, " ".repeat(owner_width - display_width(row.owner.as_bytes())));
+            line += &format!("{}{} ", row.group, " ".repeat(group_width - display_width(row.group.as_bytes())));
+            match &row.size {
+                Size::Bytes(size) => line += &format!("{:>width$} ", size, width = size_width),
+                Size::Device(major, minor) => {
+                    line += &format!("{:>major$}, {:>minor$} ", major, minor, major = size_width - 2 - minor_width, minor = minor_width);
+                }
+            }
+            match &row.time {
+                Some(time) => line += &format!("{} ", time),
+                None => line += &format!("{:>width$} ", UNKNOWN, width = time_width),
+            }
+
+            self.normal(out)?;
+            out.write_all(line.as_bytes())?;
+            let start = display_width(line.as_bytes());
+            let width = self.name(out, item, pad, start)?;
+            if let Some(link) = &item.entry.link
+                && let Some(target) = &link.target {
+                out.write_all(LINK_ARROW)?;
+                let sequence = self.colors.as_ref().and_then(|colors| colors.target_sequence(item.entry)).map(<[u8]>::to_vec);
+                self.colored(out, &render_name(target, config), sequence, false, start + width + LINK_ARROW.len())?;
+            }
+            out.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    fn row(&mut self, entry: &Entry) -> Row {
+        let Some(metadata) = &entry.metadata else {
+            return Row {
+                mode: mode_string(None, entry.file_type),
+                links: UNKNOWN.to_string(),
+                owner: UNKNOWN.to_string(),
+                group: UNKNOWN.to_string(),
+                size: Size::Bytes(UNKNOWN.to_string()),
+                time: None,
+            };
+        };
+
+        let file_type = metadata.file_type();
+        let size = if file_type.is_block_device() || file_type.is_char_device() {
+            let device = metadata.rdev();
+            Size::Device(libc::major(device).to_string(), libc::minor(device).to_string())
+        } else if self.config.human_readable {
+            Size::Bytes(human_size(metadata.len()))
+        } else {
+            Size::Bytes(metadata.len().to_string())
+        };
+        let time = metadata.modified().ok().map(|modified| format_time(modified, &self.config.time_formats, &mut self.now));
+        Row {
+            mode: mode_string(Some(metadata), None),
+            links: metadata.nlink().to_string(),
+            owner: self.owners.user(metadata.uid()).to_string(),
+            group: self.owners.group(metadata.gid()).to_string(),
+            size,
+            time,
+        }
+    }
+
+    ///
+    /// Write an item's name, colored by what it is; returns the columns it took.
+    ///
+    fn name(&mut self, out: &mut dyn Write, item: &Item, pad: bool, start: usize) -> io::Result<usize> {
+        let sequence = self.colors.as_ref().and_then(|colors| colors.name_sequence(item.entry)).map(<[u8]>::to_vec);
+        self.colored(out, &item.name, sequence, pad && !item.name.quoted, start)
+    }
+
+    ///
+    /// Write a name: the space that lines it up with quoted names, then the name in its color.
+    /// Once colored, what follows is set back, and cleared to the end of the line if the name may
+    /// have wrapped onto the next one.
+    ///
+    fn colored(&mut self, out: &mut dyn Write, name: &Name, sequence: Option<Vec<u8>>, pad: bool, start: usize) -> io::Result<usize> {
+        if pad {
+            out.write_all(b" ")?;
+        }
+        let Some(colors) = &self.colors else {
+            out.write_all(&name.bytes)?;
+            return Ok(name.width + usize::from(pad));
+        };
+
+        let ends = sequence.is_some() || colors.colors_normal();
+        if let Some(sequence) = sequence {
+            put(out, &mut self.used_color, colors, &colors.start(&sequence))?;
+        }
+        out.write_all(&name.bytes)?;
+        if ends {
+            put(out, &mut self.used_color, colors, &colors.end())?;
+            let length = name.bytes.len() + usize::from(pad);
+            let line_length = self.config.line_length;
+            if line_length != 0 && start / line_length != (start + length).saturating_sub(1) / line_length {
+                put(out, &mut self.used_color, colors, colors.clear_to_end())?;
+            }
+        }
+        Ok(name.width + usize::from(pad))
+    }
+
+    ///
+    /// Set the color for what isn't a name, when there's one for it.
+    ///
+    fn normal(&mut self, out: &mut dyn Write) -> io::Result<()> {
+        if let Some(colors) = &self.colors
+            && let Some(normal) = colors.normal() {
+            put(out, &mut self.used_color, colors, &normal)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Put the terminal back the way it was, if colors were written.
+    ///
+    pub(crate) fn finish(&mut self, out: &mut dyn Write) -> io::Result<()> {
+        if self.used_color
+            && let Some(colors) = &self.colors
+            && let Some(restore) = colors.finish() {
+            out.write_all(&restore)?;
+        }
+        Ok(())
+    }
+}
+
+///
+/// Write a color sequence; the very first one is preceded by a reset, so names start from plain text.
+///
+fn put(out: &mut dyn Write, used_color: &mut bool, colors: &Colors, sequence: &[u8]) -> io::Result<()> {
+    if !*used_color {
+        *used_color = true;
+        out.write_all(&colors.end())?;
+    }
+    out.write_all(sequence)
+}
@@ -0,0 +1,91 @@
+use std::time::SystemTime;
+use crate::config::Sort;
+use crate::entry::Entry;
+use crate::version::compare_versions;
+
+const EXTENSION_SEPARATOR: u8 = b'.';
+
+///
+/// Sort entries; ties are broken by name, and `reverse` turns the whole order around.  `width`
+/// gives how wide a name is shown, for [Sort::Width].
+///
+pub(crate) fn sort_entries(entries: &mut [Entry], sort: Sort, reverse: bool, width: impl Fn(&Entry) -> usize) {
+    if sort == Sort::None {
+        return;
+    }
+
+    entries.sort_by(|a, b| {
+        let by_name = || a.name.cmp(&b.name);
+        let ordering = match sort {
+            Sort::Name | Sort::None => by_name(),
+            Sort::Time => modified(b).cmp(&modified(a)).then_with(by_name),
+            Sort::Size => size(b).cmp(&size(a)).then_with(by_name),
+            Sort::Extension => extension(&a.name).cmp(extension(&b.name)).then_with(by_name),
+            Sort::Version => compare_versions(&a.name, &b.name).then_with(by_name),
+            Sort::Width => width(a).cmp(&width(b)).then_with(by_name),
+        };
+        if reverse { ordering.reverse() } else { ordering }
+    });
+}
+
+fn modified(entry: &Entry) -> SystemTime {
+    entry.metadata.as_ref().and_then(|metadata| metadata.modified().ok()).unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+fn size(entry: &Entry) -> u64 {
+    entry.metadata.as_ref().map_or(0, |metadata| metadata.len())
+}
+
+///
+/// Everything from the last `.` on; a name without one has no extension, and sorts first.
+///
+fn extension(name: &[u8]) -> &[u8] {
+    name.iter().rposition(|&byte| byte == EXTENSION_SEPARATOR).map_or(&[], |position| &name[position..])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::time::{Duration, SystemTime};
+    use common::test_support::TempDir;
+    use crate::config::{Hidden, Sort};
+    use crate::entry::{read_directory, Entry};
+    use crate::sort::{extension, sort_entries};
+
+    fn sorted(dir: &TempDir, sort: Sort, reverse: bool) -> Vec<String> {
+        let mut entries: Vec<Entry> = read_directory(dir.path().as_ref(), b"dir", Hidden::Omit, false, true, &mut vec![]).unwrap();
+        sort_entries(&mut entries, sort, reverse, |entry| entry.name.len());
+        entries.iter().map(|entry| String::from_utf8_lossy(&entry.name).into_owned()).collect()
+    }
+
+    fn touch(dir: &TempDir, name: &str, contents: &[u8], age: u64) {
+        dir.file(name, contents);
+        let time = SystemTime::now() - Duration::from_secs(age);
+        File::options().write(true).open(dir.join(name)).unwrap().set_modified(time).unwrap();
+    }
+
+    #[test]
+    fn test_sorts() {
+        let dir = TempDir::new("sorts");
+        touch(&dir, "b.txt", b"12345", 30);
+        touch(&dir, "a10.c", b"1", 10);
+        touch(&dir, "a9.c", b"123", 20);
+        touch(&dir, "c", b"123", 25);
+
+        assert_eq!(vec!["a10.c", "a9.c", "b.txt", "c"], sorted(&dir, Sort::Name, false));
+        assert_eq!(vec!["c", "b.txt", "a9.c", "a10.c"], sorted(&dir, Sort::Name, true));
+        assert_eq!(vec!["a10.c", "a9.c", "c", "b.txt"], sorted(&dir, Sort::Time, false));
+        assert_eq!(vec!["b.txt", "c", "a9.c", "a10.c"], sorted(&dir, Sort::Time, true));
+        assert_eq!(vec!["b.txt", "a9.c", "c", "a10.c"], sorted(&dir, Sort::Size, false));
+        assert_eq!(vec!["c", "a10.c", "a9.c", "b.txt"], sorted(&dir, Sort::Extension, false));
+        assert_eq!(vec!["a9.c", "a10.c", "b.txt", "c"], sorted(&dir, Sort::Version, false));
+        assert_eq!(vec!["c", "a9.c", "a10.c", "b.txt"], sorted(&dir, Sort::Width, false));
+    }
+
+    #[test]
+    fn test_extension() {
+        assert_eq!(b".gz", extension(b"a.tar.gz"));
+        assert_eq!(b".bashrc", extension(b".bashrc"));
+        assert_eq!(b"", extension(b"Makefile"));
+    }
+}
@@ -0,0 +1,137 @@
+use std::cmp::Ordering;
+
+///
+/// Compare file names as `-v` does, the way `filevercmp` does: `.` sorts first, then `..`, then
+/// other hidden names; then names are compared without their suffixes (`.tar.gz`, `.c`), and only
+/// if those are equal, with them.  Runs of digits compare as numbers.
+///
+pub(crate) fn compare_versions(a: &[u8], b: &[u8]) -> Ordering {
+    match (a.is_empty(), b.is_empty()) {
+        (true, true) => return Ordering::Equal,
+        (true, false) => return Ordering::Less,
+        (false, true) => return Ordering::Greater,
+        (false, false) => {}
+    }
+
+    for special in [&b"."[..], b".."] {
+        match (a == special, b == special) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            (false, false) => {}
+        }
+    }
+    match (a[0] == b'.', b[0] == b'.') {
+        (true, false) => return Ordering::Less,
+        (false, true) => return Ordering::Greater,
+        _ => {}
+    }
+
+    let a_prefix = prefix_length(a);
+    let b_prefix = prefix_length(b);
+    let ordering = compare_runs(&a[..a_prefix], &b[..b_prefix]);
+    if ordering != Ordering::Equal || (a_prefix == a.len() && b_prefix == b.len()) {
+        return ordering;
+    }
+    compare_runs(a, b)
+}
+
+///
+/// The length of a name without its suffix: the longest run at its end of `.` followed by a letter
+/// or `~`, then letters, digits and `~`.  The first character is never part of the suffix.
+///
+fn prefix_length(name: &[u8]) -> usize {
+    let suffix_character = |c: u8| c.is_ascii_alphanumeric() || c == b'~';
+    let mut prefix = 0;
+    let mut position = 0;
+
+    while position < name.len() {
+        position += 1;
+        prefix = position;
+        while position + 1 < name.len() && name[position] == b'.' && (name[position + 1].is_ascii_alphabetic() || name[position + 1] == b'~') {
+            position += 2;
+            while position < name.len() && suffix_character(name[position]) {
+                position += 1;
+            }
+        }
+    }
+    prefix
+}
+
+///
+/// Compare alternating runs of non-digits, character by character with letters first and `~`
+/// before everything, and digits, as numbers.
+///
+fn compare_runs(a: &[u8], b: &[u8]) -> Ordering {
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        while (i < a.len() && !a[i].is_ascii_digit()) || (j < b.len() && !b[j].is_ascii_digit()) {
+            let ordering = weight(a.get(i).copied()).cmp(&weight(b.get(j).copied()));
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+            i += 1;
+            j += 1;
+        }
+
+        while i < a.len() && a[i] == b'0' {
+            i += 1;
+        }
+        while j < b.len() && b[j] == b'0' {
+            j += 1;
+        }
+        let a_end = i + a[i..].iter().take_while(|c| c.is_ascii_digit()).count();
+        let b_end = j + b[j..].iter().take_while(|c| c.is_ascii_digit()).count();
+        let ordering = (a_end - i).cmp(&(b_end - j)).then_with(|| a[i..a_end].cmp(&b[j..b_end]));
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+        i = a_end;
+        j = b_end;
+    }
+
+    Ordering::Equal
+}
+
+fn weight(byte: Option<u8>) -> i32 {
+    match byte {
+        None => 0,
+        Some(digit) if digit.is_ascii_digit() => 0,
+        Some(b'~') => -1,
+        Some(letter) if letter.is_ascii_alphabetic() => letter as i32,
+        Some(other) => other as i32 + 256,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+    use crate::version::{compare_versions, prefix_length};
+
+    fn sorted(mut names: Vec<&str>) -> Vec<&str> {
+        names.sort_by(|a, b| compare_versions(a.as_bytes(), b.as_bytes()));
+        names
+    }
+
+    #[test]
+    fn test_order() {
+        assert_eq!(
+            vec![".", "..", ".b", "a1", "a2", "a10", "b~", "b", "b.c"],
+            sorted(vec!["b.c", "a10", "..", "b", ".b", "a2", "b~", "a1", "."])
+        );
+        assert_eq!(
+            vec!["foo-1.2.tar.gz", "foo-1.10.tar.gz", "foo-1.10.tgz"],
+            sorted(vec!["foo-1.10.tgz", "foo-1.10.tar.gz", "foo-1.2.tar.gz"])
+        );
+        assert_eq!(Ordering::Equal, compare_versions(b"file007", b"file7"));
+    }
+
+    #[test]
+    fn test_prefix_length() {
+        assert_eq!(7, prefix_length(b"foo-1.2.tar.gz"));
+        assert_eq!(1, prefix_length(b"a.b"));
+        assert_eq!(7, prefix_length(b".bashrc"));
+        assert_eq!(3, prefix_length(b"a.1"));
+        assert_eq!(2, prefix_length(b".a"));
+    }
+}
@@ -0,0 +1,132 @@
+use common::fs::permissions::Mode;
+use common::input::flags::flags_options::{parse_options, OptionSpec, ParsedArgument};
+use common::input::known_error::KnownError;
+use crate::errors::{invalid_mode, missing_operand, option_error};
+
+const OPTIONS: [OptionSpec; 5] = [
+    OptionSpec::required("mode", Some('m'), Some("mode")),
+    OptionSpec::flag("parents", Some('p'), Some("parents")),
+    OptionSpec::flag("verbose", Some('v'), Some("verbose")),
+    OptionSpec::flag("help", None, Some("help")),
+    OptionSpec::flag("version", None, Some("version")),
+];
+
+///
+/// Storage for the application configuration.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct Config {
+    pub(crate) print_help_and_exit: bool,
+    pub(crate) print_version_and_exit: bool,
+
+    /* missing parents are made, and directories that exist already aren't complained about */
+    pub(crate) parents: bool,
+    /* the mode from `-m`, made to `a=rwx`; without it, directories are made as the umask allows */
+    pub(crate) mode: Option<Mode>,
+    pub(crate) verbose: bool,
+
+    pub(crate) directories: Vec<String>,
+    pub(crate) errors: Vec<KnownError>,
+}
+
+impl Default for Config {
+    ///
+    /// Generate [Default] configuration.
+    ///
+    fn default() -> Self {
+        Config {
+            print_help_and_exit: false,
+            print_version_and_exit: false,
+
+            parents: false,
+            mode: None,
+            verbose: false,
+
+            directories: vec![],
+            errors: vec![],
+        }
+    }
+}
+
+impl Config {
+    ///
+    /// Initialize the application configuration based on provided application arguments; the first
+    /// argument is the program name.
+    ///
+    pub(crate) fn new(args: &[String]) -> Config {
+        let mut config = Self::default();
+
+        let parsed = match parse_options(args.get(1..).unwrap_or_default(), &OPTIONS) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                config.errors.push(option_error(&error));
+                return config;
+            }
+        };
+
+        /* only the last -m counts, and it's read once there are directories to use it on */
+        let mut mode = None;
+        for argument in parsed {
+            match argument {
+                ParsedArgument::Operand(operand) => config.directories.push(operand),
+                ParsedArgument::Option { id, value } => match id {
+                    "mode" => mode = value,
+                    "parents" => config.parents = true,
+                    "verbose" => config.verbose = true,
+                    "help" => config.print_help_and_exit = true,
+                    "version" => config.print_version_and_exit = true,
+                    _ => unreachable!("option {} has no handler", id),
+                },
+            }
+        }
+        if config.print_help_and_exit || config.print_version_and_exit {
+            return config;
+        }
+
+        if config.directories.is_empty() {
+            config.errors.push(missing_operand());
+            return config;
+        }
+        if let Some(mode) = mode {
+            match Mode::parse(&mode) {
+                Some(parsed) => config.mode = Some(parsed),
+                None => config.errors.push(invalid_mode(&mode)),
+            }
+        }
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::fs::permissions::Mode;
+    use crate::config::Config;
+
+    fn config(args: &[&str]) -> Config {
+        let args: Vec<String> = std::iter::once("mkdir").chain(args.iter().copied()).map(String::from).collect();
+        Config::new(&args)
+    }
+
+    fn message(args: &[&str]) -> String {
+        config(args).errors.first().and_then(|error| error.message.clone()).unwrap_or_default()
+    }
+
+    #[test]
+    fn test_config() {
+        let parsed = config(&["-pv", "-m", "755", "--mode=u=rwx", "a", "b"]);
+        assert!(parsed.errors.is_empty());
+        assert!(parsed.parents && parsed.verbose);
+        assert_eq!(Mode::parse("u=rwx"), parsed.mode);
+        assert_eq!(vec!["a", "b"], parsed.directories);
+        assert_eq!(None, config(&["a"]).mode);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!("mkdir: missing operand\nTry 'mkdir --help' for more information.", message(&[]));
+        assert_eq!("mkdir: missing operand\nTry 'mkdir --help' for more information.", message(&["-m", "bogus"]));
+        assert_eq!("mkdir: invalid mode 'bogus'", message(&["-m", "bogus", "a"]));
+        assert_eq!("mkdir: invalid mode '17777'", message(&["--mode=17777", "a"]));
+        assert!(config(&["--help"]).errors.is_empty());
+    }
+}
@@ -0,0 +1,46 @@
+use std::io;
+use std::path::Path;
+use common::input::flags::flags_options::OptionError;
+use common::input::known_error::KnownError;
+use common::input::source::describe_io_error;
+use common::output::quote::{quote_name, quote_path};
+
+const ERROR_CODE: i32 = 1;
+const PROGRAM: &str = "mkdir";
+
+///
+/// The command line couldn't be split into options and operands.
+///
+pub(crate) fn option_error(error: &OptionError) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}\n{}", error.message(PROGRAM), try_help()))
+}
+
+pub(crate) fn missing_operand() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: missing operand\n{}", PROGRAM, try_help()))
+}
+
+///
+/// `-m` was given something that's neither an octal nor a symbolic mode.
+///
+pub(crate) fn invalid_mode(mode: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: invalid mode {}", PROGRAM, quote_name(mode.as_bytes())))
+}
+
+pub(crate) fn cannot_create_directory(name: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot create directory {}: {}", PROGRAM, quote_path(name), describe_io_error(error)))
+}
+
+///
+/// A directory was made, but couldn't be given the mode asked for.
+///
+pub(crate) fn cannot_change_permissions(name: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot change permissions of {}: {}", PROGRAM, quote_path(name), describe_io_error(error)))
+}
+
+pub(crate) fn created_directory(name: &Path) -> String {
+    format!("{}: created directory {}", PROGRAM, quote_path(name))
+}
+
+fn try_help() -> String {
+    format!("Try '{} --help' for more information.", PROGRAM)
+}
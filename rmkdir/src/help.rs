@@ -0,0 +1,22 @@
+use std::io::{self, Write};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub(crate) fn version(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "rmkdir {}", VERSION)
+}
+
+pub(crate) fn help(out: &mut dyn Write) -> io::Result<()> {
+    version(out)?;
+    writeln!(out)?;
+    writeln!(out, "Usage: mkdir [OPTION]... DIRECTORY...")?;
+    writeln!(out, "Create the DIRECTORY(ies), if they do not already exist.")?;
+    writeln!(out)?;
+    writeln!(out, "Mandatory arguments to long options are mandatory for short options too.")?;
+    writeln!(out, "  -m, --mode=MODE   set file mode (as in chmod), not a=rwx - umask")?;
+    writeln!(out, "  -p, --parents     no error if existing, make parent directories as needed,")?;
+    writeln!(out, "                    with their file modes unaffected by any -m option.")?;
+    writeln!(out, "  -v, --verbose     print a message for each created directory")?;
+    writeln!(out, "      --help        display this help and exit")?;
+    writeln!(out, "      --version     output version information and exit")
+}
@@ -0,0 +1,15 @@
+mod config;
+mod errors;
+mod help;
+mod mkdir;
+
+use crate::mkdir::mkdir;
+
+///
+/// Main program entrypoint.
+///
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let status = mkdir(&args, &mut std::io::stdout(), &mut std::io::stderr());
+    std::process::exit(status);
+}
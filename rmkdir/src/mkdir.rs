@@ -0,0 +1,199 @@
+use std::ffi::OsStr;
+use std::fs::{self, DirBuilder, Permissions};
+use std::io::{self, ErrorKind, Write};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::path::Path;
+use common::fs::permissions::umask;
+use common::input::known_error::{report, KnownError};
+use crate::config::Config;
+use crate::errors::{cannot_change_permissions, cannot_create_directory, created_directory};
+use crate::help::{help, version};
+
+const SUCCESS: i32 = 0;
+const FAILURE: i32 = 1;
+/* a=rwx, which -m is applied to */
+const ALL: u32 = 0o777;
+/* the owner can always write to and search the parents -p makes, whatever the umask */
+const OWNER_WRITE_SEARCH: u32 = 0o300;
+/* set-user-id, set-group-id and sticky; mkdir doesn't give a new directory these */
+const SPECIAL: u32 = 0o7000;
+
+///
+/// Run `mkdir` with command line arguments; the first argument is the program name.  Returns the
+/// exit status.
+///
+pub(crate) fn mkdir(args: &[String], out: &mut dyn Write, err: &mut dyn Write) -> i32 {
+    let config = Config::new(args);
+    if let Some(first) = config.errors.first() {
+        report(err, &config.errors);
+        return first.code;
+    }
+
+    if config.print_help_and_exit {
+        return help(out).map_or(FAILURE, |_| SUCCESS);
+    }
+    if config.print_version_and_exit {
+        return version(out).map_or(FAILURE, |_| SUCCESS);
+    }
+
+    let umask = umask();
+    let mut status = SUCCESS;
+    for directory in &config.directories {
+        if let Err(error) = make(&config, Path::new(directory), umask, out) {
+            report(err, std::slice::from_ref(&error));
+            status = error.code;
+        }
+    }
+    status
+}
+
+///
+/// Make one directory, and with `-p`, the directories it's in first.
+///
+fn make(config: &Config, name: &Path, umask: u32, out: &mut dyn Write) -> Result<(), KnownError> {
+    if config.parents {
+        for ancestor in ancestors(name) {
+            make_ancestor(config, ancestor, umask, out)?;
+        }
+    }
+
+    let (mode, bits) = match &config.mode {
+        Some(mode) => mode.adjust(ALL, true, umask),
+        None => (ALL & !umask, 0),
+    };
+    match DirBuilder::new().mode(mode).create(name) {
+        Ok(()) => {}
+        Err(error) if config.parents && error.kind() == ErrorKind::AlreadyExists && name.is_dir() => return Ok(()),
+        Err(error) => return Err(cannot_create_directory(name, &error)),
+    }
+    /* the umask is left as it is, so what it took from the mode, and any special bits, are put back after */
+    if mode & umask != 0 || bits & SPECIAL != 0 {
+        fs::set_permissions(name, Permissions::from_mode(mode)).map_err(|error| cannot_change_permissions(name, &error))?;
+    }
+    tell(config, name, out);
+    Ok(())
+}
+
+///
+/// Make a directory `-p` found missing on the way to the one asked for.  As POSIX has it, `-m`
+/// isn't used; it's made as the umask allows, except that the owner may always write to it and
+/// search it, so what's below can be made.
+///
+fn make_ancestor(config: &Config, name: &Path, umask: u32, out: &mut dyn Write) -> Result<(), KnownError> {
+    match fs::create_dir(name) {
+        Ok(()) => {}
+        Err(error) if error.kind() == ErrorKind::AlreadyExists => {
+            return match fs::metadata(name) {
+                Ok(metadata) if metadata.is_dir() => Ok(()),
+                Ok(_) => Err(cannot_create_directory(name, &io::Error::from_raw_os_error(libc::ENOTDIR))),
+                Err(error) => Err(cannot_create_directory(name, &error)),
+            };
+        }
+        Err(error) => return Err(cannot_create_directory(name, &error)),
+    }
+    if umask & OWNER_WRITE_SEARCH != 0 {
+        let mode = (ALL & !umask) | OWNER_WRITE_SEARCH;
+        fs::set_permissions(name, Permissions::from_mode(mode)).map_err(|error| cannot_change_permissions(name, &error))?;
+    }
+    tell(config, name, out);
+    Ok(())
+}
+
+///
+/// The names of the directories `name` is in, from the top down, as far as it spells them out and
+/// the way it spells them: `a//b/c/` is in `a` and `a//b`.
+///
+fn ancestors(name: &Path) -> Vec<&Path> {
+    let bytes = name.as_os_str().as_bytes();
+    let end = bytes.iter().rposition(|&byte| byte != b'/').map_or(0, |last| last + 1);
+    (1..end)
+        .filter(|&index| bytes[index] == b'/' && bytes[index - 1] != b'/')
+        .map(|index| Path::new(OsStr::from_bytes(&bytes[..index])))
+        .collect()
+}
+
+fn tell(config: &Config, name: &Path, out: &mut dyn Write) {
+    if config.verbose {
+        let _ = writeln!(out, "{}", created_directory(name));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::Path;
+    use common::fs::permissions::umask;
+    use common::test_support::TempDir;
+    use crate::mkdir::{ancestors, mkdir};
+
+    fn run(args: &[&str]) -> (i32, String, String) {
+        let args: Vec<String> = std::iter::once("mkdir").chain(args.iter().copied()).map(String::from).collect();
+        let mut out = vec![];
+        let mut err = vec![];
+        let status = mkdir(&args, &mut out, &mut err);
+        (status, String::from_utf8(out).unwrap(), String::from_utf8(err).unwrap())
+    }
+
+    fn mode(path: &Path) -> u32 {
+        fs::metadata(path).unwrap().permissions().mode() & 0o7777
+    }
+
+    #[test]
+    fn test_ancestors() {
+        assert_eq!(Vec::<&Path>::new(), ancestors(Path::new("a")));
+        assert_eq!(Vec::<&Path>::new(), ancestors(Path::new("a//")));
+        assert_eq!(vec![Path::new("a"), Path::new("a//b")], ancestors(Path::new("a//b/c/")));
+        assert_eq!(vec![Path::new("/a")], ancestors(Path::new("/a/b")));
+        assert_eq!(vec![Path::new("."), Path::new("./x"), Path::new("./x/..")], ancestors(Path::new("./x/../y")));
+    }
+
+    #[test]
+    fn test_make() {
+        let dir = TempDir::new("make");
+        let a = dir.join("a");
+        let a = a.to_str().unwrap();
+        let missing = dir.join("missing/b");
+        let missing = missing.to_str().unwrap();
+
+        assert_eq!((0, format!("mkdir: created directory '{}'\n", a), String::new()), run(&["-v", a]));
+        assert_eq!(0o777 & !umask(), mode(Path::new(a)));
+        assert_eq!((1, String::new(), format!("mkdir: cannot create directory '{}': File exists\n", a)), run(&[a]));
+        assert_eq!((1, String::new(), format!("mkdir: cannot create directory '{}': No such file or directory\n", missing)), run(&[missing]));
+    }
+
+    #[test]
+    fn test_parents() {
+        let dir = TempDir::new("parents");
+        let file = dir.file("file", b"");
+        let deep = format!("{}/a//b/c/", dir.path());
+
+        let created = format!("mkdir: created directory '{0}/a'\nmkdir: created directory '{0}/a//b'\nmkdir: created directory '{1}'\n", dir.path(), deep);
+        assert_eq!((0, created, String::new()), run(&["-pv", &deep]));
+        assert!(dir.join("a/b/c").is_dir());
+        assert_eq!((0, String::new(), String::new()), run(&["-pv", &deep, dir.path()]));
+        assert_eq!((1, String::new(), format!("mkdir: cannot create directory '{}': Not a directory\n", file)), run(&["-p", &format!("{}/x", file)]));
+        assert_eq!((1, String::new(), format!("mkdir: cannot create directory '{}': File exists\n", file)), run(&["-p", &file]));
+    }
+
+    #[test]
+    fn test_modes() {
+        let dir = TempDir::new("modes");
+        let name = |name: &str| dir.join(name).to_str().unwrap().to_string();
+
+        assert_eq!(0, run(&["-m", "700", &name("octal")]).0);
+        assert_eq!(0o700, mode(&dir.join("octal")));
+        assert_eq!(0, run(&["-m", "u=rwx,g=rx,o=", &name("symbolic")]).0);
+        assert_eq!(0o750, mode(&dir.join("symbolic")));
+        assert_eq!(0, run(&["-m", "a=rwx,+t", &name("sticky")]).0);
+        assert_eq!(0o1777, mode(&dir.join("sticky")));
+        assert_eq!(0, run(&["-m", "a-x,u+s", &name("special")]).0);
+        assert_eq!(0o4666, mode(&dir.join("special")));
+
+        /* -m is for the last directory only */
+        assert_eq!(0, run(&["-p", "-m", "500", &name("parent/child")]).0);
+        assert_eq!((0o777 & !umask()) | 0o300, mode(&dir.join("parent")));
+        assert_eq!(0o500, mode(&dir.join("parent/child")));
+    }
+}
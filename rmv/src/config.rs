@@ -0,0 +1,205 @@
+use common::fs::backup::{backup_suffix, backup_type, Backup};
+use common::input::flags::flags_options::{parse_options, OptionSpec, ParsedArgument};
+use common::input::known_error::KnownError;
+use crate::errors::{backup_error, backup_no_clobber, both_target_options, extra_operand, missing_destination, missing_file_operand, option_error};
+
+const OPTIONS: [OptionSpec; 11] = [
+    OptionSpec::optional("backup", Some('b'), Some("backup")),
+    OptionSpec::flag("exchange", None, Some("exchange")),
+    OptionSpec::flag("force", Some('f'), Some("force")),
+    OptionSpec::flag("interactive", Some('i'), Some("interactive")),
+    OptionSpec::flag("no-clobber", Some('n'), Some("no-clobber")),
+    OptionSpec::required("suffix", Some('S'), Some("suffix")),
+    OptionSpec::required("target-directory", Some('t'), Some("target-directory")),
+    OptionSpec::flag("no-target-directory", Some('T'), Some("no-target-directory")),
+    OptionSpec::flag("update", Some('u'), Some("update")),
+    OptionSpec::flag("help", None, Some("help")),
+    OptionSpec::flag("version", None, Some("version")),
+];
+
+///
+/// What's done when the destination exists already; of `-f`, `-i` and `-n`, the last one given wins.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Overwrite {
+    /// Replace it, asking first only when it's write-protected and there's someone to ask.
+    Always,
+    /// Replace it without asking; `-f`.
+    Force,
+    /// Ask first; `-i`.
+    Ask,
+    /// Leave it be; `-n`.
+    Never,
+}
+
+///
+/// Storage for the application configuration.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct Config {
+    pub(crate) print_help_and_exit: bool,
+    pub(crate) print_version_and_exit: bool,
+
+    pub(crate) overwrite: Overwrite,
+    pub(crate) update: bool,
+    pub(crate) exchange: bool,
+    pub(crate) backup: Backup,
+    pub(crate) suffix: String,
+    pub(crate) target_directory: Option<String>,
+    pub(crate) no_target_directory: bool,
+
+    pub(crate) files: Vec<String>,
+    pub(crate) errors: Vec<KnownError>,
+}
+
+impl Default for Config {
+    ///
+    /// Generate [Default] configuration.
+    ///
+    fn default() -> Self {
+        Config {
+            print_help_and_exit: false,
+            print_version_and_exit: false,
+
+            overwrite: Overwrite::Always,
+            update: false,
+            exchange: false,
+            backup: Backup::None,
+            suffix: String::new(),
+            target_directory: None,
+            no_target_directory: false,
+
+            files: vec![],
+            errors: vec![],
+        }
+    }
+}
+
+///
+/// Options that are settled only once all of them have been seen.
+///
+#[derive(Default)]
+struct Selection {
+    /* whether backups were asked for, and with which control, if any */
+    backup: Option<Option<String>>,
+    suffix: Option<String>,
+}
+
+impl Config {
+    ///
+    /// Initialize the application configuration based on provided application arguments; the first
+    /// argument is the program name.
+    ///
+    pub(crate) fn new(args: &[String]) -> Config {
+        let mut config = Self::default();
+
+        let parsed = match parse_options(args.get(1..).unwrap_or_default(), &OPTIONS) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                config.errors.push(option_error(&error));
+                return config;
+            }
+        };
+
+        let mut selection = Selection::default();
+        for argument in parsed {
+            match argument {
+                ParsedArgument::Operand(operand) => config.files.push(operand),
+                ParsedArgument::Option { id, value } => config.apply(&mut selection, id, value),
+            }
+        }
+        if config.print_help_and_exit || config.print_version_and_exit {
+            return config;
+        }
+
+        /* a suffix alone asks for backups, of the type VERSION_CONTROL picks */
+        if let Some(control) = selection.backup.or(selection.suffix.as_ref().map(|_| None)) {
+            if config.overwrite == Overwrite::Never {
+                config.errors.push(backup_no_clobber());
+                return config;
+            }
+            config.backup = match backup_type(control.as_deref()) {
+                Ok(backup) => backup,
+                Err(error) => {
+                    config.errors.push(backup_error(&error));
+                    return config;
+                }
+            };
+        }
+        config.suffix = backup_suffix(selection.suffix.as_deref());
+
+        if config.target_directory.is_some() && config.no_target_directory {
+            config.errors.push(both_target_options());
+        } else if config.files.is_empty() {
+            config.errors.push(missing_file_operand());
+        } else if config.files.len() == 1 && config.target_directory.is_none() {
+            config.errors.push(missing_destination(&config.files[0]));
+        } else if config.no_target_directory && config.files.len() > 2 {
+            config.errors.push(extra_operand(&config.files[2]));
+        }
+        config
+    }
+
+    fn apply(&mut self, selection: &mut Selection, id: &str, value: Option<String>) {
+        match id {
+            "backup" => selection.backup = Some(value),
+            "exchange" => self.exchange = true,
+            "force" => self.overwrite = Overwrite::Force,
+            "interactive" => self.overwrite = Overwrite::Ask,
+            "no-clobber" => self.overwrite = Overwrite::Never,
+            "suffix" => selection.suffix = value,
+            "target-directory" => self.target_directory = value,
+            "no-target-directory" => self.no_target_directory = true,
+            "update" => self.update = true,
+            "help" => self.print_help_and_exit = true,
+            "version" => self.print_version_and_exit = true,
+            _ => unreachable!("option {} has no handler", id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::fs::backup::Backup;
+    use crate::config::{Config, Overwrite};
+
+    fn config(args: &[&str]) -> Config {
+        let args: Vec<String> = std::iter::once("mv").chain(args.iter().copied()).map(String::from).collect();
+        Config::new(&args)
+    }
+
+    fn message(args: &[&str]) -> String {
+        config(args).errors.first().and_then(|error| error.message.clone()).unwrap_or_default()
+    }
+
+    #[test]
+    fn test_overwrite() {
+        assert_eq!(Overwrite::Always, config(&["a", "b"]).overwrite);
+        assert_eq!(Overwrite::Ask, config(&["-fni", "a", "b"]).overwrite);
+        assert_eq!(Overwrite::Never, config(&["-if", "-n", "a", "b"]).overwrite);
+        assert_eq!(Overwrite::Force, config(&["-i", "--force", "a", "b"]).overwrite);
+    }
+
+    #[test]
+    fn test_backup() {
+        assert_eq!(Backup::None, config(&["a", "b"]).backup);
+        assert_eq!(Backup::Numbered, config(&["--backup=numbered", "a", "b"]).backup);
+        assert_eq!(Backup::Simple, config(&["--backup=si", "a", "b"]).backup);
+        let suffixed = config(&["-S", ".orig", "--backup=simple", "a", "b"]);
+        assert_eq!((Backup::Simple, ".orig"), (suffixed.backup, suffixed.suffix.as_str()));
+        assert_ne!(Backup::None, config(&["-S", ".orig", "a", "b"]).backup);
+    }
+
+    #[test]
+    fn test_errors() {
+        let try_help = "Try 'mv --help' for more information.";
+        assert_eq!(format!("mv: missing file operand\n{}", try_help), message(&[]));
+        assert_eq!(format!("mv: missing destination file operand after 'a'\n{}", try_help), message(&["a"]));
+        assert_eq!(format!("mv: options --backup and --no-clobber are mutually exclusive\n{}", try_help), message(&["-bn", "a", "b"]));
+        assert_eq!("mv: cannot combine --target-directory (-t) and --no-target-directory (-T)", message(&["-T", "-t", "d", "a"]));
+        assert_eq!(format!("mv: extra operand 'c'\n{}", try_help), message(&["-T", "a", "b", "c"]));
+        assert!(message(&["--backup=n", "a", "b"]).starts_with("mv: ambiguous argument 'n' for 'backup type'\nValid arguments are:\n"));
+        assert!(message(&["--backup=n", "a", "b"]).ends_with(try_help));
+        assert!(config(&["-t", "d", "a"]).errors.is_empty());
+    }
+}
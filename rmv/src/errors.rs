@@ -0,0 +1,199 @@
+use std::io;
+use std::path::Path;
+use common::fs::backup::BackupError;
+use common::input::flags::flags_options::OptionError;
+use common::input::known_error::KnownError;
+use common::input::source::describe_io_error;
+use common::output::quote::{quote_name, quote_path};
+
+const ERROR_CODE: i32 = 1;
+/* problems that are reported without failing the move */
+const WARNING_CODE: i32 = 0;
+const PROGRAM: &str = "mv";
+
+///
+/// The command line couldn't be split into options and operands.
+///
+pub(crate) fn option_error(error: &OptionError) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}\n{}", error.message(PROGRAM), try_help()))
+}
+
+///
+/// The backup type, from `--backup` or `VERSION_CONTROL`, isn't one of the names.
+///
+pub(crate) fn backup_error(error: &BackupError) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}\n{}", error.message(PROGRAM), try_help()))
+}
+
+///
+/// Backups were asked for along with `-n`, which never replaces anything to back up.
+///
+pub(crate) fn backup_no_clobber() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: options --backup and --no-clobber are mutually exclusive\n{}", PROGRAM, try_help()))
+}
+
+pub(crate) fn both_target_options() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot combine --target-directory (-t) and --no-target-directory (-T)", PROGRAM))
+}
+
+pub(crate) fn missing_file_operand() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: missing file operand\n{}", PROGRAM, try_help()))
+}
+
+pub(crate) fn missing_destination(source: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: missing destination file operand after {}\n{}", PROGRAM, quote_name(source.as_bytes()), try_help()))
+}
+
+///
+/// `-T` takes exactly one source and one destination.
+///
+pub(crate) fn extra_operand(operand: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: extra operand {}\n{}", PROGRAM, quote_name(operand.as_bytes()), try_help()))
+}
+
+///
+/// There's more than one source, and the target couldn't be looked at.
+///
+pub(crate) fn target_error(target: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: target {}: {}", PROGRAM, quote_path(target), describe_io_error(error)))
+}
+
+///
+/// There's more than one source, and the target isn't a directory to put them in.
+///
+pub(crate) fn target_not_directory(target: &Path) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: target {} is not a directory", PROGRAM, quote_path(target)))
+}
+
+///
+/// The directory given with `-t` couldn't be looked at, or isn't one.
+///
+pub(crate) fn target_directory_error(target: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: target directory {}: {}", PROGRAM, quote_path(target), describe_io_error(error)))
+}
+
+pub(crate) fn cannot_stat(name: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot stat {}: {}", PROGRAM, quote_path(name), describe_io_error(error)))
+}
+
+pub(crate) fn same_file(source: &Path, destination: &Path) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: {} and {} are the same file", PROGRAM, quote_path(source), quote_path(destination)))
+}
+
+///
+/// A directory would be moved somewhere inside itself, where it would no longer be reachable.
+///
+pub(crate) fn into_itself(source: &Path, destination: &Path) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot move {} to a subdirectory of itself, {}", PROGRAM, quote_path(source), quote_path(destination)))
+}
+
+pub(crate) fn overwrite_directory(destination: &Path) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot overwrite directory {} with non-directory", PROGRAM, quote_path(destination)))
+}
+
+pub(crate) fn overwrite_non_directory(destination: &Path, source: &Path) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot overwrite non-directory {} with directory {}", PROGRAM, quote_path(destination), quote_path(source)))
+}
+
+pub(crate) fn cannot_backup(destination: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot backup {}: {}", PROGRAM, quote_path(destination), describe_io_error(error)))
+}
+
+pub(crate) fn cannot_move(source: &Path, destination: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot move {} to {}: {}", PROGRAM, quote_path(source), quote_path(destination), describe_io_error(error)))
+}
+
+pub(crate) fn cannot_exchange(source: &Path, destination: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot exchange {} and {}: {}", PROGRAM, quote_path(source), quote_path(destination), describe_io_error(error)))
+}
+
+///
+/// A move to another file system found something in the way that couldn't be removed.
+///
+pub(crate) fn cannot_remove_target(source: &Path, destination: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: inter-device move failed: {} to {}; unable to remove target: {}", PROGRAM, quote_path(source), quote_path(destination), describe_io_error(error)))
+}
+
+pub(crate) fn cannot_remove(name: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot remove {}: {}", PROGRAM, quote_path(name), describe_io_error(error)))
+}
+
+pub(crate) fn cannot_create_directory(destination: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot create directory {}: {}", PROGRAM, quote_path(destination), describe_io_error(error)))
+}
+
+pub(crate) fn cannot_create_regular_file(destination: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot create regular file {}: {}", PROGRAM, quote_path(destination), describe_io_error(error)))
+}
+
+pub(crate) fn cannot_create_hard_link(destination: &Path, source: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot create hard link {} to {}: {}", PROGRAM, quote_path(destination), quote_path(source), describe_io_error(error)))
+}
+
+pub(crate) fn cannot_create_symbolic_link(destination: &Path, target: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot create symbolic link {} to {}: {}", PROGRAM, quote_path(destination), quote_path(target), describe_io_error(error)))
+}
+
+pub(crate) fn cannot_read_symbolic_link(source: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot read symbolic link {}: {}", PROGRAM, quote_path(source), describe_io_error(error)))
+}
+
+pub(crate) fn cannot_create_special_file(destination: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot create special file {}: {}", PROGRAM, quote_path(destination), describe_io_error(error)))
+}
+
+pub(crate) fn cannot_open(source: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot open {} for reading: {}", PROGRAM, quote_path(source), describe_io_error(error)))
+}
+
+///
+/// A directory's entries couldn't be read.
+///
+pub(crate) fn cannot_access(source: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot access {}: {}", PROGRAM, quote_path(source), describe_io_error(error)))
+}
+
+pub(crate) fn read_error(source: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: error reading {}: {}", PROGRAM, quote_path(source), describe_io_error(error)))
+}
+
+pub(crate) fn write_error(destination: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: error writing {}: {}", PROGRAM, quote_path(destination), describe_io_error(error)))
+}
+
+///
+/// The times couldn't be given to a file moved to another file system; that doesn't fail the move.
+///
+pub(crate) fn preserving_times(destination: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(WARNING_CODE, format!("{}: preserving times for {}: {}", PROGRAM, quote_path(destination), describe_io_error(error)))
+}
+
+///
+/// The owner couldn't be given to a file moved to another file system; that doesn't fail the move.
+///
+pub(crate) fn preserving_ownership(destination: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(WARNING_CODE, format!("{}: failed to preserve ownership for {}: {}", PROGRAM, quote_path(destination), describe_io_error(error)))
+}
+
+pub(crate) fn preserving_permissions(destination: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: preserving permissions for {}: {}", PROGRAM, quote_path(destination), describe_io_error(error)))
+}
+
+///
+/// The question `-i` asks before replacing a file.
+///
+pub(crate) fn overwrite_prompt(destination: &Path) -> String {
+    format!("{}: overwrite {}? ", PROGRAM, quote_path(destination))
+}
+
+///
+/// The question asked before replacing a file the user can't write to, when there's a terminal to
+/// answer it; `mode` is the file's permissions, and `symbolic` the same as `ls -l` shows them.
+///
+pub(crate) fn replace_prompt(destination: &Path, mode: u32, symbolic: &str) -> String {
+    format!("{}: replace {}, overriding mode {:04o} ({})? ", PROGRAM, quote_path(destination), mode, symbolic)
+}
+
+fn try_help() -> String {
+    format!("Try '{} --help' for more information.", PROGRAM)
+}
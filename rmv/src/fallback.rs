@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::fs::{self, DirBuilder, File, Metadata, Permissions};
+use std::io::{self, ErrorKind, Read, Write};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use common::fs::owner::{change_owner, privileged};
+use common::fs::times::Times;
+use common::fs::xattr::{get_attribute, list_attributes, set_attribute};
+use common::input::known_error::KnownError;
+use crate::errors::{cannot_access, cannot_create_directory, cannot_create_hard_link, cannot_create_regular_file, cannot_create_special_file,
+                    cannot_create_symbolic_link, cannot_open, cannot_read_symbolic_link, cannot_remove, cannot_remove_target, cannot_stat,
+                    preserving_ownership, preserving_permissions, preserving_times, read_error, write_error};
+
+const BUFFER_SIZE: usize = 128 * 1024;
+/* a directory is filled before it's given its mode, so it's made with at least these permissions */
+const OWNER_PERMISSIONS: u32 = 0o700;
+const MODE_BITS: u32 = 0o7777;
+const PERMISSION_BITS: u32 = 0o777;
+/* set-user-ID and set-group-ID, which mustn't be given to a file that didn't get its owner */
+const SET_ID_BITS: u32 = 0o6000;
+
+///
+/// Move `source`, which `metadata` describes, to a `destination` on another file system, where it
+/// can't simply be renamed: it's copied, keeping its mode, owner, times, extended attributes and
+/// the hard links within it, and the source is removed once the copy is whole.  Whatever is at
+/// the destination is removed first; a directory only when it's empty.
+///
+/// Problems are added to `errors`; those that needn't fail the move have a code of zero.  When the
+/// copy fails, what was made of it is taken away again and the source is left as it was.
+///
+pub(crate) fn move_across(source: &Path, metadata: &Metadata, destination: &Path, errors: &mut Vec<KnownError>) {
+    if let Ok(existing) = fs::symlink_metadata(destination) {
+        let removed = if existing.is_dir() { fs::remove_dir(destination) } else { fs::remove_file(destination) };
+        if let Err(error) = removed {
+            errors.push(cannot_remove_target(source, destination, &error));
+            return;
+        }
+    }
+
+    let mut transfer = Transfer { copies: HashMap::new(), errors };
+    if let Err(error) = transfer.copy(source, metadata, destination) {
+        let _ = remove(destination, metadata);
+        errors.push(error);
+        return;
+    }
+    if let Err(error) = remove(source, metadata) {
+        errors.push(cannot_remove(source, &error));
+    }
+}
+
+///
+/// One copy of a tree, stopped by the first thing that can't be copied.
+///
+struct Transfer<'a> {
+    /* the copies of files with more than one link, by device and inode, so later links to the same file are linked to them */
+    copies: HashMap<(u64, u64), PathBuf>,
+    /* problems that don't stop the copy */
+    errors: &'a mut Vec<KnownError>,
+}
+
+impl Transfer<'_> {
+    fn copy(&mut self, source: &Path, metadata: &Metadata, destination: &Path) -> Result<(), KnownError> {
+        let file_type = metadata.file_type();
+        if file_type.is_dir() {
+            self.copy_directory(source, metadata, destination)
+        } else if file_type.is_symlink() {
+            let target = fs::read_link(source).map_err(|error| cannot_read_symbolic_link(source, &error))?;
+            std::os::unix::fs::symlink(&target, destination).map_err(|error| cannot_create_symbolic_link(destination, &target, &error))?;
+            self.preserve_attributes(source, metadata, destination, None)
+        } else if file_type.is_file() {
+            self.copy_file(source, metadata, destination)
+        } else {
+            make_node(destination, metadata.mode(), metadata.rdev()).map_err(|error| cannot_create_special_file(destination, &error))?;
+            self.preserve_attributes(source, metadata, destination, None)
+        }
+    }
+
+    fn copy_directory(&mut self, source: &Path, metadata: &Metadata, destination: &Path) -> Result<(), KnownError> {
+        DirBuilder::new().mode(metadata.mode() & PERMISSION_BITS | OWNER_PERMISSIONS).create(destination)
+            .map_err(|error| cannot_create_directory(destination, &error))?;
+
+        let mut names = fs::read_dir(source)
+            .and_then(|entries| entries.map(|entry| entry.map(|entry| entry.file_name())).collect::<io::Result<Vec<_>>>())
+            .map_err(|error| cannot_access(source, &error))?;
+        names.sort();
+        for name in names {
+            let (source, destination) = (source.join(&name), destination.join(&name));
+            let metadata = fs::symlink_metadata(&source).map_err(|error| cannot_stat(&source, &error))?;
+            self.copy(&source, &metadata, &destination)?;
+        }
+        self.preserve_attributes(source, metadata, destination, None)
+    }
+
+    fn copy_file(&mut self, source: &Path, metadata: &Metadata, destination: &Path) -> Result<(), KnownError> {
+        let key = (metadata.dev(), metadata.ino());
+        if let Some(linked) = self.copies.get(&key) {
+            return fs::hard_link(linked, destination).map_err(|error| cannot_create_hard_link(destination, linked, &error));
+        }
+
+        let mut input = File::open(source).map_err(|error| cannot_open(source, &error))?;
+        let mut output = File::options().write(true).create_new(true).mode(metadata.mode() & PERMISSION_BITS).open(destination)
+            .map_err(|error| cannot_create_regular_file(destination, &error))?;
+        let mut buffer = vec![0u8; BUFFER_SIZE];
+        loop {
+            let count = match input.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(count) => count,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(read_error(source, &error)),
+            };
+            output.write_all(&buffer[..count]).map_err(|error| write_error(destination, &error))?;
+        }
+
+        if metadata.nlink() > 1 {
+            self.copies.insert(key, destination.to_path_buf());
+        }
+        self.preserve_attributes(source, metadata, destination, Some(&output))
+    }
+
+    ///
+    /// Give `destination` every attribute of its source that can be given.  Times go first, so
+    /// nothing done after can disturb them, and the mode goes last, as changing the owner clears
+    /// set-user-ID bits; only failing to give the mode fails the copy.
+    ///
+    fn preserve_attributes(&mut self, source: &Path, metadata: &Metadata, destination: &Path, file: Option<&File>) -> Result<(), KnownError> {
+        let times = Times::of(metadata);
+        let result = match file {
+            Some(file) => file.set_times(times.file_times()),
+            None => times.apply(destination, false),
+        };
+        if let Err(error) = result {
+            self.errors.push(preserving_times(destination, &error));
+        }
+
+        let owned = match change_owner(destination, metadata.uid(), metadata.gid()) {
+            Ok(()) => true,
+            Err(error) => {
+                /* only the privileged can give files away, so it's no surprise when others can't */
+                let refused = matches!(error.raw_os_error(), Some(libc::EPERM) | Some(libc::EINVAL));
+                if !refused || privileged() {
+                    self.errors.push(preserving_ownership(destination, &error));
+                }
+                false
+            }
+        };
+
+        /* file systems and users differ too much in which attributes they allow to complain about them */
+        for name in list_attributes(source).unwrap_or_default() {
+            let _ = get_attribute(source, &name).and_then(|value| set_attribute(destination, &name, &value));
+        }
+
+        if metadata.file_type().is_symlink() {
+            return Ok(());
+        }
+        let mode = metadata.mode() & if owned { MODE_BITS } else { MODE_BITS & !SET_ID_BITS };
+        fs::set_permissions(destination, Permissions::from_mode(mode)).map_err(|error| preserving_permissions(destination, &error))
+    }
+}
+
+///
+/// Remove a file, or a directory and everything in it.
+///
+fn remove(path: &Path, metadata: &Metadata) -> io::Result<()> {
+    if metadata.is_dir() { fs::remove_dir_all(path) } else { fs::remove_file(path) }
+}
+
+fn make_node(path: &Path, mode: u32, device: u64) -> io::Result<()> {
+    let path = CString::new(path.as_os_str().as_bytes()).map_err(|_| io::Error::from(ErrorKind::InvalidInput))?;
+    /* SAFETY: mknod only reads the path */
+    if unsafe { libc::mknod(path.as_ptr(), mode as libc::mode_t, device as libc::dev_t) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+    use std::time::{Duration, UNIX_EPOCH};
+    use common::fs::times::Times;
+    use common::test_support::TempDir;
+    use crate::fallback::move_across;
+
+    #[test]
+    fn test_move_across() {
+        let dir = TempDir::new("across");
+        fs::create_dir_all(dir.join("source/sub")).unwrap();
+        dir.file("source/a", b"alpha");
+        fs::hard_link(dir.join("source/a"), dir.join("source/sub/linked")).unwrap();
+        std::os::unix::fs::symlink("a", dir.join("source/link")).unwrap();
+        fs::set_permissions(dir.join("source/a"), fs::Permissions::from_mode(0o751)).unwrap();
+        let modified = UNIX_EPOCH + Duration::new(1_000_000, 0);
+        Times { accessed: Some(modified), modified: Some(modified) }.apply(&dir.join("source/sub"), true).unwrap();
+
+        let metadata = fs::symlink_metadata(dir.join("source")).unwrap();
+        let mut errors = vec![];
+        move_across(&dir.join("source"), &metadata, &dir.join("moved"), &mut errors);
+
+        assert!(errors.is_empty());
+        assert!(!dir.join("source").exists());
+        assert_eq!(b"alpha".to_vec(), fs::read(dir.join("moved/a")).unwrap());
+        assert_eq!(0o751, fs::metadata(dir.join("moved/a")).unwrap().mode() & 0o7777);
+        assert_eq!(fs::metadata(dir.join("moved/a")).unwrap().ino(), fs::metadata(dir.join("moved/sub/linked")).unwrap().ino());
+        assert_eq!("a", fs::read_link(dir.join("moved/link")).unwrap().to_str().unwrap());
+        assert_eq!(modified, fs::metadata(dir.join("moved/sub")).unwrap().modified().unwrap());
+    }
+
+    #[test]
+    fn test_move_across_target() {
+        let dir = TempDir::new("target");
+        fs::create_dir_all(dir.join("source")).unwrap();
+        fs::create_dir_all(dir.join("full/sub")).unwrap();
+        fs::create_dir_all(dir.join("empty")).unwrap();
+        let metadata = fs::symlink_metadata(dir.join("source")).unwrap();
+
+        let mut errors = vec![];
+        move_across(&dir.join("source"), &metadata, &dir.join("full"), &mut errors);
+        let message = errors.first().and_then(|error| error.message.clone()).unwrap_or_default();
+        assert_eq!(format!("mv: inter-device move failed: '{}' to '{}'; unable to remove target: Directory not empty", dir.join("source").display(), dir.join("full").display()), message);
+        assert!(dir.join("source").exists());
+
+        let mut errors = vec![];
+        move_across(&dir.join("source"), &metadata, &dir.join("empty"), &mut errors);
+        assert!(errors.is_empty());
+        assert!(!dir.join("source").exists());
+        assert!(dir.join("empty").is_dir());
+    }
+}
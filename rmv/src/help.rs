@@ -0,0 +1,42 @@
+use std::io::{self, Write};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub(crate) fn version(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "rmv {}", VERSION)
+}
+
+pub(crate) fn help(out: &mut dyn Write) -> io::Result<()> {
+    version(out)?;
+    writeln!(out)?;
+    writeln!(out, "Usage: mv [OPTION]... [-T] SOURCE DEST")?;
+    writeln!(out, "  or:  mv [OPTION]... SOURCE... DIRECTORY")?;
+    writeln!(out, "  or:  mv [OPTION]... -t DIRECTORY SOURCE...")?;
+    writeln!(out, "Rename SOURCE to DEST, or move SOURCE(s) to DIRECTORY.")?;
+    writeln!(out)?;
+    writeln!(out, "Mandatory arguments to long options are mandatory for short options too.")?;
+    writeln!(out, "      --backup[=CONTROL]       make a backup of each existing destination file")?;
+    writeln!(out, "  -b                           like --backup but does not accept an argument")?;
+    writeln!(out, "      --exchange               exchange source and destination")?;
+    writeln!(out, "  -f, --force                  do not prompt before overwriting")?;
+    writeln!(out, "  -i, --interactive            prompt before overwrite")?;
+    writeln!(out, "  -n, --no-clobber             do not overwrite an existing file")?;
+    writeln!(out, "If you specify more than one of -i, -f, -n, only the final one takes effect.")?;
+    writeln!(out, "  -S, --suffix=SUFFIX          override the usual backup suffix")?;
+    writeln!(out, "  -t, --target-directory=DIRECTORY  move all SOURCE arguments into DIRECTORY")?;
+    writeln!(out, "  -T, --no-target-directory    treat DEST as a normal file")?;
+    writeln!(out, "  -u, --update                 move only when the SOURCE file is newer")?;
+    writeln!(out, "                                 than the destination file or when the")?;
+    writeln!(out, "                                 destination file is missing")?;
+    writeln!(out, "      --help        display this help and exit")?;
+    writeln!(out, "      --version     output version information and exit")?;
+    writeln!(out)?;
+    writeln!(out, "The backup suffix is '~', unless set with --suffix or SIMPLE_BACKUP_SUFFIX.")?;
+    writeln!(out, "The version control method may be selected via the --backup option or through")?;
+    writeln!(out, "the VERSION_CONTROL environment variable.  Here are the values:")?;
+    writeln!(out)?;
+    writeln!(out, "  none, off       never make backups (even if --backup is given)")?;
+    writeln!(out, "  numbered, t     make numbered backups")?;
+    writeln!(out, "  existing, nil   numbered if numbered backups exist, simple otherwise")?;
+    writeln!(out, "  simple, never   always make simple backups")
+}
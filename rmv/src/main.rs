@@ -0,0 +1,18 @@
+mod config;
+mod errors;
+mod fallback;
+mod help;
+mod mover;
+mod mv;
+mod rename;
+
+use crate::mv::mv;
+
+///
+/// Main program entrypoint.
+///
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let status = mv(&args, &mut std::io::stdin().lock(), &mut std::io::stdout(), &mut std::io::stderr());
+    std::process::exit(status);
+}
@@ -0,0 +1,163 @@
+use std::ffi::CString;
+use std::fs::{self, Metadata};
+use std::io::{BufRead, Write};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+use common::fs::backup::make_backup;
+use common::fs::permissions::permission_string;
+use common::input::known_error::{report, KnownError};
+use crate::config::{Config, Overwrite};
+use crate::errors::{cannot_backup, cannot_exchange, cannot_move, cannot_stat, into_itself, overwrite_directory, overwrite_non_directory,
+                    overwrite_prompt, replace_prompt, same_file};
+use crate::fallback::move_across;
+use crate::rename::{rename, Replace};
+
+const MODE_BITS: u32 = 0o7777;
+///
+/// Moves files and trees the way the options ask, reporting problems as they're met.
+///
+pub(crate) struct Mover<'a> {
+    config: &'a Config,
+    input: &'a mut dyn BufRead,
+    err: &'a mut dyn Write,
+    /* whether there's someone at a terminal to ask about replacing write-protected files */
+    terminal: bool,
+}
+
+impl<'a> Mover<'a> {
+    ///
+    /// `input` answers the questions asked before replacing files, which are written to `err`
+    /// along with any problems.
+    ///
+    pub(crate) fn new(config: &'a Config, input: &'a mut dyn BufRead, err: &'a mut dyn Write, terminal: bool) -> Mover<'a> {
+        Mover { config, input, err, terminal }
+    }
+
+    ///
+    /// Move `source` to `destination`; returns whether all went well.
+    ///
+    pub(crate) fn move_operand(&mut self, source: &Path, destination: &Path) -> bool {
+        let config = self.config;
+        let metadata = match fs::symlink_metadata(source) {
+            Ok(metadata) => metadata,
+            Err(error) => return self.fail(cannot_stat(source, &error)),
+        };
+        let existing = fs::symlink_metadata(destination).ok();
+        if existing.as_ref().is_some_and(|existing| existing.dev() == metadata.dev() && existing.ino() == metadata.ino()) {
+            return self.fail(same_file(source, destination));
+        }
+        if metadata.is_dir() && let Some(error) = into_itself_error(source, destination) {
+            return self.fail(error);
+        }
+
+        if config.exchange {
+            return match rename(source, destination, Replace::Exchange) {
+                Ok(()) => true,
+                Err(error) => self.fail(cannot_exchange(source, destination, &error)),
+            };
+        }
+
+        if let Some(existing) = &existing {
+            if !self.replaces(&metadata, existing, destination) {
+                return true;
+            }
+            if existing.is_dir() && !metadata.is_dir() {
+                return self.fail(overwrite_directory(destination));
+            }
+            if !existing.is_dir() && metadata.is_dir() {
+                return self.fail(overwrite_non_directory(destination, source));
+            }
+            if let Err(error) = make_backup(destination, config.backup, &config.suffix) {
+                return self.fail(cannot_backup(destination, &error));
+            }
+        }
+
+        /* -n is held to even when something turns up at the destination after it was looked for */
+        let replace = if config.overwrite == Overwrite::Never { Replace::Never } else { Replace::Always };
+        match rename(source, destination, replace) {
+            Ok(()) => true,
+            Err(error) if replace == Replace::Never && error.raw_os_error() == Some(libc::EEXIST) => true,
+            Err(error) if error.raw_os_error() == Some(libc::EXDEV) => {
+                if replace == Replace::Never && fs::symlink_metadata(destination).is_ok() {
+                    return true;
+                }
+                let mut errors = vec![];
+                move_across(source, &metadata, destination, &mut errors);
+                self.report(&errors)
+            }
+            Err(error) => self.fail(cannot_move(source, destination, &error)),
+        }
+    }
+
+    ///
+    /// Whether an existing destination is to be replaced: `-n` leaves it, `-u` leaves it when it's
+    /// no older than the source, and `-i` asks; so is someone at a terminal when the destination is
+    /// write-protected, unless `-f` was given.
+    ///
+    fn replaces(&mut self, metadata: &Metadata, existing: &Metadata, destination: &Path) -> bool {
+        let config = self.config;
+        if config.overwrite == Overwrite::Never {
+            return false;
+        }
+        if config.update
+            && let (Ok(modified), Ok(source_modified)) = (existing.modified(), metadata.modified())
+            && modified >= source_modified {
+            return false;
+        }
+
+        let question = match config.overwrite {
+            Overwrite::Ask => overwrite_prompt(destination),
+            Overwrite::Always if self.terminal && !existing.file_type().is_symlink() && !writable(destination) => {
+                let mode = existing.mode() & MODE_BITS;
+                replace_prompt(destination, mode, &permission_string(mode))
+            }
+            _ => return true,
+        };
+        let _ = write!(self.err, "{}", question);
+        let _ = self.err.flush();
+        let mut answer = String::new();
+        if self.input.read_line(&mut answer).is_err() {
+            return false;
+        }
+        answer.starts_with(['y', 'Y'])
+    }
+
+    fn fail(&mut self, error: KnownError) -> bool {
+        self.report(&[error])
+    }
+
+    ///
+    /// Write problems out; returns whether none of them fails the move.
+    ///
+    fn report(&mut self, errors: &[KnownError]) -> bool {
+        report(self.err, errors);
+        errors.iter().all(|error| error.code == 0)
+    }
+}
+
+///
+/// A directory can't be moved into itself or anywhere below it; it would no longer be reachable.
+///
+fn into_itself_error(source: &Path, destination: &Path) -> Option<KnownError> {
+    let source_path = fs::canonicalize(source).ok()?;
+    let destination_path = match fs::canonicalize(destination) {
+        Ok(path) => path,
+        Err(_) => {
+            let parent = destination.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."));
+            fs::canonicalize(parent).ok()?.join(destination.file_name()?)
+        }
+    };
+    (destination_path != source_path && destination_path.starts_with(&source_path)).then(|| into_itself(source, destination))
+}
+
+///
+/// Whether the user may write to a file, judged by the effective ids as the kernel would.
+///
+fn writable(path: &Path) -> bool {
+    let Ok(path) = CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+    /* SAFETY: faccessat only reads the path */
+    unsafe { libc::faccessat(libc::AT_FDCWD, path.as_ptr(), libc::W_OK, libc::AT_EACCESS) == 0 }
+}
@@ -0,0 +1,215 @@
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use common::fs::path::base_name;
+use common::input::known_error::{report, KnownError};
+use crate::config::Config;
+use crate::errors::{cannot_stat, target_directory_error, target_error, target_not_directory};
+use crate::help::{help, version};
+use crate::mover::Mover;
+
+const SUCCESS: i32 = 0;
+const FAILURE: i32 = 1;
+
+///
+/// Run `mv` with command line arguments; the first argument is the program name.  `input` answers
+/// the questions asked before replacing files.  Returns the exit status: 0 when everything was
+/// moved, or left be as asked, and 1 otherwise.
+///
+pub(crate) fn mv(args: &[String], input: &mut dyn BufRead, out: &mut dyn Write, err: &mut dyn Write) -> i32 {
+    let config = Config::new(args);
+    if let Some(first) = config.errors.first() {
+        report(err, &config.errors);
+        return first.code;
+    }
+
+    if config.print_help_and_exit {
+        return help(out).map_or(FAILURE, |_| SUCCESS);
+    }
+    if config.print_version_and_exit {
+        return version(out).map_or(FAILURE, |_| SUCCESS);
+    }
+
+    let moves = match moves(&config) {
+        Ok(moves) => moves,
+        Err(error) => {
+            report(err, &[error]);
+            return FAILURE;
+        }
+    };
+
+    /* SAFETY: isatty only looks at the descriptor */
+    let terminal = unsafe { libc::isatty(libc::STDIN_FILENO) } == 1;
+    let mut mover = Mover::new(&config, input, err, terminal);
+    let mut success = true;
+    for (source, destination) in moves {
+        success &= mover.move_operand(&source, &destination);
+    }
+    if success { SUCCESS } else { FAILURE }
+}
+
+///
+/// Each source with where it's going: into the directory given with `-t`, or the last operand,
+/// when that's a directory and `-T` wasn't given; to the last operand itself otherwise.
+///
+fn moves(config: &Config) -> Result<Vec<(PathBuf, PathBuf)>, KnownError> {
+    let into = |directory: &Path, sources: &[String]| {
+        sources.iter().map(|source| (PathBuf::from(source), directory.join(base_name(Path::new(source))))).collect()
+    };
+
+    if let Some(directory) = &config.target_directory {
+        let directory = Path::new(directory);
+        return match fs::metadata(directory) {
+            Ok(metadata) if metadata.is_dir() => Ok(into(directory, &config.files)),
+            Ok(_) => Err(target_directory_error(directory, &io::Error::from_raw_os_error(libc::ENOTDIR))),
+            Err(error) => Err(target_directory_error(directory, &error)),
+        };
+    }
+
+    let Some((target, sources)) = config.files.split_last() else {
+        return Ok(vec![]);
+    };
+    let target = Path::new(target);
+    let single = || vec![(PathBuf::from(&sources[0]), target.to_path_buf())];
+    if config.no_target_directory {
+        return Ok(single());
+    }
+    match fs::metadata(target) {
+        Ok(metadata) if metadata.is_dir() => Ok(into(target, sources)),
+        Ok(_) if sources.len() > 1 => Err(target_not_directory(target)),
+        Err(error) if sources.len() > 1 => Err(target_error(target, &error)),
+        /* a target that isn't there yet is the new name; one that can't be looked at is no use */
+        Err(error) if error.kind() != io::ErrorKind::NotFound => Err(cannot_stat(target, &error)),
+        _ => Ok(single()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::os::unix::fs::MetadataExt;
+    use common::test_support::TempDir;
+    use crate::mv::mv;
+
+    fn run(args: &[&str], input: &str) -> (i32, String) {
+        let args: Vec<String> = std::iter::once("mv").chain(args.iter().copied()).map(String::from).collect();
+        let mut out = vec![];
+        let mut err = vec![];
+        let status = mv(&args, &mut input.as_bytes(), &mut out, &mut err);
+        (status, String::from_utf8(err).unwrap())
+    }
+
+    #[test]
+    fn test_files() {
+        let dir = TempDir::new("files");
+        let a = dir.file("a", b"alpha");
+        let b = dir.join("b").to_str().unwrap().to_string();
+
+        assert_eq!((0, String::new()), run(&[&a, &b], ""));
+        assert!(!dir.join("a").exists());
+        assert_eq!(b"alpha".to_vec(), fs::read(&b).unwrap());
+
+        let target = dir.join("target");
+        fs::create_dir(&target).unwrap();
+        let c = dir.file("c", b"gamma");
+        assert_eq!((0, String::new()), run(&[&b, &c, target.to_str().unwrap()], ""));
+        assert_eq!(b"alpha".to_vec(), fs::read(target.join("b")).unwrap());
+        assert_eq!(b"gamma".to_vec(), fs::read(target.join("c")).unwrap());
+
+        let d = dir.file("d", b"delta");
+        assert_eq!((0, String::new()), run(&["-t", target.to_str().unwrap(), &d], ""));
+        assert_eq!(b"delta".to_vec(), fs::read(target.join("d")).unwrap());
+
+        let renamed = dir.join("renamed");
+        assert_eq!((0, String::new()), run(&[target.to_str().unwrap(), renamed.to_str().unwrap()], ""));
+        assert!(renamed.join("d").exists());
+    }
+
+    #[test]
+    fn test_overwrite() {
+        let dir = TempDir::new("overwrite");
+        let a = dir.file("a", b"new");
+        let b = dir.file("b", b"old");
+
+        assert_eq!((0, String::new()), run(&["-n", &a, &b], ""));
+        assert_eq!(b"old".to_vec(), fs::read(&b).unwrap());
+        assert_eq!((0, format!("mv: overwrite '{}'? ", b)), run(&["-i", &a, &b], "no\n"));
+        assert_eq!(b"old".to_vec(), fs::read(&b).unwrap());
+        assert_eq!((0, String::new()), run(&["-u", &a, &b], ""));
+        assert_eq!(b"old".to_vec(), fs::read(&b).unwrap());
+        assert_eq!((0, format!("mv: overwrite '{}'? ", b)), run(&["-i", &a, &b], "y\n"));
+        assert_eq!(b"new".to_vec(), fs::read(&b).unwrap());
+        assert!(!dir.join("a").exists());
+    }
+
+    #[test]
+    fn test_exchange() {
+        let dir = TempDir::new("exchange");
+        let a = dir.file("a", b"alpha");
+        let b = dir.file("b", b"beta");
+
+        assert_eq!((0, String::new()), run(&["--exchange", &a, &b], ""));
+        assert_eq!(b"beta".to_vec(), fs::read(&a).unwrap());
+        assert_eq!(b"alpha".to_vec(), fs::read(&b).unwrap());
+        let missing = dir.join("missing").to_str().unwrap().to_string();
+        assert_eq!((1, format!("mv: cannot exchange '{}' and '{}': No such file or directory\n", a, missing)), run(&["--exchange", &a, &missing], ""));
+    }
+
+    #[test]
+    fn test_backup() {
+        let dir = TempDir::new("backup");
+        let b = dir.file("b", b"first");
+        let a = dir.file("a", b"second");
+
+        assert_eq!((0, String::new()), run(&["-b", &a, &b], ""));
+        assert_eq!(b"first".to_vec(), fs::read(dir.join("b~")).unwrap());
+        let a = dir.file("a", b"third");
+        assert_eq!((0, String::new()), run(&["--backup=numbered", &a, &b], ""));
+        assert_eq!(b"second".to_vec(), fs::read(dir.join("b.~1~")).unwrap());
+        let a = dir.file("a", b"fourth");
+        assert_eq!((0, String::new()), run(&["--backup=existing", &a, &b], ""));
+        assert_eq!(b"third".to_vec(), fs::read(dir.join("b.~2~")).unwrap());
+        let a = dir.file("a", b"fifth");
+        assert_eq!((0, String::new()), run(&["--backup=simple", "-S", ".orig", &a, &b], ""));
+        assert_eq!(b"fourth".to_vec(), fs::read(dir.join("b.orig")).unwrap());
+        assert_eq!(b"fifth".to_vec(), fs::read(&b).unwrap());
+    }
+
+    #[test]
+    fn test_no_target_directory() {
+        let dir = TempDir::new("no-target");
+        let a = dir.file("a", b"alpha");
+        let empty = dir.join("empty");
+        fs::create_dir(&empty).unwrap();
+        let d = dir.join("d");
+        fs::create_dir(&d).unwrap();
+
+        assert_eq!((1, format!("mv: cannot overwrite directory '{}' with non-directory\n", empty.display())), run(&["-T", &a, empty.to_str().unwrap()], ""));
+        let inode = fs::metadata(&d).unwrap().ino();
+        assert_eq!((0, String::new()), run(&["-T", d.to_str().unwrap(), empty.to_str().unwrap()], ""));
+        assert_eq!(inode, fs::metadata(&empty).unwrap().ino());
+    }
+
+    #[test]
+    fn test_errors() {
+        let dir = TempDir::new("errors");
+        let a = dir.file("a", b"alpha");
+        let b = dir.file("b", b"beta");
+        let d = dir.join("d");
+        fs::create_dir_all(d.join("sub")).unwrap();
+        let d = d.to_str().unwrap().to_string();
+        let missing = dir.join("missing").to_str().unwrap().to_string();
+
+        assert_eq!((1, format!("mv: '{}' and '{}' are the same file\n", a, a)), run(&[&a, &a], ""));
+        assert_eq!((1, format!("mv: cannot stat '{}': No such file or directory\n", missing)), run(&[&missing, &b], ""));
+        assert_eq!((1, format!("mv: target '{}' is not a directory\n", b)), run(&[&a, &a, &b], ""));
+        assert_eq!((1, format!("mv: target '{}': No such file or directory\n", missing)), run(&[&a, &b, &missing], ""));
+        assert_eq!((1, format!("mv: target directory '{}': Not a directory\n", b)), run(&["-t", &b, &a], ""));
+        assert_eq!((1, format!("mv: cannot stat '{}/': Not a directory\n", b)), run(&[&a, &format!("{}/", b)], ""));
+        let inside = format!("{}/sub/d", d);
+        assert_eq!((1, format!("mv: cannot move '{}' to a subdirectory of itself, '{}'\n", d, inside)), run(&[&d, &inside], ""));
+        assert_eq!((1, format!("mv: cannot overwrite non-directory '{}' with directory '{}'\n", a, d)), run(&[&d, &a], ""));
+        let nowhere = format!("{}/x/y", dir.path());
+        assert_eq!((1, format!("mv: cannot move '{}' to '{}': No such file or directory\n", a, nowhere)), run(&[&a, &nowhere], ""));
+    }
+}
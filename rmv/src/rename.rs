@@ -0,0 +1,80 @@
+use std::ffi::CString;
+use std::io::{self, ErrorKind};
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+///
+/// What a rename does with a destination that exists already.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Replace {
+    /// Replaces it.
+    Always,
+    /// Fails with `EEXIST`, leaving it be; `RENAME_NOREPLACE`.
+    Never,
+    /// Swaps it with the source, both of which must exist; `RENAME_EXCHANGE`.
+    Exchange,
+}
+
+///
+/// Rename `source` to `destination` with `renameat2`.  Where the kernel or the file system doesn't
+/// know `RENAME_NOREPLACE`, the destination is looked for first instead, which leaves a moment in
+/// which it could appear; an exchange can't be done any other way, so it fails there.
+///
+pub(crate) fn rename(source: &Path, destination: &Path, replace: Replace) -> io::Result<()> {
+    let flags = match replace {
+        Replace::Always => return std::fs::rename(source, destination),
+        Replace::Never => libc::RENAME_NOREPLACE,
+        Replace::Exchange => libc::RENAME_EXCHANGE,
+    };
+    match rename_with_flags(source, destination, flags) {
+        Err(error) if replace == Replace::Never && matches!(error.raw_os_error(), Some(libc::EINVAL) | Some(libc::ENOSYS)) => {
+            if std::fs::symlink_metadata(destination).is_ok() {
+                return Err(io::Error::from_raw_os_error(libc::EEXIST));
+            }
+            std::fs::rename(source, destination)
+        }
+        result => result,
+    }
+}
+
+fn rename_with_flags(source: &Path, destination: &Path, flags: libc::c_uint) -> io::Result<()> {
+    let source = c_path(source)?;
+    let destination = c_path(destination)?;
+    /* SAFETY: renameat2 only reads the two paths */
+    if unsafe { libc::renameat2(libc::AT_FDCWD, source.as_ptr(), libc::AT_FDCWD, destination.as_ptr(), flags) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn c_path(path: &Path) -> io::Result<CString> {
+    CString::new(path.as_os_str().as_bytes()).map_err(|_| io::Error::from(ErrorKind::InvalidInput))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use common::test_support::TempDir;
+    use crate::rename::{rename, Replace};
+
+    #[test]
+    fn test_rename() {
+        let dir = TempDir::new("rename");
+        dir.file("a", b"alpha");
+        dir.file("b", b"beta");
+
+        let error = rename(&dir.join("a"), &dir.join("b"), Replace::Never).unwrap_err();
+        assert_eq!(Some(libc::EEXIST), error.raw_os_error());
+        assert_eq!(b"beta".to_vec(), fs::read(dir.join("b")).unwrap());
+
+        rename(&dir.join("a"), &dir.join("b"), Replace::Exchange).unwrap();
+        assert_eq!(b"beta".to_vec(), fs::read(dir.join("a")).unwrap());
+        assert_eq!(b"alpha".to_vec(), fs::read(dir.join("b")).unwrap());
+
+        rename(&dir.join("a"), &dir.join("c"), Replace::Never).unwrap();
+        rename(&dir.join("c"), &dir.join("b"), Replace::Always).unwrap();
+        assert_eq!(b"beta".to_vec(), fs::read(dir.join("b")).unwrap());
+        assert!(!dir.join("c").exists());
+    }
+}
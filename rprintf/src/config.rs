@@ -0,0 +1,108 @@
+use common::input::known_error::KnownError;
+use crate::errors::missing_operand;
+
+const HELP: &str = "--help";
+const VERSION: &str = "--version";
+const END_OF_OPTIONS: &str = "--";
+
+///
+/// Storage for the application configuration.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct Config {
+    pub(crate) print_help_and_exit: bool,
+    pub(crate) print_version_and_exit: bool,
+
+    pub(crate) format: String,
+    pub(crate) arguments: Vec<String>,
+
+    pub(crate) errors: Vec<KnownError>,
+}
+
+impl Default for Config {
+    ///
+    /// Generate [Default] configuration.
+    ///
+    fn default() -> Self {
+        Config {
+            print_help_and_exit: false,
+            print_version_and_exit: false,
+
+            format: String::new(),
+            arguments: vec![],
+
+            errors: vec![],
+        }
+    }
+}
+
+impl Config {
+    ///
+    /// Build configuration from command line arguments; the first argument is the program name.
+    /// There are no options, other than `--help` and `--version` on their own, so that formats
+    /// and arguments may start with a dash; a leading `--` is skipped all the same.
+    ///
+    pub(crate) fn new(args: &[String]) -> Config {
+        let mut config = Self::default();
+        let mut args = args.get(1..).unwrap_or_default();
+
+        if let [only] = args {
+            match only.as_str() {
+                HELP => config.print_help_and_exit = true,
+                VERSION => config.print_version_and_exit = true,
+                _ => {}
+            }
+            if config.print_help_and_exit || config.print_version_and_exit {
+                return config;
+            }
+        }
+
+        if args.first().is_some_and(|first| first == END_OF_OPTIONS) {
+            args = &args[1..];
+        }
+        match args.split_first() {
+            Some((format, arguments)) => {
+                config.format = format.clone();
+                config.arguments = arguments.to_vec();
+            }
+            None => config.errors.push(missing_operand()),
+        }
+
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+
+    fn config(args: &[&str]) -> Config {
+        let args: Vec<String> = std::iter::once("printf").chain(args.iter().copied()).map(|s| s.to_string()).collect();
+        Config::new(&args)
+    }
+
+    #[test]
+    fn test_operands() {
+        let plain = config(&["%s\n", "a", "b"]);
+        assert_eq!(("%s\n".to_string(), vec!["a".to_string(), "b".to_string()]), (plain.format, plain.arguments));
+
+        assert_eq!("-n", config(&["-n"]).format);
+        assert_eq!("%d", config(&["--", "%d", "1"]).format);
+        assert_eq!("--", config(&["--", "--"]).format);
+        assert_eq!("--help", config(&["--help", "x"]).format);
+    }
+
+    #[test]
+    fn test_options() {
+        assert!(config(&["--help"]).print_help_and_exit);
+        assert!(config(&["--version"]).print_version_and_exit);
+        assert_eq!("--help", config(&["--", "--help"]).format);
+    }
+
+    #[test]
+    fn test_errors() {
+        let messages = |config: Config| config.errors.iter().map(|e| e.message.clone().unwrap()).collect::<Vec<_>>();
+        assert_eq!(vec!["printf: missing operand\nTry 'printf --help' for more information."], messages(config(&[])));
+        assert_eq!(vec!["printf: missing operand\nTry 'printf --help' for more information."], messages(config(&["--"])));
+    }
+}
@@ -0,0 +1,68 @@
+use std::io;
+use common::input::known_error::KnownError;
+use common::input::source::describe_io_error;
+use common::output::format::argument::ArgumentProblem;
+use common::output::format::directive::FormatError;
+use common::output::format::escape::EscapeError;
+
+const ERROR_CODE: i32 = 1;
+/* warnings are reported, but leave the exit status alone */
+const WARNING_CODE: i32 = 0;
+const PROGRAM: &str = "printf";
+
+///
+/// No format was given.
+///
+pub(crate) fn missing_operand() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: missing operand\nTry '{} --help' for more information.", PROGRAM, PROGRAM))
+}
+
+///
+/// The format has a directive that isn't one, or an escape that can't be read.
+///
+pub(crate) fn format_error(error: &FormatError) -> KnownError {
+    KnownError::new(ERROR_CODE, error.message(PROGRAM))
+}
+
+///
+/// A `%b` argument has an escape that can't be read.
+///
+pub(crate) fn escape_error(error: &EscapeError) -> KnownError {
+    KnownError::new(ERROR_CODE, error.message(PROGRAM))
+}
+
+///
+/// A `*` width was taken from an argument that's too big.
+///
+pub(crate) fn invalid_field_width(argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: invalid field width: '{}'", PROGRAM, argument))
+}
+
+///
+/// A `*` precision was taken from an argument that's too big.
+///
+pub(crate) fn invalid_precision(argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: invalid precision: '{}'", PROGRAM, argument))
+}
+
+///
+/// An argument wasn't entirely the number it was meant to be.
+///
+pub(crate) fn argument_problem(problem: &ArgumentProblem, argument: &str) -> KnownError {
+    let code = if problem.is_failure() { ERROR_CODE } else { WARNING_CODE };
+    KnownError::new(code, problem.message(PROGRAM, argument))
+}
+
+///
+/// The format took no arguments, so the ones given are never used.
+///
+pub(crate) fn excess_arguments(first: &str) -> KnownError {
+    KnownError::new(WARNING_CODE, format!("{}: warning: ignoring excess arguments, starting with '{}'", PROGRAM, first))
+}
+
+///
+/// Standard output couldn't be written.
+///
+pub(crate) fn write_error(error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: write error: {}", PROGRAM, describe_io_error(error)))
+}
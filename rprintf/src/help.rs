@@ -0,0 +1,44 @@
+use std::io::{self, Write};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub(crate) fn version(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "rprintf {}", VERSION)
+}
+
+pub(crate) fn help(out: &mut dyn Write) -> io::Result<()> {
+    version(out)?;
+    writeln!(out)?;
+    writeln!(out, "Usage: printf FORMAT [ARGUMENT]...")?;
+    writeln!(out, "  or:  printf OPTION")?;
+    writeln!(out, "Print ARGUMENT(s) according to FORMAT, or execute according to OPTION:")?;
+    writeln!(out)?;
+    writeln!(out, "      --help        display this help and exit")?;
+    writeln!(out, "      --version     output version information and exit")?;
+    writeln!(out)?;
+    writeln!(out, "FORMAT controls the output as in C printf.  Interpreted sequences are:")?;
+    writeln!(out)?;
+    writeln!(out, "  \\\"      double quote")?;
+    writeln!(out, "  \\\\      backslash")?;
+    writeln!(out, "  \\a      alert (BEL)")?;
+    writeln!(out, "  \\b      backspace")?;
+    writeln!(out, "  \\c      produce no further output")?;
+    writeln!(out, "  \\e      escape")?;
+    writeln!(out, "  \\f      form feed")?;
+    writeln!(out, "  \\n      new line")?;
+    writeln!(out, "  \\r      carriage return")?;
+    writeln!(out, "  \\t      horizontal tab")?;
+    writeln!(out, "  \\v      vertical tab")?;
+    writeln!(out, "  \\NNN    byte with octal value NNN (1 to 3 digits)")?;
+    writeln!(out, "  \\xHH    byte with hexadecimal value HH (1 to 2 digits)")?;
+    writeln!(out, "  \\uHHHH  Unicode (ISO/IEC 10646) character with hex value HHHH (4 digits)")?;
+    writeln!(out, "  \\UHHHHHHHH  Unicode character with hex value HHHHHHHH (8 digits)")?;
+    writeln!(out, "  %%      a single %")?;
+    writeln!(out, "  %b      ARGUMENT as a string with '\\' escapes interpreted,")?;
+    writeln!(out, "          except that octal escapes are of the form \\0 or \\0NNN")?;
+    writeln!(out, "  %q      ARGUMENT is printed in a format that can be reused as shell input,")?;
+    writeln!(out, "          escaping non-printable characters with the proposed POSIX $'' syntax.")?;
+    writeln!(out)?;
+    writeln!(out, "and all C format specifications ending with one of diouxXfeEgGcs, with")?;
+    writeln!(out, "ARGUMENTs converted to proper type first.  Variable widths are handled.")
+}
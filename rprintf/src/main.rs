@@ -0,0 +1,15 @@
+mod config;
+mod errors;
+mod help;
+mod printf;
+
+use crate::printf::printf;
+
+///
+/// Main program entrypoint.
+///
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let status = printf(&args, &mut std::io::stdout().lock(), &mut std::io::stderr());
+    std::process::exit(status);
+}
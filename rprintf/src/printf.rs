@@ -0,0 +1,246 @@
+use std::io::{BufWriter, Write};
+use common::input::known_error::{report, KnownError};
+use common::output::format::argument::{to_float, to_signed, to_unsigned, Converted};
+use common::output::format::directive::{parse_format, Conversion, Count, Directive, Piece};
+use common::output::format::escape::{unescape, EscapeMode};
+use common::output::format::render::{render_bytes, render_float, render_signed, render_unsigned, Layout};
+use common::output::quote::shell_escape;
+use crate::config::Config;
+use crate::errors::{argument_problem, escape_error, excess_arguments, format_error, invalid_field_width, invalid_precision, write_error};
+use crate::help::{help, version};
+
+const SUCCESS: i32 = 0;
+const FAILURE: i32 = 1;
+
+///
+/// Run `printf` with command line arguments; the first argument is the program name.  Returns the
+/// exit status.
+///
+pub(crate) fn printf(args: &[String], out: &mut dyn Write, err: &mut dyn Write) -> i32 {
+    let config = Config::new(args);
+    if let Some(first) = config.errors.first() {
+        report(err, &config.errors);
+        return first.code;
+    }
+
+    if config.print_help_and_exit {
+        return help(out).map_or(FAILURE, |_| SUCCESS);
+    }
+    if config.print_version_and_exit {
+        return version(out).map_or(FAILURE, |_| SUCCESS);
+    }
+
+    let mut out = BufWriter::new(out);
+    let mut formatter = Formatter { arguments: &config.arguments, next: 0, output: vec![], status: SUCCESS, err };
+    let result = formatter.run(&parse_format(config.format.as_bytes()));
+
+    /* whatever was written before an error still goes out */
+    let written = out.write_all(&formatter.output).and_then(|_| out.flush());
+    let mut status = formatter.status;
+    if let Err(error) = result {
+        report(formatter.err, std::slice::from_ref(&error));
+        status = error.code;
+    }
+    if let Err(error) = written {
+        report(formatter.err, &[write_error(&error)]);
+        status = FAILURE;
+    }
+    status
+}
+
+///
+/// Writes the format over and over, each time taking the arguments its directives need, until
+/// they've all been used.
+///
+struct Formatter<'a> {
+    arguments: &'a [String],
+    next: usize,
+    output: Vec<u8>,
+    status: i32,
+    err: &'a mut dyn Write,
+}
+
+impl<'a> Formatter<'a> {
+    ///
+    /// Write the format at least once, and again while arguments are left, as long as it takes any.
+    ///
+    fn run(&mut self, pieces: &[Piece]) -> Result<(), KnownError> {
+        loop {
+            let start = self.next;
+            if self.pass(pieces)? {
+                return Ok(());
+            }
+            if self.next == start || self.next == self.arguments.len() {
+                break;
+            }
+        }
+
+        if let Some(first) = self.arguments.get(self.next) {
+            self.warn(excess_arguments(first));
+        }
+        Ok(())
+    }
+
+    ///
+    /// Write the format once, returning whether `\c` stopped all output.
+    ///
+    fn pass(&mut self, pieces: &[Piece]) -> Result<bool, KnownError> {
+        for piece in pieces {
+            match piece {
+                Piece::Literal(bytes) => self.output.extend_from_slice(bytes),
+                Piece::Directive(directive) => {
+                    if self.directive(directive)? {
+                        return Ok(true);
+                    }
+                }
+                Piece::Stop => return Ok(true),
+                Piece::Invalid(error) => return Err(format_error(error)),
+            }
+        }
+        Ok(false)
+    }
+
+    ///
+    /// Write one directive, taking its width, precision and value from the arguments as needed.
+    /// Returns whether `\c` in a `%b` argument stopped all output.
+    ///
+    fn directive(&mut self, directive: &Directive) -> Result<bool, KnownError> {
+        let width = self.count(directive.width, invalid_field_width)?;
+        let precision = self.count(directive.precision, invalid_precision)?;
+        let layout = Layout::new(directive.flags, width, precision);
+        let argument = self.take();
+
+        let rendered = match directive.conversion {
+            Conversion::Signed => render_signed(&layout, self.number(argument, to_signed)),
+            Conversion::Unsigned { radix, upper } => render_unsigned(&layout, self.number(argument, to_unsigned), radix, upper),
+            Conversion::Float { notation, upper } => render_float(&layout, self.number(argument, to_float), notation, upper),
+            /* a missing or empty argument is a NUL */
+            Conversion::Character => render_bytes(&layout, &[argument.and_then(|a| a.bytes().next()).unwrap_or(0)]),
+            Conversion::String => render_bytes(&layout, argument.unwrap_or_default().as_bytes()),
+            Conversion::Escaped => {
+                let Some(argument) = argument else {
+                    return Ok(false);
+                };
+                return unescape(argument.as_bytes(), EscapeMode::Argument, &mut self.output).map_err(|error| escape_error(&error));
+            }
+            Conversion::Quoted => argument.map(|argument| shell_escape(argument.as_bytes()).into_bytes()).unwrap_or_default(),
+        };
+        self.output.extend_from_slice(&rendered);
+        Ok(false)
+    }
+
+    fn take(&mut self) -> Option<&'a str> {
+        let argument = self.arguments.get(self.next)?;
+        self.next += 1;
+        Some(argument)
+    }
+
+    ///
+    /// A width or precision: as given, or from the next argument, which has to fit in an `int`.
+    ///
+    fn count(&mut self, count: Option<Count>, invalid: fn(&str) -> KnownError) -> Result<Option<i64>, KnownError> {
+        match count {
+            None => Ok(None),
+            Some(Count::Given(given)) => Ok(Some(i64::try_from(given).unwrap_or(i64::MAX))),
+            Some(Count::Argument) => {
+                let argument = self.take();
+                let value = self.number(argument, to_signed);
+                if i32::try_from(value).is_err() {
+                    return Err(invalid(argument.unwrap_or_default()));
+                }
+                Ok(Some(value))
+            }
+        }
+    }
+
+    ///
+    /// Read a number from an argument, reporting anything wrong with it; a missing one is zero.
+    ///
+    fn number<T: Default>(&mut self, argument: Option<&str>, convert: fn(&str) -> Converted<T>) -> T {
+        let Some(argument) = argument else {
+            return T::default();
+        };
+        let converted = convert(argument);
+        if let Some(problem) = &converted.problem {
+            self.warn(argument_problem(problem, argument));
+        }
+        converted.value
+    }
+
+    ///
+    /// Report a problem that doesn't stop the output, failing in the end if it's more than a warning.
+    ///
+    fn warn(&mut self, error: KnownError) {
+        self.status = self.status.max(error.code);
+        report(self.err, std::slice::from_ref(&error));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::printf::printf;
+
+    fn run(args: &[&str]) -> (i32, Vec<u8>, String) {
+        let args: Vec<String> = std::iter::once("printf").chain(args.iter().copied()).map(|s| s.to_string()).collect();
+        let mut out = vec![];
+        let mut err = vec![];
+        let status = printf(&args, &mut out, &mut err);
+        (status, out, String::from_utf8(err).unwrap())
+    }
+
+    fn output(args: &[&str]) -> String {
+        let (status, out, err) = run(args);
+        assert_eq!((0, String::new()), (status, err), "{:?}", args);
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!("42 -7 10 ff FF 255\n", output(&["%d %i %o %x %X %u\n", "42", "-7", "8", "255", "255", "0xff"]));
+        assert_eq!("3.14|1.234e+03|0.0001|0x8p-3\n", output(&["%.2f|%.3e|%g|%a\n", "3.14159", "1234", "0.0001", "1"]));
+        assert_eq!("[a][  bc][d\n][\"it's\"]\n", output(&["[%c][%4s][%b][%q]\n", "abc", "bc", "d\\n", "it's"]));
+        assert_eq!("100%\n", output(&["%d%%\n", "100"]));
+    }
+
+    #[test]
+    fn test_widths() {
+        assert_eq!("    1|2    |004\n", output(&["%*d|%-*d|%.*d\n", "5", "1", "-5", "2", "3", "4"]));
+        assert_eq!("  ab|\n", output(&["%*.*s|\n", "4", "2", "abc"]));
+    }
+
+    #[test]
+    fn test_reuse() {
+        assert_eq!("a\nb\nc\n", output(&["%s\n", "a", "b", "c"]));
+        assert_eq!("1 2\n3 0\n", output(&["%d %d\n", "1", "2", "3"]));
+        assert_eq!("[] 0\n", output(&["[%s] %d\n"]));
+    }
+
+    #[test]
+    fn test_characters() {
+        assert_eq!("97 233 41\n", output(&["%d %d %x\n", "'a", "\"é", "'A"]));
+        assert_eq!(b"[\0]".to_vec(), run(&["[%c]", ""]).1);
+    }
+
+    #[test]
+    fn test_stop() {
+        assert_eq!("a", output(&["a\\cb"]));
+        assert_eq!("x a", output(&["x %b %s\n", "a\\cb", "c"]));
+    }
+
+    #[test]
+    fn test_warnings() {
+        assert_eq!((1, b"12\n0\n".to_vec(), "printf: '12abc': value not completely converted\nprintf: 'abc': expected a numeric value\n".to_string()), run(&["%d\n", "12abc", "abc"]));
+        assert_eq!((1, b"9223372036854775807".to_vec(), "printf: '99999999999999999999': Numerical result out of range\n".to_string()), run(&["%d", "99999999999999999999"]));
+        assert_eq!((0, b"97".to_vec(), "printf: warning: b: character(s) following character constant have been ignored\n".to_string()), run(&["%d", "'ab"]));
+        assert_eq!((0, b"x\n".to_vec(), "printf: warning: ignoring excess arguments, starting with 'a'\n".to_string()), run(&["x\n", "a", "b"]));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!((1, b"a".to_vec(), "printf: %zb: invalid conversion specification\n".to_string()), run(&["a%zb"]));
+        assert_eq!((1, vec![], "printf: missing hexadecimal number in escape\n".to_string()), run(&["\\x"]));
+        assert_eq!((1, b"[".to_vec(), "printf: missing hexadecimal number in escape\n".to_string()), run(&["[%b]", "\\x"]));
+        assert_eq!((1, vec![], "printf: invalid field width: '9999999999'\n".to_string()), run(&["%*d", "9999999999", "1"]));
+        assert_eq!((1, vec![], "printf: missing operand\nTry 'printf --help' for more information.\n".to_string()), run(&[]));
+    }
+}
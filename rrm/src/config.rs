@@ -0,0 +1,231 @@
+use common::input::flags::flags_options::{parse_options, OptionSpec, ParsedArgument};
+use common::input::known_error::KnownError;
+use crate::errors::{invalid_argument, missing_operand, option_error, preserve_root_argument};
+
+const INTERACTIVE_ARGUMENTS: [&[&str]; 3] = [&["never", "no", "none"], &["once"], &["always", "yes"]];
+
+const OPTIONS: [OptionSpec; 12] = [
+    OptionSpec::flag("dir", Some('d'), Some("dir")),
+    OptionSpec::flag("force", Some('f'), Some("force")),
+    OptionSpec::optional("interactive", Some('i'), Some("interactive")),
+    OptionSpec::flag("interactive-once", Some('I'), None),
+    OptionSpec::flag("one-file-system", None, Some("one-file-system")),
+    OptionSpec::flag("no-preserve-root", None, Some("no-preserve-root")),
+    OptionSpec::optional("preserve-root", None, Some("preserve-root")),
+    OptionSpec::flag("recursive", Some('r'), Some("recursive")),
+    OptionSpec::flag("recursive", Some('R'), None),
+    OptionSpec::flag("verbose", Some('v'), Some("verbose")),
+    OptionSpec::flag("help", None, Some("help")),
+    OptionSpec::flag("version", None, Some("version")),
+];
+
+///
+/// When the user is asked before something is removed.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Prompt {
+    /// Never; `-f`.
+    Never,
+    /// When it's write-protected, and there's someone at a terminal to ask.
+    WriteProtected,
+    /// Always; `-i`.
+    Always,
+}
+
+///
+/// Whether the root directory, or more, is kept from `-r`.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PreserveRoot {
+    /// It isn't; `--no-preserve-root`.
+    No,
+    /// `/` isn't removed.
+    Root,
+    /// Nor is anything on a different file system from its parent; `--preserve-root=all`.
+    All,
+}
+
+///
+/// Storage for the application configuration.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct Config {
+    pub(crate) print_help_and_exit: bool,
+    pub(crate) print_version_and_exit: bool,
+
+    pub(crate) recursive: bool,
+    pub(crate) directories: bool,
+    /* missing files aren't complained about */
+    pub(crate) force: bool,
+    pub(crate) prompt: Prompt,
+    /* ask once, before starting, when there's a lot to remove; `-I` */
+    pub(crate) prompt_once: bool,
+    pub(crate) one_file_system: bool,
+    pub(crate) preserve_root: PreserveRoot,
+    pub(crate) verbose: bool,
+
+    pub(crate) files: Vec<String>,
+    pub(crate) errors: Vec<KnownError>,
+}
+
+impl Default for Config {
+    ///
+    /// Generate [Default] configuration.
+    ///
+    fn default() -> Self {
+        Config {
+            print_help_and_exit: false,
+            print_version_and_exit: false,
+
+            recursive: false,
+            directories: false,
+            force: false,
+            prompt: Prompt::WriteProtected,
+            prompt_once: false,
+            one_file_system: false,
+            preserve_root: PreserveRoot::Root,
+            verbose: false,
+
+            files: vec![],
+            errors: vec![],
+        }
+    }
+}
+
+impl Config {
+    ///
+    /// Initialize the application configuration based on provided application arguments; the first
+    /// argument is the program name.
+    ///
+    pub(crate) fn new(args: &[String]) -> Config {
+        let mut config = Self::default();
+
+        let parsed = match parse_options(args.get(1..).unwrap_or_default(), &OPTIONS) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                config.errors.push(option_error(&error));
+                return config;
+            }
+        };
+
+        for argument in parsed {
+            match argument {
+                ParsedArgument::Operand(operand) => config.files.push(operand),
+                ParsedArgument::Option { id, value } => {
+                    if let Err(error) = config.apply(id, value) {
+                        config.errors.push(error);
+                        return config;
+                    }
+                }
+            }
+        }
+        if config.print_help_and_exit || config.print_version_and_exit {
+            return config;
+        }
+
+        if config.files.is_empty() && !config.force {
+            config.errors.push(missing_operand());
+        }
+        config
+    }
+
+    ///
+    /// Of `-f`, `-i` and `-I`, and `--interactive`, the last one given wins.
+    ///
+    fn apply(&mut self, id: &str, value: Option<String>) -> Result<(), KnownError> {
+        match id {
+            "dir" => self.directories = true,
+            "force" => {
+                self.prompt = Prompt::Never;
+                self.force = true;
+                self.prompt_once = false;
+            }
+            "interactive" => match value.as_deref().unwrap_or("always") {
+                "never" | "no" | "none" => {
+                    self.prompt = Prompt::Never;
+                    self.prompt_once = false;
+                }
+                "once" => self.ask_once(),
+                "always" | "yes" => {
+                    self.prompt = Prompt::Always;
+                    self.force = false;
+                    self.prompt_once = false;
+                }
+                other => return Err(invalid_argument(other, "--interactive", &INTERACTIVE_ARGUMENTS)),
+            },
+            "interactive-once" => self.ask_once(),
+            "one-file-system" => self.one_file_system = true,
+            "no-preserve-root" => self.preserve_root = PreserveRoot::No,
+            "preserve-root" => {
+                self.preserve_root = match value.as_deref() {
+                    None => PreserveRoot::Root,
+                    Some("all") => PreserveRoot::All,
+                    Some(other) => return Err(preserve_root_argument(other)),
+                }
+            }
+            "recursive" => self.recursive = true,
+            "verbose" => self.verbose = true,
+            "help" => self.print_help_and_exit = true,
+            "version" => self.print_version_and_exit = true,
+            _ => unreachable!("option {} has no handler", id),
+        }
+        Ok(())
+    }
+
+    fn ask_once(&mut self) {
+        self.prompt = Prompt::WriteProtected;
+        self.force = false;
+        self.prompt_once = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{Config, PreserveRoot, Prompt};
+
+    fn config(args: &[&str]) -> Config {
+        let args: Vec<String> = std::iter::once("rm").chain(args.iter().copied()).map(String::from).collect();
+        Config::new(&args)
+    }
+
+    fn message(args: &[&str]) -> String {
+        config(args).errors.first().and_then(|error| error.message.clone()).unwrap_or_default()
+    }
+
+    #[test]
+    fn test_prompt() {
+        let cases = [
+            (vec!["a"], Prompt::WriteProtected, false, false),
+            (vec!["-f", "a"], Prompt::Never, true, false),
+            (vec!["-fi", "a"], Prompt::Always, false, false),
+            (vec!["-if", "a"], Prompt::Never, true, false),
+            (vec!["-fI", "a"], Prompt::WriteProtected, false, true),
+            (vec!["--interactive", "a"], Prompt::Always, false, false),
+            (vec!["--interactive=once", "a"], Prompt::WriteProtected, false, true),
+            (vec!["-f", "--interactive=never", "a"], Prompt::Never, true, false),
+        ];
+        for (args, prompt, force, once) in cases {
+            let config = config(&args);
+            assert_eq!((prompt, force, once), (config.prompt, config.force, config.prompt_once), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn test_preserve_root() {
+        assert_eq!(PreserveRoot::Root, config(&["a"]).preserve_root);
+        assert_eq!(PreserveRoot::No, config(&["--no-preserve-root", "a"]).preserve_root);
+        assert_eq!(PreserveRoot::All, config(&["--preserve-root=all", "a"]).preserve_root);
+        assert_eq!(PreserveRoot::Root, config(&["--preserve-root=all", "--preserve-root", "a"]).preserve_root);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!("rm: missing operand\nTry 'rm --help' for more information.", message(&[]));
+        assert!(config(&["-f"]).errors.is_empty());
+        assert_eq!("rm: unrecognized --preserve-root argument: 'bogus'", message(&["--preserve-root=bogus", "a"]));
+        assert_eq!(
+            "rm: invalid argument 'bogus' for '--interactive'\nValid arguments are:\n  - 'never', 'no', 'none'\n  - 'once'\n  - 'always', 'yes'\nTry 'rm --help' for more information.",
+            message(&["--interactive=bogus", "a"])
+        );
+    }
+}
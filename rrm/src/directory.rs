@@ -0,0 +1,220 @@
+use std::ffi::{CStr, CString, OsStr};
+use std::io::{self, ErrorKind};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+///
+/// What a file is, and where; as much of `stat` as removing it needs.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Status {
+    pub(crate) mode: u32,
+    pub(crate) size: u64,
+    pub(crate) device: u64,
+    pub(crate) inode: u64,
+}
+
+impl Status {
+    pub(crate) fn is_directory(&self) -> bool {
+        self.mode & libc::S_IFMT == libc::S_IFDIR
+    }
+
+    pub(crate) fn is_symbolic_link(&self) -> bool {
+        self.mode & libc::S_IFMT == libc::S_IFLNK
+    }
+
+    ///
+    /// Whether two statuses are of the same file.
+    ///
+    pub(crate) fn same_file(&self, other: &Status) -> bool {
+        self.device == other.device && self.inode == other.inode
+    }
+
+    ///
+    /// The kind of file, the way the questions before removing it name it.
+    ///
+    pub(crate) fn kind(&self) -> &'static str {
+        match self.mode & libc::S_IFMT {
+            libc::S_IFREG if self.size == 0 => "regular empty file",
+            libc::S_IFREG => "regular file",
+            libc::S_IFDIR => "directory",
+            libc::S_IFLNK => "symbolic link",
+            libc::S_IFIFO => "fifo",
+            libc::S_IFSOCK => "socket",
+            libc::S_IFCHR => "character special file",
+            libc::S_IFBLK => "block special file",
+            _ => "file",
+        }
+    }
+
+    fn from(stat: &libc::stat) -> Status {
+        Status { mode: stat.st_mode, size: stat.st_size as u64, device: stat.st_dev, inode: stat.st_ino }
+    }
+}
+
+///
+/// An open directory, which the files in it are looked at and removed relative to.  Nothing is
+/// reached through a path of more than one name, so a directory swapped for a symbolic link can't
+/// lead anywhere else, and there's no limit on how deep a tree can be.
+///
+#[derive(Debug)]
+pub(crate) struct Directory {
+    fd: OwnedFd,
+}
+
+impl Directory {
+    ///
+    /// Open the directory at `path` only to look at and remove what's in it, which needs no
+    /// permission to read it.
+    ///
+    pub(crate) fn open(path: &Path) -> io::Result<Directory> {
+        let path = c_path(path.as_os_str())?;
+        /* SAFETY: open only reads the path */
+        let fd = unsafe { libc::open(path.as_ptr(), libc::O_PATH | libc::O_DIRECTORY | libc::O_CLOEXEC) };
+        Self::owned(fd)
+    }
+
+    ///
+    /// Open the directory `name` in this one, to read its entries; a symbolic link isn't followed.
+    ///
+    pub(crate) fn open_at(&self, name: &CStr) -> io::Result<Directory> {
+        /* SAFETY: openat only reads the name */
+        let fd = unsafe { libc::openat(self.fd.as_raw_fd(), name.as_ptr(), libc::O_RDONLY | libc::O_DIRECTORY | libc::O_NOFOLLOW | libc::O_CLOEXEC) };
+        Self::owned(fd)
+    }
+
+    ///
+    /// Open the directory this one is in.
+    ///
+    pub(crate) fn parent(&self) -> io::Result<Directory> {
+        self.open_at(c"..")
+    }
+
+    ///
+    /// The status of the directory itself.
+    ///
+    pub(crate) fn status(&self) -> io::Result<Status> {
+        self.status_at(c"", libc::AT_EMPTY_PATH)
+    }
+
+    ///
+    /// The status of `name` in this directory; a symbolic link's own.
+    ///
+    pub(crate) fn entry_status(&self, name: &CStr) -> io::Result<Status> {
+        self.status_at(name, libc::AT_SYMLINK_NOFOLLOW)
+    }
+
+    fn status_at(&self, name: &CStr, flags: libc::c_int) -> io::Result<Status> {
+        /* SAFETY: fstatat only writes into the stat struct we hand it */
+        let mut stat: libc::stat = unsafe { std::mem::zeroed() };
+        if unsafe { libc::fstatat(self.fd.as_raw_fd(), name.as_ptr(), &mut stat, flags) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Status::from(&stat))
+    }
+
+    ///
+    /// Remove `name` from this directory; `directory` says whether it's an empty directory.
+    ///
+    pub(crate) fn remove(&self, name: &CStr, directory: bool) -> io::Result<()> {
+        let flags = if directory { libc::AT_REMOVEDIR } else { 0 };
+        /* SAFETY: unlinkat only reads the name */
+        if unsafe { libc::unlinkat(self.fd.as_raw_fd(), name.as_ptr(), flags) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    ///
+    /// Whether the user is kept from writing to `name` in this directory, judged by the effective
+    /// ids as the kernel would.
+    ///
+    pub(crate) fn write_protected(&self, name: &CStr) -> bool {
+        /* SAFETY: faccessat only reads the name */
+        let result = unsafe { libc::faccessat(self.fd.as_raw_fd(), name.as_ptr(), libc::W_OK, libc::AT_EACCESS) };
+        result != 0 && io::Error::last_os_error().raw_os_error() == Some(libc::EACCES)
+    }
+
+    ///
+    /// The names of the entries in the directory, without `.` and `..`, in the order they're read.
+    ///
+    pub(crate) fn names(&self) -> io::Result<Vec<CString>> {
+        /* SAFETY: the duplicate is given to fdopendir, and closed with the stream */
+        let duplicate = unsafe { libc::fcntl(self.fd.as_raw_fd(), libc::F_DUPFD_CLOEXEC, 0) };
+        if duplicate < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let stream = unsafe { libc::fdopendir(duplicate) };
+        if stream.is_null() {
+            let error = io::Error::last_os_error();
+            unsafe { libc::close(duplicate) };
+            return Err(error);
+        }
+        /* the duplicate shares its offset with the original, which may have been read already */
+        unsafe { libc::rewinddir(stream) };
+
+        let mut names = vec![];
+        let result = loop {
+            /* SAFETY: errno is cleared so the end of the stream can be told from an error */
+            unsafe { *libc::__errno_location() = 0 };
+            let entry = unsafe { libc::readdir(stream) };
+            if entry.is_null() {
+                let error = io::Error::last_os_error();
+                break if error.raw_os_error() == Some(0) { Ok(()) } else { Err(error) };
+            }
+            /* SAFETY: d_name is a nul terminated name in the entry readdir returned */
+            let name = unsafe { CStr::from_ptr((*entry).d_name.as_ptr()) };
+            if name != c"." && name != c".." {
+                names.push(name.to_owned());
+            }
+        };
+        /* SAFETY: the stream was opened above, and isn't used again */
+        unsafe { libc::closedir(stream) };
+        result.map(|_| names)
+    }
+
+    fn owned(fd: libc::c_int) -> io::Result<Directory> {
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        /* SAFETY: the descriptor was just opened, and nothing else owns it */
+        Ok(Directory { fd: unsafe { OwnedFd::from_raw_fd(fd) } })
+    }
+}
+
+pub(crate) fn c_path(path: &OsStr) -> io::Result<CString> {
+    CString::new(path.as_bytes()).map_err(|_| io::Error::from(ErrorKind::InvalidInput))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+    use std::path::Path;
+    use common::test_support::TempDir;
+    use crate::directory::Directory;
+
+    #[test]
+    fn test_directory() {
+        let dir = TempDir::new("directory");
+        dir.file("a", b"alpha");
+        std::fs::create_dir(dir.join("d")).unwrap();
+        std::fs::write(dir.join("d/inner"), b"").unwrap();
+
+        let directory = Directory::open(Path::new(dir.path())).unwrap();
+        assert!(directory.names().is_err());
+        let mut names = directory.open_at(c".").unwrap().names().unwrap();
+        names.sort();
+        assert_eq!(vec![CString::new("a").unwrap(), CString::new("d").unwrap()], names);
+        assert_eq!("regular file", directory.entry_status(c"a").unwrap().kind());
+        assert!(directory.entry_status(c"d").unwrap().is_directory());
+
+        let inner = directory.open_at(c"d").unwrap();
+        assert!(inner.parent().unwrap().status().unwrap().same_file(&directory.status().unwrap()));
+        assert_eq!(libc::ENOTEMPTY, directory.remove(c"d", true).unwrap_err().raw_os_error().unwrap());
+        inner.remove(c"inner", false).unwrap();
+        assert!(inner.names().unwrap().is_empty());
+        directory.remove(c"d", true).unwrap();
+        assert!(directory.open_at(c"a").is_err());
+    }
+}
@@ -0,0 +1,115 @@
+use std::io;
+use std::path::Path;
+use common::input::flags::flags_options::OptionError;
+use common::input::known_error::KnownError;
+use common::input::source::describe_io_error;
+use common::output::quote::{quote_name, quote_path};
+
+const ERROR_CODE: i32 = 1;
+const PROGRAM: &str = "rm";
+
+///
+/// The command line couldn't be split into options and operands.
+///
+pub(crate) fn option_error(error: &OptionError) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}\n{}", error.message(PROGRAM), try_help()))
+}
+
+///
+/// An option was given a value that isn't one of its choices; each choice is listed with its synonyms.
+///
+pub(crate) fn invalid_argument(argument: &str, option: &str, choices: &[&[&str]]) -> KnownError {
+    let choices: Vec<String> = choices.iter()
+        .map(|synonyms| format!("  - {}", synonyms.iter().map(|name| format!("'{}'", name)).collect::<Vec<_>>().join(", ")))
+        .collect();
+    KnownError::new(ERROR_CODE, format!("{}: invalid argument '{}' for '{}'\nValid arguments are:\n{}\n{}", PROGRAM, argument, option, choices.join("\n"), try_help()))
+}
+
+///
+/// `--preserve-root` takes only `all`.
+///
+pub(crate) fn preserve_root_argument(argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: unrecognized --preserve-root argument: {}", PROGRAM, quote_name(argument.as_bytes())))
+}
+
+pub(crate) fn missing_operand() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: missing operand\n{}", PROGRAM, try_help()))
+}
+
+pub(crate) fn cannot_remove(name: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot remove {}: {}", PROGRAM, quote_path(name), describe_io_error(error)))
+}
+
+///
+/// A tree couldn't be found again on the way back up, having been moved while it was being removed.
+///
+pub(crate) fn traversal_failed(name: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: traversal failed: {}: {}", PROGRAM, quote_path(name), describe_io_error(error)))
+}
+
+///
+/// A name ending in `.` or `..` was given with `-r`.
+///
+pub(crate) fn refusing_dot(name: &Path) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: refusing to remove '.' or '..' directory: skipping {}", PROGRAM, quote_path(name)))
+}
+
+///
+/// `-r` was asked to remove the root directory, without `--no-preserve-root`.
+///
+pub(crate) fn dangerous_root(name: &Path) -> KnownError {
+    let target = if name.as_os_str() == "/" { quote_path(name) } else { format!("{} (same as '/')", quote_path(name)) };
+    KnownError::new(ERROR_CODE, format!("{}: it is dangerous to operate recursively on {}\n{}: use --no-preserve-root to override this failsafe", PROGRAM, target, PROGRAM))
+}
+
+///
+/// A directory is on another file system: below a command line argument with `--one-file-system`,
+/// or from its parent with `--preserve-root=all`.
+///
+pub(crate) fn different_device(name: &Path, preserve_root_all: bool) -> KnownError {
+    let mut message = format!("{}: skipping {}, since it's on a different device", PROGRAM, quote_path(name));
+    if preserve_root_all {
+        message.push_str(&format!("\n{}: and --preserve-root=all is in effect", PROGRAM));
+    }
+    KnownError::new(ERROR_CODE, message)
+}
+
+///
+/// The question `-I` asks before starting, when there's a lot to remove.
+///
+pub(crate) fn once_prompt(count: usize, recursive: bool) -> String {
+    let arguments = if count == 1 { "argument" } else { "arguments" };
+    let recursively = if recursive { " recursively" } else { "" };
+    format!("{}: remove {} {}{}? ", PROGRAM, count, arguments, recursively)
+}
+
+///
+/// The question asked before removing something; `kind` describes it, as `regular file` or
+/// `directory`.
+///
+pub(crate) fn remove_prompt(name: &Path, kind: &str, write_protected: bool) -> String {
+    format!("{}: remove {}{} {}? ", PROGRAM, protection(write_protected), kind, quote_path(name))
+}
+
+///
+/// The question asked before going into a directory to remove what's in it.
+///
+pub(crate) fn descend_prompt(name: &Path, write_protected: bool) -> String {
+    format!("{}: descend into {}directory {}? ", PROGRAM, protection(write_protected), quote_path(name))
+}
+
+pub(crate) fn removed(name: &Path) -> String {
+    format!("removed {}", quote_path(name))
+}
+
+pub(crate) fn removed_directory(name: &Path) -> String {
+    format!("removed directory {}", quote_path(name))
+}
+
+fn protection(write_protected: bool) -> &'static str {
+    if write_protected { "write-protected " } else { "" }
+}
+
+fn try_help() -> String {
+    format!("Try '{} --help' for more information.", PROGRAM)
+}
@@ -0,0 +1,43 @@
+use std::io::{self, Write};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub(crate) fn version(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "rrm {}", VERSION)
+}
+
+pub(crate) fn help(out: &mut dyn Write) -> io::Result<()> {
+    version(out)?;
+    writeln!(out)?;
+    writeln!(out, "Usage: rm [OPTION]... [FILE]...")?;
+    writeln!(out, "Remove (unlink) the FILE(s).")?;
+    writeln!(out)?;
+    writeln!(out, "  -f, --force           ignore nonexistent files and arguments, never prompt")?;
+    writeln!(out, "  -i                    prompt before every removal")?;
+    writeln!(out, "  -I                    prompt once before removing more than three files, or")?;
+    writeln!(out, "                          when removing recursively; less intrusive than -i,")?;
+    writeln!(out, "                          while still giving protection against most mistakes")?;
+    writeln!(out, "      --interactive[=WHEN]  prompt according to WHEN: never, once (-I), or")?;
+    writeln!(out, "                          always (-i); without WHEN, prompt always")?;
+    writeln!(out, "      --one-file-system  when removing a hierarchy recursively, skip any")?;
+    writeln!(out, "                          directory that is on a file system different from")?;
+    writeln!(out, "                          that of the corresponding command line argument")?;
+    writeln!(out, "      --no-preserve-root  do not treat '/' specially")?;
+    writeln!(out, "      --preserve-root[=all]  do not remove '/' (default);")?;
+    writeln!(out, "                              with 'all', reject any command line argument")?;
+    writeln!(out, "                              on a separate device from its parent")?;
+    writeln!(out, "  -r, -R, --recursive   remove directories and their contents recursively")?;
+    writeln!(out, "  -d, --dir             remove empty directories")?;
+    writeln!(out, "  -v, --verbose         explain what is being done")?;
+    writeln!(out, "      --help        display this help and exit")?;
+    writeln!(out, "      --version     output version information and exit")?;
+    writeln!(out)?;
+    writeln!(out, "By default, rm does not remove directories.  Use the --recursive (-r or -R)")?;
+    writeln!(out, "option to remove each listed directory, too, along with all of its contents.")?;
+    writeln!(out)?;
+    writeln!(out, "To remove a file whose name starts with a '-', for example '-foo',")?;
+    writeln!(out, "use one of these commands:")?;
+    writeln!(out, "  rm -- -foo")?;
+    writeln!(out)?;
+    writeln!(out, "  rm ./-foo")
+}
@@ -0,0 +1,17 @@
+mod config;
+mod directory;
+mod errors;
+mod help;
+mod remover;
+mod rm;
+
+use crate::rm::rm;
+
+///
+/// Main program entrypoint.
+///
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let status = rm(&args, &mut std::io::stdin().lock(), &mut std::io::stdout(), &mut std::io::stderr());
+    std::process::exit(status);
+}
@@ -0,0 +1,353 @@
+use std::ffi::{CStr, CString, OsStr};
+use std::io::{self, BufRead, Write};
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use common::fs::path::base_name;
+use common::input::known_error::KnownError;
+use crate::config::{Config, PreserveRoot, Prompt};
+use crate::directory::{c_path, Directory, Status};
+use crate::errors::{cannot_remove, dangerous_root, descend_prompt, different_device, refusing_dot, remove_prompt, removed, removed_directory,
+                    traversal_failed};
+
+/* directories more than this far above the one being emptied are closed, and opened again on the way back up */
+const OPEN_DIRECTORIES: usize = 64;
+
+///
+/// What became of something that was to be removed; the worse of two is what became of the
+/// directory holding them.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Outcome {
+    Removed,
+    /// It was left, as the user asked.
+    Kept,
+    Failed,
+}
+
+///
+/// A directory being emptied, on the way down a tree.
+///
+struct Frame {
+    /* closed while it's far from the bottom of the tree */
+    directory: Option<Directory>,
+    status: Status,
+    name: CString,
+    path: PathBuf,
+    /* the entries still to be removed, last first */
+    entries: Vec<CString>,
+    /* the worst that became of the entries so far */
+    outcome: Outcome,
+    /* the user already agreed to its removal, when asked about it empty */
+    confirmed: bool,
+}
+
+///
+/// Removes files and trees the way the options ask, reporting problems as they're met.
+///
+pub(crate) struct Remover<'a> {
+    config: &'a Config,
+    input: &'a mut dyn BufRead,
+    out: &'a mut dyn Write,
+    err: &'a mut dyn Write,
+    /* whether there's someone at a terminal to ask about write-protected files */
+    terminal: bool,
+    /* the root directory, when it's kept from -r */
+    root: Option<Status>,
+}
+
+impl<'a> Remover<'a> {
+    ///
+    /// `input` answers the questions asked before removing files, which are written to `err` along
+    /// with any problems; `-v` tells what was removed on `out`.
+    ///
+    pub(crate) fn new(config: &'a Config, input: &'a mut dyn BufRead, out: &'a mut dyn Write, err: &'a mut dyn Write, terminal: bool) -> Remover<'a> {
+        let root = match config.preserve_root {
+            PreserveRoot::No => None,
+            PreserveRoot::Root | PreserveRoot::All => Directory::open(Path::new("/")).and_then(|root| root.status()).ok(),
+        };
+        Remover { config, input, out, err, terminal, root }
+    }
+
+    ///
+    /// Remove a file named on the command line, or with `-r`, a tree.
+    ///
+    pub(crate) fn remove_operand(&mut self, path: &Path) -> Outcome {
+        let config = self.config;
+        let path = &single_slash(path);
+        let (parent_path, name) = split(path);
+        if config.recursive && matches!(base_name(name).as_os_str().as_bytes(), b"." | b"..") {
+            return self.fail(refusing_dot(path));
+        }
+
+        let found = c_path(name.as_os_str()).and_then(|name| {
+            let parent = Directory::open(parent_path)?;
+            let status = parent.entry_status(&name)?;
+            Ok((parent, name, status))
+        });
+        let (parent, name, status) = match found {
+            Ok(found) => found,
+            Err(error) if config.force && error.kind() == io::ErrorKind::NotFound => return Outcome::Removed,
+            Err(error) => return self.fail(cannot_remove(path, &error)),
+        };
+
+        if !status.is_directory() {
+            return self.remove_file(&parent, &name, path, &status);
+        }
+        if !config.recursive {
+            if !config.directories {
+                return self.fail(cannot_remove(path, &io::Error::from_raw_os_error(libc::EISDIR)));
+            }
+            /* one that isn't empty is said to be so before anything is asked about it */
+            if parent.open_at(&name).and_then(|directory| directory.names()).is_ok_and(|names| !names.is_empty()) {
+                return self.fail(cannot_remove(path, &io::Error::from_raw_os_error(libc::ENOTEMPTY)));
+            }
+            return self.remove_empty_directory(&parent, &name, path, false);
+        }
+        if self.root.is_some_and(|root| root.same_file(&status)) {
+            return self.fail(dangerous_root(path));
+        }
+        if config.preserve_root == PreserveRoot::All && parent.status().is_ok_and(|parent| parent.device != status.device) {
+            return self.fail(different_device(path, true));
+        }
+        self.remove_tree(parent, name, path.to_path_buf(), status.device)
+    }
+
+    ///
+    /// Remove a directory and everything in it, one directory at a time, with no more than a few
+    /// of them open at once however deep the tree goes.
+    ///
+    fn remove_tree(&mut self, parent: Directory, name: CString, path: PathBuf, device: u64) -> Outcome {
+        let mut stack = vec![];
+        match self.enter(&parent, name, path, device) {
+            Ok(frame) => stack.push(frame),
+            Err(outcome) => return outcome,
+        }
+
+        while let Some(frame) = stack.last_mut() {
+            if let Some(entry) = frame.entries.pop() {
+                let Some(directory) = &frame.directory else {
+                    unreachable!("the directory being emptied is open");
+                };
+                let path = frame.path.join(OsStr::from_bytes(entry.as_bytes()));
+                let outcome = match directory.entry_status(&entry) {
+                    Err(error) if self.config.force && error.kind() == io::ErrorKind::NotFound => Outcome::Removed,
+                    Err(error) => self.fail(cannot_remove(&path, &error)),
+                    Ok(status) if status.is_directory() => match self.enter(directory, entry, path, device) {
+                        Ok(child) => {
+                            stack.push(child);
+                            if stack.len() > OPEN_DIRECTORIES {
+                                let far = stack.len() - OPEN_DIRECTORIES - 1;
+                                stack[far].directory = None;
+                            }
+                            continue;
+                        }
+                        Err(outcome) => outcome,
+                    },
+                    Ok(status) => self.remove_file(directory, &entry, &path, &status),
+                };
+                frame.outcome = frame.outcome.max(outcome);
+                continue;
+            }
+
+            let Some(mut frame) = stack.pop() else {
+                break;
+            };
+            if let Some(above) = stack.last_mut()
+                && above.directory.is_none() {
+                match reopen(&frame, above) {
+                    Ok(directory) => above.directory = Some(directory),
+                    Err(error) => return self.fail(traversal_failed(&above.path, &error)),
+                }
+            }
+            frame.directory = None;
+
+            let holder = stack.last().and_then(|above| above.directory.as_ref()).unwrap_or(&parent);
+            let outcome = match frame.outcome {
+                /* whatever was left inside keeps the directory too, which needn't be said again */
+                Outcome::Removed => self.remove_empty_directory(holder, &frame.name, &frame.path, frame.confirmed),
+                outcome => outcome,
+            };
+            match stack.last_mut() {
+                Some(above) => above.outcome = above.outcome.max(outcome),
+                None => return outcome,
+            }
+        }
+        Outcome::Failed
+    }
+
+    ///
+    /// Open a directory to empty it, once it's been checked that it may be, and the user agrees.
+    ///
+    fn enter(&mut self, holder: &Directory, name: CString, path: PathBuf, device: u64) -> Result<Frame, Outcome> {
+        let config = self.config;
+        let opened = holder.open_at(&name).and_then(|directory| {
+            let status = directory.status()?;
+            let mut entries = directory.names()?;
+            entries.reverse();
+            Ok((directory, status, entries))
+        });
+        let (directory, status, entries) = match opened {
+            Ok(opened) => opened,
+            Err(error) => return Err(self.fail(cannot_remove(&path, &error))),
+        };
+        /* what's checked is what was opened, which a rename since it was looked at can't change */
+        if config.one_file_system && status.device != device {
+            return Err(self.fail(different_device(&path, false)));
+        }
+
+        let empty = entries.is_empty();
+        let mut confirmed = false;
+        if let Some(write_protected) = self.asks(holder, &name, &status) {
+            let question = if empty { remove_prompt(&path, status.kind(), write_protected) } else { descend_prompt(&path, write_protected) };
+            if !self.ask(&question) {
+                return Err(Outcome::Kept);
+            }
+            confirmed = empty;
+        }
+        Ok(Frame { directory: Some(directory), status, name, path, entries, outcome: Outcome::Removed, confirmed })
+    }
+
+    fn remove_file(&mut self, holder: &Directory, name: &CStr, path: &Path, status: &Status) -> Outcome {
+        if let Some(write_protected) = self.asks(holder, name, status)
+            && !self.ask(&remove_prompt(path, status.kind(), write_protected)) {
+            return Outcome::Kept;
+        }
+        match holder.remove(name, false) {
+            Ok(()) => self.tell(&removed(path)),
+            Err(error) if self.config.force && error.kind() == io::ErrorKind::NotFound => Outcome::Removed,
+            Err(error) => self.fail(cannot_remove(path, &error)),
+        }
+    }
+
+    fn remove_empty_directory(&mut self, holder: &Directory, name: &CStr, path: &Path, confirmed: bool) -> Outcome {
+        if !confirmed
+            && let Ok(status) = holder.entry_status(name)
+            && let Some(write_protected) = self.asks(holder, name, &status)
+            && !self.ask(&remove_prompt(path, status.kind(), write_protected)) {
+            return Outcome::Kept;
+        }
+        match holder.remove(name, true) {
+            Ok(()) => self.tell(&removed_directory(path)),
+            Err(error) => self.fail(cannot_remove(path, &error)),
+        }
+    }
+
+    ///
+    /// Whether the user is to be asked before `name` is removed, and if so, whether it's
+    /// write-protected; someone at a terminal is asked about write-protected files, and `-i` asks
+    /// about everything.
+    ///
+    fn asks(&self, holder: &Directory, name: &CStr, status: &Status) -> Option<bool> {
+        let prompt = self.config.prompt;
+        if prompt == Prompt::Never {
+            return None;
+        }
+        /* a link's own permissions don't keep it from being removed */
+        let write_protected = !status.is_symbolic_link() && (prompt == Prompt::Always || self.terminal) && holder.write_protected(name);
+        (prompt == Prompt::Always || write_protected).then_some(write_protected)
+    }
+
+    ///
+    /// Ask a question; the answer is yes when it starts with `y`.
+    ///
+    pub(crate) fn ask(&mut self, question: &str) -> bool {
+        let _ = write!(self.err, "{}", question);
+        let _ = self.err.flush();
+        let mut answer = String::new();
+        if self.input.read_line(&mut answer).is_err() {
+            return false;
+        }
+        answer.starts_with(['y', 'Y'])
+    }
+
+    fn tell(&mut self, message: &str) -> Outcome {
+        if self.config.verbose {
+            let _ = writeln!(self.out, "{}", message);
+        }
+        Outcome::Removed
+    }
+
+    fn fail(&mut self, error: KnownError) -> Outcome {
+        if let Some(message) = &error.message {
+            let _ = writeln!(self.err, "{}", message);
+        }
+        Outcome::Failed
+    }
+}
+
+///
+/// Open a directory that was closed on the way down again, from one of its subdirectories; it
+/// must be the very directory that was left, not one that's taken its place since.
+///
+fn reopen(below: &Frame, above: &Frame) -> io::Result<Directory> {
+    let Some(directory) = &below.directory else {
+        return Err(io::Error::from_raw_os_error(libc::EBADF));
+    };
+    let parent = directory.parent()?;
+    if !parent.status()?.same_file(&above.status) {
+        return Err(io::Error::from_raw_os_error(libc::ENOENT));
+    }
+    Ok(parent)
+}
+
+///
+/// The directory a name given on the command line is in, and its last part, with any slashes that
+/// followed it; they make the kernel follow a symbolic link, and insist on a directory, just as
+/// they would for the whole name.  `d/sub/` is `sub/` in `d`, and `name` alone is in the current
+/// directory.
+///
+fn split(path: &Path) -> (&Path, &Path) {
+    let bytes = path.as_os_str().as_bytes();
+    let Some(end) = bytes.iter().rposition(|&byte| byte != b'/') else {
+        return (Path::new("/"), path);
+    };
+    let start = bytes[..=end].iter().rposition(|&byte| byte == b'/').map_or(0, |slash| slash + 1);
+    let parent = match start {
+        0 => Path::new("."),
+        _ => match bytes[..start].iter().rposition(|&byte| byte != b'/') {
+            Some(last) => Path::new(OsStr::from_bytes(&bytes[..=last])),
+            None => Path::new("/"),
+        },
+    };
+    (parent, Path::new(OsStr::from_bytes(&bytes[start..])))
+}
+
+///
+/// A name with the slashes it ends with made one, as it's shown in messages.
+///
+fn single_slash(path: &Path) -> PathBuf {
+    let bytes = path.as_os_str().as_bytes();
+    let slashes = bytes.iter().rev().take_while(|&&byte| byte == b'/').count();
+    let keep = if slashes > 1 && slashes < bytes.len() { bytes.len() - slashes + 1 } else { bytes.len() };
+    PathBuf::from(OsStr::from_bytes(&bytes[..keep]))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use crate::remover::{single_slash, split};
+
+    #[test]
+    fn test_split() {
+        let cases = [
+            ("a", ".", "a"),
+            ("d/a", "d", "a"),
+            ("d//sub//", "d", "sub//"),
+            ("/a", "/", "a"),
+            ("//a", "/", "a"),
+            ("/", "/", "/"),
+            ("d/.", "d", "."),
+        ];
+        for (path, parent, name) in cases {
+            assert_eq!((Path::new(parent), Path::new(name)), split(Path::new(path)), "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_single_slash() {
+        assert_eq!(Path::new("d/sub/"), single_slash(Path::new("d/sub///")));
+        assert_eq!(Path::new("d/"), single_slash(Path::new("d/")));
+        assert_eq!(Path::new("d"), single_slash(Path::new("d")));
+        assert_eq!(Path::new("//"), single_slash(Path::new("//")));
+    }
+}
@@ -0,0 +1,175 @@
+use std::io::{BufRead, Write};
+use std::path::Path;
+use common::input::known_error::report;
+use crate::config::Config;
+use crate::errors::once_prompt;
+use crate::help::{help, version};
+use crate::remover::{Outcome, Remover};
+
+const SUCCESS: i32 = 0;
+const FAILURE: i32 = 1;
+/* -I asks before removing more files than this */
+const ONCE_LIMIT: usize = 3;
+
+///
+/// Run `rm` with command line arguments; the first argument is the program name.  `input` answers
+/// the questions asked before removing files.  Returns the exit status: 0 when everything was
+/// removed, or left as the user asked, and 1 otherwise.
+///
+pub(crate) fn rm(args: &[String], input: &mut dyn BufRead, out: &mut dyn Write, err: &mut dyn Write) -> i32 {
+    let config = Config::new(args);
+    if let Some(first) = config.errors.first() {
+        report(err, &config.errors);
+        return first.code;
+    }
+
+    if config.print_help_and_exit {
+        return help(out).map_or(FAILURE, |_| SUCCESS);
+    }
+    if config.print_version_and_exit {
+        return version(out).map_or(FAILURE, |_| SUCCESS);
+    }
+
+    /* SAFETY: isatty only looks at the descriptor */
+    let terminal = unsafe { libc::isatty(libc::STDIN_FILENO) } == 1;
+    let mut remover = Remover::new(&config, input, out, err, terminal);
+    if config.prompt_once
+        && (config.recursive || config.files.len() > ONCE_LIMIT)
+        && !remover.ask(&once_prompt(config.files.len(), config.recursive)) {
+        return SUCCESS;
+    }
+
+    let mut outcome = Outcome::Removed;
+    for file in &config.files {
+        outcome = outcome.max(remover.remove_operand(Path::new(file)));
+    }
+    if outcome == Outcome::Failed { FAILURE } else { SUCCESS }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::os::fd::AsRawFd;
+    use std::path::Path;
+    use common::test_support::TempDir;
+    use crate::rm::rm;
+
+    fn run(args: &[&str], input: &str) -> (i32, String, String) {
+        let args: Vec<String> = std::iter::once("rm").chain(args.iter().copied()).map(String::from).collect();
+        let mut out = vec![];
+        let mut err = vec![];
+        let status = rm(&args, &mut input.as_bytes(), &mut out, &mut err);
+        (status, String::from_utf8(out).unwrap(), String::from_utf8(err).unwrap())
+    }
+
+    fn tree(dir: &TempDir) -> String {
+        fs::create_dir_all(dir.join("tree/sub/deeper")).unwrap();
+        dir.file("tree/a", b"alpha");
+        dir.file("tree/sub/b", b"");
+        dir.file("tree/sub/deeper/c", b"gamma");
+        std::os::unix::fs::symlink("a", dir.join("tree/link")).unwrap();
+        dir.join("tree").to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_files() {
+        let dir = TempDir::new("files");
+        let a = dir.file("a", b"alpha");
+        let b = dir.file("b", b"beta");
+        let missing = dir.join("missing").to_str().unwrap().to_string();
+
+        assert_eq!((1, String::new(), format!("rm: cannot remove '{}': No such file or directory\n", missing)), run(&[&a, &missing], ""));
+        assert!(!Path::new(&a).exists());
+        assert_eq!((0, format!("removed '{}'\n", b), String::new()), run(&["-fv", &missing, &b], ""));
+        assert!(!Path::new(&b).exists());
+        assert_eq!((0, String::new(), String::new()), run(&["-f"], ""));
+    }
+
+    #[test]
+    fn test_directories() {
+        let dir = TempDir::new("directories");
+        let tree = tree(&dir);
+        let empty = dir.join("empty");
+        fs::create_dir(&empty).unwrap();
+        let empty = empty.to_str().unwrap();
+
+        assert_eq!((1, String::new(), format!("rm: cannot remove '{}': Is a directory\n", empty)), run(&[empty], ""));
+        assert_eq!((1, String::new(), format!("rm: cannot remove '{}': Directory not empty\n", tree)), run(&["-d", &tree], ""));
+        assert_eq!((0, format!("removed directory '{}/'\n", empty), String::new()), run(&["-dv", &format!("{}/", empty)], ""));
+
+        let (status, out, err) = run(&["-rv", &tree], "");
+        assert_eq!((0, String::new()), (status, err));
+        for removed in ["removed 'TREE/a'", "removed 'TREE/link'", "removed 'TREE/sub/deeper/c'", "removed directory 'TREE/sub/deeper'", "removed directory 'TREE'"] {
+            assert!(out.lines().any(|line| line == removed.replace("TREE", &tree)), "{}", removed);
+        }
+        assert_eq!(out.lines().last(), Some(format!("removed directory '{}'", tree).as_str()));
+        assert!(!Path::new(&tree).exists());
+    }
+
+    #[test]
+    fn test_interactive() {
+        let dir = TempDir::new("interactive");
+        let a = dir.file("a", b"alpha");
+        let tree = dir.join("tree");
+        fs::create_dir_all(tree.join("sub")).unwrap();
+        dir.file("tree/sub/b", b"");
+        let tree = tree.to_str().unwrap();
+
+        assert_eq!((0, String::new(), format!("rm: remove regular file '{}'? ", a)), run(&["-i", &a], "n\n"));
+        assert!(Path::new(&a).exists());
+        assert_eq!((0, String::new(), "rm: remove 1 argument recursively? ".to_string()), run(&["-rI", &a], "no\n"));
+        assert_eq!((0, String::new(), String::new()), run(&["-I", &a], ""));
+        assert!(!Path::new(&a).exists());
+
+        let questions = format!("rm: descend into directory '{0}'? rm: descend into directory '{0}/sub'? rm: remove regular empty file '{0}/sub/b'? ", tree);
+        assert_eq!((0, String::new(), questions), run(&["-ri", tree], "y\ny\nn\n"));
+        assert!(Path::new(tree).join("sub/b").exists());
+        let questions = format!("rm: descend into directory '{0}'? rm: descend into directory '{0}/sub'? rm: remove regular empty file '{0}/sub/b'? \
+                                 rm: remove directory '{0}/sub'? rm: remove directory '{0}'? ", tree);
+        assert_eq!((0, String::new(), questions), run(&["-ri", tree], "y\ny\ny\ny\ny\n"));
+        assert!(!Path::new(tree).exists());
+    }
+
+    #[test]
+    fn test_refusals() {
+        let dir = TempDir::new("refusals");
+        let tree = tree(&dir);
+        let dot = format!("{}/.", tree);
+
+        assert_eq!((1, String::new(), format!("rm: refusing to remove '.' or '..' directory: skipping '{}'\n", dot)), run(&["-r", &dot], ""));
+        let parent = format!("{}/sub/..//", tree);
+        assert_eq!((1, String::new(), format!("rm: refusing to remove '.' or '..' directory: skipping '{}/sub/../'\n", tree)), run(&["-rf", &parent], ""));
+        assert!(Path::new(&tree).exists());
+    }
+
+    #[test]
+    fn test_symbolic_links() {
+        let dir = TempDir::new("links");
+        let tree = tree(&dir);
+        fs::create_dir(dir.join("outside")).unwrap();
+        dir.file("outside/kept", b"");
+        std::os::unix::fs::symlink(dir.join("outside"), dir.join("tree/sub/escape")).unwrap();
+
+        assert_eq!((0, String::new(), String::new()), run(&["-r", &tree], ""));
+        assert!(!Path::new(&tree).exists());
+        assert!(dir.join("outside/kept").exists());
+    }
+
+    #[test]
+    fn test_deep_tree() {
+        let dir = TempDir::new("deep");
+        /* deeper than PATH_MAX allows a name to be, made one directory at a time */
+        let name = "directory";
+        let depth = 4096 / name.len() + 100;
+        let mut current = fs::File::open(dir.path()).unwrap();
+        for _ in 0..depth {
+            let next = Path::new("/proc/self/fd").join(current.as_raw_fd().to_string()).join(name);
+            fs::create_dir(&next).unwrap();
+            current = fs::File::open(&next).unwrap();
+        }
+
+        let top = dir.join(name);
+        assert_eq!((0, String::new(), String::new()), run(&["-r", top.to_str().unwrap()], ""));
+        assert!(!top.exists());
+    }
+}
@@ -0,0 +1,114 @@
+use common::input::flags::flags_options::{parse_options, OptionSpec, ParsedArgument};
+use common::input::known_error::KnownError;
+use crate::errors::{missing_operand, option_error};
+
+const OPTIONS: [OptionSpec; 5] = [
+    OptionSpec::flag("ignore-fail-on-non-empty", None, Some("ignore-fail-on-non-empty")),
+    OptionSpec::flag("parents", Some('p'), Some("parents")),
+    OptionSpec::flag("verbose", Some('v'), Some("verbose")),
+    OptionSpec::flag("help", None, Some("help")),
+    OptionSpec::flag("version", None, Some("version")),
+];
+
+///
+/// Storage for the application configuration.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct Config {
+    pub(crate) print_help_and_exit: bool,
+    pub(crate) print_version_and_exit: bool,
+
+    /* a directory that can't be removed only because there's something in it isn't complained about */
+    pub(crate) ignore_non_empty: bool,
+    /* the directories each operand names it to be in are removed after it, deepest first */
+    pub(crate) parents: bool,
+    pub(crate) verbose: bool,
+
+    pub(crate) directories: Vec<String>,
+    pub(crate) errors: Vec<KnownError>,
+}
+
+impl Default for Config {
+    ///
+    /// Generate [Default] configuration.
+    ///
+    fn default() -> Self {
+        Config {
+            print_help_and_exit: false,
+            print_version_and_exit: false,
+
+            ignore_non_empty: false,
+            parents: false,
+            verbose: false,
+
+            directories: vec![],
+            errors: vec![],
+        }
+    }
+}
+
+impl Config {
+    ///
+    /// Initialize the application configuration based on provided application arguments; the first
+    /// argument is the program name.
+    ///
+    pub(crate) fn new(args: &[String]) -> Config {
+        let mut config = Self::default();
+
+        let parsed = match parse_options(args.get(1..).unwrap_or_default(), &OPTIONS) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                config.errors.push(option_error(&error));
+                return config;
+            }
+        };
+
+        for argument in parsed {
+            match argument {
+                ParsedArgument::Operand(operand) => config.directories.push(operand),
+                ParsedArgument::Option { id, .. } => match id {
+                    "ignore-fail-on-non-empty" => config.ignore_non_empty = true,
+                    "parents" => config.parents = true,
+                    "verbose" => config.verbose = true,
+                    "help" => config.print_help_and_exit = true,
+                    "version" => config.print_version_and_exit = true,
+                    _ => unreachable!("option {} has no handler", id),
+                },
+            }
+        }
+        if config.print_help_and_exit || config.print_version_and_exit {
+            return config;
+        }
+
+        if config.directories.is_empty() {
+            config.errors.push(missing_operand());
+        }
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+
+    fn config(args: &[&str]) -> Config {
+        let args: Vec<String> = std::iter::once("rmdir").chain(args.iter().copied()).map(String::from).collect();
+        Config::new(&args)
+    }
+
+    #[test]
+    fn test_config() {
+        let parsed = config(&["-pv", "--ignore-fail-on-non-empty", "a", "b"]);
+        assert!(parsed.errors.is_empty());
+        assert!(parsed.parents && parsed.verbose && parsed.ignore_non_empty);
+        assert_eq!(vec!["a", "b"], parsed.directories);
+        assert!(!config(&["a"]).parents);
+    }
+
+    #[test]
+    fn test_errors() {
+        let message = config(&["-p"]).errors.first().and_then(|error| error.message.clone()).unwrap_or_default();
+        assert_eq!("rmdir: missing operand\nTry 'rmdir --help' for more information.", message);
+        assert!(config(&["--version"]).errors.is_empty());
+    }
+}
@@ -0,0 +1,46 @@
+use std::io;
+use std::path::Path;
+use common::input::flags::flags_options::OptionError;
+use common::input::known_error::KnownError;
+use common::input::source::describe_io_error;
+use common::output::quote::quote_path;
+
+const ERROR_CODE: i32 = 1;
+const PROGRAM: &str = "rmdir";
+
+///
+/// The command line couldn't be split into options and operands.
+///
+pub(crate) fn option_error(error: &OptionError) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}\n{}", error.message(PROGRAM), try_help()))
+}
+
+pub(crate) fn missing_operand() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: missing operand\n{}", PROGRAM, try_help()))
+}
+
+pub(crate) fn failed_to_remove(name: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: failed to remove {}: {}", PROGRAM, quote_path(name), describe_io_error(error)))
+}
+
+///
+/// One of the directories an operand is in couldn't be removed, with `-p`.
+///
+pub(crate) fn failed_to_remove_directory(name: &Path, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: failed to remove directory {}: {}", PROGRAM, quote_path(name), describe_io_error(error)))
+}
+
+///
+/// A name ending in `/` is a symbolic link to a directory, which `rmdir` won't remove through.
+///
+pub(crate) fn link_not_followed(name: &Path) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: failed to remove {}: Symbolic link not followed", PROGRAM, quote_path(name)))
+}
+
+pub(crate) fn removing_directory(name: &Path) -> String {
+    format!("{}: removing directory, {}", PROGRAM, quote_path(name))
+}
+
+fn try_help() -> String {
+    format!("Try '{} --help' for more information.", PROGRAM)
+}
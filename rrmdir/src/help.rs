@@ -0,0 +1,25 @@
+use std::io::{self, Write};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub(crate) fn version(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "rrmdir {}", VERSION)
+}
+
+pub(crate) fn help(out: &mut dyn Write) -> io::Result<()> {
+    version(out)?;
+    writeln!(out)?;
+    writeln!(out, "Usage: rmdir [OPTION]... DIRECTORY...")?;
+    writeln!(out, "Remove the DIRECTORY(ies), if they are empty.")?;
+    writeln!(out)?;
+    writeln!(out, "      --ignore-fail-on-non-empty")?;
+    writeln!(out, "                    ignore each failure that is solely because a directory")?;
+    writeln!(out, "                    is non-empty")?;
+    writeln!(out)?;
+    writeln!(out, "  -p, --parents     remove DIRECTORY and its ancestors; e.g., 'rmdir -p a/b/c'")?;
+    writeln!(out, "                    is similar to 'rmdir a/b/c a/b a'")?;
+    writeln!(out)?;
+    writeln!(out, "  -v, --verbose     output a diagnostic for every directory processed")?;
+    writeln!(out, "      --help        display this help and exit")?;
+    writeln!(out, "      --version     output version information and exit")
+}
@@ -0,0 +1,199 @@
+use std::ffi::OsStr;
+use std::fs;
+use std::io::{self, Write};
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use common::input::known_error::{report, KnownError};
+use crate::config::Config;
+use crate::errors::{failed_to_remove, failed_to_remove_directory, link_not_followed, removing_directory};
+use crate::help::{help, version};
+
+const SUCCESS: i32 = 0;
+const FAILURE: i32 = 1;
+
+///
+/// Run `rmdir` with command line arguments; the first argument is the program name.  Returns the
+/// exit status.
+///
+pub(crate) fn rmdir(args: &[String], out: &mut dyn Write, err: &mut dyn Write) -> i32 {
+    let config = Config::new(args);
+    if let Some(first) = config.errors.first() {
+        report(err, &config.errors);
+        return first.code;
+    }
+
+    if config.print_help_and_exit {
+        return help(out).map_or(FAILURE, |_| SUCCESS);
+    }
+    if config.print_version_and_exit {
+        return version(out).map_or(FAILURE, |_| SUCCESS);
+    }
+
+    let mut status = SUCCESS;
+    for directory in &config.directories {
+        if let Err(error) = remove(&config, Path::new(directory), out) {
+            report(err, std::slice::from_ref(&error));
+            status = error.code;
+        }
+    }
+    status
+}
+
+///
+/// Remove the directory an operand names, and with `-p`, the ones it's in.
+///
+fn remove(config: &Config, name: &Path, out: &mut dyn Write) -> Result<(), KnownError> {
+    tell(config, name, out);
+    if let Err(error) = fs::remove_dir(name) {
+        if ignorable(config, name, &error) {
+            return Ok(());
+        }
+        if link_to_directory(name, &error) {
+            return Err(link_not_followed(name));
+        }
+        return Err(failed_to_remove(name, &error));
+    }
+    if config.parents {
+        remove_parents(config, name, out)?;
+    }
+    Ok(())
+}
+
+///
+/// Remove the directories `name` is in, as it spells them, deepest first: `a/b//c/` is in `a/b`,
+/// and that's in `a`.  The first that can't be removed stops it.
+///
+fn remove_parents(config: &Config, name: &Path, out: &mut dyn Write) -> Result<(), KnownError> {
+    let mut bytes = without_trailing_slashes(name.as_os_str().as_bytes());
+    while let Some(slash) = bytes.iter().rposition(|&byte| byte == b'/') {
+        /* the slashes before the last name go with it, unless they're all there is */
+        let end = bytes[..slash].iter().rposition(|&byte| byte != b'/').map_or(1, |last| last + 1);
+        bytes = &bytes[..end];
+        let parent = Path::new(OsStr::from_bytes(bytes));
+
+        tell(config, parent, out);
+        if let Err(error) = fs::remove_dir(parent) {
+            if ignorable(config, parent, &error) {
+                return Ok(());
+            }
+            /* one of the names may have been a symbolic link, rather than the directory it was expected to be */
+            if error.raw_os_error() == Some(libc::ENOTDIR) {
+                return Err(failed_to_remove(parent, &error));
+            }
+            return Err(failed_to_remove_directory(parent, &error));
+        }
+    }
+    Ok(())
+}
+
+///
+/// Whether a failure to remove a directory is only because there's something in it, and
+/// `--ignore-fail-on-non-empty` lets that go.  Some systems report other errors first, so then
+/// the directory is looked in.
+///
+fn ignorable(config: &Config, name: &Path, error: &io::Error) -> bool {
+    if !config.ignore_non_empty {
+        return false;
+    }
+    match error.raw_os_error() {
+        Some(libc::ENOTEMPTY | libc::EEXIST) => true,
+        Some(libc::EACCES | libc::EPERM | libc::EROFS | libc::EBUSY) => fs::read_dir(name).is_ok_and(|mut entries| entries.next().is_some()),
+        _ => false,
+    }
+}
+
+///
+/// Whether `name` couldn't be removed because it ends in `/` and is a symbolic link to a directory;
+/// Linux doesn't follow the link, and says only that it isn't a directory.
+///
+fn link_to_directory(name: &Path, error: &io::Error) -> bool {
+    let bytes = name.as_os_str().as_bytes();
+    if error.raw_os_error() != Some(libc::ENOTDIR) || bytes.last() != Some(&b'/') {
+        return false;
+    }
+    let followed = match fs::metadata(name) {
+        Ok(metadata) => metadata.is_dir(),
+        Err(error) => error.raw_os_error() != Some(libc::ENOTDIR),
+    };
+    followed && fs::symlink_metadata(OsStr::from_bytes(without_trailing_slashes(bytes))).is_ok_and(|metadata| metadata.is_symlink())
+}
+
+///
+/// A name without the slashes it ends with, unless that's all it is.
+///
+fn without_trailing_slashes(bytes: &[u8]) -> &[u8] {
+    let end = bytes.iter().rposition(|&byte| byte != b'/').map_or(bytes.len().min(1), |last| last + 1);
+    &bytes[..end]
+}
+
+fn tell(config: &Config, name: &Path, out: &mut dyn Write) {
+    if config.verbose {
+        let _ = writeln!(out, "{}", removing_directory(name));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use common::test_support::TempDir;
+    use crate::rmdir::{rmdir, without_trailing_slashes};
+
+    fn run(args: &[&str]) -> (i32, String, String) {
+        let args: Vec<String> = std::iter::once("rmdir").chain(args.iter().copied()).map(String::from).collect();
+        let mut out = vec![];
+        let mut err = vec![];
+        let status = rmdir(&args, &mut out, &mut err);
+        (status, String::from_utf8(out).unwrap(), String::from_utf8(err).unwrap())
+    }
+
+    #[test]
+    fn test_without_trailing_slashes() {
+        assert_eq!(b"a/b", without_trailing_slashes(b"a/b//"));
+        assert_eq!(b"a", without_trailing_slashes(b"a"));
+        assert_eq!(b"/", without_trailing_slashes(b"///"));
+        assert_eq!(b"", without_trailing_slashes(b""));
+    }
+
+    #[test]
+    fn test_remove() {
+        let dir = TempDir::new("remove");
+        fs::create_dir_all(dir.join("full/inner")).unwrap();
+        fs::create_dir(dir.join("empty")).unwrap();
+        let file = dir.file("file", b"");
+        std::os::unix::fs::symlink("empty", dir.join("link")).unwrap();
+        let name = |name: &str| dir.join(name).to_str().unwrap().to_string();
+
+        assert_eq!((0, format!("rmdir: removing directory, '{}'\n", name("empty")), String::new()), run(&["-v", &name("empty")]));
+        assert!(!dir.join("empty").exists());
+        assert_eq!((1, String::new(), format!("rmdir: failed to remove '{}': Directory not empty\n", name("full"))), run(&[&name("full")]));
+        assert_eq!((0, String::new(), String::new()), run(&["--ignore-fail-on-non-empty", &name("full")]));
+        assert_eq!((1, String::new(), format!("rmdir: failed to remove '{}': Not a directory\n", file)), run(&[&file]));
+        assert_eq!((1, String::new(), format!("rmdir: failed to remove '{}': No such file or directory\n", name("missing"))), run(&[&name("missing")]));
+
+        fs::create_dir(dir.join("empty")).unwrap();
+        let link = format!("{}/", name("link"));
+        assert_eq!((1, String::new(), format!("rmdir: failed to remove '{}': Symbolic link not followed\n", link)), run(&[&link]));
+        assert!(dir.join("empty").exists());
+    }
+
+    #[test]
+    fn test_parents() {
+        let dir = TempDir::new("parents");
+        fs::create_dir_all(dir.join("a/b/c")).unwrap();
+        fs::create_dir_all(dir.join("kept/d")).unwrap();
+        dir.file("kept/file", b"");
+        let kept = dir.join("kept").to_str().unwrap().to_string();
+
+        /* the way up ends at the test's own directory, which isn't empty */
+        let removing = format!("rmdir: removing directory, '{0}/a/b//c/'\nrmdir: removing directory, '{0}/a/b'\n\
+                                rmdir: removing directory, '{0}/a'\nrmdir: removing directory, '{0}'\n", dir.path());
+        assert_eq!((0, removing, String::new()), run(&["-pv", "--ignore-fail-on-non-empty", &format!("{}/a/b//c/", dir.path())]));
+        assert!(!dir.join("a").exists());
+
+        assert_eq!((1, String::new(), format!("rmdir: failed to remove directory '{}': Directory not empty\n", kept)), run(&["-p", &format!("{}/d", kept)]));
+        assert!(!dir.join("kept/d").exists());
+        fs::create_dir(dir.join("kept/d")).unwrap();
+        assert_eq!((0, String::new(), String::new()), run(&["-p", "--ignore-fail-on-non-empty", &format!("{}/d", kept)]));
+        assert!(dir.join("kept/file").exists());
+    }
+}
@@ -0,0 +1,71 @@
+use std::cmp::Ordering;
+use std::io::{self, BufRead, BufReader, Read};
+use common::input::known_error::KnownError;
+use crate::compare::Comparator;
+use crate::config::CheckMode;
+use crate::errors::{cannot_read, disorder, silent_disorder};
+
+///
+/// Check that an input is already sorted, one line at a time.  With `-u`, equal lines are out of
+/// order too.  The error is the first disorder, or a failure to read.
+///
+pub(crate) fn check(input: impl Read, name: &str, comparator: &Comparator, unique: bool, delimiter: u8, mode: CheckMode) -> Result<(), KnownError> {
+    let mut reader = BufReader::new(input);
+    let mut previous: Option<Vec<u8>> = None;
+    let mut line = vec![];
+    let mut line_number = 0;
+
+    loop {
+        line.clear();
+        match reader.read_until(delimiter, &mut line) {
+            Ok(0) => return Ok(()),
+            Ok(_) => {}
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(cannot_read(name, &error)),
+        }
+        if line.last() == Some(&delimiter) {
+            line.pop();
+        }
+        line_number += 1;
+
+        if let Some(previous) = &previous {
+            let ordering = comparator.compare(previous, &line);
+            if ordering == Ordering::Greater || (unique && ordering == Ordering::Equal) {
+                return Err(match mode {
+                    CheckMode::DiagnoseFirst => disorder(name, line_number, &line),
+                    CheckMode::Quiet => silent_disorder(),
+                });
+            }
+        }
+        previous = Some(std::mem::take(&mut line));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::check::check;
+    use crate::compare::Comparator;
+    use crate::config::Config;
+
+    fn run(line: &str, input: &str) -> Result<(), (i32, Option<String>)> {
+        let args: Vec<String> = line.split_whitespace().map(|s| s.to_string()).collect();
+        let config = Config::new(&args);
+        let comparator = Comparator::new(&config);
+        check(input.as_bytes(), "-", &comparator, config.unique, config.delimiter, config.check.unwrap())
+            .map_err(|error| (error.code, error.message))
+    }
+
+    #[test]
+    fn test_sorted() {
+        assert_eq!(Ok(()), run("sort -c", "a\nb\nb\nc"));
+        assert_eq!(Ok(()), run("sort -c -n", "2\n10\n"));
+        assert_eq!(Ok(()), run("sort -c", ""));
+    }
+
+    #[test]
+    fn test_disorder() {
+        assert_eq!(Err((1, Some("sort: -:3: disorder: a".to_string()))), run("sort -c", "b\nc\na\nd\n"));
+        assert_eq!(Err((1, Some("sort: -:2: disorder: b".to_string()))), run("sort -cu", "b\nb\n"));
+        assert_eq!(Err((1, None)), run("sort -C -n", "10\n2\n"));
+    }
+}
@@ -0,0 +1,125 @@
+use std::cmp::Ordering;
+use rcal::time::locale::Locale;
+use rcal::time::name::MonthAbbreviations;
+use crate::config::Config;
+use crate::key::{Key, KeyOptions, KeyType};
+use crate::ordering::{compare_general_numeric, compare_human_numeric, compare_month, compare_numeric, compare_version};
+
+///
+/// Orders lines by the configured keys.
+///
+pub(crate) struct Comparator {
+    keys: Vec<Key>,
+    separator: Option<u8>,
+    /* when every key ties, fall back on the whole line, so equal keys still have one order */
+    last_resort: bool,
+    reverse: bool,
+    /* lowercased once here, rather than for every comparison */
+    months: MonthAbbreviations,
+}
+
+impl Comparator {
+    pub(crate) fn new(config: &Config) -> Comparator {
+        Comparator {
+            keys: config.keys.clone(),
+            separator: config.separator,
+            last_resort: !config.stable && !config.unique,
+            reverse: config.global.reverse,
+            months: MonthAbbreviations::new(Locale::from_environment()),
+        }
+    }
+
+    ///
+    /// Compare two lines, without their delimiters.
+    ///
+    pub(crate) fn compare(&self, a: &[u8], b: &[u8]) -> Ordering {
+        let by_keys = self.compare_keys(a, b);
+        if by_keys != Ordering::Equal || !self.last_resort {
+            return by_keys;
+        }
+
+        if self.reverse { b.cmp(a) } else { a.cmp(b) }
+    }
+
+    fn compare_keys(&self, a: &[u8], b: &[u8]) -> Ordering {
+        for key in self.keys.iter() {
+            let ordering = self.compare_key(&key.options, key.extract(a, self.separator), key.extract(b, self.separator));
+            if ordering != Ordering::Equal {
+                return if key.options.reverse { ordering.reverse() } else { ordering };
+            }
+        }
+        Ordering::Equal
+    }
+
+    fn compare_key(&self, options: &KeyOptions, a: &[u8], b: &[u8]) -> Ordering {
+        match options.key_type {
+            KeyType::Text => compare_text(options, a, b),
+            KeyType::Numeric => compare_numeric(a, b),
+            KeyType::GeneralNumeric => compare_general_numeric(a, b),
+            KeyType::HumanNumeric => compare_human_numeric(a, b),
+            KeyType::Month => compare_month(a, b, &self.months),
+            KeyType::Version => compare_version(a, b),
+        }
+    }
+}
+
+///
+/// Compare bytewise, leaving out what `-d` and `-i` ignore and folding case for `-f`.
+///
+fn compare_text(options: &KeyOptions, a: &[u8], b: &[u8]) -> Ordering {
+    if !options.dictionary_order && !options.ignore_nonprinting && !options.fold_case {
+        return a.cmp(b);
+    }
+
+    let keep = |byte: &u8| {
+        (!options.dictionary_order || byte.is_ascii_alphanumeric() || *byte == b' ' || *byte == b'\t')
+            && (!options.ignore_nonprinting || (b' '..=b'~').contains(byte))
+    };
+    let fold = |byte: u8| if options.fold_case { byte.to_ascii_uppercase() } else { byte };
+
+    a.iter().filter(|b| keep(b)).map(|b| fold(*b))
+        .cmp(b.iter().filter(|b| keep(b)).map(|b| fold(*b)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+    use crate::compare::Comparator;
+    use crate::config::Config;
+
+    fn comparator(line: &str) -> Comparator {
+        let args: Vec<String> = line.split_whitespace().map(|s| s.to_string()).collect();
+        Comparator::new(&Config::new(&args))
+    }
+
+    fn sorted<'a>(line: &str, mut values: Vec<&'a str>) -> Vec<&'a str> {
+        let comparator = comparator(line);
+        values.sort_by(|a, b| comparator.compare(a.as_bytes(), b.as_bytes()));
+        values
+    }
+
+    #[test]
+    fn test_text_options() {
+        assert_eq!(vec!["B", "a", "c"], sorted("sort", vec!["c", "a", "B"]));
+        assert_eq!(vec!["a", "B", "c"], sorted("sort -f", vec!["c", "a", "B"]));
+        assert_eq!(vec!["ab", "a-c", "b"], sorted("sort -d", vec!["b", "a-c", "ab"]));
+        assert_eq!(Ordering::Equal, comparator("sort -i -s").compare(b"a\x01b", b"ab"));
+    }
+
+    #[test]
+    fn test_keys() {
+        let lines = vec!["x 10 b", "y 9 a", "z 10 a"];
+
+        assert_eq!(vec!["y 9 a", "x 10 b", "z 10 a"], sorted("sort -k2,2n", lines.clone()));
+        assert_eq!(vec!["z 10 a", "x 10 b", "y 9 a"], sorted("sort -k2,2nr -k3,3", lines.clone()));
+        assert_eq!(vec!["y 9 a", "z 10 a", "x 10 b"], sorted("sort -k3,3 -k2,2n", lines));
+    }
+
+    #[test]
+    fn test_last_resort() {
+        /* the keys tie; the whole line decides, unless -s keeps input order */
+        assert_eq!(vec!["1 a", "1 b"], sorted("sort -k1,1n", vec!["1 b", "1 a"]));
+        assert_eq!(vec!["1 b", "1 a"], sorted("sort -s -k1,1n", vec!["1 b", "1 a"]));
+        assert_eq!(vec!["1 b", "1 a"], sorted("sort -r -k1,1n", vec!["1 a", "1 b"]));
+    }
+}
@@ -0,0 +1,292 @@
+use common::input::flags::flags_options::{parse_options, OptionSpec, ParsedArgument};
+use common::input::known_error::KnownError;
+use common::input::size::parse_size;
+use common::input::source::InputSource;
+use crate::errors::{empty_tab, extra_check_operand, invalid_buffer_size, invalid_check_mode, invalid_thread_count, multi_character_tab, option_error};
+use crate::key::{Key, KeyOptions};
+
+const DEFAULT_BUFFER_SIZE: usize = 256 * 1024 * 1024;
+const MAX_DEFAULT_THREADS: usize = 8;
+const KIBIBYTE: u64 = 1024;
+const NUL_TAB: &str = "\\0";
+
+const OPTIONS: [OptionSpec; 23] = [
+    OptionSpec::flag("b", Some('b'), Some("ignore-leading-blanks")),
+    OptionSpec::flag("d", Some('d'), Some("dictionary-order")),
+    OptionSpec::flag("f", Some('f'), Some("ignore-case")),
+    OptionSpec::flag("g", Some('g'), Some("general-numeric-sort")),
+    OptionSpec::flag("i", Some('i'), Some("ignore-nonprinting")),
+    OptionSpec::flag("M", Some('M'), Some("month-sort")),
+    OptionSpec::flag("h", Some('h'), Some("human-numeric-sort")),
+    OptionSpec::flag("n", Some('n'), Some("numeric-sort")),
+    OptionSpec::flag("r", Some('r'), Some("reverse")),
+    OptionSpec::flag("V", Some('V'), Some("version-sort")),
+    OptionSpec::optional("check", Some('c'), Some("check")),
+    OptionSpec::flag("check-quiet", Some('C'), None),
+    OptionSpec::required("key", Some('k'), Some("key")),
+    OptionSpec::required("output", Some('o'), Some("output")),
+    OptionSpec::flag("stable", Some('s'), Some("stable")),
+    OptionSpec::required("buffer-size", Some('S'), Some("buffer-size")),
+    OptionSpec::required("separator", Some('t'), Some("field-separator")),
+    OptionSpec::required("temporary-directory", Some('T'), Some("temporary-directory")),
+    OptionSpec::required("parallel", None, Some("parallel")),
+    OptionSpec::flag("unique", Some('u'), Some("unique")),
+    OptionSpec::flag("zero", Some('z'), Some("zero-terminated")),
+    OptionSpec::flag("help", None, Some("help")),
+    OptionSpec::flag("version", None, Some("version")),
+];
+
+///
+/// How `-c` reports input that isn't sorted.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CheckMode {
+    /// Print the first line that's out of order.
+    DiagnoseFirst,
+    /// Only set the exit status.
+    Quiet,
+}
+
+///
+/// Storage for the application configuration.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct Config {
+    pub(crate) print_help_and_exit: bool,
+    pub(crate) print_version_and_exit: bool,
+
+    pub(crate) global: KeyOptions,
+    pub(crate) keys: Vec<Key>,
+    pub(crate) separator: Option<u8>,
+    pub(crate) stable: bool,
+    pub(crate) unique: bool,
+    pub(crate) delimiter: u8,
+    pub(crate) check: Option<CheckMode>,
+
+    pub(crate) output: Option<String>,
+    pub(crate) buffer_size: usize,
+    pub(crate) temp_dirs: Vec<String>,
+    pub(crate) threads: usize,
+
+    pub(crate) sources: Vec<InputSource>,
+    pub(crate) errors: Vec<KnownError>,
+}
+
+impl Default for Config {
+    ///
+    /// Generate [Default] configuration.
+    ///
+    fn default() -> Self {
+        Config {
+            print_help_and_exit: false,
+            print_version_and_exit: false,
+
+            global: KeyOptions::default(),
+            keys: vec![],
+            separator: None,
+            stable: false,
+            unique: false,
+            delimiter: b'\n',
+            check: None,
+
+            output: None,
+            buffer_size: DEFAULT_BUFFER_SIZE,
+            temp_dirs: vec![],
+            threads: std::thread::available_parallelism().map_or(1, |n| n.get()).min(MAX_DEFAULT_THREADS),
+
+            sources: vec![],
+            errors: vec![],
+        }
+    }
+}
+
+impl Config {
+    ///
+    /// Initialize the application configuration based on provided application arguments; the first
+    /// argument is the program name.
+    ///
+    pub(crate) fn new(args: &[String]) -> Config {
+        let mut config = Self::default();
+
+        let parsed = match parse_options(args.get(1..).unwrap_or_default(), &OPTIONS) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                config.errors.push(option_error(&error));
+                return config;
+            }
+        };
+
+        /* keys without option letters take the global options, wherever those appear */
+        let mut operands = vec![];
+        let mut key_specs = vec![];
+        for argument in parsed {
+            match argument {
+                ParsedArgument::Operand(operand) => operands.push(operand),
+                ParsedArgument::Option { id: "key", value } => key_specs.push(value.unwrap_or_default()),
+                ParsedArgument::Option { id, value } => {
+                    if let Err(error) = config.apply(id, value.unwrap_or_default()) {
+                        config.errors.push(error);
+                    }
+                }
+            }
+        }
+
+        for spec in key_specs {
+            match Key::parse(&spec, &config.global) {
+                Ok(key) => config.keys.push(key),
+                Err(error) => config.errors.push(error),
+            }
+        }
+        if config.keys.is_empty() {
+            config.keys.push(Key::whole_line(config.global));
+        }
+
+        if config.check.is_some() && operands.len() > 1 {
+            config.errors.push(extra_check_operand(&operands[1]));
+        }
+        config.sources = InputSource::from_operands(&operands);
+
+        config
+    }
+
+    fn apply(&mut self, id: &str, value: String) -> Result<(), KnownError> {
+        match id {
+            "b" | "d" | "f" | "g" | "i" | "M" | "h" | "n" | "r" | "V" => {
+                let letter = id.chars().next().unwrap();
+                self.global.apply(letter, true, true)?;
+            }
+            "check" => self.check = Some(parse_check_mode(&value)?),
+            "check-quiet" => self.check = Some(CheckMode::Quiet),
+            "output" => self.output = Some(value),
+            "stable" => self.stable = true,
+            "buffer-size" => self.buffer_size = parse_buffer_size(&value).ok_or_else(|| invalid_buffer_size(&value))?,
+            "separator" => self.separator = Some(parse_separator(&value)?),
+            "temporary-directory" => self.temp_dirs.push(value),
+            "parallel" => self.threads = value.parse::<usize>().ok().filter(|&n| n > 0).ok_or_else(|| invalid_thread_count(&value))?,
+            "unique" => self.unique = true,
+            "zero" => self.delimiter = b'\0',
+            "help" => self.print_help_and_exit = true,
+            "version" => self.print_version_and_exit = true,
+            _ => unreachable!("option {} has no handler", id),
+        }
+        Ok(())
+    }
+}
+
+fn parse_check_mode(value: &str) -> Result<CheckMode, KnownError> {
+    match value {
+        "" | "diagnose-first" => Ok(CheckMode::DiagnoseFirst),
+        "quiet" | "silent" => Ok(CheckMode::Quiet),
+        _ => Err(invalid_check_mode(value)),
+    }
+}
+
+fn parse_separator(value: &str) -> Result<u8, KnownError> {
+    match value.as_bytes() {
+        [] => Err(empty_tab()),
+        [byte] => Ok(*byte),
+        _ if value == NUL_TAB => Ok(b'\0'),
+        _ => Err(multi_character_tab(value)),
+    }
+}
+
+///
+/// Read `-S`: a plain number is in KiB, `b` means bytes, `%` a share of physical memory, and the
+/// usual multiplier suffixes work too.
+///
+fn parse_buffer_size(value: &str) -> Option<usize> {
+    let bytes = if let Some(percent) = value.strip_suffix('%') {
+        let percent = percent.parse::<u64>().ok().filter(|&p| p <= 100)?;
+        physical_memory()? / 100 * percent
+    } else if let Some(bytes) = value.strip_suffix('b') {
+        bytes.parse::<u64>().ok()?
+    } else if value.bytes().all(|b| b.is_ascii_digit()) {
+        value.parse::<u64>().ok()?.checked_mul(KIBIBYTE)?
+    } else {
+        parse_size(value)?
+    };
+
+    usize::try_from(bytes).ok().filter(|&bytes| bytes > 0)
+}
+
+fn physical_memory() -> Option<u64> {
+    /* SAFETY: sysconf only reads system configuration */
+    let pages = unsafe { libc::sysconf(libc::_SC_PHYS_PAGES) };
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    if pages <= 0 || page_size <= 0 {
+        return None;
+    }
+    (pages as u64).checked_mul(page_size as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::input::source::InputSource;
+    use crate::config::{CheckMode, Config};
+    use crate::key::{Key, KeyOptions, KeyType, Position};
+
+    fn config(line: &str) -> Config {
+        let args: Vec<String> = line.split_whitespace().map(|s| s.to_string()).collect();
+        Config::new(&args)
+    }
+
+    fn messages(config: &Config) -> Vec<String> {
+        config.errors.iter().map(|e| e.message.clone().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_defaults() {
+        let config = config("sort");
+
+        assert!(config.errors.is_empty());
+        assert_eq!(vec![Key::whole_line(KeyOptions::default())], config.keys);
+        assert_eq!(vec![InputSource::Stdin], config.sources);
+        assert_eq!(None, config.separator);
+        assert_eq!(None, config.check);
+        assert_eq!(b'\n', config.delimiter);
+        assert!(config.threads >= 1);
+    }
+
+    #[test]
+    fn test_global_options_apply_to_keys() {
+        let config = config("sort -k 2 -n -k3r a b");
+
+        assert_eq!(KeyType::Numeric, config.global.key_type);
+        assert_eq!(KeyType::Numeric, config.keys[0].options.key_type);
+        assert_eq!(Position { field: 2, character: 1 }, config.keys[0].start);
+        assert_eq!(KeyType::Text, config.keys[1].options.key_type);
+        assert!(config.keys[1].options.reverse);
+        assert_eq!(2, config.sources.len());
+    }
+
+    #[test]
+    fn test_sizes_and_separators() {
+        assert_eq!(10 * 1024, config("sort -S 10").buffer_size);
+        assert_eq!(100, config("sort -S 100b").buffer_size);
+        assert_eq!(3 * 1024 * 1024, config("sort --buffer-size=3M").buffer_size);
+        assert!(config("sort -S 1%").buffer_size > 0);
+        assert_eq!(Some(b':'), config("sort -t :").separator);
+        assert_eq!(Some(b'\0'), config("sort -t \\0").separator);
+        assert_eq!(3, config("sort --parallel=3").threads);
+        assert_eq!(vec!["a", "b"], config("sort -T a -T b").temp_dirs);
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(Some(CheckMode::DiagnoseFirst), config("sort -c").check);
+        assert_eq!(Some(CheckMode::Quiet), config("sort --check=silent").check);
+        assert_eq!(Some(CheckMode::Quiet), config("sort -C").check);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(vec!["sort: options '-gn' are incompatible"], messages(&config("sort -n -g")));
+        assert_eq!(vec!["sort: multi-character tab 'ab'"], messages(&config("sort -t ab")));
+        assert_eq!(vec!["sort: invalid -S argument 'x'"], messages(&config("sort -S x")));
+        assert_eq!(vec!["sort: invalid number after '--parallel': '0'"], messages(&config("sort --parallel=0")));
+        assert_eq!(vec!["sort: invalid argument 'loud' for '--check'"], messages(&config("sort --check=loud")));
+        assert_eq!(vec!["sort: extra operand 'b' not allowed with -c"], messages(&config("sort -c a b")));
+        assert_eq!(vec!["sort: field number is zero: invalid field specification '0'"], messages(&config("sort -k 0")));
+        assert_eq!(vec!["sort: invalid option -- 'x'"], messages(&config("sort -x")));
+    }
+}
@@ -0,0 +1,134 @@
+use std::io;
+use common::input::flags::flags_options::OptionError;
+use common::input::known_error::KnownError;
+use common::input::source::describe_io_error;
+
+const ERROR_CODE: i32 = 2;
+const DISORDER_CODE: i32 = 1;
+const PROGRAM: &str = "sort";
+
+///
+/// The command line couldn't be split into options and operands.
+///
+pub(crate) fn option_error(error: &OptionError) -> KnownError {
+    KnownError::new(ERROR_CODE, error.message(PROGRAM))
+}
+
+///
+/// A key definition starts with field zero.
+///
+pub(crate) fn field_zero(spec: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: field number is zero: invalid field specification '{}'", PROGRAM, spec))
+}
+
+///
+/// A key definition starts at character zero.
+///
+pub(crate) fn offset_zero(spec: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: character offset is zero: invalid field specification '{}'", PROGRAM, spec))
+}
+
+///
+/// A key definition doesn't start with a field number.
+///
+pub(crate) fn invalid_field_start(spec: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: invalid number at field start: invalid count at start of '{}'", PROGRAM, spec))
+}
+
+///
+/// A key definition has something other than option letters after a position.
+///
+pub(crate) fn stray_character(spec: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: stray character in field spec: invalid field specification '{}'", PROGRAM, spec))
+}
+
+///
+/// Two ways of ordering were asked for at once, e.g. `-n` and `-g`.
+///
+pub(crate) fn incompatible_options(letters: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: options '-{}' are incompatible", PROGRAM, letters))
+}
+
+///
+/// `-t` was given more than one character.
+///
+pub(crate) fn multi_character_tab(argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: multi-character tab '{}'", PROGRAM, argument))
+}
+
+///
+/// `-t` was given nothing.
+///
+pub(crate) fn empty_tab() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: empty tab", PROGRAM))
+}
+
+///
+/// `-S` was given something that isn't a size.
+///
+pub(crate) fn invalid_buffer_size(argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: invalid -S argument '{}'", PROGRAM, argument))
+}
+
+///
+/// `--parallel` was given something other than a positive number.
+///
+pub(crate) fn invalid_thread_count(argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: invalid number after '--parallel': '{}'", PROGRAM, argument))
+}
+
+///
+/// `--check` was given something other than `diagnose-first`, `quiet` or `silent`.
+///
+pub(crate) fn invalid_check_mode(argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: invalid argument '{}' for '--check'", PROGRAM, argument))
+}
+
+///
+/// Checking works on a single input.
+///
+pub(crate) fn extra_check_operand(operand: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: extra operand '{}' not allowed with -c", PROGRAM, operand))
+}
+
+///
+/// An input couldn't be opened or read.
+///
+pub(crate) fn cannot_read(name: &str, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot read: {}: {}", PROGRAM, name, describe_io_error(error)))
+}
+
+///
+/// The `-o` output couldn't be opened.
+///
+pub(crate) fn open_failed(name: &str, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: open failed: {}: {}", PROGRAM, name, describe_io_error(error)))
+}
+
+///
+/// Output couldn't be written.
+///
+pub(crate) fn cannot_write(name: &str, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: write failed: {}: {}", PROGRAM, name, describe_io_error(error)))
+}
+
+///
+/// A sorted run couldn't be spilled to, or read back from, the temp directory.
+///
+pub(crate) fn temp_file_error(directory: &str, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot create temporary file in '{}': {}", PROGRAM, directory, describe_io_error(error)))
+}
+
+///
+/// With `-c`, the first line that's out of order.
+///
+pub(crate) fn disorder(name: &str, line_number: u64, line: &[u8]) -> KnownError {
+    KnownError::new(DISORDER_CODE, format!("{}: {}:{}: disorder: {}", PROGRAM, name, line_number, String::from_utf8_lossy(line)))
+}
+
+///
+/// With `-C`, out of order input is only reported through the exit status.
+///
+pub(crate) fn silent_disorder() -> KnownError {
+    KnownError { code: DISORDER_CODE, message: None }
+}
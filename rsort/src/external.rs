@@ -0,0 +1,401 @@
+use std::cmp::Ordering;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::ops::Range;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::PathBuf;
+use std::sync::atomic::{self, AtomicUsize};
+use common::input::known_error::KnownError;
+use crate::compare::Comparator;
+use crate::errors::{cannot_read, temp_file_error};
+
+/* how many runs are merged at once; more are merged in several passes */
+const FAN_IN: usize = 16;
+/* below this many lines, sorting on one thread is quicker than handing out work */
+const PARALLEL_THRESHOLD: usize = 16 * 1024;
+const IO_BUFFER_SIZE: usize = 128 * 1024;
+const DEFAULT_TEMP_DIR: &str = "/tmp";
+const TEMP_DIR_VARIABLE: &str = "TMPDIR";
+/* temp files are only for this user; they may hold what was sorted */
+const TEMP_FILE_MODE: u32 = 0o600;
+
+static RUN_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+///
+/// Where a sort spends its memory and disk.
+///
+pub(crate) struct Resources {
+    pub(crate) buffer_size: usize,
+    pub(crate) threads: usize,
+    pub(crate) temp_dirs: Vec<String>,
+}
+
+///
+/// Lines read into memory, stored back to back without their delimiters.
+///
+struct Chunk {
+    data: Vec<u8>,
+    lines: Vec<Range<usize>>,
+}
+
+impl Chunk {
+    fn new() -> Chunk {
+        Chunk { data: vec![], lines: vec![] }
+    }
+
+    fn memory(&self) -> usize {
+        self.data.len() + self.lines.len() * size_of::<Range<usize>>()
+    }
+
+    fn line(&self, index: usize) -> &[u8] {
+        &self.data[self.lines[index].clone()]
+    }
+}
+
+///
+/// A sorted run spilled to disk; removed when dropped.
+///
+struct TempRun {
+    path: PathBuf,
+}
+
+impl Drop for TempRun {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+///
+/// Sorts any amount of input: lines are gathered into chunks that fit the buffer, each chunk is
+/// sorted on several threads, and when the input doesn't fit in one chunk the sorted chunks are
+/// spilled to temp files and merged.
+///
+pub(crate) struct Sorter<'a> {
+    comparator: &'a Comparator,
+    resources: Resources,
+    unique: bool,
+    delimiter: u8,
+    chunk: Chunk,
+    runs: Vec<TempRun>,
+}
+
+impl<'a> Sorter<'a> {
+    pub(crate) fn new(comparator: &'a Comparator, resources: Resources, unique: bool, delimiter: u8) -> Sorter<'a> {
+        Sorter { comparator, resources, unique, delimiter, chunk: Chunk::new(), runs: vec![] }
+    }
+
+    ///
+    /// Read every line of one input.  A last line without a delimiter still counts as a line.
+    ///
+    pub(crate) fn add(&mut self, input: impl Read, name: &str) -> Result<(), KnownError> {
+        let mut reader = BufReader::with_capacity(IO_BUFFER_SIZE, input);
+        loop {
+            let start = self.chunk.data.len();
+            match reader.read_until(self.delimiter, &mut self.chunk.data) {
+                Ok(0) => return Ok(()),
+                Ok(_) => {}
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(cannot_read(name, &error)),
+            }
+
+            if self.chunk.data.last() == Some(&self.delimiter) {
+                self.chunk.data.pop();
+            }
+            self.chunk.lines.push(start..self.chunk.data.len());
+
+            if self.chunk.memory() >= self.resources.buffer_size {
+                self.spill()?;
+            }
+        }
+    }
+
+    ///
+    /// Write everything read so far, in order.  Nothing is written until all input has been read, so
+    /// the output may safely replace one of the inputs.
+    ///
+    pub(crate) fn finish(mut self, out: &mut dyn Write) -> Result<io::Result<()>, KnownError> {
+        sort_lines(&mut self.chunk, self.comparator, self.resources.threads);
+
+        if self.runs.is_empty() {
+            let lines = (0..self.chunk.lines.len()).map(|index| self.chunk.line(index));
+            return Ok(write_lines(lines, self.comparator, self.unique, self.delimiter, out));
+        }
+
+        if !self.chunk.lines.is_empty() {
+            self.spill_sorted()?;
+        }
+        /* each pass merges neighbours, keeping runs in input order for stability */
+        while self.runs.len() > FAN_IN {
+            let mut remaining = std::mem::take(&mut self.runs);
+            while !remaining.is_empty() {
+                let group: Vec<TempRun> = remaining.drain(..FAN_IN.min(remaining.len())).collect();
+                let (merged, file) = self.create_run()?;
+                let directory = directory_name(&merged);
+                let mut writer = BufWriter::with_capacity(IO_BUFFER_SIZE, file);
+                merge(&group, self.comparator, self.unique, self.delimiter, &mut writer)?
+                    .and_then(|_| writer.flush())
+                    .map_err(|e| temp_file_error(&directory, &e))?;
+                self.runs.push(merged);
+            }
+        }
+
+        merge(&self.runs, self.comparator, self.unique, self.delimiter, out)
+    }
+
+    fn spill(&mut self) -> Result<(), KnownError> {
+        sort_lines(&mut self.chunk, self.comparator, self.resources.threads);
+        self.spill_sorted()
+    }
+
+    fn spill_sorted(&mut self) -> Result<(), KnownError> {
+        let (run, file) = self.create_run()?;
+        let directory = directory_name(&run);
+
+        let mut writer = BufWriter::with_capacity(IO_BUFFER_SIZE, file);
+        let lines = (0..self.chunk.lines.len()).map(|index| self.chunk.line(index));
+        write_lines(lines, self.comparator, self.unique, self.delimiter, &mut writer)
+            .and_then(|_| writer.flush())
+            .map_err(|e| temp_file_error(&directory, &e))?;
+
+        self.runs.push(run);
+        self.chunk = Chunk::new();
+        Ok(())
+    }
+
+    ///
+    /// Create the file for a new run, taking turns between the temp directories.  As with
+    /// `mkstemp`, the file must be a new one, so nothing already there is followed or truncated;
+    /// when the name is taken, the next one is tried.
+    ///
+    fn create_run(&self) -> Result<(TempRun, File), KnownError> {
+        loop {
+            let number = RUN_COUNTER.fetch_add(1, atomic::Ordering::SeqCst);
+            let directory = match self.resources.temp_dirs.as_slice() {
+                [] => std::env::var(TEMP_DIR_VARIABLE).ok().filter(|d| !d.is_empty()).unwrap_or(DEFAULT_TEMP_DIR.to_string()),
+                dirs => dirs[number % dirs.len()].clone(),
+            };
+            let path = PathBuf::from(&directory).join(format!("rsort-{}-{}", std::process::id(), number));
+            match OpenOptions::new().write(true).create_new(true).mode(TEMP_FILE_MODE).open(&path) {
+                Ok(file) => return Ok((TempRun { path }, file)),
+                Err(error) if error.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(error) => return Err(temp_file_error(&directory, &error)),
+            }
+        }
+    }
+}
+
+fn directory_name(run: &TempRun) -> String {
+    run.path.parent().map_or(String::new(), |parent| parent.display().to_string())
+}
+
+///
+/// Write lines that are already in order, leaving out all but the first of equal lines for `-u`.
+///
+fn write_lines<'l>(lines: impl Iterator<Item = &'l [u8]>, comparator: &Comparator, unique: bool, delimiter: u8, out: &mut dyn Write) -> io::Result<()> {
+    let mut previous: Option<&[u8]> = None;
+    for line in lines {
+        if unique && previous.is_some_and(|previous| comparator.compare(previous, line) == Ordering::Equal) {
+            continue;
+        }
+        out.write_all(line)?;
+        out.write_all(&[delimiter])?;
+        previous = Some(line);
+    }
+    Ok(())
+}
+
+///
+/// Merge sorted runs.  When lines compare equal, the earlier run goes first, which keeps the sort
+/// stable across runs.  The outer error is for reading the runs, the inner one for writing.
+///
+fn merge(runs: &[TempRun], comparator: &Comparator, unique: bool, delimiter: u8, out: &mut dyn Write) -> Result<io::Result<()>, KnownError> {
+    let mut readers = vec![];
+    let mut heads: Vec<Option<Vec<u8>>> = vec![];
+    for run in runs {
+        let directory = directory_name(run);
+        let file = File::open(&run.path).map_err(|e| temp_file_error(&directory, &e))?;
+        let mut reader = BufReader::with_capacity(IO_BUFFER_SIZE, file);
+        heads.push(read_line(&mut reader, delimiter).map_err(|e| temp_file_error(&directory, &e))?);
+        readers.push(reader);
+    }
+
+    let mut previous: Option<Vec<u8>> = None;
+    loop {
+        /* few enough runs that a scan beats keeping a heap in order */
+        let mut smallest: Option<usize> = None;
+        for (index, head) in heads.iter().enumerate() {
+            if let Some(line) = head
+                && smallest.is_none_or(|s| comparator.compare(line, heads[s].as_ref().unwrap()) == Ordering::Less) {
+                smallest = Some(index);
+            }
+        }
+        let Some(index) = smallest else {
+            return Ok(Ok(()));
+        };
+
+        let next = read_line(&mut readers[index], delimiter).map_err(|e| temp_file_error(&directory_name(&runs[index]), &e))?;
+        let line = std::mem::replace(&mut heads[index], next).unwrap();
+        if unique && previous.as_ref().is_some_and(|previous| comparator.compare(previous, &line) == Ordering::Equal) {
+            continue;
+        }
+
+        if let Err(error) = out.write_all(&line).and_then(|_| out.write_all(&[delimiter])) {
+            return Ok(Err(error));
+        }
+        previous = Some(line);
+    }
+}
+
+fn read_line(reader: &mut impl BufRead, delimiter: u8) -> io::Result<Option<Vec<u8>>> {
+    let mut line = vec![];
+    loop {
+        match reader.read_until(delimiter, &mut line) {
+            Ok(0) => return Ok(None),
+            Ok(_) => break,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        }
+    }
+    if line.last() == Some(&delimiter) {
+        line.pop();
+    }
+    Ok(Some(line))
+}
+
+///
+/// Stable sort of a chunk's lines; large chunks are split between threads, then merged back
+/// together.
+///
+fn sort_lines(chunk: &mut Chunk, comparator: &Comparator, threads: usize) {
+    let data = &chunk.data;
+    let compare = |a: &Range<usize>, b: &Range<usize>| comparator.compare(&data[a.clone()], &data[b.clone()]);
+
+    if threads <= 1 || chunk.lines.len() < PARALLEL_THRESHOLD {
+        chunk.lines.sort_by(compare);
+        return;
+    }
+
+    let piece_size = chunk.lines.len().div_ceil(threads);
+    std::thread::scope(|scope| {
+        for piece in chunk.lines.chunks_mut(piece_size) {
+            scope.spawn(move || piece.sort_by(compare));
+        }
+    });
+
+    let mut pieces: Vec<Vec<Range<usize>>> = chunk.lines.chunks(piece_size).map(|piece| piece.to_vec()).collect();
+    while pieces.len() > 1 {
+        pieces = std::thread::scope(|scope| {
+            let handles: Vec<_> = pieces.chunks(2)
+                .map(|pair| scope.spawn(move || match pair {
+                    [left, right] => merge_sorted(left, right, compare),
+                    [only] => only.clone(),
+                    _ => unreachable!("chunks of two are never empty"),
+                }))
+                .collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+    }
+    chunk.lines = pieces.pop().unwrap_or_default();
+}
+
+fn merge_sorted(left: &[Range<usize>], right: &[Range<usize>], compare: impl Fn(&Range<usize>, &Range<usize>) -> Ordering) -> Vec<Range<usize>> {
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() && j < right.len() {
+        /* ties go left, to stay stable */
+        if compare(&right[j], &left[i]) == Ordering::Less {
+            merged.push(right[j].clone());
+            j += 1;
+        } else {
+            merged.push(left[i].clone());
+            i += 1;
+        }
+    }
+    merged.extend_from_slice(&left[i..]);
+    merged.extend_from_slice(&right[j..]);
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::sync::atomic;
+    use common::test_support::TempDir;
+    use crate::compare::Comparator;
+    use crate::config::Config;
+    use crate::external::{Resources, Sorter, RUN_COUNTER};
+
+    fn sort(line: &str, input: &str, resources: Resources) -> String {
+        let args: Vec<String> = line.split_whitespace().map(|s| s.to_string()).collect();
+        let config = Config::new(&args);
+        let comparator = Comparator::new(&config);
+        let mut sorter = Sorter::new(&comparator, resources, config.unique, config.delimiter);
+        sorter.add(input.as_bytes(), "input").unwrap();
+
+        let mut out = vec![];
+        sorter.finish(&mut out).unwrap().unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn numbers(count: usize) -> String {
+        /* a scrambled but repeatable order */
+        (0..count).map(|n| format!("{}\n", (n * 7919) % count)).collect()
+    }
+
+    #[test]
+    fn test_in_memory() {
+        let resources = Resources { buffer_size: 1 << 20, threads: 1, temp_dirs: vec![] };
+        assert_eq!("a\nb\nc\n", sort("sort", "c\na\nb", resources));
+    }
+
+    #[test]
+    fn test_parallel_matches_serial() {
+        let input = numbers(50000);
+        let serial = sort("sort -n", &input, Resources { buffer_size: 1 << 30, threads: 1, temp_dirs: vec![] });
+        let parallel = sort("sort -n", &input, Resources { buffer_size: 1 << 30, threads: 5, temp_dirs: vec![] });
+
+        assert_eq!(serial, parallel);
+        assert_eq!((0..50000).map(|n| format!("{}\n", n)).collect::<String>(), parallel);
+    }
+
+    #[test]
+    fn test_spills_and_merges() {
+        let dir = TempDir::new("spill");
+        let input = numbers(5000);
+        /* tiny buffers make hundreds of runs, and several merge passes */
+        let resources = Resources { buffer_size: 256, threads: 2, temp_dirs: vec![dir.path().to_string()] };
+
+        assert_eq!((0..5000).map(|n| format!("{}\n", n)).collect::<String>(), sort("sort -n", &input, resources));
+        assert_eq!(0, fs::read_dir(dir.path()).unwrap().count());
+    }
+
+    #[test]
+    fn test_spilled_unique_and_stable() {
+        let dir = TempDir::new("spill-unique");
+        let input: String = (0..600).map(|n| format!("{} {}\n", n % 3, n)).collect();
+        let resources = || Resources { buffer_size: 128, threads: 1, temp_dirs: vec![dir.path().to_string()] };
+
+        assert_eq!("0 0\n1 1\n2 2\n", sort("sort -u -k1,1", &input, resources()));
+        let stable = sort("sort -s -k1,1", &input, resources());
+        let expected: String = (0..3).flat_map(|k| (0..600).filter(move |n| n % 3 == k)).map(|n| format!("{} {}\n", n % 3, n)).collect();
+        assert_eq!(expected, stable);
+    }
+
+    #[test]
+    fn test_names_taken() {
+        let dir = TempDir::new("taken");
+        let victim = dir.file("victim", b"keep");
+        /* names a run could be given next are links to another file, which mustn't be written through */
+        let next = RUN_COUNTER.load(atomic::Ordering::SeqCst);
+        for number in next..next + 8 {
+            std::os::unix::fs::symlink(&victim, dir.join(&format!("rsort-{}-{}", std::process::id(), number))).unwrap();
+        }
+        let comparator = Comparator::new(&Config::new(&["sort".to_string()]));
+        let sorter = Sorter::new(&comparator, Resources { buffer_size: 1, threads: 1, temp_dirs: vec![dir.path().to_string()] }, false, b'\n');
+        let (run, _) = sorter.create_run().unwrap();
+
+        assert_eq!(0o600, fs::symlink_metadata(&run.path).unwrap().permissions().mode() & 0o777);
+        assert_eq!("keep", fs::read_to_string(&victim).unwrap());
+    }
+}
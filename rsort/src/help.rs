@@ -0,0 +1,53 @@
+use std::io::{self, Write};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub(crate) fn version(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "rsort {}", VERSION)
+}
+
+pub(crate) fn help(out: &mut dyn Write) -> io::Result<()> {
+    version(out)?;
+    writeln!(out)?;
+    writeln!(out, "Usage: sort [OPTION]... [FILE]...")?;
+    writeln!(out, "Write sorted concatenation of all FILE(s) to standard output.")?;
+    writeln!(out)?;
+    writeln!(out, "With no FILE, or when FILE is -, read standard input.")?;
+    writeln!(out)?;
+    writeln!(out, "Ordering options:")?;
+    writeln!(out, "  -b, --ignore-leading-blanks  ignore leading blanks")?;
+    writeln!(out, "  -d, --dictionary-order      consider only blanks and alphanumeric characters")?;
+    writeln!(out, "  -f, --ignore-case           fold lower case to upper case characters")?;
+    writeln!(out, "  -g, --general-numeric-sort  compare according to general numerical value")?;
+    writeln!(out, "  -i, --ignore-nonprinting    consider only printable characters")?;
+    writeln!(out, "  -M, --month-sort            compare (unknown) < 'JAN' < ... < 'DEC'")?;
+    writeln!(out, "  -h, --human-numeric-sort    compare human readable numbers (e.g., 2K 1G)")?;
+    writeln!(out, "  -n, --numeric-sort          compare according to string numerical value")?;
+    writeln!(out, "  -r, --reverse               reverse the result of comparisons")?;
+    writeln!(out, "  -V, --version-sort          natural sort of (version) numbers within text")?;
+    writeln!(out)?;
+    writeln!(out, "Other options:")?;
+    writeln!(out, "  -c, --check, --check=diagnose-first  check for sorted input; do not sort")?;
+    writeln!(out, "  -C, --check=quiet, --check=silent  like -c, but do not report first bad line")?;
+    writeln!(out, "  -k, --key=KEYDEF            sort via a key; KEYDEF gives location and type")?;
+    writeln!(out, "  -o, --output=FILE           write result to FILE instead of standard output")?;
+    writeln!(out, "  -s, --stable                stabilize sort by disabling last-resort comparison")?;
+    writeln!(out, "  -S, --buffer-size=SIZE      use SIZE for main memory buffer")?;
+    writeln!(out, "  -t, --field-separator=SEP   use SEP instead of non-blank to blank transition")?;
+    writeln!(out, "  -T, --temporary-directory=DIR  use DIR for temporaries, not $TMPDIR or /tmp;")?;
+    writeln!(out, "                              multiple options specify multiple directories")?;
+    writeln!(out, "      --parallel=N            change the number of sorts run concurrently to N")?;
+    writeln!(out, "  -u, --unique                output only the first of an equal run")?;
+    writeln!(out, "  -z, --zero-terminated       line delimiter is NUL, not newline")?;
+    writeln!(out, "      --help                  display this help and exit")?;
+    writeln!(out, "      --version               output version information and exit")?;
+    writeln!(out)?;
+    writeln!(out, "KEYDEF is F[.C][OPTS][,F[.C][OPTS]] for start and stop position, where F is a")?;
+    writeln!(out, "field number and C a character position in the field; both are origin 1, and")?;
+    writeln!(out, "the stop position defaults to the line's end.  OPTS is one or more single-letter")?;
+    writeln!(out, "ordering options [bdfgiMhnrV], which override global ordering options for that key.")?;
+    writeln!(out, "If no key is given, use the entire line as the key.")?;
+    writeln!(out)?;
+    writeln!(out, "SIZE may be followed by the following multiplicative suffixes:")?;
+    writeln!(out, "% 1% of memory, b 1, K 1024 (default), and so on for M, G, T, P, E, Z, Y, R, Q.")
+}
@@ -0,0 +1,332 @@
+use common::input::known_error::KnownError;
+use crate::errors::{field_zero, incompatible_options, invalid_field_start, offset_zero, stray_character};
+
+/* key option letters, in the order they're listed in error messages */
+const OPTION_LETTERS: &str = "bdfghiMnrV";
+
+///
+/// How the text of a key is ordered.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KeyType {
+    Text,
+    Numeric,
+    GeneralNumeric,
+    HumanNumeric,
+    Month,
+    Version,
+}
+
+impl KeyType {
+    fn letter(&self) -> Option<char> {
+        match self {
+            KeyType::Text => None,
+            KeyType::Numeric => Some('n'),
+            KeyType::GeneralNumeric => Some('g'),
+            KeyType::HumanNumeric => Some('h'),
+            KeyType::Month => Some('M'),
+            KeyType::Version => Some('V'),
+        }
+    }
+}
+
+///
+/// Ordering options; given globally, or for a single key.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct KeyOptions {
+    pub(crate) key_type: KeyType,
+    pub(crate) skip_start_blanks: bool,
+    pub(crate) skip_end_blanks: bool,
+    pub(crate) dictionary_order: bool,
+    pub(crate) fold_case: bool,
+    pub(crate) ignore_nonprinting: bool,
+    pub(crate) reverse: bool,
+}
+
+impl Default for KeyOptions {
+    fn default() -> Self {
+        KeyOptions {
+            key_type: KeyType::Text,
+            skip_start_blanks: false,
+            skip_end_blanks: false,
+            dictionary_order: false,
+            fold_case: false,
+            ignore_nonprinting: false,
+            reverse: false,
+        }
+    }
+}
+
+impl KeyOptions {
+    ///
+    /// Apply one option letter.  `-b` applies to the end of a key only when given after the comma,
+    /// and to both ends when given globally.
+    ///
+    pub(crate) fn apply(&mut self, letter: char, start: bool, end: bool) -> Result<(), KnownError> {
+        match letter {
+            'b' => {
+                self.skip_start_blanks |= start;
+                self.skip_end_blanks |= end;
+            }
+            'd' => self.dictionary_order = true,
+            'f' => self.fold_case = true,
+            'i' => self.ignore_nonprinting = true,
+            'r' => self.reverse = true,
+            'n' => self.set_type(KeyType::Numeric)?,
+            'g' => self.set_type(KeyType::GeneralNumeric)?,
+            'h' => self.set_type(KeyType::HumanNumeric)?,
+            'M' => self.set_type(KeyType::Month)?,
+            'V' => self.set_type(KeyType::Version)?,
+            _ => unreachable!("key option {} has no handler", letter),
+        }
+        Ok(())
+    }
+
+    fn set_type(&mut self, key_type: KeyType) -> Result<(), KnownError> {
+        if self.key_type != KeyType::Text && self.key_type != key_type {
+            let mut letters: Vec<char> = [self.key_type, key_type].iter().filter_map(|t| t.letter()).collect();
+            letters.sort_by_key(|letter| OPTION_LETTERS.find(*letter));
+            return Err(incompatible_options(&letters.iter().collect::<String>()));
+        }
+        self.key_type = key_type;
+        Ok(())
+    }
+}
+
+///
+/// A position in a line: a one-based field, and a one-based character within it.  At the end of a
+/// key, character 0 means the end of the field.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Position {
+    pub(crate) field: usize,
+    pub(crate) character: usize,
+}
+
+///
+/// One `-k` key definition.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Key {
+    pub(crate) start: Position,
+    pub(crate) end: Option<Position>,
+    pub(crate) options: KeyOptions,
+}
+
+impl Key {
+    ///
+    /// The key used when none are given: the whole line.
+    ///
+    pub(crate) fn whole_line(options: KeyOptions) -> Key {
+        Key { start: Position { field: 1, character: 1 }, end: None, options }
+    }
+
+    ///
+    /// Parse `F[.C][OPTS][,F[.C][OPTS]]`.  A key without any option letters takes the global options.
+    ///
+    pub(crate) fn parse(spec: &str, global: &KeyOptions) -> Result<Key, KnownError> {
+        let (start_spec, end_spec) = match spec.split_once(',') {
+            Some((start, end)) => (start, Some(end)),
+            None => (spec, None),
+        };
+
+        let mut options = KeyOptions::default();
+        let (start, start_letters) = parse_position(start_spec, spec, true)?;
+        start_letters.chars().try_for_each(|letter| options.apply(letter, true, false))?;
+
+        let mut has_letters = !start_letters.is_empty();
+        let end = match end_spec {
+            Some(end_spec) => {
+                let (end, end_letters) = parse_position(end_spec, spec, false)?;
+                end_letters.chars().try_for_each(|letter| options.apply(letter, false, true))?;
+                has_letters |= !end_letters.is_empty();
+                Some(end)
+            }
+            None => None,
+        };
+
+        Ok(Key { start, end, options: if has_letters { options } else { *global } })
+    }
+
+    ///
+    /// Find the bytes of `line` that make up this key.
+    ///
+    pub(crate) fn extract<'a>(&self, line: &'a [u8], separator: Option<u8>) -> &'a [u8] {
+        let mut start = field_start(line, self.start.field - 1, separator);
+        if self.options.skip_start_blanks {
+            start = skip_blanks(line, start);
+        }
+        start = start.saturating_add(self.start.character - 1).min(line.len());
+
+        let end = match self.end {
+            None => line.len(),
+            Some(Position { field, character: 0 }) => field_end(line, field - 1, separator),
+            Some(Position { field, character }) => {
+                let mut end = field_start(line, field - 1, separator);
+                if self.options.skip_end_blanks {
+                    end = skip_blanks(line, end);
+                }
+                /* unlike the start, the end can't run past its field */
+                end.saturating_add(character).min(field_end(line, field - 1, separator))
+            }
+        };
+
+        if start < end { &line[start..end] } else { &[] }
+    }
+}
+
+///
+/// Read a field number, an optional `.` and character number, and trailing option letters.
+///
+fn parse_position<'s>(text: &'s str, spec: &str, start: bool) -> Result<(Position, &'s str), KnownError> {
+    let (field, rest) = split_number(text);
+    let field = match field {
+        None => return Err(invalid_field_start(spec)),
+        Some(0) => return Err(field_zero(spec)),
+        Some(field) => field,
+    };
+
+    let (character, letters) = match rest.strip_prefix('.') {
+        Some(after_dot) => match split_number(after_dot) {
+            (Some(0), _) if start => return Err(offset_zero(spec)),
+            (Some(character), letters) => (character, letters),
+            (None, _) => return Err(stray_character(spec)),
+        },
+        None => (if start { 1 } else { 0 }, rest),
+    };
+
+    if letters.chars().any(|letter| !OPTION_LETTERS.contains(letter)) {
+        return Err(stray_character(spec));
+    }
+
+    Ok((Position { field, character }, letters))
+}
+
+fn split_number(text: &str) -> (Option<usize>, &str) {
+    let digits_end = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+    let (digits, rest) = text.split_at(digits_end);
+    /* a number too large to be a real position still works as one */
+    let number = if digits.is_empty() { None } else { Some(digits.parse().unwrap_or(usize::MAX)) };
+    (number, rest)
+}
+
+pub(crate) fn is_blank(byte: u8) -> bool {
+    byte == b' ' || byte == b'\t'
+}
+
+fn skip_blanks(line: &[u8], mut position: usize) -> usize {
+    while position < line.len() && is_blank(line[position]) {
+        position += 1;
+    }
+    position
+}
+
+fn skip_non_blanks(line: &[u8], mut position: usize) -> usize {
+    while position < line.len() && !is_blank(line[position]) {
+        position += 1;
+    }
+    position
+}
+
+///
+/// Where the zero-based field `index` begins.  Without a separator, fields are runs of non-blanks
+/// along with the blanks in front of them.
+///
+fn field_start(line: &[u8], index: usize, separator: Option<u8>) -> usize {
+    let mut position = 0;
+    for _ in 0..index {
+        if position == line.len() {
+            break;
+        }
+        position = match separator {
+            Some(separator) => match line[position..].iter().position(|&b| b == separator) {
+                Some(offset) => position + offset + 1,
+                None => return line.len(),
+            },
+            None => skip_non_blanks(line, skip_blanks(line, position)),
+        };
+    }
+    position
+}
+
+fn field_end(line: &[u8], index: usize, separator: Option<u8>) -> usize {
+    let start = field_start(line, index, separator);
+    match separator {
+        Some(separator) => line[start..].iter().position(|&b| b == separator).map_or(line.len(), |offset| start + offset),
+        None => skip_non_blanks(line, skip_blanks(line, start)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::key::{Key, KeyOptions, KeyType, Position};
+
+    fn key(spec: &str) -> Key {
+        Key::parse(spec, &KeyOptions::default()).unwrap()
+    }
+
+    fn error(spec: &str) -> String {
+        Key::parse(spec, &KeyOptions::default()).unwrap_err().message.unwrap()
+    }
+
+    fn extract(spec: &str, line: &str, separator: Option<u8>) -> String {
+        String::from_utf8(key(spec).extract(line.as_bytes(), separator).to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Key { start: Position { field: 2, character: 1 }, end: None, options: KeyOptions::default() }, key("2"));
+        assert_eq!(Some(Position { field: 3, character: 0 }), key("2,3").end);
+        assert_eq!(Position { field: 2, character: 4 }, key("2.4,3.5").start);
+        assert_eq!(Some(Position { field: 3, character: 5 }), key("2.4,3.5").end);
+
+        let options = key("1.2nr,1b").options;
+        assert_eq!(KeyType::Numeric, options.key_type);
+        assert!(options.reverse && options.skip_end_blanks && !options.skip_start_blanks);
+    }
+
+    #[test]
+    fn test_global_options_inherited() {
+        let global = KeyOptions { reverse: true, key_type: KeyType::Numeric, ..KeyOptions::default() };
+
+        assert_eq!(global, Key::parse("2", &global).unwrap().options);
+        assert_eq!(KeyType::Text, Key::parse("2f", &global).unwrap().options.key_type);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!("sort: field number is zero: invalid field specification '0'", error("0"));
+        assert_eq!("sort: character offset is zero: invalid field specification '1.0'", error("1.0"));
+        assert_eq!("sort: invalid number at field start: invalid count at start of 'a'", error("a"));
+        assert_eq!("sort: stray character in field spec: invalid field specification '1x'", error("1x"));
+        assert_eq!("sort: stray character in field spec: invalid field specification '1,2.x'", error("1,2.x"));
+        assert_eq!("sort: options '-gn' are incompatible", error("1n,1g"));
+    }
+
+    #[test]
+    fn test_extract_blank_separated() {
+        let line = "  alpha   beta gamma";
+
+        assert_eq!("  alpha   beta gamma", extract("1", line, None));
+        assert_eq!("  alpha", extract("1,1", line, None));
+        assert_eq!("   beta", extract("2,2", line, None));
+        assert_eq!("beta", extract("2b,2", line, None));
+        assert_eq!("et", extract("2.2b,2.3b", line, None));
+        assert_eq!(" gamma", extract("3", line, None));
+        assert_eq!("", extract("4", line, None));
+        assert_eq!("", extract("2.20,2", line, None));
+    }
+
+    #[test]
+    fn test_extract_separated() {
+        let line = "a:b::d";
+
+        assert_eq!("b", extract("2,2", line, Some(b':')));
+        assert_eq!("", extract("3,3", line, Some(b':')));
+        assert_eq!("d", extract("4", line, Some(b':')));
+        assert_eq!("b::d", extract("2", line, Some(b':')));
+        assert_eq!("", extract("5", line, Some(b':')));
+        assert_eq!("b:", extract("2,3.1", line, Some(b':')));
+    }
+}
@@ -0,0 +1,20 @@
+mod check;
+mod compare;
+mod config;
+mod errors;
+mod external;
+mod help;
+mod key;
+mod ordering;
+mod sort;
+
+use crate::sort::sort;
+
+///
+/// Main program entrypoint.
+///
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let status = sort(&args, &mut std::io::stdout(), &mut std::io::stderr());
+    std::process::exit(status);
+}
@@ -0,0 +1,258 @@
+use std::cmp::Ordering;
+use rcal::time::name::MonthAbbreviations;
+use crate::key::is_blank;
+
+const HUMAN_SUFFIXES: &[u8] = b"KMGTPEZYRQ";
+
+///
+/// Compare numbers as `-n` does, digit by digit rather than by converting them, so any length
+/// works: leading blanks, an optional `-`, digits, an optional `.` and more digits.  Anything else
+/// ends the number, and text with no number counts as zero.
+///
+pub(crate) fn compare_numeric(a: &[u8], b: &[u8]) -> Ordering {
+    let a = Decimal::parse(a);
+    let b = Decimal::parse(b);
+
+    match (a.negative, b.negative) {
+        (false, true) => Ordering::Greater,
+        (true, false) => Ordering::Less,
+        (false, false) => a.compare_magnitude(&b),
+        (true, true) => b.compare_magnitude(&a),
+    }
+}
+
+///
+/// Compare numbers as `-g` does, as floating point.  Text that isn't a number comes first, then
+/// NaN, then numbers from negative infinity up.
+///
+pub(crate) fn compare_general_numeric(a: &[u8], b: &[u8]) -> Ordering {
+    let rank = |value: Option<f64>| match value {
+        None => 0,
+        Some(number) if number.is_nan() => 1,
+        Some(_) => 2,
+    };
+    let a = parse_float_prefix(a);
+    let b = parse_float_prefix(b);
+
+    match (rank(a), rank(b)) {
+        (2, 2) => a.unwrap().partial_cmp(&b.unwrap()).unwrap_or(Ordering::Equal),
+        (rank_a, rank_b) => rank_a.cmp(&rank_b),
+    }
+}
+
+///
+/// Compare numbers as `-h` does: by sign, then by SI suffix (`K` < `M` < `G` ...), then by value;
+/// `2K` sorts after `1000`.
+///
+pub(crate) fn compare_human_numeric(a: &[u8], b: &[u8]) -> Ordering {
+    let a_number = Decimal::parse(a);
+    let b_number = Decimal::parse(b);
+    let a_sign = a_number.sign();
+    let b_sign = b_number.sign();
+    if a_sign != b_sign {
+        return a_sign.cmp(&b_sign);
+    }
+
+    let by_magnitude = suffix_rank(a, a_number.length)
+        .cmp(&suffix_rank(b, b_number.length))
+        .then_with(|| a_number.compare_magnitude(&b_number));
+    if a_sign < 0 { by_magnitude.reverse() } else { by_magnitude }
+}
+
+///
+/// Compare month names as `-M` does; anything that isn't a month sorts before January.
+///
+pub(crate) fn compare_month(a: &[u8], b: &[u8], months: &MonthAbbreviations) -> Ordering {
+    months.find(a).unwrap_or(0).cmp(&months.find(b).unwrap_or(0))
+}
+
+///
+/// Compare version numbers as `-V` does: runs of digits compare as numbers, everything else
+/// character by character, with letters before other characters and `~` before everything, even
+/// the end of the text; so `1.0~rc1` < `1.0` < `1.0a` < `1.0.1`.
+///
+pub(crate) fn compare_version(a: &[u8], b: &[u8]) -> Ordering {
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        /* non-digit prefixes */
+        while (i < a.len() && !a[i].is_ascii_digit()) || (j < b.len() && !b[j].is_ascii_digit()) {
+            let weight = |text: &[u8], index: usize| version_weight(text.get(index).copied());
+            let ordering = weight(a, i).cmp(&weight(b, j));
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+            i += 1;
+            j += 1;
+        }
+
+        /* digit runs, without leading zeros */
+        while i < a.len() && a[i] == b'0' {
+            i += 1;
+        }
+        while j < b.len() && b[j] == b'0' {
+            j += 1;
+        }
+        let a_end = i + a[i..].iter().take_while(|c| c.is_ascii_digit()).count();
+        let b_end = j + b[j..].iter().take_while(|c| c.is_ascii_digit()).count();
+        let ordering = (a_end - i).cmp(&(b_end - j)).then_with(|| a[i..a_end].cmp(&b[j..b_end]));
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+        i = a_end;
+        j = b_end;
+    }
+
+    Ordering::Equal
+}
+
+fn version_weight(byte: Option<u8>) -> i32 {
+    match byte {
+        None => 0,
+        Some(digit) if digit.is_ascii_digit() => 0,
+        Some(b'~') => -1,
+        Some(letter) if letter.is_ascii_alphabetic() => letter as i32,
+        Some(other) => other as i32 + 256,
+    }
+}
+
+///
+/// A decimal number as written, for comparing without converting.
+///
+struct Decimal<'a> {
+    negative: bool,
+    integer: &'a [u8],
+    fraction: &'a [u8],
+    /* bytes from the start of the text to the end of the number */
+    length: usize,
+}
+
+impl<'a> Decimal<'a> {
+    fn parse(text: &'a [u8]) -> Decimal<'a> {
+        let mut position = text.iter().take_while(|&&b| is_blank(b)).count();
+        let negative = text.get(position) == Some(&b'-');
+        if negative {
+            position += 1;
+        }
+
+        let integer_start = position;
+        position += text[position..].iter().take_while(|b| b.is_ascii_digit()).count();
+        let mut integer = &text[integer_start..position];
+        while integer.first() == Some(&b'0') {
+            integer = &integer[1..];
+        }
+
+        let mut fraction: &[u8] = &[];
+        if text.get(position) == Some(&b'.') {
+            let fraction_start = position + 1;
+            position = fraction_start + text[fraction_start..].iter().take_while(|b| b.is_ascii_digit()).count();
+            fraction = &text[fraction_start..position];
+            while fraction.last() == Some(&b'0') {
+                fraction = &fraction[..fraction.len() - 1];
+            }
+        }
+
+        /* negative zero is zero */
+        let negative = negative && !(integer.is_empty() && fraction.is_empty());
+        Decimal { negative, integer, fraction, length: position }
+    }
+
+    fn sign(&self) -> i32 {
+        if self.negative {
+            -1
+        } else if self.integer.is_empty() && self.fraction.is_empty() {
+            0
+        } else {
+            1
+        }
+    }
+
+    fn compare_magnitude(&self, other: &Decimal) -> Ordering {
+        self.integer.len().cmp(&other.integer.len())
+            .then_with(|| self.integer.cmp(other.integer))
+            .then_with(|| self.fraction.cmp(other.fraction))
+    }
+}
+
+fn suffix_rank(text: &[u8], number_length: usize) -> usize {
+    match text.get(number_length) {
+        Some(b'k') => 1,
+        Some(suffix) => HUMAN_SUFFIXES.iter().position(|s| s == suffix).map_or(0, |index| index + 1),
+        None => 0,
+    }
+}
+
+///
+/// Read the longest prefix that's a floating point number, after leading blanks.
+///
+fn parse_float_prefix(text: &[u8]) -> Option<f64> {
+    let text = &text[text.iter().take_while(|&&b| is_blank(b)).count()..];
+    let text = String::from_utf8_lossy(&text[..text.len().min(512)]);
+
+    /* the longest prefix wins; numbers are short, so trying each end is cheap enough */
+    let ends: Vec<usize> = text.char_indices().map(|(index, c)| index + c.len_utf8()).collect();
+    ends.iter().rev().find_map(|&end| {
+        let candidate = &text[..end];
+        let plausible = candidate.bytes().all(|b| b.is_ascii_alphanumeric() || b"+-.".contains(&b));
+        if plausible { candidate.parse::<f64>().ok() } else { None }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+    use rcal::time::locale::ENGLISH;
+    use rcal::time::name::MonthAbbreviations;
+    use crate::ordering::{compare_general_numeric, compare_human_numeric, compare_month, compare_numeric, compare_version};
+
+    fn sorted(mut values: Vec<&str>, compare: impl Fn(&[u8], &[u8]) -> Ordering) -> Vec<&str> {
+        values.sort_by(|a, b| compare(a.as_bytes(), b.as_bytes()));
+        values
+    }
+
+    #[test]
+    fn test_numeric() {
+        assert_eq!(
+            vec!["-10", "-2.5", "-2", "abc", "0", "-0", "0.5", ".75", "2", "  10", "10.01", "99999999999999999999999"],
+            sorted(vec!["10.01", "2", "-2", "-10", "abc", "0.5", "  10", "-2.5", "0", ".75", "99999999999999999999999", "-0"], compare_numeric)
+        );
+        assert_eq!(Ordering::Equal, compare_numeric(b"1.50", b"01.5"));
+        assert_eq!(Ordering::Equal, compare_numeric(b"7abc", b"7xyz"));
+    }
+
+    #[test]
+    fn test_general_numeric() {
+        assert_eq!(
+            vec!["abc", "nan", "-inf", "-1e3", "0.5", "1e2", "1e10", "inf"],
+            sorted(vec!["1e10", "inf", "0.5", "-1e3", "nan", "abc", "-inf", "1e2"], compare_general_numeric)
+        );
+        assert_eq!(Ordering::Equal, compare_general_numeric(b"  2.0x", b"2"));
+    }
+
+    #[test]
+    fn test_human_numeric() {
+        assert_eq!(
+            vec!["-1G", "-5K", "-1", "0", "1", "1000", "2k", "1.5M", "1G", "1T"],
+            sorted(vec!["1G", "1000", "2k", "-5K", "1T", "1.5M", "0", "-1G", "1", "-1"], compare_human_numeric)
+        );
+    }
+
+    #[test]
+    fn test_month() {
+        let months = MonthAbbreviations::new(&ENGLISH);
+        let by_month = |a: &[u8], b: &[u8]| compare_month(a, b, &months);
+        assert_eq!(
+            vec!["xyz", "jan", " FEB", "March", "dec"],
+            sorted(vec!["dec", "March", "xyz", " FEB", "jan"], by_month)
+        );
+    }
+
+    #[test]
+    fn test_version() {
+        assert_eq!(
+            vec!["1.0~rc1", "1.0", "1.0a", "1.0.1", "1.2", "1.10", "2"],
+            sorted(vec!["1.10", "1.0.1", "2", "1.0a", "1.0", "1.2", "1.0~rc1"], compare_version)
+        );
+        assert_eq!(Ordering::Equal, compare_version(b"file007", b"file7"));
+        assert_eq!(Ordering::Less, compare_version(b"file2.txt", b"file10.txt"));
+    }
+}
@@ -0,0 +1,162 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use common::input::known_error::{report, KnownError};
+use common::input::source::InputSource;
+use crate::check::check;
+use crate::compare::Comparator;
+use crate::config::Config;
+use crate::errors::{cannot_read, cannot_write, open_failed};
+use crate::external::{Resources, Sorter};
+use crate::help::{help, version};
+
+const SUCCESS: i32 = 0;
+const FAILURE: i32 = 2;
+const STANDARD_OUTPUT: &str = "standard output";
+const STDIN_OPERAND: &str = "-";
+
+///
+/// Run `sort` with command line arguments; the first argument is the program name.  Returns the
+/// exit status.
+///
+pub(crate) fn sort(args: &[String], out: &mut dyn Write, err: &mut dyn Write) -> i32 {
+    let config = Config::new(args);
+    if let Some(first) = config.errors.first() {
+        report(err, &config.errors);
+        return first.code;
+    }
+
+    if config.print_help_and_exit {
+        return help(out).map_or(FAILURE, |_| SUCCESS);
+    }
+    if config.print_version_and_exit {
+        return version(out).map_or(FAILURE, |_| SUCCESS);
+    }
+
+    let comparator = Comparator::new(&config);
+    let result = match config.check {
+        Some(mode) => {
+            let source = &config.sources[0];
+            /* disorder messages name standard input the way it was given */
+            let name = match source {
+                InputSource::Stdin => STDIN_OPERAND,
+                InputSource::File(path) => path,
+            };
+            source.open()
+                .map_err(|error| cannot_read(name, &error))
+                .and_then(|input| check(input, name, &comparator, config.unique, config.delimiter, mode))
+        }
+        None => sort_sources(&config, &comparator, out),
+    };
+
+    match result {
+        Ok(()) => SUCCESS,
+        Err(error) => {
+            report(err, std::slice::from_ref(&error));
+            error.code
+        }
+    }
+}
+
+///
+/// Read every input, then write them out in order, to `-o` or `out`.
+///
+fn sort_sources(config: &Config, comparator: &Comparator, out: &mut dyn Write) -> Result<(), KnownError> {
+    let resources = Resources { buffer_size: config.buffer_size, threads: config.threads, temp_dirs: config.temp_dirs.clone() };
+    let mut sorter = Sorter::new(comparator, resources, config.unique, config.delimiter);
+    for source in config.sources.iter() {
+        let input = source.open().map_err(|error| cannot_read(source.name(), &error))?;
+        sorter.add(input, source.name())?;
+    }
+
+    /* only now that every input has been read can -o replace one of them */
+    match &config.output {
+        Some(path) => {
+            let file = File::create(path).map_err(|error| open_failed(path, &error))?;
+            let mut writer = BufWriter::new(file);
+            finish(sorter, &mut writer, path)
+        }
+        None => {
+            let mut writer = BufWriter::new(out);
+            finish(sorter, &mut writer, STANDARD_OUTPUT)
+        }
+    }
+}
+
+fn finish(sorter: Sorter, writer: &mut dyn Write, name: &str) -> Result<(), KnownError> {
+    sorter.finish(writer)?
+        .and_then(|_| writer.flush())
+        .map_err(|error: io::Error| cannot_write(name, &error))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use common::test_support::TempDir;
+    use crate::sort::sort;
+
+    fn run(args: &[&str]) -> (i32, String, String) {
+        let args: Vec<String> = std::iter::once("sort").chain(args.iter().copied()).map(|s| s.to_string()).collect();
+        let mut out = vec![];
+        let mut err = vec![];
+        let status = sort(&args, &mut out, &mut err);
+        (status, String::from_utf8(out).unwrap(), String::from_utf8(err).unwrap())
+    }
+
+    #[test]
+    fn test_merges_inputs() {
+        let dir = TempDir::new("inputs");
+        let first = dir.file("first", b"pear\napple\n");
+        let second = dir.file("second", b"fig\nbanana");
+
+        assert_eq!((0, "apple\nbanana\nfig\npear\n".to_string(), String::new()), run(&[&first, &second]));
+        assert_eq!("pear\nfig\nbanana\napple\n", run(&["-r", &first, &second]).1);
+    }
+
+    #[test]
+    fn test_fields_and_separators() {
+        let dir = TempDir::new("fields");
+        let file = dir.file("passwd", b"root:x:0:0\nuser:x:1000:1000\ndaemon:x:1:1\n");
+
+        assert_eq!("root:x:0:0\ndaemon:x:1:1\nuser:x:1000:1000\n", run(&["-t", ":", "-k", "3,3n", &file]).1);
+        assert_eq!("user:x:1000:1000\ndaemon:x:1:1\nroot:x:0:0\n", run(&["-t:", "-k3,3nr", &file]).1);
+    }
+
+    #[test]
+    fn test_unique_and_zero_terminated() {
+        let dir = TempDir::new("unique");
+        let file = dir.file("file", b"b\0a\0b\0");
+
+        assert_eq!("a\0b\0", run(&["-uz", &file]).1);
+        assert_eq!("01\n2\n", run(&["-nu", &dir.file("numbers", b"2\n01\n1\n02\n")]).1);
+    }
+
+    #[test]
+    fn test_output_replaces_input() {
+        let dir = TempDir::new("output");
+        let file = dir.file("file", b"3\n1\n2\n");
+
+        assert_eq!((0, String::new(), String::new()), run(&["-o", &file, &file]));
+        assert_eq!("1\n2\n3\n", fs::read_to_string(&file).unwrap());
+    }
+
+    #[test]
+    fn test_check() {
+        let dir = TempDir::new("check");
+        let sorted = dir.file("sorted", b"a\nb\n");
+        let unsorted = dir.file("unsorted", b"b\na\n");
+
+        assert_eq!((0, String::new(), String::new()), run(&["-c", &sorted]));
+        assert_eq!((1, String::new(), format!("sort: {}:2: disorder: a\n", unsorted)), run(&["-c", &unsorted]));
+        assert_eq!((1, String::new(), String::new()), run(&["-C", &unsorted]));
+    }
+
+    #[test]
+    fn test_errors() {
+        let dir = TempDir::new("errors");
+        let missing = dir.join("missing");
+        let missing = missing.to_str().unwrap();
+
+        assert_eq!((2, String::new(), format!("sort: cannot read: {}: No such file or directory\n", missing)), run(&[missing]));
+        assert_eq!((2, String::new(), "sort: options '-Mn' are incompatible\n".to_string()), run(&["-n", "-M"]));
+    }
+}
@@ -0,0 +1,293 @@
+use std::time::Duration;
+use common::input::flags::flags_options::{parse_options, OptionSpec, ParsedArgument};
+use common::input::known_error::KnownError;
+use common::input::size::parse_size;
+use common::input::source::InputSource;
+use crate::errors::{invalid_follow_mode, invalid_number, invalid_pid, invalid_sleep_interval, option_error};
+
+const DEFAULT_COUNT: u64 = 10;
+const DEFAULT_SLEEP_SECONDS: f64 = 1.0;
+const FROM_START: char = '+';
+const FROM_END: char = '-';
+const FOLLOW_NAME: &str = "name";
+const FOLLOW_DESCRIPTOR: &str = "descriptor";
+
+const OPTIONS: [OptionSpec; 14] = [
+    OptionSpec::required("bytes", Some('c'), Some("bytes")),
+    OptionSpec::required("lines", Some('n'), Some("lines")),
+    OptionSpec::optional("follow", Some('f'), Some("follow")),
+    OptionSpec::flag("follow-retry", Some('F'), None),
+    OptionSpec::flag("retry", None, Some("retry")),
+    OptionSpec::required("pid", None, Some("pid")),
+    OptionSpec::required("sleep", Some('s'), Some("sleep-interval")),
+    OptionSpec::flag("quiet", Some('q'), Some("quiet")),
+    OptionSpec::flag("silent", None, Some("silent")),
+    OptionSpec::flag("verbose", Some('v'), Some("verbose")),
+    OptionSpec::flag("zero", Some('z'), Some("zero-terminated")),
+    OptionSpec::flag("disable-inotify", None, Some("disable-inotify")),
+    OptionSpec::flag("help", None, Some("help")),
+    OptionSpec::flag("version", None, Some("version")),
+];
+
+///
+/// What a count is counting.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Unit {
+    Lines,
+    Bytes,
+}
+
+///
+/// Where output starts: the last N units, or from unit N (one-based) onward.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Start {
+    FromEnd(u64),
+    FromStart(u64),
+}
+
+///
+/// The part of each input to print.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Selection {
+    pub(crate) unit: Unit,
+    pub(crate) start: Start,
+    pub(crate) delimiter: u8,
+}
+
+///
+/// How to keep up with files after their end has been printed.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FollowMode {
+    /// Keep reading the file that was opened, even if it's renamed or deleted.
+    Descriptor,
+    /// Keep reading whatever file has the name; reopen it when it's replaced.
+    Name,
+}
+
+///
+/// When to print `==> name <==` before each file.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Headers {
+    Auto,
+    Always,
+    Never,
+}
+
+///
+/// Storage for the application configuration.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct Config {
+    pub(crate) print_help_and_exit: bool,
+    pub(crate) print_version_and_exit: bool,
+
+    pub(crate) selection: Selection,
+    pub(crate) headers: Headers,
+    pub(crate) follow: Option<FollowMode>,
+    pub(crate) retry: bool,
+    pub(crate) pid: Option<i32>,
+    pub(crate) sleep_interval: Duration,
+    pub(crate) disable_inotify: bool,
+
+    pub(crate) sources: Vec<InputSource>,
+    pub(crate) errors: Vec<KnownError>,
+}
+
+impl Default for Config {
+    ///
+    /// Generate [Default] configuration.
+    ///
+    fn default() -> Self {
+        Config {
+            print_help_and_exit: false,
+            print_version_and_exit: false,
+
+            selection: Selection { unit: Unit::Lines, start: Start::FromEnd(DEFAULT_COUNT), delimiter: b'\n' },
+            headers: Headers::Auto,
+            follow: None,
+            retry: false,
+            pid: None,
+            sleep_interval: Duration::from_secs_f64(DEFAULT_SLEEP_SECONDS),
+            disable_inotify: false,
+
+            sources: vec![],
+            errors: vec![],
+        }
+    }
+}
+
+impl Config {
+    ///
+    /// Initialize the application configuration based on provided application arguments; the first
+    /// argument is the program name.
+    ///
+    pub(crate) fn new(args: &[String]) -> Config {
+        let mut config = Self::default();
+
+        let parsed = match parse_options(args.get(1..).unwrap_or_default(), &OPTIONS) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                config.errors.push(option_error(&error));
+                return config;
+            }
+        };
+
+        let mut operands = vec![];
+        for argument in parsed {
+            match argument {
+                ParsedArgument::Operand(operand) => operands.push(operand),
+                ParsedArgument::Option { id, value } => {
+                    if let Err(error) = config.apply(id, value.unwrap_or_default()) {
+                        config.errors.push(error);
+                    }
+                }
+            }
+        }
+        config.sources = InputSource::from_operands(&operands);
+
+        config
+    }
+
+    fn apply(&mut self, id: &str, value: String) -> Result<(), KnownError> {
+        match id {
+            "bytes" => self.selection = self.parse_count(Unit::Bytes, &value)?,
+            "lines" => self.selection = self.parse_count(Unit::Lines, &value)?,
+            "follow" => self.follow = Some(parse_follow_mode(&value)?),
+            "follow-retry" => {
+                self.follow = Some(FollowMode::Name);
+                self.retry = true;
+            }
+            "retry" => self.retry = true,
+            "pid" => self.pid = Some(value.parse::<i32>().ok().filter(|&pid| pid > 0).ok_or_else(|| invalid_pid(&value))?),
+            "sleep" => self.sleep_interval = parse_sleep_interval(&value)?,
+            "quiet" | "silent" => self.headers = Headers::Never,
+            "verbose" => self.headers = Headers::Always,
+            "zero" => self.selection.delimiter = b'\0',
+            "disable-inotify" => self.disable_inotify = true,
+            "help" => self.print_help_and_exit = true,
+            "version" => self.print_version_and_exit = true,
+            _ => unreachable!("option {} has no handler", id),
+        }
+        Ok(())
+    }
+
+    ///
+    /// Read `N`, `-N` or `+N`, with an optional multiplier suffix.
+    ///
+    fn parse_count(&self, unit: Unit, value: &str) -> Result<Selection, KnownError> {
+        let start = if let Some(count) = value.strip_prefix(FROM_START) {
+            Start::FromStart(parse_size(count).ok_or_else(|| invalid_number(unit, value))?)
+        } else {
+            let count = value.strip_prefix(FROM_END).unwrap_or(value);
+            Start::FromEnd(parse_size(count).ok_or_else(|| invalid_number(unit, value))?)
+        };
+
+        Ok(Selection { unit, start, delimiter: self.selection.delimiter })
+    }
+
+    ///
+    /// Whether to print a header before each file's output.
+    ///
+    pub(crate) fn show_headers(&self) -> bool {
+        match self.headers {
+            Headers::Always => true,
+            Headers::Never => false,
+            Headers::Auto => self.sources.len() > 1,
+        }
+    }
+}
+
+fn parse_follow_mode(value: &str) -> Result<FollowMode, KnownError> {
+    match value {
+        "" | FOLLOW_DESCRIPTOR => Ok(FollowMode::Descriptor),
+        FOLLOW_NAME => Ok(FollowMode::Name),
+        _ => Err(invalid_follow_mode(value)),
+    }
+}
+
+fn parse_sleep_interval(value: &str) -> Result<Duration, KnownError> {
+    value.parse::<f64>()
+        .ok()
+        .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+        .map(Duration::from_secs_f64)
+        .ok_or_else(|| invalid_sleep_interval(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use common::input::source::InputSource;
+    use crate::config::{Config, FollowMode, Start, Unit};
+
+    fn config(line: &str) -> Config {
+        let args: Vec<String> = line.split_whitespace().map(|s| s.to_string()).collect();
+        Config::new(&args)
+    }
+
+    fn messages(config: &Config) -> Vec<String> {
+        config.errors.iter().map(|e| e.message.clone().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_defaults() {
+        let config = config("tail");
+
+        assert!(config.errors.is_empty());
+        assert_eq!(Unit::Lines, config.selection.unit);
+        assert_eq!(Start::FromEnd(10), config.selection.start);
+        assert_eq!(vec![InputSource::Stdin], config.sources);
+        assert_eq!(None, config.follow);
+        assert_eq!(Duration::from_secs(1), config.sleep_interval);
+        assert!(!config.show_headers());
+    }
+
+    #[test]
+    fn test_counts() {
+        assert_eq!(Start::FromEnd(5), config("tail -n 5").selection.start);
+        assert_eq!(Start::FromEnd(5), config("tail -n-5").selection.start);
+        assert_eq!(Start::FromStart(5), config("tail --lines=+5").selection.start);
+        assert_eq!(Start::FromEnd(2048), config("tail -c 2K").selection.start);
+        assert_eq!(Unit::Bytes, config("tail -c 2K").selection.unit);
+        assert_eq!(Start::FromStart(0), config("tail -c +0").selection.start);
+        assert_eq!(b'\0', config("tail -z -n 3").selection.delimiter);
+        assert_eq!(b'\0', config("tail -n 3 -z").selection.delimiter);
+    }
+
+    #[test]
+    fn test_follow() {
+        assert_eq!(Some(FollowMode::Descriptor), config("tail -f a").follow);
+        assert_eq!(Some(FollowMode::Name), config("tail --follow=name a").follow);
+        assert!(!config("tail --follow=name a").retry);
+
+        let retry = config("tail -F -s 0.25 --pid 42 a");
+        assert_eq!(Some(FollowMode::Name), retry.follow);
+        assert!(retry.retry);
+        assert_eq!(Duration::from_millis(250), retry.sleep_interval);
+        assert_eq!(Some(42), retry.pid);
+    }
+
+    #[test]
+    fn test_headers_and_sources() {
+        let many = config("tail a - b");
+        assert_eq!(vec![InputSource::File("a".to_string()), InputSource::Stdin, InputSource::File("b".to_string())], many.sources);
+        assert!(many.show_headers());
+        assert!(!config("tail -q a b").show_headers());
+        assert!(config("tail -v a").show_headers());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(vec!["tail: invalid number of lines: 'x'"], messages(&config("tail -n x")));
+        assert_eq!(vec!["tail: invalid number of bytes: '+1Q'"], messages(&config("tail -c +1Q")));
+        assert_eq!(vec!["tail: invalid argument 'inode' for '--follow'"], messages(&config("tail --follow=inode")));
+        assert_eq!(vec!["tail: invalid PID: '0'"], messages(&config("tail --pid 0")));
+        assert_eq!(vec!["tail: invalid number of seconds: '-1'"], messages(&config("tail -s -1")));
+        assert_eq!(vec!["tail: invalid option -- 'x'"], messages(&config("tail -x")));
+        assert_eq!(vec!["tail: option requires an argument -- 'n'"], messages(&config("tail -n")));
+    }
+}
@@ -0,0 +1,75 @@
+use std::io;
+use common::input::flags::flags_options::OptionError;
+use common::input::known_error::KnownError;
+use common::input::source::describe_io_error;
+use crate::config::Unit;
+
+const ERROR_CODE: i32 = 1;
+const PROGRAM: &str = "tail";
+
+///
+/// The command line couldn't be split into options and operands.
+///
+pub(crate) fn option_error(error: &OptionError) -> KnownError {
+    KnownError::new(ERROR_CODE, error.message(PROGRAM))
+}
+
+///
+/// A line or byte count that isn't a number.
+///
+pub(crate) fn invalid_number(unit: Unit, argument: &str) -> KnownError {
+    let noun = match unit {
+        Unit::Lines => "lines",
+        Unit::Bytes => "bytes",
+    };
+    KnownError::new(ERROR_CODE, format!("{}: invalid number of {}: '{}'", PROGRAM, noun, argument))
+}
+
+///
+/// `--follow` was given something other than `name` or `descriptor`.
+///
+pub(crate) fn invalid_follow_mode(argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: invalid argument '{}' for '--follow'", PROGRAM, argument))
+}
+
+///
+/// `--pid` was given something other than a positive number.
+///
+pub(crate) fn invalid_pid(argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: invalid PID: '{}'", PROGRAM, argument))
+}
+
+///
+/// `-s` was given something other than a non-negative number of seconds.
+///
+pub(crate) fn invalid_sleep_interval(argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: invalid number of seconds: '{}'", PROGRAM, argument))
+}
+
+///
+/// An input couldn't be opened.
+///
+pub(crate) fn cannot_open(name: &str, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: cannot open '{}' for reading: {}", PROGRAM, name, describe_io_error(error)))
+}
+
+///
+/// An input was opened, but reading it failed.
+///
+pub(crate) fn read_error(name: &str, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: error reading '{}': {}", PROGRAM, name, describe_io_error(error)))
+}
+
+///
+/// Standard output couldn't be written.
+///
+pub(crate) fn write_error(error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: write error: {}", PROGRAM, describe_io_error(error)))
+}
+
+///
+/// Following by name, and nothing is left to follow.
+///
+pub(crate) fn no_files_remaining() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: no files remaining", PROGRAM))
+}
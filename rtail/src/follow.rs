@@ -0,0 +1,391 @@
+use std::fs::{self, File, Metadata};
+use std::io::{self, Write};
+use std::os::unix::fs::MetadataExt;
+use std::time::Duration;
+use common::input::known_error::report;
+use common::input::source::describe_io_error;
+use crate::config::FollowMode;
+use crate::reader::copy_from;
+use crate::tail::{input_error, write_header};
+use crate::watcher::Watcher;
+
+///
+/// A file being followed, and how far it has been printed.
+///
+struct FollowedFile {
+    name: String,
+    file: Option<File>,
+    offset: u64,
+    identity: Option<(u64, u64)>,
+    missing_reported: bool,
+    dropped: bool,
+}
+
+///
+/// Keeps printing what's appended to files after their end has been reached.
+///
+pub(crate) struct Follower {
+    files: Vec<FollowedFile>,
+    mode: FollowMode,
+    retry: bool,
+    show_headers: bool,
+    last_printed: Option<usize>,
+    watcher: Box<dyn Watcher>,
+}
+
+impl Follower {
+    pub(crate) fn new(mode: FollowMode, retry: bool, show_headers: bool, watcher: Box<dyn Watcher>) -> Follower {
+        Follower { files: vec![], mode, retry, show_headers, last_printed: None, watcher }
+    }
+
+    ///
+    /// Follow a file that has been printed up to `offset`.  Without a file, it couldn't be opened; it's
+    /// only followed if it may appear later.
+    ///
+    pub(crate) fn add(&mut self, name: &str, file: Option<File>, offset: u64) {
+        let identity = file.as_ref().and_then(|f| f.metadata().ok()).map(|m| identity(&m));
+        if file.is_some() {
+            self.last_printed = Some(self.files.len());
+        }
+
+        self.watcher.watch(name);
+        self.files.push(FollowedFile {
+            name: name.to_string(),
+            dropped: file.is_none() && !self.retry,
+            missing_reported: file.is_none(),
+            file,
+            offset,
+            identity,
+        });
+    }
+
+    ///
+    /// Files were added, but every one of them has since been given up on.
+    ///
+    pub(crate) fn is_exhausted(&self) -> bool {
+        !self.files.is_empty() && self.files.iter().all(|f| f.dropped)
+    }
+
+    ///
+    /// Follow until the process `pid` exits, checking at least every `interval`; forever without a
+    /// pid.  Returns false when there was nothing (left) to follow.
+    ///
+    pub(crate) fn run(&mut self, pid: Option<i32>, interval: Duration, out: &mut dyn Write, err: &mut dyn Write) -> io::Result<bool> {
+        if self.files.is_empty() {
+            return Ok(true);
+        }
+
+        loop {
+            /* check the process first, so that whatever it wrote before exiting is still printed */
+            let alive = pid.is_none_or(process_alive);
+            self.poll(out, err)?;
+            out.flush()?;
+
+            if self.is_exhausted() {
+                return Ok(false);
+            }
+            if !alive {
+                return Ok(true);
+            }
+            self.watcher.wait(interval);
+        }
+    }
+
+    ///
+    /// Check every file once, printing anything new.
+    ///
+    pub(crate) fn poll(&mut self, out: &mut dyn Write, err: &mut dyn Write) -> io::Result<()> {
+        for index in 0..self.files.len() {
+            self.poll_file(index, out, err)?;
+        }
+        Ok(())
+    }
+
+    fn poll_file(&mut self, index: usize, out: &mut dyn Write, err: &mut dyn Write) -> io::Result<()> {
+        if self.files[index].dropped {
+            return Ok(());
+        }
+        /* finish what was written to the old file before looking for a new one */
+        self.read_new(index, out, err)?;
+        if self.mode == FollowMode::Name || self.files[index].file.is_none() {
+            self.check_name(index, err)?;
+            self.read_new(index, out, err)?;
+        }
+
+        Ok(())
+    }
+
+    fn read_new(&mut self, index: usize, out: &mut dyn Write, err: &mut dyn Write) -> io::Result<()> {
+        let followed = &mut self.files[index];
+        let Some(file) = followed.file.as_mut() else {
+            return Ok(());
+        };
+        let Ok(metadata) = file.metadata() else {
+            return Ok(());
+        };
+        if !metadata.is_file() {
+            return Ok(());
+        }
+
+        if metadata.len() < followed.offset {
+            writeln!(err, "tail: {}: file truncated", followed.name)?;
+            followed.offset = 0;
+        }
+        if metadata.len() > followed.offset {
+            if self.show_headers && self.last_printed != Some(index) {
+                write_header(out, &followed.name, self.last_printed.is_none())?;
+            }
+            self.last_printed = Some(index);
+            match copy_from(file, followed.offset, out) {
+                Ok(offset) => followed.offset = offset,
+                /* give up on the file as if it had gone; following by name may pick it up again */
+                Err(error) => {
+                    report(err, &[input_error(&followed.name, error)?]);
+                    followed.file = None;
+                    followed.identity = None;
+                    followed.dropped = !self.retry;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Make sure the file being read is the one that has the name now: reopen it if it was
+    /// replaced, let go of it if it's gone, and pick it up if it (re)appeared.
+    ///
+    fn check_name(&mut self, index: usize, err: &mut dyn Write) -> io::Result<()> {
+        let retry = self.retry;
+        let followed = &mut self.files[index];
+
+        let metadata = match fs::metadata(&followed.name) {
+            Ok(metadata) => metadata,
+            Err(error) => {
+                if !followed.missing_reported {
+                    if retry {
+                        writeln!(err, "tail: '{}' has become inaccessible: {}", followed.name, describe_io_error(&error))?;
+                    } else {
+                        writeln!(err, "tail: {}: {}", followed.name, describe_io_error(&error))?;
+                    }
+                    followed.missing_reported = true;
+                }
+                followed.file = None;
+                followed.identity = None;
+                followed.dropped = !retry;
+                return Ok(());
+            }
+        };
+        if followed.identity == Some(identity(&metadata)) {
+            return Ok(());
+        }
+
+        match File::open(&followed.name) {
+            Ok(file) => {
+                let change = if followed.file.is_some() { "been replaced" } else { "appeared" };
+                writeln!(err, "tail: '{}' has {};  following new file", followed.name, change)?;
+                followed.identity = file.metadata().ok().map(|m| identity(&m));
+                followed.file = Some(file);
+                followed.offset = 0;
+                followed.missing_reported = false;
+                self.watcher.watch(&self.files[index].name);
+            }
+            Err(error) => {
+                if !followed.missing_reported {
+                    writeln!(err, "tail: cannot open '{}' for reading: {}", followed.name, describe_io_error(&error))?;
+                    followed.missing_reported = true;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn identity(metadata: &Metadata) -> (u64, u64) {
+    (metadata.dev(), metadata.ino())
+}
+
+///
+/// Whether the process exists; one owned by another user still counts.
+///
+fn process_alive(pid: i32) -> bool {
+    /* SAFETY: signal 0 only checks that the process exists, and delivers nothing */
+    let result = unsafe { libc::kill(pid, 0) };
+    result == 0 || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::time::Duration;
+    use common::test_support::TempFile;
+    use crate::config::FollowMode;
+    use crate::follow::{process_alive, Follower};
+    use crate::watcher::PollingWatcher;
+
+    fn follower(mode: FollowMode, retry: bool, show_headers: bool) -> Follower {
+        Follower::new(mode, retry, show_headers, Box::new(PollingWatcher))
+    }
+
+    fn add(follower: &mut Follower, temp: &TempFile) {
+        match File::open(temp.path()) {
+            Ok(file) => {
+                let offset = file.metadata().unwrap().len();
+                follower.add(temp.path(), Some(file), offset)
+            }
+            Err(_) => follower.add(temp.path(), None, 0),
+        }
+    }
+
+    fn poll(follower: &mut Follower) -> (String, String) {
+        let mut out = vec![];
+        let mut err = vec![];
+        follower.poll(&mut out, &mut err).unwrap();
+        (String::from_utf8(out).unwrap(), String::from_utf8(err).unwrap())
+    }
+
+    #[test]
+    fn test_appended() {
+        let temp = TempFile::new("appended");
+        temp.append("old\n");
+        let mut follower = follower(FollowMode::Descriptor, false, false);
+        add(&mut follower, &temp);
+
+        assert_eq!((String::new(), String::new()), poll(&mut follower));
+        temp.append("new\n");
+        assert_eq!(("new\n".to_string(), String::new()), poll(&mut follower));
+        temp.append("partial");
+        temp.append(" line\n");
+        assert_eq!(("partial line\n".to_string(), String::new()), poll(&mut follower));
+    }
+
+    #[test]
+    fn test_truncated() {
+        let temp = TempFile::new("truncated");
+        temp.append("a long first line\n");
+        let mut follower = follower(FollowMode::Descriptor, false, false);
+        add(&mut follower, &temp);
+
+        temp.truncate("short\n");
+        let (out, err) = poll(&mut follower);
+        assert_eq!("short\n", out);
+        assert_eq!(format!("tail: {}: file truncated\n", temp.path()), err);
+    }
+
+    #[test]
+    fn test_descriptor_keeps_reading_replaced_file() {
+        let temp = TempFile::new("descriptor");
+        temp.append("old\n");
+        let mut follower = follower(FollowMode::Descriptor, false, false);
+        add(&mut follower, &temp);
+
+        temp.replace("rotated\n");
+        assert_eq!((String::new(), String::new()), poll(&mut follower));
+    }
+
+    #[test]
+    fn test_name_reopens_replaced_file() {
+        let temp = TempFile::new("rotated");
+        temp.append("old\n");
+        let mut follower = follower(FollowMode::Name, true, false);
+        add(&mut follower, &temp);
+
+        temp.replace("rotated\n");
+        let (out, err) = poll(&mut follower);
+        assert_eq!("rotated\n", out);
+        assert_eq!(format!("tail: '{}' has been replaced;  following new file\n", temp.path()), err);
+
+        temp.append("more\n");
+        assert_eq!(("more\n".to_string(), String::new()), poll(&mut follower));
+    }
+
+    #[test]
+    fn test_name_finishes_old_file_before_reopening() {
+        let temp = TempFile::new("finish");
+        temp.append("old\n");
+        let mut follower = follower(FollowMode::Name, true, false);
+        add(&mut follower, &temp);
+
+        temp.append("last words\n");
+        temp.replace("rotated\n");
+        assert_eq!("last words\nrotated\n", poll(&mut follower).0);
+    }
+
+    #[test]
+    fn test_retry_waits_for_file() {
+        let temp = TempFile::new("retry");
+        let mut follower = follower(FollowMode::Name, true, false);
+        add(&mut follower, &temp);
+
+        assert_eq!((String::new(), String::new()), poll(&mut follower));
+        assert!(!follower.is_exhausted());
+
+        temp.append("hello\n");
+        let (out, err) = poll(&mut follower);
+        assert_eq!("hello\n", out);
+        assert_eq!(format!("tail: '{}' has appeared;  following new file\n", temp.path()), err);
+
+        temp.remove();
+        let (out, err) = poll(&mut follower);
+        assert_eq!("", out);
+        assert_eq!(format!("tail: '{}' has become inaccessible: No such file or directory\n", temp.path()), err);
+        assert_eq!((String::new(), String::new()), poll(&mut follower));
+
+        temp.append("back\n");
+        assert_eq!("back\n", poll(&mut follower).0);
+    }
+
+    #[test]
+    fn test_name_without_retry_gives_up() {
+        let temp = TempFile::new("gone");
+        temp.append("old\n");
+        let mut follower = follower(FollowMode::Name, false, false);
+        add(&mut follower, &temp);
+
+        temp.remove();
+        let (_, err) = poll(&mut follower);
+        assert_eq!(format!("tail: {}: No such file or directory\n", temp.path()), err);
+        assert!(follower.is_exhausted());
+    }
+
+    #[test]
+    fn test_headers_switch_between_files() {
+        let first = TempFile::new("first");
+        let second = TempFile::new("second");
+        first.append("1\n");
+        second.append("2\n");
+        let mut follower = follower(FollowMode::Descriptor, false, true);
+        add(&mut follower, &first);
+        add(&mut follower, &second);
+
+        second.append("more 2\n");
+        assert_eq!("more 2\n", poll(&mut follower).0);
+
+        first.append("more 1\n");
+        second.append("again 2\n");
+        assert_eq!(
+            format!("\n==> {} <==\nmore 1\n\n==> {} <==\nagain 2\n", first.path(), second.path()),
+            poll(&mut follower).0
+        );
+    }
+
+    #[test]
+    fn test_run_stops_with_process() {
+        let temp = TempFile::new("pid");
+        temp.append("old\n");
+        let mut follower = follower(FollowMode::Descriptor, false, false);
+        add(&mut follower, &temp);
+        temp.append("written before exit\n");
+
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let pid = child.id() as i32;
+        child.wait().unwrap();
+        assert!(!process_alive(pid));
+
+        let mut out = vec![];
+        assert!(follower.run(Some(pid), Duration::from_millis(10), &mut out, &mut vec![]).unwrap());
+        assert_eq!("written before exit\n", String::from_utf8(out).unwrap());
+        assert!(process_alive(std::process::id() as i32));
+    }
+}
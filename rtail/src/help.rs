@@ -0,0 +1,32 @@
+use std::io::{self, Write};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub(crate) fn version(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "rtail {}", VERSION)
+}
+
+pub(crate) fn help(out: &mut dyn Write) -> io::Result<()> {
+    version(out)?;
+    writeln!(out)?;
+    writeln!(out, "Usage: tail [OPTION]... [FILE]...")?;
+    writeln!(out, "Print the last 10 lines of each FILE to standard output.")?;
+    writeln!(out, "With more than one FILE, precede each with a header giving the file name.")?;
+    writeln!(out)?;
+    writeln!(out, "With no FILE, or when FILE is -, read standard input.")?;
+    writeln!(out)?;
+    writeln!(out, "  -c, --bytes=[+]NUM         output the last NUM bytes; or use -c +NUM to output starting with byte NUM")?;
+    writeln!(out, "  -f, --follow[={{name|descriptor}}]")?;
+    writeln!(out, "                             output appended data as the file grows")?;
+    writeln!(out, "  -F                         same as --follow=name --retry")?;
+    writeln!(out, "  -n, --lines=[+]NUM         output the last NUM lines, instead of the last 10; or use -n +NUM to skip NUM-1 lines")?;
+    writeln!(out, "      --pid=PID              with -f, terminate after process ID, PID dies")?;
+    writeln!(out, "  -q, --quiet, --silent      never output headers giving file names")?;
+    writeln!(out, "      --retry                keep trying to open a file if it is inaccessible")?;
+    writeln!(out, "  -s, --sleep-interval=N     with -f, check for changes at least every N seconds (default 1.0)")?;
+    writeln!(out, "  -v, --verbose              always output headers giving file names")?;
+    writeln!(out, "  -z, --zero-terminated      line delimiter is NUL, not newline")?;
+    writeln!(out, "      --disable-inotify      with -f, poll for changes instead of using inotify")?;
+    writeln!(out, "      --help                 display this help and exit")?;
+    writeln!(out, "      --version              output version information and exit")
+}
@@ -0,0 +1,18 @@
+mod config;
+mod errors;
+mod follow;
+mod help;
+mod reader;
+mod tail;
+mod watcher;
+
+use crate::tail::tail;
+
+///
+/// Main program entrypoint.
+///
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let status = tail(&args, &mut std::io::stdout(), &mut std::io::stderr());
+    std::process::exit(status);
+}
@@ -0,0 +1,240 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use crate::config::{Selection, Start, Unit};
+
+const BLOCK_SIZE: usize = 8192;
+
+///
+/// Why printing part of an input stopped: the input couldn't be read, or output couldn't be written.
+///
+#[derive(Debug)]
+pub(crate) enum CopyError {
+    Read(io::Error),
+    Write(io::Error),
+}
+
+///
+/// Print the selected part of a stream that can only be read front to back, such as a pipe.
+///
+/// Counting from the end keeps only what may still be printed in memory: the last N lines, or
+/// roughly the last N bytes.
+///
+pub(crate) fn tail_stream(reader: impl Read, selection: &Selection, out: &mut dyn Write) -> Result<(), CopyError> {
+    let mut reader = BufReader::new(reader);
+    match (selection.unit, selection.start) {
+        (unit, Start::FromStart(first)) => {
+            skip_leading(&mut reader, unit, first, selection.delimiter).map_err(CopyError::Read)?;
+            copy(&mut reader, out)?;
+        }
+        (Unit::Lines, Start::FromEnd(count)) => {
+            let mut lines: VecDeque<Vec<u8>> = VecDeque::new();
+            loop {
+                let mut line = vec![];
+                if reader.read_until(selection.delimiter, &mut line).map_err(CopyError::Read)? == 0 {
+                    break;
+                }
+                if count > 0 {
+                    if lines.len() as u64 == count {
+                        lines.pop_front();
+                    }
+                    lines.push_back(line);
+                }
+            }
+            lines.iter().try_for_each(|line| out.write_all(line)).map_err(CopyError::Write)?;
+        }
+        (Unit::Bytes, Start::FromEnd(count)) => {
+            let keep = usize::try_from(count).unwrap_or(usize::MAX);
+            let mut kept: Vec<u8> = vec![];
+            let mut block = [0u8; BLOCK_SIZE];
+            loop {
+                let read = reader.read(&mut block).map_err(CopyError::Read)?;
+                if read == 0 {
+                    break;
+                }
+                kept.extend_from_slice(&block[..read]);
+
+                /* trim in bulk, rather than on every block */
+                if kept.len() > keep.saturating_add(BLOCK_SIZE) {
+                    kept.drain(..kept.len() - keep);
+                }
+            }
+            out.write_all(&kept[kept.len().saturating_sub(keep)..]).map_err(CopyError::Write)?;
+        }
+    }
+
+    Ok(())
+}
+
+///
+/// Print the selected part of a file; regular files are read from the end backwards instead of
+/// front to back.
+///
+/// Returns the offset the file was read up to, where following picks up.
+///
+pub(crate) fn tail_file(file: &mut File, selection: &Selection, out: &mut dyn Write) -> Result<u64, CopyError> {
+    let metadata = file.metadata().map_err(CopyError::Read)?;
+    if !metadata.is_file() {
+        tail_stream(&mut *file, selection, out)?;
+        return Ok(0);
+    }
+
+    let length = metadata.len();
+    let offset = match (selection.unit, selection.start) {
+        (Unit::Bytes, Start::FromStart(first)) => first.saturating_sub(1).min(length),
+        (Unit::Bytes, Start::FromEnd(count)) => length.saturating_sub(count),
+        (Unit::Lines, Start::FromEnd(count)) => find_last_lines(file, length, count, selection.delimiter).map_err(CopyError::Read)?,
+        (Unit::Lines, Start::FromStart(_)) => {
+            file.seek(SeekFrom::Start(0)).map_err(CopyError::Read)?;
+            tail_stream(&mut *file, selection, out)?;
+            return file.stream_position().map_err(CopyError::Read);
+        }
+    };
+
+    copy_from(file, offset, out)
+}
+
+///
+/// Print whatever has been added to a file since `offset`; returns the new offset.
+///
+pub(crate) fn copy_from(file: &mut File, offset: u64, out: &mut dyn Write) -> Result<u64, CopyError> {
+    file.seek(SeekFrom::Start(offset)).map_err(CopyError::Read)?;
+    copy(file, out)?;
+    file.stream_position().map_err(CopyError::Read)
+}
+
+///
+/// Copy everything left in `reader` to `out`.
+///
+fn copy(reader: &mut impl Read, out: &mut dyn Write) -> Result<(), CopyError> {
+    let mut block = [0u8; BLOCK_SIZE];
+    loop {
+        let read = match reader.read(&mut block) {
+            Ok(0) => return Ok(()),
+            Ok(read) => read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(CopyError::Read(error)),
+        };
+        out.write_all(&block[..read]).map_err(CopyError::Write)?;
+    }
+}
+
+///
+/// Skip everything before the one-based unit `first`; `+0` and `+1` both start at the beginning.
+///
+fn skip_leading(reader: &mut impl BufRead, unit: Unit, first: u64, delimiter: u8) -> io::Result<()> {
+    let skip = first.saturating_sub(1);
+    match unit {
+        Unit::Bytes => {
+            io::copy(&mut reader.take(skip), &mut io::sink())?;
+        }
+        Unit::Lines => {
+            let mut scratch = vec![];
+            for _ in 0..skip {
+                scratch.clear();
+                if reader.read_until(delimiter, &mut scratch)? == 0 {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+///
+/// Find the offset of the first of the last `count` lines, reading blocks backwards from the end.
+/// The delimiter ending the final line doesn't start another line; a final line without one still counts.
+///
+fn find_last_lines(file: &mut File, length: u64, count: u64, delimiter: u8) -> io::Result<u64> {
+    if count == 0 {
+        return Ok(length);
+    }
+
+    let mut block = [0u8; BLOCK_SIZE];
+    let mut block_start = length;
+    let mut seen = 0;
+    while block_start > 0 {
+        let size = BLOCK_SIZE.min(block_start as usize);
+        block_start -= size as u64;
+        file.seek(SeekFrom::Start(block_start))?;
+        file.read_exact(&mut block[..size])?;
+
+        for index in (0..size).rev() {
+            let offset = block_start + index as u64;
+            if block[index] == delimiter && offset + 1 != length {
+                seen += 1;
+                if seen == count {
+                    return Ok(offset + 1);
+                }
+            }
+        }
+    }
+
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::Cursor;
+    use common::test_support::TempFile;
+    use crate::config::{Selection, Start, Unit};
+    use crate::reader::{tail_file, tail_stream};
+
+    const TEXT: &str = "one\ntwo\nthree\nfour\nfive\n";
+
+    fn selection(unit: Unit, start: Start) -> Selection {
+        Selection { unit, start, delimiter: b'\n' }
+    }
+
+    fn from_stream(text: &str, selection: Selection) -> String {
+        let mut out = vec![];
+        tail_stream(Cursor::new(text.as_bytes()), &selection, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn from_file(text: &str, selection: Selection) -> String {
+        let temp = TempFile::new("reader");
+        temp.append(text);
+        let mut out = vec![];
+        let offset = tail_file(&mut File::open(temp.path()).unwrap(), &selection, &mut out).unwrap();
+        assert_eq!(text.len() as u64, offset);
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_selections() {
+        let cases = [
+            (TEXT, selection(Unit::Lines, Start::FromEnd(2)), "four\nfive\n"),
+            (TEXT, selection(Unit::Lines, Start::FromEnd(0)), ""),
+            (TEXT, selection(Unit::Lines, Start::FromEnd(10)), TEXT),
+            (TEXT, selection(Unit::Lines, Start::FromStart(4)), "four\nfive\n"),
+            (TEXT, selection(Unit::Lines, Start::FromStart(0)), TEXT),
+            (TEXT, selection(Unit::Lines, Start::FromStart(9)), ""),
+            (TEXT, selection(Unit::Bytes, Start::FromEnd(5)), "five\n"),
+            (TEXT, selection(Unit::Bytes, Start::FromEnd(500)), TEXT),
+            (TEXT, selection(Unit::Bytes, Start::FromStart(20)), "five\n"),
+            (TEXT, selection(Unit::Bytes, Start::FromStart(1)), TEXT),
+            ("a\nb\nc", selection(Unit::Lines, Start::FromEnd(2)), "b\nc"),
+            ("a\nb\n\n", selection(Unit::Lines, Start::FromEnd(1)), "\n"),
+            ("no newline", selection(Unit::Lines, Start::FromEnd(1)), "no newline"),
+            ("", selection(Unit::Lines, Start::FromEnd(3)), ""),
+            ("a\0b\0c\0", Selection { unit: Unit::Lines, start: Start::FromEnd(2), delimiter: b'\0' }, "b\0c\0"),
+        ];
+
+        for (text, selection, expected) in cases {
+            assert_eq!(expected, from_stream(text, selection), "stream: {:?} {:?}", text, selection);
+            assert_eq!(expected, from_file(text, selection), "file: {:?} {:?}", text, selection);
+        }
+    }
+
+    #[test]
+    fn test_lines_across_blocks() {
+        let text: String = (0..5000).map(|n| format!("line {}\n", n)).collect();
+        let expected: String = (4997..5000).map(|n| format!("line {}\n", n)).collect();
+
+        assert_eq!(expected, from_file(&text, selection(Unit::Lines, Start::FromEnd(3))));
+        assert_eq!(expected, from_stream(&text, selection(Unit::Lines, Start::FromEnd(3))));
+        assert_eq!(text[text.len() - 10000..], from_stream(&text, selection(Unit::Bytes, Start::FromEnd(10000))));
+    }
+}
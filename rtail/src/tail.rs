@@ -0,0 +1,241 @@
+use std::fs::File;
+use std::io::{self, Write};
+use common::input::known_error::{report, KnownError};
+use common::input::source::InputSource;
+use crate::config::Config;
+use crate::errors::{cannot_open, no_files_remaining, read_error, write_error};
+use crate::follow::Follower;
+use crate::help::{help, version};
+use crate::reader::{tail_file, tail_stream, CopyError};
+use crate::watcher::create_watcher;
+
+const SUCCESS: i32 = 0;
+const FAILURE: i32 = 1;
+
+///
+/// Run `tail` with command line arguments; the first argument is the program name.  Returns the
+/// exit status.
+///
+pub(crate) fn tail(args: &[String], out: &mut dyn Write, err: &mut dyn Write) -> i32 {
+    let config = Config::new(args);
+    if let Some(first) = config.errors.first() {
+        report(err, &config.errors);
+        return first.code;
+    }
+
+    let result = if config.print_help_and_exit {
+        help(out).map(|_| vec![])
+    } else if config.print_version_and_exit {
+        version(out).map(|_| vec![])
+    } else {
+        tail_sources(&config, out, err)
+    };
+
+    match result {
+        Ok(errors) if errors.is_empty() => SUCCESS,
+        Ok(errors) => errors[0].code,
+        /* output went away, e.g. a closed pipe; nothing more can be written */
+        Err(error) => {
+            report(err, &[write_error(&error)]);
+            FAILURE
+        }
+    }
+}
+
+///
+/// Print the end of every source, then follow them if asked to.  Problems with individual sources
+/// are reported as they happen and returned; an error means output couldn't be written.
+///
+fn tail_sources(config: &Config, out: &mut dyn Write, err: &mut dyn Write) -> io::Result<Vec<KnownError>> {
+    let mut errors = vec![];
+    let mut follower = config.follow.map(|mode| Follower::new(mode, config.retry, config.show_headers(), create_watcher(config.disable_inotify)));
+    let mut first_header = true;
+
+    for source in config.sources.iter() {
+        let name = source.name();
+        let result = match source {
+            /* standard input is printed, but never followed */
+            InputSource::Stdin => {
+                header(config, out, name, &mut first_header)?;
+                match tail_stream(io::stdin().lock(), &config.selection, out) {
+                    Ok(()) => Ok(()),
+                    Err(error) => Err(input_error(name, error)?),
+                }
+            }
+            InputSource::File(path) => match File::open(path) {
+                Ok(mut file) => {
+                    header(config, out, name, &mut first_header)?;
+                    match tail_file(&mut file, &config.selection, out) {
+                        Ok(offset) => {
+                            if let Some(follower) = follower.as_mut() {
+                                follower.add(path, Some(file), offset);
+                            }
+                            Ok(())
+                        }
+                        Err(error) => Err(input_error(name, error)?),
+                    }
+                }
+                Err(error) => {
+                    if let Some(follower) = follower.as_mut() {
+                        follower.add(path, None, 0);
+                    }
+                    Err(cannot_open(name, &error))
+                }
+            },
+        };
+
+        if let Err(error) = result {
+            report(err, std::slice::from_ref(&error));
+            errors.push(error);
+        }
+    }
+    out.flush()?;
+
+    if let Some(follower) = follower.as_mut()
+        && !follower.run(config.pid, config.sleep_interval, out, err)? {
+        let error = no_files_remaining();
+        report(err, std::slice::from_ref(&error));
+        errors.push(error);
+    }
+
+    Ok(errors)
+}
+
+///
+/// Write `==> name <==` before a file's output, separated from any earlier output by a blank line.
+///
+pub(crate) fn write_header(out: &mut dyn Write, name: &str, first: bool) -> io::Result<()> {
+    if !first {
+        writeln!(out)?;
+    }
+    writeln!(out, "==> {} <==", name)
+}
+
+///
+/// A failed read is the input's problem, to be reported for it; a failed write ends everything.
+///
+pub(crate) fn input_error(name: &str, error: CopyError) -> io::Result<KnownError> {
+    match error {
+        CopyError::Read(error) => Ok(read_error(name, &error)),
+        CopyError::Write(error) => Err(error),
+    }
+}
+
+fn header(config: &Config, out: &mut dyn Write, name: &str, first_header: &mut bool) -> io::Result<()> {
+    if config.show_headers() {
+        write_header(out, name, *first_header)?;
+        *first_header = false;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use common::test_support::TempFile;
+    use crate::tail::tail;
+
+    fn run(args: &[&str]) -> (i32, String, String) {
+        let args: Vec<String> = std::iter::once("tail").chain(args.iter().copied()).map(|s| s.to_string()).collect();
+        let mut out = vec![];
+        let mut err = vec![];
+        let status = tail(&args, &mut out, &mut err);
+        (status, String::from_utf8(out).unwrap(), String::from_utf8(err).unwrap())
+    }
+
+    fn numbered(count: usize) -> String {
+        (1..=count).map(|n| format!("{}\n", n)).collect()
+    }
+
+    #[test]
+    fn test_default_ten_lines() {
+        let temp = TempFile::new("default");
+        temp.append(&numbered(15));
+
+        assert_eq!((0, (6..=15).map(|n| format!("{}\n", n)).collect(), String::new()), run(&[temp.path()]));
+    }
+
+    #[test]
+    fn test_counts() {
+        let temp = TempFile::new("counts");
+        temp.append(&numbered(15));
+
+        assert_eq!("14\n15\n", run(&["-n", "2", temp.path()]).1);
+        assert_eq!("13\n14\n15\n", run(&["--lines=+13", temp.path()]).1);
+        assert_eq!("5\n", run(&["-c", "2", temp.path()]).1);
+        assert_eq!("15\n", run(&["-c", "+34", temp.path()]).1);
+    }
+
+    #[test]
+    fn test_headers() {
+        let first = TempFile::new("header-a");
+        let second = TempFile::new("header-b");
+        first.append("a\n");
+        second.append("b\n");
+
+        let (status, out, _) = run(&[first.path(), second.path()]);
+        assert_eq!(0, status);
+        assert_eq!(format!("==> {} <==\na\n\n==> {} <==\nb\n", first.path(), second.path()), out);
+        assert_eq!("a\nb\n", run(&["-q", first.path(), second.path()]).1);
+        assert_eq!(format!("==> {} <==\na\n", first.path()), run(&["-v", first.path()]).1);
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let present = TempFile::new("present");
+        let missing = TempFile::new("missing");
+        present.append("here\n");
+
+        let (status, out, err) = run(&[missing.path(), present.path()]);
+        assert_eq!(1, status);
+        assert_eq!(format!("==> {} <==\nhere\n", present.path()), out);
+        assert_eq!(format!("tail: cannot open '{}' for reading: No such file or directory\n", missing.path()), err);
+    }
+
+    #[test]
+    fn test_write_error() {
+        let first = TempFile::new("write-a");
+        let second = TempFile::new("write-b");
+        first.append("a\n");
+        second.append("b\n");
+
+        /* told of once, not blamed on the input, whether the header or the contents couldn't be written */
+        for args in [vec![first.path()], vec![first.path(), second.path()]] {
+            let args: Vec<String> = std::iter::once("tail").chain(args).map(|s| s.to_string()).collect();
+            let mut full = File::options().write(true).open("/dev/full").unwrap();
+            let mut err = vec![];
+            assert_eq!(1, tail(&args, &mut full, &mut err));
+            assert_eq!("tail: write error: No space left on device\n", String::from_utf8(err).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_follow_missing_without_retry() {
+        let missing = TempFile::new("follow-missing");
+
+        let (status, _, err) = run(&["-f", missing.path()]);
+        assert_eq!(1, status);
+        assert_eq!(format!("tail: cannot open '{}' for reading: No such file or directory\ntail: no files remaining\n", missing.path()), err);
+    }
+
+    #[test]
+    fn test_follow_until_pid_exits() {
+        let temp = TempFile::new("follow-pid");
+        temp.append(&numbered(3));
+
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let pid = child.id().to_string();
+        child.wait().unwrap();
+
+        for follow in ["-f", "-F"] {
+            let (status, out, err) = run(&[follow, "-s", "0.01", "--pid", &pid, "-n", "1", temp.path()]);
+            assert_eq!((0, "3\n".to_string(), String::new()), (status, out, err), "{}", follow);
+        }
+    }
+
+    #[test]
+    fn test_bad_arguments() {
+        assert_eq!((1, String::new(), "tail: invalid number of lines: 'ten'\n".to_string()), run(&["-n", "ten"]));
+        assert_eq!((1, String::new(), "tail: unrecognized option '--bogus'\n".to_string()), run(&["--bogus"]));
+    }
+}
@@ -0,0 +1,158 @@
+use std::time::Duration;
+
+///
+/// Waits for followed files to change.
+///
+pub(crate) trait Watcher {
+    ///
+    /// Start watching the file at `path`, and the directory it's in, so that replacement is noticed.
+    /// Called again whenever a file is reopened.
+    ///
+    fn watch(&mut self, path: &str);
+
+    ///
+    /// Block until something being watched changes, or `timeout` passes.
+    ///
+    fn wait(&mut self, timeout: Duration);
+}
+
+///
+/// Checks files every `timeout`, whether or not they changed.
+///
+pub(crate) struct PollingWatcher;
+
+impl Watcher for PollingWatcher {
+    fn watch(&mut self, _path: &str) {}
+
+    fn wait(&mut self, timeout: Duration) {
+        std::thread::sleep(timeout);
+    }
+}
+
+///
+/// Pick a watcher: inotify where it's available, polling otherwise.
+///
+pub(crate) fn create_watcher(disable_inotify: bool) -> Box<dyn Watcher> {
+    if !disable_inotify {
+        #[cfg(target_os = "linux")]
+        if let Some(watcher) = inotify::InotifyWatcher::new() {
+            return Box::new(watcher);
+        }
+    }
+
+    Box::new(PollingWatcher)
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) mod inotify {
+    use std::ffi::CString;
+    use std::path::Path;
+    use std::time::Duration;
+    use crate::watcher::Watcher;
+
+    const FILE_EVENTS: u32 = libc::IN_MODIFY | libc::IN_ATTRIB | libc::IN_DELETE_SELF | libc::IN_MOVE_SELF;
+    const DIRECTORY_EVENTS: u32 = libc::IN_CREATE | libc::IN_MOVED_TO | libc::IN_DELETE | libc::IN_MOVED_FROM;
+    const EVENT_BUFFER_SIZE: usize = 4096;
+
+    ///
+    /// Waits on an inotify descriptor; when a watch can't be added (too many watches, or a file
+    /// system without inotify support) it degrades to polling.
+    ///
+    pub(crate) struct InotifyWatcher {
+        descriptor: libc::c_int,
+        polling: bool,
+    }
+
+    impl InotifyWatcher {
+        pub(crate) fn new() -> Option<InotifyWatcher> {
+            /* SAFETY: no pointers are involved; a negative result is an error */
+            let descriptor = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+            if descriptor < 0 {
+                None
+            } else {
+                Some(InotifyWatcher { descriptor, polling: false })
+            }
+        }
+
+        fn add_watch(&mut self, path: &Path, mask: u32) {
+            let Ok(c_path) = CString::new(path.as_os_str().as_encoded_bytes()) else {
+                self.polling = true;
+                return;
+            };
+
+            /* SAFETY: c_path is a valid nul-terminated string for the duration of the call */
+            if unsafe { libc::inotify_add_watch(self.descriptor, c_path.as_ptr(), mask) } < 0 {
+                self.polling = true;
+            }
+        }
+
+        ///
+        /// Read and discard every pending event; which file changed doesn't matter, since every file
+        /// is checked after waking up.
+        ///
+        fn drain(&mut self) {
+            let mut buffer = [0u8; EVENT_BUFFER_SIZE];
+            /* SAFETY: the buffer is writable for its full length */
+            while unsafe { libc::read(self.descriptor, buffer.as_mut_ptr().cast(), buffer.len()) } > 0 {}
+        }
+    }
+
+    impl Watcher for InotifyWatcher {
+        fn watch(&mut self, path: &str) {
+            let path = Path::new(path);
+
+            /* the file may not exist yet, when retrying; the directory will report its creation */
+            if path.exists() {
+                self.add_watch(path, FILE_EVENTS);
+            }
+            let directory = match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent,
+                _ => Path::new("."),
+            };
+            self.add_watch(directory, DIRECTORY_EVENTS);
+        }
+
+        fn wait(&mut self, timeout: Duration) {
+            if self.polling {
+                std::thread::sleep(timeout);
+                return;
+            }
+
+            let mut poll = libc::pollfd { fd: self.descriptor, events: libc::POLLIN, revents: 0 };
+            let millis = libc::c_int::try_from(timeout.as_millis()).unwrap_or(libc::c_int::MAX);
+            /* SAFETY: poll reads and writes exactly one pollfd */
+            if unsafe { libc::poll(&mut poll, 1, millis) } > 0 {
+                self.drain();
+            }
+        }
+    }
+
+    impl Drop for InotifyWatcher {
+        fn drop(&mut self) {
+            /* SAFETY: the descriptor is owned by this watcher and closed once */
+            unsafe { libc::close(self.descriptor) };
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::time::{Duration, Instant};
+        use common::test_support::TempFile;
+        use crate::watcher::inotify::InotifyWatcher;
+        use crate::watcher::Watcher;
+
+        #[test]
+        fn test_wakes_on_change() {
+            let temp = TempFile::new("inotify");
+            temp.append("first\n");
+
+            let mut watcher = InotifyWatcher::new().expect("inotify is available on linux");
+            watcher.watch(temp.path());
+            temp.append("second\n");
+
+            let started = Instant::now();
+            watcher.wait(Duration::from_secs(30));
+            assert!(started.elapsed() < Duration::from_secs(10));
+        }
+    }
+}
@@ -3,6 +3,7 @@ mod timestamp;
 mod help;
 
 use std::fs::{File, FileTimes};
+use common::fs::times::Times;
 use crate::config::Config;
 use crate::help::{help, version};
 use crate::timestamp::timestamp;
@@ -44,17 +45,17 @@ fn find_file(config: &Config, path: &str) -> Option<File> {
 /// Find the accessed/modified times for the file.
 ///
 fn find_times(config: &Config) -> FileTimes {
-    let times = FileTimes::new();
     let ts = timestamp(config);
+    let accessed = Some(ts.accessed);
+    let modified = Some(ts.modified);
 
-    if config.change_only_access_time {
-        times.set_accessed(ts.accessed);
+    let times = if config.change_only_access_time {
+        Times { accessed, modified: None }
     } else if config.change_only_modification_time {
-        times.set_modified(ts.modified);
+        Times { accessed: None, modified }
     } else {
-        times.set_accessed(ts.accessed);
-        times.set_modified(ts.modified);
-    }
+        Times { accessed, modified }
+    };
 
-    times
+    times.file_times()
 }
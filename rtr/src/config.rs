@@ -0,0 +1,180 @@
+use common::input::flags::flags_options::{parse_options, OptionSpec, ParsedArgument};
+use common::input::known_error::KnownError;
+use crate::errors::{extra_operand, missing_operand, missing_second_operand, option_error};
+use crate::set::Set;
+
+const OPTIONS: [OptionSpec; 7] = [
+    OptionSpec::flag("complement", Some('c'), Some("complement")),
+    OptionSpec::flag("complement", Some('C'), None),
+    OptionSpec::flag("delete", Some('d'), Some("delete")),
+    OptionSpec::flag("squeeze", Some('s'), Some("squeeze-repeats")),
+    OptionSpec::flag("truncate", Some('t'), Some("truncate-set1")),
+    OptionSpec::flag("help", None, Some("help")),
+    OptionSpec::flag("version", None, Some("version")),
+];
+
+///
+/// Storage for the application configuration.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct Config {
+    pub(crate) print_help_and_exit: bool,
+    pub(crate) print_version_and_exit: bool,
+
+    pub(crate) complement: bool,
+    pub(crate) delete: bool,
+    pub(crate) squeeze: bool,
+    pub(crate) truncate: bool,
+
+    pub(crate) set1: Option<Set>,
+    pub(crate) set2: Option<Set>,
+    pub(crate) errors: Vec<KnownError>,
+}
+
+impl Default for Config {
+    ///
+    /// Generate [Default] configuration.
+    ///
+    fn default() -> Self {
+        Config {
+            print_help_and_exit: false,
+            print_version_and_exit: false,
+
+            complement: false,
+            delete: false,
+            squeeze: false,
+            truncate: false,
+
+            set1: None,
+            set2: None,
+            errors: vec![],
+        }
+    }
+}
+
+impl Config {
+    ///
+    /// Initialize the application configuration based on provided application arguments; the first
+    /// argument is the program name.
+    ///
+    pub(crate) fn new(args: &[String]) -> Config {
+        let mut config = Self::default();
+
+        let parsed = match parse_options(args.get(1..).unwrap_or_default(), &OPTIONS) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                config.errors.push(option_error(&error));
+                return config;
+            }
+        };
+
+        let mut operands = vec![];
+        for argument in parsed {
+            match argument {
+                ParsedArgument::Operand(operand) => operands.push(operand),
+                ParsedArgument::Option { id, .. } => config.apply(id),
+            }
+        }
+        if config.print_help_and_exit || config.print_version_and_exit {
+            return config;
+        }
+
+        if let Err(error) = config.check_operand_count(&operands) {
+            config.errors.push(error);
+            return config;
+        }
+        for (index, operand) in operands.iter().enumerate() {
+            match Set::parse(operand) {
+                Ok(set) if index == 0 => config.set1 = Some(set),
+                Ok(set) => config.set2 = Some(set),
+                Err(error) => config.errors.push(error),
+            }
+        }
+
+        config
+    }
+
+    fn apply(&mut self, id: &str) {
+        match id {
+            "complement" => self.complement = true,
+            "delete" => self.delete = true,
+            "squeeze" => self.squeeze = true,
+            "truncate" => self.truncate = true,
+            "help" => self.print_help_and_exit = true,
+            "version" => self.print_version_and_exit = true,
+            _ => unreachable!("option {} has no handler", id),
+        }
+    }
+
+    ///
+    /// Whether bytes of the first set are replaced by bytes of the second.
+    ///
+    pub(crate) fn translating(&self) -> bool {
+        !self.delete && self.set2.is_some()
+    }
+
+    ///
+    /// Deleting takes one set, or two when squeezing too; otherwise two sets are needed, except
+    /// that squeezing alone takes one.
+    ///
+    fn check_operand_count(&self, operands: &[String]) -> Result<(), KnownError> {
+        let minimum = if self.delete == self.squeeze { 2 } else { 1 };
+        let maximum = if !self.delete || self.squeeze { 2 } else { 1 };
+
+        if operands.is_empty() {
+            return Err(missing_operand());
+        }
+        if operands.len() < minimum {
+            let why = if self.squeeze { "both deleting and squeezing repeats" } else { "translating" };
+            return Err(missing_second_operand(&operands[operands.len() - 1], why));
+        }
+        if operands.len() > maximum {
+            let why = (self.delete && !self.squeeze).then_some("Only one string may be given when deleting without squeezing repeats.");
+            return Err(extra_operand(&operands[maximum], why));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+
+    fn config(line: &str) -> Config {
+        let args: Vec<String> = line.split_whitespace().map(|s| s.to_string()).collect();
+        Config::new(&args)
+    }
+
+    fn messages(config: &Config) -> Vec<String> {
+        config.errors.iter().map(|e| e.message.clone().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_operands() {
+        let translate = config("tr abc xyz");
+        assert!(translate.errors.is_empty());
+        assert!(translate.translating());
+
+        let delete = config("tr -d abc");
+        assert!(delete.errors.is_empty() && delete.delete && !delete.translating());
+
+        let squeeze = config("tr -s abc");
+        assert!(squeeze.errors.is_empty() && squeeze.set2.is_none());
+
+        let both = config("tr -ds abc x");
+        assert!(both.errors.is_empty() && !both.translating());
+
+        let complement = config("tr -C -t abc x");
+        assert!(complement.complement && complement.truncate);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(vec!["tr: missing operand"], messages(&config("tr")));
+        assert_eq!(vec!["tr: missing operand after 'abc'\nTwo strings must be given when translating."], messages(&config("tr abc")));
+        assert_eq!(vec!["tr: missing operand after 'abc'\nTwo strings must be given when both deleting and squeezing repeats."], messages(&config("tr -ds abc")));
+        assert_eq!(vec!["tr: extra operand 'x'\nOnly one string may be given when deleting without squeezing repeats."], messages(&config("tr -d abc x")));
+        assert_eq!(vec!["tr: extra operand 'z'"], messages(&config("tr a b z")));
+        assert_eq!(vec!["tr: invalid character class 'x'"], messages(&config("tr [:x:] a")));
+    }
+}
@@ -0,0 +1,148 @@
+use std::io;
+use common::input::flags::flags_options::OptionError;
+use common::input::known_error::KnownError;
+use common::input::source::describe_io_error;
+
+const ERROR_CODE: i32 = 1;
+const PROGRAM: &str = "tr";
+
+///
+/// The command line couldn't be split into options and operands.
+///
+pub(crate) fn option_error(error: &OptionError) -> KnownError {
+    KnownError::new(ERROR_CODE, error.message(PROGRAM))
+}
+
+///
+/// No sets were given at all.
+///
+pub(crate) fn missing_operand() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: missing operand", PROGRAM))
+}
+
+///
+/// One set was given where two are needed; `why` says what needs them.
+///
+pub(crate) fn missing_second_operand(first: &str, why: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: missing operand after '{}'\nTwo strings must be given when {}.", PROGRAM, first, why))
+}
+
+///
+/// More sets were given than are used; `why` explains, when there's more to say.
+///
+pub(crate) fn extra_operand(operand: &str, why: Option<&str>) -> KnownError {
+    match why {
+        Some(why) => KnownError::new(ERROR_CODE, format!("{}: extra operand '{}'\n{}", PROGRAM, operand, why)),
+        None => KnownError::new(ERROR_CODE, format!("{}: extra operand '{}'", PROGRAM, operand)),
+    }
+}
+
+///
+/// A range like `z-a` runs backwards.
+///
+pub(crate) fn reversed_range(start: u8, end: u8) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: range-endpoints of '{}-{}' are in reverse collating sequence order", PROGRAM, describe_byte(start), describe_byte(end)))
+}
+
+///
+/// `[:name:]` names no class.
+///
+pub(crate) fn invalid_class(name: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: invalid character class '{}'", PROGRAM, name))
+}
+
+///
+/// `[=x=]` holds more or less than one character.
+///
+pub(crate) fn invalid_equivalence(operand: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: {}: equivalence class operand must be a single character", PROGRAM, operand))
+}
+
+///
+/// `[c*n]` has a count that isn't a number.
+///
+pub(crate) fn invalid_repeat_count(count: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: invalid repeat count '{}' in [c*n] construct", PROGRAM, count))
+}
+
+///
+/// `[c*]` fills the second set out to the length of the first, so it has no meaning in the first.
+///
+pub(crate) fn fill_in_first_set() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: the [c*] repeat construct may not appear in string1", PROGRAM))
+}
+
+///
+/// The second set has more than one `[c*]`, so how much each fills is unclear.
+///
+pub(crate) fn fill_repeated() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: only one [c*] repeat construct may appear in string2", PROGRAM))
+}
+
+///
+/// `[c*]` in the second set, which is only filled out when translating.
+///
+pub(crate) fn fill_without_translating() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: the [c*] construct may appear in string2 only when translating", PROGRAM))
+}
+
+///
+/// Translating to an empty second set.
+///
+pub(crate) fn empty_second_set() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: when not truncating set1, string2 must be non-empty", PROGRAM))
+}
+
+///
+/// A class other than `[:upper:]` or `[:lower:]` in the second set, when translating.
+///
+pub(crate) fn class_in_second_set() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: when translating, the only character classes that may appear in\nstring2 are 'upper' and 'lower'", PROGRAM))
+}
+
+///
+/// `[=x=]` in the second set, when translating.
+///
+pub(crate) fn equivalence_in_second_set() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: [=c=] expressions may not appear in string2 when translating", PROGRAM))
+}
+
+///
+/// `[:upper:]` or `[:lower:]` in the second set without a case class at the same place in the first.
+///
+pub(crate) fn misaligned_case_class() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: misaligned [:upper:] and/or [:lower:] construct", PROGRAM))
+}
+
+///
+/// A complemented class has no order of its own, so it can only be translated to a single byte.
+///
+pub(crate) fn complemented_class_translation() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: when translating with complemented character classes,\nstring2 must map all characters in the domain to one", PROGRAM))
+}
+
+///
+/// Standard input couldn't be read.
+///
+pub(crate) fn read_error(error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: read error: {}", PROGRAM, describe_io_error(error)))
+}
+
+///
+/// Standard output couldn't be written.
+///
+pub(crate) fn write_error(error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: write error: {}", PROGRAM, describe_io_error(error)))
+}
+
+///
+/// Show a byte the way it would be typed in a set: printable characters as themselves, anything
+/// else as an octal escape.
+///
+fn describe_byte(byte: u8) -> String {
+    if byte.is_ascii_graphic() || byte == b' ' {
+        (byte as char).to_string()
+    } else {
+        format!("\\{:03o}", byte)
+    }
+}
@@ -0,0 +1,61 @@
+use std::io::{self, Write};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub(crate) fn version(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "rtr {}", VERSION)
+}
+
+pub(crate) fn help(out: &mut dyn Write) -> io::Result<()> {
+    version(out)?;
+    writeln!(out)?;
+    writeln!(out, "Usage: tr [OPTION]... STRING1 [STRING2]")?;
+    writeln!(out, "Translate, squeeze, and/or delete characters from standard input,")?;
+    writeln!(out, "writing to standard output.  STRING1 and STRING2 specify arrays of")?;
+    writeln!(out, "characters ARRAY1 and ARRAY2 that control the action.")?;
+    writeln!(out)?;
+    writeln!(out, "  -c, -C, --complement    use the complement of ARRAY1")?;
+    writeln!(out, "  -d, --delete            delete characters in ARRAY1, do not translate")?;
+    writeln!(out, "  -s, --squeeze-repeats   replace each sequence of a repeated character")?;
+    writeln!(out, "                            that is listed in the last specified ARRAY,")?;
+    writeln!(out, "                            with a single occurrence of that character")?;
+    writeln!(out, "  -t, --truncate-set1     first truncate ARRAY1 to length of ARRAY2")?;
+    writeln!(out, "      --help              display this help and exit")?;
+    writeln!(out, "      --version           output version information and exit")?;
+    writeln!(out)?;
+    writeln!(out, "ARRAYs are specified as strings of characters.  Most represent themselves.")?;
+    writeln!(out, "Interpreted sequences are:")?;
+    writeln!(out)?;
+    writeln!(out, "  \\NNN            character with octal value NNN (1 to 3 octal digits)")?;
+    writeln!(out, "  \\\\              backslash")?;
+    writeln!(out, "  \\a              audible BEL")?;
+    writeln!(out, "  \\b              backspace")?;
+    writeln!(out, "  \\f              form feed")?;
+    writeln!(out, "  \\n              new line")?;
+    writeln!(out, "  \\r              return")?;
+    writeln!(out, "  \\t              horizontal tab")?;
+    writeln!(out, "  \\v              vertical tab")?;
+    writeln!(out, "  CHAR1-CHAR2      all characters from CHAR1 to CHAR2 in ascending order")?;
+    writeln!(out, "  [CHAR*]          in ARRAY2, copies of CHAR until length of ARRAY1")?;
+    writeln!(out, "  [CHAR*REPEAT]    REPEAT copies of CHAR, REPEAT octal if starting with 0")?;
+    writeln!(out, "  [:alnum:]        all letters and digits")?;
+    writeln!(out, "  [:alpha:]        all letters")?;
+    writeln!(out, "  [:blank:]        all horizontal whitespace")?;
+    writeln!(out, "  [:cntrl:]        all control characters")?;
+    writeln!(out, "  [:digit:]        all digits")?;
+    writeln!(out, "  [:graph:]        all printable characters, not including space")?;
+    writeln!(out, "  [:lower:]        all lower case letters")?;
+    writeln!(out, "  [:print:]        all printable characters, including space")?;
+    writeln!(out, "  [:punct:]        all punctuation characters")?;
+    writeln!(out, "  [:space:]        all horizontal or vertical whitespace")?;
+    writeln!(out, "  [:upper:]        all upper case letters")?;
+    writeln!(out, "  [:xdigit:]       all hexadecimal digits")?;
+    writeln!(out, "  [=CHAR=]         all characters which are equivalent to CHAR")?;
+    writeln!(out)?;
+    writeln!(out, "Translation occurs if -d is not given and both STRING1 and STRING2 appear.")?;
+    writeln!(out, "-t may be used only when translating.  ARRAY2 is extended to length of")?;
+    writeln!(out, "ARRAY1 by repeating its last character as necessary.  Excess characters")?;
+    writeln!(out, "of ARRAY2 are ignored.  Character classes expand in unspecified order;")?;
+    writeln!(out, "while translating, [:lower:] and [:upper:] may be used in pairs to")?;
+    writeln!(out, "specify case conversion.  Squeezing occurs after translation or deletion.")
+}
@@ -0,0 +1,17 @@
+mod config;
+mod errors;
+mod help;
+mod set;
+mod table;
+mod tr;
+
+use crate::tr::tr;
+
+///
+/// Main program entrypoint.
+///
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let status = tr(&args, &mut std::io::stdin().lock(), &mut std::io::stdout().lock(), &mut std::io::stderr());
+    std::process::exit(status);
+}
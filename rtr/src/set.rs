@@ -0,0 +1,344 @@
+use common::input::known_error::KnownError;
+use crate::errors::{invalid_class, invalid_equivalence, invalid_repeat_count, reversed_range};
+
+///
+/// The POSIX character classes, for the `C` locale.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Class {
+    Alnum,
+    Alpha,
+    Blank,
+    Cntrl,
+    Digit,
+    Graph,
+    Lower,
+    Print,
+    Punct,
+    Space,
+    Upper,
+    Xdigit,
+}
+
+impl Class {
+    fn from_name(name: &str) -> Option<Class> {
+        match name {
+            "alnum" => Some(Class::Alnum),
+            "alpha" => Some(Class::Alpha),
+            "blank" => Some(Class::Blank),
+            "cntrl" => Some(Class::Cntrl),
+            "digit" => Some(Class::Digit),
+            "graph" => Some(Class::Graph),
+            "lower" => Some(Class::Lower),
+            "print" => Some(Class::Print),
+            "punct" => Some(Class::Punct),
+            "space" => Some(Class::Space),
+            "upper" => Some(Class::Upper),
+            "xdigit" => Some(Class::Xdigit),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn contains(&self, byte: u8) -> bool {
+        match self {
+            Class::Alnum => byte.is_ascii_alphanumeric(),
+            Class::Alpha => byte.is_ascii_alphabetic(),
+            Class::Blank => byte == b' ' || byte == b'\t',
+            Class::Cntrl => byte.is_ascii_control(),
+            Class::Digit => byte.is_ascii_digit(),
+            Class::Graph => byte.is_ascii_graphic(),
+            Class::Lower => byte.is_ascii_lowercase(),
+            Class::Print => byte.is_ascii_graphic() || byte == b' ',
+            Class::Punct => byte.is_ascii_punctuation(),
+            /* \v isn't ascii whitespace to rust, but is to POSIX */
+            Class::Space => byte.is_ascii_whitespace() || byte == 0x0b,
+            Class::Upper => byte.is_ascii_uppercase(),
+            Class::Xdigit => byte.is_ascii_hexdigit(),
+        }
+    }
+
+    pub(crate) fn is_case(&self) -> bool {
+        *self == Class::Upper || *self == Class::Lower
+    }
+}
+
+///
+/// One piece of a set, as written.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Element {
+    Byte(u8),
+    /// `a-z`
+    Range(u8, u8),
+    /// `[:alpha:]`
+    Class(Class),
+    /// `[=e=]`; in the `C` locale, only the byte itself.
+    Equivalence(u8),
+    /// `[c*n]`, or `[c*]` to fill the second set out to the length of the first.
+    Repeat(u8, Option<usize>),
+}
+
+///
+/// A set of bytes, as given on the command line.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Set {
+    pub(crate) elements: Vec<Element>,
+}
+
+///
+/// A set written out in order, as runs of the same byte so that `[c*n]` costs nothing however
+/// large `n` is.  The positions of `[:upper:]` and `[:lower:]` are kept, since translating between
+/// them depends on where they are.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Expanded {
+    pub(crate) runs: Vec<(u8, usize)>,
+    pub(crate) length: usize,
+    pub(crate) case_classes: Vec<usize>,
+}
+
+impl Expanded {
+    fn push(&mut self, byte: u8, count: usize) {
+        if count > 0 {
+            self.runs.push((byte, count));
+            self.length = self.length.saturating_add(count);
+        }
+    }
+
+    pub(crate) fn last(&self) -> Option<u8> {
+        self.runs.last().map(|(byte, _)| *byte)
+    }
+
+    pub(crate) fn bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        self.runs.iter().map(|(byte, _)| *byte)
+    }
+}
+
+impl Set {
+    ///
+    /// Parse a set: escapes first, then ranges and bracketed constructs.  A `[` that doesn't start a
+    /// complete construct is just a `[`.
+    ///
+    pub(crate) fn parse(spec: &str) -> Result<Set, KnownError> {
+        let chars = unescape(spec.as_bytes());
+        let is = |index: usize, byte: u8| chars.get(index) == Some(&(byte, false));
+
+        let mut elements = vec![];
+        let mut index = 0;
+        while index < chars.len() {
+            if is(index, b'[') && let Some((element, next)) = parse_bracket(&chars, index)? {
+                elements.push(element);
+                index = next;
+                continue;
+            }
+
+            let (start, _) = chars[index];
+            if is(index + 1, b'-') && index + 2 < chars.len() {
+                let (end, _) = chars[index + 2];
+                if end < start {
+                    return Err(reversed_range(start, end));
+                }
+                elements.push(Element::Range(start, end));
+                index += 3;
+            } else {
+                elements.push(Element::Byte(start));
+                index += 1;
+            }
+        }
+
+        Ok(Set { elements })
+    }
+
+    pub(crate) fn fill_count(&self) -> usize {
+        self.elements.iter().filter(|element| matches!(element, Element::Repeat(_, None))).count()
+    }
+
+    pub(crate) fn has_class(&self, wanted: impl Fn(&Class) -> bool) -> bool {
+        self.elements.iter().any(|element| matches!(element, Element::Class(class) if wanted(class)))
+    }
+
+    pub(crate) fn has_equivalence(&self) -> bool {
+        self.elements.iter().any(|element| matches!(element, Element::Equivalence(_)))
+    }
+
+    ///
+    /// Write the set out in order; `[c*]` repeats `fill` times.
+    ///
+    pub(crate) fn expand(&self, fill: usize) -> Expanded {
+        let mut expanded = Expanded { runs: vec![], length: 0, case_classes: vec![] };
+        for element in self.elements.iter() {
+            match *element {
+                Element::Byte(byte) | Element::Equivalence(byte) => expanded.push(byte, 1),
+                Element::Range(start, end) => (start..=end).for_each(|byte| expanded.push(byte, 1)),
+                Element::Class(class) => {
+                    if class.is_case() {
+                        expanded.case_classes.push(expanded.length);
+                    }
+                    (0..=u8::MAX).filter(|byte| class.contains(*byte)).for_each(|byte| expanded.push(byte, 1));
+                }
+                Element::Repeat(byte, count) => expanded.push(byte, count.unwrap_or(fill)),
+            }
+        }
+        expanded
+    }
+}
+
+///
+/// Turn escapes into the bytes they stand for, remembering which bytes were escaped so that an
+/// escaped `[` or `-` isn't taken as syntax.
+///
+fn unescape(spec: &[u8]) -> Vec<(u8, bool)> {
+    let mut chars = vec![];
+    let mut index = 0;
+    while index < spec.len() {
+        if spec[index] != b'\\' || index + 1 == spec.len() {
+            /* a trailing backslash stands for itself */
+            chars.push((spec[index], false));
+            index += 1;
+            continue;
+        }
+
+        let escaped = spec[index + 1];
+        index += 2;
+        let byte = match escaped {
+            b'a' => 0x07,
+            b'b' => 0x08,
+            b'f' => 0x0c,
+            b'n' => b'\n',
+            b'r' => b'\r',
+            b't' => b'\t',
+            b'v' => 0x0b,
+            b'0'..=b'7' => {
+                /* up to three octal digits, as long as they fit in a byte */
+                let mut value = (escaped - b'0') as u32;
+                for _ in 0..2 {
+                    match spec.get(index) {
+                        Some(digit @ b'0'..=b'7') if value * 8 + ((digit - b'0') as u32) <= u8::MAX as u32 => {
+                            value = value * 8 + (digit - b'0') as u32;
+                            index += 1;
+                        }
+                        _ => break,
+                    }
+                }
+                value as u8
+            }
+            other => other,
+        };
+        chars.push((byte, true));
+    }
+    chars
+}
+
+///
+/// Try to read `[:class:]`, `[=e=]` or `[c*n]` starting at the `[` at `start`.  Gives the element
+/// and the index just past it, or [None] if there's no complete construct there.
+///
+fn parse_bracket(chars: &[(u8, bool)], start: usize) -> Result<Option<(Element, usize)>, KnownError> {
+    let is = |index: usize, byte: u8| chars.get(index) == Some(&(byte, false));
+    let text = |from: usize, to: usize| chars[from..to].iter().map(|(byte, _)| *byte as char).collect::<String>();
+
+    for delimiter in [b':', b'='] {
+        if !is(start + 1, delimiter) {
+            continue;
+        }
+        /* an equivalence class needs something in it; `[==]` isn't one */
+        let first_close = if delimiter == b'=' { start + 3 } else { start + 2 };
+        let close = (first_close..chars.len()).find(|&index| is(index, delimiter) && is(index + 1, b']'));
+        if let Some(close) = close {
+            let operand = text(start + 2, close);
+            let element = if delimiter == b':' {
+                Element::Class(Class::from_name(&operand).ok_or_else(|| invalid_class(&operand))?)
+            } else if close == start + 3 {
+                Element::Equivalence(chars[start + 2].0)
+            } else {
+                return Err(invalid_equivalence(&operand));
+            };
+            return Ok(Some((element, close + 2)));
+        }
+    }
+
+    if start + 2 < chars.len() && is(start + 2, b'*') {
+        let byte = chars[start + 1].0;
+        if let Some(close) = (start + 3..chars.len()).find(|&index| is(index, b']')) {
+            let count = text(start + 3, close);
+            return Ok(Some((Element::Repeat(byte, parse_repeat_count(&count)?), close + 1)));
+        }
+    }
+
+    Ok(None)
+}
+
+///
+/// Read the `n` of `[c*n]`: octal with a leading zero, decimal otherwise.  Nothing, or zero, means
+/// fill.
+///
+fn parse_repeat_count(count: &str) -> Result<Option<usize>, KnownError> {
+    if count.is_empty() {
+        return Ok(None);
+    }
+    let radix = if count.starts_with('0') { 8 } else { 10 };
+    if !count.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid_repeat_count(count));
+    }
+    match usize::from_str_radix(count, radix) {
+        Ok(0) => Ok(None),
+        Ok(count) => Ok(Some(count)),
+        Err(_) => Err(invalid_repeat_count(count)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::set::{Class, Element, Set};
+
+    fn elements(spec: &str) -> Vec<Element> {
+        Set::parse(spec).unwrap().elements
+    }
+
+    fn error(spec: &str) -> String {
+        Set::parse(spec).unwrap_err().message.unwrap()
+    }
+
+    fn bytes(spec: &str, fill: usize) -> Vec<u8> {
+        Set::parse(spec).unwrap().expand(fill).runs.iter().flat_map(|(byte, count)| std::iter::repeat_n(*byte, *count)).collect()
+    }
+
+    #[test]
+    fn test_escapes() {
+        assert_eq!(vec![Element::Byte(b'\n'), Element::Byte(b'\t'), Element::Byte(0x0b), Element::Byte(b'\\')], elements("\\n\\t\\v\\\\"));
+        assert_eq!(vec![Element::Byte(0o101), Element::Byte(0), Element::Byte(b'q')], elements("\\101\\0\\q"));
+        /* \400 doesn't fit in a byte; it's \40 then 0 */
+        assert_eq!(vec![Element::Byte(0o40), Element::Byte(b'0')], elements("\\400"));
+        assert_eq!(vec![Element::Byte(b'a'), Element::Byte(b'\\')], elements("a\\"));
+    }
+
+    #[test]
+    fn test_ranges() {
+        assert_eq!(vec![Element::Range(b'a', b'z'), Element::Byte(b'-')], elements("a-z-"));
+        assert_eq!(vec![Element::Byte(b'-'), Element::Range(b'0', b'9')], elements("-0-9"));
+        assert_eq!(vec![Element::Byte(b'a'), Element::Byte(b'-'), Element::Byte(b'z')], elements("a\\-z"));
+        assert_eq!(vec![Element::Range(0, b'\n')], elements("\\000-\\n"));
+        assert_eq!(b"abcxyz".to_vec(), bytes("a-cx-z", 0));
+    }
+
+    #[test]
+    fn test_brackets() {
+        assert_eq!(vec![Element::Class(Class::Upper), Element::Equivalence(b'e')], elements("[:upper:][=e=]"));
+        assert_eq!(vec![Element::Repeat(b'x', Some(3)), Element::Repeat(b'y', Some(8)), Element::Repeat(b'z', None)], elements("[x*3][y*010][z*]"));
+        assert_eq!(vec![Element::Byte(b'['), Element::Range(b'a', b'z'), Element::Byte(b']')], elements("[a-z]"));
+        assert_eq!(vec![Element::Byte(b'['), Element::Byte(b':'), Element::Byte(b'a')], elements("[:a"));
+        assert_eq!(b"xxxyq".to_vec(), bytes("[x*3]y[q*]", 1));
+        assert_eq!(b"0123456789".to_vec(), bytes("[:digit:]", 0));
+        assert_eq!(b"\t\n\x0b\x0c\r ".to_vec(), bytes("[:space:]", 0));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!("tr: range-endpoints of 'z-a' are in reverse collating sequence order", error("z-a"));
+        assert_eq!("tr: invalid character class 'vowel'", error("[:vowel:]"));
+        assert_eq!("tr: ab: equivalence class operand must be a single character", error("[=ab=]"));
+        assert_eq!("tr: invalid repeat count '09' in [c*n] construct", error("[a*09]"));
+        assert_eq!("tr: invalid repeat count '+1' in [c*n] construct", error("[a*+1]"));
+    }
+}
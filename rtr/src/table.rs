@@ -0,0 +1,211 @@
+use common::input::known_error::KnownError;
+use crate::config::Config;
+use crate::errors::{class_in_second_set, complemented_class_translation, empty_second_set, equivalence_in_second_set, fill_in_first_set, fill_repeated, fill_without_translating, misaligned_case_class};
+use crate::set::{Expanded, Set};
+
+const BYTES: usize = 256;
+
+///
+/// Everything `tr` does to a byte, decided up front: whether it's deleted, what it becomes, and
+/// whether repeats of what it becomes are squeezed.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Tables {
+    pub(crate) delete: [bool; BYTES],
+    pub(crate) translate: [u8; BYTES],
+    pub(crate) squeeze: [bool; BYTES],
+}
+
+impl Tables {
+    pub(crate) fn new(config: &Config) -> Result<Tables, KnownError> {
+        let mut tables = Tables { delete: [false; BYTES], translate: std::array::from_fn(|byte| byte as u8), squeeze: [false; BYTES] };
+        let Some(set1) = &config.set1 else {
+            return Ok(tables);
+        };
+        check_sets(config, set1, config.set2.as_ref())?;
+
+        let domain = if config.complement { complement(&set1.expand(0)) } else { set1.expand(0) };
+        if config.delete {
+            domain.bytes().for_each(|byte| tables.delete[byte as usize] = true);
+        }
+
+        let mut squeezed = &domain;
+        let target;
+        if let Some(set2) = &config.set2 {
+            target = if config.translating() {
+                let fill = domain.length.saturating_sub(set2.expand(0).length);
+                let target = set2.expand(fill);
+                check_translation(config, set1, &domain, &target)?;
+                map(&domain, &target, config.truncate, &mut tables.translate);
+                target
+            } else {
+                set2.expand(0)
+            };
+            squeezed = &target;
+        }
+
+        if config.squeeze {
+            squeezed.bytes().for_each(|byte| tables.squeeze[byte as usize] = true);
+        }
+        Ok(tables)
+    }
+}
+
+///
+/// Check the constructs in each set are allowed where they are.
+///
+fn check_sets(config: &Config, set1: &Set, set2: Option<&Set>) -> Result<(), KnownError> {
+    if set1.fill_count() > 0 {
+        return Err(fill_in_first_set());
+    }
+    let Some(set2) = set2 else {
+        return Ok(());
+    };
+
+    match set2.fill_count() {
+        0 => {}
+        1 if config.translating() => {}
+        1 => return Err(fill_without_translating()),
+        _ => return Err(fill_repeated()),
+    }
+    if config.translating() {
+        if set2.has_equivalence() {
+            return Err(equivalence_in_second_set());
+        }
+        if set2.has_class(|class| !class.is_case()) {
+            return Err(class_in_second_set());
+        }
+    }
+    Ok(())
+}
+
+fn check_translation(config: &Config, set1: &Set, domain: &Expanded, target: &Expanded) -> Result<(), KnownError> {
+    if target.length == 0 && domain.length > 0 && !config.truncate {
+        return Err(empty_second_set());
+    }
+
+    /* a case class in the second set only makes sense opposite one in the first */
+    if target.case_classes.iter().any(|position| !domain.case_classes.contains(position)) {
+        return Err(misaligned_case_class());
+    }
+
+    if config.complement && set1.has_class(|_| true) && target.bytes().any(|byte| Some(byte) != target.last()) {
+        return Err(complemented_class_translation());
+    }
+    Ok(())
+}
+
+///
+/// Every byte not in the set, in order.
+///
+fn complement(set: &Expanded) -> Expanded {
+    let mut members = [false; BYTES];
+    set.bytes().for_each(|byte| members[byte as usize] = true);
+
+    let runs: Vec<(u8, usize)> = (0..=u8::MAX).filter(|byte| !members[*byte as usize]).map(|byte| (byte, 1)).collect();
+    Expanded { length: runs.len(), runs, case_classes: vec![] }
+}
+
+///
+/// Map each byte of the domain to the byte at the same position in the target; a target that's
+/// too short repeats its last byte, unless truncating.  When a byte appears more than once in the
+/// domain, its last position wins.
+///
+fn map(domain: &Expanded, target: &Expanded, truncate: bool, translate: &mut [u8; BYTES]) {
+    let Some(last_target) = target.last() else {
+        return;
+    };
+
+    let mut position = 0usize;
+    let mut target_index = 0;
+    let mut target_end = target.runs[0].1;
+    for &(byte, count) in domain.runs.iter() {
+        let start = position;
+        position = position.saturating_add(count);
+        if truncate && start >= target.length {
+            break;
+        }
+
+        let last_position = position.min(if truncate { target.length } else { usize::MAX }) - 1;
+        while target_end <= last_position && target_index + 1 < target.runs.len() {
+            target_index += 1;
+            target_end = target_end.saturating_add(target.runs[target_index].1);
+        }
+        translate[byte as usize] = if last_position < target.length { target.runs[target_index].0 } else { last_target };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::table::Tables;
+
+    fn tables(args: &[&str]) -> Tables {
+        let args: Vec<String> = std::iter::once("tr").chain(args.iter().copied()).map(|s| s.to_string()).collect();
+        Tables::new(&Config::new(&args)).unwrap()
+    }
+
+    fn error(args: &[&str]) -> String {
+        let args: Vec<String> = std::iter::once("tr").chain(args.iter().copied()).map(|s| s.to_string()).collect();
+        Tables::new(&Config::new(&args)).unwrap_err().message.unwrap()
+    }
+
+    fn translated(tables: &Tables, text: &str) -> String {
+        text.bytes().map(|byte| tables.translate[byte as usize] as char).collect()
+    }
+
+    #[test]
+    fn test_translate() {
+        assert_eq!("HELLO, WORLD", translated(&tables(&["a-z", "A-Z"]), "hello, world"));
+        assert_eq!("xyzzz", translated(&tables(&["abcde", "xyz"]), "abcde"));
+        assert_eq!("xyzde", translated(&tables(&["-t", "abcde", "xyz"]), "abcde"));
+        assert_eq!("HELLO", translated(&tables(&["[:lower:]", "[:upper:]"]), "hello"));
+        assert_eq!("y", translated(&tables(&["aa", "xy"]), "a"));
+    }
+
+    #[test]
+    fn test_repeats() {
+        let fill = tables(&["a-e", "[x*]yz"]);
+        assert_eq!("xxxyz", translated(&fill, "abcde"));
+
+        let counted = tables(&["a-f", "[x*2][y*3]z"]);
+        assert_eq!("xxyyyz", translated(&counted, "abcdef"));
+
+        /* a huge count costs nothing, and the position after it still lines up */
+        let huge = tables(&["[a*1000000000]b", "[x*1000000000]y"]);
+        assert_eq!("xy", translated(&huge, "ab"));
+    }
+
+    #[test]
+    fn test_complement() {
+        let tables = tables(&["-c", "a-z\\n", "_"]);
+        assert_eq!("hello_world\n", translated(&tables, "hello world\n"));
+
+        let delete = self::tables(&["-cd", "0-9"]);
+        assert!(!delete.delete[b'5' as usize] && delete.delete[b'x' as usize]);
+    }
+
+    #[test]
+    fn test_squeeze_sets() {
+        let squeeze = tables(&["-s", "ab"]);
+        assert!(squeeze.squeeze[b'a' as usize] && !squeeze.squeeze[b'c' as usize]);
+
+        let translate = tables(&["-s", "ab", "x"]);
+        assert!(translate.squeeze[b'x' as usize] && !translate.squeeze[b'a' as usize]);
+
+        let delete = tables(&["-ds", "ab", "c"]);
+        assert!(delete.delete[b'a' as usize] && delete.squeeze[b'c' as usize] && !delete.squeeze[b'a' as usize]);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!("tr: the [c*] repeat construct may not appear in string1", error(&["[a*]", "x"]));
+        assert_eq!("tr: only one [c*] repeat construct may appear in string2", error(&["abc", "[x*][y*]"]));
+        assert_eq!("tr: the [c*] construct may appear in string2 only when translating", error(&["-ds", "abc", "[x*]"]));
+        assert_eq!("tr: when not truncating set1, string2 must be non-empty", error(&["abc", ""]));
+        assert_eq!("tr: when translating, the only character classes that may appear in\nstring2 are 'upper' and 'lower'", error(&["abc", "[:digit:]"]));
+        assert_eq!("tr: [=c=] expressions may not appear in string2 when translating", error(&["abc", "[=x=]"]));
+        assert_eq!("tr: misaligned [:upper:] and/or [:lower:] construct", error(&["a-z", "[:upper:]"]));
+        assert_eq!("tr: when translating with complemented character classes,\nstring2 must map all characters in the domain to one", error(&["-c", "[:alpha:]", "xy"]));
+    }
+}
@@ -0,0 +1,130 @@
+use std::io::{self, Read, Write};
+use common::input::known_error::{report, KnownError};
+use crate::config::Config;
+use crate::errors::{read_error, write_error};
+use crate::help::{help, version};
+use crate::table::Tables;
+
+const SUCCESS: i32 = 0;
+const FAILURE: i32 = 1;
+const BUFFER_SIZE: usize = 64 * 1024;
+
+///
+/// Run `tr` with command line arguments; the first argument is the program name.  Returns the
+/// exit status.
+///
+pub(crate) fn tr(args: &[String], input: &mut dyn Read, out: &mut dyn Write, err: &mut dyn Write) -> i32 {
+    let config = Config::new(args);
+    if let Some(first) = config.errors.first() {
+        report(err, &config.errors);
+        return first.code;
+    }
+
+    if config.print_help_and_exit {
+        return help(out).map_or(FAILURE, |_| SUCCESS);
+    }
+    if config.print_version_and_exit {
+        return version(out).map_or(FAILURE, |_| SUCCESS);
+    }
+
+    match Tables::new(&config).and_then(|tables| transform(&tables, input, out)) {
+        Ok(()) => SUCCESS,
+        Err(error) => {
+            report(err, std::slice::from_ref(&error));
+            error.code
+        }
+    }
+}
+
+///
+/// Copy input to output a buffer at a time, deleting, translating and squeezing each byte by
+/// looking it up in the tables.
+///
+fn transform(tables: &Tables, input: &mut dyn Read, out: &mut dyn Write) -> Result<(), KnownError> {
+    let mut buffer = vec![0u8; BUFFER_SIZE];
+    let mut output = Vec::with_capacity(BUFFER_SIZE);
+    /* squeezing carries over from one buffer to the next */
+    let mut last: Option<u8> = None;
+
+    loop {
+        let read = match input.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(read_error(&error)),
+        };
+
+        output.clear();
+        for &byte in buffer[..read].iter() {
+            if tables.delete[byte as usize] {
+                continue;
+            }
+            let byte = tables.translate[byte as usize];
+            if tables.squeeze[byte as usize] && last == Some(byte) {
+                continue;
+            }
+            output.push(byte);
+            last = Some(byte);
+        }
+        out.write_all(&output).map_err(|error| write_error(&error))?;
+    }
+
+    out.flush().map_err(|error| write_error(&error))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Read};
+    use crate::tr::tr;
+
+    fn run(args: &[&str], input: &[u8]) -> (i32, Vec<u8>, String) {
+        let args: Vec<String> = std::iter::once("tr").chain(args.iter().copied()).map(|s| s.to_string()).collect();
+        let mut out = vec![];
+        let mut err = vec![];
+        let status = tr(&args, &mut &input[..], &mut out, &mut err);
+        (status, out, String::from_utf8(err).unwrap())
+    }
+
+    ///
+    /// Hands out input a few bytes at a time, to cross buffer boundaries.
+    ///
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            let count = self.0.len().min(buffer.len()).min(2);
+            buffer[..count].copy_from_slice(&self.0[..count]);
+            self.0 = &self.0[count..];
+            Ok(count)
+        }
+    }
+
+    #[test]
+    fn test_modes() {
+        assert_eq!((0, b"HELLO\n".to_vec(), String::new()), run(&["a-z", "A-Z"], b"hello\n"));
+        assert_eq!(b"hll\n".to_vec(), run(&["-d", "aeiou"], b"hello\n").1);
+        assert_eq!(b"a b\n".to_vec(), run(&["-s", " "], b"a    b\n").1);
+        assert_eq!(b"a_b_c\n".to_vec(), run(&["-cs", "[:alnum:]\\n", "_"], b"a, b;; c\n").1);
+        assert_eq!(b"ac".to_vec(), run(&["-ds", "b", "c"], b"abccc").1);
+    }
+
+    #[test]
+    fn test_bytes() {
+        assert_eq!(b"\x00\x01x".to_vec(), run(&["\\377", "x"], b"\x00\x01\xff").1);
+        assert_eq!(b"\n\n".to_vec(), run(&["\\000", "\\n"], b"\x00\x00").1);
+    }
+
+    #[test]
+    fn test_squeeze_across_reads() {
+        let args: Vec<String> = ["tr", "-s", "a"].iter().map(|s| s.to_string()).collect();
+        let mut out = vec![];
+        assert_eq!(0, tr(&args, &mut Trickle(b"baaaaaaab"), &mut out, &mut vec![]));
+        assert_eq!(b"bab".to_vec(), out);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!((1, vec![], "tr: missing operand\n".to_string()), run(&[], b""));
+        assert_eq!((1, vec![], "tr: misaligned [:upper:] and/or [:lower:] construct\n".to_string()), run(&["ab", "[:upper:]"], b"ab"));
+    }
+}
@@ -0,0 +1,283 @@
+use common::input::flags::flags_options::{parse_options, OptionSpec, ParsedArgument};
+use common::input::known_error::KnownError;
+use common::input::source::InputSource;
+use crate::errors::{count_all_repeated, extra_operand, group_exclusive, invalid_method, invalid_number, option_error};
+
+const ALL_REPEATED: &str = "all-repeated";
+const GROUP: &str = "group";
+
+const OPTIONS: [OptionSpec; 12] = [
+    OptionSpec::flag("count", Some('c'), Some("count")),
+    OptionSpec::flag("repeated", Some('d'), Some("repeated")),
+    OptionSpec::optional(ALL_REPEATED, Some('D'), Some(ALL_REPEATED)),
+    OptionSpec::required("skip-fields", Some('f'), Some("skip-fields")),
+    OptionSpec::optional(GROUP, None, Some(GROUP)),
+    OptionSpec::flag("ignore-case", Some('i'), Some("ignore-case")),
+    OptionSpec::required("skip-chars", Some('s'), Some("skip-chars")),
+    OptionSpec::flag("unique", Some('u'), Some("unique")),
+    OptionSpec::flag("zero", Some('z'), Some("zero-terminated")),
+    OptionSpec::required("check-chars", Some('w'), Some("check-chars")),
+    OptionSpec::flag("help", None, Some("help")),
+    OptionSpec::flag("version", None, Some("version")),
+];
+
+///
+/// Where empty lines go, to set groups of lines apart.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Delimit {
+    None,
+    Prepend,
+    Append,
+    Separate,
+    Both,
+}
+
+///
+/// The part of each line that's compared.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Comparison {
+    pub(crate) skip_fields: usize,
+    pub(crate) skip_chars: usize,
+    pub(crate) check_chars: Option<usize>,
+    pub(crate) ignore_case: bool,
+}
+
+///
+/// What's printed for each group of equal lines.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Mode {
+    /// One line per group; groups can be left out by size.
+    Collapse { count: bool, repeated: bool, unique: bool },
+    /// Every line of each group with more than one line.
+    AllRepeated(Delimit),
+    /// Every line, with groups set apart.
+    Group(Delimit),
+}
+
+///
+/// Storage for the application configuration.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct Config {
+    pub(crate) print_help_and_exit: bool,
+    pub(crate) print_version_and_exit: bool,
+
+    pub(crate) mode: Mode,
+    pub(crate) comparison: Comparison,
+    pub(crate) delimiter: u8,
+
+    pub(crate) input: InputSource,
+    pub(crate) output: Option<String>,
+    pub(crate) errors: Vec<KnownError>,
+}
+
+impl Default for Config {
+    ///
+    /// Generate [Default] configuration.
+    ///
+    fn default() -> Self {
+        Config {
+            print_help_and_exit: false,
+            print_version_and_exit: false,
+
+            mode: Mode::Collapse { count: false, repeated: true, unique: true },
+            comparison: Comparison { skip_fields: 0, skip_chars: 0, check_chars: None, ignore_case: false },
+            delimiter: b'\n',
+
+            input: InputSource::Stdin,
+            output: None,
+            errors: vec![],
+        }
+    }
+}
+
+///
+/// Options that pick the mode; they interact, so they're gathered before the mode is decided.
+///
+#[derive(Default)]
+struct Selection {
+    count: bool,
+    only_repeated: bool,
+    only_unique: bool,
+    all_repeated: Option<Delimit>,
+    group: Option<Delimit>,
+}
+
+impl Config {
+    ///
+    /// Initialize the application configuration based on provided application arguments; the first
+    /// argument is the program name.
+    ///
+    pub(crate) fn new(args: &[String]) -> Config {
+        let mut config = Self::default();
+
+        let parsed = match parse_options(args.get(1..).unwrap_or_default(), &OPTIONS) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                config.errors.push(option_error(&error));
+                return config;
+            }
+        };
+
+        let mut selection = Selection::default();
+        let mut operands = vec![];
+        for argument in parsed {
+            match argument {
+                ParsedArgument::Operand(operand) => operands.push(operand),
+                ParsedArgument::Option { id, value } => {
+                    if let Err(error) = config.apply(&mut selection, id, value.unwrap_or_default()) {
+                        config.errors.push(error);
+                    }
+                }
+            }
+        }
+
+        match select_mode(&selection) {
+            Ok(mode) => config.mode = mode,
+            Err(error) => config.errors.push(error),
+        }
+
+        /* uniq [INPUT [OUTPUT]] */
+        let mut operands = operands.into_iter();
+        if let Some(input) = operands.next() {
+            config.input = InputSource::from_operand(&input);
+        }
+        config.output = operands.next();
+        if let Some(extra) = operands.next() {
+            config.errors.push(extra_operand(&extra));
+        }
+
+        config
+    }
+
+    fn apply(&mut self, selection: &mut Selection, id: &str, value: String) -> Result<(), KnownError> {
+        match id {
+            "count" => selection.count = true,
+            "repeated" => selection.only_repeated = true,
+            ALL_REPEATED => selection.all_repeated = Some(parse_delimit(ALL_REPEATED, &value, &[("none", Delimit::None), ("prepend", Delimit::Prepend), ("separate", Delimit::Separate)])?),
+            GROUP => selection.group = Some(parse_delimit(GROUP, &value, &[("separate", Delimit::Separate), ("prepend", Delimit::Prepend), ("append", Delimit::Append), ("both", Delimit::Both)])?),
+            "unique" => selection.only_unique = true,
+            "skip-fields" => self.comparison.skip_fields = parse_number("fields to skip", &value)?,
+            "skip-chars" => self.comparison.skip_chars = parse_number("bytes to skip", &value)?,
+            "check-chars" => self.comparison.check_chars = Some(parse_number("bytes to compare", &value)?),
+            "ignore-case" => self.comparison.ignore_case = true,
+            "zero" => self.delimiter = b'\0',
+            "help" => self.print_help_and_exit = true,
+            "version" => self.print_version_and_exit = true,
+            _ => unreachable!("option {} has no handler", id),
+        }
+        Ok(())
+    }
+}
+
+fn select_mode(selection: &Selection) -> Result<Mode, KnownError> {
+    if let Some(delimit) = selection.group {
+        if selection.count || selection.only_repeated || selection.only_unique || selection.all_repeated.is_some() {
+            return Err(group_exclusive());
+        }
+        return Ok(Mode::Group(delimit));
+    }
+
+    match selection.all_repeated {
+        Some(_) if selection.count => Err(count_all_repeated()),
+        /* -D -u leaves nothing: every line printed by -D is repeated */
+        Some(_) if selection.only_unique => Ok(Mode::Collapse { count: false, repeated: false, unique: false }),
+        Some(delimit) => Ok(Mode::AllRepeated(delimit)),
+        None => Ok(Mode::Collapse {
+            count: selection.count,
+            repeated: !selection.only_unique,
+            unique: !selection.only_repeated,
+        }),
+    }
+}
+
+///
+/// Read a method name, which may be abbreviated; no method at all means the first one listed.
+///
+fn parse_delimit(option: &str, value: &str, methods: &[(&str, Delimit)]) -> Result<Delimit, KnownError> {
+    if value.is_empty() {
+        return Ok(methods[0].1);
+    }
+    if let Some((_, delimit)) = methods.iter().find(|(name, _)| *name == value) {
+        return Ok(*delimit);
+    }
+
+    let mut matches = methods.iter().filter(|(name, _)| name.starts_with(value));
+    match (matches.next(), matches.next()) {
+        (Some((_, delimit)), None) => Ok(*delimit),
+        _ => Err(invalid_method(option, value)),
+    }
+}
+
+///
+/// Read a count; one too large to represent skips or compares everything anyway.
+///
+fn parse_number(what: &str, value: &str) -> Result<usize, KnownError> {
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid_number(what, value));
+    }
+    Ok(value.parse().unwrap_or(usize::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use common::input::source::InputSource;
+    use crate::config::{Comparison, Config, Delimit, Mode};
+
+    fn config(line: &str) -> Config {
+        let args: Vec<String> = line.split_whitespace().map(|s| s.to_string()).collect();
+        Config::new(&args)
+    }
+
+    fn messages(config: &Config) -> Vec<String> {
+        config.errors.iter().map(|e| e.message.clone().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_defaults() {
+        let config = config("uniq");
+
+        assert!(config.errors.is_empty());
+        assert_eq!(Mode::Collapse { count: false, repeated: true, unique: true }, config.mode);
+        assert_eq!(Comparison { skip_fields: 0, skip_chars: 0, check_chars: None, ignore_case: false }, config.comparison);
+        assert_eq!(InputSource::Stdin, config.input);
+        assert_eq!(None, config.output);
+        assert_eq!(b'\n', config.delimiter);
+    }
+
+    #[test]
+    fn test_modes() {
+        assert_eq!(Mode::Collapse { count: true, repeated: true, unique: false }, config("uniq -cd").mode);
+        assert_eq!(Mode::Collapse { count: false, repeated: false, unique: true }, config("uniq -u").mode);
+        assert_eq!(Mode::AllRepeated(Delimit::None), config("uniq -D").mode);
+        assert_eq!(Mode::AllRepeated(Delimit::Separate), config("uniq --all-repeated=sep").mode);
+        assert_eq!(Mode::Group(Delimit::Separate), config("uniq --group").mode);
+        assert_eq!(Mode::Group(Delimit::Both), config("uniq --group=both").mode);
+    }
+
+    #[test]
+    fn test_comparison_and_operands() {
+        let config = config("uniq -i -f 2 -s3 --check-chars=4 -z in out");
+
+        assert_eq!(Comparison { skip_fields: 2, skip_chars: 3, check_chars: Some(4), ignore_case: true }, config.comparison);
+        assert_eq!(b'\0', config.delimiter);
+        assert_eq!(InputSource::File("in".to_string()), config.input);
+        assert_eq!(Some("out".to_string()), config.output);
+        assert_eq!(usize::MAX, self::config("uniq -f 99999999999999999999999").comparison.skip_fields);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(vec!["uniq: invalid number of fields to skip: 'x'"], messages(&config("uniq -f x")));
+        assert_eq!(vec!["uniq: invalid number of bytes to skip: '-1'"], messages(&config("uniq -s -1")));
+        assert_eq!(vec!["uniq: invalid number of bytes to compare: ''"], messages(&config("uniq --check-chars=")));
+        assert_eq!(vec!["uniq: invalid argument 'x' for '--group'"], messages(&config("uniq --group=x")));
+        assert_eq!(vec!["uniq: invalid argument 'both' for '--all-repeated'"], messages(&config("uniq --all-repeated=both")));
+        assert_eq!(vec!["uniq: --group is mutually exclusive with -c/-d/-D/-u"], messages(&config("uniq --group -c")));
+        assert_eq!(vec!["uniq: printing all duplicated lines and repeat counts is meaningless"], messages(&config("uniq -D -c")));
+        assert_eq!(vec!["uniq: extra operand 'c'"], messages(&config("uniq a b c")));
+    }
+}
@@ -0,0 +1,56 @@
+use std::io;
+use common::input::flags::flags_options::OptionError;
+use common::input::known_error::KnownError;
+use common::input::source::describe_io_error;
+
+const ERROR_CODE: i32 = 1;
+const PROGRAM: &str = "uniq";
+
+///
+/// The command line couldn't be split into options and operands.
+///
+pub(crate) fn option_error(error: &OptionError) -> KnownError {
+    KnownError::new(ERROR_CODE, error.message(PROGRAM))
+}
+
+///
+/// `-f`, `-s` or `-w` was given something other than a non-negative number; `what` says which.
+///
+pub(crate) fn invalid_number(what: &str, argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: invalid number of {}: '{}'", PROGRAM, what, argument))
+}
+
+///
+/// `--all-repeated` or `--group` was given a method it doesn't know.
+///
+pub(crate) fn invalid_method(option: &str, argument: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: invalid argument '{}' for '--{}'", PROGRAM, argument, option))
+}
+
+///
+/// `--group` prints every line, so it can't be combined with options that pick lines.
+///
+pub(crate) fn group_exclusive() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: --group is mutually exclusive with -c/-d/-D/-u", PROGRAM))
+}
+
+///
+/// `-D` prints every repeated line, so there's no single line to put a count on.
+///
+pub(crate) fn count_all_repeated() -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: printing all duplicated lines and repeat counts is meaningless", PROGRAM))
+}
+
+///
+/// More than an input and an output were given.
+///
+pub(crate) fn extra_operand(operand: &str) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: extra operand '{}'", PROGRAM, operand))
+}
+
+///
+/// A file couldn't be opened, read or written.
+///
+pub(crate) fn file_error(name: &str, error: &io::Error) -> KnownError {
+    KnownError::new(ERROR_CODE, format!("{}: {}: {}", PROGRAM, name, describe_io_error(error)))
+}
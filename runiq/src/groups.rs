@@ -0,0 +1,216 @@
+use std::io::{self, Write};
+use crate::config::{Comparison, Delimit, Mode};
+
+impl Comparison {
+    ///
+    /// The part of `line` that's compared: after skipping fields, then bytes, and limited to the
+    /// bytes to check.  A field is a run of blanks followed by a run of non-blanks.
+    ///
+    pub(crate) fn key<'a>(&self, line: &'a [u8]) -> &'a [u8] {
+        let is_blank = |byte: &u8| *byte == b' ' || *byte == b'\t';
+
+        let mut position = 0;
+        for _ in 0..self.skip_fields {
+            if position == line.len() {
+                break;
+            }
+            position += line[position..].iter().take_while(|b| is_blank(b)).count();
+            position += line[position..].iter().take_while(|b| !is_blank(b)).count();
+        }
+        position = position.saturating_add(self.skip_chars).min(line.len());
+
+        let end = match self.check_chars {
+            Some(check) => position.saturating_add(check).min(line.len()),
+            None => line.len(),
+        };
+        &line[position..end]
+    }
+
+    pub(crate) fn same(&self, a: &[u8], b: &[u8]) -> bool {
+        let (a, b) = (self.key(a), self.key(b));
+        if self.ignore_case { a.eq_ignore_ascii_case(b) } else { a == b }
+    }
+}
+
+///
+/// Splits lines into groups of adjacent equal lines, and prints each group the way the mode says,
+/// as soon as it can.  Only the first line of the current group is kept.
+///
+pub(crate) struct Grouper {
+    mode: Mode,
+    comparison: Comparison,
+    delimiter: u8,
+    /* first line of the current group, and how many lines it has so far */
+    first: Vec<u8>,
+    count: u64,
+    /* whether any group has been printed, for delimiters that only go between groups */
+    printed_any: bool,
+}
+
+impl Grouper {
+    pub(crate) fn new(mode: Mode, comparison: Comparison, delimiter: u8) -> Grouper {
+        Grouper { mode, comparison, delimiter, first: vec![], count: 0, printed_any: false }
+    }
+
+    ///
+    /// Take the next line, without its delimiter.
+    ///
+    pub(crate) fn line(&mut self, line: &[u8], out: &mut dyn Write) -> io::Result<()> {
+        if self.count > 0 && self.comparison.same(&self.first, line) {
+            self.count += 1;
+            return self.repeat(line, out);
+        }
+
+        if self.count > 0 {
+            self.end_group(out)?;
+        }
+        self.first.clear();
+        self.first.extend_from_slice(line);
+        self.count = 1;
+        self.start_group(out)
+    }
+
+    ///
+    /// End the last group, at the end of input.
+    ///
+    pub(crate) fn finish(&mut self, out: &mut dyn Write) -> io::Result<()> {
+        if self.count == 0 {
+            return Ok(());
+        }
+        self.end_group(out)?;
+        self.count = 0;
+
+        if self.mode == Mode::Group(Delimit::Both) {
+            out.write_all(&[self.delimiter])?;
+        }
+        Ok(())
+    }
+
+    fn start_group(&mut self, out: &mut dyn Write) -> io::Result<()> {
+        if let Mode::Group(delimit) = self.mode {
+            let before = match delimit {
+                Delimit::Prepend | Delimit::Both => true,
+                Delimit::Separate => self.printed_any,
+                Delimit::None | Delimit::Append => false,
+            };
+            if before {
+                out.write_all(&[self.delimiter])?;
+            }
+            self.printed_any = true;
+            self.write_line(&self.first, out)?;
+        }
+        Ok(())
+    }
+
+    fn repeat(&mut self, line: &[u8], out: &mut dyn Write) -> io::Result<()> {
+        match self.mode {
+            Mode::Group(_) => self.write_line(line, out),
+            Mode::AllRepeated(delimit) => {
+                /* the second line shows the group is repeated; its first line is still owed */
+                if self.count == 2 {
+                    let before = match delimit {
+                        Delimit::Prepend => true,
+                        Delimit::Separate => self.printed_any,
+                        _ => false,
+                    };
+                    if before {
+                        out.write_all(&[self.delimiter])?;
+                    }
+                    self.printed_any = true;
+                    self.write_line(&self.first, out)?;
+                }
+                self.write_line(line, out)
+            }
+            Mode::Collapse { .. } => Ok(()),
+        }
+    }
+
+    fn end_group(&mut self, out: &mut dyn Write) -> io::Result<()> {
+        match self.mode {
+            Mode::Group(Delimit::Append) => out.write_all(&[self.delimiter]),
+            Mode::Collapse { count, repeated, unique } => {
+                let wanted = if self.count > 1 { repeated } else { unique };
+                if !wanted {
+                    return Ok(());
+                }
+                if count {
+                    write!(out, "{:>7} ", self.count)?;
+                }
+                self.write_line(&self.first, out)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn write_line(&self, line: &[u8], out: &mut dyn Write) -> io::Result<()> {
+        out.write_all(line)?;
+        out.write_all(&[self.delimiter])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{Comparison, Delimit, Mode};
+    use crate::groups::Grouper;
+
+    const PLAIN: Comparison = Comparison { skip_fields: 0, skip_chars: 0, check_chars: None, ignore_case: false };
+    const COLLAPSE: Mode = Mode::Collapse { count: false, repeated: true, unique: true };
+
+    fn run(mode: Mode, comparison: Comparison, input: &str) -> String {
+        let mut grouper = Grouper::new(mode, comparison, b'\n');
+        let mut out = vec![];
+        for line in input.split_terminator('\n') {
+            grouper.line(line.as_bytes(), &mut out).unwrap();
+        }
+        grouper.finish(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_key() {
+        let comparison = Comparison { skip_fields: 1, skip_chars: 2, check_chars: Some(3), ignore_case: false };
+
+        assert_eq!(b"bet", comparison.key(b"  one\t beta gamma"));
+        assert_eq!(b"", comparison.key(b"one"));
+        assert_eq!(b" x", Comparison { skip_fields: 5, ..PLAIN }.key(b"a b c d e x"));
+        assert!(Comparison { ignore_case: true, ..PLAIN }.same(b"Hello", b"hELLO"));
+    }
+
+    #[test]
+    fn test_collapse() {
+        let input = "a\na\nb\nc\nc\nc\n";
+
+        assert_eq!("a\nb\nc\n", run(COLLAPSE, PLAIN, input));
+        assert_eq!("      2 a\n      1 b\n      3 c\n", run(Mode::Collapse { count: true, repeated: true, unique: true }, PLAIN, input));
+        assert_eq!("a\nc\n", run(Mode::Collapse { count: false, repeated: true, unique: false }, PLAIN, input));
+        assert_eq!("b\n", run(Mode::Collapse { count: false, repeated: false, unique: true }, PLAIN, input));
+    }
+
+    #[test]
+    fn test_skipping() {
+        let input = "1 apple\n2 apple\n3 pear\n";
+        assert_eq!("1 apple\n3 pear\n", run(COLLAPSE, Comparison { skip_fields: 1, ..PLAIN }, input));
+        assert_eq!("1 apple\n3 pear\n", run(COLLAPSE, Comparison { skip_chars: 2, ..PLAIN }, input));
+        assert_eq!("1 apple\n", run(COLLAPSE, Comparison { skip_chars: 2, check_chars: Some(0), ..PLAIN }, input));
+    }
+
+    #[test]
+    fn test_all_repeated() {
+        let input = "a\na\nb\nc\nc\nd\nd\n";
+
+        assert_eq!("a\na\nc\nc\nd\nd\n", run(Mode::AllRepeated(Delimit::None), PLAIN, input));
+        assert_eq!("\na\na\n\nc\nc\n\nd\nd\n", run(Mode::AllRepeated(Delimit::Prepend), PLAIN, input));
+        assert_eq!("a\na\n\nc\nc\n\nd\nd\n", run(Mode::AllRepeated(Delimit::Separate), PLAIN, input));
+    }
+
+    #[test]
+    fn test_group() {
+        let input = "a\na\nb\n";
+
+        assert_eq!("a\na\n\nb\n", run(Mode::Group(Delimit::Separate), PLAIN, input));
+        assert_eq!("\na\na\n\nb\n", run(Mode::Group(Delimit::Prepend), PLAIN, input));
+        assert_eq!("a\na\n\nb\n\n", run(Mode::Group(Delimit::Append), PLAIN, input));
+        assert_eq!("\na\na\n\nb\n\n", run(Mode::Group(Delimit::Both), PLAIN, input));
+        assert_eq!("", run(Mode::Group(Delimit::Both), PLAIN, ""));
+    }
+}
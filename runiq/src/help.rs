@@ -0,0 +1,40 @@
+use std::io::{self, Write};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub(crate) fn version(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "runiq {}", VERSION)
+}
+
+pub(crate) fn help(out: &mut dyn Write) -> io::Result<()> {
+    version(out)?;
+    writeln!(out)?;
+    writeln!(out, "Usage: uniq [OPTION]... [INPUT [OUTPUT]]")?;
+    writeln!(out, "Filter adjacent matching lines from INPUT (or standard input),")?;
+    writeln!(out, "writing to OUTPUT (or standard output).")?;
+    writeln!(out)?;
+    writeln!(out, "With no options, matching lines are merged to the first occurrence.")?;
+    writeln!(out)?;
+    writeln!(out, "  -c, --count           prefix lines by the number of occurrences")?;
+    writeln!(out, "  -d, --repeated        only print duplicate lines, one for each group")?;
+    writeln!(out, "  -D                    print all duplicate lines")?;
+    writeln!(out, "      --all-repeated[=METHOD]  like -D, but allow separating groups")?;
+    writeln!(out, "                          with an empty line;")?;
+    writeln!(out, "                          METHOD={{none(default),prepend,separate}}")?;
+    writeln!(out, "  -f, --skip-fields=N   avoid comparing the first N fields")?;
+    writeln!(out, "      --group[=METHOD]  show all items, separating groups with an empty line;")?;
+    writeln!(out, "                          METHOD={{separate(default),prepend,append,both}}")?;
+    writeln!(out, "  -i, --ignore-case     ignore differences in case when comparing")?;
+    writeln!(out, "  -s, --skip-chars=N    avoid comparing the first N characters")?;
+    writeln!(out, "  -u, --unique          only print unique lines")?;
+    writeln!(out, "  -z, --zero-terminated     line delimiter is NUL, not newline")?;
+    writeln!(out, "  -w, --check-chars=N   compare no more than N characters in lines")?;
+    writeln!(out, "      --help            display this help and exit")?;
+    writeln!(out, "      --version         output version information and exit")?;
+    writeln!(out)?;
+    writeln!(out, "A field is a run of blanks (usually spaces and/or TABs), then non-blank")?;
+    writeln!(out, "characters.  Fields are skipped before chars.")?;
+    writeln!(out)?;
+    writeln!(out, "Note: 'uniq' does not detect repeated lines unless they are adjacent.")?;
+    writeln!(out, "You may want to sort the input first, or use 'sort -u' without 'uniq'.")
+}
@@ -0,0 +1,16 @@
+mod config;
+mod errors;
+mod groups;
+mod help;
+mod uniq;
+
+use crate::uniq::uniq;
+
+///
+/// Main program entrypoint.
+///
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let status = uniq(&args, &mut std::io::stdout(), &mut std::io::stderr());
+    std::process::exit(status);
+}
@@ -0,0 +1,127 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use common::input::known_error::{report, KnownError};
+use crate::config::Config;
+use crate::errors::file_error;
+use crate::groups::Grouper;
+use crate::help::{help, version};
+
+const SUCCESS: i32 = 0;
+const FAILURE: i32 = 1;
+const STANDARD_OUTPUT: &str = "standard output";
+
+///
+/// Run `uniq` with command line arguments; the first argument is the program name.  Returns the
+/// exit status.
+///
+pub(crate) fn uniq(args: &[String], out: &mut dyn Write, err: &mut dyn Write) -> i32 {
+    let config = Config::new(args);
+    if let Some(first) = config.errors.first() {
+        report(err, &config.errors);
+        return first.code;
+    }
+
+    if config.print_help_and_exit {
+        return help(out).map_or(FAILURE, |_| SUCCESS);
+    }
+    if config.print_version_and_exit {
+        return version(out).map_or(FAILURE, |_| SUCCESS);
+    }
+
+    let result = match &config.output {
+        Some(path) => File::create(path)
+            .map_err(|error| file_error(path, &error))
+            .and_then(|file| filter(&config, &mut BufWriter::new(file), path)),
+        None => filter(&config, &mut BufWriter::new(out), STANDARD_OUTPUT),
+    };
+
+    match result {
+        Ok(()) => SUCCESS,
+        Err(error) => {
+            report(err, std::slice::from_ref(&error));
+            error.code
+        }
+    }
+}
+
+///
+/// Stream the input through a [Grouper], one line at a time.
+///
+fn filter(config: &Config, out: &mut dyn Write, output_name: &str) -> Result<(), KnownError> {
+    let input_name = config.input.name();
+    let input = config.input.open().map_err(|error| file_error(input_name, &error))?;
+    let mut reader = BufReader::new(input);
+    let mut grouper = Grouper::new(config.mode, config.comparison, config.delimiter);
+    let to_output_error = |error: io::Error| file_error(output_name, &error);
+
+    let mut line = vec![];
+    loop {
+        line.clear();
+        match reader.read_until(config.delimiter, &mut line) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(file_error(input_name, &error)),
+        }
+        /* a last line without a delimiter gets one on output */
+        if line.last() == Some(&config.delimiter) {
+            line.pop();
+        }
+        grouper.line(&line, out).map_err(to_output_error)?;
+    }
+
+    grouper.finish(out).and_then(|_| out.flush()).map_err(to_output_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use common::test_support::TempDir;
+    use crate::uniq::uniq;
+
+    fn run(args: &[&str]) -> (i32, Vec<u8>, String) {
+        let args: Vec<String> = std::iter::once("uniq").chain(args.iter().copied()).map(|s| s.to_string()).collect();
+        let mut out = vec![];
+        let mut err = vec![];
+        let status = uniq(&args, &mut out, &mut err);
+        (status, out, String::from_utf8(err).unwrap())
+    }
+
+    #[test]
+    fn test_file_to_stdout() {
+        let dir = TempDir::new("stdout");
+        let input = dir.file("input", b"one\none\ntwo\none");
+
+        assert_eq!((0, b"one\ntwo\none\n".to_vec(), String::new()), run(&[&input]));
+        assert_eq!(b"      2 one\n      1 two\n      1 one\n".to_vec(), run(&["-c", &input]).1);
+    }
+
+    #[test]
+    fn test_output_file() {
+        let dir = TempDir::new("output");
+        let input = dir.file("input", b"a\nA\nb\n");
+        let output = dir.join("output");
+
+        assert_eq!((0, vec![], String::new()), run(&["-i", &input, output.to_str().unwrap()]));
+        assert_eq!("a\nb\n", fs::read_to_string(&output).unwrap());
+    }
+
+    #[test]
+    fn test_bytes_and_zero_terminated() {
+        let dir = TempDir::new("bytes");
+        let input = dir.file("input", b"\xff\xfe\0\xff\xfe\0x\ny\0");
+
+        assert_eq!(b"\xff\xfe\0x\ny\0".to_vec(), run(&["-z", &input]).1);
+        assert_eq!(b"      2 \xff\xfe\0".to_vec(), run(&["-zcd", &input]).1);
+    }
+
+    #[test]
+    fn test_errors() {
+        let dir = TempDir::new("errors");
+        let missing = dir.join("missing");
+        let missing = missing.to_str().unwrap();
+
+        assert_eq!((1, vec![], format!("uniq: {}: No such file or directory\n", missing)), run(&[missing]));
+        assert_eq!((1, vec![], "uniq: invalid option -- 'x'\n".to_string()), run(&["-x"]));
+    }
+}